@@ -246,6 +246,13 @@ pub struct CompileArgs {
     #[arg(long = "pdf-standard", value_delimiter = ',')]
     pub pdf_standard: Vec<PdfStandard>,
 
+    /// Embeds fonts into PDFs even if their license restricts embedding.
+    ///
+    /// Only use this if you have a separate license that permits embedding
+    /// the fonts.
+    #[arg(long = "ignore-embedding-restrictions")]
+    pub ignore_embedding_restrictions: bool,
+
     /// The PPI (pixels per inch) to use for PNG export.
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
@@ -259,6 +266,31 @@ pub struct CompileArgs {
     #[clap(flatten)]
     pub process: ProcessArgs,
 
+    /// How to report uses of deprecated definitions.
+    #[arg(long = "deprecations", default_value_t)]
+    pub deprecations: DeprecationPolicy,
+
+    /// Reports uses of deprecated definitions that are past their removal
+    /// version according to `--deprecations` instead of as errors.
+    #[arg(
+        long = "allow-past-due-deprecations",
+        env = "TYPST_ALLOW_PAST_DUE_DEPRECATIONS"
+    )]
+    pub allow_past_due_deprecations: bool,
+
+    /// Verifies the packages that the document uses against a lock file.
+    ///
+    /// Compilation fails if a package is missing from the lock or if its
+    /// files changed since it was added to the lock.
+    #[arg(long = "package-lock", value_name = "PATH")]
+    pub package_lock: Option<PathBuf>,
+
+    /// Adds the packages that the document uses to the lock file given with
+    /// `--package-lock` instead of verifying them. Creates the lock file if it
+    /// doesn't exist.
+    #[arg(long = "update-package-lock", requires = "package_lock")]
+    pub update_package_lock: bool,
+
     /// Opens the output file with the default viewer or a specific program
     /// after compilation. Ignored if output is stdout.
     #[arg(long = "open", value_name = "VIEWER")]
@@ -455,10 +487,30 @@ pub enum DiagnosticFormat {
 
 display_possible_values!(DiagnosticFormat);
 
+/// How to report uses of deprecated definitions.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum DeprecationPolicy {
+    /// Warn about all uses.
+    #[default]
+    Warn,
+    /// Turn all uses into errors.
+    Error,
+    /// Warn about uses in the project's own files, but not in packages.
+    AllowInPackages,
+}
+
+display_possible_values!(DeprecationPolicy);
+
 /// An in-development feature that may be changed or removed at any time.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum Feature {
     Html,
+    /// Warn about fonts that are shadowed by another font.
+    FontDebug,
+    /// Mark laid out content with its semantic role for exporters.
+    A11yExtras,
+    /// Make laid out lines and page breaks available to queries.
+    LayoutMetrics,
 }
 
 display_possible_values!(Feature);
@@ -470,6 +522,9 @@ pub enum PdfStandard {
     /// PDF 1.7.
     #[value(name = "1.7")]
     V_1_7,
+    /// PDF/A-1b.
+    #[value(name = "a-1b")]
+    A_1b,
     /// PDF/A-2b.
     #[value(name = "a-2b")]
     A_2b,
@@ -1,12 +1,13 @@
 use std::ffi::OsStr;
 use std::fs::{self, File};
 use std::io::{self, Write};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Datelike, Timelike, Utc};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term;
-use ecow::{eco_format, EcoString};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use parking_lot::RwLock;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use typst::diag::{
@@ -15,13 +16,14 @@ use typst::diag::{
 use typst::foundations::{Datetime, Smart};
 use typst::html::HtmlDocument;
 use typst::layout::{Frame, Page, PageRanges, PagedDocument};
+use typst::package::PackageLock;
 use typst::syntax::{FileId, Source, Span};
-use typst::WorldExt;
+use typst::{CompileOptions, WorldExt};
 use typst_pdf::{PdfOptions, PdfStandards, Timestamp};
 
 use crate::args::{
-    CompileArgs, CompileCommand, DiagnosticFormat, Input, Output, OutputFormat,
-    PdfStandard, WatchCommand,
+    CompileArgs, CompileCommand, DeprecationPolicy, DiagnosticFormat, Input, Output,
+    OutputFormat, PdfStandard, WatchCommand,
 };
 #[cfg(feature = "http-server")]
 use crate::server::HtmlServer;
@@ -59,12 +61,24 @@ pub struct CompileConfig {
     pub creation_timestamp: Option<DateTime<Utc>>,
     /// The format to emit diagnostics in.
     pub diagnostic_format: DiagnosticFormat,
+    /// How to report uses of deprecated definitions.
+    pub deprecation_policy: typst::diag::DeprecationPolicy,
+    /// Whether past-due deprecations are reported according to the policy
+    /// instead of as errors.
+    pub allow_past_due_deprecations: bool,
+    /// The lock file that packages are verified against or recorded in.
+    pub package_lock: Option<PathBuf>,
+    /// Whether the used packages are recorded in the lock file instead of being
+    /// verified against it.
+    pub update_package_lock: bool,
     /// Opens the output file with the default viewer or a specific program after
     /// compilation.
     pub open: Option<Option<String>>,
     /// One (or multiple comma-separated) PDF standards that Typst will enforce
     /// conformance with.
     pub pdf_standards: PdfStandards,
+    /// Whether to embed fonts into PDFs regardless of their license.
+    pub ignore_embedding_restrictions: bool,
     /// A path to write a Makefile rule describing the current compilation.
     pub make_deps: Option<PathBuf>,
     /// The PPI (pixels per inch) to use for PNG export.
@@ -72,6 +86,9 @@ pub struct CompileConfig {
     /// The export cache for images, used for caching output files in `typst
     /// watch` sessions with images.
     pub export_cache: ExportCache,
+    /// The last successfully compiled paged document, from which the next
+    /// compilation in `typst watch` sessions can reuse introspection results.
+    pub previous: Option<PagedDocument>,
     /// Server for `typst watch` to HTML.
     #[cfg(feature = "http-server")]
     pub server: Option<HtmlServer>,
@@ -135,6 +152,7 @@ impl CompileConfig {
                 .iter()
                 .map(|standard| match standard {
                     PdfStandard::V_1_7 => typst_pdf::PdfStandard::V_1_7,
+                    PdfStandard::A_1b => typst_pdf::PdfStandard::A_1b,
                     PdfStandard::A_2b => typst_pdf::PdfStandard::A_2b,
                 })
                 .collect::<Vec<_>>();
@@ -158,12 +176,24 @@ impl CompileConfig {
             output_format,
             pages,
             pdf_standards,
+            ignore_embedding_restrictions: args.ignore_embedding_restrictions,
             creation_timestamp: args.world.creation_timestamp,
             make_deps: args.make_deps.clone(),
             ppi: args.ppi,
             diagnostic_format: args.process.diagnostic_format,
+            deprecation_policy: match args.deprecations {
+                DeprecationPolicy::Warn => typst::diag::DeprecationPolicy::Warn,
+                DeprecationPolicy::Error => typst::diag::DeprecationPolicy::Error,
+                DeprecationPolicy::AllowInPackages => {
+                    typst::diag::DeprecationPolicy::AllowInPackages
+                }
+            },
+            allow_past_due_deprecations: args.allow_past_due_deprecations,
+            package_lock: args.package_lock.clone(),
+            update_package_lock: args.update_package_lock,
             open: args.open.clone(),
             export_cache: ExportCache::new(),
+            previous: None,
             #[cfg(feature = "http-server")]
             server,
         })
@@ -202,6 +232,7 @@ pub fn compile_once(
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
 
             write_make_deps(world, config)?;
+            write_package_lock(world, config)?;
             open_output(config)?;
         }
 
@@ -226,20 +257,71 @@ fn compile_and_export(
     world: &mut SystemWorld,
     config: &mut CompileConfig,
 ) -> Warned<SourceResult<()>> {
+    let package_lock = match &config.package_lock {
+        Some(path) if !config.update_package_lock => match read_package_lock(path) {
+            Ok(lock) => Some(lock),
+            Err(message) => {
+                let error = SourceDiagnostic::error(Span::detached(), message);
+                return Warned { output: Err(eco_vec![error]), warnings: eco_vec![] };
+            }
+        },
+        _ => None,
+    };
+
+    let options = CompileOptions {
+        deprecation_policy: config.deprecation_policy,
+        allow_past_due_deprecations: config.allow_past_due_deprecations,
+        package_lock,
+        ..CompileOptions::default()
+    };
+
     match config.output_format {
         OutputFormat::Html => {
-            let Warned { output, warnings } = typst::compile::<HtmlDocument>(world);
+            let (Warned { output, warnings }, _) =
+                typst::compile_with_options::<HtmlDocument>(world, &options);
             let result = output.and_then(|document| export_html(&document, config));
             Warned { output: result, warnings }
         }
         _ => {
-            let Warned { output, warnings } = typst::compile::<PagedDocument>(world);
-            let result = output.and_then(|document| export_paged(&document, config));
+            let previous = config.previous.take();
+            let options = CompileOptions { max_pages: max_pages(config), ..options };
+            let (Warned { output, mut warnings }, _) = match &previous {
+                Some(previous) => {
+                    typst::recompile_with_options(world, &options, previous)
+                }
+                None => typst::compile_with_options::<PagedDocument>(world, &options),
+            };
+            let result = match output {
+                Ok(document) => {
+                    let result = export_paged(&document, config, &mut warnings);
+                    if config.watching {
+                        config.previous = Some(document);
+                    }
+                    result
+                }
+                Err(errors) => {
+                    config.previous = previous;
+                    Err(errors)
+                }
+            };
             Warned { output: result, warnings }
         }
     }
 }
 
+/// The number of pages that must be laid out for export if not all of them are
+/// exported.
+fn max_pages(config: &CompileConfig) -> Option<NonZeroUsize> {
+    // File names may depend on the total number of pages.
+    if let Output::Path(path) = &config.output {
+        if output_template::has_total_template(path.to_str().unwrap_or_default()) {
+            return None;
+        }
+    }
+
+    config.pages.as_ref()?.last_page()
+}
+
 /// Export to HTML.
 fn export_html(document: &HtmlDocument, config: &CompileConfig) -> SourceResult<()> {
     let html = typst_html::html(document)?;
@@ -256,9 +338,13 @@ fn export_html(document: &HtmlDocument, config: &CompileConfig) -> SourceResult<
 }
 
 /// Export to a paged target format.
-fn export_paged(document: &PagedDocument, config: &CompileConfig) -> SourceResult<()> {
+fn export_paged(
+    document: &PagedDocument,
+    config: &CompileConfig,
+    warnings: &mut EcoVec<SourceDiagnostic>,
+) -> SourceResult<()> {
     match config.output_format {
-        OutputFormat::Pdf => export_pdf(document, config),
+        OutputFormat::Pdf => export_pdf(document, config, warnings),
         OutputFormat::Png => {
             export_image(document, config, ImageExportFormat::Png).at(Span::detached())
         }
@@ -270,7 +356,11 @@ fn export_paged(document: &PagedDocument, config: &CompileConfig) -> SourceResul
 }
 
 /// Export to a PDF.
-fn export_pdf(document: &PagedDocument, config: &CompileConfig) -> SourceResult<()> {
+fn export_pdf(
+    document: &PagedDocument,
+    config: &CompileConfig,
+    warnings: &mut EcoVec<SourceDiagnostic>,
+) -> SourceResult<()> {
     // If the timestamp is provided through the CLI, use UTC suffix,
     // else, use the current local time and timezone.
     let timestamp = match config.creation_timestamp {
@@ -290,8 +380,16 @@ fn export_pdf(document: &PagedDocument, config: &CompileConfig) -> SourceResult<
         timestamp,
         page_ranges: config.pages.clone(),
         standards: config.pdf_standards.clone(),
+        flatten_transparency: false,
+        flatten_dpi: Smart::Auto,
+        gradient_samples: Smart::Auto,
+        ignore_embedding_restrictions: config.ignore_embedding_restrictions,
+        attachments: &[],
     };
-    let buffer = typst_pdf::pdf(document, &options)?;
+    let Warned { output, warnings: pdf_warnings } =
+        typst_pdf::pdf_with_warnings(document, &options);
+    warnings.extend(pdf_warnings);
+    let buffer = output?;
     config
         .output
         .write(&buffer)
@@ -405,6 +503,12 @@ mod output_template {
         INDEXABLE.iter().any(|template| output.contains(template))
     }
 
+    pub fn has_total_template(output: &str) -> bool {
+        ["{0p}", "{n}", "{t}"]
+            .iter()
+            .any(|template| output.contains(template))
+    }
+
     pub fn format(output: &str, this_page: usize, total_pages: usize) -> String {
         // Find the base 10 width of number `i`
         fn width(i: usize) -> usize {
@@ -497,6 +601,37 @@ impl ExportCache {
     }
 }
 
+/// Reads a package lock from a TOML file.
+fn read_package_lock(path: &Path) -> StrResult<PackageLock> {
+    let text = fs::read_to_string(path).map_err(|err| {
+        eco_format!("failed to read package lock {} ({err})", path.display())
+    })?;
+    toml::from_str(&text).map_err(|err| {
+        eco_format!("failed to parse package lock {} ({})", path.display(), err.message())
+    })
+}
+
+/// Records the packages used by the last compilation in the package lock, if
+/// requested.
+fn write_package_lock(world: &mut SystemWorld, config: &CompileConfig) -> StrResult<()> {
+    let Some(path) = &config.package_lock else { return Ok(()) };
+    if !config.update_package_lock {
+        return Ok(());
+    }
+
+    let mut lock =
+        if path.exists() { read_package_lock(path)? } else { PackageLock::new() };
+    let specs = world.packages();
+    lock.record(&*world, &specs)
+        .map_err(|err| eco_format!("failed to record packages in lock ({err})"))?;
+
+    let text = toml::to_string(&lock)
+        .map_err(|err| eco_format!("failed to serialize package lock ({err})"))?;
+    fs::write(path, text).map_err(|err| {
+        eco_format!("failed to write package lock {} ({err})", path.display())
+    })
+}
+
 /// Writes a Makefile rule describing the relationship between the output and
 /// its dependencies to the path specified by the --make-deps argument, if it
 /// was provided.
@@ -637,7 +772,7 @@ pub fn print_diagnostics(
             Severity::Error => Diagnostic::error(),
             Severity::Warning => Diagnostic::warning(),
         }
-        .with_message(diagnostic.message.clone())
+        .with_message(diagnostic.message_with_occurrences())
         .with_notes(
             diagnostic
                 .hints
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use typst::text::FontVariant;
 use typst_kit::fonts::Fonts;
 
@@ -18,4 +20,14 @@ pub fn fonts(command: &FontsCommand) {
             }
         }
     }
+
+    for problem in fonts.book.problems() {
+        let path = problem.path.as_deref().unwrap_or(Path::new("<embedded>"));
+        eprintln!(
+            "warning: skipped font {} (index {}): {}",
+            path.display(),
+            problem.index,
+            problem.reason,
+        );
+    }
 }
@@ -1,17 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, OnceLock};
 use std::{fmt, fs, io, mem};
 
-use chrono::{DateTime, Datelike, FixedOffset, Local, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Local, Timelike, Utc};
 use ecow::{eco_format, EcoString};
 use parking_lot::Mutex;
-use typst::diag::{FileError, FileResult};
+use typst::diag::{FileError, FileResult, StrResult};
 use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
+use typst::layout::Axes;
+use typst::syntax::package::PackageSpec;
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
 use typst::utils::LazyHash;
+use typst::visualize::{RasterImage, SvgImage};
 use typst::{Library, World};
 use typst_kit::fonts::{FontSlot, Fonts};
 use typst_kit::package::PackageStorage;
@@ -117,6 +120,9 @@ impl SystemWorld {
                 .iter()
                 .map(|&feature| match feature {
                     Feature::Html => typst::Feature::Html,
+                    Feature::FontDebug => typst::Feature::FontDebug,
+                    Feature::A11yExtras => typst::Feature::A11yExtras,
+                    Feature::LayoutMetrics => typst::Feature::LayoutMetrics,
                 })
                 .collect();
 
@@ -171,6 +177,17 @@ impl SystemWorld {
             })
     }
 
+    /// The packages that files were accessed from during the last
+    /// compilation.
+    pub fn packages(&mut self) -> BTreeSet<PackageSpec> {
+        self.slots
+            .get_mut()
+            .values()
+            .filter(|slot| slot.accessed())
+            .filter_map(|slot| slot.id.package().cloned())
+            .collect()
+    }
+
     /// Reset the compilation state in preparation of a new compilation.
     pub fn reset(&mut self) {
         for slot in self.slots.get_mut().values_mut() {
@@ -234,6 +251,36 @@ impl World for SystemWorld {
             with_offset.day().try_into().ok()?,
         )
     }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        if id == *STDIN_ID {
+            return None;
+        }
+
+        let path = system_path(&self.root, id, &self.package_storage).ok()?;
+        let modified: DateTime<Utc> = fs::metadata(path).ok()?.modified().ok()?.into();
+        Datetime::from_ymd_hms(
+            modified.year(),
+            modified.month().try_into().ok()?,
+            modified.day().try_into().ok()?,
+            modified.hour().try_into().ok()?,
+            modified.minute().try_into().ok()?,
+            modified.second().try_into().ok()?,
+        )
+    }
+
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        let root = self
+            .package_storage
+            .prepare_package(spec, &mut PrintDownload(&spec))?;
+        let mut files = vec![];
+        collect_package_files(spec, &root, &root, &mut files)?;
+        Ok(files)
+    }
+
+    fn rasterize_svg(&self, svg: &SvgImage, size: Axes<u32>) -> StrResult<RasterImage> {
+        typst_render::rasterize_svg(svg, size)
+    }
 }
 
 impl SystemWorld {
@@ -369,6 +416,25 @@ impl<T: Clone> SlotCell<T> {
     }
 }
 
+/// Collects the ids of all files in a package directory, recursively.
+fn collect_package_files(
+    spec: &PackageSpec,
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<FileId>,
+) -> FileResult<()> {
+    let f = |err| FileError::from_io(err, dir);
+    for entry in fs::read_dir(dir).map_err(f)? {
+        let path = entry.map_err(f)?.path();
+        if path.is_dir() {
+            collect_package_files(spec, root, &path, files)?;
+        } else if let Some(vpath) = VirtualPath::within_root(&path, root) {
+            files.push(FileId::new(Some(spec.clone()), vpath));
+        }
+    }
+    Ok(())
+}
+
 /// Resolves the path of a file id on the system, downloading a package if
 /// necessary.
 fn system_path(
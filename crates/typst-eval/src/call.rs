@@ -4,10 +4,10 @@ use typst_library::diag::{
     bail, error, At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult,
     Trace, Tracepoint,
 };
-use typst_library::engine::{Engine, Sink, Traced};
+use typst_library::engine::{record_memoized, Engine, Memoized, Sink, Traced};
 use typst_library::foundations::{
     Arg, Args, Bytes, Capturer, Closure, Content, Context, Func, IntoValue,
-    NativeElement, Scope, Scopes, Value,
+    NativeElement, PluginHostInfo, Scope, Scopes, TargetElem, Value,
 };
 use typst_library::introspection::Introspector;
 use typst_library::math::LrElem;
@@ -172,6 +172,7 @@ pub fn eval_closure(
     context: Tracked<Context>,
     mut args: Args,
 ) -> SourceResult<Value> {
+    record_memoized(Memoized::Closure);
     let (name, params, body) = match closure.node.cast::<ast::Closure>() {
         Some(node) => (node.name(), node.params(), node.body()),
         None => (None, ast::Params::default(), closure.node.cast().unwrap()),
@@ -320,7 +321,16 @@ fn eval_field_call(
         // Call plugins by converting args to bytes.
         let bytes = args.all::<Bytes>()?;
         args.finish()?;
-        let value = plugin.call(&field, bytes).at(span)?.into_value();
+        let world = vm.world();
+        let library = world.library();
+        let host = PluginHostInfo {
+            target: vm.context.styles().ok().map(TargetElem::target_in),
+            features: library.features.clone(),
+        };
+        let value = plugin
+            .call(&field, bytes, library.plugin_limits, &host)
+            .at(span)?
+            .into_value();
         Ok(FieldCall::Resolved(value))
     } else if let Some(callee) = target.ty().scope().get(&field) {
         args.insert(0, target_expr.span(), target);
@@ -331,6 +341,15 @@ fn eval_field_call(
     ) {
         // Certain value types may have their own ways to access method fields.
         // e.g. `$arrow.r(v)$`, `table.cell[..]`
+        if let Value::Module(module) = &target {
+            if let Some((value, deprecation)) =
+                module.scope().get_with_deprecation(&field)
+            {
+                let value = value.clone();
+                vm.check_deprecation(deprecation, field.span());
+                return Ok(FieldCall::Normal(value, args));
+            }
+        }
         let value = target.field(&field).at(field.span())?;
         Ok(FieldCall::Normal(value, args))
     } else {
@@ -1,5 +1,5 @@
 use ecow::{eco_vec, EcoVec};
-use typst_library::diag::{bail, error, warning, At, SourceResult};
+use typst_library::diag::{bail, error, warning, At, ErrorMode, SourceResult};
 use typst_library::engine::Engine;
 use typst_library::foundations::{
     ops, Array, Capturer, Closure, Content, ContextElem, Dict, Func, NativeElement,
@@ -153,7 +153,16 @@ impl Eval for ast::Ident<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get(&self).cloned().at(self.span())
+        let found = vm.scopes.get_with_deprecation(&self, false);
+        let (value, deprecation) = found.at(self.span()).map_err(|errors| {
+            errors
+                .into_iter()
+                .map(|error| error.with_code(ErrorMode::UNKNOWN_VARIABLE))
+                .collect::<EcoVec<_>>()
+        })?;
+        let value = value.clone();
+        vm.check_deprecation(deprecation, self.span());
+        Ok(value)
     }
 }
 
@@ -310,6 +319,16 @@ impl Eval for ast::FieldAccess<'_> {
         let value = self.target().eval(vm)?;
         let field = self.field();
 
+        if let Value::Module(module) = &value {
+            if let Some((output, deprecation)) =
+                module.scope().get_with_deprecation(&field)
+            {
+                let output = output.clone();
+                vm.check_deprecation(deprecation, field.span());
+                return Ok(output);
+            }
+        }
+
         let err = match value.field(&field).at(field.span()) {
             Ok(value) => return Ok(value),
             Err(err) => err,
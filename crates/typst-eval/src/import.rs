@@ -1,16 +1,16 @@
 use comemo::TrackedMut;
 use ecow::{eco_format, eco_vec, EcoString};
 use typst_library::diag::{
-    bail, error, warning, At, FileError, SourceResult, Trace, Tracepoint,
+    bail, error, warning, At, ErrorMode, FileError, SourceResult, Trace, Tracepoint,
 };
 use typst_library::engine::Engine;
-use typst_library::foundations::{Content, Module, Value};
+use typst_library::foundations::{Content, Dict, Module, Value};
 use typst_library::World;
 use typst_syntax::ast::{self, AstNode};
 use typst_syntax::package::{PackageManifest, PackageSpec};
-use typst_syntax::{FileId, Span, VirtualPath};
+use typst_syntax::{is_ident, FileId, Span, Spanned, VirtualPath};
 
-use crate::{eval, Eval, Vm};
+use crate::{eval_impl, Eval, Vm};
 
 impl Eval for ast::ModuleImport<'_> {
     type Output = Value;
@@ -31,7 +31,13 @@ impl Eval for ast::ModuleImport<'_> {
             Value::Type(_) => {}
             Value::Module(_) => {}
             Value::Str(path) => {
-                source = Value::Module(import(&mut vm.engine, path, source_span)?);
+                source = Value::Module(load(
+                    &mut vm.engine,
+                    path,
+                    source_span,
+                    None,
+                    vm.error_mode,
+                )?);
             }
             v => {
                 bail!(
@@ -146,12 +152,41 @@ impl Eval for ast::ModuleInclude<'_> {
     type Output = Content;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let span = self.source().span();
-        let source = self.source().eval(vm)?;
-        let module = match source {
-            Value::Str(path) => import(&mut vm.engine, &path, span)?,
-            Value::Module(module) => module,
-            v => bail!(span, "expected path or module, found {}", v.ty()),
+        let (Spanned { v: source, span }, inputs) = match self.args() {
+            Some(args) => {
+                let mut args = args.eval(vm)?;
+                let source = args.expect::<Spanned<Value>>("path")?;
+                let inputs = args.named::<Spanned<Dict>>("inputs")?;
+                args.finish()?;
+                (source, inputs)
+            }
+            None => {
+                let span = self.source().span();
+                (Spanned::new(self.source().eval(vm)?, span), None)
+            }
+        };
+
+        let module = match (source, inputs) {
+            (Value::Str(path), None) => {
+                load(&mut vm.engine, &path, span, None, vm.error_mode)?
+            }
+            (Value::Str(path), Some(inputs)) => {
+                for (name, _) in inputs.v.iter() {
+                    if !is_ident(name) {
+                        bail!(
+                            inputs.span,
+                            "input name `{name}` is not a valid identifier"
+                        );
+                    }
+                }
+                load(&mut vm.engine, &path, span, Some(&inputs.v), vm.error_mode)?
+            }
+            (Value::Module(module), None) => module,
+            (Value::Module(_), Some(inputs)) => bail!(
+                inputs.span, "cannot pass inputs to a module";
+                hint: "the module was already evaluated, pass a path instead"
+            ),
+            (v, _) => bail!(span, "expected path or module, found {}", v.ty()),
         };
         Ok(module.content())
     }
@@ -159,18 +194,36 @@ impl Eval for ast::ModuleInclude<'_> {
 
 /// Process an import of a package or file relative to the current location.
 pub fn import(engine: &mut Engine, from: &str, span: Span) -> SourceResult<Module> {
+    load(engine, from, span, None, ErrorMode::Fatal)
+}
+
+/// Evaluate a package or file relative to the current location, optionally
+/// with additional bindings for it.
+fn load(
+    engine: &mut Engine,
+    from: &str,
+    span: Span,
+    inputs: Option<&Dict>,
+    mode: ErrorMode,
+) -> SourceResult<Module> {
     if from.starts_with('@') {
         let spec = from.parse::<PackageSpec>().at(span)?;
-        import_package(engine, spec, span)
+        import_package(engine, spec, span, inputs, mode)
     } else {
         let id = span.resolve_path(from).at(span)?;
-        import_file(engine, id, span)
+        import_file(engine, id, span, inputs, mode)
     }
 }
 
 /// Import a file from a path. The path is resolved relative to the given
 /// `span`.
-fn import_file(engine: &mut Engine, id: FileId, span: Span) -> SourceResult<Module> {
+fn import_file(
+    engine: &mut Engine,
+    id: FileId,
+    span: Span,
+    inputs: Option<&Dict>,
+    mode: ErrorMode,
+) -> SourceResult<Module> {
     // Load the source file.
     let source = engine.world.source(id).at(span)?;
 
@@ -179,15 +232,20 @@ fn import_file(engine: &mut Engine, id: FileId, span: Span) -> SourceResult<Modu
         bail!(span, "cyclic import");
     }
 
-    // Evaluate the file.
+    // Evaluate the file. The inputs are part of the memoization key, so
+    // evaluations with different inputs don't share results.
     let point = || Tracepoint::Import;
-    eval(
+    let sink = TrackedMut::reborrow_mut(&mut engine.sink);
+    let route = engine.route.track();
+    eval_impl(
         engine.routines,
         engine.world,
         engine.traced,
-        TrackedMut::reborrow_mut(&mut engine.sink),
-        engine.route.track(),
+        sink,
+        route,
         &source,
+        inputs,
+        mode,
     )
     .trace(engine.world, point, span)
 }
@@ -197,9 +255,11 @@ fn import_package(
     engine: &mut Engine,
     spec: PackageSpec,
     span: Span,
+    inputs: Option<&Dict>,
+    mode: ErrorMode,
 ) -> SourceResult<Module> {
     let (name, id) = resolve_package(engine, spec, span)?;
-    import_file(engine, id, span).map(|module| module.with_name(name))
+    import_file(engine, id, span, inputs, mode).map(|module| module.with_name(name))
 }
 
 /// Resolve the name and entrypoint of a package.
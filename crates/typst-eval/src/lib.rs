@@ -25,9 +25,11 @@ use self::binding::*;
 use self::methods::*;
 
 use comemo::{Track, Tracked, TrackedMut};
-use typst_library::diag::{bail, SourceResult};
-use typst_library::engine::{Engine, Route, Sink, Traced};
-use typst_library::foundations::{Context, Module, NativeElement, Scope, Scopes, Value};
+use typst_library::diag::{bail, ErrorMode, SourceResult};
+use typst_library::engine::{record_memoized, Engine, Memoized, Route, Sink, Traced};
+use typst_library::foundations::{
+    Context, Dict, Module, NativeElement, Scope, Scopes, Value,
+};
 use typst_library::introspection::Introspector;
 use typst_library::math::EquationElem;
 use typst_library::routines::Routines;
@@ -35,16 +37,81 @@ use typst_library::World;
 use typst_syntax::{ast, parse, parse_code, parse_math, Source, Span};
 
 /// Evaluate a source file and return the resulting module.
+///
+/// Evaluation stops at the first error. Use [`eval_with_mode`] to collect
+/// recoverable errors instead.
+pub fn eval(
+    routines: &Routines,
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    source: &Source,
+) -> SourceResult<Module> {
+    eval_impl(routines, world, traced, sink, route, source, None, ErrorMode::Fatal)
+}
+
+/// Evaluate a source file with the given error mode and return the resulting
+/// module.
+pub fn eval_with_mode(
+    routines: &Routines,
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    source: &Source,
+    mode: ErrorMode,
+) -> SourceResult<Module> {
+    eval_impl(routines, world, traced, sink, route, source, None, mode)
+}
+
+/// Evaluate a source file with additional bindings and return the resulting
+/// module.
+///
+/// The inputs are defined in a scope between the standard library and the
+/// file's top-level scope. The file can thus use them like global variables,
+/// but they are not part of the resulting module.
+pub fn eval_with_inputs(
+    routines: &Routines,
+    world: Tracked<dyn World + '_>,
+    traced: Tracked<Traced>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    source: &Source,
+    inputs: &Dict,
+) -> SourceResult<Module> {
+    eval_impl(
+        routines,
+        world,
+        traced,
+        sink,
+        route,
+        source,
+        Some(inputs),
+        ErrorMode::Fatal,
+    )
+}
+
+/// The internal implementation of [`eval`], [`eval_with_mode`], and
+/// [`eval_with_inputs`].
+///
+/// The inputs and the error mode are part of the memoization key, so
+/// evaluations with different ones don't share results.
 #[comemo::memoize]
 #[typst_macros::time(name = "eval", span = source.root().span())]
-pub fn eval(
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn eval_impl(
     routines: &Routines,
     world: Tracked<dyn World + '_>,
     traced: Tracked<Traced>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     source: &Source,
+    inputs: Option<&Dict>,
+    mode: ErrorMode,
 ) -> SourceResult<Module> {
+    record_memoized(Memoized::Module);
+
     // Prevent cyclic evaluation.
     let id = source.id();
     if route.contains(id) {
@@ -64,9 +131,17 @@ pub fn eval(
 
     // Prepare VM.
     let context = Context::none();
-    let scopes = Scopes::new(Some(world.library()));
+    let mut scopes = Scopes::new(Some(world.library()));
+    if let Some(inputs) = inputs {
+        for (name, value) in inputs.iter() {
+            scopes.top.define(name.as_str(), value.clone());
+        }
+        scopes.enter();
+    }
+
     let root = source.root();
     let mut vm = Vm::new(engine, context.track(), scopes, root.span());
+    vm.error_mode = mode;
 
     // Check for well-formedness unless we are in trace mode.
     let errors = root.errors();
@@ -106,6 +181,7 @@ pub fn eval_string(
     mode: EvalMode,
     scope: Scope,
 ) -> SourceResult<Value> {
+    record_memoized(Memoized::Module);
     let mut root = match mode {
         EvalMode::Code => parse_code(string),
         EvalMode::Markup => parse(string),
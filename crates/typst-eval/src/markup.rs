@@ -8,7 +8,8 @@ use typst_library::model::{
     StrongElem, Supplement, TermItem, Url,
 };
 use typst_library::text::{
-    LinebreakElem, RawContent, RawElem, SmartQuoteElem, SpaceElem, TextElem,
+    LinebreakElem, RawContent, RawElem, ShorthandElem, SmartQuoteElem, SpaceElem,
+    TextElem,
 };
 use typst_syntax::ast::{self, AstNode};
 use typst_utils::PicoStr;
@@ -50,7 +51,7 @@ fn eval_markup<'a>(
                 let tail = eval_markup(vm, exprs)?;
                 seq.push(tail.styled_with_recipe(&mut vm.engine, vm.context, recipe)?)
             }
-            expr => match expr.eval(vm)? {
+            expr => match eval_recoverable(vm, expr)? {
                 Value::Label(label) => {
                     if let Some(elem) =
                         seq.iter_mut().rev().find(|node| !node.can::<dyn Unlabellable>())
@@ -87,6 +88,38 @@ fn eval_markup<'a>(
     Ok(Content::sequence(seq))
 }
 
+/// Evaluate an expression embedded in markup.
+///
+/// In the collecting [error mode](typst_library::diag::ErrorMode::Collect),
+/// recoverable errors are recorded and the expression produces nothing
+/// instead.
+fn eval_recoverable(vm: &mut Vm, expr: ast::Expr) -> SourceResult<Value> {
+    let errors = match expr.eval(vm) {
+        Ok(value) => return Ok(value),
+        Err(errors) => errors,
+    };
+
+    // A failed binding or import would lead to follow-up errors wherever its
+    // definitions are used, so these stay fatal.
+    let binds = matches!(
+        expr,
+        ast::Expr::Let(_)
+            | ast::Expr::DestructAssign(_)
+            | ast::Expr::Import(_)
+            | ast::Expr::Include(_)
+    );
+
+    if binds
+        || vm.flow.is_some()
+        || !errors.iter().all(|error| vm.error_mode.is_recoverable(error))
+    {
+        return Err(errors);
+    }
+
+    vm.engine.sink.delay(errors);
+    Ok(Value::None)
+}
+
 impl Eval for ast::Text<'_> {
     type Output = Content;
 
@@ -131,6 +164,13 @@ impl Eval for ast::Shorthand<'_> {
     type Output = Value;
 
     fn eval(self, _: &mut Vm) -> SourceResult<Self::Output> {
+        // Dashes and ellipses are substituted when shown, so that
+        // `text.smart` can turn them off.
+        let text = self.to_untyped().text();
+        if ShorthandElem::SMART.contains(&text.as_str()) {
+            return Ok(Value::Content(ShorthandElem::new(text.clone()).pack()));
+        }
+
         Ok(Value::Symbol(Symbol::single(self.get())))
     }
 }
@@ -24,7 +24,11 @@ impl Eval for ast::MathIdent<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get_in_math(&self).cloned().at(self.span())
+        let (value, deprecation) =
+            vm.scopes.get_with_deprecation(&self, true).at(self.span())?;
+        let value = value.clone();
+        vm.check_deprecation(deprecation, self.span());
+        Ok(value)
     }
 }
 
@@ -1,5 +1,5 @@
 use comemo::Tracked;
-use typst_library::diag::warning;
+use typst_library::diag::{warning, Deprecation, ErrorMode};
 use typst_library::engine::Engine;
 use typst_library::foundations::{Context, IntoValue, Scopes, Value};
 use typst_library::World;
@@ -23,6 +23,8 @@ pub struct Vm<'a> {
     pub inspected: Option<Span>,
     /// Data that is contextually made accessible to code behind the scenes.
     pub context: Tracked<'a, Context<'a>>,
+    /// How errors in markup are dealt with.
+    pub error_mode: ErrorMode,
 }
 
 impl<'a> Vm<'a> {
@@ -34,7 +36,14 @@ impl<'a> Vm<'a> {
         target: Span,
     ) -> Self {
         let inspected = target.id().and_then(|id| engine.traced.get(id));
-        Self { engine, context, flow: None, scopes, inspected }
+        Self {
+            engine,
+            context,
+            flow: None,
+            scopes,
+            inspected,
+            error_mode: ErrorMode::Fatal,
+        }
     }
 
     /// Access the underlying world.
@@ -61,6 +70,13 @@ impl<'a> Vm<'a> {
         self.scopes.top.define_ident(var, value);
     }
 
+    /// Warn about the use of a definition if it is deprecated.
+    pub fn check_deprecation(&mut self, deprecation: Option<Deprecation>, span: Span) {
+        if let Some(deprecation) = deprecation {
+            self.engine.sink.warn(deprecation.warning(span));
+        }
+    }
+
     /// Trace a value.
     #[cold]
     pub fn trace(&mut self, value: Value) {
@@ -0,0 +1,206 @@
+//! Generation of the stylesheet for `html-numbering: "css"`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use ecow::{eco_format, EcoString};
+use typst_library::html::{attr, tag, HtmlElement, HtmlNode};
+use typst_library::model::NumberingPattern;
+
+/// Generates a stylesheet that displays the numbers of all elements marked
+/// for CSS counters with the counters.
+///
+/// Numbered headings are marked with `data-counter="heading"` and their
+/// `data-level`, numbered figures with `data-counter="figure-{kind}"`. If an
+/// element's number is to be displayed by CSS, it additionally has a
+/// `data-numbering-pattern`. For figures, this is an empty `<span>` in the
+/// caption that also carries the figure's `data-counter`.
+///
+/// Returns `None` if there are no such elements.
+pub fn stylesheet(root: &HtmlElement) -> Option<EcoString> {
+    let mut usage = Usage::default();
+    usage.visit(root);
+    if usage.headings.is_empty() && usage.figures.is_empty() {
+        return None;
+    }
+
+    let heading = |level: usize| eco_format!("typst-heading-{level}");
+    let figure = |name: &str| eco_format!("typst-{name}");
+    let max_level = usage.headings.last().copied().unwrap_or(0);
+
+    let mut css = EcoString::new();
+    css.push_str(":root { counter-reset:");
+    for level in 1..=max_level {
+        write!(css, " {}", heading(level)).unwrap();
+    }
+    for name in &usage.figures {
+        write!(css, " {}", figure(name)).unwrap();
+    }
+    css.push_str("; }\n");
+
+    // Like Typst's heading counter, a heading resets the counters of all
+    // deeper levels.
+    for &level in &usage.headings {
+        write!(
+            css,
+            "[data-counter=\"heading\"][data-level=\"{level}\"] \
+             {{ counter-increment: {}",
+            heading(level)
+        )
+        .unwrap();
+        if level < max_level {
+            css.push_str("; counter-reset:");
+            for deeper in level + 1..=max_level {
+                write!(css, " {}", heading(deeper)).unwrap();
+            }
+        }
+        css.push_str("; }\n");
+    }
+
+    for name in &usage.figures {
+        writeln!(
+            css,
+            "figure[data-counter={}] {{ counter-increment: {}; }}",
+            string(name),
+            figure(name)
+        )
+        .unwrap();
+    }
+
+    for ((level, pattern), parsed) in &usage.heading_patterns {
+        let content = content(parsed, *level, heading);
+        writeln!(
+            css,
+            "[data-counter=\"heading\"][data-level=\"{level}\"]\
+             [data-numbering-pattern={}]::before {{ content: {content} \" \"; }}",
+            string(pattern),
+        )
+        .unwrap();
+    }
+
+    for ((name, pattern), parsed) in &usage.figure_patterns {
+        let content = content(parsed, 1, |_| figure(name));
+        writeln!(
+            css,
+            "span[data-counter={}][data-numbering-pattern={}]::before \
+             {{ content: {content}; }}",
+            string(name),
+            string(pattern),
+        )
+        .unwrap();
+    }
+
+    Some(css)
+}
+
+/// The counters and numbering patterns used in a document.
+#[derive(Default)]
+struct Usage {
+    /// The levels of numbered headings.
+    headings: BTreeSet<usize>,
+    /// The numbering patterns of headings, by level.
+    heading_patterns: BTreeMap<(usize, EcoString), NumberingPattern>,
+    /// The counter names of numbered figures, like `figure-image`.
+    figures: BTreeSet<EcoString>,
+    /// The numbering patterns of figure captions, by counter name.
+    figure_patterns: BTreeMap<(EcoString, EcoString), NumberingPattern>,
+}
+
+impl Usage {
+    /// Records the counters of an element and its descendants.
+    fn visit(&mut self, elem: &HtmlElement) {
+        let counter = elem.attrs.get(attr::data_counter).filter(|name| is_name(name));
+        let pattern = elem
+            .attrs
+            .get(attr::data_numbering_pattern)
+            .and_then(|pattern| {
+                Some((pattern, pattern.parse::<NumberingPattern>().ok()?))
+            })
+            .filter(|(_, parsed)| parsed.is_css_compatible());
+
+        match counter.map(EcoString::as_str) {
+            Some("heading") => {
+                let level = elem
+                    .attrs
+                    .get(attr::data_level)
+                    .and_then(|level| level.parse::<usize>().ok())
+                    .filter(|&level| level > 0);
+                if let Some(level) = level {
+                    self.headings.insert(level);
+                    if let Some((pattern, parsed)) = pattern {
+                        self.heading_patterns.insert((level, pattern.clone()), parsed);
+                    }
+                }
+            }
+            Some(name) if name.starts_with("figure-") => {
+                if elem.tag == tag::figure {
+                    self.figures.insert(name.into());
+                } else if let (tag::span, Some((pattern, parsed))) = (elem.tag, pattern) {
+                    self.figure_patterns.insert((name.into(), pattern.clone()), parsed);
+                }
+            }
+            _ => {}
+        }
+
+        for child in &elem.children {
+            if let HtmlNode::Element(child) = child {
+                self.visit(child);
+            }
+        }
+    }
+}
+
+/// Builds the value of a CSS `content` property that displays the given
+/// number of counters with a numbering pattern.
+fn content(
+    pattern: &NumberingPattern,
+    count: usize,
+    counter: impl Fn(usize) -> EcoString,
+) -> EcoString {
+    let mut content = EcoString::new();
+    pattern.apply_with(
+        &mut content,
+        count,
+        |content, text| {
+            if !text.is_empty() {
+                push_part(content, &string(text));
+            }
+        },
+        |content, i, kind| {
+            let style = kind.css_counter_style().unwrap_or("decimal");
+            push_part(content, &eco_format!("counter({}, {style})", counter(i + 1)));
+        },
+    );
+    content
+}
+
+/// Appends a part to the value of a CSS `content` property.
+fn push_part(content: &mut EcoString, part: &str) {
+    if !content.is_empty() {
+        content.push(' ');
+    }
+    content.push_str(part);
+}
+
+/// Whether a counter name can be used as part of a CSS identifier as is.
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Encodes a CSS string.
+///
+/// Besides quotes and backslashes, this also escapes characters that the
+/// HTML encoder would turn into character references, as these are not
+/// decoded in the raw text of a `<style>` element.
+fn string(text: &str) -> EcoString {
+    let mut out = EcoString::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' | '\\' | '&' | '<' | '>' => write!(out, "\\{:x} ", c as u32).unwrap(),
+            c if c.is_control() => write!(out, "\\{:x} ", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
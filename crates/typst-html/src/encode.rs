@@ -65,6 +65,12 @@ fn write_element(w: &mut Writer, element: &HtmlElement) -> SourceResult<()> {
     for (attr, value) in &element.attrs.0 {
         w.buf.push(' ');
         w.buf.push_str(&attr.resolve());
+
+        // Boolean attributes are written without a value.
+        if value.is_empty() {
+            continue;
+        }
+
         w.buf.push('=');
         w.buf.push('"');
         for c in value.chars() {
@@ -116,8 +122,22 @@ fn write_element(w: &mut Writer, element: &HtmlElement) -> SourceResult<()> {
 }
 
 /// Whether the element should be pretty-printed.
+///
+/// The structural elements of a table are, too, because only their cells
+/// contain text. Each row thus ends up on its own line.
 fn is_pretty(element: &HtmlElement) -> bool {
-    tag::is_block_by_default(element.tag) || matches!(element.tag, tag::meta)
+    tag::is_block_by_default(element.tag)
+        || matches!(
+            element.tag,
+            tag::meta
+                | tag::table
+                | tag::colgroup
+                | tag::col
+                | tag::thead
+                | tag::tbody
+                | tag::tfoot
+                | tag::tr
+        )
 }
 
 /// Escape a character.
@@ -1,10 +1,12 @@
 //! Typst's HTML exporter.
 
+mod counters;
 mod encode;
 
 pub use self::encode::html;
 
 use comemo::{Track, Tracked, TrackedMut};
+use ecow::{eco_format, EcoString};
 use typst_library::diag::{bail, warning, At, SourceResult};
 use typst_library::engine::{Engine, Route, Sink, Traced};
 use typst_library::foundations::{Content, StyleChain, Target, TargetElem};
@@ -15,7 +17,7 @@ use typst_library::introspection::{
     Introspector, Locator, LocatorLink, SplitLocator, TagElem,
 };
 use typst_library::layout::{Abs, Axes, BoxElem, Region, Size};
-use typst_library::model::{DocumentInfo, ParElem};
+use typst_library::model::{DocumentInfo, ParElem, ParbreakElem};
 use typst_library::routines::{Arenas, Pair, RealizationKind, Routines};
 use typst_library::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
 use typst_library::World;
@@ -137,9 +139,14 @@ fn html_fragment_impl(
 
     engine.route.check_html_depth().at(content.span())?;
 
+    // Inline content is only wrapped in paragraphs if there are multiple of
+    // them. A single paragraph, like the body of an item in a tight list, is
+    // just inline content of its parent element.
+    let paragraphs = ParbreakElem::contained_in(content);
+
     let arenas = Arenas::default();
     let children = (engine.routines.realize)(
-        RealizationKind::HtmlFragment,
+        RealizationKind::HtmlFragment { paragraphs },
         &mut engine,
         &mut locator,
         &arenas,
@@ -160,7 +167,43 @@ fn handle_list<'a>(
     for (child, styles) in children {
         handle(engine, child, locator, styles, &mut output)?;
     }
-    Ok(output)
+
+    let mut coalesced = Vec::with_capacity(output.len());
+    for node in output {
+        push_coalesced(&mut coalesced, node);
+    }
+    Ok(coalesced)
+}
+
+/// Push a node, merging it into the previous one if both are inline elements
+/// with the same tag and attributes.
+///
+/// Show rules that apply per character (e.g. a regex show rule wrapping each
+/// letter) otherwise produce long runs of identical adjacent elements.
+fn push_coalesced(output: &mut Vec<HtmlNode>, node: HtmlNode) {
+    if let (Some(HtmlNode::Element(prev)), HtmlNode::Element(next)) =
+        (output.last_mut(), &node)
+    {
+        if can_coalesce(prev, next) {
+            let HtmlNode::Element(next) = node else { unreachable!() };
+            for child in next.children {
+                push_coalesced(&mut prev.children, child);
+            }
+            return;
+        }
+    }
+    output.push(node);
+}
+
+/// Whether two adjacent elements can be merged into one without changing the
+/// document's meaning.
+fn can_coalesce(prev: &HtmlElement, next: &HtmlElement) -> bool {
+    prev.tag == next.tag
+        && prev.attrs == next.attrs
+        && tag::is_inline_by_default(prev.tag)
+        && !tag::is_void(prev.tag)
+        // Each of these is meaningful on its own.
+        && !matches!(prev.tag, tag::q | tag::ruby | tag::rt | tag::rp)
 }
 
 /// Convert a child into HTML node(s).
@@ -227,11 +270,14 @@ fn handle(
         )?;
         output.push(HtmlNode::Frame(frame));
     } else {
-        engine.sink.warn(warning!(
-            child.span(),
-            "{} was ignored during HTML export",
-            child.elem().name()
-        ));
+        engine.sink.warn(
+            warning!(
+                child.span(),
+                "{} was ignored during HTML export",
+                child.elem().name()
+            )
+            .with_code("ignored-in-html"),
+        );
     }
     Ok(())
 }
@@ -244,12 +290,16 @@ fn root_element(output: Vec<HtmlNode>, info: &DocumentInfo) -> SourceResult<Html
         OutputKind::Body(body) => body,
         OutputKind::Leafs(leafs) => HtmlElement::new(tag::body).with_children(leafs),
     };
+    let stylesheet = counters::stylesheet(&body);
     Ok(HtmlElement::new(tag::html)
-        .with_children(vec![head_element(info).into(), body.into()]))
+        .with_children(vec![head_element(info, stylesheet).into(), body.into()]))
 }
 
 /// Generate a `<head>` element.
-fn head_element(info: &DocumentInfo) -> HtmlElement {
+///
+/// The stylesheet, if any, displays the numbers of elements that are numbered
+/// with CSS counters.
+fn head_element(info: &DocumentInfo, stylesheet: Option<EcoString>) -> HtmlElement {
     let mut children = vec![];
 
     children.push(HtmlElement::new(tag::meta).with_attr(attr::charset, "utf-8").into());
@@ -278,6 +328,23 @@ fn head_element(info: &DocumentInfo) -> HtmlElement {
         );
     }
 
+    for (key, value) in info.metadata.iter() {
+        children.push(
+            HtmlElement::new(tag::meta)
+                .with_attr(attr::name, eco_format!("typst:{key}"))
+                .with_attr(attr::content, value.to_text())
+                .into(),
+        );
+    }
+
+    if let Some(stylesheet) = stylesheet {
+        children.push(
+            HtmlElement::new(tag::style)
+                .with_children(vec![HtmlNode::Text(stylesheet, Span::detached())])
+                .into(),
+        );
+    }
+
     HtmlElement::new(tag::head).with_children(children)
 }
 
@@ -313,3 +380,55 @@ enum OutputKind {
     /// The user generated leafs which we wrap in a `<body>` and `<html>`.
     Leafs(Vec<HtmlNode>),
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::testing::TestWorld;
+    use typst_library::Feature;
+
+    use super::*;
+
+    #[test]
+    fn test_head_element_metadata() {
+        let world = TestWorld::new(
+            "#set document(title: \"Report\", metadata: (\n\
+             department: \"R&D\",\n\
+             classification: \"internal\",\n\
+             revision: 3,\n\
+             issued: datetime(year: 2024, month: 1, day: 2),\n\
+             ))\n\
+             = Report",
+        )
+        .with_features(&[Feature::Html]);
+        let document = typst::compile::<HtmlDocument>(&world).output.unwrap();
+        let output = html(&document).unwrap();
+        assert_eq!(output, html(&document).unwrap());
+
+        let start = output.find("<head>").unwrap();
+        let end = output.find("</head>").unwrap();
+        let metas: Vec<_> = output[start..end]
+            .split("<meta ")
+            .skip(1)
+            .filter_map(|tag| {
+                let attr = |name: &str| {
+                    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+                    let len = tag[start..].find('"')?;
+                    Some(tag[start..start + len].replace("&amp;", "&"))
+                };
+                Some((
+                    attr("name")?.strip_prefix("typst:")?.to_string(),
+                    attr("content")?,
+                ))
+            })
+            .collect();
+        assert_eq!(
+            metas,
+            [
+                ("department".to_string(), "R&D".to_string()),
+                ("classification".to_string(), "internal".to_string()),
+                ("revision".to_string(), "3".to_string()),
+                ("issued".to_string(), "2024-01-02".to_string()),
+            ]
+        );
+    }
+}
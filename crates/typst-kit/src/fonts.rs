@@ -13,7 +13,7 @@ use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use fontdb::{Database, Source};
-use typst_library::text::{Font, FontBook, FontInfo};
+use typst_library::text::{Font, FontBook, FontInfo, FontProblem};
 use typst_timing::TimingScope;
 
 /// Holds details about the location of a font and lazily the font itself.
@@ -167,16 +167,23 @@ impl FontSearcher {
 
             let info = self
                 .db
-                .with_face_data(face.id, FontInfo::new)
+                .with_face_data(face.id, FontInfo::load)
                 .expect("database must contain this font");
 
-            if let Some(info) = info {
-                self.book.push(info);
-                self.fonts.push(FontSlot {
+            match info {
+                Ok(info) => {
+                    self.book.push(info);
+                    self.fonts.push(FontSlot {
+                        path: Some(path.clone()),
+                        index: face.index,
+                        font: OnceLock::new(),
+                    });
+                }
+                Err(reason) => self.book.push_problem(FontProblem {
                     path: Some(path.clone()),
                     index: face.index,
-                    font: OnceLock::new(),
-                });
+                    reason,
+                }),
             }
         }
 
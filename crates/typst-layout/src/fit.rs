@@ -0,0 +1,66 @@
+use typst_library::diag::SourceResult;
+use typst_library::engine::Engine;
+use typst_library::foundations::{Packed, Resolve, StyleChain};
+use typst_library::introspection::Locator;
+use typst_library::layout::{Abs, Axes, FitTextElem, Frame, Region, Size};
+use typst_library::text::{TextElem, TextSize};
+
+/// Layout content at the largest text size that fits into the region.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_fit_text(
+    elem: &Packed<FitTextElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let min = elem.min(styles).resolve(styles);
+    let max = elem.max(styles).resolve(styles).max(min);
+    let step = elem
+        .step(styles)
+        .map(|step| step.resolve(styles))
+        .unwrap_or(Abs::pt(0.25))
+        .max(Abs::pt(0.01));
+
+    // Lay out the body at the given size with unbounded height to find out
+    // whether it fits. Each size is only laid out once, even if the search
+    // visits it again, because layout is memoized.
+    let fits = |engine: &mut Engine, size: Abs| -> SourceResult<bool> {
+        let pod = Region::new(Size::new(region.size.x, Abs::inf()), Axes::splat(false));
+        let frame = layout_at(elem, engine, locator.relayout(), styles, pod, size)?;
+        Ok(region.size.x.fits(frame.width()) && region.size.y.fits(frame.height()))
+    };
+
+    // Find the largest size that fits by binary search. Between `low` and
+    // `high`, the size at `low` always fits (or is the minimum) and the size
+    // at `high` never does.
+    let size = if fits(engine, max)? {
+        max
+    } else {
+        let (mut low, mut high) = (min, max);
+        while high - low > step {
+            let mid = (low + high) / 2.0;
+            if fits(engine, mid)? {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    };
+
+    layout_at(elem, engine, locator, styles, region, size)
+}
+
+/// Layout the body of the element with the given text size.
+fn layout_at(
+    elem: &Packed<FitTextElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Region,
+    size: Abs,
+) -> SourceResult<Frame> {
+    let local = TextElem::set_size(TextSize(size.into())).wrap();
+    crate::layout_frame(engine, &elem.body, locator, styles.chain(&local), region)
+}
@@ -10,6 +10,7 @@ use typst_library::layout::{
     Sides, Size, Sizing,
 };
 use typst_library::visualize::Stroke;
+use typst_library::{Feature, World};
 use typst_utils::Numeric;
 
 use crate::shapes::{clip_rect, fill_and_stroke};
@@ -98,6 +99,12 @@ pub fn layout_single_block(
         frame.label(label);
     }
 
+    if let Some(&role) = elem.role() {
+        if engine.world.library().features.is_enabled(Feature::A11yExtras) {
+            frame.set_role(role);
+        }
+    }
+
     Ok(frame)
 }
 
@@ -247,6 +254,14 @@ pub fn layout_multi_block(
         }
     }
 
+    if let Some(&role) = elem.role() {
+        if engine.world.library().features.is_enabled(Feature::A11yExtras) {
+            for frame in fragment.iter_mut() {
+                frame.set_role(role);
+            }
+        }
+    }
+
     Ok(fragment)
 }
 
@@ -5,21 +5,23 @@ use std::hash::Hash;
 use bumpalo::boxed::Box as BumpBox;
 use bumpalo::Bump;
 use comemo::{Track, Tracked, TrackedMut};
+use ecow::EcoString;
 use typst_library::diag::{bail, warning, SourceResult};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{record_memoized, Engine, Memoized, Route, Sink, Traced};
 use typst_library::foundations::{Packed, Resolve, Smart, StyleChain};
 use typst_library::introspection::{
     Introspector, Location, Locator, LocatorLink, SplitLocator, Tag, TagElem,
 };
 use typst_library::layout::{
     Abs, AlignElem, Alignment, Axes, BlockElem, ColbreakElem, FixedAlignment, FlushElem,
-    Fr, Fragment, Frame, PagebreakElem, PlaceElem, PlacementScope, Ratio, Region,
-    Regions, Rel, Size, Sizing, Spacing, VElem,
+    Fr, Fragment, Frame, Keep, PagebreakElem, PlaceElem, PlaceOverlap, PlacementScope,
+    Ratio, Region, Regions, Rel, Size, Sizing, Spacing, SpacingCollapse, VElem,
 };
 use typst_library::model::ParElem;
 use typst_library::routines::{Pair, Routines};
 use typst_library::text::TextElem;
 use typst_library::World;
+use typst_syntax::Span;
 
 use super::{layout_multi_block, layout_single_block};
 
@@ -42,7 +44,6 @@ pub fn collect<'a>(
         base,
         expand,
         output: Vec::with_capacity(children.len()),
-        last_was_par: false,
     }
     .run()
 }
@@ -56,7 +57,6 @@ struct Collector<'a, 'x, 'y> {
     expand: bool,
     locator: SplitLocator<'a>,
     output: Vec<Child<'a>>,
-    last_was_par: bool,
 }
 
 impl<'a> Collector<'a, '_, '_> {
@@ -83,11 +83,14 @@ impl<'a> Collector<'a, '_, '_> {
                     hint: "try using a `#colbreak()` instead",
                 );
             } else {
-                self.engine.sink.warn(warning!(
-                    child.span(),
-                    "{} was ignored during paged export",
-                    child.func().name()
-                ));
+                self.engine.sink.warn(
+                    warning!(
+                        child.span(),
+                        "{} was ignored during paged export",
+                        child.func().name()
+                    )
+                    .with_code("ignored-in-paged"),
+                );
             }
         }
 
@@ -97,7 +100,11 @@ impl<'a> Collector<'a, '_, '_> {
     /// Collect vertical spacing into a relative or fractional child.
     fn v(&mut self, elem: &'a Packed<VElem>, styles: StyleChain<'a>) {
         self.output.push(match elem.amount {
-            Spacing::Rel(rel) => Child::Rel(rel.resolve(styles), elem.weak(styles) as u8),
+            Spacing::Rel(rel) => Child::Rel(
+                rel.resolve(styles),
+                elem.weak(styles) as u8,
+                elem.collapse(styles),
+            ),
             Spacing::Fr(fr) => Child::Fr(fr),
         });
     }
@@ -114,18 +121,23 @@ impl<'a> Collector<'a, '_, '_> {
         let spacing = ParElem::spacing_in(styles);
         let costs = TextElem::costs_in(styles);
 
+        // Whether the first line should be indented depends on what the
+        // paragraph follows, as determined during realization.
+        let predecessor = elem.predecessor().copied().flatten();
+        let indent = ParElem::first_line_indent_in(styles).applies_after(predecessor);
+
         let lines = crate::layout_inline(
             self.engine,
             &elem.children,
             self.locator.next(&elem.span()),
             styles,
-            self.last_was_par,
+            indent,
             self.base,
             self.expand,
         )?
         .into_frames();
 
-        self.output.push(Child::Rel(spacing.into(), 4));
+        self.output.push(Child::Rel(spacing.into(), 4, SpacingCollapse::Sum));
 
         // Determine whether to prevent widow and orphans.
         let len = lines.len();
@@ -145,7 +157,7 @@ impl<'a> Collector<'a, '_, '_> {
 
         for (i, frame) in lines.into_iter().enumerate() {
             if i > 0 {
-                self.output.push(Child::Rel(leading.into(), 5));
+                self.output.push(Child::Rel(leading.into(), 5, SpacingCollapse::Sum));
             }
 
             // To prevent widows and orphans, we require enough space for
@@ -166,8 +178,7 @@ impl<'a> Collector<'a, '_, '_> {
                 .push(Child::Line(self.boxed(LineChild { frame, align, need })));
         }
 
-        self.output.push(Child::Rel(spacing.into(), 4));
-        self.last_was_par = true;
+        self.output.push(Child::Rel(spacing.into(), 4, SpacingCollapse::Sum));
 
         Ok(())
     }
@@ -180,6 +191,7 @@ impl<'a> Collector<'a, '_, '_> {
         let alone = self.children.len() == 1;
         let sticky = elem.sticky(styles);
         let breakable = elem.breakable(styles);
+        let keep = elem.keep(styles);
         let fr = match elem.height(styles) {
             Sizing::Fr(fr) => Some(fr),
             _ => None,
@@ -187,8 +199,10 @@ impl<'a> Collector<'a, '_, '_> {
 
         let fallback = LazyCell::new(|| ParElem::spacing_in(styles));
         let spacing = |amount| match amount {
-            Smart::Auto => Child::Rel((*fallback).into(), 4),
-            Smart::Custom(Spacing::Rel(rel)) => Child::Rel(rel.resolve(styles), 3),
+            Smart::Auto => Child::Rel((*fallback).into(), 4, SpacingCollapse::Sum),
+            Smart::Custom(Spacing::Rel(rel)) => {
+                Child::Rel(rel.resolve(styles), 3, SpacingCollapse::Sum)
+            }
             Smart::Custom(Spacing::Fr(fr)) => Child::Fr(fr),
         };
 
@@ -209,6 +223,7 @@ impl<'a> Collector<'a, '_, '_> {
             self.output.push(Child::Multi(self.boxed(MultiChild {
                 align,
                 sticky,
+                keep,
                 alone,
                 elem,
                 styles,
@@ -218,7 +233,6 @@ impl<'a> Collector<'a, '_, '_> {
         };
 
         self.output.push(spacing(elem.below(styles)));
-        self.last_was_par = false;
     }
 
     /// Collects a placed element into a [`PlacedChild`].
@@ -259,6 +273,11 @@ impl<'a> Collector<'a, '_, '_> {
         let locator = self.locator.next(&elem.span());
         let clearance = elem.clearance(styles);
         let delta = Axes::new(elem.dx(styles), elem.dy(styles)).resolve(styles);
+        let overlap = (!float && elem.avoid_overlap(styles) == PlaceOverlap::ShiftDown)
+            .then(|| OverlapGroup {
+                name: elem.group(styles),
+                max_shift: elem.max_shift(styles),
+            });
         self.output.push(Child::Placed(self.boxed(PlacedChild {
             align_x,
             align_y,
@@ -266,6 +285,7 @@ impl<'a> Collector<'a, '_, '_> {
             float,
             clearance,
             delta,
+            overlap,
             elem,
             styles,
             locator,
@@ -290,8 +310,8 @@ impl<'a> Collector<'a, '_, '_> {
 pub enum Child<'a> {
     /// An introspection tag.
     Tag(&'a Tag),
-    /// Relative spacing with a specific weakness level.
-    Rel(Rel<Abs>, u8),
+    /// Relative spacing with a specific weakness level and collapse mode.
+    Rel(Rel<Abs>, u8, SpacingCollapse),
     /// Fractional spacing.
     Fr(Fr),
     /// An already layouted line of a paragraph.
@@ -366,6 +386,7 @@ fn layout_single_impl(
     styles: StyleChain,
     region: Region,
 ) -> SourceResult<Frame> {
+    record_memoized(Memoized::Layout);
     let link = LocatorLink::new(locator);
     let locator = Locator::link(&link);
     let mut engine = Engine {
@@ -386,6 +407,7 @@ fn layout_single_impl(
 pub struct MultiChild<'a> {
     pub align: Axes<FixedAlignment>,
     pub sticky: bool,
+    pub keep: Keep,
     alone: bool,
     elem: &'a Packed<BlockElem>,
     styles: StyleChain<'a>,
@@ -421,6 +443,22 @@ impl<'a> MultiChild<'a> {
         Ok((frame, spill))
     }
 
+    /// Whether the block fits into one full region of the given regions'
+    /// current width.
+    ///
+    /// The block is laid out into repeating full regions, so that it spills
+    /// into a second one if it is too tall.
+    pub fn fits_full(&self, engine: &mut Engine, regions: Regions) -> SourceResult<bool> {
+        let full =
+            Regions::repeat(Size::new(regions.size.x, regions.full), regions.expand);
+        Ok(self.layout_full(engine, full)?.len() == 1)
+    }
+
+    /// The span of the block.
+    pub fn span(&self) -> Span {
+        self.elem.span()
+    }
+
     /// The shared internal implementation of [`Self::layout`] and
     /// [`MultiSpill::layout`].
     fn layout_full(
@@ -462,6 +500,7 @@ fn layout_multi_impl(
     styles: StyleChain,
     regions: Regions,
 ) -> SourceResult<Fragment> {
+    record_memoized(Memoized::Layout);
     let link = LocatorLink::new(locator);
     let locator = Locator::link(&link);
     let mut engine = Engine {
@@ -566,6 +605,7 @@ pub struct PlacedChild<'a> {
     pub float: bool,
     pub clearance: Abs,
     pub delta: Axes<Rel<Abs>>,
+    pub overlap: Option<OverlapGroup>,
     elem: &'a Packed<PlaceElem>,
     styles: StyleChain<'a>,
     locator: Locator<'a>,
@@ -600,6 +640,20 @@ impl PlacedChild<'_> {
     pub fn location(&self) -> Location {
         self.elem.location().unwrap()
     }
+
+    /// The element's span.
+    pub fn span(&self) -> Span {
+        self.elem.span()
+    }
+}
+
+/// The group among which a placed child avoids overlaps.
+#[derive(Debug)]
+pub struct OverlapGroup {
+    /// The name of the group.
+    pub name: Option<EcoString>,
+    /// The maximum distance by which the child may be moved down.
+    pub max_shift: Abs,
 }
 
 /// Wraps a parameterized computation and caches its latest output.
@@ -552,6 +552,12 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
         let separator = layout_footnote_separator(self.engine, self.config, base)?;
         area.push_footnote_separator(self.config, separator);
 
+        // Indicate that the footnote is continued from the previous region.
+        if let Some(indicator) = &self.config.footnote.continuation {
+            let frame = layout_footnote_part(self.engine, self.config, indicator, base)?;
+            area.push_footnote(self.config, frame);
+        }
+
         // Save the footnote's frame.
         let frame = iter.next().unwrap();
         area.push_footnote(self.config, frame);
@@ -583,10 +589,20 @@ fn layout_footnote_separator(
     engine: &mut Engine,
     config: &Config,
     base: Size,
+) -> SourceResult<Frame> {
+    layout_footnote_part(engine, config, &config.footnote.separator, base)
+}
+
+/// Lay out content that accompanies the footnotes, like the separator.
+fn layout_footnote_part(
+    engine: &mut Engine,
+    config: &Config,
+    content: &Content,
+    base: Size,
 ) -> SourceResult<Frame> {
     crate::layout_frame(
         engine,
-        &config.footnote.separator,
+        content,
         Locator::root(),
         config.shared,
         Region::new(base, Axes::new(config.footnote.expand, false)),
@@ -1,6 +1,13 @@
-use typst_library::introspection::Tag;
+use std::collections::HashMap;
+
+use comemo::Tracked;
+use typst_library::diag::warning;
+use typst_library::engine::Engine;
+use typst_library::foundations::NativeElement;
+use typst_library::introspection::{Introspector, Location, Locator, Tag};
 use typst_library::layout::{
-    Abs, Axes, FixedAlignment, Fr, Frame, FrameItem, Point, Region, Regions, Rel, Size,
+    Abs, Axes, FixedAlignment, Fr, Frame, FrameItem, Keep, PlaceOverlapMarker, Point,
+    Position, Region, Regions, Rel, Size, SpacingCollapse,
 };
 use typst_utils::Numeric;
 
@@ -74,8 +81,8 @@ struct DistributionSnapshot<'a, 'b> {
 enum Item<'a, 'b> {
     /// An introspection tag.
     Tag(&'a Tag),
-    /// Absolute spacing and its weakness level.
-    Abs(Abs, u8),
+    /// Absolute spacing, its weakness level, and its collapse mode.
+    Abs(Abs, u8, SpacingCollapse),
     /// Fractional spacing or a fractional block.
     Fr(Fr, Option<&'b SingleChild<'a>>),
     /// A frame for a laid out line or block.
@@ -130,7 +137,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
     fn child(&mut self, child: &'b Child<'a>) -> FlowResult<()> {
         match child {
             Child::Tag(tag) => self.tag(tag),
-            Child::Rel(amount, weakness) => self.rel(*amount, *weakness),
+            Child::Rel(amount, weakness, collapse) => {
+                self.rel(*amount, *weakness, *collapse)
+            }
             Child::Fr(fr) => self.fr(*fr),
             Child::Line(line) => self.line(line)?,
             Child::Single(single) => self.single(single)?,
@@ -157,14 +166,16 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
     }
 
     /// Processes relative spacing.
-    fn rel(&mut self, amount: Rel<Abs>, weakness: u8) {
+    fn rel(&mut self, amount: Rel<Abs>, weakness: u8, collapse: SpacingCollapse) {
         let amount = amount.relative_to(self.regions.base().y);
-        if weakness > 0 && !self.keep_spacing(amount, weakness) {
+        if collapsible(weakness, collapse)
+            && !self.keep_spacing(amount, weakness, collapse)
+        {
             return;
         }
 
         self.regions.size.y -= amount;
-        self.items.push(Item::Abs(amount, weakness));
+        self.items.push(Item::Abs(amount, weakness, collapse));
     }
 
     /// Processes fractional spacing.
@@ -175,15 +186,36 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
     /// Decides whether to keep weak spacing based on previous items. If there
     /// is a preceding weak spacing, it might be patched in place.
-    fn keep_spacing(&mut self, amount: Abs, weakness: u8) -> bool {
+    fn keep_spacing(
+        &mut self,
+        amount: Abs,
+        weakness: u8,
+        collapse: SpacingCollapse,
+    ) -> bool {
         for item in self.items.iter_mut().rev() {
             match *item {
-                Item::Abs(prev_amount, prev_weakness @ 1..) => {
-                    if weakness <= prev_weakness
+                Item::Abs(prev_amount, prev_weakness, prev_collapse)
+                    if collapsible(prev_weakness, prev_collapse) =>
+                {
+                    if collapse == SpacingCollapse::Max
+                        || prev_collapse == SpacingCollapse::Max
+                    {
+                        // Max-collapsing spacing merges with the adjacent
+                        // spacing into the larger one of the two and keeps
+                        // max-collapsing with whatever follows.
+                        if amount > prev_amount {
+                            self.regions.size.y -= amount - prev_amount;
+                        }
+                        *item = Item::Abs(
+                            amount.max(prev_amount),
+                            weakness.min(prev_weakness),
+                            SpacingCollapse::Max,
+                        );
+                    } else if weakness <= prev_weakness
                         && (weakness < prev_weakness || amount > prev_amount)
                     {
                         self.regions.size.y -= amount - prev_amount;
-                        *item = Item::Abs(amount, weakness);
+                        *item = Item::Abs(amount, weakness, collapse);
                     }
                     return false;
                 }
@@ -199,7 +231,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
     fn trim_spacing(&mut self) {
         for (i, item) in self.items.iter().enumerate().rev() {
             match *item {
-                Item::Abs(amount, 1..) => {
+                Item::Abs(amount, weakness, collapse)
+                    if collapsible(weakness, collapse) =>
+                {
                     self.regions.size.y += amount;
                     self.items.remove(i);
                     break;
@@ -214,7 +248,11 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
     fn weak_spacing(&mut self) -> Abs {
         for item in self.items.iter().rev() {
             match *item {
-                Item::Abs(amount, 1..) => return amount,
+                Item::Abs(amount, weakness, collapse)
+                    if collapsible(weakness, collapse) =>
+                {
+                    return amount;
+                }
                 Item::Tag(_) | Item::Abs(..) | Item::Placed(..) => {}
                 Item::Frame(..) | Item::Fr(..) => break,
             }
@@ -283,6 +321,22 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
         // Lay out the block.
         let (frame, spill) = multi.layout(self.composer.engine, self.regions)?;
+
+        // If the block should be kept together, but doesn't fit, move it to
+        // the next region as a whole if it fits there. Otherwise, it breaks
+        // as usual.
+        if spill.is_some() && multi.keep.is_on() && self.regions.may_progress() {
+            if multi.fits_full(self.composer.engine, self.regions)? {
+                return Err(Stop::Finish(false));
+            } else if multi.keep == Keep::Warn {
+                self.composer.engine.sink.warn(warning!(
+                    multi.span(),
+                    "block is too tall to be kept together";
+                    hint: "it was broken across regions instead",
+                ));
+            }
+        }
+
         self.frame(frame, multi.align, multi.sticky, true)?;
 
         // If the block didn't fully fit into the current region, save it into
@@ -456,7 +510,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         // Determine the amount of used space and the sum of fractionals.
         for item in &self.items {
             match item {
-                Item::Abs(v, _) => used.y += *v,
+                Item::Abs(v, ..) => used.y += *v,
                 Item::Fr(v, child) => {
                     frs += *v;
                     has_fr_child |= child.is_some();
@@ -502,6 +556,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         let mut ruler = FixedAlignment::Start;
         let mut offset = Abs::zero();
         let mut fr_frames = fr_frames.into_iter();
+        let mut shifts = None;
 
         // Position all items.
         for item in self.items {
@@ -511,7 +566,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
                     let pos = Point::with_y(y);
                     output.push(pos, FrameItem::Tag(tag.clone()));
                 }
-                Item::Abs(v, _) => {
+                Item::Abs(v, ..) => {
                     offset += v;
                 }
                 Item::Fr(v, single) => {
@@ -541,9 +596,23 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
                         _ => offset + ruler.position(free),
                     };
 
-                    let pos = Point::new(x, y)
+                    let mut pos = Point::new(x, y)
                         + placed.delta.zip_map(size, Rel::relative_to).to_point();
 
+                    if placed.overlap.is_some() {
+                        let engine = &mut *self.composer.engine;
+                        let shifts = shifts
+                            .get_or_insert_with(|| overlap_shifts(engine.introspector));
+                        pos.y += avoid_overlap(
+                            engine,
+                            shifts,
+                            placed,
+                            frame.size(),
+                            pos,
+                            &mut output,
+                        );
+                    }
+
                     output.push_frame(pos, frame);
                 }
             }
@@ -566,3 +635,87 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         self.items.truncate(snapshot.items);
     }
 }
+
+/// Records the unadjusted position of a placed child that avoids overlaps
+/// and determines how far it must be moved down.
+fn avoid_overlap(
+    engine: &mut Engine,
+    shifts: &HashMap<Location, Abs>,
+    placed: &PlacedChild,
+    size: Size,
+    pos: Point,
+    output: &mut Frame,
+) -> Abs {
+    let Some(group) = &placed.overlap else { return Abs::zero() };
+
+    let mut marker =
+        PlaceOverlapMarker::new(group.name.clone(), size.x.into(), size.y.into()).pack();
+    let key = typst_utils::hash128(&marker);
+    let loc = Locator::synthesize(placed.location())
+        .split()
+        .next_location(engine.introspector, key);
+    marker.set_location(loc);
+    output.push(pos, FrameItem::Tag(Tag::Start(marker)));
+    output.push(pos, FrameItem::Tag(Tag::End(loc, key)));
+
+    let mut shift = shifts.get(&loc).copied().unwrap_or_default();
+    if shift > group.max_shift {
+        engine.sink.warn(warning!(
+            placed.span(),
+            "placed element could not be moved far enough to avoid overlapping";
+            hint: "try increasing the `max-shift` or moving the element"
+        ));
+        shift = group.max_shift;
+    }
+
+    shift
+}
+
+/// Determines how far the placed elements that avoid overlaps must be moved
+/// down, indexed by the locations of their markers.
+///
+/// The elements of a group on the same page are stacked in document order,
+/// based on their unadjusted positions from the previous layout iteration. An
+/// element is only moved below earlier elements whose horizontal extents
+/// overlap its own, so that notes in opposite margins don't push each other
+/// down. Since the unadjusted positions do not depend on the adjustment, the
+/// layout converges.
+fn overlap_shifts(introspector: Tracked<Introspector>) -> HashMap<Location, Abs> {
+    let mut groups: HashMap<_, Vec<_>> = HashMap::new();
+    for elem in introspector.query(&PlaceOverlapMarker::elem().select()) {
+        let marker = elem.to_packed::<PlaceOverlapMarker>().unwrap();
+        let loc = marker.location().unwrap();
+        let Some(Position { page, point }) = introspector.position(loc) else {
+            continue;
+        };
+        let size = Size::new(marker.width().abs, marker.height().abs);
+        groups
+            .entry((marker.group().clone(), page))
+            .or_default()
+            .push((loc, point, size));
+    }
+
+    let mut shifts = HashMap::new();
+    for markers in groups.into_values() {
+        // The horizontal extents and bottom edges of the moved elements.
+        let mut stacked: Vec<(Abs, Abs, Abs)> = vec![];
+        for (loc, point, size) in markers {
+            let (left, right) = (point.x, point.x + size.x);
+            let top = stacked
+                .iter()
+                .filter(|&&(l, r, _)| l < right && left < r)
+                .map(|&(_, _, bottom)| bottom)
+                .fold(point.y, Abs::max);
+            stacked.push((left, right, top + size.y));
+            shifts.insert(loc, top - point.y);
+        }
+    }
+
+    shifts
+}
+
+/// Whether spacing with the given weakness level and collapse mode collapses
+/// with adjacent spacing and at the end of a region.
+fn collapsible(weakness: u8, collapse: SpacingCollapse) -> bool {
+    weakness > 0 || collapse == SpacingCollapse::Max
+}
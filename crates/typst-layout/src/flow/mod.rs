@@ -14,8 +14,8 @@ use std::rc::Rc;
 use bumpalo::Bump;
 use comemo::{Track, Tracked, TrackedMut};
 use ecow::EcoVec;
-use typst_library::diag::{bail, At, SourceDiagnostic, SourceResult};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::diag::{bail, At, PanicScope, SourceDiagnostic, SourceResult};
+use typst_library::engine::{record_memoized, Engine, Memoized, Route, Sink, Traced};
 use typst_library::foundations::{Content, Packed, Resolve, StyleChain};
 use typst_library::introspection::{
     Introspector, Location, Locator, LocatorLink, SplitLocator, Tag,
@@ -120,6 +120,8 @@ fn layout_fragment_impl(
     columns: NonZeroUsize,
     column_gutter: Rel<Abs>,
 ) -> SourceResult<Fragment> {
+    record_memoized(Memoized::Layout);
+    let _scope = PanicScope::enter(content.span(), "content");
     if !regions.size.x.is_finite() && regions.expand.x {
         bail!(content.span(), "cannot expand into infinite width");
     }
@@ -190,7 +192,8 @@ pub(crate) fn layout_flow(
             ColumnConfig { count, width, gutter, dir }
         },
         footnote: FootnoteConfig {
-            separator: FootnoteEntry::separator_in(shared),
+            separator: FootnoteEntry::separator_in(shared).unwrap_or_default(),
+            continuation: FootnoteEntry::continuation_indicator_in(shared),
             clearance: FootnoteEntry::clearance_in(shared),
             gap: FootnoteEntry::gap_in(shared),
             expand: regions.expand.x,
@@ -334,6 +337,8 @@ struct Config<'x> {
 struct FootnoteConfig {
     /// The separator between flow content and footnotes. Typically a line.
     separator: Content,
+    /// Content that precedes a footnote continued from the previous region.
+    continuation: Option<Content>,
     /// The amount of space left above the separator.
     clearance: Abs,
     /// The gap between footnote entries.
@@ -2,15 +2,18 @@ use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use ecow::eco_format;
-use typst_library::diag::{bail, At, Hint, HintedStrResult, HintedString, SourceResult};
+use typst_library::diag::{
+    bail, At, Hint, HintedStrResult, HintedString, PanicScope, SourceResult,
+};
 use typst_library::engine::Engine;
 use typst_library::foundations::{Content, Smart, StyleChain};
 use typst_library::introspection::Locator;
 use typst_library::layout::{
-    Abs, Alignment, Axes, Celled, Fragment, Length, Regions, Rel, ResolvedCelled, Sides,
-    Sizing,
+    Abs, Alignment, Axes, Celled, Fragment, FrameRole, Length, Regions, Rel,
+    ResolvedCelled, Sides, Sizing,
 };
 use typst_library::visualize::{Paint, Stroke};
+use typst_library::{Feature, World};
 use typst_syntax::Span;
 use typst_utils::NonZeroExt;
 
@@ -21,7 +24,8 @@ use super::{Footer, Header, Line, Repeatable};
 pub trait ResolvableCell {
     /// Resolves the cell's fields, given its coordinates and default grid-wide
     /// fill, align, inset and stroke properties, plus the expected value of
-    /// the `breakable` field.
+    /// the `breakable` field. If `mirrored` is true, the grid runs against
+    /// the text direction, so start and end alignment are swapped.
     /// Returns a final Cell.
     #[allow(clippy::too_many_arguments)]
     fn resolve_cell<'a>(
@@ -33,6 +37,7 @@ pub trait ResolvableCell {
         inset: Sides<Option<Rel<Length>>>,
         stroke: Sides<Option<Option<Arc<Stroke<Abs>>>>>,
         breakable: bool,
+        mirrored: bool,
         locator: Locator<'a>,
         styles: StyleChain,
     ) -> Cell<'a>;
@@ -115,6 +120,12 @@ pub struct Cell<'a> {
     /// By default, a cell spanning only fixed-size rows is unbreakable, while
     /// a cell spanning at least one `auto`-sized row is breakable.
     pub breakable: bool,
+    /// Whether the cell's first text baseline is aligned with those of the
+    /// other baseline-aligned cells in its row.
+    pub baseline: bool,
+    /// The semantic role of the cell's frames, if the
+    /// [`A11yExtras`](Feature::A11yExtras) feature is enabled.
+    pub role: Option<FrameRole>,
 }
 
 impl<'a> Cell<'a> {
@@ -129,6 +140,8 @@ impl<'a> Cell<'a> {
             stroke: Sides::splat(None),
             stroke_overridden: Sides::splat(false),
             breakable: true,
+            baseline: false,
+            role: None,
         }
     }
 
@@ -145,11 +158,21 @@ impl<'a> Cell<'a> {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
+        let _scope = PanicScope::enter(self.body.span(), "grid cell");
         let mut locator = self.locator.relayout();
         if disambiguator > 0 {
             locator = locator.split().next_inner(disambiguator as u128);
         }
-        crate::layout_fragment(engine, &self.body, locator, styles, regions)
+        let mut fragment =
+            crate::layout_fragment(engine, &self.body, locator, styles, regions)?;
+        if let Some(role) = self.role {
+            if engine.world.library().features.is_enabled(Feature::A11yExtras) {
+                for frame in fragment.iter_mut() {
+                    frame.set_role(role);
+                }
+            }
+        }
+        Ok(fragment)
     }
 }
 
@@ -244,6 +267,7 @@ impl<'a> CellGrid<'a> {
         align: &Celled<Smart<Alignment>>,
         inset: &Celled<Sides<Option<Rel<Length>>>>,
         stroke: &ResolvedCelled<Sides<Option<Option<Arc<Stroke>>>>>,
+        mirrored: bool,
         engine: &mut Engine,
         styles: StyleChain,
         span: Span,
@@ -559,6 +583,7 @@ impl<'a> CellGrid<'a> {
                     inset.resolve(engine, styles, x, y)?,
                     stroke.resolve(engine, styles, x, y)?,
                     resolve_breakable(y, rowspan),
+                    mirrored,
                     locator.next(&cell_span),
                     styles,
                 );
@@ -821,6 +846,7 @@ impl<'a> CellGrid<'a> {
                         inset.resolve(engine, styles, x, y)?,
                         stroke.resolve(engine, styles, x, y)?,
                         resolve_breakable(y, 1),
+                        mirrored,
                         locator.next(&()),
                         styles,
                     );
@@ -0,0 +1,340 @@
+use ecow::{eco_format, EcoString};
+use typst_library::diag::{warning, SourceResult};
+use typst_library::engine::Engine;
+use typst_library::foundations::repr::separated_list;
+use typst_library::foundations::{
+    Content, NativeElement, Packed, Resolve, Smart, StyleChain,
+};
+use typst_library::html::{attr, tag, HtmlElem};
+use typst_library::introspection::Locator;
+use typst_library::layout::{Abs, Dir, GridElem, Rel, Sizing};
+use typst_library::model::{TableCell, TableElem, TableHeaderScope};
+use typst_utils::{round_with_precision, Numeric};
+
+use super::{grid_to_cellgrid, table_to_cellgrid, CellGrid};
+
+/// Converts a table into semantic HTML.
+///
+/// The rows of the table's header and footer end up in `<thead>` and
+/// `<tfoot>`, while all other rows are placed into `<tbody>`. Cells in the
+/// header and cells marked as headers become `<th>` elements with a `scope`.
+/// Column sizes are carried over into a `<colgroup>` as far as CSS allows.
+/// Gutters, strokes, fills, and insets are not reflected in the output. An
+/// explicit direction becomes the table's `dir` attribute.
+#[typst_macros::time(span = elem.span())]
+pub fn html_table(
+    elem: &Packed<TableElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let dir = elem.dir(styles);
+    let is_rtl = super::resolve_rtl(dir, styles, elem.span())?;
+    let grid = table_to_cellgrid(elem, engine, Locator::root(), styles, is_rtl)?;
+    let span = elem.span();
+    let wrap = |tag, children: Vec<Content>| {
+        HtmlElem::new(tag)
+            .with_body(Some(Content::sequence(children)))
+            .pack()
+            .spanned(span)
+    };
+
+    let mut children = vec![];
+    if let Some(caption) = elem.html_caption() {
+        children.push(caption.clone().pack());
+    }
+
+    if let Some(colgroup) = colgroup(&grid, styles) {
+        children.push(wrap(tag::colgroup, colgroup));
+    }
+
+    let step = if grid.has_gutter { 2 } else { 1 };
+    let head_end = grid.header.as_ref().map_or(0, |header| header.unwrap().end);
+    let foot_start = grid
+        .footer
+        .as_ref()
+        .map_or(grid.rows.len(), |footer| footer.unwrap().start);
+
+    let mut head = vec![];
+    let mut body = vec![];
+    let mut foot = vec![];
+    for y in (0..grid.rows.len()).step_by(step) {
+        let in_head = y < head_end;
+        let mut cells = vec![];
+        for x in (0..grid.cols.len()).step_by(step) {
+            let Some(cell) = grid.cell(x, y) else { continue };
+            let Some(packed) = cell.body.to_packed::<TableCell>() else { continue };
+            let scope =
+                packed.header(styles).or(in_head.then_some(TableHeaderScope::Col));
+
+            let mut html = HtmlElem::new(if scope.is_some() { tag::th } else { tag::td });
+            if let Some(scope) = scope {
+                html = html.with_attr(
+                    attr::scope,
+                    match scope {
+                        TableHeaderScope::Col => "col",
+                        TableHeaderScope::Row => "row",
+                    },
+                );
+            }
+            if cell.colspan.get() > 1 {
+                html = html.with_attr(attr::colspan, eco_format!("{}", cell.colspan));
+            }
+            if cell.rowspan.get() > 1 {
+                html = html.with_attr(attr::rowspan, eco_format!("{}", cell.rowspan));
+            }
+
+            cells.push(
+                html.with_body(Some(cell.body.clone())).pack().spanned(packed.span()),
+            );
+        }
+
+        let row = wrap(tag::tr, cells);
+        if in_head {
+            head.push(row);
+        } else if y >= foot_start {
+            foot.push(row);
+        } else {
+            body.push(row);
+        }
+    }
+
+    if !head.is_empty() {
+        children.push(wrap(tag::thead, head));
+    }
+    if !body.is_empty() {
+        children.push(wrap(tag::tbody, body));
+    }
+    if !foot.is_empty() {
+        children.push(wrap(tag::tfoot, foot));
+    }
+
+    let mut table = HtmlElem::new(tag::table);
+    if let Smart::Custom(dir) = dir {
+        table = table.with_attr(attr::dir, css_dir(dir));
+    }
+    Ok(table
+        .with_body(Some(Content::sequence(children)))
+        .pack()
+        .spanned(span))
+}
+
+/// Converts a grid into a `<div>` with CSS grid layout.
+///
+/// The column and row sizes become the grid's template and the gutter becomes
+/// its `gap`. Each cell is a `<div>` that is explicitly placed into its slot,
+/// spanning as many columns and rows as in paged layout. Headers and footers
+/// are kept in place, but don't repeat. An explicit direction becomes the
+/// `dir` attribute. Features that CSS grid cannot express are dropped with a
+/// warning.
+#[typst_macros::time(span = elem.span())]
+pub fn html_grid(
+    elem: &Packed<GridElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let dir = elem.dir(styles);
+    let is_rtl = super::resolve_rtl(dir, styles, elem.span())?;
+    let grid = grid_to_cellgrid(elem, engine, Locator::root(), styles, is_rtl)?;
+    let span = elem.span();
+    let step = if grid.has_gutter { 2 } else { 1 };
+    let mut dropped = vec![];
+
+    let cols: Vec<Sizing> = grid.cols.iter().step_by(step).copied().collect();
+    let rows: Vec<Sizing> = grid.rows.iter().step_by(step).copied().collect();
+    let mut style = eco_format!(
+        "display: grid; grid-template-columns: {}",
+        css_tracks(&cols, styles)
+    );
+    if !rows.iter().all(|row| row.is_auto()) {
+        style.push_str(&eco_format!(
+            "; grid-template-rows: {}",
+            css_tracks(&rows, styles)
+        ));
+    }
+
+    if grid.has_gutter {
+        let mut gap = |tracks: &[Sizing]| {
+            let gutters: Vec<Sizing> =
+                tracks.iter().skip(1).step_by(2).copied().collect();
+            if gutters.windows(2).any(|pair| pair[0] != pair[1]) {
+                dropped.push("gutters of varying sizes");
+            }
+            match gutters.first() {
+                Some(Sizing::Rel(rel)) => Some(css_width(rel.resolve(styles))),
+                Some(_) => {
+                    dropped.push("fractional and automatic gutters");
+                    Some("0px".into())
+                }
+                None => None,
+            }
+        };
+
+        // A grid with only one column or row has no gutter in that direction,
+        // so any gap will do.
+        let row_gap = gap(&grid.rows);
+        let column_gap = gap(&grid.cols);
+        match (row_gap, column_gap) {
+            (Some(row), Some(column)) if row != column => {
+                style.push_str(&eco_format!("; gap: {row} {column}"));
+            }
+            (Some(gap), _) | (None, Some(gap)) => {
+                style.push_str(&eco_format!("; gap: {gap}"));
+            }
+            (None, None) => {}
+        }
+    }
+
+    let mut cells = vec![];
+    for y in (0..grid.rows.len()).step_by(step) {
+        for x in (0..grid.cols.len()).step_by(step) {
+            let Some(cell) = grid.cell(x, y) else { continue };
+            if cell.fill.is_some() {
+                dropped.push("fills");
+            }
+            if cell.stroke.iter().any(Option::is_some) {
+                dropped.push("strokes");
+            }
+
+            let style = eco_format!(
+                "grid-column: {}; grid-row: {}",
+                css_placement(x / step, cell.colspan.get()),
+                css_placement(y / step, cell.rowspan.get()),
+            );
+            cells.push(
+                HtmlElem::new(tag::div)
+                    .with_attr(attr::style, style)
+                    .with_body(Some(cell.body.clone()))
+                    .pack()
+                    .spanned(cell.body.span()),
+            );
+        }
+    }
+
+    if grid.hlines.iter().chain(&grid.vlines).any(|lines| !lines.is_empty()) {
+        dropped.push("strokes");
+    }
+    if grid
+        .header
+        .as_ref()
+        .is_some_and(|header| header.as_repeated().is_some())
+    {
+        dropped.push("repeated headers");
+    }
+    if grid
+        .footer
+        .as_ref()
+        .is_some_and(|footer| footer.as_repeated().is_some())
+    {
+        dropped.push("repeated footers");
+    }
+
+    dropped.sort_unstable();
+    dropped.dedup();
+    if !dropped.is_empty() {
+        engine.sink.warn(warning!(
+            span,
+            "grid {} were ignored during HTML export",
+            separated_list(&dropped, "and");
+            hint: "CSS grid has no equivalent for them"
+        ));
+    }
+
+    let mut div = HtmlElem::new(tag::div).with_attr(attr::style, style);
+    if let Smart::Custom(dir) = dir {
+        div = div.with_attr(attr::dir, css_dir(dir));
+    }
+    Ok(div.with_body(Some(Content::sequence(cells))).pack().spanned(span))
+}
+
+/// The value of the `dir` attribute for an explicit grid direction.
+fn css_dir(dir: Dir) -> &'static str {
+    if dir == Dir::RTL {
+        "rtl"
+    } else {
+        "ltr"
+    }
+}
+
+/// Converts track sizes into a CSS grid template.
+fn css_tracks(tracks: &[Sizing], styles: StyleChain) -> EcoString {
+    let mut template = EcoString::new();
+    for (i, track) in tracks.iter().enumerate() {
+        if i > 0 {
+            template.push(' ');
+        }
+        match track {
+            Sizing::Auto => template.push_str("auto"),
+            Sizing::Rel(rel) => template.push_str(&css_width(rel.resolve(styles))),
+            Sizing::Fr(fr) => {
+                let fr = round_with_precision(fr.get(), 2);
+                template.push_str(&eco_format!("{fr}fr"));
+            }
+        }
+    }
+    template
+}
+
+/// Converts the zero-based start and the span of a cell along one axis into a
+/// CSS grid placement.
+fn css_placement(start: usize, span: usize) -> EcoString {
+    if span > 1 {
+        eco_format!("{} / span {span}", start + 1)
+    } else {
+        eco_format!("{}", start + 1)
+    }
+}
+
+/// Creates `<col>` elements for the columns of the grid, unless all of them
+/// are automatically sized.
+///
+/// Fractional columns are sized relative to each other, which matches their
+/// paged layout if no other column takes up space.
+fn colgroup(grid: &CellGrid, styles: StyleChain) -> Option<Vec<Content>> {
+    let step = if grid.has_gutter { 2 } else { 1 };
+    let cols: Vec<Sizing> = grid.cols.iter().step_by(step).copied().collect();
+    if cols.iter().all(|col| col.is_auto()) {
+        return None;
+    }
+
+    let total_fr: f64 = cols
+        .iter()
+        .filter_map(|col| match col {
+            Sizing::Fr(fr) => Some(fr.get()),
+            _ => None,
+        })
+        .sum();
+
+    let cols = cols
+        .into_iter()
+        .map(|col| {
+            let mut elem = HtmlElem::new(tag::col);
+            let width = match col {
+                Sizing::Auto => None,
+                Sizing::Rel(rel) => Some(css_width(rel.resolve(styles))),
+                Sizing::Fr(fr) if total_fr > 0.0 => {
+                    let percent = round_with_precision(fr.get() / total_fr * 100.0, 2);
+                    Some(eco_format!("{percent}%"))
+                }
+                Sizing::Fr(_) => None,
+            };
+            if let Some(width) = width {
+                elem = elem.with_attr(attr::style, eco_format!("width: {width}"));
+            }
+            elem.pack()
+        })
+        .collect();
+
+    Some(cols)
+}
+
+/// Converts a relative length into a CSS width.
+fn css_width(rel: Rel<Abs>) -> EcoString {
+    let percent = round_with_precision(rel.rel.get() * 100.0, 2);
+    // CSS pixels are defined as 1/96 of an inch.
+    let px = round_with_precision(rel.abs.to_inches() * 96.0, 2);
+    match (rel.rel.is_zero(), rel.abs.is_zero()) {
+        (true, _) => eco_format!("{px}px"),
+        (false, true) => eco_format!("{percent}%"),
+        (false, false) => eco_format!("calc({percent}% + {px}px)"),
+    }
+}
@@ -56,6 +56,12 @@ pub struct GridLayouter<'a> {
     /// The simulated footer height for this region.
     /// The simulation occurs before any rows are laid out for a region.
     pub(super) footer_height: Abs,
+    /// The common first baseline of the baseline-aligned cells in each row,
+    /// if there are any.
+    pub(super) baselines: Vec<Option<Abs>>,
+    /// The minimum number of body rows in the first and last region of the
+    /// grid.
+    pub(super) min_rows: usize,
     /// The span of the grid element.
     pub(super) span: Span,
 }
@@ -121,10 +127,27 @@ impl<'a> GridLayouter<'a> {
             is_rtl: TextElem::dir_in(styles) == Dir::RTL,
             header_height: Abs::zero(),
             footer_height: Abs::zero(),
+            baselines: vec![None; grid.rows.len()],
+            min_rows: 1,
             span,
         }
     }
 
+    /// Lay out the columns from right to left, or from left to right, instead
+    /// of following the text direction.
+    pub fn with_rtl(mut self, is_rtl: bool) -> Self {
+        self.is_rtl = is_rtl;
+        self
+    }
+
+    /// Keep at least the given number of body rows together at the start and
+    /// the end of the grid, so that neither its first nor its last region
+    /// has fewer of them.
+    pub fn with_min_rows(mut self, min_rows: usize) -> Self {
+        self.min_rows = min_rows;
+        self
+    }
+
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self, engine: &mut Engine) -> SourceResult<Fragment> {
         self.measure_columns(engine)?;
@@ -1014,6 +1037,10 @@ impl<'a> GridLayouter<'a> {
         let mut resolved: Vec<Abs> = vec![];
         let mut pending_rowspans: Vec<(usize, usize, Vec<Abs>)> = vec![];
 
+        // The largest first baseline and the largest extent below it among
+        // baseline-aligned cells.
+        let mut baseline: Option<(Abs, Abs)> = None;
+
         for x in 0..self.rcols.len() {
             // Get the parent cell in case this is a merged position.
             let Some(parent) = self.grid.parent_cell_position(x, y) else {
@@ -1102,6 +1129,16 @@ impl<'a> GridLayouter<'a> {
                 }
             }
 
+            // Baseline-aligned cells are shifted down to the common baseline,
+            // which may need extra height.
+            if let (true, 1, [frame]) = (cell.baseline, rowspan, frames.as_slice()) {
+                if let Some(first) = first_baseline(frame) {
+                    let (above, below) = baseline.get_or_insert_with(Default::default);
+                    above.set_max(first);
+                    below.set_max(frame.height() - first);
+                }
+            }
+
             // Skip frames from previous regions if applicable.
             let mut sizes = frames
                 .iter()
@@ -1167,6 +1204,10 @@ impl<'a> GridLayouter<'a> {
             )?;
         }
 
+        if let (Some((above, below)), [height]) = (baseline, resolved.as_mut_slice()) {
+            height.set_max(above + below);
+        }
+
         debug_assert!(breakable || resolved.len() <= 1);
 
         Ok(Some(resolved))
@@ -1235,6 +1276,7 @@ impl<'a> GridLayouter<'a> {
 
         let mut output = Frame::soft(Size::new(self.width, height));
         let mut pos = Point::zero();
+        let mut laid_out = vec![];
 
         // Reverse the column order when using RTL.
         for (x, &rcol) in self.rcols.iter().enumerate().rev_if(self.is_rtl) {
@@ -1269,13 +1311,24 @@ impl<'a> GridLayouter<'a> {
                         let offset = -width + rcol;
                         pos.x += offset;
                     }
-                    output.push_frame(pos, frame);
+                    let first = if cell.baseline { first_baseline(&frame) } else { None };
+                    laid_out.push((pos, frame, first));
                 }
             }
 
             pos.x += rcol;
         }
 
+        // Shift baseline-aligned cells down to the lowest first baseline.
+        let common = laid_out.iter().filter_map(|&(_, _, first)| first).max();
+        self.baselines[y] = common;
+        for (mut pos, frame, first) in laid_out {
+            if let Some((common, first)) = common.zip(first) {
+                pos.y += common - first;
+            }
+            output.push_frame(pos, frame);
+        }
+
         Ok(output)
     }
 
@@ -1580,3 +1633,17 @@ pub(super) fn in_last_with_offset(regions: Regions<'_>, offset: Abs) -> bool {
     regions.backlog.is_empty()
         && regions.last.map_or(true, |height| regions.size.y + offset == height)
 }
+
+/// The vertical position of the first text baseline in a frame, if any.
+///
+/// Transformed groups are skipped since their text does not lie on a
+/// horizontal baseline of the frame.
+pub(super) fn first_baseline(frame: &Frame) -> Option<Abs> {
+    frame.items().find_map(|(pos, item)| match item {
+        FrameItem::Text(_) => Some(pos.y),
+        FrameItem::Group(group) if group.transform.is_identity() => {
+            first_baseline(&group.frame).map(|first| pos.y + first)
+        }
+        _ => None,
+    })
+}
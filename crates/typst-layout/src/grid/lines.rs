@@ -607,6 +607,8 @@ mod test {
             stroke: Sides::splat(Some(Arc::new(Stroke::default()))),
             stroke_overridden: Sides::splat(false),
             breakable: true,
+            baseline: false,
+            role: None,
         }
     }
 
@@ -620,6 +622,8 @@ mod test {
             stroke: Sides::splat(Some(Arc::new(Stroke::default()))),
             stroke_overridden: Sides::splat(false),
             breakable: true,
+            baseline: false,
+            role: None,
         }
     }
 
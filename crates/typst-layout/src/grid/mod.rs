@@ -1,23 +1,26 @@
 mod cells;
+mod html;
 mod layouter;
 mod lines;
 mod repeated;
 mod rowspans;
 
 pub use self::cells::{Cell, CellGrid};
+pub use self::html::{html_grid, html_table};
 pub use self::layouter::GridLayouter;
 
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use ecow::eco_format;
-use typst_library::diag::{SourceResult, Trace, Tracepoint};
+use typst_library::diag::{bail, SourceResult, Trace, Tracepoint};
 use typst_library::engine::Engine;
 use typst_library::foundations::{Fold, Packed, Smart, StyleChain};
 use typst_library::introspection::Locator;
 use typst_library::layout::{
-    Abs, Alignment, Axes, Dir, Fragment, GridCell, GridChild, GridElem, GridItem, Length,
-    OuterHAlignment, OuterVAlignment, Regions, Rel, Sides,
+    Abs, AlignElem, Alignment, Axes, Axis, Dir, Fragment, FrameRole, GridCell, GridChild,
+    GridElem, GridItem, HAlignment, Length, OuterHAlignment, OuterVAlignment, Regions,
+    Rel, Sides, VAlignment,
 };
 use typst_library::model::{TableCell, TableChild, TableElem, TableItem};
 use typst_library::text::TextElem;
@@ -44,6 +47,50 @@ pub fn layout_grid(
     styles: StyleChain,
     regions: Regions,
 ) -> SourceResult<Fragment> {
+    let is_rtl = resolve_rtl(elem.dir(styles), styles, elem.span())?;
+    let grid = grid_to_cellgrid(elem, engine, locator, styles, is_rtl)?;
+    let layouter = GridLayouter::new(&grid, regions, styles, elem.span())
+        .with_rtl(is_rtl)
+        .with_min_rows(elem.min_rows_per_region(styles).get());
+
+    // Measure the columns and layout the grid row-by-row.
+    layouter.layout(engine)
+}
+
+/// Determines whether a grid or table with the given direction is laid out
+/// from right to left.
+fn resolve_rtl(dir: Smart<Dir>, styles: StyleChain, span: Span) -> SourceResult<bool> {
+    let dir = dir.unwrap_or_else(|| TextElem::dir_in(styles));
+    if dir.axis() != Axis::X {
+        bail!(span, "grid direction must be horizontal");
+    }
+    Ok(dir == Dir::RTL)
+}
+
+/// Swaps start and end in the horizontal alignment of a cell in a grid that
+/// runs against the text direction, so that its content is aligned relative
+/// to the grid. A cell without its own alignment takes the surrounding one.
+fn mirror_align(align: Smart<Alignment>, styles: StyleChain) -> Smart<Alignment> {
+    let mirror = |h| match h {
+        HAlignment::Start => HAlignment::End,
+        HAlignment::End => HAlignment::Start,
+        h => h,
+    };
+    Smart::Custom(match align.unwrap_or_else(|| AlignElem::alignment_in(styles)) {
+        Alignment::H(h) => Alignment::H(mirror(h)),
+        Alignment::V(v) => Alignment::Both(HAlignment::End, v),
+        Alignment::Both(h, v) => Alignment::Both(mirror(h), v),
+    })
+}
+
+/// Resolves the cells of a grid into a cell grid.
+fn grid_to_cellgrid<'a>(
+    elem: &Packed<GridElem>,
+    engine: &mut Engine,
+    locator: Locator<'a>,
+    styles: StyleChain,
+    is_rtl: bool,
+) -> SourceResult<CellGrid<'a>> {
     let inset = elem.inset(styles);
     let align = elem.align(styles);
     let columns = elem.columns(styles);
@@ -57,7 +104,7 @@ pub fn layout_grid(
     let gutter = Axes::new(column_gutter.0.as_slice(), row_gutter.0.as_slice());
     // Use trace to link back to the grid when a specific cell errors
     let tracepoint = || Tracepoint::Call(Some(eco_format!("grid")));
-    let resolve_item = |item: &GridItem| grid_item_to_resolvable(item, styles);
+    let resolve_item = |item: &GridItem| grid_item_to_resolvable(item, styles, is_rtl);
     let children = elem.children().iter().map(|child| match child {
         GridChild::Header(header) => ResolvableGridChild::Header {
             repeat: header.repeat(styles),
@@ -70,10 +117,10 @@ pub fn layout_grid(
             items: footer.children().iter().map(resolve_item),
         },
         GridChild::Item(item) => {
-            ResolvableGridChild::Item(grid_item_to_resolvable(item, styles))
+            ResolvableGridChild::Item(grid_item_to_resolvable(item, styles, is_rtl))
         }
     });
-    let grid = CellGrid::resolve(
+    CellGrid::resolve(
         tracks,
         gutter,
         locator,
@@ -82,16 +129,12 @@ pub fn layout_grid(
         align,
         &inset,
         &stroke,
+        is_rtl != (TextElem::dir_in(styles) == Dir::RTL),
         engine,
         styles,
         elem.span(),
     )
-    .trace(engine.world, tracepoint, elem.span())?;
-
-    let layouter = GridLayouter::new(&grid, regions, styles, elem.span());
-
-    // Measure the columns and layout the grid row-by-row.
-    layouter.layout(engine)
+    .trace(engine.world, tracepoint, elem.span())
 }
 
 /// Layout the table.
@@ -103,6 +146,22 @@ pub fn layout_table(
     styles: StyleChain,
     regions: Regions,
 ) -> SourceResult<Fragment> {
+    let is_rtl = resolve_rtl(elem.dir(styles), styles, elem.span())?;
+    let grid = table_to_cellgrid(elem, engine, locator, styles, is_rtl)?;
+    let layouter = GridLayouter::new(&grid, regions, styles, elem.span())
+        .with_rtl(is_rtl)
+        .with_min_rows(elem.min_rows_per_region(styles).get());
+    layouter.layout(engine)
+}
+
+/// Resolves the cells of a table into a cell grid.
+fn table_to_cellgrid<'a>(
+    elem: &Packed<TableElem>,
+    engine: &mut Engine,
+    locator: Locator<'a>,
+    styles: StyleChain,
+    is_rtl: bool,
+) -> SourceResult<CellGrid<'a>> {
     let inset = elem.inset(styles);
     let align = elem.align(styles);
     let columns = elem.columns(styles);
@@ -116,7 +175,7 @@ pub fn layout_table(
     let gutter = Axes::new(column_gutter.0.as_slice(), row_gutter.0.as_slice());
     // Use trace to link back to the table when a specific cell errors
     let tracepoint = || Tracepoint::Call(Some(eco_format!("table")));
-    let resolve_item = |item: &TableItem| table_item_to_resolvable(item, styles);
+    let resolve_item = |item: &TableItem| table_item_to_resolvable(item, styles, is_rtl);
     let children = elem.children().iter().map(|child| match child {
         TableChild::Header(header) => ResolvableGridChild::Header {
             repeat: header.repeat(styles),
@@ -129,10 +188,10 @@ pub fn layout_table(
             items: footer.children().iter().map(resolve_item),
         },
         TableChild::Item(item) => {
-            ResolvableGridChild::Item(table_item_to_resolvable(item, styles))
+            ResolvableGridChild::Item(table_item_to_resolvable(item, styles, is_rtl))
         }
     });
-    let grid = CellGrid::resolve(
+    CellGrid::resolve(
         tracks,
         gutter,
         locator,
@@ -141,19 +200,18 @@ pub fn layout_table(
         align,
         &inset,
         &stroke,
+        is_rtl != (TextElem::dir_in(styles) == Dir::RTL),
         engine,
         styles,
         elem.span(),
     )
-    .trace(engine.world, tracepoint, elem.span())?;
-
-    let layouter = GridLayouter::new(&grid, regions, styles, elem.span());
-    layouter.layout(engine)
+    .trace(engine.world, tracepoint, elem.span())
 }
 
 fn grid_item_to_resolvable(
     item: &GridItem,
     styles: StyleChain,
+    is_rtl: bool,
 ) -> ResolvableGridItem<Packed<GridCell>> {
     match item {
         GridItem::HLine(hline) => ResolvableGridItem::HLine {
@@ -174,12 +232,8 @@ fn grid_item_to_resolvable(
             stroke: vline.stroke(styles),
             span: vline.span(),
             position: match vline.position(styles) {
-                OuterHAlignment::Left if TextElem::dir_in(styles) == Dir::RTL => {
-                    LinePosition::After
-                }
-                OuterHAlignment::Right if TextElem::dir_in(styles) == Dir::RTL => {
-                    LinePosition::Before
-                }
+                OuterHAlignment::Left if is_rtl => LinePosition::After,
+                OuterHAlignment::Right if is_rtl => LinePosition::Before,
                 OuterHAlignment::Start | OuterHAlignment::Left => LinePosition::Before,
                 OuterHAlignment::End | OuterHAlignment::Right => LinePosition::After,
             },
@@ -191,6 +245,7 @@ fn grid_item_to_resolvable(
 fn table_item_to_resolvable(
     item: &TableItem,
     styles: StyleChain,
+    is_rtl: bool,
 ) -> ResolvableGridItem<Packed<TableCell>> {
     match item {
         TableItem::HLine(hline) => ResolvableGridItem::HLine {
@@ -211,12 +266,8 @@ fn table_item_to_resolvable(
             stroke: vline.stroke(styles),
             span: vline.span(),
             position: match vline.position(styles) {
-                OuterHAlignment::Left if TextElem::dir_in(styles) == Dir::RTL => {
-                    LinePosition::After
-                }
-                OuterHAlignment::Right if TextElem::dir_in(styles) == Dir::RTL => {
-                    LinePosition::Before
-                }
+                OuterHAlignment::Left if is_rtl => LinePosition::After,
+                OuterHAlignment::Right if is_rtl => LinePosition::Before,
                 OuterHAlignment::Start | OuterHAlignment::Left => LinePosition::Before,
                 OuterHAlignment::End | OuterHAlignment::Right => LinePosition::After,
             },
@@ -235,6 +286,7 @@ impl ResolvableCell for Packed<TableCell> {
         inset: Sides<Option<Rel<Length>>>,
         stroke: Sides<Option<Option<Arc<Stroke<Abs>>>>>,
         breakable: bool,
+        mirrored: bool,
         locator: Locator<'a>,
         styles: StyleChain,
     ) -> Cell<'a> {
@@ -261,7 +313,7 @@ impl ResolvableCell for Packed<TableCell> {
         cell.push_x(Smart::Custom(x));
         cell.push_y(Smart::Custom(y));
         cell.push_fill(Smart::Custom(fill.clone()));
-        cell.push_align(match align {
+        let align = match align {
             Smart::Custom(align) => {
                 Smart::Custom(cell.align(styles).map_or(align, |inner| inner.fold(align)))
             }
@@ -269,7 +321,11 @@ impl ResolvableCell for Packed<TableCell> {
             // cell's alignment instead (which, in the end, will fold with
             // the outer alignment when it is effectively displayed).
             Smart::Auto => cell.align(styles),
-        });
+        };
+        let align = if mirrored { mirror_align(align, styles) } else { align };
+        let baseline =
+            align.is_custom_and(|align| align.y() == Some(VAlignment::Baseline));
+        cell.push_align(align);
         cell.push_inset(Smart::Custom(
             cell.inset(styles).map_or(inset, |inner| inner.fold(inset)),
         ));
@@ -296,6 +352,8 @@ impl ResolvableCell for Packed<TableCell> {
             stroke,
             stroke_overridden,
             breakable,
+            baseline,
+            role: Some(FrameRole::TableCell { x, y }),
         }
     }
 
@@ -330,6 +388,7 @@ impl ResolvableCell for Packed<GridCell> {
         inset: Sides<Option<Rel<Length>>>,
         stroke: Sides<Option<Option<Arc<Stroke<Abs>>>>>,
         breakable: bool,
+        mirrored: bool,
         locator: Locator<'a>,
         styles: StyleChain,
     ) -> Cell<'a> {
@@ -356,7 +415,7 @@ impl ResolvableCell for Packed<GridCell> {
         cell.push_x(Smart::Custom(x));
         cell.push_y(Smart::Custom(y));
         cell.push_fill(Smart::Custom(fill.clone()));
-        cell.push_align(match align {
+        let align = match align {
             Smart::Custom(align) => {
                 Smart::Custom(cell.align(styles).map_or(align, |inner| inner.fold(align)))
             }
@@ -364,7 +423,11 @@ impl ResolvableCell for Packed<GridCell> {
             // cell's alignment instead (which, in the end, will fold with
             // the outer alignment when it is effectively displayed).
             Smart::Auto => cell.align(styles),
-        });
+        };
+        let align = if mirrored { mirror_align(align, styles) } else { align };
+        let baseline =
+            align.is_custom_and(|align| align.y() == Some(VAlignment::Baseline));
+        cell.push_align(align);
         cell.push_inset(Smart::Custom(
             cell.inset(styles).map_or(inset, |inner| inner.fold(inset)),
         ));
@@ -391,6 +454,8 @@ impl ResolvableCell for Packed<GridCell> {
             stroke,
             stroke_overridden,
             breakable,
+            baseline,
+            role: None,
         }
     }
 
@@ -4,7 +4,7 @@ use typst_library::foundations::Resolve;
 use typst_library::layout::{Abs, Axes, Frame, Point, Region, Regions, Size, Sizing};
 use typst_utils::MaybeReverseIter;
 
-use super::layouter::{in_last_with_offset, points, Row, RowPiece};
+use super::layouter::{first_baseline, in_last_with_offset, points, Row, RowPiece};
 use super::repeated::Repeatable;
 use super::{Cell, GridLayouter};
 
@@ -153,8 +153,16 @@ impl GridLayouter<'_> {
         {
             let dy = if i == 0 {
                 // At first, we draw the rowspan starting at its expected
-                // vertical offset in the first region.
-                dy
+                // vertical offset in the first region. A baseline-aligned
+                // rowspan is aligned with the baseline of its first row.
+                let shift = cell
+                    .baseline
+                    .then(|| self.baselines[y].zip(first_baseline(&frame)))
+                    .flatten()
+                    .map_or(Abs::zero(), |(common, first)| {
+                        (common - first).max(Abs::zero())
+                    });
+                dy + shift
             } else {
                 // The rowspan continuation starts after the header (thus,
                 // at a position after the sum of the laid out header
@@ -242,7 +250,7 @@ impl GridLayouter<'_> {
                 }
             }
 
-            let row_group = self.simulate_unbreakable_row_group(
+            let mut row_group = self.simulate_unbreakable_row_group(
                 current_row,
                 amount_unbreakable_rows,
                 &self.regions,
@@ -250,6 +258,28 @@ impl GridLayouter<'_> {
                 0,
             )?;
 
+            if amount_unbreakable_rows.is_none() {
+                if let Some(amount) = self.min_rows_group(current_row) {
+                    let group = self.simulate_unbreakable_row_group(
+                        current_row,
+                        Some(amount),
+                        &self.regions,
+                        engine,
+                        0,
+                    )?;
+
+                    // Only keep the rows together if they would fit into a
+                    // fresh region. Otherwise, splitting them is unavoidable.
+                    let available =
+                        self.regions.full - self.header_height - self.footer_height;
+                    if group.rows.len() > row_group.rows.len()
+                        && available.fits(group.height)
+                    {
+                        row_group = group;
+                    }
+                }
+            }
+
             // Skip to fitting region.
             while !self.regions.size.y.fits(row_group.height)
                 && !in_last_with_offset(
@@ -356,6 +386,49 @@ impl GridLayouter<'_> {
         Ok(row_group)
     }
 
+    /// Determines how many rows starting at the given row must be laid out in
+    /// the same region so that the first and the last region of the grid both
+    /// have at least the minimum number of body rows.
+    ///
+    /// Returns `None` if the row does not start such a group. If fewer than
+    /// twice the minimum number of body rows exist, any split would leave too
+    /// few rows on one side, so all of them are kept together. The group is
+    /// extended to cover unbreakable cells that span beyond it.
+    fn min_rows_group(&self, current_row: usize) -> Option<usize> {
+        if self.min_rows <= 1 {
+            return None;
+        }
+
+        let body_start =
+            self.grid.header.as_ref().map_or(0, |header| header.unwrap().end);
+        let body_end = self
+            .grid
+            .footer
+            .as_ref()
+            .map_or(self.grid.rows.len(), |footer| footer.unwrap().start);
+        let body = || (body_start..body_end).filter(|&y| !self.grid.is_gutter_track(y));
+
+        let min = self.min_rows;
+        let mut end = if body().count() < 2 * min {
+            (body().next() == Some(current_row)).then_some(body_end)?
+        } else if body().next() == Some(current_row) {
+            body().nth(min - 1)? + 1
+        } else if body().rev().nth(min - 1) == Some(current_row) {
+            body_end
+        } else {
+            return None;
+        };
+
+        // Unbreakable cells that start within the group must end in it, too.
+        let mut y = current_row;
+        while y < end {
+            end = end.max(y + self.check_for_unbreakable_cells(y));
+            y += 1;
+        }
+
+        Some(end.min(self.grid.rows.len()) - current_row)
+    }
+
     /// Checks if one or more of the cells at the given row are unbreakable.
     /// If so, returns the largest rowspan among the unbreakable cells;
     /// the spanned rows must, as a result, be laid out in the same region.
@@ -2,7 +2,7 @@ use std::ffi::OsStr;
 
 use typst_library::diag::{bail, warning, At, SourceResult, StrResult};
 use typst_library::engine::Engine;
-use typst_library::foundations::{Packed, Smart, StyleChain};
+use typst_library::foundations::{Packed, Resolve, Smart, StyleChain};
 use typst_library::introspection::Locator;
 use typst_library::layout::{
     Abs, Axes, FixedAlignment, Frame, FrameItem, Point, Region, Size,
@@ -10,7 +10,8 @@ use typst_library::layout::{
 use typst_library::loading::Readable;
 use typst_library::text::families;
 use typst_library::visualize::{
-    Curve, Image, ImageElem, ImageFit, ImageFormat, RasterFormat, VectorFormat,
+    Curve, Image, ImageElem, ImageFit, ImageFormat, ImageKind, ImageRasterize,
+    RasterFormat, VectorFormat,
 };
 
 /// Layout the image.
@@ -48,6 +49,13 @@ pub fn layout_image(
         }
     }
 
+    let dpi = elem.dpi(styles).map(|dpi| dpi.get());
+    if let Smart::Custom(dpi) = dpi {
+        if !(dpi.is_finite() && dpi > 0.0) {
+            bail!(span, "dpi must be positive");
+        }
+    }
+
     // Construct the image itself.
     let image = Image::with_fonts(
         data.clone().into(),
@@ -56,6 +64,7 @@ pub fn layout_image(
         engine.world,
         &families(styles).map(|f| f.as_str()).collect::<Vec<_>>(),
         elem.flatten_text(styles),
+        elem.scaling(styles),
     )
     .at(span)?;
 
@@ -82,12 +91,18 @@ pub fn layout_image(
         Size::new(region.size.x.min(region.size.y * px_ratio), region.size.y)
     } else {
         // If neither is forced, take the natural image size at the image's
-        // DPI bounded by the available space.
-        let dpi = image.dpi().unwrap_or(Image::DEFAULT_DPI);
+        // DPI bounded by the available space and the maximum size.
+        let dpi = dpi.unwrap_or_else(|| image.dpi().unwrap_or(Image::DEFAULT_DPI));
         let natural = Axes::new(pxw, pxh).map(|v| Abs::inches(v / dpi));
+        let max = Axes::new(elem.max_width(styles), elem.max_height(styles));
+        let max = max.zip_map(region.size, |max, available| {
+            max.map_or(available, |max| {
+                max.resolve(styles).relative_to(available).min(available)
+            })
+        });
         Size::new(
-            natural.x.min(region.size.x).min(region.size.y * px_ratio),
-            natural.y.min(region.size.y).min(region.size.x / px_ratio),
+            natural.x.min(max.x).min(max.y * px_ratio),
+            natural.y.min(max.y).min(max.x / px_ratio),
         )
     };
 
@@ -104,6 +119,56 @@ pub fn layout_image(
         ImageFit::Stretch => target,
     };
 
+    // Embed the SVG as a raster image if requested or if it is unlikely to be
+    // rendered well as vector graphics.
+    let image = match image.kind() {
+        ImageKind::Svg(svg) => {
+            let rasterize = elem.rasterize(styles);
+            let raster_dpi = match rasterize {
+                Smart::Auto => (svg.unsupported_feature().is_some()
+                    || svg.element_count() > Image::MAX_VECTOR_ELEMENTS)
+                    .then_some(Image::RASTER_DPI),
+                Smart::Custom(ImageRasterize::Never) => None,
+                Smart::Custom(ImageRasterize::Always) => Some(Image::RASTER_DPI),
+                Smart::Custom(ImageRasterize::Dpi(dpi)) => Some(dpi.get()),
+            };
+
+            if let Some(feature) = svg.unsupported_feature() {
+                let hint = if raster_dpi.is_some() {
+                    "the image was rasterized, but might still look different than in \
+                     other SVG viewers"
+                } else {
+                    "the image might render incorrectly"
+                };
+                engine.sink.warn(warning!(
+                    span,
+                    "SVG image uses unsupported feature: {feature}";
+                    hint: "{hint}"
+                ));
+            }
+
+            match raster_dpi {
+                Some(raster_dpi) => {
+                    let size = fitted
+                        .map(|v| (v.to_inches() * raster_dpi).round().max(1.0) as u32);
+                    match image.rasterized(engine.world, size) {
+                        Ok(rasterized) => rasterized,
+                        Err(err) => {
+                            engine.sink.warn(warning!(
+                                span,
+                                "SVG image could not be rasterized ({err})";
+                                hint: "it is embedded as vector graphics instead"
+                            ));
+                            image
+                        }
+                    }
+                }
+                None => image,
+            }
+        }
+        ImageKind::Raster(_) => image,
+    };
+
     // First, place the image in a frame of exactly its size and then resize
     // the frame to the target size, center aligning the image in the
     // process.
@@ -1,10 +1,13 @@
+use std::num::NonZeroUsize;
+
 use typst_library::diag::bail;
-use typst_library::foundations::{Packed, Resolve};
+use typst_library::foundations::{Packed, Resolve, Smart};
 use typst_library::introspection::{SplitLocator, Tag, TagElem};
 use typst_library::layout::{
-    Abs, AlignElem, BoxElem, Dir, Fr, Frame, HElem, InlineElem, InlineItem, Sizing,
-    Spacing,
+    Abs, AlignElem, Axes, BoxElem, Dir, Fr, Frame, HElem, InlineElem, InlineItem, Region,
+    Sizing, Spacing,
 };
+use typst_library::model::DropCapElem;
 use typst_library::text::{
     is_default_ignorable, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes,
     SpaceElem, TextElem,
@@ -112,6 +115,16 @@ impl Segment<'_> {
     }
 }
 
+/// A layouted drop cap at the start of a paragraph.
+pub struct DropCap {
+    /// The drop cap's frame.
+    pub frame: Frame,
+    /// How many lines should wrap around the drop cap.
+    pub lines: Smart<NonZeroUsize>,
+    /// The gap between the drop cap and the wrapping lines.
+    pub gap: Abs,
+}
+
 /// Collects all text of the paragraph into one string and a collection of
 /// segments that correspond to pieces of that string. This also performs
 /// string-level preprocessing like case transformations.
@@ -123,12 +136,12 @@ pub fn collect<'a>(
     styles: &'a StyleChain<'a>,
     region: Size,
     consecutive: bool,
-) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper)> {
+) -> SourceResult<(String, Vec<Segment<'a>>, SpanMapper, Option<DropCap>)> {
     let mut collector = Collector::new(2 + children.len());
     let mut quoter = SmartQuoter::new();
 
     let outer_dir = TextElem::dir_in(*styles);
-    let first_line_indent = ParElem::first_line_indent_in(*styles);
+    let first_line_indent = ParElem::first_line_indent_in(*styles).amount;
     if !first_line_indent.is_zero()
         && consecutive
         && AlignElem::alignment_in(*styles).resolve(*styles).x == outer_dir.start().into()
@@ -189,7 +202,7 @@ pub fn collect<'a>(
                 .push_text(if elem.justify(styles) { "\u{2028}" } else { "\n" }, styles);
         } else if let Some(elem) = child.to_packed::<SmartQuoteElem>() {
             let double = elem.double(styles);
-            if elem.enabled(styles) {
+            if elem.enabled(styles) && TextElem::smart_in(styles) {
                 let quotes = SmartQuotes::get(
                     elem.quotes(styles),
                     TextElem::lang_in(styles),
@@ -226,6 +239,28 @@ pub fn collect<'a>(
                 let frame = layout_box(elem, engine, loc, styles, region)?;
                 collector.push_item(Item::Frame(frame, styles));
             }
+        } else if let Some(elem) = child.to_packed::<DropCapElem>() {
+            if collector.dropcap.is_some() || !collector.is_at_start() {
+                bail!(
+                    elem.span(), "drop cap must be at the start of a paragraph";
+                    hint: "try adding a paragraph break before the drop cap"
+                );
+            }
+
+            let pod = Region::new(region, Axes::splat(false));
+            let frame = crate::layout_frame(
+                engine,
+                &elem.body,
+                locator.next(&elem.span()),
+                styles,
+                pod,
+            )?;
+
+            collector.dropcap = Some(DropCap {
+                frame,
+                lines: elem.lines(styles),
+                gap: elem.gap(styles),
+            });
         } else if let Some(elem) = child.to_packed::<TagElem>() {
             collector.push_item(Item::Tag(&elem.tag));
         } else {
@@ -236,7 +271,7 @@ pub fn collect<'a>(
         collector.spans.push(len, child.span());
     }
 
-    Ok((collector.full, collector.segments, collector.spans))
+    Ok((collector.full, collector.segments, collector.spans, collector.dropcap))
 }
 
 /// Collects segments.
@@ -244,6 +279,7 @@ struct Collector<'a> {
     full: String,
     segments: Vec<Segment<'a>>,
     spans: SpanMapper,
+    dropcap: Option<DropCap>,
 }
 
 impl<'a> Collector<'a> {
@@ -252,9 +288,17 @@ impl<'a> Collector<'a> {
             full: String::new(),
             segments: Vec::with_capacity(capacity),
             spans: SpanMapper::new(),
+            dropcap: None,
         }
     }
 
+    /// Whether nothing but spacing and tags has been collected so far.
+    fn is_at_start(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            matches!(segment, Segment::Item(Item::Absolute(..) | Item::Tag(_)))
+        })
+    }
+
     fn push_text(&mut self, text: &str, styles: StyleChain<'a>) {
         self.build_text(styles, |full| full.push_str(text));
     }
@@ -1,4 +1,5 @@
 use typst_library::introspection::SplitLocator;
+use typst_library::layout::{Abs, Dir, Point};
 use typst_utils::Numeric;
 
 use super::*;
@@ -19,17 +20,39 @@ pub fn finalize(
     let width = if !region.x.is_finite()
         || (!expand && lines.iter().all(|line| line.fr().is_zero()))
     {
-        region
-            .x
-            .min(p.hang + lines.iter().map(|line| line.width).max().unwrap_or_default())
+        region.x.min(
+            p.hang
+                + lines
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| line.width + p.excluded(i))
+                    .max()
+                    .unwrap_or_default(),
+        )
     } else {
         region.x
     };
 
     // Stack the lines into one frame per region.
-    lines
+    let mut frames = lines
         .iter()
-        .map(|line| commit(engine, p, line, width, region.y, locator, styles))
-        .collect::<SourceResult<_>>()
-        .map(Fragment::frames)
+        .enumerate()
+        .map(|(i, line)| commit(engine, p, i, line, width, region.y, locator, styles))
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    // Place the exclusion's content into the start corner, aligned with the
+    // baseline of the last line wrapping around it.
+    if let Some(exclusion) = &p.exclusion {
+        let index = exclusion.lines.min(frames.len()).saturating_sub(1);
+        if let Some(frame) = frames.get_mut(index) {
+            let x = match p.dir {
+                Dir::RTL => width - exclusion.frame.width(),
+                _ => Abs::zero(),
+            };
+            let y = frame.baseline() - exclusion.frame.baseline();
+            frame.push_frame(Point::new(x, y), exclusion.frame.clone());
+        }
+    }
+
+    Ok(Fragment::frames(frames))
 }
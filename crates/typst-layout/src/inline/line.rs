@@ -2,11 +2,13 @@ use std::fmt::{self, Debug, Formatter};
 use std::ops::{Deref, DerefMut};
 
 use typst_library::engine::Engine;
-use typst_library::foundations::NativeElement;
+use typst_library::foundations::{NativeElement, Smart};
+use typst_library::introspection::metrics::MetricsLine;
 use typst_library::introspection::{SplitLocator, Tag};
-use typst_library::layout::{Abs, Dir, Em, Fr, Frame, FrameItem, Point};
-use typst_library::model::{ParLine, ParLineMarker};
-use typst_library::text::{Lang, TextElem};
+use typst_library::layout::{Abs, Dir, Em, Fr, Frame, FrameItem, FrameRole, Point};
+use typst_library::model::{LinkElem, ParLine, ParLineMarker};
+use typst_library::text::{CjkJustify, Lang, TextElem};
+use typst_library::{Feature, WorldExt};
 use typst_utils::Numeric;
 
 use super::*;
@@ -71,6 +73,24 @@ impl Line<'_> {
         count
     }
 
+    /// Whether the majority of the line's non-space glyphs are Chinese or
+    /// Japanese characters or CJK punctuation.
+    pub fn is_mostly_cj(&self) -> bool {
+        let (mut cj, mut total) = (0, 0);
+        for glyph in
+            self.items.iter().filter_map(Item::text).flat_map(|s| s.glyphs.iter())
+        {
+            if glyph.is_space() {
+                continue;
+            }
+            if glyph.is_cj_script() || glyph.is_cjk_punctuation() {
+                cj += 1;
+            }
+            total += 1;
+        }
+        2 * cj > total
+    }
+
     /// How much the line can stretch.
     pub fn stretchability(&self) -> Abs {
         self.items
@@ -134,7 +154,7 @@ pub fn line<'a>(
 
     // Whether the line is justified.
     let justify = full.ends_with(LINE_SEPARATOR)
-        || (p.justify && breakpoint != Breakpoint::Mandatory);
+        || (p.justify && (breakpoint != Breakpoint::Mandatory || p.justify_last));
 
     // Process dashes.
     let dash = if breakpoint.is_hyphen() || full.ends_with(SHY) {
@@ -167,6 +187,12 @@ pub fn line<'a>(
         }
     }
 
+    // Remove the tracking after the last glyph of the line. Tags after it,
+    // e.g. of a labelled element at the line end, don't take up space.
+    if let Some(shaped) = items.last_visible_text_mut() {
+        shaped.trim_tracking();
+    }
+
     // Deal with CJ characters at line boundaries.
     adjust_cj_at_line_boundaries(p, full, &mut items);
 
@@ -414,20 +440,22 @@ fn should_repeat_hyphen(pred_line: &Line, text: &str) -> bool {
 pub fn commit(
     engine: &mut Engine,
     p: &Preparation,
+    index: usize,
     line: &Line,
     width: Abs,
     full: Abs,
     locator: &mut SplitLocator<'_>,
     styles: StyleChain,
 ) -> SourceResult<Frame> {
-    let mut remaining = width - line.width - p.hang;
+    let excluded = p.excluded(index);
+    let mut remaining = width - line.width - p.hang - excluded;
     let mut offset = Abs::zero();
 
     // We always build the line from left to right. In an LTR paragraph, we must
-    // thus add the hanging indent to the offset. When the paragraph is RTL, the
-    // hanging indent arises naturally due to the line width.
+    // thus add the hanging indent and the excluded width to the offset. When
+    // the paragraph is RTL, they arise naturally due to the line width.
     if p.dir == Dir::LTR {
-        offset += p.hang;
+        offset += p.hang + excluded;
     }
 
     // Handle hanging punctuation to the left.
@@ -474,7 +502,15 @@ pub fn commit(
         remaining = (remaining + shrinkability).min(Abs::zero());
     } else if line.justify && fr.is_zero() {
         // Attempt to increase the length of the line, using stretchability.
-        if stretchability > Abs::zero() {
+        // CJK text is instead justified purely by inter-character spacing, so
+        // that spaces around Latin words don't stand out.
+        let inter_character = match p.cjk_justify {
+            Smart::Custom(CjkJustify::InterCharacter) => true,
+            Smart::Custom(CjkJustify::InterWord) => false,
+            Smart::Auto => line.is_mostly_cj(),
+        };
+
+        if stretchability > Abs::zero() && !inter_character {
             justification_ratio = (remaining / stretchability).min(1.0);
             remaining = (remaining - stretchability).max(Abs::zero());
         }
@@ -491,9 +527,14 @@ pub fn commit(
     let mut bottom = Abs::zero();
 
     // Build the frames and determine the height and baseline.
+    let roles = engine.world.library().features.is_enabled(Feature::A11yExtras);
     let mut frames = vec![];
     for item in line.items.iter() {
-        let mut push = |offset: &mut Abs, frame: Frame| {
+        let mut push = |offset: &mut Abs, frame: Frame, styles: StyleChain| {
+            let mut frame = frame.post_processed(styles);
+            if roles && !frame.is_empty() && !LinkElem::dests_in(styles).is_empty() {
+                frame.set_role(FrameRole::Link);
+            }
             let width = frame.width();
             top.set_max(frame.baseline());
             bottom.set_max(frame.size().y - frame.baseline());
@@ -512,7 +553,7 @@ pub fn commit(
                     let mut frame =
                         layout_box(elem, engine, loc.relayout(), *styles, region)?;
                     frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
-                    push(&mut offset, frame.post_processed(*styles));
+                    push(&mut offset, frame, *styles);
                 } else {
                     offset += amount;
                 }
@@ -524,12 +565,12 @@ pub fn commit(
                     justification_ratio,
                     extra_justification,
                 );
-                push(&mut offset, frame.post_processed(shaped.styles));
+                push(&mut offset, frame, shaped.styles);
             }
             Item::Frame(frame, styles) => {
                 let mut frame = frame.clone();
                 frame.translate(Point::with_y(TextElem::baseline_in(*styles)));
-                push(&mut offset, frame.post_processed(*styles));
+                push(&mut offset, frame, *styles);
             }
             Item::Tag(tag) => {
                 let mut frame = Frame::soft(Size::zero());
@@ -550,6 +591,7 @@ pub fn commit(
     output.set_baseline(top);
 
     add_par_line_marker(&mut output, styles, engine, locator, top);
+    add_line_metrics(&mut output, p, line, styles, engine, locator);
 
     // Construct the line's frame.
     for (offset, frame) in frames {
@@ -558,6 +600,8 @@ pub fn commit(
         output.push_frame(Point::new(x, y), frame);
     }
 
+    output.merge_text_runs();
+
     Ok(output)
 }
 
@@ -605,6 +649,72 @@ fn add_par_line_marker(
     output.push(pos, FrameItem::Tag(Tag::End(loc, key)));
 }
 
+/// Adds an element describing the line to a paragraph line's output frame if
+/// layout metrics are enabled, so that the line can be queried after layout.
+///
+/// The element's tags are placed at the line's top-left corner.
+fn add_line_metrics(
+    output: &mut Frame,
+    p: &Preparation,
+    line: &Line,
+    styles: StyleChain,
+    engine: &mut Engine,
+    locator: &mut SplitLocator,
+) {
+    if !engine.world.library().features.is_enabled(Feature::LayoutMetrics)
+        || !MetricsLine::record_in(styles)
+    {
+        return;
+    }
+
+    let range = source_range(engine, p, line);
+    let mut elem = MetricsLine::new(
+        output.width().into(),
+        output.height().into(),
+        output.baseline().into(),
+        range.as_ref().map(|range| range.start),
+        range.map(|range| range.end),
+    )
+    .pack();
+    let key = typst_utils::hash128(&elem);
+    let loc = locator.next_location(engine.introspector, key);
+    elem.set_location(loc);
+
+    output.push(Point::zero(), FrameItem::Tag(Tag::Start(elem)));
+    output.push(Point::zero(), FrameItem::Tag(Tag::End(loc, key)));
+}
+
+/// Determines the byte range in the source file that the text of a line comes
+/// from.
+///
+/// Only text from the same file as the first text in the line is considered.
+/// Since the glyph ranges within a shaped text are monotonic, it suffices to
+/// look at the first and last glyph of each.
+fn source_range(engine: &Engine, p: &Preparation, line: &Line) -> Option<Range> {
+    let mut file = None;
+    let mut out: Option<Range> = None;
+    for shaped in line.items.iter().filter_map(Item::text) {
+        let span_offset = TextElem::span_offset_in(shaped.styles);
+        let ends = [shaped.glyphs.first(), shaped.glyphs.last()];
+        for glyph in ends.into_iter().flatten() {
+            let (span, offset) = p.spans.span_at(glyph.range.start);
+            let Some(id) = span.id() else { continue };
+            if *file.get_or_insert(id) != id {
+                continue;
+            }
+
+            let Some(range) = engine.world.range(span) else { continue };
+            let start = (range.start + usize::from(offset) + span_offset).min(range.end);
+            let end = (start + glyph.range.len()).min(range.end);
+            out = Some(match out {
+                Some(prev) => prev.start.min(start)..prev.end.max(end),
+                None => start..end,
+            });
+        }
+    }
+    out
+}
+
 /// How much a character should hang into the end margin.
 ///
 /// For more discussion, see:
@@ -665,6 +775,15 @@ impl<'a> Items<'a> {
         self.0.last_mut()?.text_mut()
     }
 
+    /// Access the last item that is not invisible mutably, if it is text.
+    pub fn last_visible_text_mut(&mut self) -> Option<&mut ShapedText<'a>> {
+        self.0
+            .iter_mut()
+            .rev()
+            .find(|item| !matches!(***item, Item::Tag(_) | Item::Skip(_)))?
+            .text_mut()
+    }
+
     /// Reorder the items starting at the given index to RTL.
     pub fn reorder(&mut self, from: usize) {
         self.0[from..].reverse()
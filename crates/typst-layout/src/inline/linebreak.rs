@@ -8,11 +8,12 @@ use icu_provider::AsDeserializingBufferProvider;
 use icu_provider_adapters::fork::ForkByKeyProvider;
 use icu_provider_blob::BlobDataProvider;
 use icu_segmenter::LineSegmenter;
+use typst_library::diag::warning;
 use typst_library::engine::Engine;
 use typst_library::layout::{Abs, Em};
 use typst_library::model::Linebreaks;
 use typst_library::text::{is_default_ignorable, Lang, TextElem};
-use typst_syntax::link_prefix;
+use typst_syntax::{link_prefix, Span};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::*;
@@ -34,6 +35,15 @@ const MIN_RATIO: f64 = -1.0;
 const MIN_APPROX_RATIO: f64 = -0.5;
 const BOUND_EPS: f64 = 1e-3;
 
+/// The minimum cost of a line, as determined by `raw_cost`, if no penalty is
+/// negative.
+const MIN_LINE_COST: Cost = 1.0;
+
+/// The number of break opportunities beyond which justified paragraphs with
+/// automatic line breaking are broken in simple first-fit style. Optimized
+/// line breaking gets slow for very long paragraphs.
+const MAX_OPTIMIZED_BREAKPOINTS: usize = 5000;
+
 /// The ICU blob data.
 fn blob() -> BlobDataProvider {
     BlobDataProvider::try_new_from_static_blob(typst_assets::icu::ICU).unwrap()
@@ -104,17 +114,31 @@ impl Breakpoint {
     }
 }
 
-/// Breaks the paragraph into lines.
+/// Breaks the paragraph with the given span into lines.
 pub fn linebreak<'a>(
-    engine: &Engine,
+    engine: &mut Engine,
     p: &'a Preparation<'a>,
     width: Abs,
+    span: Span,
 ) -> Vec<Line<'a>> {
     let linebreaks = p.linebreaks.unwrap_or_else(|| {
-        if p.justify {
-            Linebreaks::Optimized
-        } else {
+        if !p.justify {
+            Linebreaks::Simple
+        } else if exceeds_breakpoints(p, MAX_OPTIMIZED_BREAKPOINTS) {
+            engine.sink.warn(
+                warning!(
+                    span,
+                    "paragraph is too long for optimized line breaking";
+                    hint: "paragraphs with more than {MAX_OPTIMIZED_BREAKPOINTS} \
+                           break opportunities are broken in simple first-fit style";
+                    hint: "set `par(linebreaks: \"optimized\")` to optimize them anyway",
+                )
+                .with_code("long-paragraph")
+                .reported_once(),
+            );
             Linebreaks::Simple
+        } else {
+            Linebreaks::Optimized
         }
     });
 
@@ -124,6 +148,19 @@ pub fn linebreak<'a>(
     }
 }
 
+/// Whether the paragraph has more than `max` break opportunities.
+fn exceeds_breakpoints(p: &Preparation, max: usize) -> bool {
+    // Every breakpoint follows at least one byte of text, so short paragraphs
+    // don't need to be segmented.
+    if p.text.len() <= max {
+        return false;
+    }
+
+    let mut count = 0;
+    breakpoints(p, |_, _| count += 1);
+    count > max
+}
+
 /// Performs line breaking in simple first-fit style. This means that we build
 /// lines greedily, always taking the longest possible line. This may lead to
 /// very unbalanced line, but is fast and simple.
@@ -144,7 +181,7 @@ fn linebreak_simple<'a>(
         // If the line doesn't fit anymore, we push the last fitting attempt
         // into the stack and rebuild the line from the attempt's end. The
         // resulting line cannot be broken up further.
-        if !width.fits(attempt.width) {
+        if !(width - p.excluded(lines.len())).fits(attempt.width) {
             if let Some((last_attempt, last_end)) = last.take() {
                 lines.push(last_attempt);
                 start = last_end;
@@ -155,7 +192,9 @@ fn linebreak_simple<'a>(
         // Finish the current line if there is a mandatory line break (i.e. due
         // to "\n") or if the line doesn't fit horizontally already since then
         // no shorter line will be possible.
-        if breakpoint == Breakpoint::Mandatory || !width.fits(attempt.width) {
+        if breakpoint == Breakpoint::Mandatory
+            || !(width - p.excluded(lines.len())).fits(attempt.width)
+        {
             lines.push(attempt);
             start = end;
             last = None;
@@ -221,10 +260,17 @@ fn linebreak_optimized_bounded<'a>(
         total: Cost,
         line: Line<'a>,
         end: usize,
+        lines: usize,
     }
 
     // Dynamic programming table.
-    let mut table = vec![Entry { pred: 0, total: 0.0, line: Line::empty(), end: 0 }];
+    let mut table = vec![Entry {
+        pred: 0,
+        total: 0.0,
+        line: Line::empty(),
+        end: 0,
+        lines: 0,
+    }];
 
     let mut active = 0;
     let mut prev_end = 0;
@@ -240,6 +286,18 @@ fn linebreak_optimized_bounded<'a>(
             let start = pred.end;
             let unbreakable = prev_end == start;
 
+            // Every line costs something, so if the predecessor's cost is
+            // already too close to the upper bound, it can't be part of any
+            // layout within the bound. If it is the earliest active one, we
+            // can thus remove it from the active set for good instead of
+            // building a line for it again at every following breakpoint.
+            if pred.total + metrics.min_line_cost > upper_bound + BOUND_EPS {
+                if active == pred_index {
+                    active += 1;
+                }
+                continue;
+            }
+
             // If the minimum cost we've established for the line is already
             // too much, skip this attempt.
             if line_lower_bound
@@ -255,7 +313,7 @@ fn linebreak_optimized_bounded<'a>(
             let (line_ratio, line_cost) = ratio_and_cost(
                 p,
                 metrics,
-                width,
+                width - p.excluded(pred.lines),
                 &pred.line,
                 &attempt,
                 breakpoint,
@@ -282,12 +340,14 @@ fn linebreak_optimized_bounded<'a>(
             // If the line is already underfull (`line_ratio > 0`), any shorter
             // slice of the line will be even more underfull. So it'll only get
             // worse from here and further attempts would also have a cost
-            // exceeding `bound`. There is one exception: When the line has
-            // negative spacing, we can't know for sure, so we don't assign the
-            // lower bound in that case.
+            // exceeding `bound`. There are two exceptions: When the line has
+            // negative spacing or when the available width depends on the line
+            // index due to an exclusion, we can't know for sure, so we don't
+            // assign the lower bound in that case.
             if line_ratio > 0.0
                 && line_lower_bound.is_none()
                 && !attempt.has_negative_width_items()
+                && p.exclusion.is_none()
             {
                 line_lower_bound = Some(line_cost);
             }
@@ -300,7 +360,13 @@ fn linebreak_optimized_bounded<'a>(
 
             // If this attempt is better than what we had before, take it!
             if best.as_ref().map_or(true, |best| best.total >= total) {
-                best = Some(Entry { pred: pred_index, total, line: attempt, end });
+                best = Some(Entry {
+                    pred: pred_index,
+                    total,
+                    line: attempt,
+                    end,
+                    lines: pred.lines + 1,
+                });
             }
         }
 
@@ -360,6 +426,7 @@ fn linebreak_optimized_approximate(
         pred: usize,
         total: Cost,
         end: usize,
+        lines: usize,
         unbreakable: bool,
         breakpoint: Breakpoint,
     }
@@ -369,6 +436,7 @@ fn linebreak_optimized_approximate(
         pred: 0,
         total: 0.0,
         end: 0,
+        lines: 0,
         unbreakable: false,
         breakpoint: Breakpoint::Mandatory,
     }];
@@ -399,7 +467,7 @@ fn linebreak_optimized_approximate(
             let trimmed_end = start + p.text[start..end].trim_end().len();
             let line_ratio = raw_ratio(
                 p,
-                width,
+                width - p.excluded(pred.lines),
                 estimates.widths.estimate(start..trimmed_end)
                     + if breakpoint.is_hyphen() {
                         metrics.approx_hyphen_width
@@ -437,6 +505,7 @@ fn linebreak_optimized_approximate(
                     pred: pred_index,
                     total,
                     end,
+                    lines: pred.lines + 1,
                     unbreakable,
                     breakpoint,
                 });
@@ -469,12 +538,19 @@ fn linebreak_optimized_approximate(
     // got here is only likely to be good, not guaranteed to be the best. We now
     // computes its exact cost as that gives us a sound upper bound for the
     // proper optimization pass.
-    for idx in indices.into_iter().rev() {
+    for (i, idx) in indices.into_iter().rev().enumerate() {
         let Entry { end, breakpoint, unbreakable, .. } = table[idx];
 
         let attempt = line(engine, p, start..end, breakpoint, Some(&pred));
-        let (ratio, line_cost) =
-            ratio_and_cost(p, metrics, width, &pred, &attempt, breakpoint, unbreakable);
+        let (ratio, line_cost) = ratio_and_cost(
+            p,
+            metrics,
+            width - p.excluded(i),
+            &pred,
+            &attempt,
+            breakpoint,
+            unbreakable,
+        );
 
         // If approximation produces a valid layout without too much shrinking,
         // exact layout is guaranteed to find the same layout. If, however, the
@@ -642,8 +718,9 @@ fn raw_cost(
     // From the Knuth-Plass Paper: $ (1 + beta_j + pi_j)^2 $.
     //
     // We add one to minimize the number of lines when everything else is more
-    // or less equal.
-    (1.0 + badness + penalty).powi(2)
+    // or less equal. Unless a penalty is negative, this also makes
+    // `MIN_LINE_COST` the minimum.
+    (MIN_LINE_COST + badness + penalty).powi(2)
 }
 
 /// Calls `f` for all possible points in the text where lines can broken.
@@ -859,11 +936,14 @@ struct CostMetrics {
     approx_hyphen_width: Abs,
     hyph_cost: Cost,
     runt_cost: Cost,
+    min_line_cost: Cost,
 }
 
 impl CostMetrics {
     /// Compute shared metrics for paragraph optimization.
     fn compute(p: &Preparation) -> Self {
+        let hyph_cost = DEFAULT_HYPH_COST * p.costs.hyphenation().get();
+        let runt_cost = DEFAULT_RUNT_COST * p.costs.runt().get();
         Self {
             // When justifying, we may stretch spaces below their natural width.
             min_ratio: if p.justify { MIN_RATIO } else { 0.0 },
@@ -871,8 +951,15 @@ impl CostMetrics {
             // Approximate hyphen width for estimates.
             approx_hyphen_width: Em::new(0.33).at(p.size),
             // Costs.
-            hyph_cost: DEFAULT_HYPH_COST * p.costs.hyphenation().get(),
-            runt_cost: DEFAULT_RUNT_COST * p.costs.runt().get(),
+            hyph_cost,
+            runt_cost,
+            // Negative penalties can cancel out the one we add to every line,
+            // so a line may then cost nothing at all.
+            min_line_cost: if hyph_cost < 0.0 || runt_cost < 0.0 {
+                0.0
+            } else {
+                MIN_LINE_COST
+            },
         }
     }
 
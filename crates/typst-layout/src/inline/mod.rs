@@ -11,24 +11,25 @@ mod shaping;
 pub use self::box_::layout_box;
 
 use comemo::{Track, Tracked, TrackedMut};
-use typst_library::diag::SourceResult;
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::diag::{PanicScope, SourceResult};
+use typst_library::engine::{record_memoized, Engine, Memoized, Route, Sink, Traced};
 use typst_library::foundations::{StyleChain, StyleVec};
 use typst_library::introspection::{Introspector, Locator, LocatorLink};
 use typst_library::layout::{Fragment, Size};
 use typst_library::model::ParElem;
 use typst_library::routines::Routines;
 use typst_library::World;
+use typst_syntax::Span;
 
-use self::collect::{collect, Item, Segment, SpanMapper};
+use self::collect::{collect, DropCap, Item, Segment, SpanMapper};
 use self::deco::decorate;
 use self::finalize::finalize;
 use self::line::{commit, line, Line};
 use self::linebreak::{linebreak, Breakpoint};
 use self::prepare::{prepare, Preparation};
 use self::shaping::{
-    cjk_punct_style, is_of_cj_script, shape_range, ShapedGlyph, ShapedText,
-    BEGIN_PUNCT_PAT, END_PUNCT_PAT,
+    cjk_punct_style, is_of_cj_script, shape_range, synthesized_figure_width, ShapedGlyph,
+    ShapedText, BEGIN_PUNCT_PAT, END_PUNCT_PAT,
 };
 
 /// Range of a substring of text.
@@ -77,6 +78,9 @@ fn layout_inline_impl(
     region: Size,
     expand: bool,
 ) -> SourceResult<Fragment> {
+    record_memoized(Memoized::Layout);
+    let span = Span::find(children.iter(&styles).map(|(c, _)| c.span()));
+    let _scope = PanicScope::enter(span, "paragraph");
     let link = LocatorLink::new(locator);
     let locator = Locator::link(&link);
     let mut engine = Engine {
@@ -91,14 +95,14 @@ fn layout_inline_impl(
     let mut locator = locator.split();
 
     // Collect all text into one string for BiDi analysis.
-    let (text, segments, spans) =
+    let (text, segments, spans, dropcap) =
         collect(children, &mut engine, &mut locator, &styles, region, consecutive)?;
 
     // Perform BiDi analysis and then prepares paragraph layout.
-    let p = prepare(&mut engine, children, &text, segments, spans, styles)?;
+    let p = prepare(&mut engine, children, &text, segments, spans, dropcap, styles)?;
 
     // Break the paragraph into lines.
-    let lines = linebreak(&engine, &p, region.x - p.hang);
+    let lines = linebreak(&mut engine, &p, region.x - p.hang, span);
 
     // Turn the selected lines into frames.
     finalize(&mut engine, &p, &lines, styles, region, expand, &mut locator)
@@ -1,7 +1,11 @@
+use ttf_parser::Tag;
+use typst_library::diag::warning;
 use typst_library::foundations::{Resolve, Smart};
-use typst_library::layout::{Abs, AlignElem, Dir, Em, FixedAlignment};
+use typst_library::layout::{Abs, AlignElem, Dir, Em, FixedAlignment, Frame};
 use typst_library::model::Linebreaks;
-use typst_library::text::{Costs, Lang, TextElem};
+use typst_library::text::{
+    CjkJustify, Costs, Font, Lang, NumberType, NumberWidth, TextEdgeBounds, TextElem,
+};
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 
 use super::*;
@@ -37,10 +41,16 @@ pub struct Preparation<'a> {
     pub align: FixedAlignment,
     /// Whether to justify the paragraph.
     pub justify: bool,
+    /// Whether to also justify lines ending in a mandatory break.
+    pub justify_last: bool,
     /// The paragraph's hanging indent.
     pub hang: Abs,
+    /// An area at the paragraph's start that its first lines wrap around.
+    pub exclusion: Option<Exclusion>,
     /// Whether to add spacing between CJK and Latin characters.
     pub cjk_latin_spacing: bool,
+    /// How to distribute space in justified CJK lines.
+    pub cjk_justify: Smart<CjkJustify>,
     /// Whether font fallback is enabled for this paragraph.
     pub fallback: bool,
     /// How to determine line breaks.
@@ -56,6 +66,15 @@ impl<'a> Preparation<'a> {
         &self.items[idx]
     }
 
+    /// The width taken away from the line with the given index by the
+    /// paragraph's exclusion.
+    pub fn excluded(&self, index: usize) -> Abs {
+        self.exclusion
+            .as_ref()
+            .filter(|exclusion| index < exclusion.lines)
+            .map_or(Abs::zero(), |exclusion| exclusion.width)
+    }
+
     /// Iterate over the items that intersect the given `sliced` range.
     pub fn slice(&self, sliced: Range) -> impl Iterator<Item = &(Range, Item<'a>)> {
         // Usually, we don't want empty-range items at the start of the line
@@ -71,6 +90,18 @@ impl<'a> Preparation<'a> {
     }
 }
 
+/// A rectangular area in the start corner of a paragraph around which its first
+/// lines wrap.
+pub struct Exclusion {
+    /// The content occupying the area. It is aligned with the baseline of the
+    /// last wrapping line.
+    pub frame: Frame,
+    /// How many lines wrap around the area.
+    pub lines: usize,
+    /// By how much the wrapping lines are shortened.
+    pub width: Abs,
+}
+
 /// Performs BiDi analysis and then prepares paragraph layout by building a
 /// representation on which we can do line breaking without layouting each and
 /// every line from scratch.
@@ -81,6 +112,7 @@ pub fn prepare<'a>(
     text: &'a str,
     segments: Vec<Segment<'a>>,
     spans: SpanMapper,
+    dropcap: Option<DropCap>,
     styles: StyleChain<'a>,
 ) -> SourceResult<Preparation<'a>> {
     let dir = TextElem::dir_in(styles);
@@ -114,6 +146,8 @@ pub fn prepare<'a>(
         cursor = end;
     }
 
+    warn_missing_figures(engine, &items, &spans);
+
     // Build the mapping from byte to item indices.
     let mut indices = Vec::with_capacity(text.len());
     for (i, (range, _)) in items.iter().enumerate() {
@@ -125,6 +159,15 @@ pub fn prepare<'a>(
         add_cjk_latin_spacing(&mut items);
     }
 
+    let exclusion = dropcap.map(|dropcap| Exclusion {
+        lines: match dropcap.lines {
+            Smart::Custom(lines) => lines.get(),
+            Smart::Auto => dropcap_lines(&items, &dropcap.frame, styles),
+        },
+        width: dropcap.frame.width() + dropcap.gap,
+        frame: dropcap.frame,
+    });
+
     Ok(Preparation {
         text,
         bidi: is_bidi.then_some(bidi),
@@ -137,14 +180,102 @@ pub fn prepare<'a>(
         lang: children.shared_get(styles, TextElem::lang_in),
         align: AlignElem::alignment_in(styles).resolve(styles).x,
         justify: ParElem::justify_in(styles),
+        justify_last: ParElem::justify_last_in(styles),
         hang: ParElem::hanging_indent_in(styles),
+        exclusion,
         cjk_latin_spacing,
+        cjk_justify: TextElem::cjk_justify_in(styles),
         fallback: TextElem::fallback_in(styles),
         linebreaks: ParElem::linebreaks_in(styles),
         size: TextElem::size_in(styles),
     })
 }
 
+/// Determines how many lines a drop cap spans such that its baseline lines up
+/// with the baseline of the last one, based on the metrics of the paragraph's
+/// first text run.
+fn dropcap_lines(items: &[(Range, Item)], frame: &Frame, styles: StyleChain) -> usize {
+    let size = TextElem::size_in(styles);
+    let (top, bottom) = items
+        .iter()
+        .find_map(|(_, item)| item.text())
+        .and_then(|shaped| shaped.glyphs.first())
+        .map(|glyph| {
+            glyph.font.edges(
+                TextElem::top_edge_in(styles),
+                TextElem::bottom_edge_in(styles),
+                size,
+                TextEdgeBounds::Zero,
+            )
+        })
+        .unwrap_or((size, Abs::zero()));
+
+    // The distance between the baselines of two consecutive lines.
+    let pitch = top + bottom + ParElem::leading_in(styles);
+    if pitch <= Abs::zero() {
+        return 1;
+    }
+
+    let extra = ((frame.baseline() - top) / pitch).round();
+    1 + extra.max(0.0) as usize
+}
+
+/// Warn about fonts that lack the requested style of figures.
+///
+/// Each font is reported once for the whole document, at the first digit it
+/// lacks the figures for.
+fn warn_missing_figures(
+    engine: &mut Engine,
+    items: &[(Range, Item)],
+    spans: &SpanMapper,
+) {
+    let mut checked: Vec<(&Font, bool, bool)> = vec![];
+    for (_, item) in items {
+        let Some(shaped) = item.text() else { continue };
+        let old_style = TextElem::number_type_in(shaped.styles)
+            == Smart::Custom(NumberType::OldStyle);
+        let tabular = TextElem::number_width_in(shaped.styles)
+            == Smart::Custom(NumberWidth::Tabular);
+        if !old_style && !tabular {
+            continue;
+        }
+
+        for glyph in shaped.glyphs.iter().filter(|g| g.c.is_ascii_digit()) {
+            let font = &glyph.font;
+            if checked.contains(&(font, old_style, tabular)) {
+                continue;
+            }
+            checked.push((font, old_style, tabular));
+
+            let family = &font.info().family;
+            let span = spans.span_at(glyph.range.start).0;
+            if old_style && !font.has_feature(Tag::from_bytes(b"onum")) {
+                engine.sink.warn(
+                    warning!(
+                        span,
+                        "font \"{family}\" has no old-style figures";
+                        hint: "lining figures are used instead"
+                    )
+                    .with_code("missing-figures")
+                    .reported_once(),
+                );
+            }
+
+            if tabular && synthesized_figure_width(font).is_some() {
+                engine.sink.warn(
+                    warning!(
+                        span,
+                        "font \"{family}\" has no tabular figures";
+                        hint: "digits are widened to the width of the widest digit instead"
+                    )
+                    .with_code("missing-figures")
+                    .reported_once(),
+                );
+            }
+        }
+    }
+}
+
 /// Add some spacing between Han characters and western characters. See
 /// Requirements for Chinese Text Layout, Section 3.2.2 Mixed Text Composition
 /// in Horizontal Written Mode
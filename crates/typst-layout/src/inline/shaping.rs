@@ -5,17 +5,18 @@ use std::sync::Arc;
 
 use az::SaturatingAs;
 use ecow::EcoString;
-use rustybuzz::{BufferFlags, ShapePlan, UnicodeBuffer};
+use rustybuzz::{BufferFlags, GlyphInfo, ShapePlan, UnicodeBuffer};
 use ttf_parser::Tag;
-use typst_library::engine::Engine;
-use typst_library::foundations::{Smart, StyleChain};
+use typst_library::engine::{record_memoized, Engine, Memoized};
+use typst_library::foundations::{Resolve, Smart, StyleChain};
 use typst_library::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Size};
 use typst_library::text::{
-    families, features, is_default_ignorable, variant, Font, FontFamily, FontVariant,
-    Glyph, Lang, Region, TextEdgeBounds, TextElem, TextItem,
+    families, features, glyph_covers, is_default_ignorable, select_font, variant,
+    CjkJustify, Font, FontFamily, FontVariant, Glyph, Lang, LigatureExceptions,
+    NumberWidth, Region, TextEdgeBounds, TextElem, TextItem,
 };
 use typst_library::World;
-use typst_utils::SliceExt;
+use typst_utils::{Numeric, SliceExt};
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
 
@@ -378,7 +379,7 @@ impl<'a> ShapedText<'a> {
     pub fn cjk_justifiable_at_last(&self) -> bool {
         self.glyphs
             .last()
-            .map(|g| g.is_cj_script() || g.is_cjk_punctuation())
+            .map(|g| g.is_justifiable() && (g.is_cj_script() || g.is_cjk_punctuation()))
             .unwrap_or(false)
     }
 
@@ -434,6 +435,21 @@ impl<'a> ShapedText<'a> {
         }
     }
 
+    /// Remove the tracking after the last glyph of the text.
+    ///
+    /// This is used at the end of a line, where tracking would otherwise
+    /// result in trailing space.
+    pub fn trim_tracking(&mut self) {
+        let tracking = Em::from_length(TextElem::tracking_in(self.styles), self.size);
+        if tracking.is_zero() || self.glyphs.is_empty() {
+            return;
+        }
+
+        let glyph = self.glyphs.to_mut().last_mut().unwrap();
+        glyph.x_advance -= tracking;
+        self.width -= tracking.at(self.size);
+    }
+
     /// Derive an empty text run with the same properties as this one.
     pub fn empty(&self) -> Self {
         Self {
@@ -472,7 +488,8 @@ impl<'a> ShapedText<'a> {
             let font = world.font(id)?;
             let ttf = font.ttf();
             let glyph_id = ttf.glyph_index('-')?;
-            let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?);
+            let tracking = Em::from_length(TextElem::tracking_in(self.styles), self.size);
+            let x_advance = font.to_em(ttf.glyph_hor_advance(glyph_id)?) + tracking;
             let range = match side {
                 Side::Left => self.glyphs.first().map(|g| g.range.start..g.range.start),
                 Side::Right => self.glyphs.last().map(|g| g.range.end..g.range.end),
@@ -672,6 +689,7 @@ fn shape<'a>(
         styles,
         variant: variant(styles),
         features: features(styles),
+        ligature_exceptions: TextElem::ligature_exceptions_in(styles),
         fallback: TextElem::fallback_in(styles),
         dir,
     };
@@ -680,6 +698,8 @@ fn shape<'a>(
         shape_segment(&mut ctx, base, text, families(styles));
     }
 
+    synthesize_tabular_figures(&mut ctx);
+    adjust_kerning(&mut ctx, base, text);
     track_and_space(&mut ctx);
     calculate_adjustability(&mut ctx, lang, region);
 
@@ -711,6 +731,7 @@ struct ShapingContext<'a, 'v> {
     size: Abs,
     variant: FontVariant,
     features: Vec<rustybuzz::Feature>,
+    ligature_exceptions: LigatureExceptions,
     fallback: bool,
     dir: Dir,
 }
@@ -730,33 +751,20 @@ fn shape_segment<'a>(
         return;
     }
 
-    // Find the next available family.
-    let world = ctx.engine.world;
-    let book = world.book();
-    let mut selection = None;
-    let mut covers = None;
-    for family in families.by_ref() {
-        selection = book
-            .select(family.as_str(), ctx.variant)
-            .and_then(|id| world.font(id))
-            .filter(|font| !ctx.used.contains(font));
-        if selection.is_some() {
-            covers = family.covers();
-            break;
-        }
-    }
-
-    // Do font fallback if the families are exhausted and fallback is enabled.
-    if selection.is_none() && ctx.fallback {
-        let first = ctx.used.first().map(Font::info);
-        selection = book
-            .select_fallback(first, ctx.variant, text)
-            .and_then(|id| world.font(id))
-            .filter(|font| !ctx.used.contains(font));
-    }
+    // Find the next available family or do font fallback if the families are
+    // exhausted and fallback is enabled.
+    let selection = select_font(
+        ctx.engine.world,
+        &mut families,
+        ctx.variant,
+        &ctx.used,
+        ctx.fallback,
+        text,
+        |_| {},
+    );
 
     // Extract the font id or shape notdef glyphs if we couldn't find any font.
-    let Some(font) = selection else {
+    let Some((font, covers)) = selection else {
         if let Some(font) = ctx.used.first().cloned() {
             shape_tofus(ctx, base, text, font);
         }
@@ -767,7 +775,7 @@ fn shape_segment<'a>(
 
     // Fill the buffer with our text.
     let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
+    push_text(&mut buffer, text, &ctx.ligature_exceptions);
     buffer.set_language(language(ctx.styles));
     if let Some(script) = TextElem::script_in(ctx.styles).custom().and_then(|script| {
         rustybuzz::Script::from_iso15924_tag(Tag::from_bytes(script.as_bytes()))
@@ -803,14 +811,9 @@ fn shape_segment<'a>(
     let pos = buffer.glyph_positions();
     let ltr = ctx.dir.is_positive();
 
-    // Whether the character at the given offset is covered by the coverage.
-    let is_covered = |offset| {
-        let end = text[offset..]
-            .char_indices()
-            .nth(1)
-            .map(|(i, _)| offset + i)
-            .unwrap_or(text.len());
-        covers.map_or(true, |cov| cov.is_match(&text[offset..end]))
+    // Whether the glyph covers its cluster.
+    let is_covered = |info: &GlyphInfo| {
+        glyph_covers(info.glyph_id, text, info.cluster as usize, covers)
     };
 
     // Collect the shaped glyphs, doing fallback and shaping parts again with
@@ -821,7 +824,7 @@ fn shape_segment<'a>(
         let cluster = info.cluster as usize;
 
         // Add the glyph to the shaped output.
-        if info.glyph_id != 0 && is_covered(cluster) {
+        if is_covered(info) {
             // Determine the text range of the glyph.
             let start = base + cluster;
             let end = base
@@ -854,9 +857,7 @@ fn shape_segment<'a>(
         } else {
             // First, search for the end of the tofu sequence.
             let k = i;
-            while infos.get(i + 1).is_some_and(|info| {
-                info.glyph_id == 0 || !is_covered(info.cluster as usize)
-            }) {
+            while infos.get(i + 1).is_some_and(|info| !is_covered(info)) {
                 i += 1;
             }
 
@@ -900,6 +901,39 @@ fn shape_segment<'a>(
     ctx.used.pop();
 }
 
+/// Fill the buffer with the text, placing a zero-width non-joiner between
+/// each two characters of every occurrence of a ligature exception.
+///
+/// The non-joiners only keep ligatures from forming across the joints. Each of
+/// them belongs to the cluster of the character after it and is removed during
+/// shaping, so the clusters of the glyphs still match the text.
+fn push_text(buffer: &mut UnicodeBuffer, text: &str, exceptions: &LigatureExceptions) {
+    if exceptions.0.is_empty() {
+        buffer.push_str(text);
+        return;
+    }
+
+    // Occurrences may overlap, as in "fff" with the exception "ff".
+    let mut joints = vec![];
+    for (start, _) in text.char_indices() {
+        for sequence in &exceptions.0 {
+            if text[start..].starts_with(sequence.as_str()) {
+                joints.extend(sequence.char_indices().skip(1).map(|(i, _)| start + i));
+            }
+        }
+    }
+    joints.sort_unstable();
+    joints.dedup();
+
+    let mut joints = joints.into_iter().peekable();
+    for (i, c) in text.char_indices() {
+        if joints.next_if_eq(&i).is_some() {
+            buffer.add('\u{200C}', i as u32);
+        }
+        buffer.add(c, i as u32);
+    }
+}
+
 /// Create a shape plan.
 #[comemo::memoize]
 fn create_shape_plan(
@@ -909,6 +943,7 @@ fn create_shape_plan(
     language: Option<&rustybuzz::Language>,
     features: &[rustybuzz::Feature],
 ) -> Arc<ShapePlan> {
+    record_memoized(Memoized::ShapePlan);
     Arc::new(rustybuzz::ShapePlan::new(
         font.rusty(),
         direction,
@@ -951,6 +986,76 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
     }
 }
 
+/// Widen the digits of fonts without tabular figures to a common advance if
+/// tabular figures were requested.
+///
+/// Only the digits themselves are widened. Punctuation within numbers, like
+/// decimal points, already has the same width in all numbers.
+fn synthesize_tabular_figures(ctx: &mut ShapingContext) {
+    if TextElem::number_width_in(ctx.styles) != Smart::Custom(NumberWidth::Tabular) {
+        return;
+    }
+
+    for glyph in &mut ctx.glyphs {
+        if !glyph.c.is_ascii_digit() {
+            continue;
+        }
+
+        let Some(width) = synthesized_figure_width(&glyph.font) else { continue };
+        let extra = width - glyph.x_advance;
+        if extra > Em::zero() {
+            glyph.x_advance = width;
+            glyph.x_offset += extra / 2.0;
+        }
+    }
+}
+
+/// The advance to which a font's digits must be widened to become tabular.
+///
+/// This is `None` if the font has tabular figures, either through the `tnum`
+/// feature or because its digits all have the same advance by default.
+#[comemo::memoize]
+pub fn synthesized_figure_width(font: &Font) -> Option<Em> {
+    if font.has_feature(Tag::from_bytes(b"tnum")) {
+        return None;
+    }
+
+    let advances: Vec<Em> = ('0'..='9')
+        .filter_map(|c| font.ttf().glyph_index(c))
+        .filter_map(|id| font.advance(id.0))
+        .collect();
+    let widest = advances.iter().copied().max()?;
+    advances.iter().any(|&advance| advance != widest).then_some(widest)
+}
+
+/// Add the manual kerning adjustments between configured pairs of characters.
+///
+/// The adjustment is added to the advance of the visually left glyph of a
+/// pair, which is the first character in left-to-right text and the second
+/// one in right-to-left text.
+fn adjust_kerning(ctx: &mut ShapingContext, base: usize, text: &str) {
+    let adjustments = TextElem::kerning_adjustments_in(ctx.styles);
+    if adjustments.0.is_empty() {
+        return;
+    }
+
+    let ltr = ctx.dir.is_positive();
+    let cluster =
+        |glyph: &ShapedGlyph| &text[glyph.range.start - base..glyph.range.end - base];
+    for i in 1..ctx.glyphs.len() {
+        let (left, right) = (&ctx.glyphs[i - 1], &ctx.glyphs[i]);
+        if left.range == right.range {
+            continue;
+        }
+
+        let (first, second) = if ltr { (left, right) } else { (right, left) };
+        if let Some(amount) = adjustments.get(cluster(first), cluster(second)) {
+            ctx.glyphs[i - 1].x_advance +=
+                Em::from_length(amount.resolve(ctx.styles), ctx.size);
+        }
+    }
+}
+
 /// Apply tracking and spacing to the shaped glyphs.
 fn track_and_space(ctx: &mut ShapingContext) {
     let tracking = Em::from_length(TextElem::tracking_in(ctx.styles), ctx.size);
@@ -968,9 +1073,11 @@ fn track_and_space(ctx: &mut ShapingContext) {
             glyph.x_advance = spacing.relative_to(glyph.x_advance);
         }
 
+        // Tracking is added after each cluster, including the last one. At
+        // the end of a line, it is removed again by the line builder.
         if glyphs
             .peek()
-            .is_some_and(|next| glyph.range.start != next.range.start)
+            .map_or(true, |next| glyph.range.start != next.range.start)
         {
             glyph.x_advance += tracking;
         }
@@ -981,9 +1088,15 @@ fn track_and_space(ctx: &mut ShapingContext) {
 /// and CJK punctuation adjustments according to Chinese Layout Requirements.
 fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<Region>) {
     let style = cjk_punct_style(lang, region);
+    let inter_word =
+        TextElem::cjk_justify_in(ctx.styles) == Smart::Custom(CjkJustify::InterWord);
 
     for glyph in &mut ctx.glyphs {
         glyph.adjustability = glyph.base_adjustability(style);
+        if inter_word {
+            // Only spaces receive extra space when justifying.
+            glyph.is_justifiable = glyph.is_space();
+        }
     }
 
     let mut glyphs = ctx.glyphs.iter_mut().peekable();
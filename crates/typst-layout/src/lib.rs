@@ -1,5 +1,6 @@
 //! Typst's layout engine.
 
+mod fit;
 mod flow;
 mod grid;
 mod image;
@@ -13,8 +14,9 @@ mod shapes;
 mod stack;
 mod transforms;
 
+pub use self::fit::layout_fit_text;
 pub use self::flow::{layout_columns, layout_fragment, layout_frame};
-pub use self::grid::{layout_grid, layout_table};
+pub use self::grid::{html_grid, html_table, layout_grid, layout_table};
 pub use self::image::layout_image;
 pub use self::inline::{layout_box, layout_inline};
 pub use self::lists::{layout_enum, layout_list};
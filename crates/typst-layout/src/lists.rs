@@ -2,10 +2,12 @@ use comemo::Track;
 use smallvec::smallvec;
 use typst_library::diag::SourceResult;
 use typst_library::engine::Engine;
-use typst_library::foundations::{Content, Context, Depth, Packed, StyleChain};
+use typst_library::foundations::{Content, Context, Depth, Packed, Smart, StyleChain};
 use typst_library::introspection::Locator;
-use typst_library::layout::{Axes, Fragment, HAlignment, Regions, Sizing, VAlignment};
-use typst_library::model::{EnumElem, ListElem, Numbering, ParElem};
+use typst_library::layout::{
+    Axes, Fragment, FrameRole, HAlignment, Length, Regions, Sizing, VAlignment,
+};
+use typst_library::model::{EnumElem, ListElem, Numbering, ParElem, ParbreakElem};
 use typst_library::text::TextElem;
 
 use crate::grid::{Cell, CellGrid, GridLayouter};
@@ -21,13 +23,12 @@ pub fn layout_list(
 ) -> SourceResult<Fragment> {
     let indent = elem.indent(styles);
     let body_indent = elem.body_indent(styles);
-    let gutter = elem.spacing(styles).unwrap_or_else(|| {
-        if elem.tight(styles) {
-            ParElem::leading_in(styles).into()
-        } else {
-            ParElem::spacing_in(styles).into()
-        }
-    });
+    let gutter = gutter(
+        elem.children.iter().map(|item| &item.body),
+        elem.spacing(styles),
+        elem.tight(styles),
+        styles,
+    );
 
     let Depth(depth) = ListElem::depth_in(styles);
     let marker = elem
@@ -43,10 +44,13 @@ pub fn layout_list(
         cells.push(Cell::new(Content::empty(), locator.next(&())));
         cells.push(Cell::new(marker.clone(), locator.next(&marker.span())));
         cells.push(Cell::new(Content::empty(), locator.next(&())));
-        cells.push(Cell::new(
-            item.body.clone().styled(ListElem::set_depth(Depth(1))),
-            locator.next(&item.body.span()),
-        ));
+        cells.push(Cell {
+            role: Some(FrameRole::ListItem),
+            ..Cell::new(
+                item.body.clone().styled(ListElem::set_depth(Depth(1))),
+                locator.next(&item.body.span()),
+            )
+        });
     }
 
     let grid = CellGrid::new(
@@ -56,7 +60,7 @@ pub fn layout_list(
             Sizing::Rel(body_indent.into()),
             Sizing::Auto,
         ]),
-        Axes::with_y(&[gutter.into()]),
+        Axes::with_y(&gutter),
         cells,
     );
     let layouter = GridLayouter::new(&grid, regions, styles, elem.span());
@@ -77,13 +81,12 @@ pub fn layout_enum(
     let reversed = elem.reversed(styles);
     let indent = elem.indent(styles);
     let body_indent = elem.body_indent(styles);
-    let gutter = elem.spacing(styles).unwrap_or_else(|| {
-        if elem.tight(styles) {
-            ParElem::leading_in(styles).into()
-        } else {
-            ParElem::spacing_in(styles).into()
-        }
-    });
+    let gutter = gutter(
+        elem.children.iter().map(|item| &item.body),
+        elem.spacing(styles),
+        elem.tight(styles),
+        styles,
+    );
 
     let mut cells = vec![];
     let mut locator = locator.split();
@@ -126,10 +129,13 @@ pub fn layout_enum(
         cells.push(Cell::new(Content::empty(), locator.next(&())));
         cells.push(Cell::new(resolved, locator.next(&())));
         cells.push(Cell::new(Content::empty(), locator.next(&())));
-        cells.push(Cell::new(
-            item.body.clone().styled(EnumElem::set_parents(smallvec![number])),
-            locator.next(&item.body.span()),
-        ));
+        cells.push(Cell {
+            role: Some(FrameRole::ListItem),
+            ..Cell::new(
+                item.body.clone().styled(EnumElem::set_parents(smallvec![number])),
+                locator.next(&item.body.span()),
+            )
+        });
         number =
             if reversed { number.saturating_sub(1) } else { number.saturating_add(1) };
     }
@@ -141,10 +147,39 @@ pub fn layout_enum(
             Sizing::Rel(body_indent.into()),
             Sizing::Auto,
         ]),
-        Axes::with_y(&[gutter.into()]),
+        Axes::with_y(&gutter),
         cells,
     );
     let layouter = GridLayouter::new(&grid, regions, styles, elem.span());
 
     layouter.layout(engine)
 }
+
+/// Determine the gutter rows between the items of a list or enumeration.
+///
+/// Tightness only affects the spacing between items, never the spacing
+/// between the paragraphs within an item. Thus, in a tight list, an item that
+/// consists of multiple paragraphs is separated from its neighbours by
+/// paragraph spacing instead of leading.
+fn gutter<'a>(
+    bodies: impl Iterator<Item = &'a Content>,
+    spacing: Smart<Length>,
+    tight: bool,
+    styles: StyleChain,
+) -> Vec<Sizing> {
+    if let Smart::Custom(spacing) = spacing {
+        return vec![spacing.into()];
+    }
+
+    let leading = ParElem::leading_in(styles);
+    let par_spacing = ParElem::spacing_in(styles);
+    if !tight {
+        return vec![par_spacing.into()];
+    }
+
+    let multi = bodies.map(ParbreakElem::contained_in).collect::<Vec<_>>();
+    multi
+        .windows(2)
+        .map(|pair| Sizing::from(if pair[0] || pair[1] { par_spacing } else { leading }))
+        .collect()
+}
@@ -200,7 +200,7 @@ impl MathFragment {
     /// assumed.
     pub fn kern_at_height(&self, ctx: &MathContext, corner: Corner, height: Abs) -> Abs {
         match self {
-            Self::Glyph(glyph) => {
+            Self::Glyph(glyph) if !glyph.is_substituted(ctx) => {
                 kern_at_height(ctx, glyph.font_size, glyph.id, corner, height)
                     .unwrap_or_default()
             }
@@ -269,12 +269,44 @@ impl GlyphFragment {
         Some(Self::with_id(ctx, styles, c, id, span))
     }
 
+    /// Try to create a glyph from a font other than the math font, like a
+    /// font for a specific alphabet or a symbol fallback.
+    ///
+    /// The glyph's own metrics come from the given font, but everything
+    /// around it, like the axis height and script positions, still comes
+    /// from the math font.
+    pub fn try_new_in(
+        ctx: &MathContext,
+        styles: StyleChain,
+        c: char,
+        font: &Font,
+        span: Span,
+    ) -> Option<Self> {
+        if font == ctx.font {
+            return Self::try_new(ctx, styles, c, span);
+        }
+
+        let id = font.ttf().glyph_index(c)?;
+        Some(Self::with_font(ctx, styles, c, font.clone(), id, span))
+    }
+
     pub fn with_id(
         ctx: &MathContext,
         styles: StyleChain,
         c: char,
         id: GlyphId,
         span: Span,
+    ) -> Self {
+        Self::with_font(ctx, styles, c, ctx.font.clone(), id, span)
+    }
+
+    fn with_font(
+        ctx: &MathContext,
+        styles: StyleChain,
+        c: char,
+        font: Font,
+        id: GlyphId,
+        span: Span,
     ) -> Self {
         let class = EquationElem::class_in(styles)
             .or_else(|| match c {
@@ -287,7 +319,7 @@ impl GlyphFragment {
         let mut fragment = Self {
             id,
             c,
-            font: ctx.font.clone(),
+            font,
             lang: TextElem::lang_in(styles),
             region: TextElem::region_in(styles),
             fill: TextElem::fill_in(styles).as_decoration(),
@@ -322,6 +354,11 @@ impl GlyphFragment {
     /// Sets element id and boxes in appropriate way without changing other
     /// styles. This is used to replace the glyph with a stretch variant.
     pub fn set_id(&mut self, ctx: &MathContext, id: GlyphId) {
+        if self.is_substituted(ctx) {
+            self.set_substituted_id(id);
+            return;
+        }
+
         let advance = ctx.ttf.glyph_hor_advance(id).unwrap_or_default();
         let italics = italics_correction(ctx, id, self.font_size).unwrap_or_default();
         let bbox = ctx.ttf.glyph_bounding_box(id).unwrap_or(Rect {
@@ -349,6 +386,37 @@ impl GlyphFragment {
         self.extended_shape = extended_shape;
     }
 
+    /// Sets the id of a glyph that is not from the math font. Such fonts
+    /// usually have no math table, so the glyph has no italics correction and
+    /// its accents are attached in the middle.
+    fn set_substituted_id(&mut self, id: GlyphId) {
+        let ttf = self.font.ttf();
+        let advance = ttf.glyph_hor_advance(id).unwrap_or_default();
+        let bbox = ttf.glyph_bounding_box(id).unwrap_or(Rect {
+            x_min: 0,
+            y_min: 0,
+            x_max: 0,
+            y_max: 0,
+        });
+
+        let width = self.font.to_em(advance).at(self.font_size);
+        let ascent = self.font.to_em(bbox.y_max).at(self.font_size);
+        let descent = -self.font.to_em(bbox.y_min).at(self.font_size);
+
+        self.id = id;
+        self.width = width;
+        self.ascent = ascent;
+        self.descent = descent;
+        self.italics_correction = Abs::zero();
+        self.accent_attach = width / 2.0;
+        self.extended_shape = false;
+    }
+
+    /// Whether the glyph is taken from another font than the math font.
+    pub fn is_substituted(&self, ctx: &MathContext) -> bool {
+        self.font != *ctx.font
+    }
+
     pub fn height(&self) -> Abs {
         self.ascent + self.descent
     }
@@ -395,6 +463,10 @@ impl GlyphFragment {
     }
 
     pub fn make_script_size(&mut self, ctx: &MathContext) {
+        if self.is_substituted(ctx) {
+            return;
+        }
+
         let alt_id =
             ctx.ssty_table.as_ref().and_then(|ssty| ssty.try_apply(self.id, None));
         if let Some(alt_id) = alt_id {
@@ -403,6 +475,10 @@ impl GlyphFragment {
     }
 
     pub fn make_script_script_size(&mut self, ctx: &MathContext) {
+        if self.is_substituted(ctx) {
+            return;
+        }
+
         let alt_id = ctx.ssty_table.as_ref().and_then(|ssty| {
             // We explicitly request to apply the alternate set with value 1,
             // as opposed to the default value in ssty, as the former
@@ -417,6 +493,10 @@ impl GlyphFragment {
     }
 
     pub fn make_dotless_form(&mut self, ctx: &MathContext) {
+        if self.is_substituted(ctx) {
+            return;
+        }
+
         let alt_id =
             ctx.dtls_table.as_ref().and_then(|dtls| dtls.try_apply(self.id, None));
         if let Some(alt_id) = alt_id {
@@ -425,6 +505,10 @@ impl GlyphFragment {
     }
 
     pub fn make_flattened_accent_form(&mut self, ctx: &MathContext) {
+        if self.is_substituted(ctx) {
+            return;
+        }
+
         let alt_id =
             ctx.flac_table.as_ref().and_then(|flac| flac.try_apply(self.id, None));
         if let Some(alt_id) = alt_id {
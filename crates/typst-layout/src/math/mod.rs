@@ -15,7 +15,7 @@ mod underover;
 
 use rustybuzz::Feature;
 use ttf_parser::Tag;
-use typst_library::diag::{bail, SourceResult};
+use typst_library::diag::{bail, PanicScope, SourceResult};
 use typst_library::engine::Engine;
 use typst_library::foundations::{Content, NativeElement, Packed, Resolve, StyleChain};
 use typst_library::introspection::{Counter, Locator, SplitLocator, TagElem};
@@ -53,6 +53,7 @@ pub fn layout_equation_inline(
 ) -> SourceResult<Vec<InlineItem>> {
     assert!(!elem.block(styles));
 
+    let _scope = PanicScope::enter(elem.span(), "equation");
     let font = find_math_font(engine, styles, elem.span())?;
 
     let mut locator = locator.split();
@@ -108,6 +109,7 @@ pub fn layout_equation_block(
     assert!(elem.block(styles));
 
     let span = elem.span();
+    let _scope = PanicScope::enter(span, "equation");
     let font = find_math_font(engine, styles, span)?;
 
     let mut locator = locator.split();
@@ -243,8 +245,10 @@ fn find_math_font(
     let variant = variant(styles);
     let world = engine.world;
     let Some(font) = families(styles).find_map(|family| {
-        let id = world.book().select(family.as_str(), variant)?;
-        let font = world.font(id)?;
+        let font = world
+            .book()
+            .select_variants(family.as_str(), variant)
+            .find_map(|id| world.font(id))?;
         let _ = font.ttf().tables().math?.constants?;
         Some(font)
     }) else {
@@ -253,6 +257,34 @@ fn find_math_font(
     Ok(font)
 }
 
+/// Find the math fonts in the font chain after the primary one. Their glyph
+/// variants are used when the primary font cannot stretch a glyph far enough.
+fn find_fallback_math_fonts(
+    engine: &Engine<'_>,
+    styles: StyleChain,
+    primary: &Font,
+) -> Vec<Font> {
+    let variant = variant(styles);
+    let world = engine.world;
+    let mut fallbacks: Vec<Font> = vec![];
+    for family in families(styles) {
+        let Some(font) = world
+            .book()
+            .select_variants(family.as_str(), variant)
+            .find_map(|id| world.font(id))
+        else {
+            continue;
+        };
+        if font != *primary
+            && !fallbacks.contains(&font)
+            && font.ttf().tables().math.and_then(|math| math.variants).is_some()
+        {
+            fallbacks.push(font);
+        }
+    }
+    fallbacks
+}
+
 fn add_equation_number(
     equation_builder: MathRunFrameBuilder,
     number: Frame,
@@ -381,6 +413,7 @@ struct MathContext<'a, 'v, 'e> {
     ssty_table: Option<GlyphwiseSubsts<'a>>,
     glyphwise_tables: Option<Vec<GlyphwiseSubsts<'a>>>,
     space_width: Em,
+    fallbacks: Vec<Font>,
     // Mutable.
     fragments: Vec<MathFragment>,
 }
@@ -417,6 +450,8 @@ impl<'a, 'v, 'e> MathContext<'a, 'v, 'e> {
             .map(|advance| font.to_em(advance))
             .unwrap_or(THICK);
 
+        let fallbacks = find_fallback_math_fonts(engine, styles, font);
+
         Self {
             engine,
             locator,
@@ -430,6 +465,7 @@ impl<'a, 'v, 'e> MathContext<'a, 'v, 'e> {
             ssty_table: feat(b"ssty"),
             glyphwise_tables,
             space_width,
+            fallbacks,
             fragments: vec![],
         }
     }
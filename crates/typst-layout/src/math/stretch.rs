@@ -2,7 +2,7 @@ use ttf_parser::math::{GlyphAssembly, GlyphConstruction, GlyphPart};
 use ttf_parser::LazyArray16;
 use typst_library::diag::{warning, SourceResult};
 use typst_library::foundations::{Packed, StyleChain};
-use typst_library::layout::{Abs, Axis, Frame, Point, Rel, Size};
+use typst_library::layout::{Abs, Axis, Frame, Point, Ratio, Rel, Size, Transform};
 use typst_library::math::StretchElem;
 use typst_utils::Get;
 
@@ -14,6 +14,10 @@ use super::{
 /// Maximum number of times extenders can be repeated.
 const MAX_REPEATS: usize = 1024;
 
+/// The fraction of the target size below which a glyph that cannot be
+/// stretched any further is scaled instead.
+const MIN_COVERAGE: f64 = 0.5;
+
 /// Lays out a [`StretchElem`].
 #[typst_macros::time(name = "math.stretch", span = elem.span())]
 pub fn layout_stretch(
@@ -22,6 +26,19 @@ pub fn layout_stretch(
     styles: StyleChain,
 ) -> SourceResult<()> {
     let mut fragment = ctx.layout_into_fragment(elem.body(), styles)?;
+
+    // A symbol that is passed as an argument, like in `stretch(angle.l)`, has
+    // no span of its own, so warnings about it point to the call instead.
+    match &mut fragment {
+        MathFragment::Glyph(glyph) if glyph.span.is_detached() => {
+            glyph.span = elem.span()
+        }
+        MathFragment::Variant(variant) if variant.span.is_detached() => {
+            variant.span = elem.span()
+        }
+        _ => {}
+    }
+
     stretch_fragment(
         ctx,
         styles,
@@ -81,6 +98,10 @@ pub fn stretch_fragment(
 /// Return whether the glyph is stretchable and if it is, along which axis it
 /// can be stretched.
 fn stretch_axis(ctx: &mut MathContext, base: &GlyphFragment) -> Option<Axis> {
+    if base.is_substituted(ctx) {
+        return None;
+    }
+
     let base_id = base.id;
     let vertical = ctx
         .table
@@ -132,10 +153,12 @@ pub fn stretch_glyph(
         return base.into_variant();
     }
 
+    // Glyphs from other fonts can't use the math font's constructions.
     let mut min_overlap = Abs::zero();
     let construction = ctx
         .table
         .variants
+        .filter(|_| !base.is_substituted(ctx))
         .and_then(|variants| {
             min_overlap = variants.min_connector_overlap.scaled(ctx, base.font_size);
             match axis {
@@ -157,15 +180,112 @@ pub fn stretch_glyph(
         }
     }
 
-    // This is either good or the best we've got.
-    if short_target <= best_advance || construction.assembly.is_none() {
+    // This is good enough.
+    if short_target <= best_advance {
         base.set_id(ctx, best_id);
         return base.into_variant();
     }
 
     // Assemble from parts.
-    let assembly = construction.assembly.unwrap();
-    assemble(ctx, base, assembly, min_overlap, target, axis)
+    if let Some(assembly) = construction.assembly {
+        return assemble(ctx, base, assembly, min_overlap, target, axis);
+    }
+
+    // Try the pre-made variants of the other math fonts in the font chain.
+    if let Some(variant) = fallback_variant(ctx, &base, short_target, axis) {
+        return variant;
+    }
+
+    // This is the best we've got. If the glyph is meant to be stretched, but
+    // is way too small, scale it. Glyphs without any variants, like a letter
+    // used as a large operator, are kept as they are.
+    let stretchable = !construction.variants.is_empty();
+    base.set_id(ctx, best_id);
+    let advance = match axis {
+        Axis::X => base.width,
+        Axis::Y => base.height(),
+    };
+    if stretchable && advance > Abs::zero() && advance < short_target * MIN_COVERAGE {
+        return scale_glyph(ctx, base, short_target / advance, axis);
+    }
+
+    base.into_variant()
+}
+
+/// Find a large enough pre-made variant of the glyph in one of the fallback
+/// math fonts.
+fn fallback_variant(
+    ctx: &MathContext,
+    base: &GlyphFragment,
+    short_target: Abs,
+    axis: Axis,
+) -> Option<VariantFragment> {
+    ctx.fallbacks.iter().find_map(|font| {
+        let ttf = font.ttf();
+        let variants = ttf.tables().math?.variants?;
+        let construction = match axis {
+            Axis::X => variants.horizontal_constructions,
+            Axis::Y => variants.vertical_constructions,
+        }
+        .get(ttf.glyph_index(base.c)?)?;
+
+        let variant = construction.variants.into_iter().find(|variant| {
+            short_target <= font.to_em(variant.advance_measurement).at(base.font_size)
+        })?;
+
+        let id = variant.variant_glyph;
+        let bbox = ttf.glyph_bounding_box(id)?;
+        let advance = ttf.glyph_hor_advance(id)?;
+
+        let mut glyph = base.clone();
+        glyph.font = font.clone();
+        glyph.id = id;
+        glyph.width = font.to_em(advance).at(glyph.font_size);
+        glyph.ascent = font.to_em(bbox.y_max).at(glyph.font_size);
+        glyph.descent = -font.to_em(bbox.y_min).at(glyph.font_size);
+        glyph.italics_correction = Abs::zero();
+        glyph.accent_attach = glyph.width / 2.0;
+        glyph.extended_shape = true;
+        Some(glyph.into_variant())
+    })
+}
+
+/// Scale a glyph along the axis as a last resort when no font can stretch it
+/// far enough.
+fn scale_glyph(
+    ctx: &mut MathContext,
+    base: GlyphFragment,
+    factor: f64,
+    axis: Axis,
+) -> VariantFragment {
+    ctx.engine.sink.warn(warning!(
+        base.span,
+        "glyph `{}` was scaled because the font {} cannot stretch it far enough",
+        base.c,
+        base.font.info().family;
+        hint: "it may appear distorted";
+        hint: "try a math font that provides larger variants for it"
+    ));
+
+    let (sx, sy) = match axis {
+        Axis::X => (factor, 1.0),
+        Axis::Y => (1.0, factor),
+    };
+
+    let mut variant = base.into_variant();
+    let mut inner = std::mem::take(&mut variant.frame);
+    let size = Size::new(inner.width() * sx, inner.height() * sy);
+    let baseline = inner.baseline() * sy;
+    inner.transform(Transform::scale(Ratio::new(sx), Ratio::new(sy)));
+
+    let mut frame = Frame::soft(size);
+    frame.set_baseline(baseline);
+    frame.push_frame(Point::zero(), inner);
+
+    variant.frame = frame;
+    variant.italics_correction *= sx;
+    variant.accent_attach *= sx;
+    variant
 }
 
 /// Assemble a glyph from parts.
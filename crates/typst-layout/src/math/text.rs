@@ -1,13 +1,14 @@
 use std::f64::consts::SQRT_2;
 
 use ecow::{eco_vec, EcoString};
-use typst_library::diag::SourceResult;
+use typst_library::diag::{warning, SourceResult};
 use typst_library::foundations::{Packed, StyleChain, StyleVec};
 use typst_library::layout::{Abs, Size};
 use typst_library::math::{EquationElem, MathSize, MathVariant};
 use typst_library::text::{
-    BottomEdge, BottomEdgeMetric, TextElem, TopEdge, TopEdgeMetric,
+    variant, BottomEdge, BottomEdgeMetric, FontList, TextElem, TopEdge, TopEdgeMetric,
 };
+use typst_library::World;
 use typst_syntax::{is_newline, Span};
 use unicode_math_class::MathClass;
 use unicode_segmentation::UnicodeSegmentation;
@@ -29,10 +30,10 @@ pub fn layout_text(
         .next()
         .filter(|_| chars.next().is_none())
         .map(|c| dtls_char(c, &mut dtls))
-        .map(|c| styled_char(styles, c, true))
-        .and_then(|c| GlyphFragment::try_new(ctx, styles, c, span))
+        .and_then(|c| single_glyph(ctx, styles, c, span))
     {
-        // A single letter that is available in the math font.
+        // A single letter that is available in the math font, one of the
+        // alphabet fonts, or the symbol fallbacks.
         if dtls {
             glyph.make_dotless_form(ctx);
         }
@@ -71,14 +72,17 @@ pub fn layout_text(
         let frame = MathRun::new(fragments).into_frame(styles);
         FrameFragment::new(styles, frame).with_text_like(true).into()
     } else {
-        let local = [
-            TextElem::set_top_edge(TopEdge::Metric(TopEdgeMetric::Bounds)),
-            TextElem::set_bottom_edge(BottomEdge::Metric(BottomEdgeMetric::Bounds)),
-        ]
-        .map(|p| p.wrap());
+        let mut local = vec![
+            TextElem::set_top_edge(TopEdge::Metric(TopEdgeMetric::Bounds)).wrap(),
+            TextElem::set_bottom_edge(BottomEdge::Metric(BottomEdgeMetric::Bounds))
+                .wrap(),
+        ];
+        if let Some(font) = EquationElem::text_font_in(styles) {
+            local.push(TextElem::set_font(font).wrap());
+        }
 
         // Anything else is handled by Typst's standard text layout.
-        let styles = styles.chain(&local);
+        let styles = styles.chain(local.as_slice());
         let text: EcoString =
             text.chars().map(|c| styled_char(styles, c, false)).collect();
         if text.contains(is_newline) {
@@ -104,6 +108,90 @@ pub fn layout_text(
     Ok(())
 }
 
+/// Create a glyph for a single character.
+///
+/// Letters are taken from the font for their alphabet if there is one.
+/// Everything else comes from the math font, or from the symbol fallbacks if
+/// the math font does not have it.
+fn single_glyph(
+    ctx: &mut MathContext,
+    styles: StyleChain,
+    c: char,
+    span: Span,
+) -> Option<GlyphFragment> {
+    let styled = styled_char(styles, c, true);
+    alphabet_glyph(ctx, styles, c, styled, span)
+        .or_else(|| GlyphFragment::try_new(ctx, styles, styled, span))
+        .or_else(|| {
+            let fallback = EquationElem::symbol_fallback_in(styles);
+            glyph_from_fonts(ctx, styles, &fallback, &[styled], span)
+        })
+}
+
+/// Try to take a letter from the font that is configured for its alphabet,
+/// like the [`cal_font`](EquationElem::cal_font) for calligraphic letters.
+///
+/// The styled letter is preferred, but many text fonts only have the plain
+/// one. That one is used then, with a warning since it is not styled.
+fn alphabet_glyph(
+    ctx: &mut MathContext,
+    styles: StyleChain,
+    c: char,
+    styled: char,
+    span: Span,
+) -> Option<GlyphFragment> {
+    if !c.is_alphanumeric() {
+        return None;
+    }
+
+    let (fonts, alphabet) = match EquationElem::variant_in(styles) {
+        MathVariant::Cal => (EquationElem::cal_font_in(styles), "calligraphic"),
+        MathVariant::Frak => (EquationElem::frak_font_in(styles), "fraktur"),
+        MathVariant::Bb => (EquationElem::bb_font_in(styles), "blackboard bold"),
+        MathVariant::Sans => (EquationElem::sans_font_in(styles), "sans-serif"),
+        MathVariant::Serif | MathVariant::Mono => (None, ""),
+    };
+    let fonts = fonts?;
+
+    if let Some(glyph) = glyph_from_fonts(ctx, styles, &fonts, &[styled], span) {
+        return Some(glyph);
+    }
+
+    let glyph = glyph_from_fonts(ctx, styles, &fonts, &[c], span)?;
+    if styled != c {
+        ctx.engine.sink.warn(warning!(
+            span,
+            "font {} has no {alphabet} letters",
+            glyph.font.info().family;
+            hint: "the plain letter is used instead"
+        ));
+    }
+
+    Some(glyph)
+}
+
+/// Take the first of the given characters that is available in one of the
+/// fonts, trying the fonts in order.
+fn glyph_from_fonts(
+    ctx: &MathContext,
+    styles: StyleChain,
+    fonts: &FontList,
+    chars: &[char],
+    span: Span,
+) -> Option<GlyphFragment> {
+    let world = ctx.engine.world;
+    let variant = variant(styles);
+    fonts.into_iter().find_map(|family| {
+        let font = world
+            .book()
+            .select_variants(family.as_str(), variant)
+            .find_map(|id| world.font(id))?;
+        chars
+            .iter()
+            .find_map(|&c| GlyphFragment::try_new_in(ctx, styles, c, &font, span))
+    })
+}
+
 /// Layout the given text string into a [`FrameFragment`].
 fn layout_complex_text(
     text: &str,
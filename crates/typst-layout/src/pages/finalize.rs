@@ -1,7 +1,8 @@
 use typst_library::diag::SourceResult;
 use typst_library::engine::Engine;
 use typst_library::introspection::{ManualPageCounter, Tag};
-use typst_library::layout::{Frame, FrameItem, Page, Point};
+use typst_library::layout::{Frame, FrameItem, FrameRole, Page, Point};
+use typst_library::{Feature, World};
 
 use super::LayoutedPage;
 
@@ -14,7 +15,9 @@ pub fn finalize(
     tags: &mut Vec<Tag>,
     LayoutedPage {
         inner,
+        rotated,
         mut margin,
+        bleed,
         binding,
         two_sided,
         header,
@@ -33,6 +36,15 @@ pub fn finalize(
         std::mem::swap(&mut margin.left, &mut margin.right);
     }
 
+    // Mark the marginals as artifacts.
+    let mut marginals = [header, footer, background, foreground];
+    if engine.world.library().features.is_enabled(Feature::A11yExtras) {
+        for frame in marginals.iter_mut().flatten().filter(|f| !f.is_empty()) {
+            frame.set_role(FrameRole::Artifact);
+        }
+    }
+    let [header, footer, background, foreground] = marginals;
+
     // Create a frame for the full page.
     let mut frame = Frame::hard(inner.size() + margin.sum_by_axis());
 
@@ -44,8 +56,11 @@ pub fn finalize(
     // Add the "before" marginals. The order in which we push things here is
     // important as it affects the relative ordering of introspectable elements
     // and thus how counters resolve.
+    // The background and foreground cover the bleed, so they start outside
+    // of the page.
+    let origin = Point::new(-bleed.left, -bleed.top);
     if let Some(background) = background {
-        frame.push_frame(Point::zero(), background);
+        frame.push_frame(origin, background);
     }
     if let Some(header) = header {
         frame.push_frame(Point::with_x(margin.left), header);
@@ -60,7 +75,7 @@ pub fn finalize(
         frame.push_frame(Point::new(margin.left, y), footer);
     }
     if let Some(foreground) = foreground {
-        frame.push_frame(Point::zero(), foreground);
+        frame.push_frame(origin, foreground);
     }
 
     // Apply counter updates from within the page to the manual page counter.
@@ -70,5 +85,13 @@ pub fn finalize(
     let number = counter.logical();
     counter.step();
 
-    Ok(Page { frame, fill, numbering, supplement, number })
+    Ok(Page {
+        frame,
+        fill,
+        numbering,
+        supplement,
+        number,
+        bleed,
+        rotated,
+    })
 }
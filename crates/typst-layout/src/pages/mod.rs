@@ -4,21 +4,24 @@ mod collect;
 mod finalize;
 mod run;
 
+use std::num::NonZeroUsize;
+
 use comemo::{Tracked, TrackedMut};
-use typst_library::diag::SourceResult;
-use typst_library::engine::{Engine, Route, Sink, Traced};
-use typst_library::foundations::{Content, StyleChain};
+use typst_library::diag::{PanicScope, SourceResult};
+use typst_library::engine::{record_memoized, Engine, Memoized, Route, Sink, Traced};
+use typst_library::foundations::{Content, NativeElement, StyleChain};
+use typst_library::introspection::metrics::MetricsPageBreak;
 use typst_library::introspection::{
-    Introspector, Locator, ManualPageCounter, SplitLocator, TagElem,
+    Introspector, Locator, ManualPageCounter, SplitLocator, Tag, TagElem,
 };
 use typst_library::layout::{FrameItem, Page, PagedDocument, Point};
 use typst_library::model::DocumentInfo;
 use typst_library::routines::{Arenas, Pair, RealizationKind, Routines};
-use typst_library::World;
+use typst_library::{Feature, World};
 
 use self::collect::{collect, Item};
 use self::finalize::finalize;
-use self::run::{layout_blank_page, layout_page_run, LayoutedPage};
+use self::run::{layout_blank_page, layout_marginals, layout_page_run, LayoutedPage};
 
 /// Layout content into a document.
 ///
@@ -26,13 +29,38 @@ use self::run::{layout_blank_page, layout_page_run, LayoutedPage};
 /// elements. In contrast to [`layout_fragment`](crate::layout_fragment),
 /// this does not take regions since the regions are defined by the page
 /// configuration in the content and style chain.
+///
+/// If `max_pages` is given, layout may stop once that many pages are complete,
+/// producing a document with only these pages. This only happens if nothing
+/// on these pages performed introspection that may depend on later parts of
+/// the document. Otherwise, the full document is laid out.
+///
+/// If a `previous` version of the document is given, its introspector is
+/// updated with the pages that changed instead of searching all pages for
+/// introspectable elements again.
 #[typst_macros::time(name = "layout document")]
 pub fn layout_document(
     engine: &mut Engine,
     content: &Content,
     styles: StyleChain,
+    max_pages: Option<NonZeroUsize>,
+    previous: Option<&PagedDocument>,
 ) -> SourceResult<PagedDocument> {
-    layout_document_impl(
+    let Some(previous) = previous else {
+        return layout_document_impl(
+            engine.routines,
+            engine.world,
+            engine.introspector,
+            engine.traced,
+            TrackedMut::reborrow_mut(&mut engine.sink),
+            engine.route.track(),
+            content,
+            styles,
+            max_pages,
+        );
+    };
+
+    let (pages, info) = layout_document_pages(
         engine.routines,
         engine.world,
         engine.introspector,
@@ -41,7 +69,12 @@ pub fn layout_document(
         engine.route.track(),
         content,
         styles,
-    )
+        max_pages,
+    )?;
+
+    let changed = previous.changed_pages(&pages);
+    let introspector = Introspector::update(&previous.introspector, &pages, &changed);
+    Ok(PagedDocument { pages, info, introspector })
 }
 
 /// The internal implementation of `layout_document`.
@@ -56,7 +89,39 @@ fn layout_document_impl(
     route: Tracked<Route>,
     content: &Content,
     styles: StyleChain,
+    max_pages: Option<NonZeroUsize>,
 ) -> SourceResult<PagedDocument> {
+    let (pages, info) = layout_document_pages(
+        routines,
+        world,
+        introspector,
+        traced,
+        sink,
+        route,
+        content,
+        styles,
+        max_pages,
+    )?;
+    let introspector = Introspector::paged(&pages);
+    Ok(PagedDocument { pages, info, introspector })
+}
+
+/// Lays out the document's pages without building an introspector for them.
+#[comemo::memoize]
+#[allow(clippy::too_many_arguments)]
+fn layout_document_pages(
+    routines: &Routines,
+    world: Tracked<dyn World + '_>,
+    introspector: Tracked<Introspector>,
+    traced: Tracked<Traced>,
+    sink: TrackedMut<Sink>,
+    route: Tracked<Route>,
+    content: &Content,
+    styles: StyleChain,
+    max_pages: Option<NonZeroUsize>,
+) -> SourceResult<(Vec<Page>, DocumentInfo)> {
+    record_memoized(Memoized::Layout);
+    let _scope = PanicScope::enter(content.span(), "document");
     let mut locator = Locator::root().split();
     let mut engine = Engine {
         routines,
@@ -74,19 +139,23 @@ fn layout_document_impl(
 
     let arenas = Arenas::default();
     let mut info = DocumentInfo::default();
-    let mut children = (engine.routines.realize)(
-        RealizationKind::LayoutDocument(&mut info),
-        &mut engine,
-        &mut locator,
-        &arenas,
-        content,
-        styles,
-    )?;
-
-    let pages = layout_pages(&mut engine, &mut children, locator, styles)?;
-    let introspector = Introspector::paged(&pages);
+    let (children, lookahead) = engine.observe_lookahead(|engine| {
+        (engine.routines.realize)(
+            RealizationKind::LayoutDocument(&mut info),
+            engine,
+            &mut locator,
+            &arenas,
+            content,
+            styles,
+        )
+    });
+    let mut children = children?;
 
-    Ok(PagedDocument { pages, info, introspector })
+    // If realization already looked ahead, e.g. in a show rule that reads the
+    // final page count, the full document must be laid out.
+    let max_pages = max_pages.filter(|_| !lookahead);
+    let pages = layout_pages(&mut engine, &mut children, locator, styles, max_pages)?;
+    Ok((pages, info))
 }
 
 /// Layouts the document's pages.
@@ -95,14 +164,17 @@ fn layout_pages<'a>(
     children: &'a mut [Pair<'a>],
     locator: SplitLocator<'a>,
     styles: StyleChain<'a>,
+    max_pages: Option<NonZeroUsize>,
 ) -> SourceResult<Vec<Page>> {
     // Slice up the children into logical parts.
     let items = collect(children, locator, styles);
 
-    // Layout the page runs in parallel.
+    // Layout the page runs in parallel. If only the first few pages are
+    // requested, we instead layout the runs one after another further below so
+    // that we can stop as early as possible.
     let mut runs = engine.parallelize(
         items.iter().filter_map(|item| match item {
-            Item::Run(children, initial, locator) => {
+            Item::Run(children, initial, locator) if max_pages.is_none() => {
                 Some((children, initial, locator.relayout()))
             }
             _ => None,
@@ -115,13 +187,56 @@ fn layout_pages<'a>(
     let mut pages = vec![];
     let mut tags = vec![];
     let mut counter = ManualPageCounter::new();
+    let mut lookahead = false;
 
     // Collect and finalize the runs, handling things like page parity and tags
     // between pages.
     for item in &items {
+        // Stop if we have enough pages and nothing on them may depend on the
+        // pages that would follow.
+        if max_pages.is_some_and(|max| !lookahead && pages.len() >= max.get()) {
+            break;
+        }
+
         match item {
-            Item::Run(..) => {
-                let layouted = runs.next().unwrap()?;
+            Item::Run(children, initial, locator) => {
+                // The marginals are laid out now that the physical number of
+                // the run's first page is known.
+                let first = counter.physical();
+                let layouted = if max_pages.is_some() {
+                    let (layouted, looked_ahead) =
+                        engine.observe_lookahead(|engine| -> SourceResult<_> {
+                            let mut layouted = layout_page_run(
+                                engine,
+                                children,
+                                locator.relayout(),
+                                *initial,
+                            )?;
+                            layout_marginals(
+                                engine,
+                                children,
+                                locator.relayout(),
+                                *initial,
+                                &mut layouted,
+                                first,
+                            )?;
+                            Ok(layouted)
+                        });
+                    lookahead |= looked_ahead;
+                    layouted?
+                } else {
+                    let mut layouted = runs.next().unwrap()?;
+                    layout_marginals(
+                        engine,
+                        children,
+                        locator.relayout(),
+                        *initial,
+                        &mut layouted,
+                        first,
+                    )?;
+                    layouted
+                };
+
                 for layouted in layouted {
                     let page = finalize(engine, &mut counter, &mut tags, layouted)?;
                     pages.push(page);
@@ -132,7 +247,12 @@ fn layout_pages<'a>(
                     continue;
                 }
 
-                let layouted = layout_blank_page(engine, locator.relayout(), *initial)?;
+                let layouted = layout_blank_page(
+                    engine,
+                    locator.relayout(),
+                    *initial,
+                    counter.physical(),
+                )?;
                 let page = finalize(engine, &mut counter, &mut tags, layouted)?;
                 pages.push(page);
             }
@@ -147,6 +267,11 @@ fn layout_pages<'a>(
         }
     }
 
+    // The last run may have produced more pages than requested.
+    if let Some(max) = max_pages.filter(|_| !lookahead) {
+        pages.truncate(max.get());
+    }
+
     // Add the remaining tags to the very end of the last page.
     if !tags.is_empty() {
         let last = pages.last_mut().unwrap();
@@ -155,5 +280,25 @@ fn layout_pages<'a>(
             .push_multiple(tags.into_iter().map(|tag| (pos, FrameItem::Tag(tag))));
     }
 
+    if engine.world.library().features.is_enabled(Feature::LayoutMetrics) {
+        mark_page_breaks(engine, &mut pages);
+    }
+
     Ok(pages)
 }
+
+/// Adds an element to the top of each page but the first, so that page breaks
+/// can be queried after layout.
+fn mark_page_breaks(engine: &mut Engine, pages: &mut [Page]) {
+    let mut locator = Locator::root().split();
+    for page in pages.iter_mut().skip(1) {
+        let mut elem = MetricsPageBreak::new().pack();
+        let key = typst_utils::hash128(&elem);
+        let loc = locator.next_location(engine.introspector, key);
+        elem.set_location(loc);
+        page.frame.prepend_multiple([
+            (Point::zero(), FrameItem::Tag(Tag::Start(elem))),
+            (Point::zero(), FrameItem::Tag(Tag::End(loc, key))),
+        ]);
+    }
+}
@@ -1,18 +1,22 @@
+use std::num::NonZeroUsize;
+
 use comemo::{Track, Tracked, TrackedMut};
 use typst_library::diag::SourceResult;
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{record_memoized, Engine, Memoized, Route, Sink, Traced};
 use typst_library::foundations::{
-    Content, NativeElement, Resolve, Smart, StyleChain, Styles,
+    dict, Array, Content, Dict, IntoValue, NativeElement, Resolve, Smart, StyleChain,
+    Styles, Value,
 };
 use typst_library::introspection::{
-    Counter, CounterDisplayElem, CounterKey, Introspector, Locator, LocatorLink, TagElem,
+    Counter, CounterDisplayElem, CounterKey, Introspector, Locator, LocatorLink, Tag,
+    TagElem,
 };
 use typst_library::layout::{
-    Abs, AlignElem, Alignment, Axes, Binding, ColumnsElem, Dir, Frame, HAlignment,
-    Length, OuterVAlignment, PageElem, Paper, Region, Regions, Rel, Sides, Size,
-    VAlignment,
+    Abs, AlignElem, Alignment, Angle, Axes, Binding, ColumnsElem, Dir, Frame, FrameItem,
+    HAlignment, Length, OuterVAlignment, PageElem, PageOrientation, Paper, Region,
+    Regions, Rel, Sides, Size, Transform, VAlignment,
 };
-use typst_library::model::Numbering;
+use typst_library::model::{HeadingElem, Numbering};
 use typst_library::routines::{Pair, Routines};
 use typst_library::text::{LocalName, TextElem};
 use typst_library::visualize::Paint;
@@ -22,12 +26,14 @@ use typst_utils::Numeric;
 use crate::flow::layout_flow;
 
 /// A mostly finished layout for one page. Needs only knowledge of its exact
-/// page number to be finalized into a `Page`. (Because the margins can depend
-/// on the page number.)
+/// page number to be finalized into a `Page`. (Because the margins and
+/// marginals can depend on the page number.)
 #[derive(Clone)]
 pub struct LayoutedPage {
     pub inner: Frame,
+    pub rotated: bool,
     pub margin: Sides<Abs>,
+    pub bleed: Sides<Abs>,
     pub binding: Binding,
     pub two_sided: bool,
     pub header: Option<Frame>,
@@ -44,12 +50,17 @@ pub fn layout_blank_page(
     engine: &mut Engine,
     locator: Locator,
     initial: StyleChain,
+    number: NonZeroUsize,
 ) -> SourceResult<LayoutedPage> {
-    let layouted = layout_page_run(engine, &[], locator, initial)?;
+    let mut layouted = layout_page_run(engine, &[], locator.relayout(), initial)?;
+    layout_marginals(engine, &[], locator, initial, &mut layouted, number)?;
     Ok(layouted.into_iter().next().unwrap())
 }
 
 /// Layout a page run with uniform properties.
+///
+/// The marginals of the resulting pages are laid out separately by
+/// [`layout_marginals`] once the physical page numbers are known.
 #[typst_macros::time(name = "page run")]
 pub fn layout_page_run(
     engine: &mut Engine,
@@ -84,6 +95,7 @@ fn layout_page_run_impl(
     locator: Tracked<Locator>,
     initial: StyleChain,
 ) -> SourceResult<Vec<LayoutedPage>> {
+    record_memoized(Memoized::Layout);
     let link = LocatorLink::new(locator);
     let mut locator = Locator::link(&link).split();
     let mut engine = Engine {
@@ -100,11 +112,19 @@ fn layout_page_run_impl(
     let styles = StyleChain::new(&styles);
 
     // When one of the lengths is infinite the page fits its content along
-    // that axis.
-    let width = PageElem::width_in(styles).unwrap_or(Abs::inf());
-    let height = PageElem::height_in(styles).unwrap_or(Abs::inf());
+    // that axis. Negative lengths are treated like zero, so that exporters
+    // never see pages of negative size.
+    let width = PageElem::width_in(styles).unwrap_or(Abs::inf()).max(Abs::zero());
+    let height = PageElem::height_in(styles).unwrap_or(Abs::inf()).max(Abs::zero());
     let mut size = Size::new(width, height);
-    if PageElem::flipped_in(styles) {
+
+    // A flipped page with rotated content keeps its size and only its body is
+    // laid out in landscape orientation. This requires a fixed page size.
+    let flipped = PageElem::flipped_in(styles);
+    let rotated = flipped
+        && PageElem::orientation_in(styles) == PageOrientation::RotateContent
+        && size.is_finite();
+    if flipped && !rotated {
         std::mem::swap(&mut size.x, &mut size.y);
     }
 
@@ -123,25 +143,85 @@ fn layout_page_run_impl(
         .resolve(styles)
         .relative_to(size);
 
+    let bleed = PageElem::bleed_in(styles).unwrap_or_default();
     let fill = PageElem::fill_in(styles);
-    let foreground = PageElem::foreground_in(styles);
-    let background = PageElem::background_in(styles);
-    let header_ascent = PageElem::header_ascent_in(styles).relative_to(margin.top);
-    let footer_descent = PageElem::footer_descent_in(styles).relative_to(margin.bottom);
     let numbering = PageElem::numbering_in(styles);
     let supplement = match PageElem::supplement_in(styles) {
         Smart::Auto => TextElem::packed(PageElem::local_name_in(styles)),
         Smart::Custom(content) => content.unwrap_or_default(),
     };
-    let number_align = PageElem::number_align_in(styles);
     let binding =
         PageElem::binding_in(styles).unwrap_or_else(|| match TextElem::dir_in(styles) {
             Dir::LTR => Binding::Left,
             _ => Binding::Right,
         });
 
+    // Layout the children.
+    let area = size - margin.sum_by_axis();
+    let body = if rotated { Size::new(area.y, area.x) } else { area };
+    let fragment = layout_flow(
+        &mut engine,
+        children,
+        &mut locator,
+        styles,
+        Regions::repeat(body, body.map(Abs::is_finite)),
+        PageElem::columns_in(styles),
+        ColumnsElem::gutter_in(styles),
+        true,
+    )?;
+
+    let mut layouted = Vec::with_capacity(fragment.len());
+    for mut inner in fragment {
+        if rotated {
+            rotate_body(&mut inner);
+        }
+
+        layouted.push(LayoutedPage {
+            inner,
+            rotated,
+            fill: fill.clone(),
+            numbering: numbering.clone(),
+            supplement: supplement.clone(),
+            header: None,
+            footer: None,
+            background: None,
+            foreground: None,
+            margin,
+            bleed,
+            binding,
+            two_sided,
+        });
+    }
+
+    Ok(layouted)
+}
+
+/// Layout the header, footer, background, and foreground of the pages of a
+/// run, given the physical number of the run's first page.
+///
+/// This happens after the parallel layout of the runs because the marginals
+/// know about the page number through `page.context`.
+pub fn layout_marginals(
+    engine: &mut Engine,
+    children: &[Pair],
+    locator: Locator,
+    initial: StyleChain,
+    pages: &mut [LayoutedPage],
+    first: NonZeroUsize,
+) -> SourceResult<()> {
+    // The body of the run was laid out with a split of the same locator, so
+    // the marginals get their own sublocator.
+    let mut locator = locator.split().next(&"marginals").split();
+
+    let styles = determine_page_styles(children, initial);
+    let styles = StyleChain::new(&styles);
+
+    let foreground = PageElem::foreground_in(styles);
+    let background = PageElem::background_in(styles);
+    let number_align = PageElem::number_align_in(styles);
+
     // Construct the numbering (for header or footer).
-    let numbering_marginal = numbering.as_ref().map(|numbering| {
+    let numbering_marginal = PageElem::numbering_in(styles).as_ref().map(|numbering| {
         let both = match numbering {
             Numbering::Pattern(pattern) => pattern.pieces() >= 2,
             Numbering::Func(_) => true,
@@ -171,25 +251,15 @@ fn layout_page_run_impl(
         (header.as_ref().unwrap_or(&None), footer.as_ref().unwrap_or(&numbering_marginal))
     };
 
-    // Layout the children.
-    let area = size - margin.sum_by_axis();
-    let fragment = layout_flow(
-        &mut engine,
-        children,
-        &mut locator,
-        styles,
-        Regions::repeat(area, area.map(Abs::is_finite)),
-        PageElem::columns_in(styles),
-        ColumnsElem::gutter_in(styles),
-        true,
-    )?;
-
     // Layouts a single marginal.
-    let mut layout_marginal = |content: &Option<Content>, area, align| {
+    let mut layout_marginal = |content: &Option<Content>, area, align, info: &Dict| {
         let Some(content) = content else { return Ok(None) };
-        let aligned = content.clone().styled(AlignElem::set_alignment(align));
+        let aligned = content
+            .clone()
+            .styled(AlignElem::set_alignment(align))
+            .styled(PageElem::set_marginal(Some(info.clone())));
         crate::layout_frame(
-            &mut engine,
+            engine,
             &aligned,
             locator.next(&content.span()),
             styles,
@@ -198,29 +268,61 @@ fn layout_page_run_impl(
         .map(Some)
     };
 
-    // Layout marginals.
-    let mut layouted = Vec::with_capacity(fragment.len());
-    for inner in fragment {
-        let header_size = Size::new(inner.width(), margin.top - header_ascent);
-        let footer_size = Size::new(inner.width(), margin.bottom - footer_descent);
-        let full_size = inner.size() + margin.sum_by_axis();
+    for (i, page) in pages.iter_mut().enumerate() {
+        let margin = page.margin;
+        let header_ascent = PageElem::header_ascent_in(styles).relative_to(margin.top);
+        let footer_descent =
+            PageElem::footer_descent_in(styles).relative_to(margin.bottom);
+        let header_size = Size::new(page.inner.width(), margin.top - header_ascent);
+        let footer_size = Size::new(page.inner.width(), margin.bottom - footer_descent);
+        let full_size =
+            page.inner.size() + margin.sum_by_axis() + page.bleed.sum_by_axis();
         let mid = HAlignment::Center + VAlignment::Horizon;
-        layouted.push(LayoutedPage {
-            inner,
-            fill: fill.clone(),
-            numbering: numbering.clone(),
-            supplement: supplement.clone(),
-            header: layout_marginal(header, header_size, Alignment::BOTTOM)?,
-            footer: layout_marginal(footer, footer_size, Alignment::TOP)?,
-            background: layout_marginal(background, full_size, mid)?,
-            foreground: layout_marginal(foreground, full_size, mid)?,
-            margin,
-            binding,
-            two_sided,
-        });
+        let info = page_info(&page.inner, first.saturating_add(i));
+        page.header = layout_marginal(header, header_size, Alignment::BOTTOM, &info)?;
+        page.footer = layout_marginal(footer, footer_size, Alignment::TOP, &info)?;
+        page.background = layout_marginal(background, full_size, mid, &info)?;
+        page.foreground = layout_marginal(foreground, full_size, mid, &info)?;
     }
 
-    Ok(layouted)
+    Ok(())
+}
+
+/// Rotates the landscape body of a page counterclockwise by 90 degrees, so that
+/// it fills the portrait page area.
+fn rotate_body(frame: &mut Frame) {
+    let width = frame.width();
+    frame.transform(
+        Transform::translate(Abs::zero(), width)
+            .pre_concat(Transform::rotate(Angle::deg(-90.0))),
+    );
+    frame.set_size(Size::new(frame.height(), width));
+}
+
+/// Collects the information about a page that is available to its marginals
+/// through `page.context`.
+fn page_info(frame: &Frame, number: NonZeroUsize) -> Dict {
+    let mut headings = Array::new();
+    collect_headings(frame, &mut headings);
+    dict! {
+        "number" => number.get(),
+        "first" => headings.first().ok().unwrap_or(Value::None),
+        "last" => headings.last().ok().unwrap_or(Value::None),
+        "headings" => headings,
+    }
+}
+
+/// Collects the headings that start in a frame, in layout order.
+fn collect_headings(frame: &Frame, headings: &mut Array) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => collect_headings(&group.frame, headings),
+            FrameItem::Tag(Tag::Start(elem)) if elem.is::<HeadingElem>() => {
+                headings.push(elem.clone().into_value());
+            }
+            _ => {}
+        }
+    }
 }
 
 /// Determines the styles used for a page run itself and page-level content like
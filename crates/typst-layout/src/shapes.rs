@@ -1,18 +1,18 @@
 use std::f64::consts::SQRT_2;
 
-use kurbo::{CubicBez, ParamCurveExtrema};
+use kurbo::{CubicBez, ParamCurve, ParamCurveExtrema};
 use typst_library::diag::{bail, SourceResult};
 use typst_library::engine::Engine;
 use typst_library::foundations::{Content, Packed, Resolve, Smart, StyleChain};
 use typst_library::introspection::Locator;
 use typst_library::layout::{
-    Abs, Axes, Corner, Corners, Frame, FrameItem, Length, Point, Ratio, Region, Rel,
-    Sides, Size,
+    Abs, Angle, Axes, Corner, Corners, Frame, FrameItem, Length, Point, Ratio, Region,
+    Rel, Sides, Size, Transform,
 };
 use typst_library::visualize::{
-    CircleElem, CloseMode, Curve, CurveComponent, CurveElem, EllipseElem, FillRule,
-    FixedStroke, Geometry, LineElem, Paint, PathElem, PathVertex, PolygonElem, RectElem,
-    Shape, SquareElem, Stroke,
+    CircleElem, CloseMode, Curve, CurveComponent, CurveElem, CurveItem, EllipseElem,
+    FillRule, FixedStroke, Geometry, LineCap, LineElem, Marker, MarkerShape, Paint,
+    PathElem, PathVertex, PolygonElem, RectElem, Shape, SquareElem, Stroke,
 };
 use typst_syntax::Span;
 use typst_utils::{Get, Numeric};
@@ -21,8 +21,8 @@ use typst_utils::{Get, Numeric};
 #[typst_macros::time(span = elem.span())]
 pub fn layout_line(
     elem: &Packed<LineElem>,
-    _: &mut Engine,
-    _: Locator,
+    engine: &mut Engine,
+    locator: Locator,
     styles: StyleChain,
     region: Region,
 ) -> SourceResult<Frame> {
@@ -44,8 +44,35 @@ pub fn layout_line(
     }
 
     let mut frame = Frame::soft(size);
-    let shape = Geometry::Line(delta.to_point()).stroked(stroke);
-    frame.push(start.to_point(), FrameItem::Shape(shape, elem.span()));
+    let markers = [elem.marker_start(styles), elem.marker_end(styles)];
+    if markers.iter().all(Option::is_none) {
+        let shape = Geometry::Line(delta.to_point()).stroked(stroke);
+        frame.push(start.to_point(), FrameItem::Shape(shape, elem.span()));
+        return Ok(frame);
+    }
+
+    // Go through a curve so that lines and curves share the marker logic.
+    let mut curve = Curve::new();
+    curve.move_(start.to_point());
+    curve.line((start + delta).to_point());
+    let marks = layout_markers(
+        engine,
+        locator,
+        styles,
+        &mut curve,
+        &stroke,
+        markers,
+        elem.span(),
+    )?;
+
+    if let [CurveItem::Move(from), CurveItem::Line(to)] = curve.0[..] {
+        if from != to {
+            let shape = Geometry::Line(to - from).stroked(stroke);
+            frame.push(from, FrameItem::Shape(shape, elem.span()));
+        }
+    }
+
+    frame.push_frame(Point::zero(), marks);
     Ok(frame)
 }
 
@@ -53,8 +80,8 @@ pub fn layout_line(
 #[typst_macros::time(span = elem.span())]
 pub fn layout_path(
     elem: &Packed<PathElem>,
-    _: &mut Engine,
-    _: Locator,
+    engine: &mut Engine,
+    locator: Locator,
     styles: StyleChain,
     region: Region,
 ) -> SourceResult<Frame> {
@@ -129,13 +156,19 @@ pub fn layout_path(
     };
 
     let mut frame = Frame::soft(size);
-    let shape = Shape {
-        geometry: Geometry::Curve(curve),
-        stroke,
+    let markers = [elem.marker_start(styles), elem.marker_end(styles)];
+    push_curve(
+        engine,
+        locator,
+        styles,
+        &mut frame,
+        curve,
         fill,
         fill_rule,
-    };
-    frame.push(Point::zero(), FrameItem::Shape(shape, elem.span()));
+        stroke,
+        markers,
+        elem.span(),
+    )?;
     Ok(frame)
 }
 
@@ -143,8 +176,8 @@ pub fn layout_path(
 #[typst_macros::time(span = elem.span())]
 pub fn layout_curve(
     elem: &Packed<CurveElem>,
-    _: &mut Engine,
-    _: Locator,
+    engine: &mut Engine,
+    locator: Locator,
     styles: StyleChain,
     region: Region,
 ) -> SourceResult<Frame> {
@@ -217,13 +250,19 @@ pub fn layout_curve(
     };
 
     let mut frame = Frame::soft(size);
-    let shape = Shape {
-        geometry: Geometry::Curve(curve),
-        stroke,
+    let markers = [elem.marker_start(styles), elem.marker_end(styles)];
+    push_curve(
+        engine,
+        locator,
+        styles,
+        &mut frame,
+        curve,
         fill,
         fill_rule,
-    };
-    frame.push(Point::zero(), FrameItem::Shape(shape, elem.span()));
+        stroke,
+        markers,
+        elem.span(),
+    )?;
     Ok(frame)
 }
 
@@ -389,6 +428,275 @@ fn point_to_kurbo(point: Point) -> kurbo::Point {
     kurbo::Point::new(point.x.to_raw(), point.y.to_raw())
 }
 
+/// Convert a `kurbo::Point` to a point.
+fn point_from_kurbo(point: kurbo::Point) -> Point {
+    Point::new(Abs::raw(point.x), Abs::raw(point.y))
+}
+
+/// The size of a predefined marker relative to the stroke's thickness if no
+/// size is set explicitly.
+const MARKER_SCALE: f64 = 5.0;
+
+/// Push a filled and stroked curve with its markers into the frame.
+#[allow(clippy::too_many_arguments)]
+fn push_curve(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    frame: &mut Frame,
+    curve: Curve,
+    fill: Option<Paint>,
+    fill_rule: FillRule,
+    stroke: Option<FixedStroke>,
+    markers: [Option<Marker>; 2],
+    span: Span,
+) -> SourceResult<()> {
+    let stroke = match stroke {
+        Some(stroke) if markers.iter().any(Option::is_some) => stroke,
+        stroke => {
+            let shape = Shape {
+                geometry: Geometry::Curve(curve),
+                stroke,
+                fill,
+                fill_rule,
+            };
+            frame.push(Point::zero(), FrameItem::Shape(shape, span));
+            return Ok(());
+        }
+    };
+
+    // The markers shorten the stroke, but not the fill, so the two become
+    // separate shapes.
+    if let Some(fill) = fill {
+        let shape = Shape {
+            geometry: Geometry::Curve(curve.clone()),
+            stroke: None,
+            fill: Some(fill),
+            fill_rule,
+        };
+        frame.push(Point::zero(), FrameItem::Shape(shape, span));
+    }
+
+    let mut curve = curve;
+    let marks =
+        layout_markers(engine, locator, styles, &mut curve, &stroke, markers, span)?;
+    let shape = Geometry::Curve(curve).stroked(stroke);
+    frame.push(Point::zero(), FrameItem::Shape(shape, span));
+    frame.push_frame(Point::zero(), marks);
+    Ok(())
+}
+
+/// Lay out the markers at the open ends of a curve and shorten the curve such
+/// that it ends within them.
+///
+/// The start marker is attached to the start of the curve's first component
+/// and the end marker to the end of its last component. Markers are omitted
+/// at the ends of closed components.
+fn layout_markers(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    curve: &mut Curve,
+    stroke: &FixedStroke,
+    [start, end]: [Option<Marker>; 2],
+    span: Span,
+) -> SourceResult<Frame> {
+    let mut locator = locator.split();
+    let mut marks = Frame::soft(Size::zero());
+    let items = &mut curve.0;
+
+    // The first component is open if it isn't closed before the next move.
+    let open = items
+        .iter()
+        .skip(1)
+        .take_while(|item| !matches!(item, CurveItem::Move(_)))
+        .all(|item| !matches!(item, CurveItem::Close));
+
+    if let Some(marker) = start.filter(|_| open) {
+        if let Some(segment) = cubic_segment(items, 1) {
+            let reversed = reverse(segment);
+            let tip = point_from_kurbo(reversed.p3);
+            let angle = end_angle(reversed);
+            let by = layout_marker(
+                engine,
+                locator.next(&()),
+                styles,
+                &mut marks,
+                &marker,
+                stroke,
+                tip,
+                angle,
+                span,
+            )?;
+            let trimmed = reverse(trim_end(reversed, by.to_raw()));
+            items[0] = CurveItem::Move(point_from_kurbo(trimmed.p0));
+            set_cubic_segment(items, 1, trimmed);
+        }
+    }
+
+    if let Some(marker) = end {
+        let last = items.len().saturating_sub(1);
+        if let Some(segment) = cubic_segment(items, last) {
+            let tip = point_from_kurbo(segment.p3);
+            let by = layout_marker(
+                engine,
+                locator.next(&()),
+                styles,
+                &mut marks,
+                &marker,
+                stroke,
+                tip,
+                end_angle(segment),
+                span,
+            )?;
+            set_cubic_segment(items, last, trim_end(segment, by.to_raw()));
+        }
+    }
+
+    Ok(marks)
+}
+
+/// Lay out a marker whose tip lies at the given point and that points in the
+/// direction of the given angle.
+///
+/// Returns by how much the stroke must be shortened so that it ends within
+/// the marker.
+#[allow(clippy::too_many_arguments)]
+fn layout_marker(
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    frame: &mut Frame,
+    marker: &Marker,
+    stroke: &FixedStroke,
+    tip: Point,
+    angle: Angle,
+    span: Span,
+) -> SourceResult<Abs> {
+    let (shape, size) = match marker {
+        Marker::Shape(shape, size) => (*shape, *size),
+        Marker::Content(body) => {
+            let region = Region::new(Size::splat(Abs::inf()), Axes::splat(false));
+            let mut mark =
+                (engine.routines.layout_frame)(engine, body, locator, styles, region)?;
+            let center = mark.size().to_point() / 2.0;
+            mark.transform(
+                Transform::translate(center.x, center.y)
+                    .pre_concat(Transform::rotate(angle))
+                    .pre_concat(Transform::translate(-center.x, -center.y)),
+            );
+            frame.push_frame(tip - center, mark);
+            return Ok(Abs::zero());
+        }
+    };
+
+    let size = size
+        .map(|size| size.resolve(styles))
+        .unwrap_or(MARKER_SCALE * stroke.thickness);
+    let place = |point: Point| point.transform(Transform::rotate(angle)) + tip;
+    let fill = |geometry: Geometry| {
+        FrameItem::Shape(geometry.filled(stroke.paint.clone()), span)
+    };
+
+    Ok(match shape {
+        MarkerShape::Arrow => {
+            let mut curve = Curve::new();
+            curve.move_(tip);
+            curve.line(place(Point::new(-size, 0.4 * size)));
+            curve.line(place(Point::new(-size, -0.4 * size)));
+            curve.close();
+            frame.push(Point::zero(), fill(Geometry::Curve(curve)));
+            size
+        }
+        MarkerShape::Bar => {
+            let from = place(Point::with_y(-size / 2.0));
+            let to = place(Point::with_y(size / 2.0));
+            let bar = FixedStroke { cap: LineCap::Butt, dash: None, ..stroke.clone() };
+            let shape = Geometry::Line(to - from).stroked(bar);
+            frame.push(from, FrameItem::Shape(shape, span));
+            Abs::zero()
+        }
+        MarkerShape::Circle => {
+            let mut curve = Curve::ellipse(Size::splat(size));
+            curve.translate(tip - Point::splat(size / 2.0));
+            frame.push(Point::zero(), fill(Geometry::Curve(curve)));
+            size / 2.0
+        }
+    })
+}
+
+/// The line or cubic segment at the given index of a curve as a cubic Bézier
+/// curve, starting at the point where the previous item ended.
+fn cubic_segment(items: &[CurveItem], index: usize) -> Option<CubicBez> {
+    let from = match items.get(index.checked_sub(1)?)? {
+        CurveItem::Move(p) | CurveItem::Line(p) | CurveItem::Cubic(_, _, p) => *p,
+        CurveItem::Close => return None,
+    };
+    let p0 = point_to_kurbo(from);
+    Some(match items[index] {
+        CurveItem::Line(to) => {
+            let p3 = point_to_kurbo(to);
+            CubicBez::new(p0, p0.lerp(p3, 1.0 / 3.0), p0.lerp(p3, 2.0 / 3.0), p3)
+        }
+        CurveItem::Cubic(c1, c2, to) => {
+            CubicBez::new(p0, point_to_kurbo(c1), point_to_kurbo(c2), point_to_kurbo(to))
+        }
+        _ => return None,
+    })
+}
+
+/// Replace the line or cubic segment at the given index of a curve, keeping
+/// its kind.
+fn set_cubic_segment(items: &mut [CurveItem], index: usize, segment: CubicBez) {
+    let item = &mut items[index];
+    *item = match item {
+        CurveItem::Line(_) => CurveItem::Line(point_from_kurbo(segment.p3)),
+        _ => CurveItem::Cubic(
+            point_from_kurbo(segment.p1),
+            point_from_kurbo(segment.p2),
+            point_from_kurbo(segment.p3),
+        ),
+    };
+}
+
+/// Reverse the direction of a segment.
+fn reverse(segment: CubicBez) -> CubicBez {
+    CubicBez::new(segment.p3, segment.p2, segment.p1, segment.p0)
+}
+
+/// The angle of the direction in which a segment leaves its end point.
+fn end_angle(segment: CubicBez) -> Angle {
+    let dir = [segment.p2, segment.p1, segment.p0]
+        .into_iter()
+        .map(|p| segment.p3 - p)
+        .find(|dir| dir.hypot2() > 0.0)
+        .unwrap_or_default();
+    Angle::rad(dir.atan2())
+}
+
+/// Cut off the end of a segment such that its new end point lies at the given
+/// distance from the old one.
+fn trim_end(segment: CubicBez, by: f64) -> CubicBez {
+    if by <= 0.0 {
+        return segment;
+    } else if segment.p0.distance(segment.p3) <= by {
+        return CubicBez::new(segment.p0, segment.p0, segment.p0, segment.p0);
+    }
+
+    // Find the point at the given distance by bisection.
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..32 {
+        let mid = (lo + hi) / 2.0;
+        if segment.eval(mid).distance(segment.p3) > by {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    segment.subsegment(0.0..lo)
+}
+
 /// Layout the polygon.
 #[typst_macros::time(span = elem.span())]
 pub fn layout_polygon(
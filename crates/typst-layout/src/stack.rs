@@ -6,6 +6,7 @@ use typst_library::layout::{
     Abs, AlignElem, Axes, Axis, Dir, FixedAlignment, Fr, Fragment, Frame, HElem, Point,
     Regions, Size, Spacing, StackChild, StackElem, VElem,
 };
+use typst_library::text::TextElem;
 use typst_syntax::Span;
 use typst_utils::{Get, Numeric};
 
@@ -18,8 +19,8 @@ pub fn layout_stack(
     styles: StyleChain,
     regions: Regions,
 ) -> SourceResult<Fragment> {
-    let mut layouter =
-        StackLayouter::new(elem.span(), elem.dir(styles), locator, styles, regions);
+    let dir = elem.dir(styles).unwrap_or_else(|| TextElem::dir_in(styles));
+    let mut layouter = StackLayouter::new(elem.span(), dir, locator, styles, regions);
 
     let axis = layouter.dir.axis();
 
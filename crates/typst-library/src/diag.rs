@@ -1,7 +1,9 @@
 //! Diagnostics.
 
+use std::cell::RefCell;
 use std::fmt::{self, Display, Formatter};
 use std::io;
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
@@ -11,6 +13,7 @@ use ecow::{eco_vec, EcoVec};
 use typst_syntax::package::{PackageSpec, PackageVersion};
 use typst_syntax::{Span, Spanned, SyntaxError};
 
+use crate::package::PackageHash;
 use crate::{World, WorldExt};
 
 /// Early-return with a [`StrResult`] or [`SourceResult`].
@@ -164,6 +167,25 @@ pub struct SourceDiagnostic {
     /// Additional hints to the user, indicating how this problem could be avoided
     /// or worked around.
     pub hints: EcoVec<EcoString>,
+    /// An identifier for the kind of problem, shared by all diagnostics of
+    /// the kind.
+    ///
+    /// Only a limited number of warnings with the same code is reported, so
+    /// that a problem that occurs in many places does not bury other ones.
+    pub code: Option<&'static str>,
+    /// How often the diagnostic was emitted.
+    ///
+    /// Identical warnings are reported once with the number of times they
+    /// occurred. This includes repetitions due to content that is laid out
+    /// more than once, e.g. because it was measured.
+    pub occurrences: usize,
+    /// Whether the diagnostic is only reported at its first span.
+    ///
+    /// Further warnings with the same code and message anywhere in the
+    /// document only count as occurrences of the first one. This is useful
+    /// for problems with a global cause, like a font, that would otherwise be
+    /// reported for every paragraph.
+    pub once: bool,
 }
 
 /// The severity of a [`SourceDiagnostic`].
@@ -184,6 +206,9 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            code: None,
+            occurrences: 1,
+            once: false,
         }
     }
 
@@ -195,6 +220,9 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            code: None,
+            occurrences: 1,
+            once: false,
         }
     }
 
@@ -214,6 +242,29 @@ impl SourceDiagnostic {
         self.hints.extend(hints);
         self
     }
+
+    /// Sets the code that identifies the kind of problem.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Reports the diagnostic only at its first span, see
+    /// [`once`](Self::once).
+    pub fn reported_once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+
+    /// The message, followed by the number of occurrences if the diagnostic
+    /// was emitted more than once.
+    pub fn message_with_occurrences(&self) -> EcoString {
+        if self.occurrences > 1 {
+            eco_format!("{} (repeated {} times)", self.message, self.occurrences)
+        } else {
+            self.message.clone()
+        }
+    }
 }
 
 impl From<SyntaxError> for SourceDiagnostic {
@@ -224,6 +275,154 @@ impl From<SyntaxError> for SourceDiagnostic {
             message: error.message,
             trace: eco_vec![],
             hints: error.hints,
+            code: None,
+            occurrences: 1,
+            once: false,
+        }
+    }
+}
+
+/// How evaluation deals with errors in markup.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ErrorMode {
+    /// Stop at the first error.
+    #[default]
+    Fatal,
+    /// Continue past recoverable errors to report as many errors as possible.
+    ///
+    /// If an expression embedded in markup fails because of an unknown
+    /// variable or an invalid argument, the error is recorded and the
+    /// expression produces nothing. Evaluation then continues with the rest
+    /// of the markup. Other errors, like syntax errors and failing imports,
+    /// are still fatal because the errors that would follow them are likely
+    /// to be misleading. The same holds for errors in bindings and set and
+    /// show rules as well as in function bodies.
+    ///
+    /// A compilation with recorded errors always fails, but reports all of
+    /// them.
+    Collect,
+}
+
+impl ErrorMode {
+    /// The code of errors about variables that are not defined.
+    pub const UNKNOWN_VARIABLE: &'static str = "unknown-variable";
+
+    /// The code of errors about arguments that a function does not accept,
+    /// e.g. because they have the wrong type.
+    pub const INVALID_ARGUMENT: &'static str = "invalid-argument";
+
+    /// Whether an error may be recorded and skipped in this mode.
+    pub fn is_recoverable(self, error: &SourceDiagnostic) -> bool {
+        self == Self::Collect
+            && error.severity == Severity::Error
+            && matches!(error.code, Some(Self::UNKNOWN_VARIABLE | Self::INVALID_ARGUMENT))
+    }
+}
+
+/// Marks a definition in the standard library as deprecated.
+///
+/// Uses of a deprecated definition are reported with a warning that carries
+/// the [`DEPRECATED`](Self::DEPRECATED) code, or the
+/// [`PAST_DUE`](Self::PAST_DUE) code once the compiler reached the version in
+/// which the definition is supposed to be removed. How the warnings are
+/// reported in the end is determined by the [`DeprecationPolicy`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Deprecation {
+    /// The message to report, explaining what to use instead.
+    message: &'static str,
+    /// The compiler version in which the definition will be removed.
+    until: Option<&'static str>,
+}
+
+impl Deprecation {
+    /// The code of warnings about uses of deprecated definitions.
+    pub const DEPRECATED: &'static str = "deprecated";
+
+    /// The code of warnings about uses of deprecated definitions that should
+    /// already have been removed.
+    pub const PAST_DUE: &'static str = "deprecated-past-due";
+
+    /// Create a new deprecation with a message.
+    pub const fn new(message: &'static str) -> Self {
+        Self { message, until: None }
+    }
+
+    /// Set the compiler version in which the definition will be removed,
+    /// e.g. `"0.15.0"`.
+    pub const fn with_until(mut self, version: &'static str) -> Self {
+        self.until = Some(version);
+        self
+    }
+
+    /// The message to report.
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+
+    /// The compiler version in which the definition will be removed.
+    pub fn until(&self) -> Option<&'static str> {
+        self.until
+    }
+
+    /// Whether the current compiler version is at or past the version in
+    /// which the definition should have been removed.
+    pub fn is_past_due(&self) -> bool {
+        self.until
+            .and_then(|until| until.parse::<PackageVersion>().ok())
+            .is_some_and(|until| PackageVersion::compiler() >= until)
+    }
+
+    /// Create the warning for a use of the deprecated definition.
+    pub fn warning(&self, span: Span) -> SourceDiagnostic {
+        let code = if self.is_past_due() { Self::PAST_DUE } else { Self::DEPRECATED };
+        let mut warning = SourceDiagnostic::warning(span, self.message).with_code(code);
+        if let Some(until) = self.until {
+            warning.hint(eco_format!("it will be removed in Typst {until}"));
+        }
+        warning
+    }
+}
+
+/// How uses of deprecated definitions are reported.
+///
+/// Regardless of the policy, uses of definitions whose removal is past due
+/// are errors, unless this is explicitly allowed.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DeprecationPolicy {
+    /// Warn about all uses.
+    #[default]
+    Warn,
+    /// Turn all uses into errors. Useful in CI to prepare for an upgrade.
+    Error,
+    /// Warn about uses in the project's own files, but not about uses in
+    /// packages, as these cannot be fixed by the user.
+    AllowInPackages,
+}
+
+impl DeprecationPolicy {
+    /// Determines the severity with which a diagnostic is reported under this
+    /// policy, or `None` if it is not reported at all.
+    ///
+    /// Diagnostics other than deprecation warnings keep their severity. If
+    /// `allow_past_due` is `false`, past-due deprecations are errors.
+    pub fn severity(
+        self,
+        diagnostic: &SourceDiagnostic,
+        allow_past_due: bool,
+    ) -> Option<Severity> {
+        let past_due = match diagnostic.code {
+            _ if diagnostic.severity == Severity::Error => return Some(Severity::Error),
+            Some(Deprecation::DEPRECATED) => false,
+            Some(Deprecation::PAST_DUE) => !allow_past_due,
+            _ => return Some(diagnostic.severity),
+        };
+
+        let in_package = diagnostic.span.id().is_some_and(|id| id.package().is_some());
+        match self {
+            _ if past_due => Some(Severity::Error),
+            Self::Error => Some(Severity::Error),
+            Self::AllowInPackages if in_package => None,
+            Self::Warn | Self::AllowInPackages => Some(Severity::Warning),
         }
     }
 }
@@ -237,6 +436,9 @@ pub enum Tracepoint {
     Show(EcoString),
     /// A module import.
     Import,
+    /// Another label that is easily confused with the one that the diagnostic
+    /// is about, given by its representation.
+    SimilarLabel(EcoString),
 }
 
 impl Display for Tracepoint {
@@ -254,6 +456,9 @@ impl Display for Tracepoint {
             Tracepoint::Import => {
                 write!(f, "error occurred while importing this module")
             }
+            Tracepoint::SimilarLabel(label) => {
+                write!(f, "the similar label `{label}` is attached here")
+            }
         }
     }
 }
@@ -291,6 +496,83 @@ impl<T> Trace<T> for SourceResult<T> {
     }
 }
 
+/// Marks a piece of the document as being processed on the current thread.
+///
+/// Layout entry points enter a scope for the content they lay out. If an
+/// internal invariant breaks and the compiler panics, the spans of the active
+/// scopes indicate which part of the document triggered the panic. They are
+/// captured while the panic unwinds through the innermost scope, so no panic
+/// hook is needed. See `typst::take_panic_context` for how they are reported.
+///
+/// Entering and leaving a scope only pushes to and pops from a thread-local
+/// stack, so it is cheap enough to do for every laid out element. Parallel
+/// tasks spawned through [`Engine::parallelize`](crate::engine::Engine::parallelize)
+/// re-enter the scopes of the spawning thread.
+#[must_use = "the scope is left when it is dropped"]
+pub struct PanicScope(PhantomData<*const ()>);
+
+/// The span of a piece of the document being processed and what kind of
+/// content it belongs to, e.g. `"grid cell"`.
+pub type PanicSpan = (Span, &'static str);
+
+thread_local! {
+    /// The active panic scopes, innermost last.
+    static PANIC_SCOPES: RefCell<Vec<PanicSpan>> = const { RefCell::new(Vec::new()) };
+
+    /// The scopes that were active when the current thread last panicked,
+    /// innermost first.
+    static PANIC_CAPTURED: RefCell<Option<Vec<PanicSpan>>> =
+        const { RefCell::new(None) };
+}
+
+impl PanicScope {
+    /// Enter a scope for the given span. The `kind` describes what is being
+    /// processed, e.g. `"grid cell"`.
+    pub fn enter(span: Span, kind: &'static str) -> Self {
+        PANIC_SCOPES.with_borrow_mut(|scopes| scopes.push((span, kind)));
+        Self(PhantomData)
+    }
+
+    /// Enter all the given scopes, outermost first.
+    pub fn enter_all(scopes: &[PanicSpan]) -> Vec<Self> {
+        scopes.iter().map(|&(span, kind)| Self::enter(span, kind)).collect()
+    }
+
+    /// The scopes that are entered on the current thread, outermost first.
+    pub fn current() -> Vec<PanicSpan> {
+        PANIC_SCOPES.with_borrow(Vec::clone)
+    }
+
+    /// Take the scopes that were active when the current thread last
+    /// panicked, innermost first.
+    pub fn take_captured() -> Option<Vec<PanicSpan>> {
+        PANIC_CAPTURED.with_borrow_mut(Option::take)
+    }
+
+    /// Record scopes that were captured on another thread, e.g. on the worker
+    /// of a parallel task, as those of the panic that is propagated to the
+    /// current thread.
+    pub fn set_captured(captured: Vec<PanicSpan>) {
+        PANIC_CAPTURED.with_borrow_mut(|slot| *slot = Some(captured));
+    }
+}
+
+impl Drop for PanicScope {
+    fn drop(&mut self) {
+        PANIC_SCOPES.with_borrow_mut(|scopes| {
+            // The innermost scope is left first while unwinding, so it sees
+            // all scopes that were active when the panic occurred.
+            if std::thread::panicking() {
+                PANIC_CAPTURED.with_borrow_mut(|captured| {
+                    captured
+                        .get_or_insert_with(|| scopes.iter().rev().copied().collect());
+                });
+            }
+            scopes.pop();
+        });
+    }
+}
+
 /// A result type with a string error message.
 pub type StrResult<T> = Result<T, EcoString>;
 
@@ -509,6 +791,10 @@ pub enum PackageError {
     NetworkFailed(Option<EcoString>),
     /// The package archive was malformed.
     MalformedArchive(Option<EcoString>),
+    /// The package is not in the package lock.
+    NotLocked(PackageSpec),
+    /// The package's contents don't match the hash in the package lock.
+    HashMismatch { spec: PackageSpec, expected: PackageHash, found: PackageHash },
     /// Another error.
     Other(Option<EcoString>),
 }
@@ -538,6 +824,16 @@ impl Display for PackageError {
             Self::MalformedArchive(None) => {
                 f.pad("failed to decompress package (archive malformed)")
             }
+            Self::NotLocked(spec) => {
+                write!(f, "package {spec} is not in the package lock")
+            }
+            Self::HashMismatch { spec, expected, found } => {
+                write!(
+                    f,
+                    "package {spec} does not match the package lock \
+                     (expected hash {expected}, found {found})",
+                )
+            }
             Self::Other(Some(err)) => write!(f, "failed to load package ({err})"),
             Self::Other(None) => f.pad("failed to load package"),
         }
@@ -578,3 +874,104 @@ pub fn format_xml_like_error(format: &str, error: roxmltree::Error) -> EcoString
         err => eco_format!("failed to parse {format} ({err})"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use typst_syntax::{FileId, VirtualPath};
+
+    use super::*;
+
+    /// A deprecation warning in a file of a package or of the project.
+    fn warning(deprecation: Deprecation, in_package: bool) -> SourceDiagnostic {
+        let spec = in_package.then(|| "@test/legacy:0.1.0".parse().unwrap());
+        let id = FileId::new(spec, VirtualPath::new("lib.typ"));
+        deprecation.warning(Span::from_range(id, 0..3))
+    }
+
+    #[test]
+    fn test_deprecation_warning() {
+        let deprecated = Deprecation::new("`old` is deprecated");
+        let warning = deprecated.warning(Span::detached());
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(warning.code, Some(Deprecation::DEPRECATED));
+        assert!(warning.hints.is_empty());
+
+        let future = deprecated.with_until("999.0.0");
+        assert!(!future.is_past_due());
+        let warning = future.warning(Span::detached());
+        assert_eq!(warning.code, Some(Deprecation::DEPRECATED));
+        let hints: Vec<_> = warning.hints.iter().map(EcoString::as_str).collect();
+        assert_eq!(hints, ["it will be removed in Typst 999.0.0"]);
+
+        let past_due = deprecated.with_until("0.1.0");
+        assert!(past_due.is_past_due());
+        let warning = past_due.warning(Span::detached());
+        assert_eq!(warning.code, Some(Deprecation::PAST_DUE));
+    }
+
+    #[test]
+    fn test_deprecation_policy() {
+        let deprecated = Deprecation::new("`old` is deprecated");
+        let past_due = deprecated.with_until("0.1.0");
+        let (warn, error) = (Some(Severity::Warning), Some(Severity::Error));
+
+        // The severities of a use in a package and in the project.
+        let severities = |policy: DeprecationPolicy, deprecation, allow_past_due| {
+            [true, false].map(|in_package| {
+                policy.severity(&warning(deprecation, in_package), allow_past_due)
+            })
+        };
+
+        assert_eq!(severities(DeprecationPolicy::Warn, deprecated, false), [warn, warn]);
+        assert_eq!(
+            severities(DeprecationPolicy::Error, deprecated, false),
+            [error, error]
+        );
+        assert_eq!(
+            severities(DeprecationPolicy::AllowInPackages, deprecated, false),
+            [None, warn]
+        );
+
+        // Uses of definitions that are past their removal version are errors
+        // regardless of the policy, unless explicitly allowed.
+        assert_eq!(
+            severities(DeprecationPolicy::AllowInPackages, past_due, false),
+            [error, error]
+        );
+        assert_eq!(
+            severities(DeprecationPolicy::AllowInPackages, past_due, true),
+            [None, warn]
+        );
+
+        // Other diagnostics keep their severity.
+        let other = SourceDiagnostic::warning(Span::detached(), "unrelated");
+        assert_eq!(DeprecationPolicy::Error.severity(&other, false), warn);
+        let other = SourceDiagnostic::error(Span::detached(), "unrelated");
+        assert_eq!(DeprecationPolicy::AllowInPackages.severity(&other, false), error);
+    }
+
+    #[test]
+    fn test_error_mode_recoverable() {
+        let error = |code| {
+            let error = SourceDiagnostic::error(Span::detached(), "unknown variable: x");
+            match code {
+                Some(code) => error.with_code(code),
+                None => error,
+            }
+        };
+
+        // Only errors of recoverable kinds are recorded, regardless of their
+        // message.
+        for code in [ErrorMode::UNKNOWN_VARIABLE, ErrorMode::INVALID_ARGUMENT] {
+            assert!(ErrorMode::Collect.is_recoverable(&error(Some(code))));
+            assert!(!ErrorMode::Fatal.is_recoverable(&error(Some(code))));
+        }
+        assert!(!ErrorMode::Collect.is_recoverable(&error(None)));
+        assert!(!ErrorMode::Collect.is_recoverable(&error(Some("other"))));
+
+        // Warnings are never recorded as errors.
+        let warning = SourceDiagnostic::warning(Span::detached(), "unknown variable")
+            .with_code(ErrorMode::UNKNOWN_VARIABLE);
+        assert!(!ErrorMode::Collect.is_recoverable(&warning));
+    }
+}
@@ -1,14 +1,20 @@
 //! Definition of the central compilation context.
 
-use std::collections::HashSet;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
-use ecow::EcoVec;
+use ecow::{eco_format, EcoVec};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use typst_syntax::{FileId, Span};
 
-use crate::diag::{bail, HintedStrResult, SourceDiagnostic, SourceResult, StrResult};
+use crate::diag::{
+    bail, HintedStrResult, PanicScope, SourceDiagnostic, SourceResult, StrResult,
+};
 use crate::foundations::{Styles, Value};
 use crate::introspection::Introspector;
 use crate::routines::Routines;
@@ -63,31 +69,92 @@ impl Engine<'_> {
         // using `par_bridge` because it does not retain the ordering.
         let work: Vec<T> = iter.into_iter().collect();
 
+        // Count the memoized work of the tasks like that of this thread and
+        // attribute panics in them to the content processed by this thread.
+        let counters = MemoScope::current();
+        let scopes = PanicScope::current();
+        let captured = Mutex::new(None);
+
         // Work in parallel.
         let mut pairs: Vec<(U, Sink)> = Vec::with_capacity(work.len());
-        work.into_par_iter()
-            .map(|value| {
-                let mut sink = Sink::new();
-                let mut engine = Engine {
-                    world,
-                    introspector,
-                    traced,
-                    sink: sink.track_mut(),
-                    route: route.clone(),
-                    routines,
-                };
-                (f(&mut engine, value), sink)
-            })
-            .collect_into_vec(&mut pairs);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            work.into_par_iter()
+                .map(|value| {
+                    let _scopes = MemoScope::enter_all(&counters);
+                    let mut sink = Sink::new();
+                    let mut engine = Engine {
+                        world,
+                        introspector,
+                        traced,
+                        sink: sink.track_mut(),
+                        route: route.clone(),
+                        routines,
+                    };
+                    let output = panic::catch_unwind(AssertUnwindSafe(|| {
+                        let _scopes = PanicScope::enter_all(&scopes);
+                        f(&mut engine, value)
+                    }));
+                    match output {
+                        Ok(output) => (output, sink),
+                        Err(payload) => {
+                            // Hand the context of the panic over to this
+                            // thread, which rayon propagates the panic to.
+                            if let Some(spans) = PanicScope::take_captured() {
+                                captured.lock().unwrap().get_or_insert(spans);
+                            }
+                            panic::resume_unwind(payload)
+                        }
+                    }
+                })
+                .collect_into_vec(&mut pairs)
+        }));
+
+        if let Err(payload) = result {
+            if let Some(spans) = captured.into_inner().ok().flatten() {
+                PanicScope::set_captured(spans);
+            }
+            panic::resume_unwind(payload);
+        }
 
         // Apply the subsinks to the outer sink.
         for (_, sink) in &mut pairs {
             let sink = std::mem::take(sink);
-            self.sink.extend(sink.delayed, sink.warnings, sink.values);
+            self.sink.extend(
+                sink.delayed,
+                sink.warnings,
+                sink.values,
+                sink.stats,
+                sink.lookahead,
+            );
         }
 
         pairs.into_iter().map(|(output, _)| output)
     }
+
+    /// Runs a task on the engine and additionally returns whether it
+    /// performed introspection that may depend on later parts of the document.
+    ///
+    /// See [`Sink::lookahead`] for details.
+    pub fn observe_lookahead<T>(
+        &mut self,
+        f: impl FnOnce(&mut Engine) -> T,
+    ) -> (T, bool) {
+        let mut sink = Sink::new();
+        let mut engine = Engine {
+            routines: self.routines,
+            world: self.world,
+            introspector: self.introspector,
+            traced: self.traced,
+            sink: sink.track_mut(),
+            route: Route::extend(self.route.track()),
+        };
+
+        let output = f(&mut engine);
+        let lookahead = sink.lookahead;
+        self.sink
+            .extend(sink.delayed, sink.warnings, sink.values, sink.stats, lookahead);
+        (output, lookahead)
+    }
 }
 
 /// May hold a span that is currently under inspection.
@@ -134,16 +201,25 @@ pub struct Sink {
     delayed: EcoVec<SourceDiagnostic>,
     /// Warnings emitted during iteration.
     warnings: EcoVec<SourceDiagnostic>,
-    /// Hashes of all warning's spans and messages for warning deduplication.
-    warnings_set: HashSet<u128>,
+    /// Maps the hashes of all warnings' codes, spans, and messages to their
+    /// index in `warnings` for deduplication. See `warning_hash`.
+    warnings_index: HashMap<u128, usize>,
     /// A sequence of traced values for a span.
     values: EcoVec<(Value, Option<Styles>)>,
+    /// Statistics about the work performed.
+    stats: CompileStats,
+    /// Whether introspection that may depend on later parts of the document
+    /// was performed.
+    lookahead: bool,
 }
 
 impl Sink {
     /// The maximum number of traced values.
     pub const MAX_VALUES: usize = 10;
 
+    /// The default maximum number of reported warnings with the same code.
+    pub const MAX_WARNINGS_PER_CODE: usize = 20;
+
     /// Create a new empty sink.
     pub fn new() -> Self {
         Self::default()
@@ -155,8 +231,62 @@ impl Sink {
     }
 
     /// Get the stored warnings.
+    ///
+    /// Of the warnings with the same code, at most
+    /// [`MAX_WARNINGS_PER_CODE`](Self::MAX_WARNINGS_PER_CODE) are returned.
     pub fn warnings(self) -> EcoVec<SourceDiagnostic> {
-        self.warnings
+        self.warnings_with_limit(Self::MAX_WARNINGS_PER_CODE)
+    }
+
+    /// Get the stored warnings, with at most `limit` warnings per code.
+    ///
+    /// Identical warnings were recorded only once, with the number of their
+    /// occurrences. Warnings with the same code beyond the limit are dropped
+    /// and summarized in one additional warning per code.
+    pub fn warnings_with_limit(self, limit: usize) -> EcoVec<SourceDiagnostic> {
+        let mut kept: HashMap<&'static str, usize> = HashMap::new();
+        let mut suppressed: Vec<(&'static str, usize)> = vec![];
+        let mut warnings = EcoVec::with_capacity(self.warnings.len());
+        for warning in self.warnings {
+            if let Some(code) = warning.code {
+                let count = kept.entry(code).or_default();
+                if *count >= limit {
+                    match suppressed.iter_mut().find(|(c, _)| *c == code) {
+                        Some((_, n)) => *n += 1,
+                        None => suppressed.push((code, 1)),
+                    }
+                    continue;
+                }
+                *count += 1;
+            }
+            warnings.push(warning);
+        }
+
+        for (code, n) in suppressed {
+            let (noun, verb) =
+                if n == 1 { ("warning", "was") } else { ("warnings", "were") };
+            warnings.push(
+                SourceDiagnostic::warning(
+                    Span::detached(),
+                    eco_format!("{n} more {noun} of the kind `{code}` {verb} suppressed"),
+                )
+                .with_hint("resolve the warnings above to see the remaining ones")
+                .with_code(code),
+            );
+        }
+
+        warnings
+    }
+
+    /// Keep only the warnings for which `f` returns `true`.
+    pub fn retain_warnings(&mut self, mut f: impl FnMut(&SourceDiagnostic) -> bool) {
+        self.warnings.retain(|warning| f(warning));
+        self.warnings_index = self
+            .warnings
+            .iter()
+            .enumerate()
+            .map(|(i, warning)| (warning_hash(warning), i))
+            .collect();
     }
 
     /// Get the values for the traced span.
@@ -164,9 +294,26 @@ impl Sink {
         self.values
     }
 
+    /// Get the collected statistics.
+    pub fn stats(&mut self) -> CompileStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    /// Whether introspection that may depend on later parts of the document
+    /// was performed.
+    pub fn looked_ahead(&self) -> bool {
+        self.lookahead
+    }
+
     /// Extend from another sink.
     pub fn extend_from_sink(&mut self, other: Sink) {
-        self.extend(other.delayed, other.warnings, other.values);
+        self.extend(
+            other.delayed,
+            other.warnings,
+            other.values,
+            other.stats,
+            other.lookahead,
+        );
     }
 }
 
@@ -178,11 +325,18 @@ impl Sink {
     }
 
     /// Add a warning.
+    ///
+    /// If an identical warning was already added, only its number of
+    /// occurrences is increased. For warnings that are
+    /// [reported once](SourceDiagnostic::once), the span does not matter.
     pub fn warn(&mut self, warning: SourceDiagnostic) {
-        // Check if warning is a duplicate.
-        let hash = typst_utils::hash128(&(&warning.span, &warning.message));
-        if self.warnings_set.insert(hash) {
-            self.warnings.push(warning);
+        let hash = warning_hash(&warning);
+        match self.warnings_index.get(&hash) {
+            Some(&i) => self.warnings.make_mut()[i].occurrences += warning.occurrences,
+            None => {
+                self.warnings_index.insert(hash, self.warnings.len());
+                self.warnings.push(warning);
+            }
         }
     }
 
@@ -193,12 +347,32 @@ impl Sink {
         }
     }
 
+    /// Add statistics about performed work.
+    pub fn count(&mut self, stats: CompileStats) {
+        self.stats.merge(&stats);
+    }
+
+    /// Record that introspection was performed which may depend on later
+    /// parts of the document.
+    ///
+    /// This is a conservative signal: It is recorded for things like
+    /// `context` expressions, outlines, and references, as well as for calls
+    /// to functions like `query` or `counter.final` (which may also happen in
+    /// show rules without an explicit `context`), regardless of whether they
+    /// actually look forward. Layout uses it to decide whether it may
+    /// stop early when only the first few pages are requested.
+    pub fn lookahead(&mut self) {
+        self.lookahead = true;
+    }
+
     /// Extend from parts of another sink.
     fn extend(
         &mut self,
         delayed: EcoVec<SourceDiagnostic>,
         warnings: EcoVec<SourceDiagnostic>,
         values: EcoVec<(Value, Option<Styles>)>,
+        stats: CompileStats,
+        lookahead: bool,
     ) {
         self.delayed.extend(delayed);
         for warning in warnings {
@@ -207,6 +381,230 @@ impl Sink {
         if let Some(remaining) = Self::MAX_VALUES.checked_sub(self.values.len()) {
             self.values.extend(values.into_iter().take(remaining));
         }
+        self.count(stats);
+        self.lookahead |= lookahead;
+    }
+}
+
+/// The hash by which identical warnings are deduplicated.
+///
+/// Warnings that are [reported once](SourceDiagnostic::once) are identical
+/// regardless of their span.
+fn warning_hash(warning: &SourceDiagnostic) -> u128 {
+    let span = (!warning.once).then_some(warning.span);
+    typst_utils::hash128(&(&warning.code, span, &warning.message))
+}
+
+/// Statistics about the work performed during a compilation.
+///
+/// These are cheap counters meant for capacity planning, e.g. to reject or
+/// bill pathological documents in a compile service. All counts are
+/// approximate: Work that is served from the cache is accounted for as if it
+/// was performed again, and counts that are collected during layout are summed
+/// over all layout iterations.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CompileStats {
+    /// The number of elements produced by realization.
+    pub elements: usize,
+    /// The number of frames in the resulting document.
+    pub frames: usize,
+    /// The number of items in the frames of the resulting document.
+    pub items: usize,
+    /// The number of layout iterations performed until all introspections
+    /// stabilized.
+    pub iterations: usize,
+    /// The number of distinct fonts that were loaded.
+    pub fonts: usize,
+    /// The total number of bytes of all distinct decoded raster images in the
+    /// resulting document.
+    pub image_bytes: usize,
+    /// The largest number of bytes that were allocated in a single set of
+    /// realization arenas.
+    pub peak_arena_bytes: usize,
+}
+
+impl CompileStats {
+    /// Merge the counts of another set of statistics into this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.elements += other.elements;
+        self.frames += other.frames;
+        self.items += other.items;
+        self.iterations += other.iterations;
+        self.fonts += other.fonts;
+        self.image_bytes += other.image_bytes;
+        self.peak_arena_bytes = self.peak_arena_bytes.max(other.peak_arena_bytes);
+    }
+}
+
+/// Counts the results that memoized compiler routines added to the
+/// memoization cache since it was last cleared.
+///
+/// A routine only counts when it actually executes, not when its result is
+/// served from the cache. Since partial eviction does not report what it
+/// removed, the counts are an upper bound for the number of cached entries.
+///
+/// Nothing is counted until [`enable_memo_counters`] is called.
+pub static MEMO_COUNTERS: MemoCounters = MemoCounters::new();
+
+/// Whether executions are counted in [`MEMO_COUNTERS`].
+static GLOBAL_COUNTING: AtomicBool = AtomicBool::new(false);
+
+/// The number of counters that are entered on any thread.
+static ENTERED_COUNTERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Start counting executions of memoized routines in [`MEMO_COUNTERS`].
+pub fn enable_memo_counters() {
+    GLOBAL_COUNTING.store(true, Ordering::Relaxed);
+}
+
+thread_local! {
+    /// The counters that were entered on this thread in addition to the
+    /// global ones.
+    static SCOPED_COUNTERS: RefCell<Vec<Arc<MemoCounters>>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// The kinds of memoized compiler routines.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Memoized {
+    /// The evaluation of a source file or string.
+    Module,
+    /// The call of a user-defined closure.
+    Closure,
+    /// The layout of a document, page run, flow, block, or paragraph.
+    Layout,
+    /// The creation of a text shaping plan.
+    ShapePlan,
+}
+
+/// Record one execution of a memoized routine.
+///
+/// This is counted in the global [`MEMO_COUNTERS`] if they are
+/// [enabled](enable_memo_counters) and in all counters that were
+/// [entered](MemoCounters::enter) on the current thread. If neither is the
+/// case, this does nothing.
+#[inline]
+pub fn record_memoized(kind: Memoized) {
+    if GLOBAL_COUNTING.load(Ordering::Relaxed) {
+        MEMO_COUNTERS.get(kind).increment();
+    }
+
+    if ENTERED_COUNTERS.load(Ordering::Relaxed) > 0 {
+        SCOPED_COUNTERS.with_borrow(|scoped| {
+            for counters in scoped {
+                counters.get(kind).increment();
+            }
+        });
+    }
+}
+
+/// Counters for the different kinds of memoized compiler routines.
+#[derive(Debug, Default)]
+pub struct MemoCounters {
+    /// Evaluations of source files and strings into modules and values.
+    pub modules: MemoCounter,
+    /// Calls of user-defined closures.
+    pub closures: MemoCounter,
+    /// Layouts of documents, page runs, flows, blocks, and paragraphs.
+    pub layouts: MemoCounter,
+    /// Text shaping plans for combinations of fonts and features.
+    pub shape_plans: MemoCounter,
+}
+
+impl MemoCounters {
+    /// Create new counters at zero.
+    pub const fn new() -> Self {
+        Self {
+            modules: MemoCounter::new(),
+            closures: MemoCounter::new(),
+            layouts: MemoCounter::new(),
+            shape_plans: MemoCounter::new(),
+        }
+    }
+
+    /// The counter for the given kind of routine.
+    pub fn get(&self, kind: Memoized) -> &MemoCounter {
+        match kind {
+            Memoized::Module => &self.modules,
+            Memoized::Closure => &self.closures,
+            Memoized::Layout => &self.layouts,
+            Memoized::ShapePlan => &self.shape_plans,
+        }
+    }
+
+    /// Reset all counters, e.g. after the cache was cleared completely.
+    pub fn reset(&self) {
+        self.modules.reset();
+        self.closures.reset();
+        self.layouts.reset();
+        self.shape_plans.reset();
+    }
+
+    /// Additionally count the executions on the current thread in these
+    /// counters until the returned scope is dropped.
+    ///
+    /// Work that [`Engine::parallelize`] distributes to other threads is
+    /// counted as well. This way, the counters only include the work of the
+    /// compilations that run within the scope and not that of concurrent
+    /// compilations on other threads.
+    pub fn enter(self: &Arc<Self>) -> MemoScope {
+        SCOPED_COUNTERS.with_borrow_mut(|scoped| scoped.push(self.clone()));
+        ENTERED_COUNTERS.fetch_add(1, Ordering::Relaxed);
+        MemoScope(PhantomData)
+    }
+}
+
+/// Keeps counters entered on the current thread, see [`MemoCounters::enter`].
+pub struct MemoScope(PhantomData<*const ()>);
+
+impl MemoScope {
+    /// Enter all the given counters.
+    fn enter_all(counters: &[Arc<MemoCounters>]) -> Vec<Self> {
+        counters.iter().map(MemoCounters::enter).collect()
+    }
+
+    /// The counters that are entered on the current thread.
+    fn current() -> Vec<Arc<MemoCounters>> {
+        SCOPED_COUNTERS.with_borrow(Vec::clone)
+    }
+}
+
+impl Drop for MemoScope {
+    fn drop(&mut self) {
+        SCOPED_COUNTERS.with_borrow_mut(|scoped| scoped.pop());
+        ENTERED_COUNTERS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A counter that can be shared across threads.
+#[derive(Debug)]
+pub struct MemoCounter(AtomicUsize);
+
+impl MemoCounter {
+    /// Create a new counter at zero.
+    pub const fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    /// The current count.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Reset the counter to zero.
+    pub fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+
+    /// Count one execution.
+    fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Default for MemoCounter {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
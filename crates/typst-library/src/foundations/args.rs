@@ -3,7 +3,9 @@ use std::fmt::{self, Debug, Formatter};
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use typst_syntax::{Span, Spanned};
 
-use crate::diag::{bail, error, At, SourceDiagnostic, SourceResult, StrResult};
+use crate::diag::{
+    bail, error, At, ErrorMode, SourceDiagnostic, SourceResult, StrResult,
+};
 use crate::foundations::{
     cast, func, repr, scope, ty, Array, Dict, FromValue, IntoValue, Repr, Str, Value,
 };
@@ -105,8 +107,7 @@ impl Args {
         for (i, slot) in self.items.iter().enumerate() {
             if slot.name.is_none() {
                 let value = self.items.remove(i).value;
-                let span = value.span;
-                return T::from_value(value).at(span).map(Some);
+                return cast_arg(value).map(Some);
             }
         }
         Ok(None)
@@ -170,8 +171,7 @@ impl Args {
         for (i, slot) in self.items.iter().enumerate() {
             if slot.name.is_none() && T::castable(&slot.value.v) {
                 let value = self.items.remove(i).value;
-                let span = value.span;
-                return T::from_value(value).at(span).map(Some);
+                return cast_arg(value).map(Some);
             }
         }
         Ok(None)
@@ -190,7 +190,7 @@ impl Args {
             }
             let span = item.value.span;
             let spanned = Spanned::new(std::mem::take(&mut item.value.v), span);
-            match T::from_value(spanned).at(span) {
+            match cast_arg(spanned) {
                 Ok(val) => list.push(val),
                 Err(diags) => errors.extend(diags),
             }
@@ -215,8 +215,7 @@ impl Args {
         while i < self.items.len() {
             if self.items[i].name.as_deref() == Some(name) {
                 let value = self.items.remove(i).value;
-                let span = value.span;
-                found = Some(T::from_value(value).at(span)?);
+                found = Some(cast_arg(value)?);
             } else {
                 i += 1;
             }
@@ -439,6 +438,23 @@ where
     }
 }
 
+/// Casts the value of an argument.
+///
+/// A failing cast is marked as an
+/// [invalid argument](ErrorMode::INVALID_ARGUMENT).
+fn cast_arg<T>(value: Spanned<Value>) -> SourceResult<T>
+where
+    T: FromValue<Spanned<Value>>,
+{
+    let span = value.span;
+    T::from_value(value).at(span).map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|error| error.with_code(ErrorMode::INVALID_ARGUMENT))
+            .collect()
+    })
+}
+
 /// The missing key access error message when no default was given.
 #[cold]
 fn missing_key_no_default(key: ArgumentKey) -> EcoString {
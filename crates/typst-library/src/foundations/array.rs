@@ -15,6 +15,7 @@ use crate::foundations::{
     cast, func, ops, repr, scope, ty, Args, Bytes, CastInfo, Context, Dict, FromValue,
     Func, IntoValue, Reflect, Repr, Str, Value, Version,
 };
+use crate::text::{Collation, Lang, TextElem};
 
 /// Create a new [`Array`] from values.
 #[macro_export]
@@ -828,6 +829,18 @@ impl Array {
     /// )
     /// #array.sorted(key: it => (it.a, it.b))
     /// ```
+    ///
+    /// By default, strings are compared by their Unicode code points. The `by`
+    /// parameter selects a different comparison, e.g. to sort names in the
+    /// order of a dictionary:
+    ///
+    /// ```example
+    /// #("Öl", "Zebra", "Apfel", "Ofen")
+    ///   .sorted(by: "locale", lang: "de")
+    ///
+    /// #("item10", "item2", "item1")
+    ///   .sorted(by: "natural")
+    /// ```
     #[func]
     pub fn sorted(
         self,
@@ -841,33 +854,63 @@ impl Array {
         /// determine the keys to sort by.
         #[named]
         key: Option<Func>,
+        /// How to compare the keys.
+        ///
+        /// With `{"natural"}` and `{"locale"}`, strings (also within arrays
+        /// returned by the key function) are compared as described below. Other
+        /// values are compared as usual.
+        ///
+        /// A function receives two keys and must return whether the first one
+        /// should come before the second one.
+        #[named]
+        by: Option<SortBy>,
+        /// The language whose alphabet is used for `{"locale"}` comparison.
+        ///
+        /// Defaults to the [text language]($text.lang) when used within
+        /// [context] and to English otherwise.
+        #[named]
+        lang: Option<Lang>,
     ) -> SourceResult<Array> {
-        let mut result = Ok(());
-        let mut vec = self.0;
-        let mut key_of = |x: Value| match &key {
-            // NOTE: We are relying on `comemo`'s memoization of function
-            // evaluation to not excessively reevaluate the `key`.
-            Some(f) => f.call(engine, context, [x]),
-            None => Ok(x),
+        let collation = match &by {
+            Some(SortBy::Natural) => Some(Collation::Natural),
+            Some(SortBy::Locale) => Some(Collation::Locale(
+                lang.or_else(|| context.styles().ok().map(TextElem::lang_in))
+                    .unwrap_or(Lang::ENGLISH),
+            )),
+            _ => None,
         };
-        vec.make_mut().sort_by(|a, b| {
-            // Until we get `try` blocks :)
-            match (key_of(a.clone()), key_of(b.clone())) {
-                (Ok(a), Ok(b)) => ops::compare(&a, &b).unwrap_or_else(|err| {
-                    if result.is_ok() {
-                        result = Err(err).at(span);
-                    }
-                    Ordering::Equal
-                }),
-                (Err(e), _) | (_, Err(e)) => {
-                    if result.is_ok() {
-                        result = Err(e);
-                    }
-                    Ordering::Equal
-                }
-            }
-        });
-        result.map(|_| vec.into())
+
+        let keys = match &key {
+            Some(f) => self
+                .iter()
+                .map(|v| f.call(engine, context, [v.clone()]))
+                .collect::<SourceResult<Vec<_>>>()?,
+            None => self.0.to_vec(),
+        };
+
+        let mut indices: Vec<usize> = (0..keys.len()).collect();
+        merge_sort(&mut indices, &mut |&i, &j| {
+            let (a, b) = (&keys[i], &keys[j]);
+            let Some(SortBy::Func(f)) = &by else {
+                return collate(a, b, collation).at(span);
+            };
+
+            let mut before = |x: &Value, y: &Value| {
+                f.call(engine, context, [x.clone(), y.clone()])?
+                    .cast::<bool>()
+                    .at(span)
+            };
+
+            Ok(if before(a, b)? {
+                Ordering::Less
+            } else if before(b, a)? {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            })
+        })?;
+
+        Ok(indices.into_iter().map(|i| self.0[i].clone()).collect())
     }
 
     /// Deduplicates all items in the array.
@@ -984,6 +1027,70 @@ impl Array {
     }
 }
 
+/// Stably sorts a slice with a fallible comparison, stopping at the first
+/// error.
+///
+/// Unlike `slice::sort_by`, this cannot panic if the comparison is not a total
+/// order, which a user-defined function may well violate.
+fn merge_sort<T: Copy>(
+    items: &mut [T],
+    cmp: &mut impl FnMut(&T, &T) -> SourceResult<Ordering>,
+) -> SourceResult<()> {
+    if items.len() <= 1 {
+        return Ok(());
+    }
+
+    let mid = items.len() / 2;
+    merge_sort(&mut items[..mid], cmp)?;
+    merge_sort(&mut items[mid..], cmp)?;
+
+    let mut merged = Vec::with_capacity(items.len());
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < items.len() {
+        // Taking from the left half on equality keeps the sort stable.
+        if cmp(&items[j], &items[i])?.is_lt() {
+            merged.push(items[j]);
+            j += 1;
+        } else {
+            merged.push(items[i]);
+            i += 1;
+        }
+    }
+
+    merged.extend_from_slice(&items[i..mid]);
+    merged.extend_from_slice(&items[j..]);
+    items.copy_from_slice(&merged);
+    Ok(())
+}
+
+/// How to compare values when sorting an array.
+pub enum SortBy {
+    /// Compare embedded numbers by value.
+    Natural,
+    /// Compare according to the alphabet of a language.
+    Locale,
+    /// Compare with a custom function.
+    Func(Func),
+}
+
+cast! {
+    SortBy,
+    self => match self {
+        Self::Natural => "natural".into_value(),
+        Self::Locale => "locale".into_value(),
+        Self::Func(v) => v.into_value(),
+    },
+    /// Compares runs of digits by their numeric value and everything else by
+    /// code point, such that `{"item2"}` comes before `{"item10"}`.
+    "natural" => Self::Natural,
+    /// Compares letters by their place in the alphabet of the
+    /// [language]($array.sorted.lang). Letters with diacritics are sorted
+    /// with their base letter unless the language treats them as letters of
+    /// their own, and diacritics and case only break ties.
+    "locale" => Self::Locale,
+    v: Func => Self::Func(v),
+}
+
 /// A value that can be cast to bytes.
 pub struct ToArray(Array);
 
@@ -1124,6 +1231,23 @@ impl<T: FromValue, const N: usize> FromValue for SmallVec<[T; N]> {
     }
 }
 
+/// Compare two values, with strings compared by the given collation.
+fn collate(a: &Value, b: &Value, collation: Option<Collation>) -> StrResult<Ordering> {
+    match (a, b, collation) {
+        (Value::Str(a), Value::Str(b), Some(collation)) => Ok(collation.compare(a, b)),
+        (Value::Array(a), Value::Array(b), Some(_)) => {
+            for (x, y) in a.iter().zip(b.iter()) {
+                let ordering = collate(x, y, collation)?;
+                if ordering.is_ne() {
+                    return Ok(ordering);
+                }
+            }
+            Ok(a.len().cmp(&b.len()))
+        }
+        _ => ops::compare(a, b),
+    }
+}
+
 /// The error message when the array is empty.
 #[cold]
 fn array_is_empty() -> EcoString {
@@ -0,0 +1,280 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Neg;
+use std::str::FromStr;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::StrResult;
+use crate::foundations::{cast, func, repr, scope, ty, Repr, Str, Value};
+
+/// An arbitrary-precision integer.
+///
+/// Regular [integers]($int) are 64 bits wide. Arithmetic that leaves that
+/// range fails with an error instead of silently wrapping around or losing
+/// precision. When you need exact integers beyond that range, for example for
+/// large factorials or other combinatorics, convert one of the operands to a
+/// `bigint` first.
+///
+/// Big integers can be added, subtracted, and multiplied with each other and
+/// with integers. The result of such an operation is always a `bigint`. They
+/// compare equal to and can be ordered against integers with the same value.
+/// They are also compared exactly against [floats]($float), so a `bigint` only
+/// equals a float that has precisely the same integral value.
+///
+/// Big integers cannot be mixed with floats or [decimals]($decimal) in
+/// arithmetic, as that would lose precision. Instead, convert them explicitly
+/// with the [`float`] or [`str`] constructors, or with the [`int`] constructor
+/// if they fit into 64 bits.
+///
+/// The functions [`calc.pow`]($calc.pow), [`calc.fact`]($calc.fact),
+/// [`calc.perm`]($calc.perm), and [`calc.binom`]($calc.binom) automatically
+/// return a `bigint` when their integer result does not fit into an integer.
+///
+/// # Example
+/// ```example
+/// #(bigint(9223372036854775807) + 1) \
+/// #calc.fact(25) \
+/// #bigint("2").pow(100)
+/// ```
+#[ty(scope, name = "bigint", title = "Big Integer")]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BigInt(num_bigint::BigInt);
+
+impl BigInt {
+    /// The maximum number of bits that a big integer may have.
+    ///
+    /// This bounds the time and memory that a single operation can take.
+    pub const MAX_BITS: u64 = 1 << 16;
+
+    /// Returns the value as an integer if it fits into 64 bits.
+    pub fn to_i64(&self) -> Option<i64> {
+        i64::try_from(&self.0).ok()
+    }
+
+    /// Returns the closest float to this value.
+    pub fn to_f64(&self) -> f64 {
+        // Parsing the decimal digits rounds correctly, unlike a conversion
+        // based on the leading digits only.
+        self.0.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// Checked addition.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Self::checked(&self.0 + &other.0)
+    }
+
+    /// Checked subtraction.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Self::checked(&self.0 - &other.0)
+    }
+
+    /// Checked multiplication.
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        if self.0.bits() + other.0.bits() > Self::MAX_BITS + 1 {
+            return None;
+        }
+        Self::checked(&self.0 * &other.0)
+    }
+
+    /// Checked exponentiation.
+    pub fn checked_pow(&self, exponent: u32) -> Option<Self> {
+        if self.0.bits().saturating_sub(1).saturating_mul(exponent as u64)
+            > Self::MAX_BITS
+        {
+            return None;
+        }
+        Self::checked(self.0.pow(exponent))
+    }
+
+    /// Computes the product of the numbers from `start` to `end`, both
+    /// inclusive.
+    pub fn product(start: u64, end: u64) -> Option<Self> {
+        let mut acc = num_bigint::BigInt::from(1);
+        for i in start..=end {
+            acc *= i;
+            if acc.bits() > Self::MAX_BITS {
+                return None;
+            }
+        }
+        Some(Self(acc))
+    }
+
+    /// Computes `n` choose `k`.
+    pub fn binom(n: u64, k: u64) -> Option<Self> {
+        if k > n {
+            return Some(Self::from(0));
+        }
+
+        let k = k.min(n - k);
+        let mut acc = num_bigint::BigInt::from(1);
+        for i in 0..k {
+            acc = acc * (n - i) / (i + 1);
+            if acc.bits() > Self::MAX_BITS {
+                return None;
+            }
+        }
+        Some(Self(acc))
+    }
+
+    /// Compares this value to an integer.
+    pub fn cmp_int(&self, other: i64) -> Ordering {
+        self.0.cmp(&other.into())
+    }
+
+    /// Compares this value exactly to a float.
+    ///
+    /// Returns `None` if the float is NaN.
+    pub fn cmp_float(&self, other: f64) -> Option<Ordering> {
+        if other.is_nan() {
+            return None;
+        } else if other.is_infinite() {
+            return Some(if other > 0.0 { Ordering::Less } else { Ordering::Greater });
+        }
+
+        // Formatting with an explicit precision prints the exact value of the
+        // integral part, which is then compared without any rounding.
+        let trunc = other.trunc();
+        let int = num_bigint::BigInt::from_str(&format!("{trunc:.0}")).ok()?;
+        Some(self.0.cmp(&int).then_with(|| 0.0_f64.total_cmp(&(other - trunc))))
+    }
+
+    /// Ensures that a result is within the size limit.
+    fn checked(v: num_bigint::BigInt) -> Option<Self> {
+        (v.bits() <= Self::MAX_BITS).then_some(Self(v))
+    }
+}
+
+#[scope]
+impl BigInt {
+    /// Converts a value to a `bigint`.
+    ///
+    /// Strings must consist of decimal digits, optionally preceded by a minus
+    /// sign.
+    ///
+    /// ```example
+    /// #bigint(5) \
+    /// #bigint("-123456789012345678901234567890")
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The value that should be converted to a big integer.
+        value: ToBigInt,
+    ) -> BigInt {
+        value.0
+    }
+
+    /// Raises the big integer to the power of an exponent.
+    ///
+    /// ```example
+    /// #bigint(2).pow(100)
+    /// ```
+    #[func]
+    pub fn pow(
+        &self,
+        /// The exponent of the power. Must be non-negative.
+        exponent: u32,
+    ) -> StrResult<BigInt> {
+        self.checked_pow(exponent)
+            .ok_or_else(|| "the result is too large".into())
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.replace(repr::MINUS_SIGN, "-");
+        let invalid = || eco_format!("invalid big integer: {s}");
+        let unsigned = digits.strip_prefix('-').unwrap_or(&digits);
+        if unsigned.is_empty() || !unsigned.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        num_bigint::BigInt::from_str(&digits)
+            .ok()
+            .and_then(Self::checked)
+            .ok_or_else(invalid)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self(value.into())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0.sign() == num_bigint::Sign::Minus {
+            f.write_str(repr::MINUS_SIGN)?;
+        }
+        self.0.magnitude().fmt(f)
+    }
+}
+
+impl Repr for BigInt {
+    fn repr(&self) -> EcoString {
+        eco_format!("bigint({})", eco_format!("{}", self.0).repr())
+    }
+}
+
+/// A value that can be cast to a big integer.
+pub struct ToBigInt(BigInt);
+
+cast! {
+    ToBigInt,
+    v: i64 => Self(v.into()),
+    v: BigInt => Self(v),
+    v: Str => Self(v.parse()?),
+}
+
+/// An integer that is promoted to a big integer if it does not fit into 64
+/// bits.
+pub enum Integer {
+    Small(i64),
+    Big(BigInt),
+}
+
+impl From<BigInt> for Integer {
+    fn from(value: BigInt) -> Self {
+        match value.to_i64() {
+            Some(v) => Self::Small(v),
+            None => Self::Big(value),
+        }
+    }
+}
+
+cast! {
+    Integer,
+    self => match self {
+        Self::Small(v) => v.into_value(),
+        Self::Big(v) => v.into_value(),
+    },
+    v: i64 => Self::Small(v),
+    v: BigInt => Self::Big(v),
+}
+
+/// Returns the operands as big integers if at least one of them is a big
+/// integer and the other one is an integer or big integer.
+pub fn big_operands(lhs: &Value, rhs: &Value) -> Option<(BigInt, BigInt)> {
+    let convert = |v: &Value| match v {
+        Value::Int(i) => Some(BigInt::from(*i)),
+        Value::Dyn(d) => d.downcast::<BigInt>().cloned(),
+        _ => None,
+    };
+
+    let is_big = |v: &Value| matches!(v, Value::Dyn(d) if d.is::<BigInt>());
+    if !is_big(lhs) && !is_big(rhs) {
+        return None;
+    }
+
+    Some((convert(lhs)?, convert(rhs)?))
+}
@@ -0,0 +1,160 @@
+//! Construction of documents from Rust.
+//!
+//! Content can be built directly from Rust values instead of generating
+//! Typst markup and evaluating it. This is faster and avoids escaping issues:
+//! Text passed to the builder is never parsed, so it always shows up verbatim.
+//!
+//! A [`ContentBuilder`] collects the blocks of a document in order. The
+//! finished content can be laid out with `typst::compile_content`, which
+//! takes the fonts, files, and standard library from a `World` just like a
+//! regular compilation.
+//!
+//! ```ignore
+//! use typst::foundations::doc;
+//! use typst::layout::{Abs, PagedDocument};
+//! use typst::text::{TextElem, TextSize};
+//!
+//! let content = doc()
+//!     .styled(|styles| styles.set(TextElem::set_size(TextSize(Abs::pt(10.0).into()))))
+//!     .heading(1, "Sales")
+//!     .par("All figures are in thousands of *units*.")
+//!     .table([["Region", "Q1", "Q2"], ["North", "12", "15"], ["South", "9", "11"]])
+//!     .build();
+//!
+//! let document = typst::compile_content::<PagedDocument>(&world, &content)
+//!     .output
+//!     .expect("failed to lay out");
+//! ```
+//!
+//! The builder covers the common building blocks. Everything else can be
+//! constructed with the elements' own constructors, like
+//! `FigureElem::new(body).pack()`, and added with [`ContentBuilder::push`].
+//! Elements whose fields are filled in during layout, like the numbering of
+//! a heading, need no special treatment: Such fields are synthesized when the
+//! content is laid out, in the same way as for evaluated markup.
+
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use smallvec::smallvec;
+use typst_utils::NonZeroExt;
+
+use crate::foundations::{Content, NativeElement, Packed, Styles};
+use crate::layout::{Sizing, TrackSizings};
+use crate::model::{
+    HeadingElem, ParbreakElem, TableCell, TableChild, TableElem, TableItem,
+};
+use crate::text::TextElem;
+
+/// Start building a document.
+///
+/// This is a shorthand for [`ContentBuilder::new`].
+pub fn doc() -> ContentBuilder {
+    ContentBuilder::new()
+}
+
+/// Builds content from Rust values, block by block.
+///
+/// Each method adds a block after the ones added before. Call
+/// [`build`](Self::build) to get the finished content.
+#[derive(Debug, Default, Clone)]
+pub struct ContentBuilder {
+    /// The blocks added so far.
+    children: Vec<Content>,
+    /// Styles that apply from the block at the given index onwards.
+    styles: Vec<(usize, Styles)>,
+}
+
+impl ContentBuilder {
+    /// Create an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a heading with the given depth and text.
+    ///
+    /// The depth starts at one for top-level headings. A depth of zero is
+    /// treated as one.
+    pub fn heading(self, depth: usize, title: impl Into<EcoString>) -> Self {
+        let depth = NonZeroUsize::new(depth).unwrap_or(NonZeroUsize::ONE);
+        let heading = HeadingElem::new(TextElem::packed(title)).with_depth(depth);
+        self.push(heading.pack())
+    }
+
+    /// Add a paragraph with the given text.
+    ///
+    /// The text is not interpreted as markup.
+    pub fn par(self, text: impl Into<EcoString>) -> Self {
+        self.push(Content::sequence([
+            ParbreakElem::shared().clone(),
+            TextElem::packed(text),
+            ParbreakElem::shared().clone(),
+        ]))
+    }
+
+    /// Add a table with the given rows of text.
+    ///
+    /// The table has as many columns as its longest row. Shorter rows are
+    /// filled up with empty cells.
+    pub fn table<R, C>(self, rows: R) -> Self
+    where
+        R: IntoIterator,
+        R::Item: IntoIterator<Item = C>,
+        C: Into<EcoString>,
+    {
+        let rows: Vec<Vec<EcoString>> = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect())
+            .collect();
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut children = vec![];
+        for row in rows {
+            let len = row.len();
+            let cells = row
+                .into_iter()
+                .map(TextElem::packed)
+                .chain(std::iter::repeat_with(Content::empty).take(columns - len));
+            children.extend(cells.map(|body| {
+                TableChild::Item(TableItem::Cell(Packed::new(TableCell::new(body))))
+            }));
+        }
+
+        let table = TableElem::new(children)
+            .with_columns(TrackSizings(smallvec![Sizing::Auto; columns]));
+        self.push(table.pack())
+    }
+
+    /// Add arbitrary content, for example an element that was constructed
+    /// with its own constructor.
+    pub fn push(mut self, content: Content) -> Self {
+        self.children.push(content);
+        self
+    }
+
+    /// Apply styles to all blocks that are added afterwards.
+    ///
+    /// This works like a set rule in markup: The styles stay active until the
+    /// end of the document.
+    ///
+    /// ```ignore
+    /// doc().styled(|styles| styles.set(TextElem::set_lang(Lang::GERMAN)))
+    /// ```
+    pub fn styled(mut self, f: impl FnOnce(&mut Styles)) -> Self {
+        let mut styles = Styles::new();
+        f(&mut styles);
+        self.styles.push((self.children.len(), styles));
+        self
+    }
+
+    /// Finish building and return the content.
+    pub fn build(mut self) -> Content {
+        // Wrap the blocks following each set of styles from the innermost
+        // outwards, so that later styles take precedence.
+        for (index, styles) in self.styles.into_iter().rev() {
+            let rest = self.children.split_off(index);
+            self.children.push(Content::sequence(rest).styled_with_map(styles));
+        }
+        Content::sequence(self.children)
+    }
+}
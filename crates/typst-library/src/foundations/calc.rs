@@ -8,7 +8,9 @@ use typst_syntax::{Span, Spanned};
 use typst_utils::{round_int_with_precision, round_with_precision};
 
 use crate::diag::{bail, At, HintedString, SourceResult, StrResult};
-use crate::foundations::{cast, func, ops, Decimal, IntoValue, Module, Scope, Value};
+use crate::foundations::{
+    cast, func, ops, BigInt, Decimal, Integer, IntoValue, Module, Scope, Value,
+};
 use crate::layout::{Angle, Fr, Length, Ratio};
 
 /// A module with calculation definitions.
@@ -91,6 +93,9 @@ cast! {
 
 /// Raises a value to some exponent.
 ///
+/// If both the base and the exponent are [integers]($int) and the result does
+/// not fit into an integer, it is returned as a [big integer]($bigint) instead.
+///
 /// ```example
 /// #calc.pow(2, 3) \
 /// #calc.pow(decimal("2.5"), 2)
@@ -105,7 +110,7 @@ pub fn pow(
     base: DecNum,
     /// The exponent of the power.
     exponent: Spanned<Num>,
-) -> SourceResult<DecNum> {
+) -> SourceResult<Value> {
     match exponent.v {
         _ if exponent.v.float() == 0.0 && base.is_zero() => {
             bail!(span, "zero to the power of zero is undefined")
@@ -120,14 +125,19 @@ pub fn pow(
     };
 
     match (base, exponent.v) {
-        (DecNum::Int(a), Num::Int(b)) if b >= 0 => a
-            .checked_pow(b as u32)
-            .map(DecNum::Int)
+        (DecNum::Int(a), Num::Int(b)) if b >= 0 => match a.checked_pow(b as u32) {
+            Some(v) => Ok(v.into_value()),
+            None => BigInt::from(a)
+                .checked_pow(b as u32)
+                .map(IntoValue::into_value)
+                .ok_or_else(too_large)
+                .at(span),
+        },
+        (DecNum::Decimal(a), Num::Int(b)) => a
+            .checked_powi(b)
+            .map(IntoValue::into_value)
             .ok_or_else(too_large)
             .at(span),
-        (DecNum::Decimal(a), Num::Int(b)) => {
-            a.checked_powi(b).map(DecNum::Decimal).ok_or_else(too_large).at(span)
-        }
         (a, b) => {
             let Some(a) = a.float() else {
                 return Err(cant_apply_to_decimal_and_float()).at(span);
@@ -147,7 +157,7 @@ pub fn pow(
                 bail!(span, "the result is not a real number")
             }
 
-            Ok(DecNum::Float(result))
+            Ok(result.into_value())
         }
     }
 }
@@ -474,6 +484,9 @@ pub fn ln(
 
 /// Calculates the factorial of a number.
 ///
+/// If the result does not fit into an [integer]($int), it is returned as a
+/// [big integer]($bigint) instead.
+///
 /// ```example
 /// #calc.fact(5)
 /// ```
@@ -481,8 +494,8 @@ pub fn ln(
 pub fn fact(
     /// The number whose factorial to calculate. Must be non-negative.
     number: u64,
-) -> StrResult<i64> {
-    Ok(fact_impl(1, number).ok_or_else(too_large)?)
+) -> StrResult<Integer> {
+    fact_or_big(1, number)
 }
 
 /// Calculates a permutation.
@@ -490,6 +503,9 @@ pub fn fact(
 /// Returns the `k`-permutation of `n`, or the number of ways to choose `k`
 /// items from a set of `n` with regard to order.
 ///
+/// If the result does not fit into an [integer]($int), it is returned as a
+/// [big integer]($bigint) instead.
+///
 /// ```example
 /// $ "perm"(n, k) &= n!/((n - k)!) \
 ///   "perm"(5, 3) &= #calc.perm(5, 3) $
@@ -500,13 +516,22 @@ pub fn perm(
     base: u64,
     /// The number of permutations. Must be non-negative.
     numbers: u64,
-) -> StrResult<i64> {
+) -> StrResult<Integer> {
     // By convention.
     if base < numbers {
-        return Ok(0);
+        return Ok(Integer::Small(0));
     }
 
-    Ok(fact_impl(base - numbers + 1, base).ok_or_else(too_large)?)
+    fact_or_big(base - numbers + 1, base)
+}
+
+/// Calculates the product of a range of numbers, promoting the result to a
+/// big integer if it does not fit into an `i64`.
+fn fact_or_big(start: u64, end: u64) -> StrResult<Integer> {
+    match fact_impl(start, end) {
+        Some(v) => Ok(Integer::Small(v)),
+        None => Ok(BigInt::product(start, end).ok_or_else(too_large)?.into()),
+    }
 }
 
 /// Calculates the product of a range of numbers. Used to calculate
@@ -531,6 +556,9 @@ fn fact_impl(start: u64, end: u64) -> Option<i64> {
 /// Returns the `k`-combination of `n`, or the number of ways to choose `k`
 /// items from a set of `n` without regard to order.
 ///
+/// If the result does not fit into an [integer]($int), it is returned as a
+/// [big integer]($bigint) instead.
+///
 /// ```example
 /// #calc.binom(10, 5)
 /// ```
@@ -540,8 +568,11 @@ pub fn binom(
     n: u64,
     /// The lower coefficient. Must be non-negative.
     k: u64,
-) -> StrResult<i64> {
-    Ok(binom_impl(n, k).ok_or_else(too_large)?)
+) -> StrResult<Integer> {
+    match binom_impl(n, k) {
+        Some(v) => Ok(Integer::Small(v)),
+        None => Ok(BigInt::binom(n, k).ok_or_else(too_large)?.into()),
+    }
 }
 
 /// Calculates a binomial coefficient, with `n` the upper coefficient and `k`
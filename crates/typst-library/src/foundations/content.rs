@@ -448,8 +448,11 @@ impl Content {
         text
     }
 
-    /// Traverse this content.
-    fn traverse<F>(&self, f: &mut F)
+    /// Traverses this content and all content in its fields, in document
+    /// order.
+    ///
+    /// Elements produced in `show` rules will not be visited.
+    pub fn traverse<F>(&self, f: &mut F)
     where
         F: FnMut(Content),
     {
@@ -603,6 +606,59 @@ impl Content {
     pub fn location(&self) -> Option<Location> {
         self.inner.location
     }
+
+    /// Whether two pieces of content are structurally equal.
+    ///
+    /// Compares the element functions and the fields of both pieces of content
+    /// recursively. Functions in fields are compared by identity, so two
+    /// closures are only equal if they stem from the same definition.
+    ///
+    /// Unlike the `==` operator, this can also take into account where in the
+    /// source code the content was created.
+    ///
+    /// ```example
+    /// #let a = [Hello *World*]
+    /// #let b = [Hello *World*]
+    /// #content.equals(a, b) \
+    /// #content.equals(a, b, ignore-spans: false)
+    /// ```
+    #[func]
+    pub fn equals(
+        /// The first piece of content.
+        a: Content,
+        /// The second piece of content.
+        b: Content,
+        /// Whether to ignore where in the source code the content was created.
+        #[named]
+        #[default(true)]
+        ignore_spans: bool,
+    ) -> bool {
+        structurally_equal(&a, &b, ignore_spans)
+    }
+
+    /// Describes the differences between two pieces of content.
+    ///
+    /// Returns an empty string if both are [equal]($content.equals). Otherwise,
+    /// lists the first few differences, each with the path of field accesses
+    /// that leads to it, the value on the left (`-`) and the value on the
+    /// right (`+`). This is useful to make failing assertions readable.
+    ///
+    /// ```example
+    /// #let a = [Hello *World*]
+    /// #let b = [Hello *Welt*]
+    /// #raw(content.diff(a, b))
+    /// ```
+    #[func]
+    pub fn diff(
+        /// The first piece of content.
+        a: Content,
+        /// The second piece of content.
+        b: Content,
+    ) -> Str {
+        let mut differ = Differ::default();
+        differ.content(&mut String::new(), &a, &b);
+        differ.finish().into()
+    }
 }
 
 impl Default for Content {
@@ -713,6 +769,177 @@ impl Serialize for Content {
     }
 }
 
+/// Whether two pieces of content have the same element and fields, optionally
+/// also comparing their spans.
+fn structurally_equal(a: &Content, b: &Content, ignore_spans: bool) -> bool {
+    if !ignore_spans && a.span() != b.span() {
+        return false;
+    }
+
+    if a.elem() != b.elem() {
+        return false;
+    }
+
+    let (fa, fb) = (a.fields(), b.fields());
+    fa.len() == fb.len()
+        && fa.iter().all(|(key, va)| {
+            fb.get(key).is_ok_and(|vb| values_equal(va, vb, ignore_spans))
+        })
+}
+
+/// Whether two field values are structurally equal.
+fn values_equal(a: &Value, b: &Value, ignore_spans: bool) -> bool {
+    match (a, b) {
+        (Value::Content(a), Value::Content(b)) => structurally_equal(a, b, ignore_spans),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter().zip(b.iter()).all(|(x, y)| values_equal(x, y, ignore_spans))
+        }
+        (Value::Dict(a), Value::Dict(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, x)| {
+                    b.get(key).is_ok_and(|y| values_equal(x, y, ignore_spans))
+                })
+        }
+        _ => a == b,
+    }
+}
+
+/// Collects the differences between two pieces of content for
+/// [`Content::diff`].
+#[derive(Default)]
+struct Differ {
+    /// The listed differences.
+    listed: Vec<EcoString>,
+    /// How many differences were found beyond the listed ones.
+    more: usize,
+    /// Whether two functions differed.
+    funcs: bool,
+}
+
+impl Differ {
+    /// The maximum number of listed differences.
+    const MAX_LISTED: usize = 5;
+
+    /// The maximum length of a value in the output.
+    const MAX_VALUE_LEN: usize = 60;
+
+    /// Compare two pieces of content at the given path.
+    fn content(&mut self, path: &mut String, a: &Content, b: &Content) {
+        if a.elem() != b.elem() {
+            self.push(path, &a.repr(), &b.repr());
+            return;
+        }
+
+        let (fa, fb) = (a.fields(), b.fields());
+        self.fields(path, &fa, &fb);
+    }
+
+    /// Compare the entries of two dictionaries of fields.
+    fn fields(&mut self, path: &mut String, a: &Dict, b: &Dict) {
+        for (key, x) in a.iter() {
+            self.nested(path, &eco_format!(".{key}"), |differ, path| match b.get(key) {
+                Ok(y) => differ.value(path, x, y),
+                Err(_) => differ.push(path, &x.repr(), "(unset)"),
+            });
+        }
+
+        for (key, y) in b.iter().filter(|(key, _)| !a.contains(key)) {
+            self.nested(path, &eco_format!(".{key}"), |differ, path| {
+                differ.push(path, "(unset)", &y.repr())
+            });
+        }
+    }
+
+    /// Compare two values at the given path.
+    fn value(&mut self, path: &mut String, a: &Value, b: &Value) {
+        match (a, b) {
+            (Value::Content(a), Value::Content(b)) => self.content(path, a, b),
+            (Value::Array(a), Value::Array(b)) => {
+                for i in 0..a.len().max(b.len()) {
+                    self.nested(path, &eco_format!(".at({i})"), |differ, path| {
+                        match (a.as_slice().get(i), b.as_slice().get(i)) {
+                            (Some(x), Some(y)) => differ.value(path, x, y),
+                            (Some(x), None) => differ.push(path, &x.repr(), "(missing)"),
+                            (None, Some(y)) => differ.push(path, "(missing)", &y.repr()),
+                            (None, None) => {}
+                        }
+                    });
+                }
+            }
+            (Value::Dict(a), Value::Dict(b)) => self.fields(path, a, b),
+            (Value::Func(_), Value::Func(_)) if a != b => {
+                self.funcs = true;
+                self.push(path, &a.repr(), &b.repr());
+            }
+            _ if a != b => self.push(path, &a.repr(), &b.repr()),
+            _ => {}
+        }
+    }
+
+    /// Run `f` with a path that is extended by `segment`.
+    fn nested(
+        &mut self,
+        path: &mut String,
+        segment: &str,
+        f: impl FnOnce(&mut Self, &mut String),
+    ) {
+        let len = path.len();
+        path.push_str(segment);
+        f(self, path);
+        path.truncate(len);
+    }
+
+    /// Record a difference.
+    fn push(&mut self, path: &str, a: &str, b: &str) {
+        if self.listed.len() >= Self::MAX_LISTED {
+            self.more += 1;
+            return;
+        }
+
+        let path = path.strip_prefix('.').unwrap_or(path);
+        let path = if path.is_empty() { "(root)" } else { path };
+        self.listed.push(eco_format!(
+            "{path}:\n  - {}\n  + {}",
+            Self::shorten(a),
+            Self::shorten(b),
+        ));
+    }
+
+    /// Shorten an overly long value.
+    fn shorten(value: &str) -> EcoString {
+        if value.chars().count() <= Self::MAX_VALUE_LEN {
+            return value.into();
+        }
+
+        let mut short: EcoString = value.chars().take(Self::MAX_VALUE_LEN - 1).collect();
+        short.push('…');
+        short
+    }
+
+    /// Format the collected differences.
+    fn finish(self) -> EcoString {
+        let mut out = EcoString::new();
+        for (i, listed) in self.listed.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str(listed);
+        }
+
+        if self.more > 0 {
+            let s = if self.more == 1 { "" } else { "s" };
+            out.push_str(&eco_format!("\n… and {} more difference{s}", self.more));
+        }
+
+        if self.funcs {
+            out.push_str("\nnote: functions are compared by identity");
+        }
+
+        out
+    }
+}
+
 /// The trait that combines all the other traits into a trait object.
 trait Bounds: Debug + Repr + Fields + Send + Sync + 'static {
     fn dyn_type_id(&self) -> TypeId;
@@ -839,6 +1066,11 @@ impl<T: NativeElement> Packed<T> {
         self.0.label()
     }
 
+    /// Sets the label of the element.
+    pub fn set_label(&mut self, label: Label) {
+        self.0.set_label(label);
+    }
+
     /// Accesses the location of the element.
     pub fn location(&self) -> Option<Location> {
         self.0.location()
@@ -99,12 +99,22 @@ use crate::World;
 /// - `day`: Displays the day of the datetime.
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     day is padded.
+///   - `repr`: Can be `ordinal` to display the day as an English ordinal
+///     number like "1st" or "22nd". Cannot be combined with other modifiers.
+/// - `ordinal`: Displays the day of the year, starting at 1.
+///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
+///     day of the year is padded.
+/// - `quarter`: Displays the quarter of the year, from 1 to 4. Has no
+///   modifiers.
 /// - `week_number`: Displays the week number of the datetime.
 ///   - `padding`: Can be either `zero`, `space` or `none`. Specifies how the
 ///     week number is padded.
 ///   - `repr`: Can be either `ISO`, `sunday` or `monday`. In the case of `ISO`,
 ///      week numbers are between 1 and 53, while the other ones are between 0
-///      and 53.
+///      and 53. ISO weeks belong to the year in which their Thursday falls,
+///      so the first days of January can be in week 52 or 53 of the
+///      previous year. Use `[[year base:iso_week]]` to display the matching
+///      year.
 /// - `weekday`: Displays the weekday of the date.
 ///   - `repr` Can be either `long`, `short`, `sunday` or `monday`. In the case
 ///     of `long` and `short`, the corresponding English name will be displayed
@@ -358,11 +368,30 @@ impl Datetime {
                 }
             },
 
-            Smart::Custom(DisplayPattern(_, format)) => match self {
-                Self::Date(date) => date.format(&format),
-                Self::Time(time) => time.format(&format),
-                Self::Datetime(datetime) => datetime.format(&format),
-            },
+            Smart::Custom(DisplayPattern(_, items)) => {
+                let mut out = EcoString::new();
+                for item in &items {
+                    match item {
+                        PatternItem::Format(format) => out.push_str(
+                            &match self {
+                                Self::Date(date) => date.format(format),
+                                Self::Time(time) => time.format(format),
+                                Self::Datetime(datetime) => datetime.format(format),
+                            }
+                            .map_err(format_time_format_error)?,
+                        ),
+                        PatternItem::Quarter => {
+                            let month = self.month().ok_or(INSUFFICIENT)?;
+                            out.push_str(&eco_format!("{}", month.div_ceil(3)));
+                        }
+                        PatternItem::OrdinalDay => {
+                            let day = self.day().ok_or(INSUFFICIENT)?;
+                            out.push_str(&eco_format!("{day}{}", ordinal_suffix(day)));
+                        }
+                    }
+                }
+                return Ok(out);
+            }
         };
         result.map(EcoString::from).map_err(format_time_format_error)
     }
@@ -437,6 +466,81 @@ impl Datetime {
         }
     }
 
+    /// Adds years, months, weeks, days, hours, minutes, and seconds to the
+    /// datetime.
+    ///
+    /// Years and months are added first and respect the calendar: Adding a
+    /// month to January 15th yields February 15th. If the day does not exist
+    /// in the resulting month, it is clamped to the month's last day, so
+    /// adding a month to January 31st yields February 28th (or 29th in a leap
+    /// year). The remaining components are then added like a
+    /// [duration].
+    ///
+    /// ```example
+    /// #let date = datetime(year: 2024, month: 1, day: 31)
+    /// #date.add(months: 1).display() \
+    /// #date.add(years: 1, months: 1).display() \
+    /// #date.add(weeks: 2).display()
+    /// ```
+    #[func]
+    pub fn add(
+        &self,
+        /// The number of years to add.
+        #[named]
+        #[default(0)]
+        years: i64,
+        /// The number of months to add.
+        #[named]
+        #[default(0)]
+        months: i64,
+        /// The number of weeks to add.
+        #[named]
+        #[default(0)]
+        weeks: i64,
+        /// The number of days to add.
+        #[named]
+        #[default(0)]
+        days: i64,
+        /// The number of hours to add.
+        #[named]
+        #[default(0)]
+        hours: i64,
+        /// The number of minutes to add.
+        #[named]
+        #[default(0)]
+        minutes: i64,
+        /// The number of seconds to add.
+        #[named]
+        #[default(0)]
+        seconds: i64,
+    ) -> StrResult<Datetime> {
+        let mut datetime = *self;
+        let months = years
+            .checked_mul(12)
+            .and_then(|years| years.checked_add(months))
+            .ok_or("date is out of range")?;
+        if months != 0 {
+            datetime = match datetime {
+                Self::Date(date) => Self::Date(add_months(date, months)?),
+                Self::Datetime(datetime) => Self::Datetime(
+                    datetime.replace_date(add_months(datetime.date(), months)?),
+                ),
+                Self::Time(_) => bail!("cannot add years or months to a time"),
+            };
+        }
+
+        let duration = Duration::from(
+            time::Duration::weeks(weeks)
+                .checked_add(time::Duration::days(days))
+                .and_then(|d| d.checked_add(time::Duration::hours(hours)))
+                .and_then(|d| d.checked_add(time::Duration::minutes(minutes)))
+                .and_then(|d| d.checked_add(time::Duration::seconds(seconds)))
+                .ok_or("duration is out of range")?,
+        );
+
+        Ok(datetime + duration)
+    }
+
     /// The ordinal (day of the year), or `{none}` for times without a date.
     #[func]
     pub fn ordinal(&self) -> Option<u16> {
@@ -516,15 +620,108 @@ impl Sub for Datetime {
 }
 
 /// A format in which a datetime can be displayed.
-pub struct DisplayPattern(Str, format_description::OwnedFormatItem);
+pub struct DisplayPattern(Str, Vec<PatternItem>);
+
+/// A part of a display pattern.
+enum PatternItem {
+    /// A format description that is handled by the `time` crate.
+    Format(format_description::OwnedFormatItem),
+    /// The `[quarter]` component.
+    Quarter,
+    /// The `[day repr:ordinal]` component.
+    OrdinalDay,
+}
+
+impl DisplayPattern {
+    /// Parse a display pattern.
+    ///
+    /// Components that the `time` crate does not support are split off at the
+    /// top level. Everything in between is parsed by the `time` crate.
+    fn parse(pattern: &str) -> StrResult<Vec<PatternItem>> {
+        let mut items = vec![];
+        let mut start = 0;
+        let mut depth = 0usize;
+        let flush = |items: &mut Vec<PatternItem>, start: usize, end: usize| {
+            if start < end {
+                let format = format_description::parse_owned::<2>(&pattern[start..end])
+                    .map_err(|err| {
+                    format_time_invalid_format_description_error(err, start)
+                })?;
+                items.push(PatternItem::Format(format));
+            }
+            Ok::<_, EcoString>(())
+        };
+
+        let mut i = 0;
+        let bytes = pattern.as_bytes();
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'[' if depth == 0 => {
+                    let custom = pattern[i + 1..].find(']').and_then(|len| {
+                        let inner = &pattern[i + 1..i + 1 + len];
+                        let words: Vec<&str> = inner.split_whitespace().collect();
+                        let item = match words.as_slice() {
+                            ["quarter"] => PatternItem::Quarter,
+                            ["day", "repr:ordinal"] => PatternItem::OrdinalDay,
+                            _ => return None,
+                        };
+                        Some((item, i + len + 2))
+                    });
+                    if let Some((item, end)) = custom {
+                        flush(&mut items, start, i)?;
+                        items.push(item);
+                        start = end;
+                        i = end;
+                        continue;
+                    }
+                    depth += 1;
+                }
+                b'[' => depth += 1,
+                b']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            i += 1;
+        }
+
+        flush(&mut items, start, pattern.len())?;
+        Ok(items)
+    }
+}
 
 cast! {
     DisplayPattern,
     self => self.0.into_value(),
     v: Str => {
-        let item = format_description::parse_owned::<2>(&v)
-            .map_err(format_time_invalid_format_description_error)?;
-        Self(v, item)
+        let items = Self::parse(&v)?;
+        Self(v, items)
+    }
+}
+
+/// The error message for components that need information the datetime does
+/// not have.
+const INSUFFICIENT: &str = "failed to format datetime (insufficient information)";
+
+/// Add a number of months to a date, clamping the day to the last day of the
+/// resulting month.
+fn add_months(date: time::Date, months: i64) -> StrResult<time::Date> {
+    let total = i64::from(date.year()) * 12 + i64::from(u8::from(date.month()) - 1);
+    let total = total.checked_add(months).ok_or("date is out of range")?;
+    let year = i32::try_from(total.div_euclid(12)).map_err(|_| "date is out of range")?;
+    let month = Month::try_from(total.rem_euclid(12) as u8 + 1).unwrap();
+    let day = date.day().min(time::util::days_in_year_month(year, month));
+    time::Date::from_calendar_date(year, month, day)
+        .map_err(|_| "date is out of range".into())
+}
+
+/// The English ordinal suffix for a day of the month.
+fn ordinal_suffix(day: u8) -> &'static str {
+    match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
     }
 }
 
@@ -545,35 +742,41 @@ fn format_time_format_error(error: Format) -> EcoString {
 }
 
 /// Format the `InvalidFormatDescription` error of the time crate in an
-/// appropriate way.
+/// appropriate way. The `offset` is added to the indices in the error.
 fn format_time_invalid_format_description_error(
     error: InvalidFormatDescription,
+    offset: usize,
 ) -> EcoString {
     match error {
         InvalidFormatDescription::UnclosedOpeningBracket { index, .. } => {
-            eco_format!("missing closing bracket for bracket at index {}", index)
+            eco_format!("missing closing bracket for bracket at index {}", index + offset)
         }
         InvalidFormatDescription::InvalidComponentName { name, index, .. } => {
-            eco_format!("invalid component name '{}' at index {}", name, index)
+            eco_format!("invalid component name '{}' at index {}", name, index + offset)
         }
         InvalidFormatDescription::InvalidModifier { value, index, .. } => {
-            eco_format!("invalid modifier '{}' at index {}", value, index)
+            eco_format!("invalid modifier '{}' at index {}", value, index + offset)
         }
         InvalidFormatDescription::Expected { what, index, .. } => {
-            eco_format!("expected {} at index {}", what, index)
+            eco_format!("expected {} at index {}", what, index + offset)
         }
         InvalidFormatDescription::MissingComponentName { index, .. } => {
-            eco_format!("expected component name at index {}", index)
+            eco_format!("expected component name at index {}", index + offset)
         }
         InvalidFormatDescription::MissingRequiredModifier { name, index, .. } => {
             eco_format!(
                 "missing required modifier {} for component at index {}",
                 name,
-                index
+                index + offset
             )
         }
         InvalidFormatDescription::NotSupported { context, what, index, .. } => {
-            eco_format!("{} is not supported in {} at index {}", what, context, index)
+            eco_format!(
+                "{} is not supported in {} at index {}",
+                what,
+                context,
+                index + offset
+            )
         }
         err => eco_format!("failed to parse datetime format ({err})"),
     }
@@ -15,7 +15,7 @@ use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
     cast, Args, Content, Dict, FieldAccessError, Func, ParamInfo, Repr, Scope, Selector,
-    StyleChain, Styles, Value,
+    StyleChain, Styles, Target, Value,
 };
 use crate::text::{Lang, Region};
 
@@ -135,6 +135,15 @@ impl Element {
     pub fn local_name(&self, lang: Lang, region: Option<Region>) -> Option<&'static str> {
         (self.0).0.local_name.map(|f| f(lang, region))
     }
+
+    /// The element's local name in the form used in references, if any.
+    pub fn local_reference_name(
+        &self,
+        lang: Lang,
+        region: Option<Region>,
+    ) -> Option<&'static str> {
+        (self.0).0.local_reference_name.map(|f| f(lang, region))
+    }
 }
 
 impl Debug for Element {
@@ -292,6 +301,9 @@ pub struct NativeElementData {
     pub field_from_styles: fn(u8, StyleChain) -> Result<Value, FieldAccessError>,
     /// Gets the localized name for this element (see [`LocalName`][crate::text::LocalName]).
     pub local_name: Option<fn(Lang, Option<Region>) -> &'static str>,
+    /// Gets the localized name for this element in the form used in
+    /// references (see [`LocalName`][crate::text::LocalName]).
+    pub local_reference_name: Option<fn(Lang, Option<Region>) -> &'static str>,
     pub scope: LazyLock<Scope>,
     /// A list of parameter information for each field.
     pub params: LazyLock<Vec<ParamInfo>>,
@@ -322,6 +334,49 @@ pub trait Show {
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content>;
 }
 
+/// A show rule for an element that is implemented natively.
+///
+/// It receives the element to show, which can be downcast with
+/// [`Content::to_packed`].
+/// Its output is realized again, so it must not contain the
+/// element itself unchanged.
+pub type NativeShowRule = fn(&Content, &mut Engine, StyleChain) -> SourceResult<Content>;
+
+/// Show rules that an embedder implements natively, per element and target.
+///
+/// A native rule replaces the element's [built-in show rule](Show) for its
+/// target. User-defined show rules still take precedence over it, just like
+/// over built-in ones. Native rules can also be defined for elements without
+/// a built-in show rule.
+///
+/// Native rules are registered through the [`LibraryBuilder`](crate::LibraryBuilder).
+#[derive(Debug, Default, Clone, Hash)]
+pub struct NativeRuleMap(Vec<(Element, Target, NativeShowRule)>);
+
+impl NativeRuleMap {
+    /// Register a rule for an element and target, replacing any rule that was
+    /// previously registered for the same combination.
+    pub fn register(&mut self, elem: Element, target: Target, rule: NativeShowRule) {
+        match self.0.iter_mut().find(|(e, t, _)| *e == elem && *t == target) {
+            Some(entry) => entry.2 = rule,
+            None => self.0.push((elem, target, rule)),
+        }
+    }
+
+    /// Retrieve the rule for an element and target, if any.
+    pub fn get(&self, elem: Element, target: Target) -> Option<NativeShowRule> {
+        self.0
+            .iter()
+            .find(|(e, t, _)| *e == elem && *t == target)
+            .map(|&(_, _, rule)| rule)
+    }
+
+    /// Whether no rules are registered.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 /// Defines built-in show set rules for an element.
 ///
 /// This is a bit more powerful than a user-defined show-set because it can
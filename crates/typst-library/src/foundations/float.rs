@@ -1,10 +1,11 @@
 use std::num::ParseFloatError;
 
 use ecow::{eco_format, EcoString};
+use typst_utils::Scalar;
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{
-    cast, func, repr, scope, ty, Bytes, Decimal, Endianness, Repr, Str,
+    cast, func, repr, scope, ty, BigInt, Bytes, Decimal, Endianness, Repr, Str,
 };
 use crate::layout::Ratio;
 
@@ -194,6 +195,7 @@ cast! {
     v: f64 => Self(v),
     v: bool => Self(v as i64 as f64),
     v: i64 => Self(v as f64),
+    v: BigInt => Self(v.to_f64()),
     v: Decimal => Self(f64::try_from(v).map_err(|_| eco_format!("invalid float: {}", v))?),
     v: Ratio => Self(v.get()),
     v: Str => Self(
@@ -202,6 +204,12 @@ cast! {
     ),
 }
 
+cast! {
+    Scalar,
+    self => self.get().into_value(),
+    v: f64 => Self::new(v),
+}
+
 fn parse_float(s: EcoString) -> Result<f64, ParseFloatError> {
     s.replace(repr::MINUS_SIGN, "-").parse()
 }
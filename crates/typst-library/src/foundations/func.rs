@@ -213,6 +213,18 @@ impl Func {
         self.params()?.iter().find(|param| param.name == name)
     }
 
+    /// Whether the function has a named parameter with the given name.
+    ///
+    /// An argument sink does not count, so that functions which collect all
+    /// their arguments don't receive arguments they don't know about.
+    pub fn has_named_param(&self, name: &str) -> bool {
+        match &self.repr {
+            Repr::Closure(closure) => closure.has_named_param(name),
+            Repr::With(with) => with.0.has_named_param(name),
+            _ => self.param(name).is_some_and(|param| param.named),
+        }
+    }
+
     /// Get details about the function's return type.
     pub fn returns(&self) -> Option<&'static CastInfo> {
         match &self.repr {
@@ -266,6 +278,21 @@ impl Func {
         }
     }
 
+    /// Decomposes an element function with pre-applied arguments (like
+    /// `{text.with(red)}`) into the element and the arguments, so that it can
+    /// be used as a set rule.
+    pub fn to_set_rule(&self) -> Option<(Element, Args)> {
+        match &self.repr {
+            Repr::Element(elem) => Some((*elem, Args::new::<Value>(self.span, []))),
+            Repr::With(with) => {
+                let (elem, mut args) = with.0.to_set_rule()?;
+                args.items.extend(with.1.items.iter().cloned());
+                Some((elem, args))
+            }
+            _ => None,
+        }
+    }
+
     /// Call the function with the given context and arguments.
     pub fn call<A: IntoArgs>(
         &self,
@@ -358,6 +385,11 @@ impl Func {
     /// == Subsection
     /// === Sub-subsection
     /// ```
+    ///
+    /// Instead of an exact value, a field can also be matched with a
+    /// [regular expression]($regex) or a matcher like
+    /// [`selector.contains`]($selector.contains), as described in the
+    /// [selector documentation]($selector/#matching-fields).
     #[func]
     pub fn where_(
         self,
@@ -527,6 +559,15 @@ impl Closure {
     pub fn name(&self) -> Option<&str> {
         self.node.cast::<ast::Closure>()?.name().map(|ident| ident.as_str())
     }
+
+    /// Whether the closure has a named parameter with the given name.
+    pub fn has_named_param(&self, name: &str) -> bool {
+        let Some(closure) = self.node.cast::<ast::Closure>() else { return false };
+        closure.params().children().any(|param| match param {
+            ast::Param::Named(named) => named.name().as_str() == name,
+            ast::Param::Spread(_) | ast::Param::Pos(_) => false,
+        })
+    }
 }
 
 impl From<Closure> for Func {
@@ -4,7 +4,7 @@ use ecow::{eco_format, EcoString};
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{
-    cast, func, repr, scope, ty, Bytes, Cast, Decimal, Repr, Str, Value,
+    cast, func, repr, scope, ty, BigInt, Bytes, Cast, Decimal, Repr, Str, Value,
 };
 
 /// A whole number.
@@ -18,6 +18,9 @@ use crate::foundations::{
 /// represented as an integer literal, and are instead parsed as a `{float}`.
 /// The minimum integer value can still be obtained through integer arithmetic.
 ///
+/// Arithmetic whose result does not fit into this range fails with an error.
+/// For exact computations with larger numbers, use a [`bigint`] instead.
+///
 /// The number can also be specified as hexadecimal, octal, or binary by
 /// starting it with a zero followed by either `x`, `o`, or `b`.
 ///
@@ -370,6 +373,7 @@ cast! {
     v: bool => Self(v as i64),
     v: f64 => Self(convert_float_to_int(v)?),
     v: Decimal => Self(i64::try_from(v).map_err(|_| eco_format!("number too large"))?),
+    v: BigInt => Self(v.to_i64().ok_or_else(|| eco_format!("number too large"))?),
     v: Str => Self(parse_int(&v).map_err(|_| eco_format!("invalid integer: {}", v))?),
 }
 
@@ -8,7 +8,9 @@ pub mod sys;
 mod args;
 mod array;
 mod auto;
+mod bigint;
 mod bool;
+mod builder;
 mod bytes;
 mod cast;
 mod content;
@@ -40,6 +42,8 @@ mod version;
 pub use self::args::*;
 pub use self::array::*;
 pub use self::auto::*;
+pub use self::bigint::*;
+pub use self::builder::*;
 pub use self::bytes::*;
 pub use self::cast::*;
 pub use self::content::*;
@@ -96,6 +100,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict, features: &Features) {
     global.category(FOUNDATIONS);
     global.define_type::<bool>();
     global.define_type::<i64>();
+    global.define_type::<BigInt>();
     global.define_type::<f64>();
     global.define_type::<Str>();
     global.define_type::<Label>();
@@ -119,6 +124,7 @@ pub(super) fn define(global: &mut Scope, inputs: Dict, features: &Features) {
     global.define_func::<panic>();
     global.define_func::<assert>();
     global.define_func::<eval>();
+    global.define_func::<styled>();
     if features.is_enabled(Feature::Html) {
         global.define_func::<target>();
     }
@@ -2,11 +2,13 @@
 
 use std::cmp::Ordering;
 
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 use typst_utils::Numeric;
 
-use crate::diag::{bail, HintedStrResult, StrResult};
-use crate::foundations::{format_str, Datetime, IntoValue, Regex, Repr, Value};
+use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
+use crate::foundations::{
+    big_operands, format_str, BigInt, Datetime, IntoValue, Regex, Repr, Value,
+};
 use crate::layout::{Alignment, Length, Rel};
 use crate::text::TextElem;
 use crate::visualize::Stroke;
@@ -52,6 +54,7 @@ pub fn pos(value: Value) -> HintedStrResult<Value> {
         Ratio(v) => Ratio(v),
         Relative(v) => Relative(v),
         Fraction(v) => Fraction(v),
+        Dyn(d) if d.is::<BigInt>() => Dyn(d),
         Symbol(_) | Str(_) | Bytes(_) | Content(_) | Array(_) | Dict(_) | Datetime(_) => {
             mismatch!("cannot apply unary '+' to {}", value)
         }
@@ -70,7 +73,12 @@ pub fn pos(value: Value) -> HintedStrResult<Value> {
 pub fn neg(value: Value) -> HintedStrResult<Value> {
     use Value::*;
     Ok(match value {
-        Int(v) => Int(v.checked_neg().ok_or_else(too_large)?),
+        Int(v) => {
+            Int(v.checked_neg().ok_or_else(|| int_too_large(eco_format!("-({v})")))?)
+        }
+        Dyn(d) if d.is::<BigInt>() => {
+            (-d.downcast::<BigInt>().unwrap().clone()).into_value()
+        }
         Float(v) => Float(-v),
         Decimal(v) => Decimal(-v),
         Length(v) => Length(-v),
@@ -87,11 +95,17 @@ pub fn neg(value: Value) -> HintedStrResult<Value> {
 /// Compute the sum of two values.
 pub fn add(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     use Value::*;
+    if let Some((a, b)) = big_operands(&lhs, &rhs) {
+        return Ok(a.checked_add(&b).ok_or_else(too_large)?.into_value());
+    }
+
     Ok(match (lhs, rhs) {
         (a, None) => a,
         (None, b) => b,
 
-        (Int(a), Int(b)) => Int(a.checked_add(b).ok_or_else(too_large)?),
+        (Int(a), Int(b)) => Int(a
+            .checked_add(b)
+            .ok_or_else(|| int_too_large(eco_format!("{a} + {b}")))?),
         (Int(a), Float(b)) => Float(a as f64 + b),
         (Float(a), Int(b)) => Float(a + b as f64),
         (Float(a), Float(b)) => Float(a + b),
@@ -170,8 +184,14 @@ pub fn add(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
 /// Compute the difference of two values.
 pub fn sub(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     use Value::*;
+    if let Some((a, b)) = big_operands(&lhs, &rhs) {
+        return Ok(a.checked_sub(&b).ok_or_else(too_large)?.into_value());
+    }
+
     Ok(match (lhs, rhs) {
-        (Int(a), Int(b)) => Int(a.checked_sub(b).ok_or_else(too_large)?),
+        (Int(a), Int(b)) => Int(a
+            .checked_sub(b)
+            .ok_or_else(|| int_too_large(eco_format!("{a} - {b}")))?),
         (Int(a), Float(b)) => Float(a as f64 - b),
         (Float(a), Int(b)) => Float(a - b as f64),
         (Float(a), Float(b)) => Float(a - b),
@@ -214,8 +234,14 @@ pub fn sub(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
 /// Compute the product of two values.
 pub fn mul(lhs: Value, rhs: Value) -> HintedStrResult<Value> {
     use Value::*;
+    if let Some((a, b)) = big_operands(&lhs, &rhs) {
+        return Ok(a.checked_mul(&b).ok_or_else(too_large)?.into_value());
+    }
+
     Ok(match (lhs, rhs) {
-        (Int(a), Int(b)) => Int(a.checked_mul(b).ok_or_else(too_large)?),
+        (Int(a), Int(b)) => Int(a
+            .checked_mul(b)
+            .ok_or_else(|| int_too_large(eco_format!("{a} * {b}")))?),
         (Int(a), Float(b)) => Float(a as f64 * b),
         (Float(a), Int(b)) => Float(a * b as f64),
         (Float(a), Float(b)) => Float(a * b),
@@ -450,6 +476,12 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
 
         // Some technically different things should compare equal.
         (&Int(i), &Float(f)) | (&Float(f), &Int(i)) => i as f64 == f,
+        (&Int(i), Dyn(d)) | (Dyn(d), &Int(i)) if d.is::<BigInt>() => {
+            d.downcast::<BigInt>().unwrap().cmp_int(i).is_eq()
+        }
+        (&Float(f), Dyn(d)) | (Dyn(d), &Float(f)) if d.is::<BigInt>() => {
+            d.downcast::<BigInt>().unwrap().cmp_float(f) == Some(Ordering::Equal)
+        }
         (&Int(i), &Decimal(d)) | (&Decimal(d), &Int(i)) => {
             crate::foundations::Decimal::from(i) == d
         }
@@ -481,6 +513,21 @@ pub fn compare(lhs: &Value, rhs: &Value) -> StrResult<Ordering> {
         (Str(a), Str(b)) => a.cmp(b),
 
         // Some technically different things should be comparable.
+        (Int(a), Dyn(b)) if b.is::<BigInt>() => {
+            b.downcast::<BigInt>().unwrap().cmp_int(*a).reverse()
+        }
+        (Dyn(a), Int(b)) if a.is::<BigInt>() => {
+            a.downcast::<BigInt>().unwrap().cmp_int(*b)
+        }
+        (Dyn(a), Dyn(b)) if a.is::<BigInt>() && b.is::<BigInt>() => {
+            a.downcast::<BigInt>().unwrap().cmp(b.downcast::<BigInt>().unwrap())
+        }
+        (Float(a), Dyn(b)) if b.is::<BigInt>() => {
+            try_cmp_big_float(b.downcast::<BigInt>().unwrap(), *a)?.reverse()
+        }
+        (Dyn(a), Float(b)) if a.is::<BigInt>() => {
+            try_cmp_big_float(a.downcast::<BigInt>().unwrap(), *b)?
+        }
         (Int(a), Float(b)) => try_cmp_values(&(*a as f64), b)?,
         (Float(a), Int(b)) => try_cmp_values(a, &(*b as f64))?,
         (Int(a), Decimal(b)) => crate::foundations::Decimal::from(*a).cmp(b),
@@ -504,6 +551,12 @@ fn try_cmp_values<T: PartialOrd + Repr>(a: &T, b: &T) -> StrResult<Ordering> {
         .ok_or_else(|| eco_format!("cannot compare {} with {}", a.repr(), b.repr()))
 }
 
+/// Try to compare a big integer with a float.
+fn try_cmp_big_float(a: &BigInt, b: f64) -> StrResult<Ordering> {
+    a.cmp_float(b)
+        .ok_or_else(|| eco_format!("cannot compare {} with {}", a.repr(), b.repr()))
+}
+
 /// Try to compare two datetimes.
 fn try_cmp_datetimes(a: &Datetime, b: &Datetime) -> StrResult<Ordering> {
     a.partial_cmp(b)
@@ -565,3 +618,14 @@ pub fn contains(lhs: &Value, rhs: &Value) -> Option<bool> {
 fn too_large() -> &'static str {
     "value is too large"
 }
+
+/// The error when the result of integer arithmetic does not fit into an
+/// integer.
+#[cold]
+fn int_too_large(operation: EcoString) -> HintedString {
+    HintedString::new(too_large().into())
+        .with_hint(eco_format!(
+            "the result of `{operation}` does not fit into a 64-bit integer"
+        ))
+        .with_hint("use `bigint` to compute with arbitrarily large integers")
+}
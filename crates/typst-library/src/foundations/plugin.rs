@@ -8,8 +8,8 @@ use wasmi::{AsContext, AsContextMut};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, repr, scope, ty, Bytes};
-use crate::World;
+use crate::foundations::{func, repr, scope, ty, Bytes, Target};
+use crate::{Features, World};
 
 /// A WebAssembly plugin.
 ///
@@ -105,6 +105,37 @@ use crate::World;
 ///   immediately after this function returns. If the message should be
 ///   interpreted as an error message, it should be encoded as UTF-8.
 ///
+/// Additionally, plugin modules may import the following function. Plugins
+/// that don't import it are unaffected by its existence.
+///
+/// - `(import "typst_env" "typst_host_info" (func (param i32 i32) (result i32)))`
+///
+///   Writes information about the current compilation into a plugin-allocated
+///   buffer. The first parameter is a pointer to the buffer (`ptr`) and the
+///   second one is its capacity (`len`). The function returns the length of
+///   the information in bytes. It is only written if it fits into the buffer,
+///   so a plugin can first call the function with a capacity of zero to find
+///   out how large the buffer must be.
+///
+///   The information is a UTF-8 encoded JSON object with the following keys:
+///
+///   - `version`: The version of this object's format, currently `{1}`. New
+///     keys may be added without changing it.
+///   - `typst`: The version of Typst, like `{"0.13.0"}`.
+///   - `target`: The [compilation target]($target) at the call site of the
+///     plugin function, either `{"paged"}` or `{"html"}`. This is only known
+///     within [context]($context) and `{null}` otherwise.
+///   - `features`: The names of the enabled in-development features, like
+///     `{"html"}`.
+///
+///   ```typ
+///   #let p = plugin("renderer.wasm")
+///   #let render(code) = context p.render(bytes(code))
+///   ```
+///
+///   Since the information is part of the call, a plugin function that
+///   behaves differently per target is still pure.
+///
 /// # Resources
 /// For more resources, check out the
 /// [wasm-minimal-protocol repository](https://github.com/astrale-sharp/wasm-minimal-protocol).
@@ -132,6 +163,25 @@ struct Repr {
 /// Owns all data associated with the WebAssembly module.
 type Store = wasmi::Store<StoreData>;
 
+/// Limits on the resources a plugin may use during a single call.
+///
+/// All limits are disabled by default. They can be configured by embedders
+/// that compile untrusted documents through
+/// [`LibraryBuilder::with_plugin_limits`](crate::LibraryBuilder::with_plugin_limits).
+///
+/// There is no wall-clock timeout because the WebAssembly runtime cannot
+/// interrupt a running function. Instead, the fuel limit bounds the time a
+/// plugin can take in a deterministic way.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PluginLimits {
+    /// The maximum size of a plugin's linear memory in bytes. Growing the
+    /// memory beyond this size fails the plugin call.
+    pub max_memory: Option<usize>,
+    /// The maximum amount of fuel a single plugin call may consume. Roughly,
+    /// one unit of fuel is consumed per executed WebAssembly instruction.
+    pub max_fuel: Option<u64>,
+}
+
 /// If there was an error reading/writing memory, keep the offset + length to
 /// display an error message.
 struct MemoryError {
@@ -139,12 +189,87 @@ struct MemoryError {
     length: u32,
     write: bool,
 }
+/// Information about the compilation that a plugin can query through the
+/// `typst_host_info` import.
+#[derive(Debug, Default, Clone, Hash)]
+pub struct PluginHostInfo {
+    /// The compilation target at the call site, if it is known.
+    pub target: Option<Target>,
+    /// The enabled in-development features.
+    pub features: Features,
+}
+
+impl PluginHostInfo {
+    /// The version of the JSON format.
+    const VERSION: u32 = 1;
+
+    /// Encode the information as JSON.
+    fn to_json(&self) -> Vec<u8> {
+        let target = self.target.map(|target| match target {
+            Target::Paged => "paged",
+            Target::Html => "html",
+        });
+        let json = serde_json::json!({
+            "version": Self::VERSION,
+            "typst": env!("CARGO_PKG_VERSION"),
+            "target": target,
+            "features": self.features.names().collect::<Vec<_>>(),
+        });
+        serde_json::to_vec(&json).unwrap()
+    }
+}
+
 /// The persistent store data used for communication between store and host.
 #[derive(Default)]
 struct StoreData {
     args: Vec<Bytes>,
     output: Vec<u8>,
+    host_info: Vec<u8>,
     memory_error: Option<MemoryError>,
+    limiter: MemoryLimiter,
+}
+
+/// Enforces the memory limit of a plugin and records when it was hit.
+#[derive(Default)]
+struct MemoryLimiter {
+    max: Option<usize>,
+    exceeded: bool,
+}
+
+impl wasmi::ResourceLimiter for MemoryLimiter {
+    fn memory_growing(
+        &mut self,
+        _: usize,
+        desired: usize,
+        _: Option<usize>,
+    ) -> Result<bool, wasmi::errors::MemoryError> {
+        if self.max.is_some_and(|max| desired > max) {
+            self.exceeded = true;
+            return Err(wasmi::errors::MemoryError::OutOfBoundsGrowth);
+        }
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _: u32,
+        _: u32,
+        _: Option<u32>,
+    ) -> Result<bool, wasmi::errors::TableError> {
+        Ok(true)
+    }
+
+    fn instances(&self) -> usize {
+        usize::MAX
+    }
+
+    fn tables(&self) -> usize {
+        usize::MAX
+    }
+
+    fn memories(&self) -> usize {
+        usize::MAX
+    }
 }
 
 #[scope]
@@ -171,7 +296,9 @@ impl Plugin {
     #[comemo::memoize]
     #[typst_macros::time(name = "load plugin")]
     pub fn new(bytes: Bytes) -> StrResult<Plugin> {
-        let engine = wasmi::Engine::default();
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, bytes.as_slice())
             .map_err(|err| format!("failed to load WebAssembly module ({err})"))?;
 
@@ -190,8 +317,13 @@ impl Plugin {
                 wasm_minimal_protocol_write_args_to_buffer,
             )
             .unwrap();
+        linker
+            .func_wrap("typst_env", "typst_host_info", typst_host_info)
+            .unwrap();
 
         let mut store = Store::new(&engine, StoreData::default());
+        store.limiter(|data| &mut data.limiter);
+        store.set_fuel(u64::MAX).unwrap();
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre_instance| pre_instance.start(&mut store))
@@ -217,10 +349,18 @@ impl Plugin {
         Ok(Plugin(Arc::new(Repr { bytes, functions, store: Mutex::new(store) })))
     }
 
-    /// Call the plugin function with the given `name`.
+    /// Call the plugin function with the given `name`, enforcing the given
+    /// resource limits. The plugin can query the `host` information while it
+    /// runs.
     #[comemo::memoize]
     #[typst_macros::time(name = "call plugin")]
-    pub fn call(&self, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    pub fn call(
+        &self,
+        name: &str,
+        args: Vec<Bytes>,
+        limits: PluginLimits,
+        host: &PluginHostInfo,
+    ) -> StrResult<Bytes> {
         // Find the function with the given name.
         let func = self
             .0
@@ -264,11 +404,29 @@ impl Plugin {
 
         // Store the input data.
         store.data_mut().args = args;
+        store.data_mut().host_info = host.to_json();
+
+        // Apply the limits.
+        store.data_mut().limiter =
+            MemoryLimiter { max: limits.max_memory, exceeded: false };
+        store.set_fuel(limits.max_fuel.unwrap_or(u64::MAX)).unwrap();
 
         // Call the function.
         let mut code = wasmi::Val::I32(-1);
-        func.call(store.as_context_mut(), &lengths, std::slice::from_mut(&mut code))
-            .map_err(|err| eco_format!("plugin panicked: {err}"))?;
+        let result =
+            func.call(store.as_context_mut(), &lengths, std::slice::from_mut(&mut code));
+        if let Err(err) = result {
+            if let Some(max) = limits.max_memory.filter(|_| store.data().limiter.exceeded)
+            {
+                bail!("plugin exceeded its memory limit of {}", format_bytes(max));
+            }
+            if let Some(max) =
+                limits.max_fuel.filter(|_| store.get_fuel().ok() == Some(0))
+            {
+                bail!("plugin exceeded its fuel limit of {max}");
+            }
+            bail!("plugin panicked: {err}");
+        }
         if let Some(MemoryError { offset, length, write }) =
             store.data_mut().memory_error.take()
         {
@@ -326,6 +484,16 @@ impl Hash for Plugin {
     }
 }
 
+/// Format a number of bytes for an error message.
+fn format_bytes(bytes: usize) -> EcoString {
+    const MIB: usize = 1024 * 1024;
+    if bytes % MIB == 0 {
+        eco_format!("{} MiB", bytes / MIB)
+    } else {
+        eco_format!("{bytes} bytes")
+    }
+}
+
 /// Write the arguments to the plugin function into the plugin's memory.
 fn wasm_minimal_protocol_write_args_to_buffer(
     mut caller: wasmi::Caller<StoreData>,
@@ -363,3 +531,74 @@ fn wasm_minimal_protocol_send_result_to_host(
     }
     caller.data_mut().output = buffer;
 }
+
+/// Writes information about the compilation into the plugin's memory if it
+/// fits into the given capacity and returns its length.
+fn typst_host_info(mut caller: wasmi::Caller<StoreData>, ptr: u32, len: u32) -> u32 {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let info = std::mem::take(&mut caller.data_mut().host_info);
+    let length = info.len() as u32;
+    if length <= len && memory.write(&mut caller, ptr as usize, &info).is_err() {
+        caller.data_mut().memory_error =
+            Some(MemoryError { offset: ptr, length, write: true });
+    }
+    caller.data_mut().host_info = info;
+    length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module that exports its memory and the functions `spin`, which loops
+    /// forever, and `grow`, which grows the memory by 1 MiB in a loop.
+    #[rustfmt::skip]
+    const GREEDY: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Type section: () -> i32.
+        0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f,
+        // Function section: two functions of type 0.
+        0x03, 0x03, 0x02, 0x00, 0x00,
+        // Memory section: one memory with one page.
+        0x05, 0x03, 0x01, 0x00, 0x01,
+        // Export section: `memory`, `spin`, and `grow`.
+        0x07, 0x18, 0x03,
+        0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00,
+        0x04, b's', b'p', b'i', b'n', 0x00, 0x00,
+        0x04, b'g', b'r', b'o', b'w', 0x00, 0x01,
+        // Code section.
+        0x0a, 0x1a, 0x02,
+        // spin: loop { br 0 }; i32.const 0
+        0x09, 0x00, 0x03, 0x40, 0x0c, 0x00, 0x0b, 0x41, 0x00, 0x0b,
+        // grow: loop { drop(memory.grow(16)); br 0 }; i32.const 0
+        0x0e, 0x00, 0x03, 0x40, 0x41, 0x10, 0x40, 0x00, 0x1a, 0x0c, 0x00, 0x0b,
+        0x41, 0x00, 0x0b,
+    ];
+
+    #[test]
+    fn test_plugin_fuel_limit() {
+        let plugin = Plugin::new(Bytes::from_static(GREEDY)).unwrap();
+        let limits = PluginLimits { max_fuel: Some(10_000), ..Default::default() };
+        assert_eq!(
+            plugin
+                .call("spin", vec![], limits, &PluginHostInfo::default())
+                .unwrap_err(),
+            "plugin exceeded its fuel limit of 10000",
+        );
+    }
+
+    #[test]
+    fn test_plugin_memory_limit() {
+        let plugin = Plugin::new(Bytes::from_static(GREEDY)).unwrap();
+        let limits = PluginLimits {
+            max_memory: Some(4 * 1024 * 1024),
+            ..Default::default()
+        };
+        assert_eq!(
+            plugin
+                .call("grow", vec![], limits, &PluginHostInfo::default())
+                .unwrap_err(),
+            "plugin exceeded its memory limit of 4 MiB",
+        );
+    }
+}
@@ -1,7 +1,6 @@
 //! Debug representation of values.
 
 use ecow::{eco_format, EcoString};
-use typst_utils::round_with_precision;
 
 use crate::foundations::{func, Str, Value};
 
@@ -78,28 +77,37 @@ pub fn format_int_with_base(mut n: i64, base: i64) -> EcoString {
 ///
 /// The returned string is always valid Typst code. As such, it might not be a
 /// float literal. For example, it may return `"float.inf"`.
+///
+/// Without a unit, very large and very small magnitudes are written in
+/// scientific notation if `force_separator` is set, like `1e22`. With a
+/// precision, the value is rounded like in [`display_float_with_digits`] and
+/// trailing zeros are omitted.
 pub fn format_float(
-    mut value: f64,
+    value: f64,
     precision: Option<u8>,
     force_separator: bool,
     unit: &str,
 ) -> EcoString {
-    if let Some(p) = precision {
-        value = round_with_precision(value, p as i16);
-    }
-    // Debug for f64 always prints a decimal separator, while Display only does
-    // when necessary.
     let unit_multiplication = if unit.is_empty() { "" } else { " * 1" };
     if value.is_nan() {
-        eco_format!("float.nan{unit_multiplication}{unit}")
+        return eco_format!("float.nan{unit_multiplication}{unit}");
     } else if value.is_infinite() {
         let sign = if value < 0.0 { "-" } else { "" };
-        eco_format!("{sign}float.inf{unit_multiplication}{unit}")
-    } else if force_separator {
-        eco_format!("{value:?}{unit}")
+        return eco_format!("{sign}float.inf{unit_multiplication}{unit}");
+    }
+
+    let digits = FloatDigits::rounded(value, precision);
+    let mut buf = EcoString::new();
+    if value.is_sign_negative() {
+        buf.push('-');
+    }
+    if force_separator && digits.is_extreme() {
+        digits.write_scientific(&mut buf);
     } else {
-        eco_format!("{value}{unit}")
+        digits.write_positional(&mut buf, force_separator as usize);
     }
+    buf.push_str(unit);
+    buf
 }
 
 /// Converts a float to a string representation with a precision of three
@@ -117,15 +125,189 @@ pub fn format_float_with_unit(value: f64, unit: &str) -> EcoString {
 
 /// Converts a float to a string that can be used to display the float as text.
 pub fn display_float(value: f64) -> EcoString {
+    display_float_with_digits(value, None)
+}
+
+/// Converts a float to a string that can be used to display the float as text,
+/// optionally with a fixed number of digits after the decimal separator.
+///
+/// The float is never written in scientific notation. Without `digits`, it is
+/// written with the shortest digits that parse back to the same float. With
+/// `digits`, the exact value of the float is rounded to the given number of
+/// fractional digits and padded with zeros. A remainder of exactly one half is
+/// rounded away from zero. Since the rounding is based on the exact value,
+/// `2.675` becomes `2.67`: The closest float to it is slightly smaller.
+pub fn display_float_with_digits(value: f64, digits: Option<u8>) -> EcoString {
     if value.is_nan() {
-        "NaN".into()
+        return "NaN".into();
     } else if value.is_infinite() {
         let sign = if value < 0.0 { MINUS_SIGN } else { "" };
-        eco_format!("{sign}∞")
-    } else if value < 0.0 {
-        eco_format!("{}{}", MINUS_SIGN, value.abs())
-    } else {
-        eco_format!("{}", value.abs())
+        return eco_format!("{sign}∞");
+    }
+
+    let decimal = FloatDigits::rounded(value, digits);
+
+    let mut buf = EcoString::new();
+    if value < 0.0 && !decimal.is_zero() {
+        buf.push_str(MINUS_SIGN);
+    }
+    decimal.write_positional(&mut buf, digits.unwrap_or(0).into());
+    buf
+}
+
+/// The shortest decimal digits of a finite float's magnitude that parse back
+/// to the same float.
+///
+/// All float formatting goes through this, so that floats have the same
+/// digits in every notation. The digits come from the standard library's
+/// shortest round-trip algorithm, which only uses integer arithmetic and thus
+/// yields the same result on every platform.
+struct FloatDigits {
+    /// The significant digits as ASCII, without leading and trailing zeros.
+    /// Empty for zero.
+    digits: Vec<u8>,
+    /// The position of the decimal point relative to the first digit. May be
+    /// negative or larger than the number of digits.
+    point: i32,
+}
+
+impl FloatDigits {
+    /// Determine the digits of a finite float.
+    fn new(value: f64) -> Self {
+        // Scientific notation has exactly one digit before the point, so the
+        // exponent directly yields the position of the point.
+        let formatted = format!("{:e}", value.abs());
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mut digits: Vec<u8> =
+            mantissa.bytes().filter(|&b| b.is_ascii_digit()).collect();
+        while digits.last() == Some(&b'0') {
+            digits.pop();
+        }
+
+        let point =
+            if digits.is_empty() { 0 } else { exponent.parse::<i32>().unwrap_or(0) + 1 };
+
+        Self { digits, point }
+    }
+
+    /// Determine the digits of a finite float, rounded to the given number of
+    /// fractional digits if any.
+    ///
+    /// This is the single rounding rule for all float formatting: The exact
+    /// value of the float is rounded and a remainder of exactly one half is
+    /// rounded away from zero.
+    fn rounded(value: f64, fractional: Option<u8>) -> Self {
+        match fractional {
+            Some(n) => {
+                let mut digits = Self::exact(value);
+                digits.round(n);
+                digits
+            }
+            None => Self::new(value),
+        }
+    }
+
+    /// Determine all digits of the exact value of a finite float, rather than
+    /// the shortest digits that identify it.
+    fn exact(value: f64) -> Self {
+        // Every finite float is a multiple of 2^-1074 and thus has at most
+        // 1074 fractional digits, which the standard library writes exactly.
+        let formatted = format!("{:.1074}", value.abs());
+        let (int, fractional) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        let all = int.bytes().chain(fractional.bytes());
+        let leading = all.clone().take_while(|&b| b == b'0').count();
+        let mut digits: Vec<u8> = all.skip(leading).collect();
+        while digits.last() == Some(&b'0') {
+            digits.pop();
+        }
+
+        let point = if digits.is_empty() { 0 } else { int.len() as i32 - leading as i32 };
+
+        Self { digits, point }
+    }
+
+    /// Whether the value is zero.
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Whether the magnitude is so large or small that it is better written in
+    /// scientific notation.
+    fn is_extreme(&self) -> bool {
+        !self.is_zero() && (self.point > 16 || self.point < -3)
+    }
+
+    /// Round to the given number of fractional digits, with ties away from
+    /// zero.
+    fn round(&mut self, fractional: u8) {
+        let keep = self.point + i32::from(fractional);
+        if keep < 0 {
+            self.digits.clear();
+        } else if (keep as usize) < self.digits.len() {
+            let keep = keep as usize;
+            let up = self.digits[keep] >= b'5';
+            self.digits.truncate(keep);
+            if up {
+                match self.digits.iter().rposition(|&d| d != b'9') {
+                    Some(i) => {
+                        self.digits[i] += 1;
+                        self.digits.truncate(i + 1);
+                    }
+                    None => {
+                        self.digits.clear();
+                        self.digits.push(b'1');
+                        self.point += 1;
+                    }
+                }
+            }
+            while self.digits.last() == Some(&b'0') {
+                self.digits.pop();
+            }
+        }
+
+        if self.digits.is_empty() {
+            self.point = 0;
+        }
+    }
+
+    /// Write the digits in positional notation, with at least the given number
+    /// of fractional digits.
+    fn write_positional(&self, buf: &mut EcoString, min_fractional: usize) {
+        let digit = |i: i32| {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| self.digits.get(i))
+                .map_or('0', |&d| d as char)
+        };
+
+        if self.point <= 0 {
+            buf.push('0');
+        }
+        for i in 0..self.point {
+            buf.push(digit(i));
+        }
+
+        let fractional = (self.digits.len() as i32 - self.point).max(0) as usize;
+        let fractional = fractional.max(min_fractional);
+        if fractional > 0 {
+            buf.push('.');
+            for i in 0..fractional {
+                buf.push(digit(self.point + i as i32));
+            }
+        }
+    }
+
+    /// Write the digits in scientific notation, like `1.5e-7`.
+    fn write_scientific(&self, buf: &mut EcoString) {
+        let (first, rest) = self.digits.split_first().unwrap_or((&b'0', &[]));
+        buf.push(*first as char);
+        if !rest.is_empty() {
+            buf.push('.');
+            for &d in rest {
+                buf.push(d as char);
+            }
+        }
+        buf.push_str(&eco_format!("e{}", self.point - 1));
     }
 }
 
@@ -232,4 +414,80 @@ mod tests {
         assert_eq!(&format_int_with_base(i64::MAX, 36), "1y2p0ij32e8e7");
         assert_eq!(&format_int_with_base(i64::MIN, 36), "\u{2212}1y2p0ij32e8e8");
     }
+
+    #[test]
+    fn test_float_shortest_round_trip() {
+        let values = [
+            0.1 + 0.2,
+            1.0 / 3.0,
+            -123.456,
+            100.0,
+            0.0,
+            -0.0,
+            1e15,
+            1e16,
+            1e22,
+            1e-4,
+            1e-5,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+        ];
+
+        for v in values {
+            let repr = format_float(v, None, true, "");
+            assert_eq!(repr, format!("{v:?}"));
+            assert_eq!(repr.parse::<f64>(), Ok(v));
+
+            let display = display_float(v).replace(MINUS_SIGN, "-");
+            let sign = if v < 0.0 { "-" } else { "" };
+            assert_eq!(display, format!("{sign}{}", v.abs()));
+            assert_eq!(display.parse::<f64>(), Ok(v));
+        }
+
+        assert_eq!(&format_float(0.1 + 0.2, None, true, ""), "0.30000000000000004");
+        assert_eq!(&format_float(1e22, None, true, ""), "1e22");
+        assert_eq!(&display_float(1e22), "10000000000000000000000");
+        assert_eq!(&format_float(5e-324, None, true, ""), "5e-324");
+        assert_eq!(display_float(5e-324), format!("0.{}5", "0".repeat(323)));
+    }
+
+    #[test]
+    fn test_float_digits() {
+        let round = |v, digits| display_float_with_digits(v, Some(digits));
+        assert_eq!(&round(0.1 + 0.2, 2), "0.30");
+        assert_eq!(&round(0.1 + 0.2, 20), "0.30000000000000004441");
+        assert_eq!(&round(2.675, 2), "2.67");
+        assert_eq!(&round(0.125, 2), "0.13");
+        assert_eq!(&round(-0.125, 2), "\u{2212}0.13");
+        assert_eq!(&round(9.995, 2), "9.99");
+        assert_eq!(&round(9.9951, 2), "10.00");
+        assert_eq!(&round(0.5, 0), "1");
+        assert_eq!(&round(0.05, 0), "0");
+        assert_eq!(&round(-0.004, 2), "0.00");
+        assert_eq!(&round(1e22, 1), "10000000000000000000000.0");
+        assert_eq!(&round(5e-324, 3), "0.000");
+        assert_eq!(&format_float(0.1 + 0.2, Some(2), false, "pt"), "0.3pt");
+        assert_eq!(&format_float(-1.005, Some(2), false, "pt"), "-1pt");
+    }
+
+    #[test]
+    fn test_float_rounding_is_shared() {
+        // Representations and displayed text round the same way, the former
+        // just omit trailing zeros.
+        let values = [1.005, -1.005, 2.675, 0.125, -9.995, 0.1 + 0.2, 1e22, 5e-324];
+        for v in values {
+            for digits in [0, 1, 2, 3, 20] {
+                let repr = format_float(v, Some(digits), false, "");
+                let display =
+                    display_float_with_digits(v, Some(digits)).replace(MINUS_SIGN, "-");
+                let trimmed = if display.contains('.') {
+                    display.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    &display
+                };
+                assert_eq!(repr, trimmed, "{v} with {digits} digits");
+            }
+        }
+    }
 }
@@ -10,7 +10,7 @@ use typst_syntax::ast::{self, AstNode};
 use typst_syntax::Span;
 use typst_utils::Static;
 
-use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
+use crate::diag::{bail, Deprecation, HintedStrResult, HintedString, StrResult};
 use crate::foundations::{
     Element, Func, IntoValue, Module, NativeElement, NativeFunc, NativeFuncData,
     NativeType, Type, Value,
@@ -48,36 +48,43 @@ impl<'a> Scopes<'a> {
 
     /// Try to access a variable immutably.
     pub fn get(&self, var: &str) -> HintedStrResult<&Value> {
-        std::iter::once(&self.top)
-            .chain(self.scopes.iter().rev())
-            .find_map(|scope| scope.get(var))
-            .or_else(|| {
-                self.base.and_then(|base| match base.global.scope().get(var) {
-                    Some(value) => Some(value),
-                    None if var == "std" => Some(&base.std),
-                    None => None,
-                })
-            })
-            .ok_or_else(|| unknown_variable(var))
+        self.get_with_deprecation(var, false).map(|(value, _)| value)
     }
 
     /// Try to access a variable immutably in math.
     pub fn get_in_math(&self, var: &str) -> HintedStrResult<&Value> {
+        self.get_with_deprecation(var, true).map(|(value, _)| value)
+    }
+
+    /// Try to access a variable immutably, in code or in math, together with
+    /// the deprecation of its definition, if it is deprecated.
+    pub fn get_with_deprecation(
+        &self,
+        var: &str,
+        in_math: bool,
+    ) -> HintedStrResult<(&Value, Option<Deprecation>)> {
         std::iter::once(&self.top)
             .chain(self.scopes.iter().rev())
-            .find_map(|scope| scope.get(var))
+            .find_map(|scope| scope.get_with_deprecation(var))
             .or_else(|| {
-                self.base.and_then(|base| match base.math.scope().get(var) {
-                    Some(value) => Some(value),
-                    None if var == "std" => Some(&base.std),
+                let base = self.base?;
+                let module = if in_math { &base.math } else { &base.global };
+                match module.scope().get_with_deprecation(var) {
+                    Some(found) => Some(found),
+                    None if var == "std" => Some((&base.std, None)),
                     None => None,
-                })
+                }
             })
             .ok_or_else(|| {
-                unknown_variable_math(
-                    var,
-                    self.base.is_some_and(|base| base.global.scope().get(var).is_some()),
-                )
+                if in_math {
+                    unknown_variable_math(
+                        var,
+                        self.base
+                            .is_some_and(|base| base.global.scope().get(var).is_some()),
+                    )
+                } else {
+                    unknown_variable(var)
+                }
             })
     }
 
@@ -257,6 +264,17 @@ impl Scope {
         self.define(module.name().clone(), module);
     }
 
+    /// Mark an existing definition as deprecated.
+    ///
+    /// Uses of the definition are then reported with a warning.
+    #[track_caller]
+    pub fn deprecate(&mut self, var: &str, deprecation: Deprecation) {
+        match self.map.get_mut(var) {
+            Some(slot) => slot.deprecation = Some(deprecation),
+            None => panic!("cannot deprecate undefined variable: {var}"),
+        }
+    }
+
     /// Try to access a variable immutably.
     pub fn get(&self, var: &str) -> Option<&Value> {
         self.map.get(var).map(Slot::read)
@@ -280,6 +298,15 @@ impl Scope {
         self.map.get(var)?.category
     }
 
+    /// Try to access a variable immutably, together with the deprecation of
+    /// its definition, if it is deprecated.
+    pub fn get_with_deprecation(
+        &self,
+        var: &str,
+    ) -> Option<(&Value, Option<Deprecation>)> {
+        self.map.get(var).map(|slot| (slot.read(), slot.deprecation))
+    }
+
     /// Iterate over all definitions.
     pub fn iter(&self) -> impl Iterator<Item = (&EcoString, &Value, Span)> {
         self.map.iter().map(|(k, v)| (k, v.read(), v.span))
@@ -326,6 +353,8 @@ struct Slot {
     span: Span,
     /// The category of the slot.
     category: Option<Category>,
+    /// Whether the definition is deprecated.
+    deprecation: Option<Deprecation>,
 }
 
 /// The different kinds of slots.
@@ -349,7 +378,7 @@ pub enum Capturer {
 impl Slot {
     /// Create a new slot.
     fn new(value: Value, span: Span, kind: Kind, category: Option<Category>) -> Self {
-        Self { value, span, kind, category }
+        Self { value, span, kind, category, deprecation: None }
     }
 
     /// Read the value.
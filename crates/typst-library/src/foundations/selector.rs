@@ -69,6 +69,26 @@ pub use crate::__select_where as select_where;
 /// == So will this
 /// === But this will not.
 /// ```
+///
+/// # Matching fields
+/// When selecting elements with [`where`]($function.where), fields are
+/// compared for equality with the given values by default. Some values match
+/// more loosely instead:
+/// - A [regular expression]($regex) matches fields whose plain text it matches.
+/// - [`selector.contains`]($selector.contains) matches fields whose plain
+///   text contains a string.
+/// - [`selector.one-of`]($selector.one-of) matches fields that match any of a
+///   number of values.
+///
+/// ```example
+/// #show heading.where(body: selector.contains("Appendix")): set text(red)
+/// #show link.where(dest: regex("^https://typst")): underline
+///
+/// = Introduction
+/// = Appendix A
+/// #link("https://typst.app")[Typst] \
+/// #link("https://example.com")[Example]
+/// ```
 #[ty(scope, cast)]
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Selector {
@@ -125,8 +145,10 @@ impl Selector {
         match self {
             Self::Elem(element, dict) => {
                 target.elem() == *element
-                    && dict.iter().flat_map(|dict| dict.iter()).all(|(id, value)| {
-                        target.get(*id, styles).as_ref().ok() == Some(value)
+                    && dict.iter().flat_map(|dict| dict.iter()).all(|(id, pattern)| {
+                        target
+                            .get(*id, styles)
+                            .is_ok_and(|field| field_matches(&field, pattern))
                     })
             }
             Self::Label(label) => target.label() == Some(*label),
@@ -159,6 +181,53 @@ impl Selector {
         target
     }
 
+    /// Matches fields whose plain text contains the given string, for use
+    /// with [`where`]($function.where).
+    ///
+    /// Only fields holding text, like strings, symbols, labels, and content,
+    /// can match. Of content, only the plain text is considered, without any
+    /// formatting.
+    ///
+    /// ```example
+    /// #show heading.where(
+    ///   body: selector.contains("Appendix"),
+    /// ): set heading(numbering: "A.")
+    ///
+    /// = Appendix
+    /// = Appendix
+    /// ```
+    #[func]
+    pub fn contains(
+        /// The string to search for.
+        text: Str,
+    ) -> Matcher {
+        Matcher::Contains(text.into())
+    }
+
+    /// Matches fields that match any of the given values, for use with
+    /// [`where`]($function.where).
+    ///
+    /// The values are matched like in `where` itself, so they can also be
+    /// regular expressions or other matchers.
+    ///
+    /// ```example
+    /// #figure(table[A], caption: [Table])
+    /// #figure(raw("B"), caption: [Code])
+    /// #figure(image("glacier.jpg", width: 20%), caption: [Image])
+    ///
+    /// #context query(
+    ///   figure.where(kind: selector.one-of(table, raw))
+    /// ).len()
+    /// ```
+    #[func]
+    pub fn one_of(
+        /// The values to match against.
+        #[variadic]
+        values: Vec<Value>,
+    ) -> Matcher {
+        Matcher::OneOf(values.into_iter().collect())
+    }
+
     /// Selects all elements that match this or any of the other selectors.
     #[func]
     pub fn or(
@@ -282,6 +351,81 @@ cast! {
     location: Location => Self::Location(location),
 }
 
+/// A pattern for the value of a field in a [`where`]($function.where)
+/// selector that matches other values than just equal ones.
+///
+/// Matchers are created with [`selector.contains`]($selector.contains) and
+/// [`selector.one-of`]($selector.one-of).
+#[ty]
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Matcher {
+    /// Matches values whose plain text contains the string.
+    Contains(EcoString),
+    /// Matches values that match any of the patterns.
+    OneOf(EcoVec<Value>),
+}
+
+impl Matcher {
+    /// Whether the value of a field matches.
+    pub fn matches(&self, field: &Value) -> bool {
+        match self {
+            Self::Contains(text) => {
+                field_text(field).is_some_and(|field| field.contains(text.as_str()))
+            }
+            Self::OneOf(patterns) => {
+                patterns.iter().any(|pattern| field_matches(field, pattern))
+            }
+        }
+    }
+}
+
+impl Repr for Matcher {
+    fn repr(&self) -> EcoString {
+        match self {
+            Self::Contains(text) => eco_format!("selector.contains({})", text.repr()),
+            Self::OneOf(patterns) => {
+                let pieces: Vec<_> = patterns.iter().map(Value::repr).collect();
+                eco_format!("selector.one-of{}", repr::pretty_array_like(&pieces, false))
+            }
+        }
+    }
+}
+
+/// Whether the value of a field matches a value from a `where` selector.
+///
+/// Regular expressions and matchers match loosely, all other values must be
+/// equal.
+fn field_matches(field: &Value, pattern: &Value) -> bool {
+    if let Value::Dyn(dynamic) = pattern {
+        if let Some(matcher) = dynamic.downcast::<Matcher>() {
+            return matcher.matches(field);
+        } else if let Some(regex) = dynamic.downcast::<Regex>() {
+            return field_text(field).is_some_and(|text| regex.is_match(&text));
+        }
+    }
+    field == pattern
+}
+
+/// The plain text of a field, if it holds text.
+fn field_text(field: &Value) -> Option<EcoString> {
+    match field {
+        Value::Str(text) => Some(text.clone().into()),
+        Value::Symbol(symbol) => Some(symbol.get().into()),
+        Value::Label(label) => Some(label.resolve().as_str().into()),
+        Value::Content(content) => Some(plain_text(content)),
+        _ => None,
+    }
+}
+
+/// The plain text of content.
+///
+/// This is cached, as show rules with matchers check the same elements over
+/// and over.
+#[comemo::memoize]
+fn plain_text(content: &Content) -> EcoString {
+    content.plain_text()
+}
+
 /// A selector that can be used with `query`.
 ///
 /// Hopefully, this is made obsolete by a more powerful query mechanism in the
@@ -12,8 +12,8 @@ use unicode_segmentation::UnicodeSegmentation;
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, dict, func, repr, scope, ty, Array, Bytes, Context, Decimal, Dict, Func,
-    IntoValue, Label, Repr, Type, Value, Version,
+    cast, dict, func, repr, scope, ty, Array, BigInt, Bytes, Context, Decimal, Dict,
+    Func, IntoValue, Label, Repr, Type, Value, Version,
 };
 use crate::layout::Alignment;
 
@@ -130,6 +130,8 @@ impl Str {
     /// - Integers are formatted in base 10. This can be overridden with the
     ///   optional `base` parameter.
     /// - Floats are formatted in base 10 and never in exponential notation.
+    ///   They are written with the shortest digits that identify the float
+    ///   exactly, unless the `digits` parameter is given.
     /// - Negative integers and floats are formatted with the Unicode minus sign
     ///   ("−" U+2212) instead of the ASCII minus sign ("-" U+002D).
     /// - From labels the name is extracted.
@@ -144,6 +146,8 @@ impl Str {
     /// #str(4000, base: 16) \
     /// #str(2.7) \
     /// #str(1e8) \
+    /// #str(0.1 + 0.2) \
+    /// #str(0.1 + 0.2, digits: 2) \
     /// #str(<intro>)
     /// ```
     #[func(constructor)]
@@ -154,20 +158,38 @@ impl Str {
         #[named]
         #[default(Spanned::new(10, Span::detached()))]
         base: Spanned<i64>,
+        /// The number of digits after the decimal separator to display floats
+        /// with.
+        ///
+        /// The float is rounded to this many digits and padded with zeros if
+        /// necessary. Rounding is based on the exact value of the float, and a
+        /// remainder of exactly one half is rounded away from zero. Thus,
+        /// `{str(2.675, digits: 2)}` yields `{"2.67"}` because the float
+        /// closest to 2.675 is slightly smaller.
+        #[named]
+        digits: Option<Spanned<u8>>,
     ) -> SourceResult<Str> {
-        Ok(match value {
-            ToStr::Str(s) => {
-                if base.v != 10 {
-                    bail!(base.span, "base is only supported for integers");
-                }
-                s
+        if base.v != 10 && !matches!(value, ToStr::Int(_)) {
+            bail!(base.span, "base is only supported for integers");
+        }
+
+        if let Some(digits) = &digits {
+            if !matches!(value, ToStr::Float(_)) {
+                bail!(digits.span, "digits are only supported for floats");
             }
+        }
+
+        Ok(match value {
+            ToStr::Str(s) => s,
             ToStr::Int(n) => {
                 if base.v < 2 || base.v > 36 {
                     bail!(base.span, "base must be between 2 and 36");
                 }
                 repr::format_int_with_base(n, base.v).into()
             }
+            ToStr::Float(v) => {
+                repr::display_float_with_digits(v, digits.map(|d| d.v)).into()
+            }
         })
     }
 
@@ -775,13 +797,16 @@ pub enum ToStr {
     Str(Str),
     /// An integer about to be formatted in a given base.
     Int(i64),
+    /// A float about to be formatted with a given number of digits.
+    Float(f64),
 }
 
 cast! {
     ToStr,
     v: i64 => Self::Int(v),
-    v: f64 => Self::Str(repr::display_float(v).into()),
+    v: f64 => Self::Float(v),
     v: Decimal => Self::Str(format_str!("{}", v)),
+    v: BigInt => Self::Str(format_str!("{}", v)),
     v: Version => Self::Str(format_str!("{}", v)),
     v: Bytes => Self::Str(
         std::str::from_utf8(&v)
@@ -6,16 +6,62 @@ use std::{mem, ptr};
 use comemo::Tracked;
 use ecow::{eco_vec, EcoString, EcoVec};
 use smallvec::SmallVec;
-use typst_syntax::Span;
+use typst_syntax::{Span, Spanned};
 use typst_utils::LazyHash;
 
-use crate::diag::{SourceResult, Trace, Tracepoint};
+use crate::diag::{At, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, ty, Content, Context, Element, Func, NativeElement, Repr, Selector,
+    cast, func, ty, Content, Context, Element, Func, NativeElement, Repr, Selector,
 };
 use crate::text::{FontFamily, FontList, TextElem};
 
+/// Applies set rules to content and contains all styles within it.
+///
+/// This is like writing set rules at the start of a content block, but the
+/// styles are guaranteed to stay local to the body: Neither the given styles
+/// nor set rules within the body are ever lifted to the page level, e.g. to
+/// style the page's header and footer. This makes it useful for reusable
+/// components whose internal styling should not affect anything around them,
+/// even when they are used in a [context] block.
+///
+/// The set rules are given as element functions with pre-applied arguments.
+///
+/// # Example
+/// ```example
+/// #let note(body) = styled(
+///   body,
+///   text.with(size: 0.8em, fill: blue),
+///   par.with(justify: true),
+/// )
+///
+/// #note[A small, blue note.] \
+/// Regular text.
+/// ```
+#[func]
+pub fn styled(
+    engine: &mut Engine,
+    /// The content to style.
+    body: Content,
+    /// The set rules to apply, as element functions with arguments (like
+    /// `{text.with(red)}`). Later rules take precedence over earlier ones.
+    #[variadic]
+    rules: Vec<Spanned<Func>>,
+) -> SourceResult<Content> {
+    let mut styles = Styles::new();
+    styles.set(Style::Boundary);
+    for Spanned { v: func, span } in rules {
+        let (elem, args) = func
+            .to_set_rule()
+            .ok_or("only element functions can be used as set rules")
+            .at(span)?;
+        let mut set = elem.set(engine, args.spanned(span))?.spanned(span);
+        set.apply(styles);
+        styles = set;
+    }
+    Ok(body.styled_with_map(styles))
+}
+
 /// A list of style properties.
 #[ty(cast)]
 #[derive(Default, PartialEq, Clone, Hash)]
@@ -172,6 +218,9 @@ pub enum Style {
     /// place we need it for the moment. Normal show rules use guards directly
     /// on elements instead.
     Revocation(RecipeIndex),
+    /// Marks styled content whose styles must not escape it, as created by
+    /// the [`styled`] function.
+    Boundary,
 }
 
 impl Style {
@@ -196,7 +245,7 @@ impl Style {
         match self {
             Self::Property(property) => property.span,
             Self::Recipe(recipe) => recipe.span,
-            Self::Revocation(_) => Span::detached(),
+            Self::Revocation(_) | Self::Boundary => Span::detached(),
         }
     }
 
@@ -209,7 +258,7 @@ impl Style {
                 Some(Selector::Elem(elem, _)) => Some(elem),
                 _ => None,
             },
-            Style::Revocation(_) => None,
+            Style::Revocation(_) | Style::Boundary => None,
         }
     }
 
@@ -219,7 +268,7 @@ impl Style {
         match self {
             Self::Property(property) => property.liftable,
             Self::Recipe(_) => true,
-            Self::Revocation(_) => false,
+            Self::Revocation(_) | Self::Boundary => false,
         }
     }
 
@@ -229,10 +278,15 @@ impl Style {
         match self {
             Self::Property(property) => property.outside,
             Self::Recipe(recipe) => recipe.outside,
-            Self::Revocation(_) => false,
+            Self::Revocation(_) | Self::Boundary => false,
         }
     }
 
+    /// Whether this is a style boundary.
+    pub fn is_boundary(&self) -> bool {
+        matches!(self, Self::Boundary)
+    }
+
     /// Turn this style into prehashed style.
     pub fn wrap(self) -> LazyHash<Style> {
         LazyHash::new(self)
@@ -245,6 +299,7 @@ impl Debug for Style {
             Self::Property(property) => property.fmt(f),
             Self::Recipe(recipe) => recipe.fmt(f),
             Self::Revocation(guard) => guard.fmt(f),
+            Self::Boundary => f.write_str("Boundary"),
         }
     }
 }
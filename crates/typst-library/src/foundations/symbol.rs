@@ -8,8 +8,16 @@ use serde::{Serialize, Serializer};
 use typst_syntax::{is_ident, Span, Spanned};
 use typst_utils::hash128;
 
+use unicode_math_class::MathClass;
+
 use crate::diag::{bail, SourceResult, StrResult};
-use crate::foundations::{cast, func, scope, ty, Array, Func, NativeFunc, Repr as _};
+use crate::foundations::repr::separated_list;
+use crate::foundations::{
+    cast, func, scope, ty, Array, Content, Dict, Func, IntoValue, NativeFunc, Repr as _,
+    Value,
+};
+use crate::math::EquationElem;
+use crate::text::TextElem;
 
 /// A Unicode symbol.
 ///
@@ -61,7 +69,7 @@ enum Repr {
 #[derive(Clone, Eq, PartialEq, Hash)]
 enum List {
     Static(&'static [(&'static str, char)]),
-    Runtime(Box<[(EcoString, char)]>),
+    Runtime(Box<[(EcoString, char, Option<MathClass>)]>),
 }
 
 impl Symbol {
@@ -77,9 +85,10 @@ impl Symbol {
         Self(Repr::Complex(list))
     }
 
-    /// Create a symbol with a runtime variant list.
+    /// Create a symbol with a runtime variant list, where each variant may
+    /// override the math class of its character.
     #[track_caller]
-    pub fn runtime(list: Box<[(EcoString, char)]>) -> Self {
+    pub fn runtime(list: Box<[(EcoString, char, Option<MathClass>)]>) -> Self {
         debug_assert!(!list.is_empty());
         Self(Repr::Modified(Arc::new((List::Runtime(list), EcoString::new()))))
     }
@@ -93,6 +102,26 @@ impl Symbol {
         }
     }
 
+    /// Get the math class that overrides the default class of the symbol's
+    /// character, if any.
+    pub fn math_class(&self) -> Option<MathClass> {
+        let Repr::Modified(arc) = &self.0 else { return None };
+        let classes = arc.0.classified().map(|(s, _, class)| (s, class));
+        find(classes, &arc.1).flatten()
+    }
+
+    /// Display the symbol as content.
+    ///
+    /// If the symbol's variant overrides the math class, the resulting text is
+    /// styled to use that class in equations.
+    pub fn display(&self) -> Content {
+        let text = TextElem::packed(self.get());
+        match self.math_class() {
+            Some(class) => text.styled(EquationElem::set_class(Some(class))),
+            None => text,
+        }
+    }
+
     /// Try to get the function associated with the symbol, if any.
     pub fn func(&self) -> StrResult<Func> {
         match self.get() {
@@ -128,17 +157,49 @@ impl Symbol {
         }
 
         if let Repr::Modified(arc) = &mut self.0 {
-            let (list, modifiers) = Arc::make_mut(arc);
-            if !modifiers.is_empty() {
-                modifiers.push('.');
-            }
-            modifiers.push_str(modifier);
-            if find(list.variants(), modifiers).is_some() {
+            let joined = join_modifier(&arc.1, modifier);
+            if find(arc.0.variants(), &joined).is_some() {
+                Arc::make_mut(arc).1 = joined;
                 return Ok(self);
             }
         }
 
-        bail!("unknown symbol modifier")
+        Err(self.unknown_modifier(modifier))
+    }
+
+    /// Produce an error message for a modifier that cannot be applied,
+    /// suggesting modifiers that can.
+    fn unknown_modifier(&self, modifier: &str) -> EcoString {
+        let mut msg = eco_format!("unknown symbol modifier {}", modifier.repr());
+        let applied = match &self.0 {
+            Repr::Modified(arc) => arc.1.as_str(),
+            _ => "",
+        };
+
+        let available: Vec<&str> = self
+            .modifiers()
+            .filter(|m| find(self.variants(), &join_modifier(applied, m)).is_some())
+            .collect();
+
+        if let Some(similar) = available
+            .iter()
+            .map(|m| (edit_distance(modifier, m), m))
+            .filter(|&(distance, m)| distance <= m.len().max(modifier.len()) / 3)
+            .min()
+        {
+            msg.push_str(&eco_format!(", did you mean {}?", similar.1.repr()));
+        } else if let [only] = available.as_slice() {
+            msg.push_str(&eco_format!(
+                ", the only available modifier is {}",
+                only.repr()
+            ));
+        } else if !available.is_empty() && available.len() <= 10 {
+            let pieces: Vec<_> = available.iter().map(|m| m.repr()).collect();
+            msg.push_str(", available modifiers are ");
+            msg.push_str(&separated_list(&pieces, "and"));
+        }
+
+        msg
     }
 
     /// The characters that are covered by this symbol.
@@ -196,6 +257,21 @@ impl Symbol {
         /// and the symbol. Individual modifiers should be separated by dots. When
         /// displaying a symbol, Typst selects the first from the variants that have
         /// all attached modifiers and the minimum number of other modifiers.
+        ///
+        /// A variant can also be a dictionary with the keys `modifiers`
+        /// (optional), `value`, and `math-class` (optional). The latter
+        /// overrides the [class]($math.class) of the symbol when it is used in
+        /// an equation.
+        ///
+        /// ```example
+        /// #let corr = symbol(
+        ///   "⟐",
+        ///   (modifiers: "rel", value: "⟐", math-class: "relation"),
+        /// )
+        ///
+        /// $a corr b$ \
+        /// $a corr.rel b$
+        /// ```
         #[variadic]
         variants: Vec<Spanned<SymbolVariant>>,
     ) -> SourceResult<Symbol> {
@@ -253,7 +329,7 @@ impl Symbol {
             seen.insert(hash, i);
         }
 
-        let list = variants.into_iter().map(|s| (s.v.0, s.v.1)).collect();
+        let list = variants.into_iter().map(|s| (s.v.0, s.v.1, s.v.2)).collect();
         Ok(Symbol::runtime(list))
     }
 }
@@ -288,36 +364,41 @@ impl crate::foundations::Repr for Symbol {
         match &self.0 {
             Repr::Single(c) => eco_format!("symbol(\"{}\")", *c),
             Repr::Complex(variants) => {
-                eco_format!("symbol{}", repr_variants(variants.iter().copied(), ""))
+                let variants = variants.iter().map(|&(s, c)| (s, c, None));
+                eco_format!("symbol{}", repr_variants(variants, ""))
             }
             Repr::Modified(arc) => {
                 let (list, modifiers) = arc.as_ref();
-                if modifiers.is_empty() {
-                    eco_format!("symbol{}", repr_variants(list.variants(), ""))
-                } else {
-                    eco_format!("symbol{}", repr_variants(list.variants(), modifiers))
-                }
+                eco_format!("symbol{}", repr_variants(list.classified(), modifiers))
             }
         }
     }
 }
 
 fn repr_variants<'a>(
-    variants: impl Iterator<Item = (&'a str, char)>,
+    variants: impl Iterator<Item = (&'a str, char, Option<MathClass>)>,
     applied_modifiers: &str,
 ) -> String {
     crate::foundations::repr::pretty_array_like(
         &variants
-            .filter(|(variant, _)| {
+            .filter(|(variant, ..)| {
                 // Only keep variants that can still be accessed, i.e., variants
                 // that contain all applied modifiers.
                 parts(applied_modifiers).all(|am| variant.split('.').any(|m| m == am))
             })
-            .map(|(variant, c)| {
+            .map(|(variant, c, class)| {
                 let trimmed_variant = variant
                     .split('.')
                     .filter(|&m| parts(applied_modifiers).all(|am| m != am));
-                if trimmed_variant.clone().all(|m| m.is_empty()) {
+                if let Some(class) = class {
+                    let trimmed_modifiers = trimmed_variant.collect::<Vec<_>>().join(".");
+                    eco_format!(
+                        "(modifiers: {}, value: \"{}\", math-class: {})",
+                        trimmed_modifiers.repr(),
+                        c,
+                        class.into_value().repr(),
+                    )
+                } else if trimmed_variant.clone().all(|m| m.is_empty()) {
                     eco_format!("\"{c}\"")
                 } else {
                     let trimmed_modifiers = trimmed_variant.collect::<Vec<_>>().join(".");
@@ -346,28 +427,44 @@ impl List {
             List::Runtime(list) => Variants::Runtime(list.iter()),
         }
     }
+
+    /// The characters that are covered by this list, alongside their math
+    /// class overrides.
+    fn classified(&self) -> impl Iterator<Item = (&str, char, Option<MathClass>)> {
+        self.variants().enumerate().map(|(i, (s, c))| match self {
+            List::Static(_) => (s, c, None),
+            List::Runtime(list) => (s, c, list[i].2),
+        })
+    }
 }
 
 /// A value that can be cast to a symbol.
-pub struct SymbolVariant(EcoString, char);
+pub struct SymbolVariant(EcoString, char, Option<MathClass>);
 
 cast! {
     SymbolVariant,
-    c: char => Self(EcoString::new(), c),
+    c: char => Self(EcoString::new(), c, None),
     array: Array => {
         let mut iter = array.into_iter();
         match (iter.next(), iter.next(), iter.next()) {
-            (Some(a), Some(b), None) => Self(a.cast()?, b.cast()?),
+            (Some(a), Some(b), None) => Self(a.cast()?, b.cast()?, None),
             _ => Err("variant array must contain exactly two entries")?,
         }
     },
+    mut dict: Dict => {
+        let modifiers = dict.take("modifiers").ok().map(Value::cast).transpose()?;
+        let value = dict.take("value")?.cast()?;
+        let class = dict.take("math-class").ok().map(Value::cast).transpose()?;
+        dict.finish(&["modifiers", "value", "math-class"])?;
+        Self(modifiers.unwrap_or_default(), value, class)
+    },
 }
 
 /// Iterator over variants.
 enum Variants<'a> {
     Single(std::option::IntoIter<char>),
     Static(std::slice::Iter<'static, (&'static str, char)>),
-    Runtime(std::slice::Iter<'a, (EcoString, char)>),
+    Runtime(std::slice::Iter<'a, (EcoString, char, Option<MathClass>)>),
 }
 
 impl<'a> Iterator for Variants<'a> {
@@ -377,16 +474,16 @@ impl<'a> Iterator for Variants<'a> {
         match self {
             Self::Single(iter) => Some(("", iter.next()?)),
             Self::Static(list) => list.next().copied(),
-            Self::Runtime(list) => list.next().map(|(s, c)| (s.as_str(), *c)),
+            Self::Runtime(list) => list.next().map(|(s, c, _)| (s.as_str(), *c)),
         }
     }
 }
 
 /// Find the best symbol from the list.
-fn find<'a>(
-    variants: impl Iterator<Item = (&'a str, char)>,
+fn find<'a, T>(
+    variants: impl Iterator<Item = (&'a str, T)>,
     modifiers: &str,
-) -> Option<char> {
+) -> Option<T> {
     let mut best = None;
     let mut best_score = None;
 
@@ -417,6 +514,31 @@ fn find<'a>(
     best
 }
 
+/// Append a modifier to a list of modifiers.
+fn join_modifier(modifiers: &str, modifier: &str) -> EcoString {
+    if modifiers.is_empty() {
+        modifier.into()
+    } else {
+        eco_format!("{modifiers}.{modifier}")
+    }
+}
+
+/// The number of single-character edits needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let next = (prev + usize::from(x != y)).min(row[j] + 1).min(row[j + 1] + 1);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
 /// Split a modifier list into its parts.
 fn parts(modifiers: &str) -> impl Iterator<Item = &str> {
     modifiers.split('.').filter(|s| !s.is_empty())
@@ -13,8 +13,8 @@ use typst_utils::ArcExt;
 
 use crate::diag::{HintedStrResult, HintedString, StrResult};
 use crate::foundations::{
-    fields, ops, repr, Args, Array, AutoValue, Bytes, CastInfo, Content, Datetime,
-    Decimal, Dict, Duration, Fold, FromValue, Func, IntoValue, Label, Module,
+    fields, ops, repr, Args, Array, AutoValue, BigInt, Bytes, CastInfo, Content,
+    Datetime, Decimal, Dict, Duration, Fold, FromValue, Func, IntoValue, Label, Module,
     NativeElement, NativeType, NoneValue, Plugin, Reflect, Repr, Resolve, Scope, Str,
     Styles, Symbol, Type, Version,
 };
@@ -207,9 +207,12 @@ impl Value {
             Self::Int(v) => TextElem::packed(repr::format_int_with_base(v, 10)),
             Self::Float(v) => TextElem::packed(repr::display_float(v)),
             Self::Decimal(v) => TextElem::packed(eco_format!("{v}")),
+            Self::Dyn(v) if v.is::<BigInt>() => {
+                TextElem::packed(eco_format!("{}", v.downcast::<BigInt>().unwrap()))
+            }
             Self::Str(v) => TextElem::packed(v),
             Self::Version(v) => TextElem::packed(eco_format!("{v}")),
-            Self::Symbol(v) => TextElem::packed(v.get()),
+            Self::Symbol(v) => v.display(),
             Self::Content(v) => v,
             Self::Module(module) => module.content(),
             _ => RawElem::new(RawContent::Text(self.repr()))
@@ -656,7 +659,7 @@ primitive! { Duration: "duration", Duration }
 primitive! { Content: "content",
     Content,
     None => Content::empty(),
-    Symbol(v) => TextElem::packed(v.get()),
+    Symbol(v) => v.display(),
     Str(v) => TextElem::packed(v)
 }
 primitive! { Styles: "styles", Styles }
@@ -1,11 +1,11 @@
 use std::fmt::{self, Debug, Display, Formatter};
 
-use ecow::{EcoString, EcoVec};
+use ecow::{eco_format, EcoString, EcoVec};
 use typst_syntax::Span;
-use typst_utils::{PicoStr, ResolvedPicoStr};
+use typst_utils::{Numeric, PicoStr, ResolvedPicoStr};
 
 use crate::diag::{bail, HintedStrResult, StrResult};
-use crate::foundations::{cast, Dict, Repr, Str};
+use crate::foundations::{cast, repr, Dict, Repr, Str, Value};
 use crate::introspection::{Introspector, Tag};
 use crate::layout::Frame;
 use crate::model::DocumentInfo;
@@ -21,6 +21,46 @@ pub struct HtmlDocument {
     pub introspector: Introspector,
 }
 
+impl HtmlDocument {
+    /// Calls `f` on every element of the document, in document order and with
+    /// parents before their children.
+    ///
+    /// This allows embedders to post-process the document before it is
+    /// encoded, for example to rewrite the URLs of images or to add nonces to
+    /// `style` elements:
+    ///
+    /// ```ignore
+    /// document.rewrite(|element| {
+    ///     if element.tag == tag::img {
+    ///         if let Some(src) = element.attrs.get(attr::src) {
+    ///             let src = eco_format!("https://cdn.example.com/{src}");
+    ///             element.attrs.set(attr::src, src);
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// Any change that upholds the following invariants can be encoded:
+    ///
+    /// - Tags and attribute names are valid because they can only be created
+    ///   through [`HtmlTag::intern`] and [`HtmlAttr::intern`] or their
+    ///   constant counterparts.
+    /// - Each attribute appears at most once per element. Use
+    ///   [`HtmlAttrs::set`] instead of [`HtmlAttrs::push`] to change existing
+    ///   attributes.
+    /// - [Void](tag::is_void) elements, like `img`, have no children.
+    /// - Text and attribute values only contain characters that can be
+    ///   encoded in HTML, i.e. no control characters other than whitespace.
+    ///   Encoding fails with an error otherwise.
+    ///
+    /// The [`introspector`](Self::introspector) is not updated, so queries
+    /// still reflect the document as it was before the rewrite. Tag nodes
+    /// should be left in place.
+    pub fn rewrite(&mut self, mut f: impl FnMut(&mut HtmlElement)) {
+        self.root.rewrite(&mut f);
+    }
+}
+
 /// A child of an HTML element.
 #[derive(Debug, Clone, Hash)]
 pub enum HtmlNode {
@@ -90,6 +130,17 @@ impl HtmlElement {
         self.span = span;
         self
     }
+
+    /// Calls `f` on this element and all elements nested in it, with parents
+    /// before their children. See [`HtmlDocument::rewrite`] for details.
+    pub fn rewrite(&mut self, f: &mut impl FnMut(&mut HtmlElement)) {
+        f(self);
+        for child in &mut self.children {
+            if let HtmlNode::Element(element) = child {
+                element.rewrite(f);
+            }
+        }
+    }
 }
 
 /// The tag of an HTML element.
@@ -169,6 +220,26 @@ impl HtmlAttrs {
     pub fn push(&mut self, attr: HtmlAttr, value: impl Into<EcoString>) {
         self.0.push((attr, value.into()));
     }
+
+    /// Get the value of an attribute.
+    pub fn get(&self, attr: HtmlAttr) -> Option<&EcoString> {
+        self.0.iter().find(|&&(key, _)| key == attr).map(|(_, value)| value)
+    }
+
+    /// Set an attribute, replacing its previous value if there is one.
+    pub fn set(&mut self, attr: HtmlAttr, value: impl Into<EcoString>) {
+        let value = value.into();
+        match self.0.make_mut().iter_mut().find(|(key, _)| *key == attr) {
+            Some((_, slot)) => *slot = value,
+            None => self.0.push((attr, value)),
+        }
+    }
+
+    /// Remove an attribute and return its value, if it was present.
+    pub fn remove(&mut self, attr: HtmlAttr) -> Option<EcoString> {
+        let index = self.0.iter().position(|&(key, _)| key == attr)?;
+        Some(self.0.remove(index).1)
+    }
 }
 
 cast! {
@@ -178,14 +249,49 @@ cast! {
         .map(|(key, value)| (key.resolve().as_str().into(), value.into_value()))
         .collect::<Dict>()
         .into_value(),
-    values: Dict => Self(values
-        .into_iter()
-        .map(|(k, v)| {
-            let attr = HtmlAttr::intern(&k)?;
-            let value = v.cast::<EcoString>()?;
-            Ok((attr, value))
-        })
-        .collect::<HintedStrResult<_>>()?),
+    values: Dict => {
+        let mut attrs = Self::default();
+        for (key, value) in values {
+            let attr = HtmlAttr::intern(&key)?;
+            if let Some(value) = attr_value(attr, value)? {
+                attrs.push(attr, value);
+            }
+        }
+        attrs
+    },
+}
+
+/// Convert a value into the string value of an attribute.
+///
+/// Returns `None` if the attribute should be omitted.
+fn attr_value(attr: HtmlAttr, value: Value) -> HintedStrResult<Option<EcoString>> {
+    Ok(Some(match value {
+        Value::Str(v) => v.into(),
+        Value::Bool(true) => EcoString::new(),
+        Value::Bool(false) => return Ok(None),
+        Value::Int(v) => eco_format!("{v}"),
+        Value::Float(v) => eco_format!("{v}"),
+        Value::Length(v) => {
+            if !v.em.is_zero() {
+                bail!(
+                    "cannot convert a font-relative length to pixels";
+                    hint: "use an absolute length like `10pt` instead"
+                );
+            }
+            // CSS pixels are defined as 1/96 of an inch.
+            let px = v.abs.to_inches() * 96.0;
+            if matches!(attr, attr::width | attr::height) {
+                repr::format_float(px, Some(2), false, "")
+            } else {
+                repr::format_float_with_unit(px, "px")
+            }
+        }
+        Value::Color(v) => v.to_hex(),
+        v => bail!(
+            "expected string, boolean, integer, float, length, or color, found {}",
+            v.ty()
+        ),
+    }))
 }
 
 /// An attribute of an HTML.
@@ -205,6 +311,21 @@ impl HtmlAttr {
             bail!("the character {} is not valid in an attribute name", c.repr());
         }
 
+        if let Some(name) = string.strip_prefix("data-") {
+            if name.is_empty() {
+                bail!("data attribute name must not be empty after `data-`");
+            } else if name.chars().any(|c| c.is_ascii_uppercase()) {
+                bail!("data attribute name must not contain uppercase letters");
+            }
+        } else if let Some(name) = string.strip_prefix("aria-") {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase()) {
+                bail!(
+                    "ARIA attribute name must only consist of lowercase letters \
+                     after `aria-`"
+                );
+            }
+        }
+
         Ok(Self(PicoStr::intern(string)))
     }
 
@@ -569,26 +690,28 @@ pub mod tag {
         )
     }
 
+    /// The void tags, whose associated elements may not have children.
+    pub const VOID: &[HtmlTag] = &[
+        self::area,
+        self::base,
+        self::br,
+        self::col,
+        self::embed,
+        self::hr,
+        self::img,
+        self::input,
+        self::link,
+        self::meta,
+        self::param,
+        self::source,
+        self::track,
+        self::wbr,
+    ];
+
     /// Whether this is a void tag whose associated element may not have a
     /// children.
     pub fn is_void(tag: HtmlTag) -> bool {
-        matches!(
-            tag,
-            self::area
-                | self::base
-                | self::br
-                | self::col
-                | self::embed
-                | self::hr
-                | self::img
-                | self::input
-                | self::link
-                | self::meta
-                | self::param
-                | self::source
-                | self::track
-                | self::wbr
-        )
+        VOID.contains(&tag)
     }
 
     /// Whether this is a tag containing raw text.
@@ -619,13 +742,30 @@ pub mod attr {
 
     attrs! {
         charset
+        colspan
         content
+        dir
+        height
         href
+        id
         name
+        nonce
         value
         role
+        rowspan
+        scope
+        src
+        style
+        width
     }
 
     #[allow(non_upper_case_globals)]
     pub const aria_level: HtmlAttr = HtmlAttr::constant("aria-level");
+    #[allow(non_upper_case_globals)]
+    pub const data_counter: HtmlAttr = HtmlAttr::constant("data-counter");
+    #[allow(non_upper_case_globals)]
+    pub const data_level: HtmlAttr = HtmlAttr::constant("data-level");
+    #[allow(non_upper_case_globals)]
+    pub const data_numbering_pattern: HtmlAttr =
+        HtmlAttr::constant("data-numbering-pattern");
 }
@@ -5,8 +5,14 @@ mod dom;
 pub use self::dom::*;
 
 use ecow::EcoString;
+use typst_syntax::Spanned;
 
-use crate::foundations::{category, elem, Category, Content, Module, Scope};
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::repr::separated_list;
+use crate::foundations::{
+    category, elem, Args, Category, Construct, Content, Module, NativeElement, Scope,
+};
 
 /// HTML output.
 #[category]
@@ -22,22 +28,63 @@ pub fn module() -> Module {
 }
 
 /// A HTML element that can contain Typst content.
-#[elem(name = "elem")]
+#[elem(name = "elem", Construct)]
 pub struct HtmlElem {
     /// The element's tag.
     #[required]
     pub tag: HtmlTag,
 
     /// The element's attributes.
+    ///
+    /// Values are converted to attribute strings as follows:
+    /// - Strings are used as is.
+    /// - Integers and floats are written out as numbers.
+    /// - Lengths must be absolute and are converted to CSS pixels. For the
+    ///   `width` and `height` attributes, which do not accept units, just the
+    ///   number is written out. Otherwise, it is suffixed with `px`.
+    /// - Colors are written in hexadecimal notation.
+    /// - `{true}` adds the attribute without a value and `{false}` omits it.
+    ///   This is how boolean attributes like `hidden` work.
+    ///
+    /// Attribute names starting with `data-` must not contain uppercase
+    /// letters and names starting with `aria-` must only consist of lowercase
+    /// letters.
     #[borrowed]
     pub attrs: HtmlAttrs,
 
     /// The contents of the HTML element.
+    ///
+    /// Void elements like `img` and `meta` cannot have contents.
     #[positional]
     #[borrowed]
     pub body: Option<Content>,
 }
 
+impl Construct for HtmlElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        let tag = args.expect::<HtmlTag>("tag")?;
+        let attrs = args.named::<HtmlAttrs>("attrs")?;
+        let body = args.find::<Spanned<Option<Content>>>()?;
+
+        let mut elem = HtmlElem::new(tag);
+        if let Some(attrs) = attrs {
+            elem.push_attrs(attrs);
+        }
+        if let Some(Spanned { v: body, span }) = body {
+            if body.is_some() && tag::is_void(tag) {
+                let void: Vec<_> = tag::VOID.iter().map(|tag| tag.resolve()).collect();
+                bail!(
+                    span, "{tag} is a void element and cannot have a body";
+                    hint: "the void elements are {}", separated_list(&void, "and")
+                );
+            }
+            elem.push_body(body);
+        }
+
+        Ok(elem.pack())
+    }
+}
+
 impl HtmlElem {
     /// Add an atribute to the element.
     pub fn with_attr(mut self, attr: HtmlAttr, value: impl Into<EcoString>) -> Self {
@@ -7,7 +7,7 @@ use smallvec::{smallvec, SmallVec};
 use typst_syntax::Span;
 use typst_utils::NonZeroExt;
 
-use crate::diag::{bail, At, HintedStrResult, SourceResult};
+use crate::diag::{bail, warning, At, HintedStrResult, SourceResult, StrResult};
 use crate::engine::{Engine, Route, Sink, Traced};
 use crate::foundations::{
     cast, elem, func, scope, select_where, ty, Args, Array, Construct, Content, Context,
@@ -103,6 +103,30 @@ use crate::World;
 /// }
 /// ```
 ///
+/// # Snapshots
+/// With the `snapshot` method, you can save the current value of a counter
+/// under a name and later return to it with `restore`. This is useful if the
+/// numbering is interrupted by something that is counted separately, but
+/// should resume where it left off afterwards.
+///
+/// ```example
+/// #let problem = counter("problem")
+/// #let problem-title = {
+///   problem.step()
+///   [*Problem #context problem.display()*]
+/// }
+///
+/// #problem-title \
+/// #problem-title
+///
+/// #problem.snapshot("main")
+/// #problem.update(0)
+/// _Warm-up:_ #problem-title
+///
+/// #problem.restore("main")
+/// #problem-title
+/// ```
+///
 /// # Page counter
 /// The page counter is special. It is automatically stepped at each pagebreak.
 /// But like other counters, you can also step it manually. For example, you
@@ -130,6 +154,10 @@ use crate::World;
 /// Arabic numbers.
 /// ```
 ///
+/// In HTML export, there are no pages. There, the page counter is `{1}`
+/// everywhere (unless updated manually) and Typst emits a warning when it is
+/// used, so that templates shared with paged export still compile.
+///
 /// # Custom counters
 /// To define your own counter, call the `counter` function with a string as a
 /// key. This key identifies the counter globally.
@@ -255,6 +283,33 @@ impl Counter {
         Ok(state)
     }
 
+    /// Gets the value that was last saved under the given name before the
+    /// given location.
+    pub fn snapshot_at_loc(
+        &self,
+        engine: &mut Engine,
+        location: Location,
+        name: &Str,
+        span: Span,
+    ) -> SourceResult<CounterState> {
+        let sequence = self.sequence(engine)?;
+        let offset = engine.introspector.query_count_before(&self.selector(), location);
+        let elems = engine.introspector.query(&self.selector());
+
+        // A snapshot doesn't change the state, so the state after the snapshot
+        // is the saved one.
+        elems[..offset]
+            .iter()
+            .rposition(|elem| {
+                elem.to_packed::<CounterUpdateElem>().is_some_and(|elem| {
+                    matches!(&elem.update, CounterUpdate::Snapshot(n) if n == name)
+                })
+            })
+            .map(|i| sequence[i + 1].0.clone())
+            .ok_or_else(|| eco_format!("counter has no snapshot named {}", name.repr()))
+            .at(span)
+    }
+
     /// Displays the value of the counter at the given location.
     pub fn display_at_loc(
         &self,
@@ -278,6 +333,14 @@ impl Counter {
         &self,
         engine: &mut Engine,
     ) -> SourceResult<EcoVec<(CounterState, NonZeroUsize)>> {
+        if self.is_page() && engine.introspector.is_html() {
+            engine.sink.warn(warning!(
+                Span::detached(),
+                "the page counter is always 1 in HTML export";
+                hint: "HTML documents are not split into pages"
+            ));
+        }
+
         self.sequence_impl(
             engine.routines,
             engine.world,
@@ -311,6 +374,7 @@ impl Counter {
         let mut state = CounterState::init(matches!(self.0, CounterKey::Page));
         let mut page = NonZeroUsize::ONE;
         let mut stops = eco_vec![(state.clone(), page)];
+        let mut snapshots = CounterSnapshots::default();
 
         for elem in introspector.query(&self.selector()) {
             if self.is_page() {
@@ -327,7 +391,7 @@ impl Counter {
                 Some(countable) => countable.update(),
                 None => Some(CounterUpdate::Step(NonZeroUsize::ONE)),
             } {
-                state.update(&mut engine, update)?;
+                state.apply(&mut engine, update, &mut snapshots, elem.span())?;
             }
 
             stops.push((state.clone(), page));
@@ -492,6 +556,7 @@ impl Counter {
         selector: LocatableSelector,
     ) -> SourceResult<CounterState> {
         let loc = selector.resolve_unique(engine.introspector, context).at(span)?;
+        engine.sink.lookahead();
         self.at_loc(engine, loc)
     }
 
@@ -508,6 +573,7 @@ impl Counter {
         span: Span,
     ) -> SourceResult<CounterState> {
         context.introspect().at(span)?;
+        engine.sink.lookahead();
         let sequence = self.sequence(engine)?;
         let (mut state, page) = sequence.last().unwrap().clone();
         if self.is_page() {
@@ -555,6 +621,89 @@ impl Counter {
     ) -> Content {
         CounterUpdateElem::new(self.0, update).pack().spanned(span)
     }
+
+    /// Saves the current value of the counter under a name, so that it can be
+    /// [restored]($counter.restore) or [retrieved]($counter.at-snapshot)
+    /// later.
+    ///
+    /// Just like with `step`, the snapshot is only taken if you put the
+    /// resulting content into the document. Taking another snapshot with the
+    /// same name replaces the previous one from there on.
+    #[func]
+    pub fn snapshot(
+        self,
+        /// The call span of the update.
+        span: Span,
+        /// The name to save the value under.
+        name: Str,
+    ) -> Content {
+        self.update(span, CounterUpdate::Snapshot(name))
+    }
+
+    /// Resets the counter to the value last saved under the given name with
+    /// [`snapshot`]($counter.snapshot).
+    ///
+    /// Just like with `step`, the update only occurs if you put the resulting
+    /// content into the document.
+    #[func]
+    pub fn restore(
+        self,
+        /// The call span of the update.
+        span: Span,
+        /// The name the value was saved under.
+        name: Str,
+    ) -> Content {
+        self.update(span, CounterUpdate::Restore(name))
+    }
+
+    /// Retrieves the value last saved under the given name with
+    /// [`snapshot`]($counter.snapshot) before the current location.
+    #[func(contextual)]
+    pub fn at_snapshot(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The name the value was saved under.
+        name: Str,
+    ) -> SourceResult<CounterState> {
+        let loc = context.location().at(span)?;
+        self.snapshot_at_loc(engine, loc, &name, span)
+    }
+
+    /// Retrieves the value of the counter at the current location with the
+    /// given numbers added to it level by level.
+    ///
+    /// This is useful to display a number relative to the counter's value
+    /// without updating the counter, e.g. the number of the next problem.
+    ///
+    /// ```example
+    /// #let c = counter("problem")
+    /// #c.update(3)
+    /// #context [
+    ///   Now: #c.get().first() \
+    ///   Next: #c.offset((1,)).first()
+    /// ]
+    /// ```
+    #[func(contextual)]
+    pub fn offset(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The numbers to add. Levels that the counter or the offset lack are
+        /// treated as zero. Negative numbers subtract from the counter.
+        by: CounterOffset,
+    ) -> SourceResult<CounterState> {
+        let loc = context.location().at(span)?;
+        self.at_loc(engine, loc)?.offset(&by).at(span)
+    }
 }
 
 impl Repr for Counter {
@@ -614,6 +763,10 @@ pub enum CounterUpdate {
     Step(NonZeroUsize),
     /// Apply the given function to the counter's state.
     Func(Func),
+    /// Save the counter's state under a name.
+    Snapshot(Str),
+    /// Reset the counter's state to the one saved under a name.
+    Restore(Str),
 }
 
 cast! {
@@ -628,8 +781,44 @@ pub trait Count {
     fn update(&self) -> Option<CounterUpdate>;
 }
 
-/// Counts through elements with different levels.
+/// Numbers to add to a counter's state level by level.
 #[derive(Debug, Clone, PartialEq, Hash)]
+pub struct CounterOffset(pub SmallVec<[i64; 3]>);
+
+cast! {
+    CounterOffset,
+    num: i64 => Self(smallvec![num]),
+    array: Array => Self(array
+        .into_iter()
+        .map(Value::cast)
+        .collect::<HintedStrResult<_>>()?),
+}
+
+/// Counter states saved with [`CounterUpdate::Snapshot`].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+struct CounterSnapshots(EcoVec<(Str, CounterState)>);
+
+impl CounterSnapshots {
+    /// Save a state under a name, replacing a previous one.
+    fn save(&mut self, name: Str, state: CounterState) {
+        match self.0.make_mut().iter_mut().find(|(n, _)| *n == name) {
+            Some((_, saved)) => *saved = state,
+            None => self.0.push((name, state)),
+        }
+    }
+
+    /// Retrieve the state saved under a name.
+    fn load(&self, name: &Str) -> StrResult<CounterState> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, state)| state.clone())
+            .ok_or_else(|| eco_format!("counter has no snapshot named {}", name.repr()))
+    }
+}
+
+/// Counts through elements with different levels.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct CounterState(pub SmallVec<[usize; 3]>);
 
 impl CounterState {
@@ -654,10 +843,43 @@ impl CounterState {
                     .cast()
                     .at(func.span())?
             }
+            // Snapshots are tracked by the caller, see `apply`.
+            CounterUpdate::Snapshot(_) | CounterUpdate::Restore(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Like [`update`](Self::update), but also saves and restores snapshots.
+    fn apply(
+        &mut self,
+        engine: &mut Engine,
+        update: CounterUpdate,
+        snapshots: &mut CounterSnapshots,
+        span: Span,
+    ) -> SourceResult<()> {
+        match update {
+            CounterUpdate::Snapshot(name) => snapshots.save(name, self.clone()),
+            CounterUpdate::Restore(name) => *self = snapshots.load(&name).at(span)?,
+            update => self.update(engine, update)?,
         }
         Ok(())
     }
 
+    /// Add the given numbers to the state level by level.
+    pub fn offset(&self, by: &CounterOffset) -> StrResult<Self> {
+        let len = self.0.len().max(by.0.len());
+        (0..len)
+            .map(|i| {
+                let num = self.0.get(i).copied().unwrap_or(0) as i64;
+                let delta = by.0.get(i).copied().unwrap_or(0);
+                num.checked_add(delta)
+                    .and_then(|n| usize::try_from(n).ok())
+                    .ok_or_else(|| eco_format!("counter value would become negative"))
+            })
+            .collect::<StrResult<_>>()
+            .map(Self)
+    }
+
     /// Advance the number of the given level by the specified amount.
     pub fn step(&mut self, level: NonZeroUsize, by: usize) {
         let level = level.get();
@@ -743,7 +965,7 @@ pub struct CounterDisplayElem {
     /// Whether to display both the current and final value.
     #[required]
     #[internal]
-    both: bool,
+    pub both: bool,
 }
 
 impl Construct for CounterDisplayElem {
@@ -769,16 +991,21 @@ impl Show for Packed<CounterDisplayElem> {
 
 /// An specialized handler of the page counter that tracks both the physical
 /// and the logical page counter.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct ManualPageCounter {
     physical: NonZeroUsize,
     logical: usize,
+    snapshots: CounterSnapshots,
 }
 
 impl ManualPageCounter {
     /// Create a new fast page counter, starting at 1.
     pub fn new() -> Self {
-        Self { physical: NonZeroUsize::ONE, logical: 1 }
+        Self {
+            physical: NonZeroUsize::ONE,
+            logical: 1,
+            snapshots: CounterSnapshots::default(),
+        }
     }
 
     /// Get the current physical page counter state.
@@ -802,7 +1029,12 @@ impl ManualPageCounter {
                     };
                     if *elem.key() == CounterKey::Page {
                         let mut state = CounterState(smallvec![self.logical]);
-                        state.update(engine, elem.update.clone())?;
+                        state.apply(
+                            engine,
+                            elem.update.clone(),
+                            &mut self.snapshots,
+                            elem.span(),
+                        )?;
                         self.logical = state.first();
                     }
                 }
@@ -2,22 +2,26 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 
-use ecow::EcoVec;
+use ecow::{EcoString, EcoVec};
 use smallvec::SmallVec;
 use typst_utils::NonZeroExt;
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{Content, Label, Repr, Selector};
-use crate::html::{HtmlElement, HtmlNode};
+use crate::html::{attr, HtmlElement, HtmlNode};
 use crate::introspection::{Location, Tag};
 use crate::layout::{Frame, FrameItem, Page, Point, Position, Transform};
-use crate::model::Numbering;
+use crate::model::{slugify, unique_anchors, AnchorNaming, Numbering};
 
 /// Can be queried for elements and their positions.
 #[derive(Default, Clone)]
 pub struct Introspector {
+    /// Whether the introspector was built for an HTML document.
+    html: bool,
+    /// Whether elements were dropped with [`slim`](Self::slim).
+    slim: bool,
     /// The number of pages in the document.
     pages: usize,
     /// The page numberings, indexed by page number minus 1.
@@ -35,6 +39,15 @@ pub struct Introspector {
     locations: HashMap<Location, usize>,
     /// Accelerates lookup of elements by label.
     labels: MultiMap<Label, usize>,
+    /// The ids of the nearest preceding HTML elements with an id, for
+    /// elements in HTML documents.
+    anchors: HashMap<Location, EcoString>,
+    /// The unified anchors of all labels in the document.
+    label_anchors: HashMap<Label, EcoString>,
+    /// The introspectable items of each page, from which an updated
+    /// introspector can be built. Only kept by introspectors that were
+    /// created with [`update`](Self::update).
+    page_items: Vec<Arc<[Discovery]>>,
 
     /// Caches queries done on the introspector. This is important because
     /// even if all top-level queries are distinct, they often have shared
@@ -48,9 +61,44 @@ type Pair = (Content, Position);
 
 impl Introspector {
     /// Creates an introspector for a page list.
+    ///
+    /// The introspector cannot be reused by [`update`](Self::update), which
+    /// searches all pages if given such a previous introspector.
     #[typst_macros::time(name = "introspect pages")]
     pub fn paged(pages: &[Page]) -> Self {
-        IntrospectorBuilder::new().build_paged(pages)
+        IntrospectorBuilder::new().build_paged(pages, |_| None)
+    }
+
+    /// Creates an introspector for a page list by updating the introspector
+    /// of a previous version of the document.
+    ///
+    /// The `changed` pages are indices into `pages` of the pages whose tags
+    /// differ from those of the page at the same index in the previous
+    /// document. Only
+    /// these pages and pages that the previous document didn't have are
+    /// searched for elements. For all other pages, the elements found in the
+    /// previous document are reused. The result is the same as that of
+    /// [`paged`](Self::paged).
+    ///
+    /// See [`PagedDocument::changed_pages`] for how to determine the changed
+    /// pages. Only introspectors that were themselves created by this
+    /// function can be reused, as keeping the elements of each page is only
+    /// worth it if the introspector is updated later. For any other previous
+    /// introspector, all pages are searched. To start a series of updates,
+    /// update [`Introspector::default`] with all pages.
+    ///
+    /// [`PagedDocument::changed_pages`]: crate::layout::PagedDocument::changed_pages
+    #[typst_macros::time(name = "introspect pages (update)")]
+    pub fn update(previous: &Introspector, pages: &[Page], changed: &[usize]) -> Self {
+        let mut reusable: Vec<_> = previous.page_items.iter().map(Some).collect();
+        for &i in changed {
+            if let Some(items) = reusable.get_mut(i) {
+                *items = None;
+            }
+        }
+
+        IntrospectorBuilder { retain: true, ..IntrospectorBuilder::new() }
+            .build_paged(pages, |i| reusable.get(i).copied().flatten().cloned())
     }
 
     /// Creates an introspector for HTML.
@@ -59,6 +107,69 @@ impl Introspector {
         IntrospectorBuilder::new().build_html(root)
     }
 
+    /// Creates a reduced introspector that only retains the elements for
+    /// which `keep` returns `true`.
+    ///
+    /// This is meant for long-running sessions like language servers that
+    /// keep the last document around for queries. Retaining only the few
+    /// kinds of elements that these queries need, like headings and labelled
+    /// elements, avoids keeping every locatable element and its content
+    /// alive. Page numberings and supplements as well as the positions of
+    /// retained elements are kept.
+    ///
+    /// Queries on the reduced introspector behave as if the dropped elements
+    /// did not exist: They yield no results, dropped locations have no
+    /// [`position`](Self::position), and labels on dropped elements do not
+    /// resolve. Use [`is_slim`](Self::is_slim) to
+    /// find out whether results may be incomplete. The reduced introspector
+    /// cannot be used to lay out a document.
+    pub fn slim(&self, keep: &dyn Fn(&Content) -> bool) -> Introspector {
+        let mut locations = HashMap::new();
+        let mut labels = MultiMap::default();
+        let mut elems = Vec::new();
+        for pair in &self.elems {
+            if !keep(&pair.0) {
+                continue;
+            }
+
+            let idx = elems.len();
+            locations.insert(pair.0.location().unwrap(), idx);
+            if let Some(label) = pair.0.label() {
+                labels.insert(label, idx);
+            }
+            elems.push(pair.clone());
+        }
+
+        let anchors = self
+            .anchors
+            .iter()
+            .filter(|(loc, _)| locations.contains_key(loc))
+            .map(|(loc, id)| (*loc, id.clone()))
+            .collect();
+
+        Introspector {
+            html: self.html,
+            slim: true,
+            pages: self.pages,
+            page_numberings: self.page_numberings.clone(),
+            page_supplements: self.page_supplements.clone(),
+            elems,
+            keys: MultiMap::default(),
+            locations,
+            labels,
+            anchors,
+            label_anchors: self.label_anchors.clone(),
+            page_items: Vec::new(),
+            queries: QueryCache::default(),
+        }
+    }
+
+    /// Whether this introspector was reduced with [`slim`](Self::slim), in
+    /// which case queries only find the retained elements.
+    pub fn is_slim(&self) -> bool {
+        self.slim
+    }
+
     /// Iterates over all locatable elements.
     pub fn all(&self) -> impl Iterator<Item = &Content> + '_ {
         self.elems.iter().map(|(c, _)| c)
@@ -257,14 +368,55 @@ impl Introspector {
     }
 
     /// Find the page number for the given location.
+    ///
+    /// Falls back to the first page if the location is unknown, e.g. because
+    /// the element was not laid out yet.
     pub fn page(&self, location: Location) -> NonZeroUsize {
-        self.position(location).page
+        self.position(location).map_or(NonZeroUsize::ONE, |pos| pos.page)
+    }
+
+    /// Whether this introspector belongs to an HTML document.
+    ///
+    /// HTML documents are not paginated, so all elements are on the first
+    /// page and at the origin.
+    pub fn is_html(&self) -> bool {
+        self.html
+    }
+
+    /// The index of the element with the given location in document order.
+    pub fn index(&self, location: Location) -> Option<usize> {
+        self.locations.get(&location).copied()
+    }
+
+    /// The anchor of the given label, as configured by the document's
+    /// [`anchors`](crate::model::DocumentElem::anchors) setting.
+    ///
+    /// Both the HTML and the PDF exporter name link targets with this, so
+    /// that the same anchor works for both formats.
+    pub fn anchor_name(&self, label: Label, naming: AnchorNaming) -> EcoString {
+        match naming {
+            AnchorNaming::Label => label.resolve().as_str().into(),
+            AnchorNaming::Unified => self
+                .label_anchors
+                .get(&label)
+                .cloned()
+                .unwrap_or_else(|| slugify(label.resolve().as_str(), "anchor")),
+        }
+    }
+
+    /// The id of the HTML element that the given location was realized as,
+    /// or else of the nearest HTML element with an id that precedes or
+    /// contains it in an HTML document.
+    pub fn anchor(&self, location: Location) -> Option<EcoString> {
+        self.anchors.get(&location).cloned()
     }
 
     /// Find the position for the given location.
-    pub fn position(&self, location: Location) -> Position {
+    ///
+    /// Returns `None` if the location is unknown, e.g. because the element was
+    /// not laid out yet or was dropped with [`slim`](Self::slim).
+    pub fn position(&self, location: Location) -> Option<Position> {
         self.get_pos_by_loc(&location)
-            .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() })
     }
 
     /// Gets the page numbering for the given location, if any.
@@ -353,6 +505,7 @@ impl Clone for QueryCache {
 /// Builds the introspector.
 #[derive(Default)]
 struct IntrospectorBuilder {
+    html: bool,
     pages: usize,
     page_numberings: Vec<Option<Numbering>>,
     page_supplements: Vec<Content>,
@@ -361,6 +514,27 @@ struct IntrospectorBuilder {
     keys: MultiMap<u128, Location>,
     locations: HashMap<Location, usize>,
     labels: MultiMap<Label, usize>,
+    anchor: Option<EcoString>,
+    anchors: HashMap<Location, EcoString>,
+    /// Whether to keep the items of each page for later updates.
+    retain: bool,
+    page_items: Vec<Arc<[Discovery]>>,
+}
+
+/// An introspectable item found in a frame.
+///
+/// Replaying the items of a frame in the order in which they were found has
+/// the same effect on an [`IntrospectorBuilder`] as searching the frame
+/// again.
+#[derive(Clone)]
+enum Discovery {
+    /// The start of an element.
+    Start(Content, Position),
+    /// The end of an element, with its key.
+    End(Location, u128),
+    /// The items of a group that belong after the element with the given
+    /// location.
+    Insertion(Location, Vec<Discovery>),
 }
 
 impl IntrospectorBuilder {
@@ -369,23 +543,39 @@ impl IntrospectorBuilder {
         Self::default()
     }
 
-    /// Build an introspector for a page list.
-    fn build_paged(mut self, pages: &[Page]) -> Introspector {
+    /// Build an introspector for a page list, reusing the items that `reuse`
+    /// returns for a page index instead of searching the page.
+    fn build_paged(
+        mut self,
+        pages: &[Page],
+        mut reuse: impl FnMut(usize) -> Option<Arc<[Discovery]>>,
+    ) -> Introspector {
         self.pages = pages.len();
         self.page_numberings.reserve(pages.len());
         self.page_supplements.reserve(pages.len());
+        if self.retain {
+            self.page_items.reserve(pages.len());
+        }
 
         // Discover all elements.
         let mut elems = Vec::new();
         for (i, page) in pages.iter().enumerate() {
             self.page_numberings.push(page.numbering.clone());
             self.page_supplements.push(page.supplement.clone());
-            self.discover_in_frame(
-                &mut elems,
-                &page.frame,
-                NonZeroUsize::new(1 + i).unwrap(),
-                Transform::identity(),
-            );
+            let items = reuse(i).unwrap_or_else(|| {
+                let mut items = Vec::new();
+                discover_in_frame(
+                    &mut items,
+                    &page.frame,
+                    NonZeroUsize::new(1 + i).unwrap(),
+                    Transform::identity(),
+                );
+                items.into()
+            });
+            self.replay(&mut elems, &items);
+            if self.retain {
+                self.page_items.push(items);
+            }
         }
 
         self.finalize(elems)
@@ -393,63 +583,62 @@ impl IntrospectorBuilder {
 
     /// Build an introspector for an HTML document.
     fn build_html(mut self, root: &HtmlElement) -> Introspector {
+        self.html = true;
         let mut elems = Vec::new();
         self.discover_in_html(&mut elems, root);
         self.finalize(elems)
     }
 
-    /// Processes the tags in the frame.
-    fn discover_in_frame(
-        &mut self,
-        sink: &mut Vec<Pair>,
-        frame: &Frame,
-        page: NonZeroUsize,
-        ts: Transform,
-    ) {
-        for (pos, item) in frame.items() {
+    /// Processes the items found in a frame.
+    fn replay(&mut self, sink: &mut Vec<Pair>, items: &[Discovery]) {
+        for item in items {
             match item {
-                FrameItem::Group(group) => {
-                    let ts = ts
-                        .pre_concat(Transform::translate(pos.x, pos.y))
-                        .pre_concat(group.transform);
-
-                    if let Some(parent) = group.parent {
-                        let mut nested = vec![];
-                        self.discover_in_frame(&mut nested, &group.frame, page, ts);
-                        self.insertions.insert(parent, nested);
-                    } else {
-                        self.discover_in_frame(sink, &group.frame, page, ts);
-                    }
-                }
-                FrameItem::Tag(tag) => {
-                    self.discover_in_tag(
-                        sink,
-                        tag,
-                        Position { page, point: pos.transform(ts) },
-                    );
+                Discovery::Start(elem, position) => self.start(sink, elem, *position),
+                Discovery::End(loc, key) => self.keys.insert(*key, *loc),
+                Discovery::Insertion(parent, items) => {
+                    let mut nested = vec![];
+                    self.replay(&mut nested, items);
+                    self.insertions.insert(*parent, nested);
                 }
-                _ => {}
             }
         }
     }
 
     /// Processes the tags in the HTML element.
     fn discover_in_html(&mut self, sink: &mut Vec<Pair>, elem: &HtmlElement) {
-        for child in &elem.children {
+        if let Some(id) = elem.attrs.get(attr::id) {
+            self.anchor = Some(id.clone());
+        }
+
+        for (i, child) in elem.children.iter().enumerate() {
             match child {
-                HtmlNode::Tag(tag) => self.discover_in_tag(
-                    sink,
-                    tag,
-                    Position { page: NonZeroUsize::ONE, point: Point::zero() },
-                ),
+                HtmlNode::Tag(tag) => {
+                    // An element that was realized as an HTML element with an
+                    // id, like a labelled heading, is anchored at that id.
+                    let own = first_id(&elem.children[i + 1..]);
+                    if let (Tag::Start(elem), Some(anchor)) =
+                        (tag, own.or(self.anchor.as_ref()))
+                    {
+                        self.anchors.insert(elem.location().unwrap(), anchor.clone());
+                    }
+                    self.discover_in_tag(
+                        sink,
+                        tag,
+                        Position { page: NonZeroUsize::ONE, point: Point::zero() },
+                    )
+                }
                 HtmlNode::Text(_, _) => {}
                 HtmlNode::Element(elem) => self.discover_in_html(sink, elem),
-                HtmlNode::Frame(frame) => self.discover_in_frame(
-                    sink,
-                    frame,
-                    NonZeroUsize::ONE,
-                    Transform::identity(),
-                ),
+                HtmlNode::Frame(frame) => {
+                    let mut items = Vec::new();
+                    discover_in_frame(
+                        &mut items,
+                        frame,
+                        NonZeroUsize::ONE,
+                        Transform::identity(),
+                    );
+                    self.replay(sink, &items);
+                }
             }
         }
     }
@@ -457,18 +646,21 @@ impl IntrospectorBuilder {
     /// Handle a tag.
     fn discover_in_tag(&mut self, sink: &mut Vec<Pair>, tag: &Tag, position: Position) {
         match tag {
-            Tag::Start(elem) => {
-                let loc = elem.location().unwrap();
-                if self.seen.insert(loc) {
-                    sink.push((elem.clone(), position));
-                }
-            }
+            Tag::Start(elem) => self.start(sink, elem, position),
             Tag::End(loc, key) => {
                 self.keys.insert(*key, *loc);
             }
         }
     }
 
+    /// Handle the start of an element. Only its first occurrence counts.
+    fn start(&mut self, sink: &mut Vec<Pair>, elem: &Content, position: Position) {
+        let loc = elem.location().unwrap();
+        if self.seen.insert(loc) {
+            sink.push((elem.clone(), position));
+        }
+    }
+
     /// Build a complete introspector with all acceleration structures from a
     /// list of top-level pairs.
     fn finalize(mut self, root: Vec<Pair>) -> Introspector {
@@ -480,7 +672,14 @@ impl IntrospectorBuilder {
             self.visit(&mut elems, pair);
         }
 
+        // Anchors depend on the document order of the labels, so they can
+        // only be assigned once all elements are in place.
+        let label_anchors =
+            unique_anchors(elems.iter().filter_map(|(elem, _)| elem.label()));
+
         Introspector {
+            html: self.html,
+            slim: false,
             pages: self.pages,
             page_numberings: self.page_numberings,
             page_supplements: self.page_supplements,
@@ -488,6 +687,9 @@ impl IntrospectorBuilder {
             keys: self.keys,
             locations: self.locations,
             labels: self.labels,
+            anchors: self.anchors,
+            label_anchors,
+            page_items: self.page_items,
             queries: QueryCache::default(),
         }
     }
@@ -518,3 +720,170 @@ impl IntrospectorBuilder {
         }
     }
 }
+
+/// The id of the first HTML element among the nodes, skipping leading tags.
+fn first_id(nodes: &[HtmlNode]) -> Option<&EcoString> {
+    match nodes.iter().find(|node| !matches!(node, HtmlNode::Tag(_)))? {
+        HtmlNode::Element(elem) => elem.attrs.get(attr::id),
+        _ => None,
+    }
+}
+
+/// Finds the introspectable items in a frame.
+fn discover_in_frame(
+    sink: &mut Vec<Discovery>,
+    frame: &Frame,
+    page: NonZeroUsize,
+    ts: Transform,
+) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+
+                if let Some(parent) = group.parent {
+                    let mut nested = vec![];
+                    discover_in_frame(&mut nested, &group.frame, page, ts);
+                    sink.push(Discovery::Insertion(parent, nested));
+                } else {
+                    discover_in_frame(sink, &group.frame, page, ts);
+                }
+            }
+            FrameItem::Tag(Tag::Start(elem)) => {
+                let position = Position { page, point: pos.transform(ts) };
+                sink.push(Discovery::Start(elem.clone(), position));
+            }
+            FrameItem::Tag(Tag::End(loc, key)) => {
+                sink.push(Discovery::End(*loc, *key));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_utils::PicoStr;
+
+    use super::*;
+    use crate::foundations::{NativeElement, Smart};
+    use crate::layout::{Abs, PagedDocument, Sides, Size};
+    use crate::model::HeadingElem;
+
+    /// A labelled heading with the given location hash.
+    fn heading(hash: u128) -> Content {
+        let mut elem = HeadingElem::new(Content::empty())
+            .pack()
+            .labelled(Label::new(PicoStr::intern(&format!("sec-{hash}"))));
+        elem.set_location(Location::new(hash));
+        elem
+    }
+
+    /// A page with the given elements, one below the other.
+    fn page(elems: &[Content]) -> Page {
+        let mut frame = Frame::soft(Size::new(Abs::pt(100.0), Abs::pt(100.0)));
+        for (i, elem) in elems.iter().enumerate() {
+            let pos = Point::with_y(Abs::pt(10.0 * i as f64));
+            let loc = elem.location().unwrap();
+            frame.push(pos, FrameItem::Tag(Tag::Start(elem.clone())));
+            frame.push(pos, FrameItem::Tag(Tag::End(loc, loc.hash())));
+        }
+
+        Page {
+            frame,
+            fill: Smart::Auto,
+            numbering: None,
+            supplement: Content::empty(),
+            number: 1,
+            bleed: Sides::splat(Abs::zero()),
+            rotated: false,
+        }
+    }
+
+    /// A document with one heading per page.
+    fn pages(count: u128) -> Vec<Page> {
+        (0..count).map(|i| page(&[heading(i)])).collect()
+    }
+
+    /// The elements with their positions, the number of pages, and the
+    /// location of a labelled heading.
+    fn summary(
+        introspector: &Introspector,
+    ) -> (Vec<(Location, Position)>, NonZeroUsize, Option<Location>) {
+        let elems = introspector
+            .all()
+            .map(|elem| {
+                let loc = elem.location().unwrap();
+                (loc, introspector.position(loc).unwrap())
+            })
+            .collect();
+        let label = Label::new(PicoStr::intern("sec-30"));
+        let labelled =
+            introspector.query_label(label).ok().map(|e| e.location().unwrap());
+        (elems, introspector.pages(), labelled)
+    }
+
+    /// Checks that updating the introspector of `before` for the pages of
+    /// `after` yields the same introspector as building it from scratch.
+    fn check_update(before: Vec<Page>, after: Vec<Page>) -> Vec<usize> {
+        let previous = PagedDocument {
+            introspector: Introspector::update(&Introspector::default(), &before, &[]),
+            pages: before,
+            ..Default::default()
+        };
+        let changed = previous.changed_pages(&after);
+        let updated = Introspector::update(&previous.introspector, &after, &changed);
+        assert_eq!(summary(&updated), summary(&Introspector::paged(&after)));
+        changed
+    }
+
+    #[test]
+    fn test_update_unchanged() {
+        assert_eq!(check_update(pages(40), pages(40)), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_update_reuses_pages() {
+        // Pages that are not marked as changed are not searched again.
+        let before = pages(40);
+        let previous = Introspector::update(&Introspector::default(), &before, &[]);
+        let mut after = before.clone();
+        after[20] = page(&[heading(20), heading(100)]);
+        let updated = Introspector::update(&previous, &after, &[]);
+        assert_eq!(summary(&updated), summary(&previous));
+    }
+
+    #[test]
+    fn test_paged_is_not_reused() {
+        // A fully built introspector keeps no items, so all pages are
+        // searched again.
+        let before = pages(40);
+        let previous = Introspector::paged(&before);
+        assert!(previous.page_items.is_empty());
+        let mut after = before.clone();
+        after[20] = page(&[heading(20), heading(100)]);
+        let updated = Introspector::update(&previous, &after, &[]);
+        assert_eq!(summary(&updated), summary(&Introspector::paged(&after)));
+    }
+
+    #[test]
+    fn test_update_changed_page() {
+        let mut after = pages(40);
+        after[20] = page(&[heading(20), heading(100)]);
+        assert_eq!(check_update(pages(40), after), [20]);
+    }
+
+    #[test]
+    fn test_update_inserted_page() {
+        let mut after = pages(40);
+        after.insert(20, page(&[heading(100)]));
+        assert_eq!(check_update(pages(40), after), (20..41).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_update_removed_pages() {
+        assert_eq!(check_update(pages(40), pages(30)), Vec::<usize>::new());
+    }
+}
@@ -37,5 +37,6 @@ pub fn locate(
     ///   [`location()`]($content.location) method on content.
     selector: LocatableSelector,
 ) -> HintedStrResult<Location> {
+    engine.sink.lookahead();
     selector.resolve_unique(engine.introspector, context)
 }
@@ -2,10 +2,11 @@ use std::fmt::{self, Debug, Formatter};
 use std::num::NonZeroUsize;
 
 use ecow::EcoString;
+use typst_utils::NonZeroExt;
 
 use crate::engine::Engine;
-use crate::foundations::{func, scope, ty, Repr};
-use crate::layout::Position;
+use crate::foundations::{func, scope, ty, Dict, IntoValue, Repr};
+use crate::layout::{Point, Position};
 use crate::model::Numbering;
 
 /// Identifies an element in the document.
@@ -75,9 +76,25 @@ impl Location {
     ///
     /// If you only need the page number, use `page()` instead as it allows
     /// Typst to skip unnecessary work.
+    ///
+    /// HTML documents have no pages, so there, the page is always `{1}` and
+    /// the coordinates are zero. Instead, the dictionary has two additional
+    /// keys: `index`, the position of the element among all locatable
+    /// elements in document order, and `anchor`, the `id` of the nearest
+    /// HTML element with an `id` that precedes or contains the location (or
+    /// `{none}` if there is no such element).
     #[func]
-    pub fn position(self, engine: &mut Engine) -> Position {
-        engine.introspector.position(self)
+    pub fn position(self, engine: &mut Engine) -> Dict {
+        let position = engine
+            .introspector
+            .position(self)
+            .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() });
+        let mut dict: Dict = position.into();
+        if engine.introspector.is_html() {
+            dict.insert("index".into(), engine.introspector.index(self).into_value());
+            dict.insert("anchor".into(), engine.introspector.anchor(self).into_value());
+        }
+        dict
     }
 
     /// Returns the page numbering pattern of the page at this location. This
@@ -0,0 +1,101 @@
+//! Layout decisions that can be queried after layout.
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Args, Construct, Content, Module, Scope};
+use crate::introspection::{Locatable, INTROSPECTION};
+use crate::layout::Length;
+
+/// Create a module with all layout metrics definitions.
+pub fn module() -> Module {
+    let mut metrics = Scope::deduplicating();
+    metrics.category(INTROSPECTION);
+    metrics.define_elem::<MetricsLine>();
+    metrics.define_elem::<MetricsPageBreak>();
+    Module::new("metrics", metrics)
+}
+
+/// A line of a paragraph, as it ended up after layout.
+///
+/// When the `layout-metrics` feature is enabled, paragraph layout emits one
+/// such element per laid-out line. The elements cannot be constructed
+/// manually, but they can be [queried]($query) like any other locatable
+/// element. The [location]($location) of a line yields its page and the
+/// position of its top-left corner. Just like other positions, this
+/// information is only available once the document was laid out at least
+/// once, so queries take effect from the next layout iteration on.
+///
+/// # Example
+/// Line numbers in the margin can be built from the lines with a page
+/// foreground. The numbers themselves should not be recorded as lines, as
+/// they would otherwise number themselves.
+///
+/// ```typ
+/// #set page(foreground: {
+///   set metrics.line(record: false)
+///   context for (i, line) in query(metrics.line).enumerate() {
+///     let pos = line.location().position()
+///     if pos.page == here().page() {
+///       place(
+///         dx: 0.5cm,
+///         dy: pos.y + line.baseline,
+///         text(size: 7pt, top-edge: "baseline")[#(i + 1)],
+///       )
+///     }
+///   }
+/// })
+/// ```
+#[elem(name = "line", title = "Laid-out Line", Construct, Locatable)]
+pub struct MetricsLine {
+    /// Whether lines are recorded.
+    ///
+    /// Set this to `{false}` for content that should not show up in queries,
+    /// like line numbers that are placed based on the other lines.
+    #[ghost]
+    #[default(true)]
+    pub record: bool,
+
+    /// The width of the line, including the space that is left over for
+    /// alignment.
+    #[required]
+    pub width: Length,
+
+    /// The height of the line.
+    #[required]
+    pub height: Length,
+
+    /// The distance from the top of the line to its baseline.
+    #[required]
+    pub baseline: Length,
+
+    /// The byte offset in the source file at which the text of the line
+    /// starts, if the line contains text from a file.
+    #[required]
+    pub start: Option<usize>,
+
+    /// The byte offset in the source file at which the text of the line ends.
+    #[required]
+    pub end: Option<usize>,
+}
+
+impl Construct for MetricsLine {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
+
+/// The start of a page that follows another page.
+///
+/// When the `layout-metrics` feature is enabled, an element of this kind is
+/// emitted at the very top of every page but the first. Its
+/// [location]($location) yields the page that starts after the break.
+/// Together with [lines]($metrics.line), this can be used to find the
+/// content that is split across pages, e.g. to add "continued" markers.
+#[elem(name = "page-break", title = "Resolved Page Break", Construct, Locatable)]
+pub struct MetricsPageBreak {}
+
+impl Construct for MetricsPageBreak {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
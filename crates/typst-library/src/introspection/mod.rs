@@ -1,5 +1,7 @@
 //! Interaction between document parts.
 
+pub mod metrics;
+
 mod counter;
 #[path = "here.rs"]
 mod here_;
@@ -150,6 +150,7 @@ pub fn query(
     target: LocatableSelector,
 ) -> HintedStrResult<Array> {
     context.introspect()?;
+    engine.sink.lookahead();
     let vec = engine.introspector.query(&target.0);
     Ok(vec.into_iter().map(Value::Content).collect())
 }
@@ -319,6 +319,7 @@ impl State {
         selector: LocatableSelector,
     ) -> SourceResult<Value> {
         let loc = selector.resolve_unique(engine.introspector, context).at(span)?;
+        engine.sink.lookahead();
         self.at_loc(engine, loc)
     }
 
@@ -334,6 +335,7 @@ impl State {
         span: Span,
     ) -> SourceResult<Value> {
         context.introspect().at(span)?;
+        engine.sink.lookahead();
         let sequence = self.sequence(engine)?;
         Ok(sequence.last().unwrap().clone())
     }
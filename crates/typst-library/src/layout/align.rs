@@ -154,6 +154,12 @@ pub enum Alignment {
 }
 
 impl Alignment {
+    /// Aligns the first text baselines of the cells in a grid or table row.
+    ///
+    /// This is not in the type's scope, as it is only meaningful for grids
+    /// and tables. It is available as `grid.baseline` and `table.baseline`.
+    pub const BASELINE: Self = Alignment::V(VAlignment::Baseline);
+
     /// The horizontal component.
     pub const fn x(self) -> Option<HAlignment> {
         match self {
@@ -456,6 +462,9 @@ pub enum VAlignment {
     Top,
     Horizon,
     Bottom,
+    /// Aligns the first baselines of grid cells in the same row and acts like
+    /// `Top` elsewhere. Only available in the scopes of grid and table.
+    Baseline,
 }
 
 impl VAlignment {
@@ -465,6 +474,7 @@ impl VAlignment {
             Self::Top => Self::Bottom,
             Self::Horizon => Self::Horizon,
             Self::Bottom => Self::Top,
+            Self::Baseline => Self::Baseline,
         }
     }
 
@@ -472,7 +482,7 @@ impl VAlignment {
     /// extent.
     pub fn position(self, extent: Abs) -> Abs {
         match self {
-            Self::Top => Abs::zero(),
+            Self::Top | Self::Baseline => Abs::zero(),
             Self::Horizon => extent / 2.0,
             Self::Bottom => extent,
         }
@@ -483,7 +493,7 @@ impl FixAlignment for VAlignment {
     fn fix(self, _: Dir) -> FixedAlignment {
         // The vertical alignment does not depend on text direction.
         match self {
-            Self::Top => FixedAlignment::Start,
+            Self::Top | Self::Baseline => FixedAlignment::Start,
             Self::Horizon => FixedAlignment::Center,
             Self::Bottom => FixedAlignment::End,
         }
@@ -496,6 +506,7 @@ impl Repr for VAlignment {
             Self::Top => "top".into(),
             Self::Horizon => "horizon".into(),
             Self::Bottom => "bottom".into(),
+            Self::Baseline => "baseline".into(),
         }
     }
 }
@@ -528,7 +539,10 @@ impl TryFrom<Alignment> for VAlignment {
     fn try_from(value: Alignment) -> StrResult<Self> {
         match value {
             Alignment::V(v) => Ok(v),
-            v => bail!("expected `top`, `horizon`, or `bottom`, found {}", v.repr()),
+            v => bail!(
+                "expected `top`, `horizon`, `bottom`, or `baseline`, found {}",
+                v.repr()
+            ),
         }
     }
 }
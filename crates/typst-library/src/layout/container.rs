@@ -6,8 +6,8 @@ use crate::foundations::{
 };
 use crate::introspection::Locator;
 use crate::layout::{
-    Abs, Corners, Em, Fr, Fragment, Frame, Length, Region, Regions, Rel, Sides, Size,
-    Spacing,
+    Abs, Corners, Em, Fr, Fragment, Frame, FrameRole, Length, Region, Regions, Rel,
+    Sides, Size, Spacing,
 };
 use crate::visualize::{Paint, Stroke};
 
@@ -352,10 +352,44 @@ pub struct BlockElem {
     #[default(false)]
     pub sticky: bool,
 
+    /// Whether to keep the block's contents together on one page.
+    ///
+    /// This is useful to group several blocks, for instance the title, body,
+    /// and footer of a callout box that are emitted by a show rule. If such a
+    /// group does not fit into the remaining space on the page, it moves to
+    /// the next page as a whole. Other than with
+    /// [`breakable: false`]($block.breakable), a group that is taller than a
+    /// full page does not overflow, but is broken across pages as usual. Set
+    /// this to `{"warn"}` to get a warning when that happens.
+    ///
+    /// The block's [spacing]($block.spacing) is not part of the group, and
+    /// weak spacing at the group's edges collapses like in any other block.
+    /// Floating figures within the group are placed within it. When groups
+    /// are nested, the outermost group that fits on a page is kept together.
+    ///
+    /// This has no effect on blocks that are not breakable anyway.
+    ///
+    /// ```example
+    /// >>> #set page(height: 140pt)
+    /// #lorem(20)
+    ///
+    /// #block(keep: true)[
+    ///   #block(fill: aqua, inset: 4pt)[*Note*]
+    ///   #block(fill: aqua.lighten(50%), inset: 4pt, lorem(10))
+    /// ]
+    /// ```
+    #[default(Keep::Off)]
+    pub keep: Keep,
+
     /// The contents of the block.
     #[positional]
     #[borrowed]
     pub body: Option<BlockBody>,
+
+    /// The semantic role of the block's contents, set by built-in show rules.
+    #[internal]
+    #[synthesized]
+    pub role: FrameRole,
 }
 
 impl BlockElem {
@@ -425,6 +459,39 @@ cast! {
     v: Content => Self::Content(v),
 }
 
+/// Whether and how a block keeps its contents together.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Keep {
+    /// The block breaks freely.
+    #[default]
+    Off,
+    /// The block moves to the next region if it does not fit into the
+    /// current one, unless it is taller than a full region.
+    On,
+    /// Like `On`, but warns when the block is taller than a full region.
+    Warn,
+}
+
+impl Keep {
+    /// Whether the block should be kept together.
+    pub fn is_on(self) -> bool {
+        self != Self::Off
+    }
+}
+
+cast! {
+    Keep,
+    self => match self {
+        Self::Off => false.into_value(),
+        Self::On => true.into_value(),
+        Self::Warn => "warn".into_value(),
+    },
+    v: bool => if v { Self::On } else { Self::Off },
+    /// Keep the contents together and warn if they are too tall to be kept
+    /// together.
+    "warn" => Self::Warn,
+}
+
 /// Defines how to size something along an axis.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Sizing {
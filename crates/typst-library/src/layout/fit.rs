@@ -0,0 +1,66 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Packed, Show, Smart, StyleChain};
+use crate::layout::{Abs, BlockElem, Length};
+
+/// Shrinks or grows text to fit into the available space.
+///
+/// The body is laid out at the largest [text size]($text.size) between `min`
+/// and `max` for which it fits into its container, both horizontally and
+/// vertically. This is useful for slides, posters, and business cards, where
+/// text of varying length must fill a fixed-size box.
+///
+/// # Example
+/// ```example
+/// #let card(body) = box(
+///   width: 4cm,
+///   height: 1.5cm,
+///   stroke: 0.5pt,
+///   inset: 4pt,
+///   fit-text(body),
+/// )
+///
+/// #card[Hello]
+/// #card[A much longer text that needs to be set much smaller]
+/// ```
+///
+/// # Behavior
+/// - All paragraphs of the body share one text size.
+/// - Only the text size changes. Everything that is relative to it, like
+///   spacing in `em`, changes with it, but content with an absolute size,
+///   like most [images]($image), stays as it is.
+/// - A nested `fit-text` is fitted into the space that it receives from the
+///   outer one and its size is not affected by the outer size.
+/// - If the body does not fit even at the `min` size, it is laid out at that
+///   size and overflows its container.
+/// - If the available space is unbounded in both directions, the body is laid
+///   out at the `max` size.
+#[elem(Show)]
+pub struct FitTextElem {
+    /// The smallest text size that may be used.
+    #[default(Abs::pt(8.0).into())]
+    pub min: Length,
+
+    /// The largest text size that may be used.
+    #[default(Abs::pt(64.0).into())]
+    pub max: Length,
+
+    /// How precisely to search for the size that fits best.
+    ///
+    /// The selected size is at most this far below the largest size that
+    /// fits. Smaller steps need more layout attempts. When `{auto}`, a quarter
+    /// of a point is used.
+    pub step: Smart<Length>,
+
+    /// The content whose text should fit.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for Packed<FitTextElem> {
+    fn show(&self, engine: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(BlockElem::single_layouter(self.clone(), engine.routines.layout_fit_text)
+            .pack()
+            .spanned(self.span()))
+    }
+}
@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use smallvec::SmallVec;
 use typst_syntax::Span;
-use typst_utils::{LazyHash, Numeric};
+use typst_utils::{hash128, LazyHash, Numeric};
 
 use crate::foundations::{cast, dict, Dict, Label, StyleChain, Value};
 use crate::introspection::{Location, Tag};
@@ -144,6 +144,63 @@ impl Frame {
     pub fn items(&self) -> std::slice::Iter<'_, (Point, FrameItem)> {
         self.items.iter()
     }
+
+    /// Whether this frame shares its items with another frame, in which case
+    /// both contain the same items. This is much cheaper than comparing the
+    /// items, but may miss frames whose items are equal but were created
+    /// separately.
+    pub fn shares_items(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.items, &other.items)
+    }
+
+    /// Whether this frame contains the same tags at the same positions as
+    /// another frame, which makes them indistinguishable for introspection.
+    ///
+    /// Only tags and groups are compared and subframes that share their items
+    /// are skipped. This is much cheaper than comparing or hashing the frames,
+    /// which would also look at all text and shapes.
+    pub fn same_tags(&self, other: &Self) -> bool {
+        if self.shares_items(other) {
+            return true;
+        }
+
+        let relevant = |(_, item): &&(Point, FrameItem)| {
+            matches!(item, FrameItem::Group(_) | FrameItem::Tag(_))
+        };
+        let mut ours = self.items().filter(relevant);
+        let mut theirs = other.items().filter(relevant);
+        loop {
+            let same = match (ours.next(), theirs.next()) {
+                (None, None) => return true,
+                (Some((p1, item1)), Some((p2, item2))) => {
+                    p1 == p2
+                        && match (item1, item2) {
+                            (FrameItem::Group(g1), FrameItem::Group(g2)) => {
+                                g1.transform == g2.transform
+                                    && g1.parent == g2.parent
+                                    && g1.frame.same_tags(&g2.frame)
+                            }
+                            (
+                                FrameItem::Tag(Tag::Start(e1)),
+                                FrameItem::Tag(Tag::Start(e2)),
+                            ) => {
+                                e1.location() == e2.location()
+                                    && hash128(e1) == hash128(e2)
+                            }
+                            (
+                                FrameItem::Tag(Tag::End(l1, k1)),
+                                FrameItem::Tag(Tag::End(l2, k2)),
+                            ) => l1 == l2 && k1 == k2,
+                            _ => false,
+                        }
+                }
+                _ => false,
+            };
+            if !same {
+                return false;
+            }
+        }
+    }
 }
 
 /// Insert items and subframes.
@@ -155,7 +212,12 @@ impl Frame {
     }
 
     /// Add an item at a position in the foreground.
+    ///
+    /// In debug builds, panics if the position is not finite. Exporters cannot
+    /// represent such positions, so layout must never produce them.
+    #[track_caller]
     pub fn push(&mut self, pos: Point, item: FrameItem) {
+        debug_assert!(pos.is_finite(), "frame item position must be finite");
         Arc::make_mut(&mut self.items).push((pos, item));
     }
 
@@ -347,6 +409,41 @@ impl Frame {
         }
     }
 
+    /// Merge adjacent text items that seamlessly continue each other into
+    /// single runs.
+    ///
+    /// This does not change how the frame looks, but reduces the number of
+    /// items exporters have to process, e.g. for text produced by a show rule
+    /// that styles each character individually.
+    pub fn merge_text_runs(&mut self) {
+        let mergeable = self.items.windows(2).any(|pair| match pair {
+            [(a, FrameItem::Text(prev)), (b, FrameItem::Text(next))] => {
+                prev.continues_into(*a, next, *b)
+            }
+            _ => false,
+        });
+
+        if !mergeable {
+            return;
+        }
+
+        let items = Arc::make_mut(&mut self.items);
+        let mut merged: Vec<(Point, FrameItem)> = Vec::with_capacity(items.len());
+        for (pos, item) in items.drain(..) {
+            if let (Some((prev_pos, FrameItem::Text(prev))), FrameItem::Text(next)) =
+                (merged.last_mut(), &item)
+            {
+                if prev.continues_into(*prev_pos, next, pos) {
+                    let FrameItem::Text(next) = item else { unreachable!() };
+                    prev.append(next);
+                    continue;
+                }
+            }
+            merged.push((pos, item));
+        }
+        **items = merged;
+    }
+
     /// Hide all content in the frame, but keep metadata.
     pub fn hide(&mut self) {
         Arc::make_mut(&mut self.items).retain_mut(|(_, item)| match item {
@@ -390,6 +487,11 @@ impl Frame {
         self.group(|g| g.label = Some(label));
     }
 
+    /// Mark the frame's contents with a semantic role.
+    pub fn set_role(&mut self, role: FrameRole) {
+        self.group(|g| g.role = Some(role));
+    }
+
     /// Set a parent for the frame. As a result, all elements in the frame
     /// become logically ordered immediately after the given location.
     pub fn set_parent(&mut self, parent: Location) {
@@ -551,6 +653,11 @@ pub struct GroupItem {
     /// The group's logical parent. All elements in this group are logically
     /// ordered immediately after the parent's start location.
     pub parent: Option<Location>,
+    /// The semantic role of the group's contents.
+    ///
+    /// Only assigned if the [`A11yExtras`](crate::Feature::A11yExtras) feature
+    /// is enabled.
+    pub role: Option<FrameRole>,
 }
 
 impl GroupItem {
@@ -562,8 +669,26 @@ impl GroupItem {
             clip: None,
             label: None,
             parent: None,
+            role: None,
         }
     }
+
+    /// Whether nothing in the group can be visible.
+    ///
+    /// This is the case if the group's transform collapses it into a line or
+    /// a point, or if it is clipped to a curve without any segments. Exporters
+    /// skip such groups. Groups without a clip are never invisible because of
+    /// their size as their contents may overflow.
+    pub fn is_invisible(&self) -> bool {
+        let Transform { sx, ky, kx, sy, tx, ty } = self.transform;
+        let det = sx.get() * sy.get() - kx.get() * ky.get();
+        let singular = !det.is_finite() || det.abs() < 1e-12;
+        let clipped = self
+            .clip
+            .as_ref()
+            .is_some_and(|clip| !clip.has_segments() || !clip.is_finite());
+        singular || !tx.is_finite() || !ty.is_finite() || clipped
+    }
 }
 
 impl Debug for GroupItem {
@@ -573,6 +698,37 @@ impl Debug for GroupItem {
     }
 }
 
+/// What a group of frame items represents in the document's logical
+/// structure.
+///
+/// Roles are assigned by the layouters and built-in show rules when the
+/// [`A11yExtras`](crate::Feature::A11yExtras) feature is enabled. They give
+/// exporters the structure that is otherwise only available by matching up
+/// start and end [tags](FrameItem::Tag).
+///
+/// A role covers all frames that its element was laid out into. The group of
+/// a heading that breaks across two pages is thus present on both pages.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum FrameRole {
+    /// A heading of the given level.
+    Heading(NonZeroUsize),
+    /// The body of a list or enumeration item.
+    ListItem,
+    /// A table cell at the given position. Header and footer cells that are
+    /// repeated on multiple pages are marked on each of them.
+    TableCell { x: usize, y: usize },
+    /// The caption of a figure.
+    Caption,
+    /// A run of linked inline content. A link that spans multiple lines or
+    /// differently styled runs of text is marked once per line and run. The
+    /// group contains the [`FrameItem::Link`] with the link's destination.
+    Link,
+    /// Content that is not part of the document's logical structure, like
+    /// page headers, footers, and backgrounds.
+    Artifact,
+}
+
 /// A physical position in a document.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Position {
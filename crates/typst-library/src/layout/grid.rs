@@ -9,10 +9,12 @@ use crate::diag::{bail, At, HintedStrResult, HintedString, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, scope, Array, CastInfo, Content, Context, Fold, FromValue, Func,
-    IntoValue, NativeElement, Packed, Reflect, Resolve, Show, Smart, StyleChain, Value,
+    IntoValue, NativeElement, Packed, Reflect, Resolve, Show, Smart, StyleChain,
+    TargetElem, Value,
 };
 use crate::layout::{
-    Alignment, BlockElem, Length, OuterHAlignment, OuterVAlignment, Rel, Sides, Sizing,
+    Alignment, BlockElem, Dir, Length, OuterHAlignment, OuterVAlignment, Rel, Sides,
+    Sizing,
 };
 use crate::model::{TableCell, TableFooter, TableHLine, TableHeader, TableVLine};
 use crate::visualize::{Paint, Stroke};
@@ -203,7 +205,9 @@ pub struct GridElem {
     /// This can either be a single alignment, an array of alignments
     /// (corresponding to each column) or a function that returns an alignment.
     /// The function receives the cells' column and row indices, starting from
-    /// zero. If set to `{auto}`, the outer alignment is used.
+    /// zero. If set to `{auto}`, the outer alignment is used. With the
+    /// vertical alignment `grid.baseline`, the first text baselines of the
+    /// cells in a row are aligned.
     ///
     /// You can find an example for this argument at the
     /// [`table.align`]($table.align) parameter.
@@ -298,6 +302,20 @@ pub struct GridElem {
     #[fold]
     pub inset: Celled<Sides<Option<Rel<Length>>>>,
 
+    /// The minimum number of body rows that the grid keeps together in the
+    /// first and the last region it is split across.
+    ///
+    /// You can find an example for this argument at the
+    /// [`table.min-rows-per-region`]($table.min-rows-per-region) parameter.
+    #[default(NonZeroUsize::ONE)]
+    pub min_rows_per_region: NonZeroUsize,
+
+    /// The direction in which the columns are laid out.
+    ///
+    /// You can find an example for this argument at the
+    /// [`table.dir`]($table.dir) parameter.
+    pub dir: Smart<Dir>,
+
     /// The contents of the grid cells, plus any extra grid lines specified
     /// with the [`grid.hline`]($grid.hline) and [`grid.vline`]($grid.vline)
     /// elements.
@@ -309,6 +327,13 @@ pub struct GridElem {
 
 #[scope]
 impl GridElem {
+    /// Aligns the first text baselines of the cells in a row. Cells without
+    /// text are aligned at the top.
+    ///
+    /// Use it as the vertical part of the [`align`]($grid.align) argument of
+    /// the grid or its cells.
+    const BASELINE: Alignment = Alignment::BASELINE;
+
     #[elem]
     type GridCell;
 
@@ -326,7 +351,11 @@ impl GridElem {
 }
 
 impl Show for Packed<GridElem> {
-    fn show(&self, engine: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            return (engine.routines.html_grid)(self, engine, styles);
+        }
+
         Ok(BlockElem::multi_layouter(self.clone(), engine.routines.layout_grid)
             .pack()
             .spanned(self.span()))
@@ -747,6 +776,10 @@ cast! {
 
 impl Show for Packed<GridCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(self.body().clone());
+        }
+
         show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
     }
 }
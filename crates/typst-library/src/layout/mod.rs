@@ -9,6 +9,7 @@ mod container;
 mod corners;
 mod dir;
 mod em;
+mod fit;
 mod fr;
 mod fragment;
 mod frame;
@@ -42,6 +43,7 @@ pub use self::container::*;
 pub use self::corners::*;
 pub use self::dir::*;
 pub use self::em::*;
+pub use self::fit::*;
 pub use self::fr::*;
 pub use self::fragment::*;
 pub use self::frame::*;
@@ -96,6 +98,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<AlignElem>();
     global.define_elem::<PadElem>();
     global.define_elem::<RepeatElem>();
+    global.define_elem::<FitTextElem>();
     global.define_elem::<MoveElem>();
     global.define_elem::<ScaleElem>();
     global.define_elem::<RotateElem>();
@@ -3,19 +3,19 @@ use std::num::NonZeroUsize;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-use comemo::Track;
-use typst_utils::{singleton, NonZeroExt, Scalar};
+use comemo::{Track, Tracked};
+use typst_utils::{singleton, NonZeroExt, Numeric, Scalar};
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Args, AutoValue, Cast, Construct, Content, Context, Dict, Fold, Func,
-    NativeElement, Set, Smart, StyleChain, Value,
+    cast, dict, elem, func, scope, Args, AutoValue, Cast, Construct, Content, Context,
+    Dict, Fold, Func, NativeElement, Set, Smart, StyleChain, Value,
 };
 use crate::introspection::Introspector;
 use crate::layout::{
     Abs, Alignment, FlushElem, Frame, HAlignment, Length, OuterVAlignment, Ratio, Rel,
-    Sides, SpecificAlignment,
+    Sides, Size, SpecificAlignment,
 };
 use crate::model::{DocumentInfo, Numbering};
 use crate::text::LocalName;
@@ -40,7 +40,7 @@ use crate::visualize::{Color, Paint};
 ///
 /// There you go, US friends!
 /// ```
-#[elem(Construct)]
+#[elem(scope, Construct)]
 pub struct PageElem {
     /// A standard paper size to set width and height.
     ///
@@ -109,6 +109,69 @@ pub struct PageElem {
     #[ghost]
     pub flipped: bool,
 
+    /// How a [flipped]($page.flipped) page is turned into landscape
+    /// orientation.
+    ///
+    /// - `{"flip-box"}`: The page itself is turned into landscape orientation.
+    ///   Its header and footer run along its long edges.
+    /// - `{"rotate-content"}`: The page stays in portrait orientation like the
+    ///   pages around it. Only its body is laid out in landscape orientation
+    ///   and rotated by 90 degrees counterclockwise. The margins, header, and
+    ///   footer stay where they are on portrait pages, so that the page number
+    ///   ends up in the same physical corner when the document is printed. In
+    ///   PDF export, the page is marked to be displayed rotated by 90 degrees
+    ///   clockwise, so that viewers show its body upright.
+    ///
+    /// This has no effect on pages that are not flipped and on pages whose
+    /// width or height is `{auto}`, which are always flipped as a whole.
+    ///
+    /// ```example
+    /// #set page(width: 120pt, height: 160pt, numbering: "1")
+    /// #page(flipped: true, orientation: "rotate-content")[
+    ///   A wide table goes here.
+    /// ]
+    /// ```
+    #[ghost]
+    pub orientation: PageOrientation,
+
+    /// How far the page's fill, background, and foreground extend beyond the
+    /// edges of the page.
+    ///
+    /// Printed documents are often produced on larger sheets and cut to size
+    /// afterwards. So that no thin white edges remain where the cut is not
+    /// perfectly accurate, colors and images that reach to the edge of the
+    /// page should extend a bit beyond it, into the _bleed._ Printers
+    /// typically ask for a bleed of 3mm.
+    ///
+    /// The bleed is added around the page and does not change its size. The
+    /// [fill]($page.fill) covers it and the [background]($page.background)
+    /// and [foreground]($page.foreground) are laid out with their size
+    /// including it, which is available through
+    /// [`page.full-size`]($page.full-size). In PDF export, the page without
+    /// bleed becomes the document's _TrimBox_ while the page with bleed becomes
+    /// its _MediaBox_ and _BleedBox._ PNG and SVG export show the page without
+    /// bleed.
+    ///
+    /// The bleed can be set for all sides at once or, like the
+    /// [margins]($page.margin), for individual sides with a dictionary.
+    ///
+    /// ```example
+    /// #set page(
+    ///   bleed: 3mm,
+    ///   background: context {
+    ///     let size = page.full-size()
+    ///     rect(..size, fill: aqua)
+    ///   },
+    /// )
+    ///
+    /// The background extends into
+    /// the bleed around the page.
+    /// ```
+    #[resolve]
+    #[fold]
+    #[ghost]
+    pub bleed: Sides<Option<Length>>,
+
     /// The page's margins.
     ///
     /// - `{auto}`: The margins are set automatically to 2.5/21 times the smaller
@@ -360,6 +423,12 @@ pub struct PageElem {
     #[ghost]
     pub foreground: Option<Content>,
 
+    /// Information about the page whose marginals are being laid out, as
+    /// returned by `page.context`.
+    #[internal]
+    #[ghost]
+    pub marginal: Option<Dict>,
+
     /// The contents of the page(s).
     ///
     /// Multiple pages will be created if the content does not fit on a single
@@ -391,6 +460,93 @@ impl Construct for PageElem {
     }
 }
 
+#[scope]
+impl PageElem {
+    /// Provides information about the page in its header, footer, background,
+    /// and foreground.
+    ///
+    /// Returns a dictionary with the following keys:
+    /// - `number`: The physical number of the page, starting at one. Unlike
+    ///   the [page counter]($counter), this is not affected by counter
+    ///   updates and page numbering.
+    /// - `headings`: All headings that start on the page, in order.
+    /// - `first`: The first of these headings or `{none}`.
+    /// - `last`: The last of these headings or `{none}`.
+    ///
+    /// The information is collected while the page is laid out. Unlike a
+    /// [query] for headings, it thus does not need an additional layout
+    /// iteration, which makes it well suited for running heads.
+    ///
+    /// ```example
+    /// #set page(height: 80pt, header: context {
+    ///   let info = page.context()
+    ///   if info.first != none {
+    ///     info.first.body
+    ///     h(1fr)
+    ///     info.last.body
+    ///   }
+    /// })
+    /// #show heading: set text(11pt)
+    ///
+    /// = Aardvark
+    /// A burrowing mammal.
+    /// = Abacus
+    /// A counting frame.
+    /// = Abbey
+    /// A home for monks.
+    /// ```
+    #[func(contextual)]
+    pub fn context(
+        /// The callsite context.
+        context: Tracked<Context>,
+    ) -> HintedStrResult<Dict> {
+        match PageElem::marginal_in(context.styles()?) {
+            Some(info) => Ok(info),
+            None => bail!(
+                "page information is only available in the marginals of a page";
+                hint: "use it in the header, footer, background, or foreground"
+            ),
+        }
+    }
+
+    /// The size of the page including its [bleed]($page.bleed).
+    ///
+    /// Returns a dictionary with the keys `width` and `height`. This is the
+    /// size that the page's background and foreground are laid out with, so
+    /// content of this size fills the page up to the edges of the bleed.
+    ///
+    /// ```example
+    /// #set page(width: 120pt, height: 80pt, bleed: 5pt)
+    /// #context page.full-size()
+    /// ```
+    #[func(contextual)]
+    pub fn full_size(
+        /// The callsite context.
+        context: Tracked<Context>,
+    ) -> HintedStrResult<Dict> {
+        let styles = context.styles()?;
+        let (Smart::Custom(width), Smart::Custom(height)) =
+            (PageElem::width_in(styles), PageElem::height_in(styles))
+        else {
+            bail!(
+                "the full size of a page is only known if its size is fixed";
+                hint: "use relative sizes like `100%` in the background instead"
+            );
+        };
+
+        let mut size = Size::new(width, height);
+        if PageElem::flipped_in(styles)
+            && PageElem::orientation_in(styles) == PageOrientation::FlipBox
+        {
+            std::mem::swap(&mut size.x, &mut size.y);
+        }
+
+        let Size { x, y } =
+            size + PageElem::bleed_in(styles).unwrap_or_default().sum_by_axis();
+        Ok(dict! { "width" => x, "height" => y })
+    }
+}
+
 impl LocalName for PageElem {
     const KEY: &'static str = "page";
 }
@@ -463,6 +619,32 @@ pub struct PagedDocument {
     pub introspector: Introspector,
 }
 
+impl PagedDocument {
+    /// Determines the indices of the pages whose tags differ from the tags of
+    /// this document's pages at the same index.
+    ///
+    /// Pages beyond the end of this document count as changed. The result
+    /// can be passed to [`Introspector::update`].
+    ///
+    /// Only the tags are compared, see [`Frame::same_tags`]. Hashing the
+    /// frames instead would be much more expensive than searching them for
+    /// tags again, as pages are typically assembled anew on every layout
+    /// iteration and thus do not share their frames with the previous
+    /// document.
+    pub fn changed_pages(&self, pages: &[Page]) -> Vec<usize> {
+        pages
+            .iter()
+            .enumerate()
+            .filter(|(i, page)| {
+                self.pages
+                    .get(*i)
+                    .map_or(true, |prev| !prev.frame.same_tags(&page.frame))
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
 /// A finished page.
 #[derive(Debug, Clone)]
 pub struct Page {
@@ -484,6 +666,20 @@ pub struct Page {
     /// The logical page number (controlled by `counter(page)` and may thus not
     /// match the physical number).
     pub number: usize,
+    /// How far the page's fill and marginals extend beyond the frame on each
+    /// side.
+    ///
+    /// Items in the bleed have negative coordinates or coordinates beyond the
+    /// frame's size. Exporters that show the page without bleed simply crop
+    /// it to the frame.
+    pub bleed: Sides<Abs>,
+    /// Whether viewers should display the page rotated by 90 degrees
+    /// clockwise.
+    ///
+    /// This is the case for flipped pages with
+    /// [`orientation: "rotate-content"`](PageElem::orientation), whose body
+    /// is rotated counterclockwise within the frame.
+    pub rotated: bool,
 }
 
 impl Page {
@@ -500,6 +696,16 @@ impl Page {
     pub fn fill_or_white(&self) -> Option<Paint> {
         self.fill.clone().unwrap_or_else(|| Some(Color::WHITE.into()))
     }
+
+    /// Whether the page has a bleed on any side.
+    pub fn has_bleed(&self) -> bool {
+        self.bleed.iter().any(|side| !side.is_zero())
+    }
+
+    /// The size of the page including its bleed.
+    pub fn full_size(&self) -> Size {
+        self.frame.size() + self.bleed.sum_by_axis()
+    }
 }
 
 /// Specification of the page's margins.
@@ -720,6 +926,28 @@ impl PageRanges {
             (None, None) => true,
         })
     }
+
+    /// The number of the last page that may be included.
+    ///
+    /// Returns `None` if there are no ranges or one of them is unbounded at
+    /// the end.
+    pub fn last_page(&self) -> Option<NonZeroUsize> {
+        let mut last = None;
+        for range in &self.0 {
+            last = last.max(Some((*range.end())?));
+        }
+        last
+    }
+}
+
+/// How a flipped page is turned into landscape orientation.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PageOrientation {
+    /// The page itself is turned into landscape orientation.
+    #[default]
+    FlipBox,
+    /// The page stays in portrait orientation and its body is rotated.
+    RotateContent,
 }
 
 /// Whether something should be even or odd.
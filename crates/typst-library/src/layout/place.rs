@@ -1,4 +1,8 @@
-use crate::foundations::{elem, scope, Cast, Content, Packed, Smart};
+use ecow::EcoString;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, scope, Args, Cast, Construct, Content, Packed, Smart};
 use crate::introspection::{Locatable, Unqueriable};
 use crate::layout::{Alignment, Em, Length, Rel};
 
@@ -159,6 +163,48 @@ pub struct PlaceElem {
     /// were wrapped in a [`move`] element.
     pub dy: Rel<Length>,
 
+    /// Whether the placed element moves to avoid overlapping other placed
+    /// elements of its [group]($place.group).
+    ///
+    /// This is useful for margin notes, which are placed next to the line
+    /// they refer to and would otherwise overlap when they refer to nearby
+    /// lines. Once the positions of all elements of a group are known,
+    /// elements on the same page are moved down by the minimal amount that
+    /// removes their overlaps. They keep the order in which they appear in the
+    /// document. Elements that are side by side, like notes in opposite
+    /// margins, do not overlap and thus stay where they are.
+    ///
+    /// Has no effect if `float` is `{true}`.
+    ///
+    /// ```example
+    /// #set page(height: 100pt, margin: (right: 60pt))
+    /// #let note(body) = box(place(
+    ///   right,
+    ///   dx: 55pt,
+    ///   avoid-overlap: "shift-down",
+    ///   group: "notes",
+    ///   block(width: 50pt, text(7pt, body)),
+    /// ))
+    ///
+    /// A#note[First note]
+    /// B#note[Second note] \
+    /// C#note[Third note]
+    /// ```
+    pub avoid_overlap: PlaceOverlap,
+
+    /// The group of placed elements among which overlaps are avoided.
+    ///
+    /// Elements without a group form a group of their own.
+    pub group: Option<EcoString>,
+
+    /// The maximum distance by which an element is moved to avoid overlaps.
+    ///
+    /// If an element would have to be moved further, it is only moved by this
+    /// distance and a warning is emitted.
+    #[default(Em::new(20.0).into())]
+    #[resolve]
+    pub max_shift: Length,
+
     /// The content to place.
     #[required]
     pub body: Content,
@@ -174,6 +220,45 @@ impl PlaceElem {
     type FlushElem;
 }
 
+/// How a placed element avoids overlapping other placed elements.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PlaceOverlap {
+    /// Overlaps are not avoided.
+    #[default]
+    None,
+    /// The element is moved down until it does not overlap any element of its
+    /// group that comes earlier in the document.
+    ShiftDown,
+}
+
+/// A marker that records where a placed element that avoids overlaps would
+/// be positioned without adjustment.
+///
+/// This element is added next to the placed content during layout and later
+/// searched to determine how far the elements of a group must be moved.
+#[elem(Construct, Locatable, Unqueriable)]
+pub struct PlaceOverlapMarker {
+    #[internal]
+    #[required]
+    pub group: Option<EcoString>,
+
+    #[internal]
+    #[required]
+    pub width: Length,
+
+    #[internal]
+    #[required]
+    pub height: Length,
+}
+
+impl Construct for PlaceOverlapMarker {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
+
+impl Unqueriable for Packed<PlaceOverlapMarker> {}
+
 /// Relative to which containing scope something shall be placed.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum PlacementScope {
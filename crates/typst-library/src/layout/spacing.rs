@@ -1,6 +1,6 @@
 use typst_utils::Numeric;
 
-use crate::foundations::{cast, elem, Content};
+use crate::foundations::{cast, elem, Cast, Content};
 use crate::layout::{Abs, Em, Fr, Length, Ratio, Rel};
 
 /// Inserts horizontal spacing into a paragraph.
@@ -113,6 +113,32 @@ pub struct VElem {
     /// ```
     pub weak: bool,
 
+    /// How the spacing combines with adjacent spacing.
+    ///
+    /// By default, the spacing is inserted in addition to any spacing around
+    /// it. With `{"max"}`, the spacing instead merges with adjacent weak or
+    /// max-collapsing spacing (including the spacing around blocks and
+    /// paragraphs) into the larger of the two, similar to margin collapsing in
+    /// CSS. Like weak spacing, max-collapsing spacing is discarded at the
+    /// start or end of a flow.
+    ///
+    /// This is useful to give elements consistent spacing in show rules
+    /// without it piling up with the spacing of the following element.
+    ///
+    /// ```example
+    /// #set par(spacing: 8pt)
+    /// #show heading: set block(below: 0pt)
+    /// #show heading: it => {
+    ///   it
+    ///   v(12pt, collapse: "max")
+    /// }
+    ///
+    /// = Introduction
+    /// The gap above is 12pt, not 20pt.
+    /// ```
+    #[default(SpacingCollapse::Sum)]
+    pub collapse: SpacingCollapse,
+
     /// Whether the spacing collapses if not immediately preceded by a
     /// paragraph.
     #[internal]
@@ -125,6 +151,16 @@ cast! {
     v: Content => v.unpack::<Self>().map_err(|_| "expected `v` element")?,
 }
 
+/// How vertical spacing combines with adjacent spacing.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum SpacingCollapse {
+    /// The spacing adds up with adjacent spacing.
+    #[default]
+    Sum,
+    /// Adjacent collapsible spacings merge into the largest of them.
+    Max,
+}
+
 /// Kinds of spacing.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Spacing {
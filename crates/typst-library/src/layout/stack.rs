@@ -2,7 +2,9 @@ use std::fmt::{self, Debug, Formatter};
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{cast, elem, Content, NativeElement, Packed, Show, StyleChain};
+use crate::foundations::{
+    cast, elem, Content, NativeElement, Packed, Show, Smart, StyleChain,
+};
 use crate::layout::{BlockElem, Dir, Spacing};
 
 /// Arranges content and spacing horizontally or vertically.
@@ -27,6 +29,8 @@ pub struct StackElem {
     /// - `{rtl}`: Right to left.
     /// - `{ttb}`: Top to bottom.
     /// - `{btt}`: Bottom to top.
+    /// - `{auto}`: Horizontally, in the [text direction]($text.dir). In
+    ///   right-to-left text, the first item is thus on the right.
     ///
     /// You can use the `start` and `end` methods to obtain the initial and
     /// final points (respectively) of a direction, as `alignment`. You can also
@@ -36,8 +40,18 @@ pub struct StackElem {
     ///
     /// For example, `{ttb.start()}` is `top`, `{ttb.end()}` is `bottom`,
     /// `{ttb.axis()}` is `{"vertical"}` and `{ttb.inv()}` is equal to `btt`.
-    #[default(Dir::TTB)]
-    pub dir: Dir,
+    ///
+    /// ```example
+    /// #set text(lang: "ar")
+    /// #stack(
+    ///   dir: auto,
+    ///   spacing: 4pt,
+    ///   square(size: 20pt, fill: red),
+    ///   square(size: 20pt, fill: blue),
+    /// )
+    /// ```
+    #[default(Smart::Custom(Dir::TTB))]
+    pub dir: Smart<Dir>,
 
     /// Spacing to insert between items where no explicit spacing was provided.
     pub spacing: Option<Spacing>,
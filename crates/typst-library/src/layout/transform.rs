@@ -96,12 +96,31 @@ pub struct RotateElem {
     /// the original content. If set to `{true}`, the bounding box will take the
     /// rotation of the content into account and adjust the layout accordingly.
     ///
+    /// When a reflowing rotation appears within a paragraph, i.e. after some
+    /// text or other inline content, it is laid out inline and the line makes
+    /// room for the rotated bounding box. Elsewhere, it is laid out as a block.
+    ///
     /// ```example
     /// Hello #rotate(90deg, reflow: true)[World]!
     /// ```
     #[default(false)]
     pub reflow: bool,
 
+    /// An amount to shift the baseline of the rotated content by when it is
+    /// laid out inline with `{reflow: true}`.
+    ///
+    /// Relative values are resolved relative to the height of the rotated
+    /// bounding box. By default, the bounding box is centered on the text's
+    /// baseline. With `{0%}`, its bottom edge sits on the baseline instead.
+    ///
+    /// ```example
+    /// #set rotate(reflow: true)
+    /// A #rotate(90deg)[NEW!] and
+    /// a #rotate(90deg, baseline: 0%)[NEW!]
+    /// ```
+    #[default(Rel::from(Ratio::new(0.5)))]
+    pub baseline: Rel<Length>,
+
     /// The content to rotate.
     #[required]
     pub body: Content,
@@ -168,12 +187,22 @@ pub struct ScaleElem {
     /// other content. If set to `{true}`, it will compute the new size of
     /// the scaled content and adjust the layout accordingly.
     ///
+    /// Like a [reflowing rotation]($rotate.reflow), a reflowing scaling that
+    /// appears within a paragraph is laid out inline.
+    ///
     /// ```example
     /// Hello #scale(x: 20%, y: 40%, reflow: true)[World]!
     /// ```
     #[default(false)]
     pub reflow: bool,
 
+    /// An amount to shift the baseline of the scaled content by when it is
+    /// laid out inline with `{reflow: true}`.
+    ///
+    /// Works just like the [baseline of a rotation]($rotate.baseline).
+    #[default(Rel::from(Ratio::new(0.5)))]
+    pub baseline: Rel<Length>,
+
     /// The content to scale.
     #[required]
     pub body: Content,
@@ -254,12 +283,22 @@ pub struct SkewElem {
     /// the original content. If set to `{true}`, the bounding box will take the
     /// transformation of the content into account and adjust the layout accordingly.
     ///
+    /// Like a [reflowing rotation]($rotate.reflow), a reflowing skew that
+    /// appears within a paragraph is laid out inline.
+    ///
     /// ```example
     /// Hello #skew(ay: 30deg, reflow: true, "World")!
     /// ```
     #[default(false)]
     pub reflow: bool,
 
+    /// An amount to shift the baseline of the skewed content by when it is
+    /// laid out inline with `{reflow: true}`.
+    ///
+    /// Works just like the [baseline of a rotation]($rotate.baseline).
+    #[default(Rel::from(Ratio::new(0.5)))]
+    pub baseline: Rel<Length>,
+
     /// The content to skew.
     #[required]
     pub body: Content,
@@ -21,6 +21,7 @@ pub mod layout;
 pub mod loading;
 pub mod math;
 pub mod model;
+pub mod package;
 pub mod routines;
 pub mod symbols;
 pub mod text;
@@ -28,14 +29,18 @@ pub mod visualize;
 
 use std::ops::{Deref, Range};
 
+use typst_syntax::package::PackageSpec;
 use typst_syntax::{FileId, Source, Span};
 use typst_utils::{LazyHash, SmallBitSet};
 
-use crate::diag::FileResult;
-use crate::foundations::{Array, Bytes, Datetime, Dict, Module, Scope, Styles, Value};
-use crate::layout::{Alignment, Dir};
+use crate::diag::{FileError, FileResult, StrResult};
+use crate::foundations::{
+    Array, Bytes, Datetime, Dict, Module, NativeRuleMap, PluginLimits, Scope, Styles,
+    Value,
+};
+use crate::layout::{Alignment, Axes, Dir};
 use crate::text::{Font, FontBook};
-use crate::visualize::Color;
+use crate::visualize::{Color, RasterImage, SvgImage};
 
 /// The environment in which typesetting occurs.
 ///
@@ -82,6 +87,38 @@ pub trait World: Send + Sync {
     /// If this function returns `None`, Typst's `datetime` function will
     /// return an error.
     fn today(&self, offset: Option<i64>) -> Option<Datetime>;
+
+    /// Get the date at which the specified file was last modified.
+    ///
+    /// This is used to derive the document's date from its sources with
+    /// `{set document(date: "source")}`. If this function returns `None` for
+    /// the main file (the default), using that feature results in an error.
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        let _ = id;
+        None
+    }
+
+    /// List the files of a package.
+    ///
+    /// This is used to verify packages against a
+    /// [`PackageLock`](crate::package::PackageLock). By default, listing is
+    /// not supported, so verification fails.
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        let _ = spec;
+        Err(FileError::Other(Some("cannot list the files of a package".into())))
+    }
+
+    /// Render an SVG image into a raster image with the given pixel size.
+    ///
+    /// This is used for SVGs that are [rasterized]($image.rasterize) instead
+    /// of being embedded as vector graphics. Embedders that want to support
+    /// this can delegate to `typst_render::rasterize_svg`. By default,
+    /// rasterization is not supported, so such SVGs stay vector graphics and
+    /// a warning is emitted.
+    fn rasterize_svg(&self, svg: &SvgImage, size: Axes<u32>) -> StrResult<RasterImage> {
+        let _ = (svg, size);
+        Err("rasterizing SVG images is not supported".into())
+    }
 }
 
 macro_rules! world_impl {
@@ -114,6 +151,22 @@ macro_rules! world_impl {
             fn today(&self, offset: Option<i64>) -> Option<Datetime> {
                 self.deref().today(offset)
             }
+
+            fn modified(&self, id: FileId) -> Option<Datetime> {
+                self.deref().modified(id)
+            }
+
+            fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+                self.deref().package_files(spec)
+            }
+
+            fn rasterize_svg(
+                &self,
+                svg: &SvgImage,
+                size: Axes<u32>,
+            ) -> StrResult<RasterImage> {
+                self.deref().rasterize_svg(svg, size)
+            }
         }
     };
 }
@@ -150,6 +203,10 @@ pub struct Library {
     pub std: Value,
     /// In-development features that were enabled.
     pub features: Features,
+    /// Show rules that were implemented natively by the embedder.
+    pub rules: NativeRuleMap,
+    /// Limits on the resources that plugins may use.
+    pub plugin_limits: PluginLimits,
 }
 
 impl Library {
@@ -173,6 +230,8 @@ impl Default for Library {
 pub struct LibraryBuilder {
     inputs: Option<Dict>,
     features: Features,
+    rules: NativeRuleMap,
+    plugin_limits: PluginLimits,
 }
 
 impl LibraryBuilder {
@@ -190,6 +249,29 @@ impl LibraryBuilder {
         self
     }
 
+    /// Register show rules that are implemented natively.
+    ///
+    /// A native rule replaces the built-in show rule of its element for its
+    /// target, while user-defined show rules still take precedence. If this
+    /// is called multiple times or registers multiple rules for the same
+    /// element and target, the last registration wins.
+    pub fn with_native_rules(mut self, f: impl FnOnce(&mut NativeRuleMap)) -> Self {
+        f(&mut self.rules);
+        self
+    }
+
+    /// Configure limits on the memory and execution time of WebAssembly
+    /// plugins.
+    ///
+    /// By default, plugins are not limited. Embedders that compile untrusted
+    /// documents (e.g. a shared compile server) should set limits, so that a
+    /// misbehaving plugin results in an error instead of exhausting the
+    /// server's resources.
+    pub fn with_plugin_limits(mut self, limits: PluginLimits) -> Self {
+        self.plugin_limits = limits;
+        self
+    }
+
     /// Consumes the builder and returns a `Library`.
     pub fn build(self) -> Library {
         let math = math::module();
@@ -202,6 +284,8 @@ impl LibraryBuilder {
             styles: Styles::new(),
             std,
             features: self.features,
+            rules: self.rules,
+            plugin_limits: self.plugin_limits,
         }
     }
 }
@@ -217,6 +301,14 @@ impl Features {
     pub fn is_enabled(&self, feature: Feature) -> bool {
         self.0.contains(feature as usize)
     }
+
+    /// The names of the enabled features.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        Feature::ALL
+            .into_iter()
+            .filter(|&feature| self.is_enabled(feature))
+            .map(Feature::name)
+    }
 }
 
 impl FromIterator<Feature> for Features {
@@ -234,6 +326,31 @@ impl FromIterator<Feature> for Features {
 #[non_exhaustive]
 pub enum Feature {
     Html,
+    /// Warn about fonts that are shadowed by another font with the same
+    /// family and variant.
+    FontDebug,
+    /// Mark frames with the semantic [role](crate::layout::FrameRole) of
+    /// their contents.
+    A11yExtras,
+    /// Emit [elements](crate::introspection::metrics) for laid-out lines and
+    /// page breaks that can be queried after layout.
+    LayoutMetrics,
+}
+
+impl Feature {
+    /// All features.
+    const ALL: [Self; 4] =
+        [Self::Html, Self::FontDebug, Self::A11yExtras, Self::LayoutMetrics];
+
+    /// The name of the feature, as it is spelled on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::FontDebug => "font-debug",
+            Self::A11yExtras => "a11y-extras",
+            Self::LayoutMetrics => "layout-metrics",
+        }
+    }
 }
 
 /// Construct the module with global definitions.
@@ -253,6 +370,9 @@ fn global(math: Module, inputs: Dict, features: &Features) -> Module {
     if features.is_enabled(Feature::Html) {
         global.define_module(self::html::module());
     }
+    if features.is_enabled(Feature::LayoutMetrics) {
+        global.define_module(self::introspection::metrics::module());
+    }
     prelude(&mut global);
     Module::new("global", global)
 }
@@ -297,3 +417,30 @@ fn prelude(global: &mut Scope) {
     global.define("horizon", Alignment::HORIZON);
     global.define("bottom", Alignment::BOTTOM);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_all_features() {
+        // Adding a feature breaks this match, which is a reminder to also add
+        // it to `Feature::ALL`.
+        let index = |feature| match feature {
+            Feature::Html => 0,
+            Feature::FontDebug => 1,
+            Feature::A11yExtras => 2,
+            Feature::LayoutMetrics => 3,
+        };
+        assert_eq!(Feature::ALL.len(), 4, "the number of arms above");
+        for (i, feature) in Feature::ALL.into_iter().enumerate() {
+            assert_eq!(index(feature), i);
+            assert_eq!(feature as usize, i);
+        }
+
+        let names: HashSet<_> = Feature::ALL.into_iter().map(Feature::name).collect();
+        assert_eq!(names.len(), Feature::ALL.len());
+    }
+}
@@ -1,10 +1,13 @@
+use comemo::Tracked;
 use ecow::{eco_format, EcoString};
 use typst_syntax::Spanned;
 
-use crate::diag::{bail, At, SourceResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{cast, func, scope, Array, Dict, IntoValue, Type, Value};
-use crate::loading::Readable;
+use crate::foundations::{
+    cast, func, scope, Array, Context, Dict, Func, IntoValue, Repr, Type, Value,
+};
+use crate::loading::{Columns, Readable, RowRange};
 use crate::World;
 
 /// Reads structured data from a CSV file.
@@ -24,10 +27,41 @@ use crate::World;
 ///   ..results.flatten(),
 /// )
 /// ```
+///
+/// # Loading large files
+/// If you only need a part of a large CSV file, select it with the `rows`,
+/// `columns`, and `filter` parameters instead of loading the whole file and
+/// slicing the result. These are applied while the file is parsed, so that
+/// only the selected data is ever turned into Typst values:
+///
+/// - Rows before the selected range are parsed, but not converted, and
+///   parsing stops after the last selected row. Selecting rows near the start
+///   of a file is thus cheaper than selecting rows near its end.
+/// - Columns that are not selected are never converted.
+/// - The filter is called once for each row in the selected range and only
+///   the rows that pass it are kept.
+///
+/// The memory needed for the result is proportional to the retained data,
+/// not to the size of the file. The file's raw bytes must still be read in
+/// full, though.
+///
+/// ```example
+/// #let data = csv.decode(
+///   "name,value\nA,1\nB,12\nC,7\nD,30\n",
+///   row-type: dictionary,
+///   rows: (1, 4),
+///   columns: ("value",),
+///   filter: row => int(row.value) > 5,
+/// )
+///
+/// #data
+/// ```
 #[func(scope, title = "CSV")]
 pub fn csv(
     /// The engine.
     engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
     /// Path to a CSV file.
     ///
     /// For more details, see the [Paths section]($syntax/#paths).
@@ -47,11 +81,37 @@ pub fn csv(
     #[named]
     #[default(RowType::Array)]
     row_type: RowType,
+    /// The rows to load, as a zero-based start index and an exclusive end
+    /// index like `{(100, 200)}`. A header row does not count. If this is
+    /// `{none}` (default), all rows are loaded.
+    #[named]
+    #[default]
+    rows: Option<RowRange>,
+    /// The columns to load, in the order in which they should appear in each
+    /// row. Columns can be selected by their zero-based index or, with
+    /// `row-type` set to `dictionary`, by their header. If this is `{none}`
+    /// (default), all columns are loaded.
+    #[named]
+    #[default]
+    columns: Option<Columns>,
+    /// A function that decides for each row whether to keep it. It receives
+    /// the row with the selected columns and must return a boolean.
+    #[named]
+    filter: Option<Spanned<Func>>,
 ) -> SourceResult<Array> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
     let data = engine.world.file(id).at(span)?;
-    self::csv::decode(Spanned::new(Readable::Bytes(data), span), delimiter, row_type)
+    self::csv::decode(
+        engine,
+        context,
+        Spanned::new(Readable::Bytes(data), span),
+        delimiter,
+        row_type,
+        rows,
+        columns,
+        filter,
+    )
 }
 
 #[scope]
@@ -59,6 +119,10 @@ impl csv {
     /// Reads structured data from a CSV string/bytes.
     #[func(title = "Decode CSV")]
     pub fn decode(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
         /// CSV data.
         data: Spanned<Readable>,
         /// The delimiter that separates columns in the CSV file.
@@ -76,6 +140,24 @@ impl csv {
         #[named]
         #[default(RowType::Array)]
         row_type: RowType,
+        /// The rows to load, as a zero-based start index and an exclusive end
+        /// index like `{(100, 200)}`. A header row does not count. If this is
+        /// `{none}` (default), all rows are loaded.
+        #[named]
+        #[default]
+        rows: Option<RowRange>,
+        /// The columns to load, in the order in which they should appear in
+        /// each row. Columns can be selected by their zero-based index or,
+        /// with `row-type` set to `dictionary`, by their header. If this is
+        /// `{none}` (default), all columns are loaded.
+        #[named]
+        #[default]
+        columns: Option<Columns>,
+        /// A function that decides for each row whether to keep it. It
+        /// receives the row with the selected columns and must return a
+        /// boolean.
+        #[named]
+        filter: Option<Spanned<Func>>,
     ) -> SourceResult<Array> {
         let Spanned { v: data, span } = data;
         let has_headers = row_type == RowType::Dict;
@@ -101,23 +183,66 @@ impl csv {
             );
         }
 
+        let columns = match columns {
+            None => None,
+            Some(Columns::Indices(indices)) => Some(indices),
+            Some(Columns::Names(names)) => {
+                let Some(headers) = &headers else {
+                    bail!(
+                        span, "cannot select columns by name without a header row";
+                        hint: "use `row-type: dictionary` to read the header row"
+                    );
+                };
+                Some(
+                    names
+                        .iter()
+                        .map(|name| {
+                            headers
+                                .iter()
+                                .position(|header| header == name.as_str())
+                                .ok_or_else(|| {
+                                    eco_format!("column {} does not exist", name.repr())
+                                })
+                        })
+                        .collect::<StrResult<_>>()
+                        .at(span)?,
+                )
+            }
+        };
+
+        // The record is reused for all rows, so that rows outside of the
+        // selected range do not allocate.
+        let mut record = ::csv::StringRecord::new();
         let mut array = Array::new();
-        for (line, result) in reader.records().enumerate() {
+        for index in 0.. {
+            if rows.as_ref().is_some_and(|rows| rows.is_done(index)) {
+                break;
+            }
+
             // Original solution was to use line from error, but that is
             // incorrect with `has_headers` set to `false`. See issue:
             // https://github.com/BurntSushi/rust-csv/issues/184
-            let line = line + line_offset;
-            let row = result.map_err(|err| format_csv_error(err, line)).at(span)?;
-            let item = if let Some(headers) = &headers {
-                let mut dict = Dict::new();
-                for (field, value) in headers.iter().zip(&row) {
-                    dict.insert(field.into(), value.into_value());
+            let line = index + line_offset;
+            let more = reader
+                .read_record(&mut record)
+                .map_err(|err| format_csv_error(err, line))
+                .at(span)?;
+            if !more {
+                break;
+            }
+
+            if rows.as_ref().is_some_and(|rows| !rows.contains(index)) {
+                continue;
+            }
+
+            let item = convert_row(&record, headers.as_ref(), columns.as_deref(), line)
+                .at(span)?;
+            if let Some(filter) = &filter {
+                if !apply_filter(engine, context, filter, item.clone(), index, line)? {
+                    continue;
                 }
-                dict.into_value()
-            } else {
-                let sub = row.into_iter().map(|field| field.into_value()).collect();
-                Value::Array(sub)
-            };
+            }
+
             array.push(item);
         }
 
@@ -176,6 +301,68 @@ cast! {
     },
 }
 
+/// Converts a CSV record into a row value with the selected columns.
+fn convert_row(
+    record: &::csv::StringRecord,
+    headers: Option<&::csv::StringRecord>,
+    columns: Option<&[usize]>,
+    line: usize,
+) -> StrResult<Value> {
+    let fields = match columns {
+        Some(columns) => columns
+            .iter()
+            .map(|&i| {
+                record.get(i).map(|field| (i, field)).ok_or_else(|| {
+                    eco_format!(
+                        "column index {i} is out of bounds \
+                         ({} fields in line {line})",
+                        record.len()
+                    )
+                })
+            })
+            .collect::<StrResult<Vec<_>>>()?,
+        None => record.iter().enumerate().collect(),
+    };
+
+    Ok(if let Some(headers) = headers {
+        let mut dict = Dict::new();
+        for (i, value) in fields {
+            if let Some(field) = headers.get(i) {
+                dict.insert(field.into(), value.into_value());
+            }
+        }
+        dict.into_value()
+    } else {
+        let sub = fields.into_iter().map(|(_, field)| field.into_value()).collect();
+        Value::Array(sub)
+    })
+}
+
+/// Calls the filter on a row and returns whether the row should be kept.
+///
+/// Errors are annotated with the row, as the same filter runs on many rows.
+fn apply_filter(
+    engine: &mut Engine,
+    context: Tracked<Context>,
+    filter: &Spanned<Func>,
+    row: Value,
+    index: usize,
+    line: usize,
+) -> SourceResult<bool> {
+    filter
+        .v
+        .call(engine, context, [row])
+        .and_then(|keep| keep.cast::<bool>().at(filter.span))
+        .map_err(|mut errors| {
+            for error in errors.make_mut().iter_mut() {
+                error.hint(eco_format!(
+                    "error occurred while filtering row {index} (line {line})"
+                ));
+            }
+            errors
+        })
+}
+
 /// Format the user-facing CSV error message.
 fn format_csv_error(err: ::csv::Error, line: usize) -> EcoString {
     match err.kind() {
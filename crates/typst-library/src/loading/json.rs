@@ -1,10 +1,14 @@
+use std::fmt::{self, Formatter};
+
 use ecow::{eco_format, EcoString};
+use serde::de::{IgnoredAny, SeqAccess, Visitor};
+use serde::Deserializer;
 use typst_syntax::Spanned;
 
 use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::foundations::{func, scope, Array, IntoValue, Str, Value};
+use crate::loading::{Columns, Readable, RowRange};
 use crate::World;
 
 /// Reads structured data from a JSON file.
@@ -49,6 +53,16 @@ use crate::World;
 /// #forecast(json("monday.json"))
 /// #forecast(json("tuesday.json"))
 /// ```
+///
+/// # Loading large files
+/// If a JSON file contains a large array at the top level and you only need
+/// a part of it, select that part with the `rows` and `columns` parameters.
+/// These are applied while the file is parsed: Elements outside of the
+/// selected rows are still checked for valid syntax, but never turned into
+/// Typst values, and only the selected columns of each row are kept. The
+/// memory needed for the result is thus proportional to the retained data,
+/// not to the size of the file. The file's raw bytes must still be read in
+/// full, though.
 #[func(scope, title = "JSON")]
 pub fn json(
     /// The engine.
@@ -57,11 +71,26 @@ pub fn json(
     ///
     /// For more details, see the [Paths section]($syntax/#paths).
     path: Spanned<EcoString>,
+    /// The elements of the top-level array to load, as a zero-based start
+    /// index and an exclusive end index like `{(100, 200)}`. If this or
+    /// `columns` is set, the file must contain an array. If this is `{none}` (default),
+    /// all elements are loaded.
+    #[named]
+    #[default]
+    rows: Option<RowRange>,
+    /// The columns to keep from each element of the top-level array, in the
+    /// order in which they are given. Columns are selected by key from
+    /// objects and by zero-based index from arrays. Columns that an element
+    /// lacks are left out. If this is `{none}` (default), elements are loaded
+    /// in full.
+    #[named]
+    #[default]
+    columns: Option<Columns>,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
     let data = engine.world.file(id).at(span)?;
-    json::decode(Spanned::new(Readable::Bytes(data), span))
+    json::decode(Spanned::new(Readable::Bytes(data), span), rows, columns)
 }
 
 #[scope]
@@ -71,10 +100,43 @@ impl json {
     pub fn decode(
         /// JSON data.
         data: Spanned<Readable>,
+        /// The elements of the top-level array to load, as a zero-based start
+        /// index and an exclusive end index like `{(100, 200)}`. If this or
+        /// `columns` is set, the data must contain an array. If this is
+        /// `{none}` (default), all elements are loaded.
+        #[named]
+        #[default]
+        rows: Option<RowRange>,
+        /// The columns to keep from each element of the top-level array, in
+        /// the order in which they are given. Columns are selected by key from
+        /// objects and by zero-based index from arrays. Columns that an
+        /// element lacks are left out. If this is `{none}` (default), elements
+        /// are loaded in full.
+        #[named]
+        #[default]
+        columns: Option<Columns>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_json::from_slice(data.as_slice())
+        if rows.is_none() && columns.is_none() {
+            return serde_json::from_slice(data.as_slice())
+                .map_err(|err| eco_format!("failed to parse JSON ({err})"))
+                .at(span);
+        }
+
+        let mut deserializer = serde_json::Deserializer::from_slice(data.as_slice());
+        let (start, array) = deserializer
+            .deserialize_seq(RowsVisitor(rows))
+            .and_then(|array| deserializer.end().map(|_| array))
             .map_err(|err| eco_format!("failed to parse JSON ({err})"))
+            .at(span)?;
+
+        let Some(columns) = columns else { return Ok(array.into_value()) };
+        array
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| columns.select(row, start + i))
+            .collect::<Result<Array, _>>()
+            .map(IntoValue::into_value)
             .at(span)
     }
 
@@ -99,3 +161,37 @@ impl json {
         .at(span)
     }
 }
+
+/// Deserializes the selected elements of a JSON array, skipping over the
+/// others without turning them into values.
+///
+/// Produces the index of the first selected element and the selected
+/// elements.
+struct RowsVisitor(Option<RowRange>);
+
+impl<'de> Visitor<'de> for RowsVisitor {
+    type Value = (usize, Array);
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("an array")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let start = self.0.as_ref().map_or(0, |rows| rows.0.start);
+        let mut array = Array::new();
+        for index in 0.. {
+            if self.0.as_ref().map_or(true, |rows| rows.contains(index)) {
+                match seq.next_element::<Value>()? {
+                    Some(value) => array.push(value),
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+        }
+        Ok((start, array))
+    }
+}
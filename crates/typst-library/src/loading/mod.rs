@@ -10,6 +10,8 @@ mod json_;
 mod read_;
 #[path = "toml.rs"]
 mod toml_;
+#[path = "xlsx.rs"]
+mod xlsx_;
 #[path = "xml.rs"]
 mod xml_;
 #[path = "yaml.rs"]
@@ -20,10 +22,17 @@ pub use self::csv_::*;
 pub use self::json_::*;
 pub use self::read_::*;
 pub use self::toml_::*;
+pub use self::xlsx_::*;
 pub use self::xml_::*;
 pub use self::yaml_::*;
 
-use crate::foundations::{cast, category, Bytes, Category, Scope, Str};
+use std::ops::Range;
+
+use crate::diag::{bail, HintedStrResult};
+use crate::foundations::{
+    array, cast, category, Array, Bytes, Category, Dict, IntoValue, Scope, Str, Type,
+    Value,
+};
 
 /// Data loading from external files.
 ///
@@ -42,6 +51,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<yaml>();
     global.define_func::<cbor>();
     global.define_func::<xml>();
+    global.define_func::<xlsx>();
 }
 
 /// A value that can be read from a file.
@@ -87,3 +97,97 @@ impl From<Readable> for Bytes {
         }
     }
 }
+
+/// A contiguous range of rows to load, given as an array of a zero-based
+/// start index and an exclusive end index like `{(100, 200)}`.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct RowRange(pub Range<usize>);
+
+impl RowRange {
+    /// Whether the row with the given index is in the range.
+    pub fn contains(&self, index: usize) -> bool {
+        self.0.contains(&index)
+    }
+
+    /// Whether no rows after the one with the given index are in the range.
+    pub fn is_done(&self, index: usize) -> bool {
+        index >= self.0.end
+    }
+}
+
+cast! {
+    RowRange,
+    self => array![self.0.start, self.0.end].into_value(),
+    v: Array => {
+        let mut iter = v.into_iter();
+        let (Some(start), Some(end), None) = (iter.next(), iter.next(), iter.next())
+        else {
+            bail!(
+                "rows must be given as a start and an end index";
+                hint: "use `(start, end)` to select the rows from `start` up to, \
+                       but not including, `end`"
+            );
+        };
+        let (start, end) = (start.cast::<usize>()?, end.cast::<usize>()?);
+        if end < start {
+            bail!("the end of the rows must not be before their start");
+        }
+        Self(start..end)
+    },
+}
+
+/// A selection of columns to load.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Columns {
+    /// Columns selected by their header or key.
+    Names(Vec<Str>),
+    /// Columns selected by their zero-based index.
+    Indices(Vec<usize>),
+}
+
+impl Columns {
+    /// Selects the columns from a row of structured data, in the order in
+    /// which they were given. Columns that the row lacks are left out.
+    ///
+    /// The index of the row is only used for error messages.
+    pub fn select(&self, row: Value, index: usize) -> HintedStrResult<Value> {
+        Ok(match (self, row) {
+            (Self::Names(names), Value::Dict(dict)) => names
+                .iter()
+                .filter_map(|name| Some((name.clone(), dict.get(name).ok()?.clone())))
+                .collect::<Dict>()
+                .into_value(),
+            (Self::Indices(indices), Value::Array(array)) => indices
+                .iter()
+                .filter_map(|&i| array.as_slice().get(i).cloned())
+                .collect::<Array>()
+                .into_value(),
+            (Self::Names(_), row) => bail!(
+                "expected dictionary for row {index}, found {}", row.ty();
+                hint: "columns can only be selected by name from dictionaries"
+            ),
+            (Self::Indices(_), row) => bail!(
+                "expected array for row {index}, found {}", row.ty();
+                hint: "columns can only be selected by index from arrays"
+            ),
+        })
+    }
+}
+
+cast! {
+    Columns,
+    self => match self {
+        Self::Names(names) => names.into_value(),
+        Self::Indices(indices) => indices.into_value(),
+    },
+    v: Array => {
+        let is_str = |v: &Value| v.ty() == Type::of::<Str>();
+        if v.iter().all(is_str) {
+            Self::Names(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?)
+        } else if v.iter().any(is_str) {
+            bail!("columns must be selected either all by name or all by index");
+        } else {
+            Self::Indices(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?)
+        }
+    },
+}
@@ -0,0 +1,1221 @@
+use std::borrow::Cow;
+use std::io::{BufReader, Cursor, Read};
+use std::ops::ControlFlow;
+
+use ecow::{eco_format, EcoString};
+use quick_xml::events::{BytesStart, Event};
+use typst_syntax::Spanned;
+
+use crate::diag::{
+    bail, error, warning, At, HintedStrResult, HintedString, SourceResult,
+};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, func, scope, Array, Datetime, Dict, IntoValue, Repr, Smart, Str, Value,
+};
+use crate::loading::Readable;
+use crate::World;
+
+/// Reads structured data from a spreadsheet file.
+///
+/// Both Excel workbooks (`.xlsx`) and OpenDocument spreadsheets (`.ods`) are
+/// supported. One sheet of the workbook is read into an array of rows. By
+/// default, the first row is treated as a header and each following row is
+/// represented as a dictionary mapping from the header's cells to the row's
+/// cells. Empty rows are skipped.
+///
+/// Cells are converted as follows:
+/// - Numbers become [integers]($int) if they are whole and
+///   [floats]($float) otherwise.
+/// - Numbers formatted as dates or times as well as date and time cells
+///   become [datetimes]($datetime).
+/// - Text becomes a [string]($str) and booleans a [boolean]($bool).
+/// - Empty cells become `{none}`.
+/// - Formulas are not evaluated. Instead, the value that the spreadsheet
+///   application computed when the file was last saved is used.
+///
+/// If a cell contains an error like `#DIV/0!` or cannot be converted, loading
+/// fails with an error that names the sheet and the cell.
+///
+/// # Example
+/// ```typ
+/// #let grades = xlsx("grades.xlsx", sheet: "Term 1")
+///
+/// #table(
+///   columns: 2,
+///   [*Student*], [*Grade*],
+///   ..grades.map(row => (row.Name, str(row.Grade))).flatten(),
+/// )
+/// ```
+#[func(scope, title = "XLSX")]
+pub fn xlsx(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to a spreadsheet file.
+    ///
+    /// For more details, see the [Paths section]($syntax/#paths).
+    path: Spanned<EcoString>,
+    /// Which sheet to read.
+    ///
+    /// Can be the name of a sheet or its zero-based index in the workbook. If
+    /// `{auto}`, the first sheet is read.
+    #[named]
+    #[default]
+    sheet: Smart<Sheet>,
+    /// Whether the first row is a header.
+    ///
+    /// - If `{true}`, each following row is represented as a dictionary
+    ///   mapping from the header's cells to the row's cells. Cells without a
+    ///   header are keyed by their column's letters. Loading fails if two
+    ///   columns have the same key.
+    /// - If `{false}`, each row is represented as a plain array of cells.
+    #[named]
+    #[default(true)]
+    header: bool,
+    /// Whether to warn if the sheet contains formulas, as their values may be
+    /// out of date.
+    #[named]
+    #[default(false)]
+    warn_formulas: bool,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let id = span.resolve_path(&path).at(span)?;
+    let data = engine.world.file(id).at(span)?;
+    xlsx::decode(
+        engine,
+        Spanned::new(Readable::Bytes(data), span),
+        sheet,
+        header,
+        warn_formulas,
+    )
+}
+
+#[scope]
+impl xlsx {
+    /// Reads structured data from spreadsheet bytes.
+    #[func(title = "Decode XLSX")]
+    pub fn decode(
+        /// The engine.
+        engine: &mut Engine,
+        /// Spreadsheet data.
+        data: Spanned<Readable>,
+        /// Which sheet to read.
+        ///
+        /// Can be the name of a sheet or its zero-based index in the workbook.
+        /// If `{auto}`, the first sheet is read.
+        #[named]
+        #[default]
+        sheet: Smart<Sheet>,
+        /// Whether the first row is a header.
+        ///
+        /// - If `{true}`, each following row is represented as a dictionary
+        ///   mapping from the header's cells to the row's cells. Cells without
+        ///   a header are keyed by their column's letters. Loading fails if two
+        ///   columns have the same key.
+        /// - If `{false}`, each row is represented as a plain array of cells.
+        #[named]
+        #[default(true)]
+        header: bool,
+        /// Whether to warn if the sheet contains formulas, as their values may
+        /// be out of date.
+        #[named]
+        #[default(false)]
+        warn_formulas: bool,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let table = Workbook::open(data.as_slice())
+            .and_then(|mut workbook| workbook.read(sheet))
+            .at(span)?;
+
+        if warn_formulas {
+            if let Some(cell) = &table.formula {
+                engine.sink.warn(warning!(
+                    span,
+                    "sheet {} contains formulas",
+                    table.name.repr();
+                    hint: "the values computed when the file was last saved are used";
+                    hint: "the first formula is in cell {cell}",
+                ));
+            }
+        }
+
+        table.into_array(header).at(span)
+    }
+
+    /// Lists the names of the sheets in a spreadsheet file.
+    ///
+    /// ```typ
+    /// #for name in xlsx.sheets("grades.xlsx") [
+    ///   - #name
+    /// ]
+    /// ```
+    #[func(title = "XLSX Sheets")]
+    pub fn sheets(
+        /// The engine.
+        engine: &mut Engine,
+        /// Path to a spreadsheet file.
+        ///
+        /// For more details, see the [Paths section]($syntax/#paths).
+        path: Spanned<EcoString>,
+    ) -> SourceResult<Array> {
+        let Spanned { v: path, span } = path;
+        let id = span.resolve_path(&path).at(span)?;
+        let data = engine.world.file(id).at(span)?;
+        let names = Workbook::open(&data)
+            .and_then(|mut workbook| workbook.names())
+            .at(span)?;
+        Ok(names.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+/// Identifies a sheet in a workbook.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Sheet {
+    /// The sheet with the given name.
+    Name(EcoString),
+    /// The sheet with the given zero-based index.
+    Index(usize),
+}
+
+cast! {
+    Sheet,
+    self => match self {
+        Self::Name(name) => name.into_value(),
+        Self::Index(index) => index.into_value(),
+    },
+    v: EcoString => Self::Name(v),
+    v: usize => Self::Index(v),
+}
+
+/// The ZIP archive that spreadsheets are stored in.
+type Archive<'a> = zip::ZipArchive<Cursor<&'a [u8]>>;
+
+/// The maximum number of columns of a sheet, as in Excel.
+const MAX_COLS: usize = 16384;
+
+/// The maximum number of rows of a sheet, as in Excel.
+const MAX_ROWS: usize = 1_048_576;
+
+/// The maximum number of cells that are read from a sheet, including the
+/// empty cells between non-empty ones.
+const MAX_CELLS: usize = 1 << 24;
+
+/// The maximum size of a decompressed part of a spreadsheet in bytes.
+const MAX_PART_SIZE: u64 = 256 * 1024 * 1024;
+
+/// An opened workbook.
+enum Workbook<'a> {
+    Xlsx(Xlsx<'a>),
+    Ods(Ods<'a>),
+}
+
+impl<'a> Workbook<'a> {
+    /// Open a workbook in one of the supported formats.
+    fn open(data: &'a [u8]) -> HintedStrResult<Self> {
+        let archive = zip::ZipArchive::new(Cursor::new(data))
+            .map_err(|_| "file is not a valid XLSX or ODS spreadsheet")?;
+        if archive.index_for_name("xl/workbook.xml").is_some() {
+            Ok(Self::Xlsx(Xlsx::open(archive)?))
+        } else if archive.index_for_name("content.xml").is_some() {
+            Ok(Self::Ods(Ods { archive }))
+        } else {
+            bail!("file is not a valid XLSX or ODS spreadsheet");
+        }
+    }
+
+    /// The names of all sheets in order.
+    fn names(&mut self) -> HintedStrResult<Vec<EcoString>> {
+        match self {
+            Self::Xlsx(xlsx) => Ok(xlsx.names()),
+            Self::Ods(ods) => Ok(ods.read(None)?.0),
+        }
+    }
+
+    /// Read the cells of a sheet.
+    fn read(&mut self, sheet: Smart<Sheet>) -> HintedStrResult<Table> {
+        match self {
+            Self::Xlsx(xlsx) => {
+                let names = xlsx.names();
+                let index = resolve(&sheet, &names)?;
+                let mut table = Table::new(names[index].clone());
+                xlsx.read(index, &mut table)?;
+                Ok(table)
+            }
+            Self::Ods(ods) => match ods.read(Some(&sheet))? {
+                (_, Some(table)) => Ok(table),
+                // No sheet matched, so this fails with the appropriate error.
+                (names, None) => {
+                    resolve(&sheet, &names).map(|index| Table::new(names[index].clone()))
+                }
+            },
+        }
+    }
+}
+
+/// Determine the index of the requested sheet among the given names.
+fn resolve(sheet: &Smart<Sheet>, names: &[EcoString]) -> HintedStrResult<usize> {
+    Ok(match sheet {
+        Smart::Auto if names.is_empty() => bail!("spreadsheet has no sheets"),
+        Smart::Auto => 0,
+        Smart::Custom(Sheet::Index(index)) => {
+            if *index >= names.len() {
+                bail!("sheet index out of bounds (index: {index}, len: {})", names.len());
+            }
+            *index
+        }
+        Smart::Custom(Sheet::Name(name)) => match names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => bail!(
+                "sheet {} does not exist", name.repr();
+                hint: "available sheets are {}",
+                names.iter().map(|name| name.repr()).collect::<Vec<_>>().join(", ")
+            ),
+        },
+    })
+}
+
+/// The cells of one sheet.
+struct Table {
+    /// The sheet's name.
+    name: EcoString,
+    /// The non-empty rows, without trailing empty cells.
+    rows: Vec<Vec<Value>>,
+    /// The number of cells in the rows.
+    cells: usize,
+    /// The first cell with a formula, if any.
+    formula: Option<EcoString>,
+}
+
+impl Table {
+    /// Create an empty table for the sheet with the given name.
+    fn new(name: EcoString) -> Self {
+        Self { name, rows: vec![], cells: 0, formula: None }
+    }
+
+    /// Add a row, skipping it if it is empty.
+    fn push_row(&mut self, mut row: Vec<Value>) -> HintedStrResult<()> {
+        while row.last().is_some_and(|cell| matches!(cell, Value::None)) {
+            row.pop();
+        }
+        if row.is_empty() {
+            return Ok(());
+        }
+
+        self.cells += row.len();
+        if self.cells > MAX_CELLS {
+            bail!(
+                "sheet {} has too many cells", self.name.repr();
+                hint: "at most {MAX_CELLS} cells can be read from a sheet"
+            );
+        }
+
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// The error for a cell beyond the maximum size of a sheet.
+    fn out_of_bounds(&self) -> HintedString {
+        error!(
+            "sheet {} exceeds the maximum size", self.name.repr();
+            hint: "sheets can have at most {MAX_COLS} columns and {MAX_ROWS} rows"
+        )
+    }
+
+    /// Convert the rows into an array of dictionaries or arrays.
+    ///
+    /// Fails if two columns have the same key.
+    fn into_array(self, header: bool) -> HintedStrResult<Array> {
+        let name = self.name;
+        let mut rows = self.rows.into_iter();
+        if !header {
+            return Ok(rows
+                .map(|row| row.into_iter().collect::<Array>().into_value())
+                .collect());
+        }
+
+        let header_row = rows.next().unwrap_or_default();
+        let mut keys: Vec<Str> = Vec::with_capacity(header_row.len());
+        for (col, cell) in header_row.into_iter().enumerate() {
+            let key = match cell {
+                Value::Str(key) => key,
+                Value::None => column_name(col).into(),
+                cell => cell.repr().into(),
+            };
+            check_key(&name, &keys, &key, col)?;
+            keys.push(key);
+        }
+
+        rows.map(|row| {
+            let mut dict = Dict::new();
+            for (col, key) in keys.iter().enumerate() {
+                dict.insert(key.clone(), row.get(col).cloned().unwrap_or(Value::None));
+            }
+            for (col, cell) in row.into_iter().enumerate().skip(keys.len()) {
+                let key = Str::from(column_name(col));
+                check_key(&name, &keys, &key, col)?;
+                dict.insert(key, cell);
+            }
+            Ok(dict.into_value())
+        })
+        .collect()
+    }
+}
+
+/// Ensure that the key of a column in a sheet differs from the keys of the
+/// columns before it.
+fn check_key(
+    sheet: &EcoString,
+    keys: &[Str],
+    key: &Str,
+    col: usize,
+) -> HintedStrResult<()> {
+    if let Some(first) = keys.iter().position(|k| k == key) {
+        bail!(
+            "sheet {} has multiple columns with the header {}",
+            sheet.repr(), key.repr();
+            hint: "columns {} and {} have the same header",
+            column_name(first), column_name(col);
+            hint: "rename one of the headers or use `header: false`",
+        );
+    }
+    Ok(())
+}
+
+/// An Office Open XML workbook.
+struct Xlsx<'a> {
+    archive: Archive<'a>,
+    /// The names of the sheets and the paths of their worksheet parts.
+    sheets: Vec<(EcoString, String)>,
+    /// Whether dates are counted from 1904 instead of 1900.
+    date1904: bool,
+}
+
+impl<'a> Xlsx<'a> {
+    /// Read the list of sheets.
+    fn open(mut archive: Archive<'a>) -> HintedStrResult<Self> {
+        let mut listed = vec![];
+        let mut date1904 = false;
+        read_xml(&mut archive, "xl/workbook.xml", |event| {
+            match event {
+                Xml::Start(tag) if is(tag, "sheet") => {
+                    if let (Some(name), Some(id)) = (attr(tag, "name"), attr(tag, "id")) {
+                        listed.push((EcoString::from(name), id.into_owned()));
+                    }
+                }
+                Xml::Start(tag) if is(tag, "workbookPr") => {
+                    date1904 =
+                        attr(tag, "date1904").is_some_and(|v| v == "1" || v == "true");
+                }
+                _ => {}
+            }
+            Ok(())
+        })?;
+
+        let mut targets = vec![];
+        let found = read_xml(&mut archive, "xl/_rels/workbook.xml.rels", |event| {
+            if let Xml::Start(tag) = event {
+                if is(tag, "Relationship") {
+                    if let (Some(id), Some(target)) =
+                        (attr(tag, "Id"), attr(tag, "Target"))
+                    {
+                        targets.push((id.into_owned(), target.into_owned()));
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        if !found {
+            bail!("spreadsheet is missing its workbook relationships");
+        }
+
+        let sheets = listed
+            .into_iter()
+            .filter_map(|(name, id)| {
+                let (_, target) = targets.iter().find(|(rel, _)| *rel == id)?;
+                let path = match target.strip_prefix('/') {
+                    Some(absolute) => absolute.into(),
+                    None => format!("xl/{target}"),
+                };
+                Some((name, path))
+            })
+            .collect();
+
+        Ok(Self { archive, sheets, date1904 })
+    }
+
+    /// The names of all sheets in order.
+    fn names(&self) -> Vec<EcoString> {
+        self.sheets.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Read the cells of the sheet with the given index.
+    ///
+    /// Only the requested worksheet is decompressed, and it is parsed while
+    /// it is decompressed.
+    fn read(&mut self, index: usize, table: &mut Table) -> HintedStrResult<()> {
+        let date1904 = self.date1904;
+        let strings = self.shared_strings()?;
+        let date_styles = self.date_styles()?;
+
+        let path = self.sheets[index].1.clone();
+        let name = table.name.clone();
+        let mut row_number = 0;
+        let mut row = vec![];
+        let mut cell: Option<XlsxCell> = None;
+        let found = read_xml(&mut self.archive, &path, |event| {
+            match event {
+                Xml::Start(tag) if is(tag, "row") => {
+                    row_number = match attr(tag, "r") {
+                        Some(r) => r.trim().parse().unwrap_or(0),
+                        None => row_number + 1,
+                    };
+                    if !(1..=MAX_ROWS).contains(&row_number) {
+                        return Err(table.out_of_bounds());
+                    }
+                }
+                Xml::Start(tag) if is(tag, "c") => {
+                    let col = match attr(tag, "r") {
+                        Some(r) => {
+                            column_index(&r).ok_or_else(|| table.out_of_bounds())?
+                        }
+                        None => row.len(),
+                    };
+                    if col >= MAX_COLS {
+                        return Err(table.out_of_bounds());
+                    }
+                    cell = Some(XlsxCell {
+                        col,
+                        kind: attr(tag, "t").unwrap_or("n".into()).into(),
+                        style: attr(tag, "s").and_then(|s| s.parse().ok()),
+                        formula: false,
+                        value: None,
+                        in_value: false,
+                        inline: None,
+                    });
+                }
+                Xml::End(b"row") => table.push_row(std::mem::take(&mut row))?,
+                Xml::End(b"c") => {
+                    let Some(cell) = cell.take() else { return Ok(()) };
+                    let cell_ref = eco_format!("{}{row_number}", column_name(cell.col));
+                    let failed = |reason: &str| {
+                        eco_format!(
+                            "failed to convert cell {cell_ref} in sheet {} ({reason})",
+                            name.repr()
+                        )
+                    };
+
+                    if table.formula.is_none() && cell.formula {
+                        table.formula = Some(cell_ref.clone());
+                    }
+
+                    let value = match (cell.kind.as_str(), cell.value) {
+                        ("inlineStr", _) => cell
+                            .inline
+                            .map(|item| item.text.into_value())
+                            .unwrap_or_default(),
+                        (_, None) => Value::None,
+                        ("s", Some(v)) => v
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| strings.get(i))
+                            .ok_or_else(|| failed("invalid shared string"))?
+                            .clone()
+                            .into_value(),
+                        ("str", Some(v)) => v.into_value(),
+                        ("b", Some(v)) => (v.trim() == "1").into_value(),
+                        ("e", Some(v)) => bail!(
+                            "cell {cell_ref} in sheet {} contains the error {}",
+                            name.repr(),
+                            v.trim()
+                        ),
+                        ("d", Some(v)) => parse_iso_datetime(&v)
+                            .ok_or_else(|| failed("invalid date"))?,
+                        (_, Some(v)) => {
+                            let number: f64 =
+                                v.trim().parse().map_err(|_| failed("invalid number"))?;
+                            let is_date = cell
+                                .style
+                                .and_then(|s| date_styles.get(s))
+                                .copied()
+                                .unwrap_or(false);
+                            if is_date {
+                                serial_to_datetime(number, date1904)
+                                    .ok_or_else(|| failed("date is out of range"))?
+                                    .into_value()
+                            } else {
+                                number_value(number)
+                            }
+                        }
+                    };
+
+                    if cell.col >= row.len() {
+                        row.resize(cell.col + 1, Value::None);
+                    }
+                    row[cell.col] = value;
+                }
+                event => {
+                    if let Some(cell) = &mut cell {
+                        cell.handle(&event);
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        if !found {
+            bail!("sheet {} is missing", name.repr());
+        }
+
+        Ok(())
+    }
+
+    /// Read the table of strings that cells refer to.
+    fn shared_strings(&mut self) -> HintedStrResult<Vec<EcoString>> {
+        let mut strings = vec![];
+        let mut item: Option<StringItem> = None;
+        read_xml(&mut self.archive, "xl/sharedStrings.xml", |event| {
+            match event {
+                Xml::Start(tag) if is(tag, "si") => item = Some(StringItem::default()),
+                Xml::End(b"si") => strings.extend(item.take().map(|item| item.text)),
+                event => {
+                    if let Some(item) = &mut item {
+                        item.handle(&event);
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        Ok(strings)
+    }
+
+    /// Determine which cell styles format numbers as dates or times.
+    fn date_styles(&mut self) -> HintedStrResult<Vec<bool>> {
+        let mut custom: Vec<(u32, bool)> = vec![];
+        let mut formats: Vec<u32> = vec![];
+        let mut in_cell_formats = false;
+        read_xml(&mut self.archive, "xl/styles.xml", |event| {
+            match event {
+                Xml::Start(tag) if is(tag, "numFmt") => {
+                    let id = attr(tag, "numFmtId").and_then(|id| id.parse().ok());
+                    if let (Some(id), Some(code)) = (id, attr(tag, "formatCode")) {
+                        custom.push((id, is_date_format(&code)));
+                    }
+                }
+                Xml::Start(tag) if is(tag, "cellXfs") => in_cell_formats = true,
+                Xml::End(b"cellXfs") => in_cell_formats = false,
+                Xml::Start(tag) if in_cell_formats && is(tag, "xf") => {
+                    formats.push(
+                        attr(tag, "numFmtId").and_then(|id| id.parse().ok()).unwrap_or(0),
+                    );
+                }
+                _ => {}
+            }
+            Ok(())
+        })?;
+
+        Ok(formats
+            .into_iter()
+            .map(|id| match custom.iter().find(|(custom_id, _)| *custom_id == id) {
+                Some(&(_, is_date)) => is_date,
+                None => matches!(id, 14..=22 | 27..=36 | 45..=47 | 50..=58),
+            })
+            .collect())
+    }
+}
+
+/// A cell of an Office Open XML worksheet that is being read.
+struct XlsxCell {
+    /// The cell's zero-based column.
+    col: usize,
+    /// The type of the cell's value, like `s` for a shared string.
+    kind: EcoString,
+    /// The index of the cell's style.
+    style: Option<usize>,
+    /// Whether the cell has a formula.
+    formula: bool,
+    /// The text of the cell's value, if it has one.
+    value: Option<EcoString>,
+    /// Whether the cell's value is open.
+    in_value: bool,
+    /// The cell's inline string, if it has one.
+    inline: Option<StringItem>,
+}
+
+impl XlsxCell {
+    /// Collect the cell's contents.
+    fn handle(&mut self, event: &Xml) {
+        if let Some(inline) = &mut self.inline {
+            match event {
+                Xml::End(b"is") => {}
+                event => return inline.handle(event),
+            }
+        }
+
+        match event {
+            Xml::Start(tag) if is(tag, "f") => self.formula = true,
+            Xml::Start(tag) if is(tag, "v") => {
+                self.value = Some(EcoString::new());
+                self.in_value = true;
+            }
+            Xml::Start(tag) if is(tag, "is") => self.inline = Some(StringItem::default()),
+            Xml::End(b"v") => self.in_value = false,
+            Xml::Text(text) if self.in_value => {
+                if let Some(value) = &mut self.value {
+                    value.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A string item in an Office Open XML workbook that is being read.
+#[derive(Default)]
+struct StringItem {
+    /// The text without phonetic hints.
+    text: EcoString,
+    /// Whether a text run is open.
+    in_run: bool,
+    /// How many phonetic hints are open.
+    phonetic: usize,
+}
+
+impl StringItem {
+    /// Collect the item's text.
+    fn handle(&mut self, event: &Xml) {
+        match event {
+            Xml::Start(tag) if is(tag, "t") => self.in_run = true,
+            Xml::Start(tag) if is(tag, "rPh") => self.phonetic += 1,
+            Xml::End(b"t") => self.in_run = false,
+            Xml::End(b"rPh") => self.phonetic = self.phonetic.saturating_sub(1),
+            Xml::Text(text) if self.in_run && self.phonetic == 0 => {
+                self.text.push_str(text)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An OpenDocument spreadsheet.
+struct Ods<'a> {
+    archive: Archive<'a>,
+}
+
+impl Ods<'_> {
+    /// Read the names of the sheets and the cells of the requested sheet.
+    ///
+    /// The document's content is parsed once while it is decompressed.
+    /// Parsing stops after the requested sheet, so the names only include the
+    /// sheets up to it if it was found. Repeated empty rows and cells are not
+    /// expanded, so sheets that span all rows or columns are read
+    /// efficiently.
+    fn read(
+        &mut self,
+        sheet: Option<&Smart<Sheet>>,
+    ) -> HintedStrResult<(Vec<EcoString>, Option<Table>)> {
+        let mut names = vec![];
+        let mut table: Option<Table> = None;
+        let mut reading = false;
+        let mut depth = 0;
+        let mut spreadsheet = None;
+
+        let mut row_number = 0;
+        let mut row_repeat = 1;
+        let mut row = vec![];
+        let mut empty = 0;
+        let mut cell: Option<OdsCell> = None;
+
+        stream_xml(&mut self.archive, "content.xml", |event| {
+            // The depth of the element that starts or ends.
+            let level = match event {
+                Xml::Start(_) => {
+                    depth += 1;
+                    depth
+                }
+                Xml::End(_) => {
+                    depth -= 1;
+                    depth + 1
+                }
+                Xml::Text(_) => depth,
+            };
+
+            match event {
+                Xml::Start(tag) if is(tag, "spreadsheet") => spreadsheet = Some(level),
+                Xml::Start(tag) if is(tag, "table") && spreadsheet == Some(level - 1) => {
+                    let name = EcoString::from(attr(tag, "name").unwrap_or_default());
+                    let matches = match sheet {
+                        Some(Smart::Auto) => names.is_empty(),
+                        Some(Smart::Custom(Sheet::Index(index))) => names.len() == *index,
+                        Some(Smart::Custom(Sheet::Name(wanted))) => name == *wanted,
+                        None => false,
+                    };
+                    if table.is_none() && matches {
+                        table = Some(Table::new(name.clone()));
+                        reading = true;
+                    }
+                    names.push(name);
+                }
+                // The requested sheet is complete.
+                Xml::End(b"table") if spreadsheet == Some(level - 1) && reading => {
+                    return Ok(ControlFlow::Break(()));
+                }
+                _ if !reading => {}
+                Xml::Start(tag) if is(tag, "table-row") => {
+                    row_repeat = repeat(tag, "number-rows-repeated");
+                    empty = 0;
+                }
+                Xml::Start(tag)
+                    if is(tag, "table-cell") || is(tag, "covered-table-cell") =>
+                {
+                    cell = Some(OdsCell::new(tag));
+                }
+                Xml::End(b"table-row") => {
+                    let Some(table) = &mut table else { return Ok(()) };
+                    let row = std::mem::take(&mut row);
+                    if row.is_empty() {
+                        row_number = (row_number + row_repeat).min(MAX_ROWS);
+                        return Ok(ControlFlow::Continue(()));
+                    }
+
+                    row_number += row_repeat;
+                    if row_number > MAX_ROWS {
+                        return Err(table.out_of_bounds());
+                    }
+
+                    for _ in 1..row_repeat {
+                        table.push_row(row.clone())?;
+                    }
+                    table.push_row(row)?;
+                }
+                Xml::End(b"table-cell" | b"covered-table-cell") => {
+                    let (Some(table), Some(cell)) = (&mut table, cell.take()) else {
+                        return Ok(ControlFlow::Continue(()));
+                    };
+
+                    let col = row.len() + empty;
+                    let cell_ref = eco_format!("{}{}", column_name(col), row_number + 1);
+                    let failed = |reason: &str| {
+                        eco_format!(
+                            "failed to convert cell {cell_ref} in sheet {} ({reason})",
+                            table.name.repr()
+                        )
+                    };
+
+                    if table.formula.is_none() && cell.formula {
+                        table.formula = Some(cell_ref.clone());
+                    }
+
+                    let number = |v: Option<&str>| -> HintedStrResult<Value> {
+                        let number: f64 = v
+                            .unwrap_or_default()
+                            .parse()
+                            .map_err(|_| failed("invalid number"))?;
+                        Ok(number_value(number))
+                    };
+
+                    let value = match cell.kind.as_deref() {
+                        None => Value::None,
+                        Some("float" | "percentage" | "currency") => {
+                            number(cell.value.as_deref())?
+                        }
+                        Some("boolean") => {
+                            (cell.value.as_deref() == Some("true")).into_value()
+                        }
+                        Some("date") => cell
+                            .value
+                            .as_deref()
+                            .and_then(parse_iso_datetime)
+                            .ok_or_else(|| failed("invalid date"))?,
+                        Some("time") => cell
+                            .value
+                            .as_deref()
+                            .and_then(parse_iso_duration)
+                            .ok_or_else(|| failed("invalid time"))?
+                            .into_value(),
+                        Some(_) => cell.text.into_value(),
+                    };
+
+                    // Empty cells are only counted, so that trailing ones are
+                    // never materialized.
+                    if matches!(value, Value::None) {
+                        empty = empty.saturating_add(cell.repeat);
+                        return Ok(ControlFlow::Continue(()));
+                    }
+
+                    if col.saturating_add(cell.repeat) > MAX_COLS {
+                        return Err(table.out_of_bounds());
+                    }
+
+                    row.resize(col, Value::None);
+                    row.extend(std::iter::repeat_n(value, cell.repeat));
+                    empty = 0;
+                }
+                event => {
+                    if let Some(cell) = &mut cell {
+                        cell.handle(&event);
+                    }
+                }
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })?;
+
+        Ok((names, table))
+    }
+}
+
+/// A cell of an OpenDocument spreadsheet that is being read.
+struct OdsCell {
+    /// The type of the cell's value, if it has one.
+    kind: Option<EcoString>,
+    /// The cell's value in the attribute that belongs to its type.
+    value: Option<EcoString>,
+    /// Whether the cell has a formula.
+    formula: bool,
+    /// How often the cell is repeated.
+    repeat: usize,
+    /// The cell's text, with one line per paragraph.
+    text: EcoString,
+    /// The number of the cell's paragraphs.
+    paragraphs: usize,
+    /// Whether one of the cell's paragraphs is open.
+    in_paragraph: bool,
+    /// The depth of the open elements within the cell.
+    depth: usize,
+}
+
+impl OdsCell {
+    /// Start a cell with the attributes of its element.
+    fn new(tag: &BytesStart) -> Self {
+        let kind = attr(tag, "value-type").map(EcoString::from);
+        let key = match kind.as_deref() {
+            Some("boolean") => "boolean-value",
+            Some("date") => "date-value",
+            Some("time") => "time-value",
+            _ => "value",
+        };
+        Self {
+            value: attr(tag, key).map(EcoString::from),
+            kind,
+            formula: attr(tag, "formula").is_some(),
+            repeat: repeat(tag, "number-columns-repeated"),
+            text: EcoString::new(),
+            paragraphs: 0,
+            in_paragraph: false,
+            depth: 0,
+        }
+    }
+
+    /// Collect the text of the cell's paragraphs.
+    fn handle(&mut self, event: &Xml) {
+        match event {
+            Xml::Start(tag) => {
+                if self.depth == 0 && is(tag, "p") {
+                    if self.paragraphs > 0 {
+                        self.text.push('\n');
+                    }
+                    self.paragraphs += 1;
+                    self.in_paragraph = true;
+                } else if self.in_paragraph && is(tag, "s") {
+                    // No cell can hold more characters than this.
+                    let count = attr(tag, "c").and_then(|c| c.parse().ok()).unwrap_or(1);
+                    for _ in 0..count.min(32767) {
+                        self.text.push(' ');
+                    }
+                } else if self.in_paragraph && is(tag, "tab") {
+                    self.text.push('\t');
+                } else if self.in_paragraph && is(tag, "line-break") {
+                    self.text.push('\n');
+                }
+                self.depth += 1;
+            }
+            Xml::End(_) => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    self.in_paragraph = false;
+                }
+            }
+            Xml::Text(text) if self.in_paragraph => self.text.push_str(text),
+            Xml::Text(_) => {}
+        }
+    }
+}
+
+/// How often an OpenDocument row or cell is repeated.
+fn repeat(tag: &BytesStart, key: &str) -> usize {
+    attr(tag, key).and_then(|n| n.parse().ok()).unwrap_or(1).max(1)
+}
+
+/// An event while streaming an XML part of a spreadsheet.
+enum Xml<'a> {
+    /// An element starts. Self-closing elements are directly followed by
+    /// their end.
+    Start(&'a BytesStart<'a>),
+    /// The element with the given local name ends.
+    End(&'a [u8]),
+    /// Text, with its entities resolved.
+    Text(&'a str),
+}
+
+/// Stream an XML part of the archive to `f` and return whether it exists.
+///
+/// The part is parsed while it is decompressed. Parts that would decompress
+/// to more than [`MAX_PART_SIZE`] bytes are rejected.
+fn read_xml(
+    archive: &mut Archive,
+    name: &str,
+    mut f: impl FnMut(Xml) -> HintedStrResult<()>,
+) -> HintedStrResult<bool> {
+    stream_xml(archive, name, |event| f(event).map(ControlFlow::Continue))
+}
+
+/// Stream an XML part of the archive to `f` until it breaks and return
+/// whether the part exists.
+///
+/// The rest of the part is neither decompressed nor parsed once `f` breaks.
+fn stream_xml(
+    archive: &mut Archive,
+    name: &str,
+    mut f: impl FnMut(Xml) -> HintedStrResult<ControlFlow<()>>,
+) -> HintedStrResult<bool> {
+    let file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(false),
+        Err(_) => bail!("failed to read {name} from spreadsheet"),
+    };
+
+    let too_large = || -> HintedString {
+        error!(
+            "{name} in spreadsheet is too large";
+            hint: "parts of spreadsheets can be at most {MAX_PART_SIZE} bytes"
+        )
+    };
+
+    if file.size() > MAX_PART_SIZE {
+        return Err(too_large());
+    }
+
+    // The declared size could be wrong, so the decompressed data is limited,
+    // too.
+    let mut reader =
+        quick_xml::Reader::from_reader(BufReader::new(file.take(MAX_PART_SIZE + 1)));
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event_into(&mut buf);
+        let failed = |err: quick_xml::Error| {
+            if reader.get_ref().get_ref().limit() == 0 {
+                too_large()
+            } else {
+                eco_format!("failed to parse spreadsheet ({err})").into()
+            }
+        };
+
+        let flow = match event {
+            Ok(Event::Start(tag)) => f(Xml::Start(&tag))?,
+            Ok(Event::Empty(tag)) => match f(Xml::Start(&tag))? {
+                ControlFlow::Continue(()) => f(Xml::End(tag.local_name().as_ref()))?,
+                flow => flow,
+            },
+            Ok(Event::End(tag)) => f(Xml::End(tag.local_name().as_ref()))?,
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(failed)?;
+                f(Xml::Text(&text))?
+            }
+            Ok(Event::CData(data)) => f(Xml::Text(&String::from_utf8_lossy(&data)))?,
+            Ok(Event::Eof) => break,
+            Ok(_) => ControlFlow::Continue(()),
+            Err(err) => return Err(failed(err)),
+        };
+
+        if flow.is_break() {
+            return Ok(true);
+        }
+        buf.clear();
+    }
+
+    if reader.get_ref().get_ref().limit() == 0 {
+        return Err(too_large());
+    }
+
+    Ok(true)
+}
+
+/// Whether the tag has the given local name.
+fn is(tag: &BytesStart, name: &str) -> bool {
+    tag.local_name().as_ref() == name.as_bytes()
+}
+
+/// The value of the attribute with the given local name.
+fn attr<'a>(tag: &'a BytesStart, name: &str) -> Option<Cow<'a, str>> {
+    tag.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attr| attr.unescape_value().ok())
+}
+
+/// The letters of a zero-based column index, e.g. `AA` for 26.
+fn column_name(mut col: usize) -> EcoString {
+    let mut letters = vec![];
+    loop {
+        letters.push(b'A' + (col % 26) as u8);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    letters.iter().rev().map(|&b| b as char).collect()
+}
+
+/// The zero-based column index of a cell reference like `B3`.
+fn column_index(cell_ref: &str) -> Option<usize> {
+    let mut col = 0usize;
+    for b in cell_ref.bytes().take_while(u8::is_ascii_alphabetic) {
+        let digit = (b.to_ascii_uppercase() - b'A') as usize + 1;
+        col = col.checked_mul(26)?.checked_add(digit)?;
+    }
+    col.checked_sub(1)
+}
+
+/// Convert a number to an integer if it is whole.
+fn number_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < (1u64 << 53) as f64 {
+        Value::Int(number as i64)
+    } else {
+        Value::Float(number)
+    }
+}
+
+/// Whether a custom number format displays dates or times.
+fn is_date_format(code: &str) -> bool {
+    // Only the first section applies to positive numbers.
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut bracket: Option<String> = None;
+    for c in code.chars() {
+        if let Some(inner) = &mut bracket {
+            if c != ']' {
+                inner.push(c);
+                continue;
+            }
+
+            // Elapsed times like `[h]` or `[mm]` are durations. Other brackets
+            // hold colors like `[Magenta]`, conditions, or locales.
+            if !inner.is_empty()
+                && inner
+                    .chars()
+                    .all(|c| matches!(c.to_ascii_lowercase(), 'h' | 'm' | 's'))
+            {
+                return true;
+            }
+            bracket = None;
+            continue;
+        }
+
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            '[' => bracket = Some(String::new()),
+            ';' => break,
+            'd' | 'D' | 'm' | 'M' | 'y' | 'Y' | 'h' | 'H' | 's' | 'S' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Convert a spreadsheet serial date to a datetime.
+///
+/// Serial dates count days since the end of 1899 (or the start of 1904) and
+/// represent the time of day as a fraction.
+fn serial_to_datetime(serial: f64, date1904: bool) -> Option<Datetime> {
+    if !serial.is_finite() || serial < 0.0 || serial > 3e6 {
+        return None;
+    }
+
+    let mut days = serial.trunc() as i64;
+    let mut secs = (serial.fract() * 86400.0).round() as i64;
+    if secs >= 86400 {
+        days += 1;
+        secs -= 86400;
+    }
+
+    let time = time::Time::from_hms(
+        (secs / 3600) as u8,
+        (secs / 60 % 60) as u8,
+        (secs % 60) as u8,
+    )
+    .ok()?;
+
+    // Excel's 1900 date system counts a non-existent 29 February 1900, so
+    // days before it are shifted by one.
+    let epoch = if date1904 {
+        time::Date::from_calendar_date(1904, time::Month::January, 1)
+    } else if days < 60 {
+        time::Date::from_calendar_date(1899, time::Month::December, 31)
+    } else {
+        time::Date::from_calendar_date(1899, time::Month::December, 30)
+    }
+    .ok()?;
+
+    if days == 0 && !date1904 {
+        return Some(Datetime::Time(time));
+    }
+
+    let date = epoch.checked_add(time::Duration::days(days))?;
+    Some(if secs == 0 {
+        Datetime::Date(date)
+    } else {
+        Datetime::Datetime(time::PrimitiveDateTime::new(date, time))
+    })
+}
+
+/// Parse an ISO 8601 date or date and time, like `2024-03-01T10:30:00`.
+///
+/// Fractional seconds and time zones are ignored.
+fn parse_iso_datetime(text: &str) -> Option<Value> {
+    let (date, time) = match text.trim().split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (text.trim(), None),
+    };
+
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i32>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
+    let date =
+        time::Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()?;
+
+    let Some(time) = time else { return Some(Datetime::Date(date).into_value()) };
+    let mut parts = time.get(..8)?.splitn(3, ':').map(|part| part.parse::<u8>().ok());
+    let (hour, minute, second) = (parts.next()??, parts.next()??, parts.next()??);
+    let time = time::Time::from_hms(hour, minute, second).ok()?;
+    Some(Datetime::Datetime(time::PrimitiveDateTime::new(date, time)).into_value())
+}
+
+/// Parse the ISO 8601 duration of an OpenDocument time cell, like
+/// `PT10H30M00S`, into a time of day.
+fn parse_iso_duration(text: &str) -> Option<Datetime> {
+    let mut rest = text.trim().strip_prefix("PT")?;
+    let mut secs = 0.0;
+    for (unit, factor) in [('H', 3600.0), ('M', 60.0), ('S', 1.0)] {
+        if let Some((number, tail)) = rest.split_once(unit) {
+            secs += number.parse::<f64>().ok()? * factor;
+            rest = tail;
+        }
+    }
+
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let secs = secs.round() as i64 % 86400;
+    let time = time::Time::from_hms(
+        (secs / 3600) as u8,
+        (secs / 60 % 60) as u8,
+        (secs % 60) as u8,
+    )
+    .ok()?;
+    Some(Datetime::Time(time))
+}
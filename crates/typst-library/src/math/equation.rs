@@ -99,6 +99,54 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// The font for text in the equation, like words in quotes and function
+    /// names like `sin`.
+    ///
+    /// When `{none}`, such text is set in the math font. Single letters are
+    /// not affected.
+    ///
+    /// ```example
+    /// #set math.equation(text-font: "Libertinus Serif")
+    /// $ "area" = pi r^2 "if" sin x > 0 $
+    /// ```
+    pub text_font: Option<FontList>,
+
+    /// Fonts to take symbols from that the math font does not have.
+    ///
+    /// These fonts are tried in order before falling back to the regular
+    /// [text font]($text.font). Glyphs from them are positioned with the
+    /// metrics of the math font, like its axis height and rule thickness.
+    pub symbol_fallback: FontList,
+
+    /// The font for [calligraphic]($math.cal) letters.
+    ///
+    /// When set, letters in the calligraphic alphabet are taken from this font
+    /// instead of the math font. All other glyphs and the spacing and
+    /// positioning of the equation still come from the math font. Many text
+    /// fonts don't have the styled letters. Typst then uses their plain
+    /// letters and emits a warning.
+    ///
+    /// ```example
+    /// #set math.equation(cal-font: "Libertinus Serif")
+    /// $ cal(A) subset.eq cal(P)(X) $
+    /// ```
+    pub cal_font: Option<FontList>,
+
+    /// The font for [fraktur]($math.frak) letters.
+    ///
+    /// Works like [`cal-font`]($math.equation.cal-font).
+    pub frak_font: Option<FontList>,
+
+    /// The font for [blackboard bold]($math.bb) letters.
+    ///
+    /// Works like [`cal-font`]($math.equation.cal-font).
+    pub bb_font: Option<FontList>,
+
+    /// The font for [sans-serif]($math.sans) letters.
+    ///
+    /// Works like [`cal-font`]($math.equation.cal-font).
+    pub sans_font: Option<FontList>,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+use typst_syntax::is_id_continue;
+
+use crate::diag::HintedStrResult;
+use crate::engine::Engine;
+use crate::foundations::{cast, func, Context, Label, Str};
+use crate::model::DocumentElem;
+
+/// The name under which a labelled element can be linked to from outside of
+/// the exported document.
+///
+/// When a document is exported to HTML, labelled headings receive their
+/// anchor as their `id`. In PDF, they become named destinations of the same
+/// name. Thus, the same URL fragment works for both formats, e.g.
+/// `doc.pdf#sec-intro` and `doc.html#sec-intro`.
+///
+/// How anchors are derived from labels is configured with the
+/// [`anchors`]($document.anchors) parameter of the document.
+///
+/// ```example
+/// #set document(anchors: "unified")
+///
+/// = Introduction <sec:intro>
+/// #context link(
+///   "https://example.com/doc.pdf#" + anchor(<sec:intro>),
+/// )
+/// ```
+#[func(contextual)]
+pub fn anchor(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite context.
+    context: Tracked<Context>,
+    /// The label to derive the anchor for.
+    label: Label,
+) -> HintedStrResult<Str> {
+    let styles = context.styles()?;
+    let naming = DocumentElem::anchors_in(styles);
+    Ok(engine.introspector.anchor_name(label, naming).into())
+}
+
+/// How anchors for links into exported documents are derived from labels.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AnchorNaming {
+    /// The anchor is the label's name as is.
+    #[default]
+    Label,
+    /// The anchor is a slug of the label's name that is unique within the
+    /// document.
+    Unified,
+}
+
+cast! {
+    AnchorNaming,
+    self => match self {
+        Self::Label => "label".into_value(),
+        Self::Unified => "unified".into_value(),
+    },
+    /// The anchor is the label's name as is.
+    "label" => Self::Label,
+    /// The anchor is a slug of the label's name that is unique within the
+    /// document.
+    "unified" => Self::Unified,
+}
+
+/// Assigns unique anchors to labels, which must be given in document order.
+///
+/// Each label's anchor is its [slug](slugify). If an earlier label already
+/// took the same slug, the first free one of its [`suffixed`] variants is used
+/// instead.
+pub fn unique_anchors(
+    labels: impl IntoIterator<Item = Label>,
+) -> HashMap<Label, EcoString> {
+    let mut anchors = HashMap::new();
+    let mut taken = HashSet::new();
+    for label in labels {
+        if anchors.contains_key(&label) {
+            continue;
+        }
+
+        let slug = slugify(label.resolve().as_str(), "anchor");
+        let anchor = suffixed(&slug).find(|anchor| !taken.contains(anchor)).unwrap();
+        taken.insert(anchor.clone());
+        anchors.insert(label, anchor);
+    }
+    anchors
+}
+
+/// Turns a text into a slug that is safe to use in label names, URL fragments,
+/// and CSS selectors.
+///
+/// Characters that may continue an identifier, like letters, digits, and
+/// combining marks, are lowercased and kept. Every run of other characters,
+/// including hyphens, turns into a single hyphen. Leading and trailing hyphens
+/// are dropped, so that a label with the slug as its name can be referenced
+/// with the `@` syntax. If nothing remains, the slug is the `fallback`.
+pub fn slugify(text: &str, fallback: &str) -> EcoString {
+    let mut slug = EcoString::new();
+    let mut gap = false;
+    for c in text.chars() {
+        if is_id_continue(c) && c != '-' {
+            if gap && !slug.is_empty() {
+                slug.push('-');
+            }
+            gap = false;
+            slug.extend(c.to_lowercase());
+        } else {
+            gap = true;
+        }
+    }
+
+    if slug.is_empty() {
+        slug.push_str(fallback);
+    }
+
+    slug
+}
+
+/// The candidates for making a slug unique: the slug itself, followed by the
+/// slug with the suffixes `-2`, `-3`, and so on.
+pub fn suffixed(slug: &str) -> impl Iterator<Item = EcoString> + '_ {
+    (1..).map(move |n| match n {
+        1 => slug.into(),
+        _ => eco_format!("{slug}-{n}"),
+    })
+}
@@ -33,12 +33,13 @@ use crate::layout::{
     Sizing, TrackSizings, VElem,
 };
 use crate::model::{
-    CitationForm, CiteGroup, Destination, FootnoteElem, HeadingElem, LinkElem, ParElem,
-    Url,
+    CitationForm, CiteGroup, CiteGroupSort, Destination, FootnoteElem, HeadingElem,
+    LinkElem, ParElem, Url,
 };
 use crate::routines::{EvalMode, Routines};
 use crate::text::{
-    FontStyle, Lang, LocalName, Region, SubElem, SuperElem, TextElem, WeightDelta,
+    Collation, FontStyle, Lang, LocalName, Region, SubElem, SuperElem, TextElem,
+    WeightDelta,
 };
 use crate::World;
 
@@ -597,6 +598,51 @@ struct GroupInfo {
     subinfos: SmallVec<[CiteInfo; 1]>,
 }
 
+/// Sorts the references with the same collation as
+/// `array.sorted(by: "locale")`.
+///
+/// Hayagriva compares sort keys by code point, which places e.g. "Ärger" after
+/// "Zebra". If the references are in the alphabetical order of their rendered
+/// text, which is the case for the usual author-based sorting, they are sorted
+/// according to the alphabet of the bibliography's language instead. Other
+/// orders (by citation or chronological) are kept. So are numeric styles, as
+/// their numbers were already assigned in hayagriva's order.
+fn collate_references(
+    bibliography: &mut hayagriva::RenderedBibliography,
+    style: &citationberg::IndependentStyle,
+    lang: Lang,
+) {
+    let numeric = style.info.category.iter().any(|category| {
+        matches!(
+            category,
+            citationberg::StyleCategory::CitationFormat {
+                format: citationberg::CitationFormat::Numeric
+            }
+        )
+    });
+    if numeric {
+        return;
+    }
+
+    let mut keyed: Vec<_> = std::mem::take(&mut bibliography.items)
+        .into_iter()
+        .map(|item| {
+            let mut text = String::new();
+            item.content
+                .write_buf(&mut text, hayagriva::BufWriteFormat::Plain)
+                .ok();
+            (text.to_lowercase(), item)
+        })
+        .collect();
+
+    if keyed.windows(2).all(|pair| pair[0].0 <= pair[1].0) {
+        let collation = Collation::Locale(lang);
+        keyed.sort_by(|(a, _), (b, _)| collation.compare(a, b));
+    }
+
+    bibliography.items = keyed.into_iter().map(|(_, item)| item).collect();
+}
+
 /// Details about a citation item in a request.
 struct CiteInfo {
     /// The citation's key.
@@ -635,6 +681,7 @@ impl<'a> Generator<'a> {
         let database = self.bibliography.bibliography();
         let bibliography_style = self.bibliography.style(StyleChain::default());
         let styles = Arena::new();
+        let mut adjusted_styles = HashMap::new();
 
         // Process all citation groups.
         let mut driver = BibliographyDriver::new();
@@ -646,8 +693,7 @@ impl<'a> Generator<'a> {
             // Groups should never be empty.
             let Some(first) = children.first() else { continue };
 
-            let mut subinfos = SmallVec::with_capacity(children.len());
-            let mut items = Vec::with_capacity(children.len());
+            let mut cites = Vec::with_capacity(children.len());
             let mut errors = EcoVec::new();
             let mut normal = true;
 
@@ -685,8 +731,11 @@ impl<'a> Generator<'a> {
                 };
 
                 normal &= special_form.is_none();
-                subinfos.push(CiteInfo { key, supplement, hidden });
-                items.push(CitationItem::new(entry, locator, None, hidden, special_form));
+                cites.push((
+                    entry.date().map(|date| date.year),
+                    CiteInfo { key, supplement, hidden },
+                    CitationItem::new(entry, locator, None, hidden, special_form),
+                ));
             }
 
             if !errors.is_empty() {
@@ -694,11 +743,46 @@ impl<'a> Generator<'a> {
                 continue;
             }
 
-            let style = match first.style(StyleChain::default()) {
+            let mut style = match first.style(StyleChain::default()) {
                 Smart::Auto => &bibliography_style.style,
                 Smart::Custom(style) => styles.alloc(style.style),
             };
 
+            // Apply the group's sorting and separator. Unless the style's own
+            // sorting is requested, it is disabled so that hayagriva keeps the
+            // order of the items.
+            let sort = first.group_sort(StyleChain::default());
+            let separator = first.group_separator(StyleChain::default());
+            if sort == CiteGroupSort::Year {
+                cites.sort_by_key(|(year, ..)| year.unwrap_or(i32::MAX));
+            }
+
+            if sort != CiteGroupSort::Csl
+                || (separator.is_custom() && style.citation.layout.delimiter.is_none())
+            {
+                // Each style is adjusted once and then shared by all groups
+                // with the same settings.
+                let key =
+                    (Arc::as_ptr(style), sort != CiteGroupSort::Csl, separator.clone());
+                style = *adjusted_styles.entry(key).or_insert_with(|| {
+                    let mut adjusted = (***style).clone();
+                    if sort != CiteGroupSort::Csl {
+                        adjusted.citation.sort = None;
+                    }
+                    if let Smart::Custom(separator) = separator {
+                        adjusted
+                            .citation
+                            .layout
+                            .delimiter
+                            .get_or_insert(separator.into());
+                    }
+                    &*styles.alloc(Arc::new(LazyHash::new(adjusted)))
+                });
+            }
+
+            let (subinfos, items): (SmallVec<_>, Vec<_>) =
+                cites.into_iter().map(|(_, info, item)| (info, item)).unzip();
+
             self.infos.push(GroupInfo {
                 location,
                 subinfos,
@@ -719,10 +803,8 @@ impl<'a> Generator<'a> {
             ));
         }
 
-        let locale = locale(
-            self.bibliography.lang().copied().unwrap_or(Lang::ENGLISH),
-            self.bibliography.region().copied().flatten(),
-        );
+        let lang = self.bibliography.lang().copied().unwrap_or(Lang::ENGLISH);
+        let locale = locale(lang, self.bibliography.region().copied().flatten());
 
         // Add hidden items for everything if we should print the whole
         // bibliography.
@@ -738,11 +820,17 @@ impl<'a> Generator<'a> {
             }
         }
 
-        driver.finish(BibliographyRequest {
+        let mut rendered = driver.finish(BibliographyRequest {
             style: bibliography_style.get(),
             locale: Some(locale),
             locale_files: &LOCALES,
-        })
+        });
+
+        if let Some(bibliography) = &mut rendered.bibliography {
+            collate_references(bibliography, bibliography_style.get(), lang);
+        }
+
+        rendered
     }
 
     /// Displays hayagriva's output as content for the citations and references.
@@ -1,3 +1,5 @@
+use ecow::EcoString;
+
 use crate::diag::{error, At, HintedString, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
@@ -97,6 +99,43 @@ pub struct CiteElem {
     #[parse(CslStyle::parse_smart(engine, args)?)]
     pub style: Smart<CslStyle>,
 
+    /// Whether the citation may be grouped with adjacent citations.
+    ///
+    /// Adjacent citations are normally combined into a single citation, e.g.
+    /// into one parenthetical. When this is set to `{false}`, the citation is
+    /// always displayed on its own.
+    ///
+    /// ```example
+    /// #set cite(style: "apa")
+    /// @netwok #cite(<arrgh>, group: false)
+    /// >>> #set text(0pt)
+    /// >>> #bibliography("works.bib")
+    /// ```
+    #[default(true)]
+    pub group: bool,
+
+    /// How to order the citations in a group of adjacent citations.
+    ///
+    /// The settings of the first citation in a group apply to the whole
+    /// group.
+    ///
+    /// ```example
+    /// #set cite(style: "apa", group-sort: "year")
+    /// @netwok @arrgh
+    /// >>> #set text(0pt)
+    /// >>> #bibliography("works.bib")
+    /// ```
+    pub group_sort: CiteGroupSort,
+
+    /// The separator between the citations in a group of adjacent citations.
+    ///
+    /// This is only used if the citation style does not define a separator
+    /// itself. When set to `{auto}`, the citations are not separated by
+    /// anything in this case. Just like the
+    /// [`group-sort`]($cite.group-sort), the setting of the first citation in
+    /// a group applies to the whole group.
+    pub group_separator: Smart<EcoString>,
+
     /// The text language setting where the citation is.
     #[internal]
     #[synthesized]
@@ -138,6 +177,20 @@ pub enum CitationForm {
     Year,
 }
 
+/// How to order the citations in a group.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum CiteGroupSort {
+    /// Order the citations as prescribed by the citation style.
+    #[default]
+    Csl,
+    /// Order the citations by year of publication. Citations from the same
+    /// year keep their order.
+    Year,
+    /// Keep the citations in the order in which they appear in the document.
+    #[string("none")]
+    Unsorted,
+}
+
 /// A group of citations.
 ///
 /// This is automatically created from adjacent citations during show rule
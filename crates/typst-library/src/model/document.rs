@@ -1,11 +1,12 @@
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 
 use crate::diag::{bail, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Args, Array, Construct, Content, Datetime, Fields, Smart, StyleChain,
-    Styles, Value,
+    cast, elem, Args, Array, Construct, Content, Datetime, Dict, Fields, Label, Regex,
+    Repr, Smart, StyleChain, Styles, Value,
 };
+use crate::model::AnchorNaming;
 
 /// The root element of a document and its metadata.
 ///
@@ -54,10 +55,135 @@ pub struct DocumentElem {
     /// The year component must be at least zero in order to be embedded into a
     /// PDF.
     ///
+    /// If this is `{"source"}`, Typst uses the most recent modification date
+    /// among all files the compilation depends on. This way, the date reflects
+    /// when the content last changed rather than when it was compiled. This
+    /// requires the environment to provide modification dates (which the CLI
+    /// does) and fails otherwise.
+    ///
     /// If you want to create byte-by-byte reproducible PDFs, set this to
-    /// something other than `{auto}`.
+    /// something other than `{auto}`. When using `{"source"}`, the output is
+    /// only reproducible if the modification dates of all files are preserved.
     #[ghost]
-    pub date: Smart<Option<Datetime>>,
+    pub date: Smart<DocumentDate>,
+
+    /// Custom metadata entries, for example for document management and
+    /// indexing systems.
+    ///
+    /// This is a dictionary whose values are strings, integers, or
+    /// [datetimes]($datetime). Keys must start with an ASCII letter and may
+    /// only contain ASCII letters, digits, hyphens, and underscores. Keys that
+    /// collide with the standard metadata fields, like `title` or `subject`,
+    /// are not allowed.
+    ///
+    /// In PDF, each entry is embedded as a property in the `typst` namespace
+    /// of the XMP metadata and as an entry of the document information
+    /// dictionary. Files that conform to PDF/A do not receive custom
+    /// metadata. In HTML, each entry becomes a `<meta>` tag with the name
+    /// `typst:key` in the document's `<head>`. Datetimes are written in the
+    /// ISO 8601 format in both cases.
+    ///
+    /// ```example
+    /// #set document(metadata: (
+    ///   department: "R&D",
+    ///   classification: "internal",
+    ///   revision: 3,
+    /// ))
+    /// ```
+    #[ghost]
+    pub metadata: DocumentMetadata,
+
+    /// How the [anchors]($anchor) of labelled elements are named.
+    ///
+    /// When exporting to HTML, labelled headings get their anchor as their
+    /// `id`. When exporting to PDF, they get a named destination with their
+    /// anchor as its name. Links from outside of the document can thus use the
+    /// same URL fragment for both formats.
+    ///
+    /// - With `{"label"}` (default), the anchor is the label's name as is.
+    /// - With `{"unified"}`, the anchor is a slug of the label's name: Letters
+    ///   are lowercased. Letters, digits, and underscores are kept, while all
+    ///   other characters turn into hyphens. Runs of hyphens are collapsed
+    ///   into one and leading and trailing hyphens are dropped. If nothing
+    ///   remains, the slug is `anchor`. If multiple labels have the same
+    ///   slug, the first one in the document keeps it and the following ones
+    ///   get the smallest free suffix of `-2`, `-3`, and so on. For example,
+    ///   the anchor of `<sec:intro>` is `sec-intro`.
+    ///
+    /// The unified naming is safe to use in URLs and CSS selectors and also
+    /// keeps anchors within the length limits of PDF. It will become the
+    /// default in the next breaking release. As this changes the `id`s of
+    /// headings in HTML and the named destinations in PDF, check existing
+    /// links into your documents when opting into it.
+    ///
+    /// ```example
+    /// #set document(anchors: "unified")
+    ///
+    /// = Results & Discussion <Results>
+    /// #context anchor(<Results>)
+    /// ```
+    #[ghost]
+    pub anchors: AnchorNaming,
+
+    /// How the numbers of headings and figures are written in HTML export.
+    ///
+    /// - With `{"text"}` (default), the numbers are written into the HTML as
+    ///   text, exactly as they appear in PDF.
+    /// - With `{"css"}`, numbered headings and figures are instead annotated
+    ///   with `data-counter`, `data-level`, and `data-numbering-pattern`
+    ///   attributes and the document's `<head>` receives a stylesheet that
+    ///   displays the numbers with
+    ///   [CSS counters](https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_counter_styles/Using_CSS_counters).
+    ///   This way, the numbers are not part of the text content, and
+    ///   stylesheets of your own can restyle or hide them.
+    ///
+    /// CSS counters can only reproduce numbering patterns whose counting
+    /// symbols are `1`, `a`, `A`, `i`, or `I`, like `{"1.1"}`, `{"A."}`, or
+    /// `{"(1)"}`. Numbers with other patterns or with a numbering function
+    /// are still written as text and produce a warning. Equations are not
+    /// exported to HTML yet, so this setting does not affect them.
+    ///
+    /// Note that only the numbers at the elements themselves are generated
+    /// by CSS. [References]($ref) and [outlines]($outline) always contain
+    /// the numbers as text. Since CSS counters only count the elements they
+    /// see, the two disagree if you update a [counter]($counter) manually.
+    /// If you create your own `<html>` element, the stylesheet is not added
+    /// to it.
+    ///
+    /// ```example
+    /// #set document(html-numbering: "css")
+    /// #set heading(numbering: "1.1")
+    ///
+    /// = Introduction
+    /// == Motivation
+    /// ```
+    #[ghost]
+    pub html_numbering: HtmlNumbering,
+
+    /// Conventions that the names of labels in the document must follow.
+    ///
+    /// This is a dictionary with the following keys, which are all optional:
+    /// - `pattern`: A [regular expression]($regex) that each label's name
+    ///   must match. Labels that don't match produce an error at the labelled
+    ///   element.
+    /// - `near-duplicates`: Whether labels that only differ in case or in
+    ///   underscores and hyphens, like `<fig-results>` and `<fig_results>`,
+    ///   are reported. With `{"warn"}`, each such pair of labels produces a
+    ///   warning. With `{"ignore"}` (default), they are not reported.
+    ///
+    /// Labels are only checked if a policy is set.
+    ///
+    /// ```example
+    /// #set document(label-policy: (
+    ///   pattern: regex("^(fig|tab|sec|eq):[a-z0-9-]+$"),
+    ///   near-duplicates: "warn",
+    /// ))
+    ///
+    /// = Introduction <sec:intro>
+    /// See @sec:intro.
+    /// ```
+    #[ghost]
+    pub label_policy: Option<LabelPolicy>,
 }
 
 impl Construct for DocumentElem {
@@ -66,6 +192,26 @@ impl Construct for DocumentElem {
     }
 }
 
+/// The creation date of a document.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub enum DocumentDate {
+    /// A fixed date, or `None` if no date should be embedded.
+    Fixed(Option<Datetime>),
+    /// The most recent modification date among the compilation's files.
+    Source,
+}
+
+cast! {
+    DocumentDate,
+    self => match self {
+        Self::Fixed(date) => date.into_value(),
+        Self::Source => "source".into_value(),
+    },
+    v: Option<Datetime> => Self::Fixed(v),
+    /// The most recent modification date among the compilation's files.
+    "source" => Self::Source,
+}
+
 /// A list of authors.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
 pub struct Author(Vec<EcoString>);
@@ -88,6 +234,250 @@ cast! {
     v: Array => Self(v.into_iter().map(Value::cast).collect::<HintedStrResult<_>>()?),
 }
 
+/// Custom metadata entries, in the order in which they were given.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct DocumentMetadata(pub Vec<(EcoString, DocumentMetadataValue)>);
+
+impl DocumentMetadata {
+    /// Metadata keys that collide with standard metadata fields of Typst,
+    /// PDF, or HTML, normalized by [`Self::normalize`].
+    const RESERVED: &'static [&'static str] = &[
+        "author",
+        "creationdate",
+        "creator",
+        "date",
+        "description",
+        "keywords",
+        "moddate",
+        "producer",
+        "subject",
+        "title",
+        "trapped",
+    ];
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the entries.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &DocumentMetadataValue)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Checks that a key is a valid and unreserved name for an entry.
+    fn check_key(key: &str) -> HintedStrResult<()> {
+        if !key.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "metadata key {} is not valid", key.repr();
+                hint: "keys must start with a letter and may only contain \
+                       ASCII letters, digits, hyphens, and underscores"
+            );
+        }
+
+        let normalized = Self::normalize(key);
+        if Self::RESERVED.contains(&normalized.as_str()) {
+            if ["author", "date", "description", "keywords", "title"]
+                .contains(&normalized.as_str())
+            {
+                bail!(
+                    "metadata key {} is reserved", key.repr();
+                    hint: "use the `{normalized}` parameter of `document` instead"
+                );
+            }
+            bail!(
+                "metadata key {} is reserved", key.repr();
+                hint: "it collides with a standard metadata field"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Lowercases a key and removes hyphens and underscores, so that
+    /// variants of the same name are recognized.
+    fn normalize(key: &str) -> EcoString {
+        key.chars()
+            .filter(|&c| c != '-' && c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+}
+
+cast! {
+    DocumentMetadata,
+    self => self
+        .0
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    v: Dict => {
+        let mut entries = Vec::with_capacity(v.len());
+        for (key, value) in v {
+            Self::check_key(&key)?;
+            entries.push((key.into(), value.cast()?));
+        }
+        Self(entries)
+    },
+}
+
+/// The value of a custom metadata entry.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum DocumentMetadataValue {
+    /// A string.
+    Str(EcoString),
+    /// An integer.
+    Int(i64),
+    /// A date, a time, or both.
+    Datetime(Datetime),
+}
+
+impl DocumentMetadataValue {
+    /// The value as text, with datetimes in the ISO 8601 format.
+    pub fn to_text(&self) -> EcoString {
+        match self {
+            Self::Str(v) => v.clone(),
+            Self::Int(v) => eco_format!("{v}"),
+            Self::Datetime(v) => {
+                let date = match (v.year(), v.month(), v.day()) {
+                    (Some(y), Some(m), Some(d)) => {
+                        Some(eco_format!("{y:04}-{m:02}-{d:02}"))
+                    }
+                    _ => None,
+                };
+                let time = match (v.hour(), v.minute(), v.second()) {
+                    (Some(h), Some(m), Some(s)) => {
+                        Some(eco_format!("{h:02}:{m:02}:{s:02}"))
+                    }
+                    _ => None,
+                };
+                match (date, time) {
+                    (Some(date), Some(time)) => eco_format!("{date}T{time}"),
+                    (Some(text), None) | (None, Some(text)) => text,
+                    (None, None) => EcoString::new(),
+                }
+            }
+        }
+    }
+}
+
+cast! {
+    DocumentMetadataValue,
+    self => match self {
+        Self::Str(v) => v.into_value(),
+        Self::Int(v) => v.into_value(),
+        Self::Datetime(v) => v.into_value(),
+    },
+    v: EcoString => Self::Str(v),
+    v: i64 => Self::Int(v),
+    v: Datetime => Self::Datetime(v),
+}
+
+/// Conventions that the names of labels must follow.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct LabelPolicy {
+    /// A pattern that the names of labels must match.
+    pub pattern: Option<Regex>,
+    /// Whether labels that only differ in case or in underscores and hyphens
+    /// are reported.
+    pub near_duplicates: NearDuplicates,
+}
+
+impl LabelPolicy {
+    /// Checks that a label's name matches the pattern.
+    pub fn check(&self, label: Label) -> HintedStrResult<()> {
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(label.resolve().as_str()) {
+                bail!(
+                    "label `{}` does not match the label pattern", label.repr();
+                    hint: "the document's label policy requires labels to match {}",
+                    pattern.repr(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Lowercases a label's name and turns underscores into hyphens, so that
+    /// labels that are easily confused have the same normalized name.
+    pub fn normalize(label: Label) -> EcoString {
+        label
+            .resolve()
+            .as_str()
+            .chars()
+            .flat_map(char::to_lowercase)
+            .map(|c| if c == '_' { '-' } else { c })
+            .collect()
+    }
+}
+
+cast! {
+    LabelPolicy,
+    self => {
+        let mut dict = Dict::new();
+        if let Some(pattern) = self.pattern {
+            dict.insert("pattern".into(), pattern.into_value());
+        }
+        dict.insert("near-duplicates".into(), self.near_duplicates.into_value());
+        dict.into_value()
+    },
+    mut v: Dict => {
+        let pattern = v.take("pattern").ok().map(|v| v.cast()).transpose()?;
+        let near_duplicates =
+            v.take("near-duplicates").ok().map(|v| v.cast()).transpose()?;
+        v.finish(&["pattern", "near-duplicates"])?;
+        Self { pattern, near_duplicates: near_duplicates.unwrap_or_default() }
+    },
+}
+
+/// Whether labels that only differ in case or in underscores and hyphens are
+/// reported.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NearDuplicates {
+    /// Such labels are not reported.
+    #[default]
+    Ignore,
+    /// Each pair of such labels produces a warning.
+    Warn,
+}
+
+cast! {
+    NearDuplicates,
+    self => match self {
+        Self::Ignore => "ignore".into_value(),
+        Self::Warn => "warn".into_value(),
+    },
+    /// Such labels are not reported.
+    "ignore" => Self::Ignore,
+    /// Each pair of such labels produces a warning.
+    "warn" => Self::Warn,
+}
+
+/// How the numbers of headings and figures are written in HTML export.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HtmlNumbering {
+    /// The numbers are written as text.
+    #[default]
+    Text,
+    /// The numbers are displayed with CSS counters.
+    Css,
+}
+
+cast! {
+    HtmlNumbering,
+    self => match self {
+        Self::Text => "text".into_value(),
+        Self::Css => "css".into_value(),
+    },
+    /// The numbers are written as text.
+    "text" => Self::Text,
+    /// The numbers are displayed with CSS counters.
+    "css" => Self::Css,
+}
+
 /// Details about the document.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
 pub struct DocumentInfo {
@@ -101,6 +491,16 @@ pub struct DocumentInfo {
     pub keywords: Vec<EcoString>,
     /// The document's creation date.
     pub date: Smart<Option<Datetime>>,
+    /// Whether the creation date should be derived from the modification
+    /// dates of the compilation's files. This is resolved by the compiler,
+    /// which then sets the `date` accordingly.
+    pub date_from_source: bool,
+    /// The document's custom metadata entries.
+    pub metadata: DocumentMetadata,
+    /// How the anchors of labelled elements are named.
+    pub anchors: AnchorNaming,
+    /// The conventions for the names of labels.
+    pub label_policy: Option<LabelPolicy>,
 }
 
 impl DocumentInfo {
@@ -125,7 +525,21 @@ impl DocumentInfo {
             self.keywords = DocumentElem::keywords_in(chain).0;
         }
         if has(<DocumentElem as Fields>::Enum::Date) {
-            self.date = DocumentElem::date_in(chain);
+            let date = DocumentElem::date_in(chain);
+            self.date_from_source = date == Smart::Custom(DocumentDate::Source);
+            self.date = match date {
+                Smart::Custom(DocumentDate::Fixed(date)) => Smart::Custom(date),
+                _ => Smart::Auto,
+            };
+        }
+        if has(<DocumentElem as Fields>::Enum::Metadata) {
+            self.metadata = DocumentElem::metadata_in(chain);
+        }
+        if has(<DocumentElem as Fields>::Enum::Anchors) {
+            self.anchors = DocumentElem::anchors_in(chain);
+        }
+        if has(<DocumentElem as Fields>::Enum::LabelPolicy) {
+            self.label_policy = DocumentElem::label_policy_in(chain);
         }
     }
 }
@@ -167,7 +167,8 @@ pub struct EnumElem {
     ///
     /// If set to `{auto}`, uses paragraph [`leading`]($par.leading) for tight
     /// enumerations and paragraph [`spacing`]($par.spacing) for wide
-    /// (non-tight) enumerations.
+    /// (non-tight) enumerations. Items that consist of multiple paragraphs are
+    /// always separated by paragraph spacing, even in tight enumerations.
     pub spacing: Smart<Length>,
 
     /// The alignment that enum numbers should have.
@@ -2,26 +2,32 @@ use std::borrow::Cow;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
+use smallvec::smallvec;
 use typst_utils::NonZeroExt;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, select_where, Content, Element, NativeElement, Packed, Selector,
-    Show, ShowSet, Smart, StyleChain, Styles, Synthesize, TargetElem,
+    cast, elem, scope, select_where, Content, Dict, Element, NativeElement, Packed, Repr,
+    Selector, Show, ShowSet, Smart, Str, StyleChain, Styles, Synthesize, TargetElem,
 };
-use crate::html::{tag, HtmlElem};
+use crate::html::{attr, tag, HtmlElem};
 use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
 use crate::layout::{
-    AlignElem, Alignment, BlockBody, BlockElem, Em, HAlignment, Length, OuterVAlignment,
-    PlaceElem, PlacementScope, VAlignment, VElem,
+    Abs, AlignElem, Alignment, BlockBody, BlockElem, Em, Fr, FrameRole, GridCell,
+    GridChild, GridElem, GridItem, HAlignment, Length, PlaceElem, PlacementScope, Ratio,
+    Rel, Sizing, TrackSizings, VAlignment, VElem,
+};
+use crate::model::{
+    css_numbering_pattern, slugify, DocumentElem, HtmlNumbering, Numbering,
+    NumberingPattern, Outlinable, Refable, Supplement, SupplementForm, TableElem,
 };
-use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::text::{Lang, Region, TextElem};
 use crate::visualize::ImageElem;
+use crate::{Feature, World};
 
 /// A figure with an optional caption.
 ///
@@ -208,6 +214,27 @@ pub struct FigureElem {
     ///   kind: "foo",
     /// )
     /// ```
+    ///
+    /// In many languages, the supplement takes a different form in a
+    /// [reference]($ref) than in the caption. If the function has a named
+    /// parameter `form`, it is called once with `{"caption"}` for the caption
+    /// and once with `{"reference"}` for references to the figure. With the
+    /// automatic supplement, Typst picks the right form for the text language
+    /// where it knows one.
+    ///
+    /// ```example
+    /// #set text(lang: "ru")
+    /// #set figure(supplement: (form: none, ..) => {
+    ///   if form == "reference" [рис.] else [Рисунок]
+    /// })
+    ///
+    /// #figure(
+    ///   rect[Круг],
+    ///   caption: [Пример],
+    /// ) <rect>
+    ///
+    /// См. @rect.
+    /// ```
     #[borrowed]
     pub supplement: Smart<Option<Supplement>>,
 
@@ -217,7 +244,8 @@ pub struct FigureElem {
     #[borrowed]
     pub numbering: Option<Numbering>,
 
-    /// The vertical gap between the body and caption.
+    /// The gap between the body and caption. If the caption is placed to the
+    /// side of the body, this is the horizontal gap between them.
     #[default(Em::new(0.65).into())]
     pub gap: Length,
 
@@ -236,6 +264,11 @@ pub struct FigureElem {
     /// number or reset the counter.
     #[synthesized]
     pub counter: Option<Counter>,
+
+    /// The figure's supplement in the form used in references.
+    #[internal]
+    #[synthesized]
+    pub reference_supplement: Option<Content>,
 }
 
 #[scope]
@@ -263,27 +296,32 @@ impl Synthesize for Packed<FigureElem> {
                 .unwrap_or_else(|| FigureKind::Elem(ImageElem::elem()))
         });
 
-        // Resolve the supplement.
-        let supplement = match elem.supplement(styles).as_ref() {
+        // Resolve the supplement, both for the caption and for references.
+        let (supplement, reference_supplement) = match elem.supplement(styles).as_ref() {
             Smart::Auto => {
                 // Default to the local name for the kind, if available.
-                let name = match &kind {
-                    FigureKind::Elem(func) => func
-                        .local_name(
-                            TextElem::lang_in(styles),
-                            TextElem::region_in(styles),
-                        )
-                        .map(TextElem::packed),
+                let lang = TextElem::lang_in(styles);
+                let region = TextElem::region_in(styles);
+                let names = match &kind {
+                    FigureKind::Elem(func) => func.local_name(lang, region).map(|name| {
+                        let reference = func.local_reference_name(lang, region);
+                        (name, reference.unwrap_or(name))
+                    }),
                     FigureKind::Name(_) => None,
                 };
 
-                if numbering.is_some() && name.is_none() {
+                if numbering.is_some() && names.is_none() {
                     bail!(span, "please specify the figure's supplement")
                 }
 
-                Some(name.unwrap_or_default())
+                let (name, reference) = names
+                    .map(|(name, reference)| {
+                        (TextElem::packed(name), TextElem::packed(reference))
+                    })
+                    .unwrap_or_default();
+                (Some(name), Some(reference))
             }
-            Smart::Custom(None) => None,
+            Smart::Custom(None) => (None, None),
             Smart::Custom(Some(supplement)) => {
                 // Resolve the supplement with the first descendant of the kind or
                 // just the body, if none was found.
@@ -296,7 +334,27 @@ impl Synthesize for Packed<FigureElem> {
                 };
 
                 let target = descendant.unwrap_or_else(|| Cow::Borrowed(elem.body()));
-                Some(supplement.resolve(engine, styles, [target])?)
+                let caption = supplement.resolve_form(
+                    engine,
+                    styles,
+                    [target.clone()],
+                    SupplementForm::Caption,
+                )?;
+
+                // Only call functions a second time if they can tell the forms
+                // apart.
+                let reference = match supplement {
+                    Supplement::Func(func) if func.has_named_param("form") => supplement
+                        .resolve_form(
+                            engine,
+                            styles,
+                            [target],
+                            SupplementForm::Reference,
+                        )?,
+                    _ => caption.clone(),
+                };
+
+                (Some(caption), Some(reference))
             }
         };
 
@@ -319,6 +377,7 @@ impl Synthesize for Packed<FigureElem> {
         elem.push_kind(Smart::Custom(kind));
         elem.push_supplement(Smart::Custom(supplement.map(Supplement::Content)));
         elem.push_counter(Some(counter));
+        elem.push_reference_supplement(reference_supplement);
         elem.push_caption(caption);
 
         Ok(())
@@ -333,25 +392,65 @@ impl Show for Packed<FigureElem> {
 
         // Build the caption, if any.
         if let Some(caption) = self.caption(styles) {
-            let (first, second) = match caption.position(styles) {
-                OuterVAlignment::Top => (caption.pack(), realized),
-                OuterVAlignment::Bottom => (realized, caption.pack()),
+            let side = caption.position(styles).resolve(self.kind(styles).custom());
+            let caption_first = matches!(side, CaptionSide::Top | CaptionSide::Start);
+            let (first, second) = if caption_first {
+                (caption.clone().pack(), realized)
+            } else {
+                (realized, caption.clone().pack())
             };
-            let mut seq = Vec::with_capacity(3);
-            seq.push(first);
-            if !target.is_html() {
+
+            if target.is_html() {
+                realized = match self.body.to_packed::<TableElem>() {
+                    // A table gets the caption as its own `<caption>`.
+                    Some(table) => {
+                        let mut caption = caption.clone();
+                        caption.push_in_table(true);
+                        let mut table = table.clone();
+                        table.push_html_caption(caption);
+                        table.pack()
+                    }
+                    None => first + second,
+                };
+            } else if matches!(side, CaptionSide::Start | CaptionSide::End) {
+                // The grid lays its columns out from right to left in
+                // right-to-left text, so start and end are respected.
+                let width = Sizing::Rel(caption.side_width(styles));
+                let rest = Sizing::Fr(Fr::one());
+                let columns = if caption_first { [width, rest] } else { [rest, width] };
+                let cell = |body| {
+                    GridChild::Item(GridItem::Cell(
+                        Packed::new(GridCell::new(body)).spanned(self.span()),
+                    ))
+                };
+                realized = GridElem::new(vec![cell(first), cell(second)])
+                    .with_columns(TrackSizings(columns.into_iter().collect()))
+                    .with_column_gutter(TrackSizings(smallvec![Sizing::Rel(
+                        self.gap(styles).into()
+                    )]))
+                    .pack()
+                    .spanned(self.span());
+            } else {
                 let v = VElem::new(self.gap(styles).into()).with_weak(true);
-                seq.push(v.pack().spanned(self.span()))
+                realized =
+                    Content::sequence([first, v.pack().spanned(self.span()), second]);
             }
-            seq.push(second);
-            realized = Content::sequence(seq)
         }
 
         if target.is_html() {
-            return Ok(HtmlElem::new(tag::figure)
-                .with_body(Some(realized))
-                .pack()
-                .spanned(self.span()));
+            let mut figure = HtmlElem::new(tag::figure).with_body(Some(realized));
+
+            // Numbered figures are counted by CSS, so that captions can
+            // display the number with a CSS counter.
+            if DocumentElem::html_numbering_in(styles) == HtmlNumbering::Css
+                && (**self).numbering(styles).is_some()
+            {
+                if let Smart::Custom(kind) = self.kind(styles) {
+                    figure = figure.with_attr(attr::data_counter, html_counter(&kind));
+                }
+            }
+
+            return Ok(figure.pack().spanned(self.span()));
         }
 
         // Wrap the contents in a block.
@@ -404,6 +503,10 @@ impl Count for Packed<FigureElem> {
 impl Refable for Packed<FigureElem> {
     fn supplement(&self) -> Content {
         // After synthesis, this should always be custom content.
+        if let Some(Some(reference)) = (**self).reference_supplement() {
+            return reference.clone();
+        }
+
         match (**self).supplement(StyleChain::default()).as_ref() {
             Smart::Custom(Some(Supplement::Content(content))) => content.clone(),
             _ => Content::empty(),
@@ -486,7 +589,28 @@ impl Outlinable for Packed<FigureElem> {
 /// ```
 #[elem(name = "caption", Synthesize, Show)]
 pub struct FigureCaption {
-    /// The caption's position in the figure. Either `{top}` or `{bottom}`.
+    /// The caption's position in the figure.
+    ///
+    /// - `{top}` or `{bottom}`: The caption is placed above or below the body.
+    /// - `{start}` or `{end}`: The caption is placed in a column next to the
+    ///   body, on the side where text starts or ends. The width of the column
+    ///   is configured with [`side-width`]($figure.caption.side-width).
+    /// - A dictionary: The position depends on the figure's kind. The keys are
+    ///   kinds, i.e. the names of element functions like `table` and `image`
+    ///   or the names of custom kinds. Figures whose kind is not in the
+    ///   dictionary have their caption at the bottom.
+    ///
+    /// ```example
+    /// #set figure.caption(position: (table: top, image: bottom))
+    ///
+    /// #figure(
+    ///   table(columns: 2)[A][B],
+    ///   caption: [I'm up here],
+    /// )
+    /// ```
+    ///
+    /// The position can also be set for specific figures with a show-set
+    /// rule:
     ///
     /// ```example
     /// #show figure.where(
@@ -511,8 +635,23 @@ pub struct FigureCaption {
     ///   )
     /// )
     /// ```
-    #[default(OuterVAlignment::Bottom)]
-    pub position: OuterVAlignment,
+    #[default(CaptionPosition::Uniform(CaptionSide::Bottom))]
+    pub position: CaptionPosition,
+
+    /// The width of the caption's column when it is placed to the
+    /// [side]($figure.caption.position) of the body. The body takes the
+    /// remaining width.
+    ///
+    /// ```example
+    /// #set figure.caption(position: end, side-width: 40%)
+    ///
+    /// #figure(
+    ///   rect(width: 100%)[Body],
+    ///   caption: [A caption in the margin of the body],
+    /// )
+    /// ```
+    #[default(Ratio::new(0.3).into())]
+    pub side_width: Rel<Length>,
 
     /// The separator which will appear between the number and body.
     ///
@@ -569,6 +708,11 @@ pub struct FigureCaption {
     #[internal]
     #[synthesized]
     pub figure_location: Option<Location>,
+
+    /// Whether the caption is emitted as a table's `<caption>` in HTML export.
+    #[internal]
+    #[synthesized]
+    pub in_table: bool,
 }
 
 impl FigureCaption {
@@ -607,17 +751,36 @@ impl Show for Packed<FigureCaption> {
         let mut realized = self.body().clone();
 
         if let (
+            Some(kind),
             Some(Some(mut supplement)),
             Some(Some(numbering)),
             Some(Some(counter)),
             Some(Some(location)),
         ) = (
+            self.kind(),
             self.supplement().cloned(),
             self.numbering(),
             self.counter(),
             self.figure_location(),
         ) {
-            let numbers = counter.display_at_loc(engine, *location, styles, numbering)?;
+            let css = TargetElem::target_in(styles).is_html()
+                && DocumentElem::html_numbering_in(styles) == HtmlNumbering::Css;
+            let css_pattern = if css {
+                css_numbering_pattern(engine, numbering, self.span())
+            } else {
+                None
+            };
+
+            // With a CSS counter, the number is filled into an empty span by
+            // the stylesheet.
+            let numbers = match css_pattern {
+                Some(pattern) => HtmlElem::new(tag::span)
+                    .with_attr(attr::data_counter, html_counter(kind))
+                    .with_attr(attr::data_numbering_pattern, pattern.pattern())
+                    .pack()
+                    .spanned(self.span()),
+                None => counter.display_at_loc(engine, *location, styles, numbering)?,
+            };
             if !supplement.is_empty() {
                 supplement += TextElem::packed('\u{a0}');
             }
@@ -625,12 +788,28 @@ impl Show for Packed<FigureCaption> {
         }
 
         if TargetElem::target_in(styles).is_html() {
-            return Ok(HtmlElem::new(tag::figcaption)
+            let tag = if self.in_table() == Some(&true) {
+                tag::caption
+            } else {
+                tag::figcaption
+            };
+            return Ok(HtmlElem::new(tag)
                 .with_body(Some(realized))
                 .pack()
                 .spanned(self.span()));
         }
 
+        if engine.world.library().features.is_enabled(Feature::A11yExtras) {
+            // The block only carries the role, so it must not add spacing
+            // around the caption.
+            let mut block = BlockElem::new()
+                .with_body(Some(BlockBody::Content(realized)))
+                .with_above(Smart::Custom(Abs::zero().into()))
+                .with_below(Smart::Custom(Abs::zero().into()));
+            block.push_role(FrameRole::Caption);
+            realized = block.pack().spanned(self.span());
+        }
+
         Ok(realized)
     }
 }
@@ -649,6 +828,15 @@ pub enum FigureKind {
     Name(EcoString),
 }
 
+/// The value of the `data-counter` attribute with which figures of the given
+/// kind and their numbers are marked for CSS counters in HTML export.
+fn html_counter(kind: &FigureKind) -> EcoString {
+    match kind {
+        FigureKind::Elem(elem) => eco_format!("figure-{}", elem.name()),
+        FigureKind::Name(name) => eco_format!("figure-{}", slugify(name, "anchor")),
+    }
+}
+
 cast! {
     FigureKind,
     self => match self {
@@ -659,6 +847,84 @@ cast! {
     v: EcoString => Self::Name(v),
 }
 
+/// Where a figure's caption is placed, possibly depending on the figure's kind.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum CaptionPosition {
+    /// The caption is placed on the same side for all kinds of figures.
+    Uniform(CaptionSide),
+    /// The caption's side depends on the figure's kind.
+    PerKind(Vec<(Str, CaptionSide)>),
+}
+
+impl CaptionPosition {
+    /// Resolve the side of the caption for a figure of the given kind.
+    pub fn resolve(&self, kind: Option<FigureKind>) -> CaptionSide {
+        match self {
+            Self::Uniform(side) => *side,
+            Self::PerKind(sides) => {
+                let name = match &kind {
+                    Some(FigureKind::Elem(elem)) => elem.name(),
+                    Some(FigureKind::Name(name)) => name.as_str(),
+                    None => return CaptionSide::Bottom,
+                };
+                sides
+                    .iter()
+                    .find(|(key, _)| key.as_str() == name)
+                    .map_or(CaptionSide::Bottom, |&(_, side)| side)
+            }
+        }
+    }
+}
+
+cast! {
+    CaptionPosition,
+    self => match self {
+        Self::Uniform(side) => side.into_value(),
+        Self::PerKind(sides) => sides
+            .into_iter()
+            .map(|(key, side)| (key, side.into_value()))
+            .collect::<Dict>()
+            .into_value(),
+    },
+    side: CaptionSide => Self::Uniform(side),
+    sides: Dict => Self::PerKind(
+        sides
+            .into_iter()
+            .map(|(key, value)| Ok((key, value.cast()?)))
+            .collect::<HintedStrResult<_>>()?,
+    ),
+}
+
+/// A side of a figure's body on which its caption can be placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CaptionSide {
+    /// Above the body.
+    Top,
+    /// Below the body.
+    Bottom,
+    /// Next to the body, where text starts.
+    Start,
+    /// Next to the body, where text ends.
+    End,
+}
+
+cast! {
+    CaptionSide,
+    self => match self {
+        Self::Top => Alignment::TOP,
+        Self::Bottom => Alignment::BOTTOM,
+        Self::Start => Alignment::START,
+        Self::End => Alignment::END,
+    }.into_value(),
+    align: Alignment => match align {
+        Alignment::V(VAlignment::Top) => Self::Top,
+        Alignment::V(VAlignment::Bottom) => Self::Bottom,
+        Alignment::H(HAlignment::Start) => Self::Start,
+        Alignment::H(HAlignment::End) => Self::End,
+        v => bail!("expected `top`, `bottom`, `start`, or `end`, found {}", v.repr()),
+    },
+}
+
 /// An element that can be auto-detected in a figure.
 ///
 /// This trait is used to determine the type of a figure.
@@ -1,13 +1,14 @@
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
+use comemo::Track;
 use typst_utils::NonZeroExt;
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, Label, NativeElement, Packed, Show, ShowSet, Smart,
-    StyleChain, Styles,
+    cast, elem, scope, Cast, Content, Context, Label, NativeElement, Packed, Show,
+    ShowSet, Smart, StyleChain, Styles,
 };
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
 use crate::layout::{Abs, Em, HElem, Length, Ratio};
@@ -56,9 +57,11 @@ pub struct FootnoteElem {
     /// How to number footnotes.
     ///
     /// By default, the footnote numbering continues throughout your document.
-    /// If you prefer per-page footnote numbering, you can reset the footnote
-    /// [counter] in the page [header]($page.header). In the future, there might
-    /// be a simpler way to achieve this.
+    /// If you prefer per-page footnote numbering, you can set the
+    /// [`restart`]($footnote.restart) parameter to `{"page"}`.
+    ///
+    /// Symbol numbering cycles through `*`, `†`, `‡`, `§`, `¶`, and `‖` and
+    /// then continues with doubled symbols like `**`.
     ///
     /// ```example
     /// #set footnote(numbering: "*")
@@ -71,6 +74,27 @@ pub struct FootnoteElem {
     #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
     pub numbering: Numbering,
 
+    /// When to restart the footnote numbering.
+    ///
+    /// - `{"none"}`: The numbering continues throughout the document.
+    /// - `{"page"}`: The numbering starts over on each page, based on the page
+    ///   that the footnote ends up on after layout.
+    ///
+    /// This only affects how footnotes are numbered. The footnote [counter]
+    /// still counts all footnotes in the document.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #set footnote(numbering: "*", restart: "page")
+    ///
+    /// One#footnote[First] and
+    /// two#footnote[Second].
+    /// #pagebreak()
+    /// Three#footnote[Third].
+    /// ```
+    #[default(FootnoteRestart::None)]
+    pub restart: FootnoteRestart,
+
     /// The content to put into the footnote. Can also be the label of another
     /// footnote this one should point to.
     #[required]
@@ -142,8 +166,8 @@ impl Show for Packed<FootnoteElem> {
         let span = self.span();
         let loc = self.declaration_location(engine).at(span)?;
         let numbering = self.numbering(styles);
-        let counter = Counter::of(FootnoteElem::elem());
-        let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        let restart = self.restart(styles);
+        let num = display_number(engine, loc, styles, numbering, restart)?;
         let sup = SuperElem::new(num).pack().spanned(span);
         let loc = loc.variant(1);
         // Add zero-width weak spacing to make the footnote "sticky".
@@ -157,6 +181,46 @@ impl Count for Packed<FootnoteElem> {
     }
 }
 
+/// When to restart the numbering of footnotes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FootnoteRestart {
+    /// Number footnotes continuously throughout the document.
+    None,
+    /// Start over on each page.
+    Page,
+}
+
+/// Displays the number of the footnote declared at the given location.
+fn display_number(
+    engine: &mut Engine,
+    loc: Location,
+    styles: StyleChain,
+    numbering: &Numbering,
+    restart: FootnoteRestart,
+) -> SourceResult<Content> {
+    if restart == FootnoteRestart::None {
+        let counter = Counter::of(FootnoteElem::elem());
+        return counter.display_at_loc(engine, loc, styles, numbering);
+    }
+
+    // Count the footnote declarations on the same page up to this one.
+    let page = engine.introspector.page(loc);
+    let mut number = 0;
+    for elem in engine.introspector.query(&FootnoteElem::elem().select()) {
+        let note = elem.to_packed::<FootnoteElem>().unwrap();
+        let Some(other) = note.location() else { continue };
+        if !note.is_ref() && engine.introspector.page(other) == page {
+            number += 1;
+        }
+        if other == loc {
+            break;
+        }
+    }
+
+    let context = Context::new(Some(loc), Some(styles));
+    Ok(numbering.apply(engine, context.track(), &[number.max(1)])?.display())
+}
+
 /// The body of a footnote can be either some content or a label referencing
 /// another footnote.
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -215,6 +279,10 @@ pub struct FootnoteEntry {
 
     /// The separator between the document body and the footnote listing.
     ///
+    /// Its length and thickness can be adjusted by passing a custom
+    /// [`line`]. If set to `{none}`, there is no separator, but the
+    /// [`clearance`]($footnote.entry.clearance) is still kept.
+    ///
     /// ```example
     /// #set footnote.entry(
     ///   separator: repeat[.]
@@ -234,8 +302,9 @@ pub struct FootnoteEntry {
                 ..Default::default()
             })
             .pack()
+            .into()
     )]
-    pub separator: Content,
+    pub separator: Option<Content>,
 
     /// The amount of clearance between the document body and the separator.
     ///
@@ -275,6 +344,24 @@ pub struct FootnoteEntry {
     /// ```
     #[default(Em::new(1.0).into())]
     pub indent: Length,
+
+    /// Content that is shown when an entry is too long for a page and
+    /// continues on the next page.
+    ///
+    /// The indicator is placed on the next page, below the separator and
+    /// above the continued part of the entry. It is laid out like an entry of
+    /// its own, with the gap between them. The continued part itself has no
+    /// number.
+    ///
+    /// ```example
+    /// #set page(height: 100pt)
+    /// #set footnote.entry(
+    ///   continuation-indicator: emph[(continued)],
+    /// )
+    ///
+    /// Long#footnote[#lorem(30)]
+    /// ```
+    pub continuation_indicator: Option<Content>,
 }
 
 impl Show for Packed<FootnoteEntry> {
@@ -285,7 +372,7 @@ impl Show for Packed<FootnoteEntry> {
         let number_gap = Em::new(0.05);
         let default = StyleChain::default();
         let numbering = note.numbering(default);
-        let counter = Counter::of(FootnoteElem::elem());
+        let restart = note.restart(default);
         let Some(loc) = note.location() else {
             bail!(
                 span, "footnote entry must have a location";
@@ -293,7 +380,7 @@ impl Show for Packed<FootnoteEntry> {
             );
         };
 
-        let num = counter.display_at_loc(engine, loc, styles, numbering)?;
+        let num = display_number(engine, loc, styles, numbering, restart)?;
         let sup = SuperElem::new(num)
             .pack()
             .spanned(span)
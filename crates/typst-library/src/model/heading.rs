@@ -1,21 +1,31 @@
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use std::sync::Arc;
 
+use comemo::{Track, Tracked};
 use ecow::eco_format;
-use typst_utils::NonZeroExt;
+use typst_utils::{NonZeroExt, PicoStr};
 
-use crate::diag::{warning, SourceResult};
+use crate::diag::{bail, warning, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Content, NativeElement, Packed, Resolve, Show, ShowSet, Smart, StyleChain,
-    Styles, Synthesize, TargetElem,
+    cast, elem, Content, Context, Func, Label, NativeElement, Packed, Resolve, Selector,
+    Show, ShowSet, Smart, StyleChain, Styles, Synthesize, TargetElem, Value,
 };
 use crate::html::{attr, tag, HtmlElem};
 use crate::introspection::{
-    Count, Counter, CounterUpdate, Locatable, Locator, LocatorLink,
+    Count, Counter, CounterUpdate, Introspector, Locatable, Location, Locator,
+    LocatorLink,
+};
+use crate::layout::{
+    Abs, Axes, BlockBody, BlockElem, Em, FrameRole, HElem, Length, Region,
+};
+use crate::model::{
+    css_numbering_pattern, slugify, suffixed, DocumentElem, HtmlNumbering, Numbering,
+    Outlinable, ParElem, Refable, Supplement,
 };
-use crate::layout::{Abs, Axes, BlockBody, BlockElem, Em, HElem, Length, Region};
-use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
 use crate::text::{FontWeight, LocalName, SpaceElem, TextElem, TextSize};
+use crate::{Feature, World};
 
 /// A section heading.
 ///
@@ -180,9 +190,48 @@ pub struct HeadingElem {
     #[default(Smart::Auto)]
     pub hanging_indent: Smart<Length>,
 
+    /// Whether and how to generate labels for headings that don't have one.
+    ///
+    /// - When set to `{false}`, headings are only labelled explicitly. This is
+    ///   the default.
+    /// - When set to `{true}`, a label is derived from the heading's title:
+    ///   Letters, including non-Latin ones, are lowercased and kept, as are
+    ///   digits. Every run of other characters turns into a single hyphen. For
+    ///   example, `[= Getting Started!]` is labelled `<getting-started>`.
+    /// - When set to a function, it is called with the heading and should
+    ///   return a [label], a [string]($str) to turn into a label, or `{none}`
+    ///   to leave the heading unlabelled.
+    ///
+    /// If several headings end up with the same label, the second one gets the
+    /// suffix `-2`, the third one `-3`, and so on, in document order. Labels
+    /// that are already attached to something else in the document are
+    /// skipped. A heading with an explicit label always keeps it.
+    ///
+    /// Generated labels are real labels: They can be [referenced]($ref),
+    /// [linked]($link) to, and [queried]($query). In HTML export, they also
+    /// become the `id` of the heading. However, show rules that select a label
+    /// only apply to explicit labels.
+    ///
+    /// ```example
+    /// #set heading(numbering: "1.", auto-label: true)
+    ///
+    /// = Introduction
+    /// We start in @getting-started.
+    ///
+    /// = Getting Started
+    /// See @introduction for the goals.
+    /// ```
+    pub auto_label: HeadingAutoLabel,
+
     /// The heading's title.
     #[required]
     pub body: Content,
+
+    /// The label that a generated label is based on, before any suffix was
+    /// added to make it unique.
+    #[internal]
+    #[synthesized]
+    pub label_base: Label,
 }
 
 impl HeadingElem {
@@ -211,6 +260,15 @@ impl Synthesize for Packed<HeadingElem> {
         let elem = self.as_mut();
         elem.push_level(Smart::Custom(elem.resolve_level(styles)));
         elem.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
+
+        if self.label().is_none() {
+            if let Some(base) = generate_label(self, engine, styles)? {
+                let label = unique_label(engine, self.location(), base);
+                self.as_mut().push_label_base(base);
+                self.set_label(label);
+            }
+        }
+
         Ok(())
     }
 }
@@ -231,7 +289,15 @@ impl Show for Packed<HeadingElem> {
             Smart::Auto => Abs::zero(),
         };
 
-        if let Some(numbering) = (**self).numbering(styles).as_ref() {
+        // In HTML, the number may be left to CSS counters instead.
+        let numbering = (**self).numbering(styles).as_ref();
+        let css = html && DocumentElem::html_numbering_in(styles) == HtmlNumbering::Css;
+        let css_pattern = match numbering {
+            Some(numbering) if css => css_numbering_pattern(engine, numbering, span),
+            _ => None,
+        };
+
+        if let Some(numbering) = numbering.filter(|_| css_pattern.is_none()) {
             let location = self.location().unwrap();
             let numbering = Counter::of(HeadingElem::elem())
                 .display_at_loc(engine, location, styles, numbering)?
@@ -274,7 +340,7 @@ impl Show for Packed<HeadingElem> {
             // reason, levels are offset by one: A Typst level 1 heading becomes
             // a `<h2>`.
             let level = self.resolve_level(styles).get();
-            if level >= 6 {
+            let mut elem = if level >= 6 {
                 engine.sink.warn(warning!(span,
                     "heading of level {} was transformed to \
                     <div role=\"heading\" aria-level=\"{}\">, which is not \
@@ -287,19 +353,157 @@ impl Show for Packed<HeadingElem> {
                     .with_body(Some(realized))
                     .with_attr(attr::role, "heading")
                     .with_attr(attr::aria_level, eco_format!("{}", level + 1))
-                    .pack()
-                    .spanned(span)
             } else {
                 let t = [tag::h2, tag::h3, tag::h4, tag::h5, tag::h6][level - 1];
-                HtmlElem::new(t).with_body(Some(realized)).pack().spanned(span)
+                let mut elem = HtmlElem::new(t).with_body(Some(realized));
+                if let Some(label) = self.label() {
+                    let naming = DocumentElem::anchors_in(styles);
+                    let anchor = engine.introspector.anchor_name(label, naming);
+                    elem = elem.with_attr(attr::id, anchor);
+                }
+                elem
+            };
+
+            // Numbered headings are counted by CSS even if their number is
+            // written as text, so that the following numbers are right.
+            if css && numbering.is_some() {
+                elem = elem
+                    .with_attr(attr::data_counter, "heading")
+                    .with_attr(attr::data_level, eco_format!("{level}"));
+            }
+
+            if let Some(pattern) = css_pattern {
+                elem = elem.with_attr(attr::data_numbering_pattern, pattern.pattern());
             }
+
+            elem.pack().spanned(span)
         } else {
             let realized = BlockBody::Content(realized);
-            BlockElem::new().with_body(Some(realized)).pack().spanned(span)
+            let mut block = BlockElem::new().with_body(Some(realized));
+            if engine.world.library().features.is_enabled(Feature::A11yExtras) {
+                block.push_role(FrameRole::Heading(self.resolve_level(styles)));
+            }
+            block.pack().spanned(span)
         })
     }
 }
 
+/// How to generate labels for headings without an explicit label.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub enum HeadingAutoLabel {
+    /// Don't generate labels.
+    #[default]
+    Off,
+    /// Derive the label from the heading's title.
+    Slug,
+    /// Call a function with the heading to get the label.
+    Func(Func),
+}
+
+cast! {
+    HeadingAutoLabel,
+    self => match self {
+        Self::Off => false.into_value(),
+        Self::Slug => true.into_value(),
+        Self::Func(func) => func.into_value(),
+    },
+    v: bool => if v { Self::Slug } else { Self::Off },
+    v: Func => Self::Func(v),
+}
+
+/// Generates the label for a heading without an explicit label, before it is
+/// made unique.
+fn generate_label(
+    heading: &Packed<HeadingElem>,
+    engine: &mut Engine,
+    styles: StyleChain,
+) -> SourceResult<Option<Label>> {
+    let name = match heading.auto_label(styles) {
+        HeadingAutoLabel::Off => return Ok(None),
+        HeadingAutoLabel::Slug => slugify(&heading.body().plain_text(), "heading"),
+        HeadingAutoLabel::Func(func) => {
+            let context = Context::new(heading.location(), Some(styles));
+            match func.call(engine, context.track(), [heading.clone().pack()])? {
+                Value::None => return Ok(None),
+                Value::Label(label) => return Ok(Some(label)),
+                Value::Str(name) if !name.is_empty() => name.into(),
+                v => bail!(
+                    heading.span(),
+                    "expected label, non-empty string, or none, found {}",
+                    v.ty(),
+                ),
+            }
+        }
+    };
+
+    Ok(Some(Label::new(PicoStr::intern(&name))))
+}
+
+/// Makes a generated label unique by adding a numeric suffix if necessary.
+///
+/// The n-th heading in document order whose generated label has the given
+/// `base` receives the n-th of the candidates `base`, `base-2`, `base-3`, and
+/// so on that is not already taken by an explicit label elsewhere in the
+/// document. This only depends on the previous layout iteration, so the
+/// labels settle like other introspected values.
+fn unique_label(engine: &Engine, location: Option<Location>, base: Label) -> Label {
+    let introspector = engine.introspector;
+    let index = GeneratedLabels::of(introspector);
+
+    // A heading that was not there in the previous iteration counts as coming
+    // after all others.
+    let same_base = index.bases.get(&base).map_or(&[][..], Vec::as_slice);
+    let earlier = match location.and_then(|loc| index.positions.get(&loc)) {
+        Some(&position) => same_base.partition_point(|&p| p < position),
+        None => same_base.len(),
+    };
+
+    let taken = |label: Label| {
+        introspector.query(&Selector::Label(label)).iter().any(|elem| {
+            elem.to_packed::<HeadingElem>()
+                .map_or(true, |heading| heading.label_base().is_none())
+        })
+    };
+
+    suffixed(base.resolve().as_str())
+        .map(|name| Label::new(PicoStr::intern(&name)))
+        .filter(|&label| !taken(label))
+        .nth(earlier)
+        .unwrap()
+}
+
+/// The headings of a document and the bases of their generated labels.
+struct GeneratedLabels {
+    /// The position of each heading among all headings in document order.
+    positions: HashMap<Location, usize>,
+    /// The positions of the headings with a generated label, by its base.
+    bases: HashMap<Label, Vec<usize>>,
+}
+
+impl GeneratedLabels {
+    /// Index the headings found by the introspector.
+    ///
+    /// This is memoized so that the index is built once per layout iteration
+    /// instead of once per heading.
+    #[comemo::memoize]
+    fn of(introspector: Tracked<Introspector>) -> Arc<GeneratedLabels> {
+        let mut positions = HashMap::new();
+        let mut bases = HashMap::<Label, Vec<usize>>::new();
+        for (i, elem) in
+            introspector.query(&HeadingElem::elem().select()).iter().enumerate()
+        {
+            if let Some(location) = elem.location() {
+                positions.insert(location, i);
+            }
+            let heading = elem.to_packed::<HeadingElem>().unwrap();
+            if let Some(&base) = heading.label_base() {
+                bases.entry(base).or_default().push(i);
+            }
+        }
+        Arc::new(GeneratedLabels { positions, bases })
+    }
+}
+
 impl ShowSet for Packed<HeadingElem> {
     fn show_set(&self, styles: StyleChain) -> Styles {
         let level = (**self).resolve_level(styles).get();
@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::{eco_format, EcoString};
+use typst_utils::NonZeroExt;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, scope, Content, NativeElement, Packed, Show, ShowSet, Smart, StyleChain, Styles,
+};
+use crate::introspection::{Counter, CounterKey, Locatable, Location};
+use crate::layout::{Em, HElem};
+use crate::model::{
+    Destination, EmphElem, FirstLineIndent, HeadingElem, NumberingPattern, ParElem,
+    ParbreakElem,
+};
+use crate::text::{Collation, LinebreakElem, LocalName, TextElem};
+
+/// A back-of-book index.
+///
+/// The index lists all terms that were marked with
+/// [`index.entry`]($index.entry) throughout the document, together with the
+/// pages on which they appear. Terms are sorted alphabetically according to
+/// the [text language]($text.lang), sub-entries are listed below their term,
+/// and consecutive pages are collapsed into ranges.
+///
+/// # Example
+/// ```example
+/// #set page(height: 120pt)
+///
+/// Cats #index.entry("Cat") purr.
+/// #index.entry("Cat", sub: "Tabby")
+/// #index.see("Kitten", "Cat")
+///
+/// #pagebreak()
+/// More cats #index.entry("Cat").
+///
+/// #pagebreak()
+/// Dogs #index.entry("Dog") bark.
+///
+/// #index()
+/// ```
+///
+/// # Styling the index
+/// Each line of the index is an [`index.row`]($index.row) element, which can
+/// be customized with a show rule.
+#[elem(scope, Show, ShowSet, LocalName)]
+pub struct IndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    pub title: Smart<Option<Content>>,
+}
+
+#[scope]
+impl IndexElem {
+    #[elem]
+    type IndexEntry;
+
+    #[elem]
+    type IndexSee;
+
+    #[elem]
+    type IndexRow;
+}
+
+impl Show for Packed<IndexElem> {
+    #[typst_macros::time(name = "index", span = self.span())]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::shared().clone()];
+        if let Some(title) = self.title(styles).unwrap_or_else(|| {
+            Some(TextElem::packed(Self::local_name_in(styles)).spanned(self.span()))
+        }) {
+            seq.push(
+                HeadingElem::new(title)
+                    .with_depth(NonZeroUsize::ONE)
+                    .pack()
+                    .spanned(self.span()),
+            );
+        }
+
+        // Group the marked hits by term and sub-entry.
+        let mut terms: BTreeMap<EcoString, Term> = BTreeMap::new();
+        for elem in engine.introspector.query(&IndexEntry::elem().select()) {
+            let entry = elem.to_packed::<IndexEntry>().unwrap();
+            let location = elem.location().unwrap();
+            let page = Counter::new(CounterKey::Page).at_loc(engine, location)?.first();
+            let hit = Hit { page, location };
+            let term = terms.entry(entry.term().clone()).or_default();
+            match entry.sub(styles) {
+                Some(sub) => term.subs.entry(sub).or_default().push(hit),
+                None => term.hits.push(hit),
+            }
+        }
+
+        for elem in engine.introspector.query(&IndexSee::elem().select()) {
+            let see = elem.to_packed::<IndexSee>().unwrap();
+            let term = terms.entry(see.term().clone()).or_default();
+            if !term.see.contains(see.target()) {
+                term.see.push(see.target().clone());
+            }
+        }
+
+        let collation = Collation::Locale(TextElem::lang_in(styles));
+        let mut terms: Vec<_> = terms.into_iter().collect();
+        terms.sort_by(|(a, _), (b, _)| collation.compare(a, b));
+
+        let see_word = Packed::<IndexSee>::local_name_in(styles);
+        for (name, term) in terms {
+            let pages = format_pages(engine, &term.hits, styles)?;
+            let see = (!term.see.is_empty()).then(|| {
+                let mut see = term.see.clone();
+                see.sort_by(|a, b| collation.compare(a, b));
+                Content::sequence([
+                    EmphElem::new(TextElem::packed(see_word)).pack(),
+                    TextElem::packed(eco_format!(" {}", see.join("; "))),
+                ])
+            });
+
+            let row =
+                IndexRow::new(NonZeroUsize::ONE, TextElem::packed(name), pages, see);
+            seq.push(row.pack().spanned(self.span()));
+            seq.push(LinebreakElem::shared().clone());
+
+            let mut subs: Vec<_> = term.subs.into_iter().collect();
+            subs.sort_by(|(a, _), (b, _)| collation.compare(a, b));
+            for (sub, hits) in subs {
+                let pages = format_pages(engine, &hits, styles)?;
+                let row = IndexRow::new(
+                    NonZeroUsize::new(2).unwrap(),
+                    TextElem::packed(sub),
+                    pages,
+                    None,
+                );
+                seq.push(row.pack().spanned(self.span()));
+                seq.push(LinebreakElem::shared().clone());
+            }
+        }
+
+        seq.push(ParbreakElem::shared().clone());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ShowSet for Packed<IndexElem> {
+    fn show_set(&self, _: StyleChain) -> Styles {
+        let mut out = Styles::new();
+        out.set(HeadingElem::set_outlined(false));
+        out.set(HeadingElem::set_numbering(None));
+        out.set(ParElem::set_first_line_indent(FirstLineIndent::default()));
+        out
+    }
+}
+
+impl LocalName for Packed<IndexElem> {
+    const KEY: &'static str = "index";
+}
+
+/// Marks a term for inclusion in the [index]($index).
+///
+/// The marker is invisible. The index lists the page on which it appears
+/// under the given term, or under the given sub-entry of the term.
+///
+/// ```example
+/// Typst #index.entry("Typst") is a
+/// markup-based typesetting system.
+/// #index.entry("Typst", sub: "markup")
+///
+/// #index(title: none)
+/// ```
+#[elem(name = "entry", title = "Index Entry", Locatable, Show)]
+pub struct IndexEntry {
+    /// The term under which the marked page is listed.
+    #[required]
+    pub term: EcoString,
+
+    /// A sub-entry of the term under which the marked page is listed instead.
+    pub sub: Option<EcoString>,
+}
+
+impl Show for Packed<IndexEntry> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+/// Refers from one term in the [index]($index) to another.
+///
+/// The cross-reference is listed without page numbers. Like an
+/// [`index.entry`]($index.entry), it is invisible in the place where it
+/// appears.
+///
+/// ```example
+/// #index.see("Kitten", "Cat")
+/// Cats #index.entry("Cat") purr.
+///
+/// #index(title: none)
+/// ```
+#[elem(name = "see", title = "Index Cross-Reference", Locatable, Show, LocalName)]
+pub struct IndexSee {
+    /// The term that refers to another one.
+    #[required]
+    pub term: EcoString,
+
+    /// The term that is referred to.
+    #[required]
+    pub target: EcoString,
+}
+
+impl Show for Packed<IndexSee> {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+impl LocalName for Packed<IndexSee> {
+    const KEY: &'static str = "see";
+}
+
+/// A line in the [index]($index), for a term or one of its sub-entries.
+///
+/// The index creates one row per term and sub-entry. Rows can be customized
+/// with a show rule:
+///
+/// ```example
+/// #show index.row.where(level: 1): strong
+///
+/// Cats #index.entry("Cat") purr.
+/// #index.entry("Cat", sub: "Tabby")
+///
+/// #index(title: none)
+/// ```
+#[elem(name = "row", title = "Index Row", Show)]
+pub struct IndexRow {
+    /// The nesting level of this row. Starts at `{1}` for terms and is `{2}`
+    /// for sub-entries.
+    #[required]
+    pub level: NonZeroUsize,
+
+    /// The term or sub-entry.
+    #[required]
+    pub term: Content,
+
+    /// The pages on which the term appears, formatted with the numbering of
+    /// each page and linked to the marked locations. Consecutive pages are
+    /// collapsed into ranges. This is `{none}` for terms that only refer to
+    /// other terms.
+    #[required]
+    pub pages: Option<Content>,
+
+    /// The terms that this term refers to, preceded by the word "see" in the
+    /// [text language]($text.lang).
+    #[required]
+    pub see: Option<Content>,
+}
+
+impl Show for Packed<IndexRow> {
+    #[typst_macros::time(name = "index.row", span = self.span())]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![];
+        let indent = self.level().get() - 1;
+        if indent > 0 {
+            seq.push(HElem::new(Em::new(indent as f64).into()).pack());
+        }
+
+        seq.push(self.term().clone());
+        if let Some(pages) = self.pages() {
+            seq.push(TextElem::packed(", "));
+            seq.push(pages.clone());
+        }
+
+        if let Some(see) = self.see() {
+            seq.push(TextElem::packed(if self.pages().is_some() { "; " } else { ", " }));
+            seq.push(see.clone());
+        }
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+/// The hits and cross-references of a term.
+#[derive(Default)]
+struct Term {
+    hits: Vec<Hit>,
+    subs: BTreeMap<EcoString, Vec<Hit>>,
+    see: Vec<EcoString>,
+}
+
+/// A marked location and the value of the page counter there.
+#[derive(Copy, Clone)]
+struct Hit {
+    page: usize,
+    location: Location,
+}
+
+/// Format the pages of a term's hits, collapsing consecutive pages into
+/// ranges and repeated hits on one page into a single number.
+fn format_pages(
+    engine: &mut Engine,
+    hits: &[Hit],
+    styles: StyleChain,
+) -> SourceResult<Option<Content>> {
+    let mut runs: Vec<(Hit, Hit)> = vec![];
+    for &hit in hits {
+        match runs.last_mut() {
+            Some((_, end)) if hit.page == end.page => {}
+            Some((_, end)) if hit.page == end.page + 1 => *end = hit,
+            _ => runs.push((hit, hit)),
+        }
+    }
+
+    if runs.is_empty() {
+        return Ok(None);
+    }
+
+    let mut seq = vec![];
+    for (i, (start, end)) in runs.into_iter().enumerate() {
+        if i > 0 {
+            seq.push(TextElem::packed(", "));
+        }
+        seq.push(page_number(engine, start.location, styles)?);
+        if end.page != start.page {
+            seq.push(TextElem::packed("\u{2013}"));
+            seq.push(page_number(engine, end.location, styles)?);
+        }
+    }
+
+    Ok(Some(Content::sequence(seq)))
+}
+
+/// The page number at a location, formatted with the page's numbering and
+/// linked to the location.
+fn page_number(
+    engine: &mut Engine,
+    location: Location,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    let numbering = engine
+        .introspector
+        .page_numbering(location)
+        .cloned()
+        .unwrap_or_else(|| NumberingPattern::from_str("1").unwrap().into());
+    Ok(Counter::new(CounterKey::Page)
+        .display_at_loc(engine, location, styles, &numbering)?
+        .linked(Destination::Location(location)))
+}
@@ -1,17 +1,20 @@
+use std::num::NonZeroUsize;
 use std::ops::Deref;
 
 use ecow::{eco_format, EcoString};
+use serde::Serialize;
 use smallvec::SmallVec;
+use typst_syntax::Span;
 
 use crate::diag::{bail, warning, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Content, Label, NativeElement, Packed, Repr, Show, Smart, StyleChain,
-    TargetElem,
+    cast, elem, Cast, Content, Label, NativeElement, Packed, Repr, Selector, Show, Smart,
+    StyleChain, Synthesize, TargetElem,
 };
 use crate::html::{attr, tag, HtmlElem};
-use crate::introspection::Location;
-use crate::layout::Position;
+use crate::introspection::{Introspector, Locatable, Location};
+use crate::layout::{Frame, FrameItem, Point, Position, Size, Transform};
 use crate::text::{Hyphenate, TextElem};
 
 /// Links to a URL or a location in the document.
@@ -31,10 +34,18 @@ use crate::text::{Hyphenate, TextElem};
 /// ]
 /// ```
 ///
+/// To style links differently depending on where they lead, you can match on
+/// the [`dest-kind`]($link.dest-kind) and [`same-page`]($link.same-page)
+/// fields. [References]($ref) also produce links and can thus be styled in the
+/// same way.
+///
+/// All links, including those produced by references, can be
+/// [queried]($query).
+///
 /// # Syntax
 /// This function also has dedicated syntax: Text that starts with `http://` or
 /// `https://` is automatically turned into a link.
-#[elem(Show)]
+#[elem(Show, Locatable, Synthesize)]
 pub struct LinkElem {
     /// The destination the link points to.
     ///
@@ -85,6 +96,43 @@ pub struct LinkElem {
     })]
     pub body: Content,
 
+    /// What kind of destination the link points to.
+    ///
+    /// This is determined automatically from the destination: Links to labels
+    /// and locations have the kind `{"location"}`, links to a page and
+    /// coordinates have the kind `{"position"}`, and all other links have the
+    /// kind `{"url"}`.
+    ///
+    /// ```example
+    /// #show link.where(dest-kind: "url"): underline
+    ///
+    /// = Introduction <intro>
+    /// #link("https://typst.app")[Typst] \
+    /// #link(<intro>)[Introduction]
+    /// ```
+    #[synthesized]
+    pub dest_kind: LinkDestKind,
+
+    /// Whether the link points to the page it is on.
+    ///
+    /// This is `{none}` for links to URLs and for links whose target could not
+    /// be found.
+    ///
+    /// Since it depends on where the link and its target end up, this is
+    /// resolved through introspection: In the first layout pass, it may not yet
+    /// be accurate and Typst lays the document out again until it stabilizes.
+    /// Styling links differently depending on this field should thus not affect
+    /// which page they end up on. Otherwise, the layout may not converge.
+    ///
+    /// ```example
+    /// #show link.where(same-page: false): underline
+    ///
+    /// = Introduction <intro>
+    /// See the #link(<intro>)[introduction].
+    /// ```
+    #[synthesized]
+    pub same_page: Option<bool>,
+
     /// This style is set on the content contained in the `link` element.
     #[internal]
     #[ghost]
@@ -99,6 +147,35 @@ impl LinkElem {
     }
 }
 
+impl Synthesize for Packed<LinkElem> {
+    fn synthesize(&mut self, engine: &mut Engine, _: StyleChain) -> SourceResult<()> {
+        let introspector = engine.introspector;
+        let (kind, page) = match self.dest() {
+            LinkTarget::Dest(Destination::Url(_)) => (LinkDestKind::Url, None),
+            LinkTarget::Dest(Destination::Position(pos)) => {
+                (LinkDestKind::Position, Some(pos.page))
+            }
+            LinkTarget::Dest(Destination::Location(loc)) => {
+                (LinkDestKind::Location, Some(introspector.page(*loc)))
+            }
+            LinkTarget::Label(label) => (
+                LinkDestKind::Location,
+                introspector
+                    .query_label(*label)
+                    .ok()
+                    .and_then(|elem| elem.location())
+                    .map(|loc| introspector.page(loc)),
+            ),
+        };
+
+        let here = self.location().map(|loc| introspector.page(loc));
+        let elem = self.as_mut();
+        elem.push_dest_kind(kind);
+        elem.push_same_page(here.zip(page).map(|(here, page)| here == page));
+        Ok(())
+    }
+}
+
 impl Show for Packed<LinkElem> {
     #[typst_macros::time(name = "link", span = self.span())]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
@@ -106,18 +183,31 @@ impl Show for Packed<LinkElem> {
         let dest = self.dest();
 
         Ok(if TargetElem::target_in(styles).is_html() {
-            if let LinkTarget::Dest(Destination::Url(url)) = dest {
-                HtmlElem::new(tag::a)
-                    .with_attr(attr::href, url.clone().into_inner())
+            let href = match dest {
+                LinkTarget::Dest(Destination::Url(url)) => Some(url.clone().into_inner()),
+                LinkTarget::Dest(Destination::Position(_)) => {
+                    engine.sink.warn(warning!(
+                        self.span(),
+                        "links to positions are not supported by HTML export"
+                    ));
+                    None
+                }
+                LinkTarget::Dest(Destination::Location(loc)) => {
+                    html_fragment(engine, *loc, self.span())
+                }
+                LinkTarget::Label(label) => {
+                    let elem = engine.introspector.query_label(*label).at(self.span())?;
+                    html_fragment(engine, elem.location().unwrap(), self.span())
+                }
+            };
+
+            match href {
+                Some(href) => HtmlElem::new(tag::a)
+                    .with_attr(attr::href, href)
                     .with_body(Some(body))
                     .pack()
-                    .spanned(self.span())
-            } else {
-                engine.sink.warn(warning!(
-                    self.span(),
-                    "non-URL links are not yet supported by HTML export"
-                ));
-                body
+                    .spanned(self.span()),
+                None => body,
             }
         } else {
             let linked = match self.dest() {
@@ -134,6 +224,22 @@ impl Show for Packed<LinkElem> {
     }
 }
 
+/// The URL fragment under which the element at the location can be reached in
+/// an HTML document, i.e. the id of the element or else of the nearest one
+/// that precedes or contains it.
+fn html_fragment(engine: &mut Engine, loc: Location, span: Span) -> Option<EcoString> {
+    let fragment = engine.introspector.anchor(loc).map(|id| eco_format!("#{id}"));
+    if fragment.is_none() {
+        engine.sink.warn(warning!(
+            span,
+            "link destination has no id in HTML export";
+            hint: "links go to the nearest labelled heading or HTML element \
+                   with an `id` before the destination",
+        ));
+    }
+    fragment
+}
+
 fn body_from_url(url: &Url) -> Content {
     let mut text = url.as_str();
     for prefix in ["mailto:", "tel:"] {
@@ -166,6 +272,17 @@ impl From<Destination> for LinkTarget {
     }
 }
 
+/// The kind of destination a link points to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LinkDestKind {
+    /// A URL.
+    Url,
+    /// A location in the document, e.g. specified through a label.
+    Location,
+    /// A position on a page.
+    Position,
+}
+
 /// A link destination.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Destination {
@@ -230,3 +347,147 @@ cast! {
     self => self.0.into_value(),
     v: EcoString => Self::new(v)?,
 }
+
+/// A clickable link area in exported output.
+///
+/// This is meant for exporters without native link support (like raster
+/// images) so that consumers can build an interactive overlay on top of the
+/// output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkRegion {
+    /// The corners of the link area in output coordinates, starting with the
+    /// top-left corner of the untransformed area and continuing clockwise.
+    ///
+    /// If the link is rotated or skewed, this is not an axis-aligned
+    /// rectangle.
+    pub quad: [[f64; 2]; 4],
+    /// The axis-aligned bounding box of the quad as `[x, y, width, height]`.
+    pub rect: [f64; 4],
+    /// Where the link points to.
+    pub dest: LinkRegionDest,
+}
+
+/// The destination of a [`LinkRegion`] in a serializable form.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum LinkRegionDest {
+    /// A link to a URL.
+    Url { url: EcoString },
+    /// A link to a point on a page, in points from the page's top-left
+    /// corner. Pages are counted from one.
+    Position { page: NonZeroUsize, x: f64, y: f64 },
+    /// A link to an element in the document.
+    ///
+    /// The anchor is the element's label if it has one and an identifier
+    /// derived from its location otherwise.
+    Anchor { anchor: EcoString, page: NonZeroUsize, x: f64, y: f64 },
+    /// A link to a location that could not be resolved because no
+    /// introspector was provided.
+    Unresolved,
+}
+
+impl LinkRegionDest {
+    /// Convert a link destination, resolving locations with the introspector
+    /// if one is given.
+    pub fn new(dest: &Destination, introspector: Option<&Introspector>) -> Self {
+        match dest {
+            Destination::Url(url) => Self::Url { url: url.clone().into_inner() },
+            Destination::Position(pos) => Self::Position {
+                page: pos.page,
+                x: pos.point.x.to_pt(),
+                y: pos.point.y.to_pt(),
+            },
+            Destination::Location(loc) => {
+                let Some(introspector) = introspector else { return Self::Unresolved };
+                let anchor = introspector
+                    .query_first(&Selector::Location(*loc))
+                    .and_then(|elem| elem.label())
+                    .map(|label| label.resolve().as_str().into())
+                    .unwrap_or_else(|| eco_format!("loc-{:032x}", loc.hash()));
+                let Some(pos) = introspector.position(*loc) else {
+                    return Self::Unresolved;
+                };
+                Self::Anchor {
+                    anchor,
+                    page: pos.page,
+                    x: pos.point.x.to_pt(),
+                    y: pos.point.y.to_pt(),
+                }
+            }
+        }
+    }
+}
+
+/// Collect the link regions of a frame.
+///
+/// The transform maps from frame coordinates to output coordinates and the
+/// transforms of all nested groups are applied on top of it.
+pub fn link_regions(
+    frame: &Frame,
+    ts: Transform,
+    introspector: Option<&Introspector>,
+) -> Vec<LinkRegion> {
+    let mut regions = vec![];
+    collect_link_regions(frame, ts, introspector, &mut regions);
+    regions
+}
+
+/// Recursively collect link regions into `regions`.
+fn collect_link_regions(
+    frame: &Frame,
+    ts: Transform,
+    introspector: Option<&Introspector>,
+    regions: &mut Vec<LinkRegion>,
+) {
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts.pre_concat(group.transform);
+                collect_link_regions(&group.frame, ts, introspector, regions);
+            }
+            FrameItem::Link(dest, size) => {
+                regions.push(LinkRegion::new(dest, *size, ts, introspector));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl LinkRegion {
+    /// Create a region for a link area of the given size, placed at the
+    /// origin of the transform.
+    fn new(
+        dest: &Destination,
+        size: Size,
+        ts: Transform,
+        introspector: Option<&Introspector>,
+    ) -> Self {
+        let corners = [
+            Point::zero(),
+            Point::with_x(size.x),
+            size.to_point(),
+            Point::with_y(size.y),
+        ];
+
+        let quad = corners.map(|corner| {
+            let p = corner.transform(ts);
+            [p.x.to_pt(), p.y.to_pt()]
+        });
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for [x, y] in quad {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        Self {
+            quad,
+            rect: [min_x, min_y, max_x - min_x, max_y - min_y],
+            dest: LinkRegionDest::new(dest, introspector),
+        }
+    }
+}
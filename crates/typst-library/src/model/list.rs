@@ -110,7 +110,8 @@ pub struct ListElem {
     ///
     /// If set to `{auto}`, uses paragraph [`leading`]($par.leading) for tight
     /// lists and paragraph [`spacing`]($par.spacing) for wide (non-tight)
-    /// lists.
+    /// lists. Items that consist of multiple paragraphs are always separated
+    /// by paragraph spacing, even in tight lists.
     pub spacing: Smart<Length>,
 
     /// The bullet list's children.
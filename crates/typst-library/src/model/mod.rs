@@ -1,5 +1,7 @@
 //! Structuring elements that define the document model.
 
+#[path = "anchor.rs"]
+mod anchor_;
 mod bibliography;
 mod cite;
 mod document;
@@ -9,6 +11,7 @@ mod enum_;
 mod figure;
 mod footnote;
 mod heading;
+mod index;
 mod link;
 mod list;
 #[path = "numbering.rs"]
@@ -21,6 +24,7 @@ mod strong;
 mod table;
 mod terms;
 
+pub use self::anchor_::*;
 pub use self::bibliography::*;
 pub use self::cite::*;
 pub use self::document::*;
@@ -29,6 +33,7 @@ pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::list::*;
 pub use self::numbering_::*;
@@ -57,6 +62,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<RefElem>();
     global.define_elem::<LinkElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<IndexElem>();
     global.define_elem::<HeadingElem>();
     global.define_elem::<FigureElem>();
     global.define_elem::<FootnoteElem>();
@@ -67,9 +73,11 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<ListElem>();
     global.define_elem::<ParbreakElem>();
     global.define_elem::<ParElem>();
+    global.define_elem::<DropCapElem>();
     global.define_elem::<TableElem>();
     global.define_elem::<TermsElem>();
     global.define_elem::<EmphElem>();
     global.define_elem::<StrongElem>();
     global.define_func::<numbering>();
+    global.define_func::<anchor>();
 }
@@ -5,10 +5,11 @@ use chinese_number::{
 };
 use comemo::Tracked;
 use ecow::{eco_format, EcoString, EcoVec};
+use typst_syntax::Span;
 
-use crate::diag::SourceResult;
+use crate::diag::{warning, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{cast, func, Context, Func, Str, Value};
+use crate::foundations::{cast, func, Context, Func, Repr, Str, Value};
 use crate::text::Case;
 
 /// Applies a numbering to a sequence of numbers.
@@ -122,6 +123,46 @@ impl Numbering {
         }
         self
     }
+
+    /// The pattern with which CSS counters can display this numbering, if
+    /// there is one.
+    pub fn css_pattern(&self) -> Option<&NumberingPattern> {
+        match self {
+            Self::Pattern(pattern) if pattern.is_css_compatible() => Some(pattern),
+            _ => None,
+        }
+    }
+}
+
+/// Determines the pattern with which CSS counters display a numbering in HTML
+/// export.
+///
+/// Emits a warning if the numbering cannot be displayed with CSS counters.
+/// The caller is then expected to write the number as text instead.
+pub(crate) fn css_numbering_pattern(
+    engine: &mut Engine,
+    numbering: &Numbering,
+    span: Span,
+) -> Option<NumberingPattern> {
+    if let Some(pattern) = numbering.css_pattern() {
+        return Some(pattern.clone());
+    }
+
+    engine.sink.warn(match numbering {
+        Numbering::Pattern(pattern) => warning!(
+            span,
+            "numbering pattern {} cannot be displayed with CSS counters",
+            pattern.pattern().repr();
+            hint: "only the counting symbols 1, a, A, i, and I are supported";
+            hint: "the number was written as text instead"
+        ),
+        Numbering::Func(_) => warning!(
+            span,
+            "numbering functions cannot be displayed with CSS counters";
+            hint: "the number was written as text instead"
+        ),
+    });
+    None
 }
 
 impl From<NumberingPattern> for Numbering {
@@ -160,30 +201,70 @@ impl NumberingPattern {
     /// Apply the pattern to the given number.
     pub fn apply(&self, numbers: &[usize]) -> EcoString {
         let mut fmt = EcoString::new();
-        let mut numbers = numbers.iter();
+        self.apply_with(
+            &mut fmt,
+            numbers.len(),
+            |fmt, text| fmt.push_str(text),
+            |fmt, i, kind| fmt.push_str(&kind.apply(numbers[i])),
+        );
+        fmt
+    }
 
-        for (i, ((prefix, kind), &n)) in self.pieces.iter().zip(&mut numbers).enumerate()
-        {
+    /// Walk through the pattern as if applying it to `count` numbers.
+    ///
+    /// Calls `text` with each piece of verbatim text and `number` with the
+    /// index and kind of each number, in order. This is what [`apply`]
+    /// builds on, but it also serves to express the pattern in other forms,
+    /// like CSS counters.
+    ///
+    /// [`apply`]: Self::apply
+    pub fn apply_with<T>(
+        &self,
+        acc: &mut T,
+        count: usize,
+        mut text: impl FnMut(&mut T, &str),
+        mut number: impl FnMut(&mut T, usize, NumberingKind),
+    ) {
+        let direct = count.min(self.pieces.len());
+        for (i, (prefix, kind)) in self.pieces.iter().take(direct).enumerate() {
             if i > 0 || !self.trimmed {
-                fmt.push_str(prefix);
+                text(acc, prefix);
             }
-            fmt.push_str(&kind.apply(n));
+            number(acc, i, *kind);
         }
 
-        for ((prefix, kind), &n) in self.pieces.last().into_iter().cycle().zip(numbers) {
-            if prefix.is_empty() {
-                fmt.push_str(&self.suffix);
-            } else {
-                fmt.push_str(prefix);
+        if let Some((prefix, kind)) = self.pieces.last() {
+            for i in direct..count {
+                if prefix.is_empty() {
+                    text(acc, &self.suffix);
+                } else {
+                    text(acc, prefix);
+                }
+                number(acc, i, *kind);
             }
-            fmt.push_str(&kind.apply(n));
         }
 
         if !self.trimmed {
-            fmt.push_str(&self.suffix);
+            text(acc, &self.suffix);
         }
+    }
 
-        fmt
+    /// Whether all counting symbols of the pattern can be expressed with CSS
+    /// counter styles.
+    pub fn is_css_compatible(&self) -> bool {
+        !self.trimmed
+            && self.pieces.iter().all(|(_, kind)| kind.css_counter_style().is_some())
+    }
+
+    /// The pattern as it would be written in Typst.
+    pub fn pattern(&self) -> EcoString {
+        let mut pat = EcoString::new();
+        for (prefix, kind) in &self.pieces {
+            pat.push_str(prefix);
+            pat.push(kind.to_char());
+        }
+        pat.push_str(&self.suffix);
+        pat
     }
 
     /// Apply only the k-th segment of the pattern to a number.
@@ -238,15 +319,7 @@ impl FromStr for NumberingPattern {
 
 cast! {
     NumberingPattern,
-    self => {
-        let mut pat = EcoString::new();
-        for (prefix, kind) in &self.pieces {
-            pat.push_str(prefix);
-            pat.push(kind.to_char());
-        }
-        pat.push_str(&self.suffix);
-        pat.into_value()
-    },
+    self => self.pattern().into_value(),
     v: Str => v.parse()?,
 }
 
@@ -380,6 +453,19 @@ impl NumberingKind {
         }
     }
 
+    /// The predefined CSS counter style that displays numbers the same way as
+    /// this kind, if there is one.
+    pub fn css_counter_style(self) -> Option<&'static str> {
+        Some(match self {
+            Self::Arabic => "decimal",
+            Self::LowerLatin => "lower-alpha",
+            Self::UpperLatin => "upper-alpha",
+            Self::LowerRoman => "lower-roman",
+            Self::UpperRoman => "upper-roman",
+            _ => return None,
+        })
+    }
+
     /// Apply the numbering to the given number.
     pub fn apply(self, n: usize) -> EcoString {
         match self {
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
@@ -11,12 +12,16 @@ use crate::foundations::{
     cast, elem, scope, select_where, Content, Context, Func, LocatableSelector,
     NativeElement, Packed, Show, ShowSet, Smart, StyleChain, Styles,
 };
-use crate::introspection::{Counter, CounterKey, Locatable};
+use crate::introspection::{
+    Counter, CounterKey, Locatable, Location, Locator, LocatorLink,
+};
 use crate::layout::{
-    BoxElem, Dir, Em, Fr, HElem, HideElem, Length, Rel, RepeatElem, Spacing,
+    Abs, Alignment, Axes, BoxElem, Dir, Fr, HElem, Length, Region, Rel, RepeatElem,
+    Spacing,
 };
 use crate::model::{
-    Destination, HeadingElem, NumberingPattern, ParElem, ParbreakElem, Refable,
+    Destination, FirstLineIndent, HeadingElem, NumberingPattern, ParElem, ParbreakElem,
+    Refable,
 };
 use crate::text::{LinebreakElem, LocalName, SpaceElem, TextElem};
 
@@ -123,8 +128,13 @@ pub struct OutlineElem {
     ///
     /// - `{none}`: No indent
     /// - `{auto}`: Indents the numbering of the nested entry with the title of
-    ///   its parent entry. This only has an effect if the entries are numbered
-    ///   (e.g., via [heading numbering]($heading.numbering)).
+    ///   its parent entry. The numberings of each level are laid out in a
+    ///   column as wide as the widest numbering of that level, so that the
+    ///   titles of all entries of a level start at the same position, even if
+    ///   their numberings have different widths (like `I` and `III`). This
+    ///   only has an effect if the entries are numbered (e.g., via
+    ///   [heading numbering]($heading.numbering)). In right-to-left text, the
+    ///   indent is mirrored.
     /// - [Relative length]($relative): Indents the item by this length
     ///   multiplied by its nesting level. Specifying `{2em}`, for instance,
     ///   would indent top-level headings (not nested) by `{0em}`, second level
@@ -170,13 +180,47 @@ pub struct OutlineElem {
     /// Content to fill the space between the title and the page number. Can be
     /// set to `{none}` to disable filling.
     ///
+    /// - [Content]($content): Used as the leader for all entries.
+    /// - [Array]($array): Specifies the leader for each nesting level, starting
+    ///   with top-level entries. Each item may be content or `{none}`. Levels
+    ///   beyond the end of the array use its last item.
+    /// - [Function]($function): A function that receives the entry's level
+    ///   (starting at 1 for top-level entries) and returns content or `{none}`.
+    ///
     /// ```example
     /// #outline(fill: line(length: 100%))
     ///
     /// = A New Beginning
     /// ```
-    #[default(Some(RepeatElem::new(TextElem::packed(".")).pack()))]
-    pub fill: Option<Content>,
+    ///
+    /// ```example
+    /// #show outline.entry.where(level: 1): strong
+    /// #outline(fill: (none, repeat[.]))
+    ///
+    /// = Introduction
+    /// == Motivation
+    /// == Approach
+    /// ```
+    #[default(Some(OutlineFill::Content(RepeatElem::new(TextElem::packed(".")).pack())))]
+    #[borrowed]
+    pub fill: Option<OutlineFill>,
+
+    /// The width of the column reserved for page numbers.
+    ///
+    /// - `{auto}`: Page numbers take up as much space as they need. Leaders
+    ///   stop right before them, so they end at different positions for
+    ///   page numbers of different widths.
+    /// - [Length]($length): Page numbers are right-aligned in a column of this
+    ///   width, so that leaders of all entries end at the same position.
+    ///
+    /// ```example
+    /// #outline(number-width: 1.5em)
+    ///
+    /// = Short
+    /// #counter(page).update(99)
+    /// = Long
+    /// ```
+    pub number_width: Smart<Length>,
 }
 
 #[scope]
@@ -204,9 +248,8 @@ impl Show for Packed<OutlineElem> {
         let indent = self.indent(styles);
         let depth = self.depth(styles).unwrap_or(NonZeroUsize::new(usize::MAX).unwrap());
 
-        let mut ancestors: Vec<&Content> = vec![];
         let elems = engine.introspector.query(&self.target(styles).0);
-
+        let mut entries = vec![];
         for elem in &elems {
             let Some(entry) = OutlineEntry::from_outlinable(
                 engine,
@@ -219,11 +262,26 @@ impl Show for Packed<OutlineElem> {
                 continue;
             };
 
-            let level = entry.level();
-            if depth < *level {
+            if depth < *entry.level() {
                 continue;
             }
 
+            entries.push((elem, entry.with_number_width(self.number_width(styles))));
+        }
+
+        let widths = match indent {
+            Some(Smart::Auto) => NumberingWidths::measure(
+                engine,
+                entries.iter().map(|&(elem, _)| elem),
+                styles,
+            )?,
+            _ => NumberingWidths::default(),
+        };
+
+        let mut ancestors: Vec<&Content> = vec![];
+        for (elem, entry) in entries {
+            let level = entry.level();
+
             // Deals with the ancestors of the current element.
             // This is only applicable for elements with a hierarchy/level.
             while ancestors
@@ -238,6 +296,8 @@ impl Show for Packed<OutlineElem> {
                 indent,
                 engine,
                 &ancestors,
+                elem,
+                &widths,
                 &mut seq,
                 styles,
                 self.span(),
@@ -261,7 +321,7 @@ impl ShowSet for Packed<OutlineElem> {
         let mut out = Styles::new();
         out.set(HeadingElem::set_outlined(false));
         out.set(HeadingElem::set_numbering(None));
-        out.set(ParElem::set_first_line_indent(Em::new(0.0).into()));
+        out.set(ParElem::set_first_line_indent(FirstLineIndent::default()));
         out
     }
 }
@@ -295,10 +355,13 @@ pub enum OutlineIndent {
 }
 
 impl OutlineIndent {
+    #[allow(clippy::too_many_arguments)]
     fn apply(
         indent: &Option<Smart<Self>>,
         engine: &mut Engine,
         ancestors: &Vec<&Content>,
+        elem: &Content,
+        widths: &NumberingWidths,
         seq: &mut Vec<Content>,
         styles: StyleChain,
         span: Span,
@@ -309,26 +372,27 @@ impl OutlineIndent {
 
             // 'auto' | 'true' => use numbering alignment for indenting
             Some(Smart::Auto) => {
-                // Add hidden ancestors numberings to realize the indent.
-                let mut hidden = Content::empty();
+                // Leave room for the numbering of each numbered ancestor,
+                // followed by the space that separates it from its title.
                 for ancestor in ancestors {
-                    let ancestor_outlinable = ancestor.with::<dyn Outlinable>().unwrap();
-
-                    if let Some(numbering) = ancestor_outlinable.numbering() {
-                        let numbers = ancestor_outlinable.counter().display_at_loc(
-                            engine,
-                            ancestor.location().unwrap(),
-                            styles,
-                            numbering,
-                        )?;
-
-                        hidden += numbers + SpaceElem::shared().clone();
-                    };
+                    let outlinable = ancestor.with::<dyn Outlinable>().unwrap();
+                    if outlinable.numbering().is_some() {
+                        let width = widths.level(outlinable.level());
+                        seq.push(
+                            BoxElem::new().with_width(width.into()).pack().spanned(span),
+                        );
+                        seq.push(SpaceElem::shared().clone().spanned(span));
+                    }
                 }
 
-                if !ancestors.is_empty() {
-                    seq.push(HideElem::new(hidden).pack().spanned(span));
-                    seq.push(SpaceElem::shared().clone().spanned(span));
+                // Pad the numbering of the entry itself to the width of the
+                // widest numbering of its level, so that the titles align.
+                if let Some(width) = widths.get(elem) {
+                    let level = elem.with::<dyn Outlinable>().unwrap().level();
+                    let padding = widths.level(level) - width;
+                    if padding > Abs::zero() {
+                        seq.push(HElem::new(padding.into()).pack().spanned(span));
+                    }
                 }
             }
 
@@ -360,6 +424,71 @@ impl OutlineIndent {
     }
 }
 
+/// The measured widths of the numberings of an outline's entries, used for
+/// `{auto}` indentation.
+#[derive(Debug, Default)]
+struct NumberingWidths {
+    /// The width of each numbered entry's numbering.
+    entries: HashMap<Location, Abs>,
+    /// The width of the widest numbering on each level, starting with the
+    /// top-level.
+    levels: Vec<Abs>,
+}
+
+impl NumberingWidths {
+    /// Lays out the numbering of each of the outlined elements to determine
+    /// its width.
+    fn measure<'a>(
+        engine: &mut Engine,
+        elems: impl IntoIterator<Item = &'a Content>,
+        styles: StyleChain,
+    ) -> SourceResult<Self> {
+        let mut widths = Self::default();
+        let pod = Region::new(Axes::splat(Abs::inf()), Axes::splat(false));
+
+        for elem in elems {
+            let outlinable = elem.with::<dyn Outlinable>().unwrap();
+            let Some(numbering) = outlinable.numbering() else { continue };
+
+            let location = elem.location().unwrap();
+            let numbers = outlinable
+                .counter()
+                .display_at_loc(engine, location, styles, numbering)?;
+
+            // The numbering is not part of the document, so we use the
+            // measurement infrastructure.
+            let link = LocatorLink::measure(location);
+            let width = (engine.routines.layout_frame)(
+                engine,
+                &numbers,
+                Locator::link(&link),
+                styles,
+                pod,
+            )?
+            .width();
+
+            let level = outlinable.level().get();
+            if widths.levels.len() < level {
+                widths.levels.resize(level, Abs::zero());
+            }
+            widths.levels[level - 1].set_max(width);
+            widths.entries.insert(location, width);
+        }
+
+        Ok(widths)
+    }
+
+    /// The width of an element's numbering, if it has one.
+    fn get(&self, elem: &Content) -> Option<Abs> {
+        self.entries.get(&elem.location()?).copied()
+    }
+
+    /// The width of the widest numbering on the given level.
+    fn level(&self, level: NonZeroUsize) -> Abs {
+        self.levels.get(level.get() - 1).copied().unwrap_or_default()
+    }
+}
+
 cast! {
     OutlineIndent,
     self => match self {
@@ -370,6 +499,49 @@ cast! {
     v: Func => OutlineIndent::Func(v),
 }
 
+/// Defines the leaders between an outline entry's body and its page number.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum OutlineFill {
+    Content(Content),
+    Levels(Vec<Option<Content>>),
+    Func(Func),
+}
+
+impl OutlineFill {
+    /// Resolve the leader for an entry at the given nesting level.
+    fn resolve(
+        fill: &Option<Self>,
+        engine: &mut Engine,
+        level: NonZeroUsize,
+        styles: StyleChain,
+        span: Span,
+    ) -> SourceResult<Option<Content>> {
+        Ok(match fill {
+            None => None,
+            Some(Self::Content(content)) => Some(content.clone()),
+            Some(Self::Levels(levels)) => {
+                levels.get(level.get() - 1).or(levels.last()).cloned().flatten()
+            }
+            Some(Self::Func(func)) => func
+                .call(engine, Context::new(None, Some(styles)).track(), [level])?
+                .cast()
+                .at(span)?,
+        })
+    }
+}
+
+cast! {
+    OutlineFill,
+    self => match self {
+        Self::Content(v) => v.into_value(),
+        Self::Levels(v) => v.into_value(),
+        Self::Func(v) => v.into_value(),
+    },
+    v: Content => Self::Content(v),
+    v: Vec<Option<Content>> => Self::Levels(v),
+    v: Func => Self::Func(v),
+}
+
 struct LengthOrContent(Content);
 
 cast! {
@@ -383,8 +555,10 @@ cast! {
 ///
 /// This element is intended for use with show rules to control the appearance
 /// of outlines. To customize an entry's line, you can build it from scratch by
-/// accessing the `level`, `element`, `body`, `fill` and `page` fields on the
-/// entry.
+/// accessing the `level`, `element`, `body`, `fill`, `page` and `number-width`
+/// fields on the entry. However, to only adjust the styling of an entry, it is
+/// usually easier to wrap the default appearance, as in the example below. This
+/// keeps the leaders, page number alignment and indent intact.
 ///
 /// ```example
 /// #set heading(numbering: "1.")
@@ -426,7 +600,8 @@ pub struct OutlineEntry {
 
     /// The content used to fill the space between the element's outline and
     /// its page number, as defined by the outline element this entry is
-    /// located in. When `{none}`, empty space is inserted in that gap instead.
+    /// located in (resolved for the entry's level). When `{none}`, empty space
+    /// is inserted in that gap instead.
     ///
     /// Note that, when using show rules to override outline entries, it is
     /// recommended to wrap the filling content in a [`box`] with fractional
@@ -439,6 +614,11 @@ pub struct OutlineEntry {
     /// numbering set for the referenced page.
     #[required]
     pub page: Content,
+
+    /// The width of the column the page number is right-aligned in, as defined
+    /// by the outline element this entry is located in. When `{auto}`, the
+    /// page number takes up as much space as it needs.
+    pub number_width: Smart<Length>,
 }
 
 impl OutlineEntry {
@@ -450,7 +630,7 @@ impl OutlineEntry {
         engine: &mut Engine,
         span: Span,
         elem: Content,
-        fill: Option<Content>,
+        fill: &Option<OutlineFill>,
         styles: StyleChain,
     ) -> SourceResult<Option<Self>> {
         let Some(outlinable) = elem.with::<dyn Outlinable>() else {
@@ -475,7 +655,10 @@ impl OutlineEntry {
             &page_numbering,
         )?;
 
-        Ok(Some(Self::new(outlinable.level(), elem, body, fill, page)))
+        let level = outlinable.level();
+        let fill = OutlineFill::resolve(fill, engine, level, styles, span)?;
+
+        Ok(Some(Self::new(level, elem, body, fill, page)))
     }
 }
 
@@ -534,8 +717,15 @@ impl Show for Packed<OutlineEntry> {
             seq.push(HElem::new(Fr::one().into()).pack().spanned(self.span()));
         }
 
-        // Add the page number.
-        let page = self.page().clone().linked(Destination::Location(location));
+        // Add the page number, right-aligned in its column if requested.
+        let mut page = self.page().clone().linked(Destination::Location(location));
+        if let Smart::Custom(width) = self.number_width(styles) {
+            page = BoxElem::new()
+                .with_body(Some(page.aligned(Alignment::END)))
+                .with_width(width.into())
+                .pack()
+                .spanned(self.span());
+        }
         seq.push(page);
 
         Ok(Content::sequence(seq))
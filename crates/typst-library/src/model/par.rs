@@ -1,12 +1,14 @@
 use std::fmt::{self, Debug, Formatter};
+use std::num::NonZeroUsize;
 
+use ecow::EcoString;
 use typst_utils::singleton;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, scope, Args, Cast, Construct, Content, NativeElement, Packed, Set, Smart,
-    StyleVec, Unlabellable,
+    cast, dict, elem, scope, Args, Cast, Construct, Content, Dict, Element,
+    NativeElement, Packed, SequenceElem, Set, Smart, StyleVec, StyledElem, Unlabellable,
 };
 use crate::introspection::{Count, CounterUpdate, Locatable};
 use crate::layout::{Em, HAlignment, Length, OuterHAlignment};
@@ -85,6 +87,23 @@ pub struct ParElem {
     #[default(false)]
     pub justify: bool,
 
+    /// Whether to also justify the last line of a justified paragraph and
+    /// lines before a [forced line break]($linebreak).
+    ///
+    /// This only has an effect if [`justify`]($par.justify) is enabled. Some
+    /// typographic traditions, for instance in Chinese, justify final lines
+    /// flush with both edges.
+    ///
+    /// ```example
+    /// #set page(width: 160pt)
+    /// #set par(justify: true, justify-last: true)
+    /// This paragraph's last line
+    /// is stretched across the full width.
+    /// ```
+    #[ghost]
+    #[default(false)]
+    pub justify_last: bool,
+
     /// How to determine line breaks.
     ///
     /// When this property is set to `{auto}`, its default value, optimized line
@@ -92,6 +111,14 @@ pub struct ParElem {
     /// breaks for ragged paragraphs may also be worthwhile to improve the
     /// appearance of the text.
     ///
+    /// Optimized line breaking considers many combinations of line breaks and
+    /// thus gets slow for very long paragraphs. Justified paragraphs with more
+    /// than 5000 break opportunities (roughly, words and hyphenation points)
+    /// therefore use simple line breaks when this property is `{auto}` and
+    /// Typst emits a warning. Explicitly set this property to `{"optimized"}` to
+    /// optimize them anyway. An explicit `{"simple"}` is always honored, also
+    /// for justified paragraphs.
+    ///
     /// ```example
     /// #set page(width: 207pt)
     /// #set par(linebreaks: "simple")
@@ -111,15 +138,47 @@ pub struct ParElem {
 
     /// The indent the first line of a paragraph should have.
     ///
-    /// Only the first line of a consecutive paragraph will be indented (not
-    /// the first one in a block or on the page).
+    /// By default, only the first line of a consecutive paragraph will be
+    /// indented (not the first one in a block or on the page, and not one
+    /// following a heading, figure, or other block-level element).
     ///
     /// By typographic convention, paragraph breaks are indicated either by some
     /// space between paragraphs or by indented first lines. Consider reducing
     /// the [paragraph spacing]($block.spacing) to the [`leading`]($par.leading)
     /// when using this property (e.g. using `[#set par(spacing: 0.65em)]`).
+    ///
+    /// Instead of just a length, you can also pass a dictionary with the
+    /// following keys to control which paragraphs are indented:
+    /// - `amount`: The indent amount. Defaults to `{0pt}`.
+    /// - `all`: Whether to indent paragraphs that aren't directly preceded by
+    ///   another paragraph. Defaults to `{false}`.
+    /// - `after`: A dictionary that maps element names to whether paragraphs
+    ///   directly following such an element should be indented. Elements that
+    ///   aren't mentioned follow the `all` setting, while paragraphs are
+    ///   always indented after other paragraphs unless `par` is listed.
+    ///
+    /// The preceding element is the one that was visible in the document. If a
+    /// show rule transforms a heading into a block, a following paragraph
+    /// still counts as following a heading.
+    ///
+    /// ```example
+    /// #set par(spacing: 0.65em, first-line-indent: (
+    ///   amount: 1em,
+    ///   after: (list: true),
+    /// ))
+    ///
+    /// = Heading
+    /// Not indented, as it follows a
+    /// heading.
+    ///
+    /// Indented, as it follows another
+    /// paragraph.
+    ///
+    /// - A list
+    /// Indented, as configured.
+    /// ```
     #[ghost]
-    pub first_line_indent: Length,
+    pub first_line_indent: FirstLineIndent,
 
     /// The indent all but the first line of a paragraph should have.
     #[ghost]
@@ -135,6 +194,22 @@ pub struct ParElem {
     #[internal]
     #[variadic]
     pub children: StyleVec,
+
+    /// The kind of visible element that directly precedes the paragraph in
+    /// its container, if any.
+    #[internal]
+    #[synthesized]
+    pub predecessor: Option<Element>,
+
+    /// Whether paragraphs receive a location like locatable elements, so
+    /// that they can be found in the laid-out document.
+    ///
+    /// This is not the case by default because locating every paragraph has
+    /// some overhead. It is set when compiling with
+    /// `CompileOptions::locate_paragraphs`.
+    #[internal]
+    #[ghost]
+    pub located: bool,
 }
 
 #[scope]
@@ -165,6 +240,71 @@ impl Debug for ParElem {
     }
 }
 
+/// Configures which paragraphs have an indented first line.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct FirstLineIndent {
+    /// The indent amount.
+    pub amount: Length,
+    /// Whether to indent paragraphs that aren't preceded by another one.
+    pub all: bool,
+    /// Overrides for paragraphs preceded by specific elements, keyed by the
+    /// elements' names.
+    pub after: Vec<(EcoString, bool)>,
+}
+
+impl FirstLineIndent {
+    /// Whether a paragraph directly preceded by the given element (or by
+    /// nothing) should be indented.
+    pub fn applies_after(&self, predecessor: Option<Element>) -> bool {
+        let Some(elem) = predecessor else { return self.all };
+        self.after
+            .iter()
+            .find(|(name, _)| name == elem.name())
+            .map(|&(_, indent)| indent)
+            .unwrap_or(self.all || elem == ParElem::elem())
+    }
+}
+
+impl From<Length> for FirstLineIndent {
+    fn from(amount: Length) -> Self {
+        Self { amount, ..Default::default() }
+    }
+}
+
+cast! {
+    FirstLineIndent,
+    self => dict![
+        "amount" => self.amount,
+        "all" => self.all,
+        "after" => self
+            .after
+            .into_iter()
+            .map(|(name, indent)| (name.into(), indent.into_value()))
+            .collect::<Dict>(),
+    ].into_value(),
+    amount: Length => amount.into(),
+    mut v: Dict => {
+        let after = v
+            .take("after")
+            .ok()
+            .map(|v| v.cast::<Dict>())
+            .transpose()?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, indent)| Ok((EcoString::from(name), indent.cast::<bool>()?)))
+            .collect::<HintedStrResult<Vec<_>>>()?;
+        let amount = v.take("amount").ok().map(|v| v.cast()).transpose()?;
+        let all = v.take("all").ok().map(|v| v.cast()).transpose()?;
+        let ret = Self {
+            amount: amount.unwrap_or_default(),
+            all: all.unwrap_or_default(),
+            after,
+        };
+        v.finish(&["amount", "all", "after"])?;
+        ret
+    },
+}
+
 /// How to determine line breaks in a paragraph.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Linebreaks {
@@ -203,10 +343,67 @@ impl ParbreakElem {
     pub fn shared() -> &'static Content {
         singleton!(Content, ParbreakElem::new().pack())
     }
+
+    /// Whether the content contains a paragraph break outside of nested
+    /// elements, that is, whether it consists of multiple paragraphs.
+    pub fn contained_in(content: &Content) -> bool {
+        if content.is::<Self>() {
+            true
+        } else if let Some(sequence) = content.to_packed::<SequenceElem>() {
+            sequence.children.iter().any(Self::contained_in)
+        } else if let Some(styled) = content.to_packed::<StyledElem>() {
+            Self::contained_in(&styled.child)
+        } else {
+            false
+        }
+    }
 }
 
 impl Unlabellable for Packed<ParbreakElem> {}
 
+/// A large initial at the start of a paragraph that spans multiple lines.
+///
+/// The drop cap is placed in the start corner of the paragraph and the
+/// paragraph's first lines wrap around it. Once the drop cap ends, the lines
+/// return to the full measure. The drop cap must be the first thing in its
+/// paragraph.
+///
+/// # Example
+/// ```example
+/// #dropcap(text(size: 3em)[T])ypst
+/// is a new markup-based typesetting
+/// system that is designed to be as
+/// powerful as LaTeX while being much
+/// easier to learn and use.
+/// ```
+#[elem(name = "dropcap", title = "Drop Cap")]
+pub struct DropCapElem {
+    /// How many lines wrap around the drop cap.
+    ///
+    /// When set to `{auto}`, the number of lines is derived from the measured
+    /// height of the drop cap such that its baseline lines up with the
+    /// baseline of the last wrapping line.
+    ///
+    /// ```example
+    /// #dropcap(lines: 2, scale(
+    ///   x: 200%, y: 200%, reflow: true,
+    /// )[A])
+    /// drop cap that spans exactly
+    /// two lines, no matter its size.
+    /// ```
+    pub lines: Smart<NonZeroUsize>,
+
+    /// The horizontal gap between the drop cap and the lines wrapping around
+    /// it.
+    #[resolve]
+    #[default(Em::new(0.5).into())]
+    pub gap: Length,
+
+    /// The drop cap's content, typically a single enlarged letter.
+    #[required]
+    pub body: Content,
+}
+
 /// A paragraph line.
 ///
 /// This element is exclusively used for line number configuration through set
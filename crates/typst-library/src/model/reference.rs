@@ -1,16 +1,17 @@
 use comemo::Track;
 use ecow::eco_format;
+use typst_syntax::Spanned;
 
 use crate::diag::{bail, At, Hint, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Cast, Content, Context, Func, IntoValue, Label, NativeElement, Packed,
-    Show, Smart, StyleChain, Synthesize,
+    cast, elem, Arg, Args, Cast, Content, Context, Func, IntoValue, Label, NativeElement,
+    Packed, Show, Smart, StyleChain, Synthesize,
 };
 use crate::introspection::{Counter, CounterKey, Locatable};
 use crate::math::EquationElem;
 use crate::model::{
-    BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, Numbering,
+    BibliographyElem, CiteElem, Destination, Figurable, FootnoteElem, LinkElem, Numbering,
 };
 use crate::text::TextElem;
 
@@ -22,8 +23,9 @@ use crate::text::TextElem;
 /// The default, a `{"normal"}` reference, produces a textual reference to a
 /// label. For example, a reference to a heading will yield an appropriate
 /// string such as "Section 1" for a reference to the first heading. The
-/// references are also links to the respective element. Reference syntax can
-/// also be used to [cite] from a bibliography.
+/// references are also [links]($link) to the respective element and can be
+/// styled with link show rules. Reference syntax can also be used to [cite]
+/// from a bibliography.
 ///
 /// As the default form requires a supplement and numbering, the label must be
 /// attached to a _referenceable element_. Referenceable elements include
@@ -310,7 +312,10 @@ fn show_reference(
         content = supplement + TextElem::packed("\u{a0}") + content;
     }
 
-    Ok(content.linked(Destination::Location(loc)))
+    // Produce a proper link element so that link show rules can style
+    // references, too.
+    let dest = Destination::Location(loc);
+    Ok(LinkElem::new(dest.into(), content).pack().spanned(reference.span()))
 }
 
 /// Turn a reference into a citation.
@@ -357,6 +362,37 @@ impl Supplement {
                 .display(),
         })
     }
+
+    /// Tries to resolve the supplement into its content in the given form.
+    ///
+    /// Functions receive the form as the named argument `form` if they have a
+    /// parameter of that name. Other functions are called as for
+    /// [`resolve`](Self::resolve).
+    pub fn resolve_form<T: IntoValue>(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        args: impl IntoIterator<Item = T>,
+        form: SupplementForm,
+    ) -> SourceResult<Content> {
+        let Supplement::Func(func) = self else {
+            return self.resolve(engine, styles, args);
+        };
+
+        let span = func.span();
+        let mut args = Args::new(span, args);
+        if func.has_named_param("form") {
+            args.items.push(Arg {
+                span,
+                name: Some("form".into()),
+                value: Spanned::new(form.into_value(), span),
+            });
+        }
+
+        Ok(func
+            .call(engine, Context::new(None, Some(styles)).track(), args)?
+            .display())
+    }
 }
 
 cast! {
@@ -369,6 +405,18 @@ cast! {
     v: Func => Self::Func(v),
 }
 
+/// The place in which a supplement is shown.
+///
+/// In many languages, the supplement of a figure is declined differently when
+/// it is referenced in running text than in the figure's caption.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum SupplementForm {
+    /// The supplement is shown in a caption, e.g. "Рисунок 3".
+    Caption,
+    /// The supplement is shown in a reference, e.g. "рис. 3".
+    Reference,
+}
+
 /// The form of the reference.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum RefForm {
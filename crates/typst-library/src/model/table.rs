@@ -6,14 +6,15 @@ use typst_utils::NonZeroExt;
 use crate::diag::{bail, HintedStrResult, HintedString, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Content, NativeElement, Packed, Show, Smart, StyleChain,
+    cast, elem, scope, Cast, Content, NativeElement, Packed, Show, Smart, StyleChain,
+    TargetElem,
 };
 use crate::layout::{
-    show_grid_cell, Abs, Alignment, BlockElem, Celled, GridCell, GridFooter, GridHLine,
-    GridHeader, GridVLine, Length, OuterHAlignment, OuterVAlignment, Rel, Sides,
-    TrackSizings,
+    show_grid_cell, Abs, Alignment, BlockElem, Celled, Dir, GridCell, GridFooter,
+    GridHLine, GridHeader, GridVLine, Length, OuterHAlignment, OuterVAlignment, Rel,
+    Sides, TrackSizings,
 };
-use crate::model::Figurable;
+use crate::model::{Figurable, FigureCaption};
 use crate::text::LocalName;
 use crate::visualize::{Paint, Stroke};
 
@@ -180,7 +181,9 @@ pub struct TableElem {
     /// This can either be a single alignment, an array of alignments
     /// (corresponding to each column) or a function that returns an alignment.
     /// The function receives the cells' column and row indices, starting from
-    /// zero. If set to `{auto}`, the outer alignment is used.
+    /// zero. If set to `{auto}`, the outer alignment is used. With the
+    /// vertical alignment `table.baseline`, the first text baselines of the
+    /// cells in a row are aligned.
     ///
     /// ```example
     /// #table(
@@ -233,15 +236,83 @@ pub struct TableElem {
     #[default(Celled::Value(Sides::splat(Some(Abs::pt(5.0).into()))))]
     pub inset: Celled<Sides<Option<Rel<Length>>>>,
 
+    /// The minimum number of body rows that the table keeps together in the
+    /// first and the last region it is split across, like orphan and widow
+    /// control for paragraphs.
+    ///
+    /// When the table would start with fewer than this many body rows at the
+    /// bottom of a page, it moves to the next page instead. Likewise, the
+    /// last page of a table always has at least this many body rows. Pages
+    /// in between are filled as usual, so the page before the last one may
+    /// end up with fewer rows when the last rows move on. Header and footer
+    /// rows are not counted, but cells that must not be broken across pages
+    /// are kept together with the rows they span. If the rows don't fit into
+    /// a region even when starting on a fresh one, the table is split as
+    /// usual.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #v(70pt)
+    ///
+    /// #table(
+    ///   columns: 2,
+    ///   min-rows-per-region: 3,
+    ///   table.header[*Item*][*Count*],
+    ///   ..range(6).map(i => ([Item #i], [#i])).flatten(),
+    /// )
+    /// ```
+    #[default(NonZeroUsize::ONE)]
+    pub min_rows_per_region: NonZeroUsize,
+
+    /// The direction in which the columns are laid out. Either `{ltr}` or
+    /// `{rtl}`.
+    ///
+    /// If set to `{auto}`, the columns follow the [text direction]($text.dir),
+    /// which is derived from the [language]($text.lang) by default. In a
+    /// right-to-left table, the first column is on the right.
+    ///
+    /// Only the visual order changes: Column indices, the `x` positions of
+    /// cells and lines, and per-cell functions for `fill`, `align`, `stroke`,
+    /// and `inset` still count from the first column. Similarly, a
+    /// [`table.vline`]($table.vline) with `position: start` is drawn on the
+    /// right of its column. The text in the cells keeps following the text
+    /// direction, but the `start` and `end` [alignment]($table.align) of a
+    /// cell refer to the table's direction. A right-to-left table in
+    /// left-to-right text thus aligns its cells to the right by default.
+    ///
+    /// ```example
+    /// #set text(lang: "he")
+    /// #table(
+    ///   columns: 3,
+    ///   table.vline(x: 1),
+    ///   [א], [ב], [ג],
+    ///   [1], [2], [3],
+    /// )
+    /// ```
+    pub dir: Smart<Dir>,
+
     /// The contents of the table cells, plus any extra table lines specified
     /// with the [`table.hline`]($table.hline) and
     /// [`table.vline`]($table.vline) elements.
     #[variadic]
     pub children: Vec<TableChild>,
+
+    /// The caption of a figure that consists of just this table. In HTML
+    /// export, it becomes the table's `<caption>`.
+    #[internal]
+    #[synthesized]
+    pub html_caption: Packed<FigureCaption>,
 }
 
 #[scope]
 impl TableElem {
+    /// Aligns the first text baselines of the cells in a row. Cells without
+    /// text are aligned at the top.
+    ///
+    /// Use it as the vertical part of the [`align`]($table.align) argument of
+    /// the table or its cells.
+    const BASELINE: Alignment = Alignment::BASELINE;
+
     #[elem]
     type TableCell;
 
@@ -259,7 +330,11 @@ impl TableElem {
 }
 
 impl Show for Packed<TableElem> {
-    fn show(&self, engine: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            return (engine.routines.html_table)(self, engine, styles);
+        }
+
         Ok(BlockElem::multi_layouter(self.clone(), engine.routines.layout_table)
             .pack()
             .spanned(self.span()))
@@ -697,6 +772,23 @@ pub struct TableCell {
     /// unbreakable, while a cell spanning at least one `{auto}`-sized row is
     /// breakable.
     pub breakable: Smart<bool>,
+
+    /// Whether this cell is a header for its column or its row.
+    ///
+    /// In HTML export, header cells become `<th>` elements with a matching
+    /// `scope` attribute. Cells within a [`table.header`]($table.header) are
+    /// column headers unless specified otherwise. This has no effect on
+    /// paged export.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   table.header[][Mass][Volume],
+    ///   table.cell(header: "row")[Sample A], [1.2 g], [1 ml],
+    ///   table.cell(header: "row")[Sample B], [2.7 g], [3 ml],
+    /// )
+    /// ```
+    pub header: Option<TableHeaderScope>,
 }
 
 cast! {
@@ -706,6 +798,10 @@ cast! {
 
 impl Show for Packed<TableCell> {
     fn show(&self, _engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(self.body().clone());
+        }
+
         show_grid_cell(self.body().clone(), self.inset(styles), self.align(styles))
     }
 }
@@ -722,3 +818,12 @@ impl From<Content> for TableCell {
         value.unpack::<Self>().unwrap_or_else(Self::new)
     }
 }
+
+/// Which cells a header cell of a table applies to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum TableHeaderScope {
+    /// The cell is a header for its column.
+    Col,
+    /// The cell is a header for its row.
+    Row,
+}
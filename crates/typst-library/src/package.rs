@@ -0,0 +1,217 @@
+//! Verification of package contents.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Display, Formatter};
+
+use comemo::{Track, Tracked};
+use ecow::EcoString;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use typst_syntax::package::PackageSpec;
+use typst_syntax::FileId;
+
+use crate::diag::{FileResult, PackageError};
+use crate::World;
+
+/// Pins packages to the hashes of their contents.
+///
+/// A lock maps package specifications to [hashes](PackageHash) over the
+/// packages' files. When a lock is passed to the compiler, each package is
+/// verified the first time one of its files is accessed. Compilation fails if
+/// the package's contents changed since the lock was recorded or if the
+/// package is not in the lock at all.
+///
+/// The lock serializes to a map from specifications to hashes, for example
+/// in TOML:
+///
+/// ```toml
+/// "@preview/example:0.1.0" = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PackageLock(BTreeMap<PackageSpec, PackageHash>);
+
+impl PackageLock {
+    /// Create an empty lock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The hash that the package is pinned to, if any.
+    pub fn get(&self, spec: &PackageSpec) -> Option<PackageHash> {
+        self.0.get(spec).copied()
+    }
+
+    /// Pin a package to a hash.
+    pub fn insert(&mut self, spec: PackageSpec, hash: PackageHash) {
+        self.0.insert(spec, hash);
+    }
+
+    /// Iterate over the pinned packages and their hashes.
+    pub fn iter(&self) -> impl Iterator<Item = (&PackageSpec, PackageHash)> {
+        self.0.iter().map(|(spec, &hash)| (spec, hash))
+    }
+
+    /// Pin the given packages to the current hashes of their contents in the
+    /// world.
+    ///
+    /// This creates or updates the lock after a successful compilation with
+    /// the packages that the compilation used. Other entries are kept.
+    pub fn record<'a>(
+        &mut self,
+        world: &dyn World,
+        specs: impl IntoIterator<Item = &'a PackageSpec>,
+    ) -> FileResult<()> {
+        for spec in specs {
+            let hash = PackageHash::compute(world, spec)?;
+            self.insert(spec.clone(), hash);
+        }
+        Ok(())
+    }
+
+    /// Check that the package's contents in the world match its pinned hash.
+    pub fn verify(&self, world: &dyn World, spec: &PackageSpec) -> FileResult<()> {
+        let Some(expected) = self.get(spec) else {
+            return Err(PackageError::NotLocked(spec.clone()).into());
+        };
+
+        let found = PackageHash::compute(world, spec)?;
+        if found != expected {
+            return Err(PackageError::HashMismatch {
+                spec: spec.clone(),
+                expected,
+                found,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// A hash over the files of a package.
+///
+/// The hash covers the path and contents of every file that the world lists
+/// for the package with [`World::package_files`]. It is a SHA-256 digest over
+/// the number of files, followed by the path and contents of each file in
+/// order of their paths. Paths and contents are each preceded by their length
+/// in bytes as a 64-bit little-endian integer, so that the encoding is
+/// unambiguous.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PackageHash([u8; 32]);
+
+impl PackageHash {
+    /// Hash the files of a package in the world.
+    ///
+    /// The result is cached: As long as the package's files stay the same,
+    /// they are only read and hashed once.
+    pub fn compute(world: &dyn World, spec: &PackageSpec) -> FileResult<Self> {
+        compute_impl(world.track(), spec)
+    }
+}
+
+/// The cached implementation of [`PackageHash::compute`].
+#[comemo::memoize]
+fn compute_impl(
+    world: Tracked<dyn World + '_>,
+    spec: &PackageSpec,
+) -> FileResult<PackageHash> {
+    let mut files: Vec<(EcoString, FileId)> = world
+        .package_files(spec)?
+        .into_iter()
+        .map(|id| (path(id), id))
+        .collect();
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    files.dedup_by(|(a, _), (b, _)| a == b);
+
+    let mut hasher = Sha256::new();
+    hasher.update((files.len() as u64).to_le_bytes());
+    for (path, id) in files {
+        let data = world.file(id)?;
+        for part in [path.as_bytes(), data.as_slice()] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+    }
+
+    Ok(PackageHash(hasher.finalize().into()))
+}
+
+impl Debug for PackageHash {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for PackageHash {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for PackageHash {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PackageHash {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let string = EcoString::deserialize(d)?;
+        if string.len() != 64 {
+            return Err(serde::de::Error::custom("package hash must have 64 digits"));
+        }
+
+        let mut hash = [0; 32];
+        for (byte, digits) in hash.iter_mut().zip(string.as_bytes().chunks(2)) {
+            *byte = std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| serde::de::Error::custom("invalid package hash"))?;
+        }
+
+        Ok(Self(hash))
+    }
+}
+
+/// The path of a file within its package, with forward slashes on all
+/// platforms.
+fn path(id: FileId) -> EcoString {
+    let mut path = EcoString::new();
+    for component in id.vpath().as_rootless_path().components() {
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(&component.as_os_str().to_string_lossy());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_serde() {
+        let spec: PackageSpec = "@preview/example:0.1.0".parse().unwrap();
+        let mut lock = PackageLock::new();
+        let mut hash = [0; 32];
+        hash[30] = 0xa;
+        hash[31] = 0xbc;
+        lock.insert(spec.clone(), PackageHash(hash));
+
+        let json = serde_json::to_string(&lock).unwrap();
+        assert_eq!(
+            json,
+            format!(r#"{{"@preview/example:0.1.0":"{}0abc"}}"#, "0".repeat(60))
+        );
+
+        let parsed: PackageLock = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.get(&spec), Some(PackageHash(hash)));
+        assert!(serde_json::from_str::<PackageLock>(r#"{"@preview/example:0.1.0":"x"}"#)
+            .is_err());
+    }
+}
@@ -15,9 +15,9 @@ use crate::foundations::{
 };
 use crate::introspection::{Introspector, Locator, SplitLocator};
 use crate::layout::{
-    Abs, BoxElem, ColumnsElem, Fragment, Frame, GridElem, InlineItem, MoveElem, PadElem,
-    PagedDocument, Region, Regions, Rel, RepeatElem, RotateElem, ScaleElem, Size,
-    SkewElem, StackElem,
+    Abs, BoxElem, ColumnsElem, FitTextElem, Fragment, Frame, GridElem, InlineItem,
+    MoveElem, PadElem, PagedDocument, Region, Regions, Rel, RepeatElem, RotateElem,
+    ScaleElem, Size, SkewElem, StackElem,
 };
 use crate::math::EquationElem;
 use crate::model::{DocumentInfo, EnumElem, ListElem, TableElem};
@@ -160,6 +160,20 @@ routines! {
         regions: Regions,
     ) -> SourceResult<Fragment>
 
+    /// Converts a [`TableElem`] into semantic HTML table elements.
+    fn html_table(
+        elem: &Packed<TableElem>,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Content>
+
+    /// Converts a [`GridElem`] into an HTML element with CSS grid layout.
+    fn html_grid(
+        elem: &Packed<GridElem>,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Content>
+
     /// Lays out a [`StackElem`].
     fn layout_stack(
         elem: &Packed<StackElem>,
@@ -223,6 +237,15 @@ routines! {
         region: Region,
     ) -> SourceResult<Frame>
 
+    /// Lays out a [`FitTextElem`].
+    fn layout_fit_text(
+        elem: &Packed<FitTextElem>,
+        engine: &mut Engine,
+        locator: Locator,
+        styles: StyleChain,
+        region: Region,
+    ) -> SourceResult<Frame>
+
     /// Lays out a [`PadElem`].
     fn layout_pad(
         elem: &Packed<PadElem>,
@@ -354,7 +377,12 @@ pub enum RealizationKind<'a> {
     /// to document metadata that will be filled from `set document` rules.
     HtmlDocument(&'a mut DocumentInfo),
     /// A nested realization in a container (e.g. a `block`).
-    HtmlFragment,
+    HtmlFragment {
+        /// Whether inline content is grouped into paragraphs, like at the
+        /// root. This is the case for fragments that contain paragraph breaks,
+        /// like the body of a list item with multiple paragraphs.
+        paragraphs: bool,
+    },
     /// A realization within math.
     Math,
 }
@@ -372,5 +400,14 @@ pub struct Arenas {
     pub bump: bumpalo::Bump,
 }
 
+impl Arenas {
+    /// The approximate number of bytes allocated in the arenas so far.
+    pub fn allocated_bytes(&self) -> usize {
+        self.content.len() * std::mem::size_of::<Content>()
+            + self.styles.len() * std::mem::size_of::<Styles>()
+            + self.bump.allocated_bytes()
+    }
+}
+
 /// A pair of content and a style chain that applies to it.
 pub type Pair<'a> = (&'a Content, StyleChain<'a>);
@@ -0,0 +1,204 @@
+use std::cmp::Ordering;
+
+use crate::text::Lang;
+
+/// How to compare strings for sorting.
+///
+/// This is a compact approximation of the Unicode Collation Algorithm: Letters
+/// with diacritics sort with their base letter, and diacritics and case only
+/// break ties. A few languages move some letters to their own place in the
+/// alphabet, e.g. `ä` after `z` in Swedish. Full collation tables are not
+/// included because they would considerably increase the size of the
+/// compiler, in particular for the web app. Scripts other than Latin are
+/// compared by code point.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Collation {
+    /// Compares runs of digits by their numeric value and everything else by
+    /// code point, such that `item2` comes before `item10`.
+    Natural,
+    /// Compares strings according to the alphabet of the given language.
+    Locale(Lang),
+}
+
+impl Collation {
+    /// Compare two strings.
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Natural => natural(a, b),
+            Self::Locale(lang) => locale(a, b, lang),
+        }
+    }
+}
+
+/// Compare two strings with embedded numbers by value.
+fn natural(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = digits(&mut a);
+                let y = digits(&mut b);
+                let ordering = x
+                    .trim_start_matches('0')
+                    .len()
+                    .cmp(&y.trim_start_matches('0').len())
+                    .then_with(|| {
+                        x.trim_start_matches('0').cmp(y.trim_start_matches('0'))
+                    })
+                    .then_with(|| x.len().cmp(&y.len()));
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Consume a run of ASCII digits.
+fn digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut run = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        run.push(c);
+    }
+    run
+}
+
+/// Compare two strings according to the alphabet of a language.
+fn locale(a: &str, b: &str, lang: Lang) -> Ordering {
+    let a = weights(a, lang);
+    let b = weights(b, lang);
+    let a = a.iter();
+    let b = b.iter();
+    (a.clone().map(|w| w.primary).cmp(b.clone().map(|w| w.primary)))
+        .then_with(|| a.clone().map(|w| w.secondary).cmp(b.clone().map(|w| w.secondary)))
+        .then_with(|| a.map(|w| w.tertiary).cmp(b.map(|w| w.tertiary)))
+}
+
+/// The collation weights of a single character.
+#[derive(Debug, Copy, Clone)]
+struct Weight {
+    /// The base letter.
+    primary: u32,
+    /// The diacritic.
+    secondary: u8,
+    /// The case. Lowercase sorts first.
+    tertiary: u8,
+}
+
+/// Latin base letters and their variants with diacritics, in the order in
+/// which the variants sort among each other.
+const VARIANTS: &[(char, &str)] = &[
+    ('a', "áàăâǎåäãąā"),
+    ('c', "ćĉčċç"),
+    ('d', "ďđð"),
+    ('e', "éèĕêěëėęē"),
+    ('g', "ğĝġģ"),
+    ('h', "ĥħ"),
+    ('i', "íìĭîïĩįīı"),
+    ('j', "ĵ"),
+    ('k', "ķ"),
+    ('l', "ĺľļŀł"),
+    ('n', "ńňñņ"),
+    ('o', "óòŏôöőõøō"),
+    ('r', "ŕřŗ"),
+    ('s', "śŝšşș"),
+    ('t', "ťţțŧ"),
+    ('u', "úùŭûůüűũųū"),
+    ('w', "ŵ"),
+    ('y', "ýŷÿ"),
+    ('z', "źžż"),
+];
+
+/// Letters that are written as multiple base letters for sorting.
+const EXPANSIONS: &[(char, &str)] =
+    &[('ß', "ss"), ('æ', "ae"), ('œ', "oe"), ('þ', "th"), ('ĳ', "ij")];
+
+/// Letters that have their own place in the alphabet of a language. Each
+/// entry lists letters that sort, in this order, after the given base letter.
+fn tailoring(lang: Lang) -> &'static [(char, &'static str)] {
+    match lang.as_str() {
+        "sv" | "fi" => &[('z', "åäö")],
+        "da" | "nb" | "nn" | "no" => &[('z', "æøå")],
+        "es" => &[('n', "ñ")],
+        _ => &[],
+    }
+}
+
+/// Compute the collation weights of a string.
+fn weights(text: &str, lang: Lang) -> Vec<Weight> {
+    let tailoring = tailoring(lang);
+    let mut weights = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let tertiary = u8::from(lower != c);
+        let base = |c: char| u32::from(c) << 8;
+
+        // Tailored letters take precedence over decomposition, e.g. `ä` is a
+        // letter of its own in Swedish.
+        if let Some((after, pos)) = tailoring.iter().find_map(|(after, letters)| {
+            letters.chars().position(|l| l == lower).map(|pos| (*after, pos))
+        }) {
+            let primary = base(after) + pos as u32 + 1;
+            weights.push(Weight { primary, secondary: 0, tertiary });
+            continue;
+        }
+
+        if let Some((_, expanded)) = EXPANSIONS.iter().find(|(l, _)| *l == lower) {
+            for l in expanded.chars() {
+                weights.push(Weight { primary: base(l), secondary: 1, tertiary });
+            }
+            continue;
+        }
+
+        let (primary, secondary) = VARIANTS
+            .iter()
+            .find_map(|(letter, variants)| {
+                variants
+                    .chars()
+                    .position(|v| v == lower)
+                    .map(|pos| (base(*letter), pos as u8 + 1))
+            })
+            .unwrap_or((base(lower), 0));
+
+        weights.push(Weight { primary, secondary, tertiary });
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural() {
+        let cmp = |a, b| Collation::Natural.compare(a, b);
+        assert_eq!(cmp("item2", "item10"), Ordering::Less);
+        assert_eq!(cmp("item02", "item2"), Ordering::Greater);
+        assert_eq!(cmp("a1b", "a1b"), Ordering::Equal);
+        assert_eq!(cmp("1.10", "1.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_locale() {
+        let cmp = |a, b, lang| Collation::Locale(lang).compare(a, b);
+        assert_eq!(cmp("Ärger", "Apfel", Lang::GERMAN), Ordering::Greater);
+        assert_eq!(cmp("Ärger", "Arm", Lang::GERMAN), Ordering::Less);
+        assert_eq!(cmp("Ärger", "Zebra", Lang::GERMAN), Ordering::Less);
+        assert_eq!(cmp("Ärger", "Zebra", Lang::SWEDISH), Ordering::Greater);
+        assert_eq!(cmp("apfel", "Apfel", Lang::GERMAN), Ordering::Less);
+        assert_eq!(cmp("Straße", "Strasse", Lang::GERMAN), Ordering::Greater);
+        assert_eq!(cmp("ñu", "nube", Lang::SPANISH), Ordering::Greater);
+        assert_eq!(cmp("ñu", "oso", Lang::SPANISH), Ordering::Less);
+    }
+}
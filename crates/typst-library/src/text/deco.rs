@@ -1,11 +1,19 @@
+use std::fmt::{Display, Write};
+
+use ecow::{eco_format, EcoString};
 use smallvec::smallvec;
+use typst_syntax::Span;
+use typst_utils::Numeric;
 
-use crate::diag::SourceResult;
+use crate::diag::{warning, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{elem, Content, Packed, Show, Smart, StyleChain};
+use crate::foundations::{
+    elem, repr, Content, NativeElement, Packed, Show, Smart, StyleChain, TargetElem,
+};
+use crate::html::{attr, tag, HtmlElem};
 use crate::layout::{Abs, Corners, Length, Rel, Sides};
 use crate::text::{BottomEdge, BottomEdgeMetric, TextElem, TopEdge, TopEdgeMetric};
-use crate::visualize::{Color, FixedStroke, Paint, Stroke};
+use crate::visualize::{Color, DashLength, FixedStroke, Paint, Stroke};
 
 /// Underlines text.
 ///
@@ -80,7 +88,19 @@ pub struct UnderlineElem {
 
 impl Show for Packed<UnderlineElem> {
     #[typst_macros::time(name = "underline", span = self.span())]
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            let mut css = HtmlDeco::new(engine, self.span(), "underline");
+            css.line("underline", self.stroke(styles));
+            if let Smart::Custom(offset) = self.offset(styles) {
+                css.push("text-underline-offset", length(offset));
+            }
+            css.evade(self.evade(styles));
+            css.background(self.background(styles));
+            css.line_extent(self.extent(styles));
+            return Ok(css.finish(self.body.clone()));
+        }
+
         Ok(self.body().clone().styled(TextElem::set_deco(smallvec![Decoration {
             line: DecoLine::Underline {
                 stroke: self.stroke(styles).unwrap_or_default(),
@@ -172,7 +192,17 @@ pub struct OverlineElem {
 
 impl Show for Packed<OverlineElem> {
     #[typst_macros::time(name = "overline", span = self.span())]
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            let mut css = HtmlDeco::new(engine, self.span(), "overline");
+            css.line("overline", self.stroke(styles));
+            css.line_offset(self.offset(styles));
+            css.evade(self.evade(styles));
+            css.background(self.background(styles));
+            css.line_extent(self.extent(styles));
+            return Ok(css.finish(self.body.clone()));
+        }
+
         Ok(self.body().clone().styled(TextElem::set_deco(smallvec![Decoration {
             line: DecoLine::Overline {
                 stroke: self.stroke(styles).unwrap_or_default(),
@@ -249,7 +279,16 @@ pub struct StrikeElem {
 
 impl Show for Packed<StrikeElem> {
     #[typst_macros::time(name = "strike", span = self.span())]
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            let mut css = HtmlDeco::new(engine, self.span(), "strike");
+            css.line("line-through", self.stroke(styles));
+            css.line_offset(self.offset(styles));
+            css.background(self.background(styles));
+            css.line_extent(self.extent(styles));
+            return Ok(css.finish(self.body.clone()));
+        }
+
         Ok(self.body().clone().styled(TextElem::set_deco(smallvec![Decoration {
             // Note that we do not support evade option for strikethrough.
             line: DecoLine::Strikethrough {
@@ -344,7 +383,11 @@ pub struct HighlightElem {
 
 impl Show for Packed<HighlightElem> {
     #[typst_macros::time(name = "highlight", span = self.span())]
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if TargetElem::target_in(styles).is_html() {
+            return Ok(self.show_html(engine, styles));
+        }
+
         Ok(self.body().clone().styled(TextElem::set_deco(smallvec![Decoration {
             line: DecoLine::Highlight {
                 fill: self.fill(styles),
@@ -361,6 +404,218 @@ impl Show for Packed<HighlightElem> {
     }
 }
 
+impl Packed<HighlightElem> {
+    /// Shows the highlight as a span with a background in HTML export.
+    ///
+    /// The top and bottom edges of the background are determined by the
+    /// browser, which typically matches the default ascender and descender
+    /// edges.
+    fn show_html(&self, engine: &mut Engine, styles: StyleChain) -> Content {
+        let mut css = HtmlDeco::new(engine, self.span(), "highlight");
+        match self.fill(styles) {
+            Some(Paint::Solid(color)) => css.push("background-color", color.to_hex()),
+            Some(_) => css.ignored("gradient or tiling fill"),
+            None => {}
+        }
+
+        let stroke = self
+            .stroke(styles)
+            .unwrap_or_default()
+            .map(|stroke| stroke.map(Stroke::unwrap_or_default));
+        if stroke.is_uniform() {
+            if let Some(stroke) = &stroke.top {
+                css.border("border", stroke);
+            }
+        } else {
+            for (property, stroke) in [
+                ("border-top", &stroke.top),
+                ("border-right", &stroke.right),
+                ("border-bottom", &stroke.bottom),
+                ("border-left", &stroke.left),
+            ] {
+                if let Some(stroke) = stroke {
+                    css.border(property, stroke);
+                }
+            }
+        }
+
+        let radius = self.radius(styles).unwrap_or_default();
+        if radius.iter().any(|r| !r.is_zero()) {
+            let [tl, tr, br, bl] = [
+                radius.top_left,
+                radius.top_right,
+                radius.bottom_right,
+                radius.bottom_left,
+            ]
+            .map(relative);
+            if radius.is_uniform() {
+                css.push("border-radius", tl);
+            } else {
+                css.push("border-radius", format_args!("{tl} {tr} {br} {bl}"));
+            }
+        }
+
+        let extent = self.extent(styles);
+        if extent > Abs::zero() {
+            css.push("padding-inline", length(extent));
+        } else if extent < Abs::zero() {
+            css.ignored("negative extent");
+        }
+
+        if self.top_edge(styles) != TopEdge::Metric(TopEdgeMetric::Ascender) {
+            css.ignored("top edge");
+        }
+        if self.bottom_edge(styles) != BottomEdge::Metric(BottomEdgeMetric::Descender) {
+            css.ignored("bottom edge");
+        }
+
+        // Like in paged export, every line gets its own rounded corners,
+        // borders, and extents.
+        css.push("box-decoration-break", "clone");
+        css.push("-webkit-box-decoration-break", "clone");
+        css.finish(self.body.clone())
+    }
+}
+
+/// Builds the inline CSS for a text decoration in HTML export.
+///
+/// Parameters without a CSS equivalent are dropped with a warning, so that the
+/// differences to paged export do not go unnoticed.
+struct HtmlDeco<'a, 'b> {
+    engine: &'a mut Engine<'b>,
+    span: Span,
+    name: &'static str,
+    css: EcoString,
+}
+
+impl<'a, 'b> HtmlDeco<'a, 'b> {
+    /// Start building the CSS for the element with the given name.
+    fn new(engine: &'a mut Engine<'b>, span: Span, name: &'static str) -> Self {
+        Self { engine, span, name, css: EcoString::new() }
+    }
+
+    /// Add a CSS declaration.
+    fn push(&mut self, property: &str, value: impl Display) {
+        if !self.css.is_empty() {
+            self.css.push_str("; ");
+        }
+        write!(self.css, "{property}: {value}").unwrap();
+    }
+
+    /// Warn that a parameter was dropped.
+    fn ignored(&mut self, what: &str) {
+        self.engine.sink.warn(
+            warning!(
+                self.span,
+                "{} {what} was ignored during HTML export", self.name;
+                hint: "CSS has no equivalent for it"
+            )
+            .with_code("ignored-in-html"),
+        );
+    }
+
+    /// Add a CSS text decoration line with the given stroke.
+    fn line(&mut self, line: &str, stroke: Smart<Stroke<Abs>>) {
+        self.push("text-decoration-line", line);
+        let Smart::Custom(stroke) = stroke else { return };
+        if let Smart::Custom(thickness) = stroke.thickness {
+            self.push("text-decoration-thickness", length(thickness));
+        }
+        match stroke.dash {
+            Smart::Custom(Some(dash)) => self.push(
+                "text-decoration-style",
+                match dash.array.first() {
+                    Some(DashLength::LineWidth) => "dotted",
+                    _ => "dashed",
+                },
+            ),
+            Smart::Custom(None) => self.push("text-decoration-style", "solid"),
+            Smart::Auto => {}
+        }
+        match stroke.paint {
+            Smart::Custom(Paint::Solid(color)) => {
+                self.push("text-decoration-color", color.to_hex())
+            }
+            Smart::Custom(_) => self.ignored("gradient or tiling stroke"),
+            Smart::Auto => {}
+        }
+    }
+
+    /// Handle the offset of an overline or strikethrough, which CSS always
+    /// places itself.
+    fn line_offset(&mut self, offset: Smart<Abs>) {
+        if offset.is_custom() {
+            self.ignored("offset");
+        }
+    }
+
+    /// Disable skipping of descenders if requested.
+    fn evade(&mut self, evade: bool) {
+        if !evade {
+            self.push("text-decoration-skip-ink", "none");
+        }
+    }
+
+    /// Handle the placement behind the text, which CSS decides per line kind.
+    fn background(&mut self, background: bool) {
+        if background {
+            self.ignored("background placement");
+        }
+    }
+
+    /// Handle the extent of a decoration line, which CSS always draws exactly
+    /// across the text.
+    fn line_extent(&mut self, extent: Abs) {
+        if !extent.is_zero() {
+            self.ignored("extent");
+        }
+    }
+
+    /// Add a CSS border with the given stroke.
+    fn border(&mut self, property: &str, stroke: &FixedStroke) {
+        let style = match &stroke.dash {
+            Some(dash) if dash.array.first() == Some(&stroke.thickness) => "dotted",
+            Some(_) => "dashed",
+            None => "solid",
+        };
+        let color = match &stroke.paint {
+            Paint::Solid(color) => color.to_hex(),
+            _ => {
+                self.ignored("gradient or tiling stroke");
+                "currentcolor".into()
+            }
+        };
+        self.push(property, format_args!("{} {style} {color}", length(stroke.thickness)));
+    }
+
+    /// Wrap the body into a span with the built CSS.
+    fn finish(self, body: Content) -> Content {
+        HtmlElem::new(tag::span)
+            .with_attr(attr::style, self.css)
+            .with_body(Some(body))
+            .pack()
+            .spanned(self.span)
+    }
+}
+
+/// Format a length for CSS.
+fn length(abs: Abs) -> EcoString {
+    repr::format_float_with_unit(abs.to_pt(), "pt")
+}
+
+/// Format a relative length for CSS.
+///
+/// The relative part refers to the size of the span, which only approximates
+/// its meaning in paged export.
+fn relative(rel: Rel<Abs>) -> EcoString {
+    let ratio = repr::format_float(rel.rel.get() * 100.0, Some(2), false, "");
+    match (rel.rel.is_zero(), rel.abs.is_zero()) {
+        (true, _) => length(rel.abs),
+        (false, true) => eco_format!("{ratio}%"),
+        (false, false) => eco_format!("calc({ratio}% + {})", length(rel.abs)),
+    }
+}
+
 /// A text decoration.
 ///
 /// Can be positioned over, under, or on top of text, or highlight the text with
@@ -0,0 +1,235 @@
+use comemo::Tracked;
+use ecow::EcoString;
+
+use crate::foundations::{dict, Array, IntoValue, Regex, StyleChain};
+use crate::text::{families, variant, Font, FontFamily, FontVariant, TextElem};
+use crate::World;
+
+/// Selects the font for a piece of text, as done during shaping.
+///
+/// The families are tried in order and the first one that is in the font book
+/// and whose best matching variant is not in `used` is selected. Selecting a
+/// family consumes it from the iterator, so that the next call continues with
+/// the following families. Once the families are exhausted and `fallback` is
+/// enabled, a font that covers the text is searched among all fonts instead.
+///
+/// The `used` fonts are those that were already tried for the text and did not
+/// cover it. Returns the selected font together with the user-set coverage of
+/// its family. Each considered family and fallback font is reported to
+/// `observe`. The report only borrows from the selection, so that observing
+/// nothing costs nothing.
+pub fn select_font<'a>(
+    world: Tracked<dyn World + '_>,
+    families: &mut impl Iterator<Item = &'a FontFamily>,
+    variant: FontVariant,
+    used: &[Font],
+    fallback: bool,
+    text: &str,
+    mut observe: impl FnMut(FontCandidate<'_>),
+) -> Option<(Font, Option<&'a Regex>)> {
+    let book = world.book();
+    for family in families {
+        // Like with fallback, variants that fail to load are skipped in favor
+        // of the next best one.
+        let mut ids = book.select_variants(family.as_str(), variant).peekable();
+        let found = ids.peek().is_some();
+        let font = ids.find_map(|id| world.font(id));
+        let selected = font.as_ref().is_some_and(|font| !used.contains(font));
+        observe(FontCandidate {
+            family: family.as_str(),
+            fallback: false,
+            found,
+            font: font.as_ref(),
+            used: !selected && font.is_some(),
+        });
+        if selected {
+            return font.map(|font| (font, family.covers()));
+        }
+    }
+
+    if !fallback {
+        return None;
+    }
+
+    // Fonts that fail to load are skipped as if they didn't cover the text.
+    let first = used.first().map(Font::info);
+    let font = book
+        .select_fallbacks(first, variant, text)
+        .find_map(|id| world.font(id))?;
+    let selected = !used.contains(&font);
+    observe(FontCandidate {
+        family: font.info().family.as_str(),
+        fallback: true,
+        found: true,
+        font: Some(&font),
+        used: !selected,
+    });
+    selected.then_some((font, None))
+}
+
+/// A family or fallback font that was considered by [`select_font`].
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct FontCandidate<'a> {
+    /// The name of the family, as given in the styles, or the family of the
+    /// fallback font.
+    pub family: &'a str,
+    /// Whether the font was found by the automatic fallback.
+    pub fallback: bool,
+    /// Whether the family is in the font book.
+    pub found: bool,
+    /// The best matching variant of the family, if it is in the font book and
+    /// could be loaded.
+    pub font: Option<&'a Font>,
+    /// Whether the font was skipped because it was already tried.
+    pub used: bool,
+}
+
+/// Explains which font is chosen for a character and why the fonts before it
+/// were skipped.
+///
+/// This runs the same font selection and coverage check as the shaper, see
+/// [`glyph_covers`].
+pub fn explain_font_choice(
+    world: Tracked<dyn World + '_>,
+    styles: StyleChain,
+    c: char,
+) -> FontChoiceExplanation {
+    let variant = variant(styles);
+    let fallback = TextElem::fallback_in(styles);
+    let mut families = families(styles);
+    let text = c.encode_utf8(&mut [0; 4]).to_string();
+
+    let mut candidates = Vec::new();
+    let mut used = Vec::new();
+    let mut selection = None;
+    while let Some((font, covers)) =
+        select_font(world, &mut families, variant, &used, fallback, &text, |candidate| {
+            candidates.push(ExplainedCandidate::new(candidate, variant))
+        })
+    {
+        let covered = shapes_fully(&font, &text, covers);
+        if let Some(last) = candidates.last_mut() {
+            last.covers = covered;
+            last.selected = covered;
+        }
+        if covered {
+            selection = Some(font);
+            break;
+        }
+        used.push(font);
+    }
+
+    FontChoiceExplanation { candidates, selection }
+}
+
+/// Whether a glyph that shaping yielded for the cluster starting at `cluster`
+/// in the text covers it.
+///
+/// This is how the shaper decides whether a font covers text: The glyph must
+/// not be the missing glyph and the cluster's first character must match the
+/// user-set coverage of the font's family, if any.
+pub fn glyph_covers(
+    glyph_id: u32,
+    text: &str,
+    cluster: usize,
+    covers: Option<&Regex>,
+) -> bool {
+    glyph_id != 0
+        && covers.map_or(true, |covers| {
+            let end = text[cluster..]
+                .char_indices()
+                .nth(1)
+                .map_or(text.len(), |(i, _)| cluster + i);
+            covers.is_match(&text[cluster..end])
+        })
+}
+
+/// Whether shaping the text with the font yields only glyphs that
+/// [cover](glyph_covers) it.
+///
+/// This may differ from looking up the glyphs of the individual characters, as
+/// shaping can compose and decompose characters.
+fn shapes_fully(font: &Font, text: &str, covers: Option<&Regex>) -> bool {
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.guess_segment_properties();
+    buffer.set_flags(rustybuzz::BufferFlags::REMOVE_DEFAULT_IGNORABLES);
+    let buffer = rustybuzz::shape(font.rusty(), &[], buffer);
+    buffer
+        .glyph_infos()
+        .iter()
+        .all(|info| glyph_covers(info.glyph_id, text, info.cluster as usize, covers))
+}
+
+/// The result of [`explain_font_choice`].
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct FontChoiceExplanation {
+    /// The considered families and fallback fonts, in order.
+    pub candidates: Vec<ExplainedCandidate>,
+    /// The font that is chosen for the character, if any font covers it.
+    pub selection: Option<Font>,
+}
+
+impl FontChoiceExplanation {
+    /// Turns the candidates into an array of dictionaries for use in Typst.
+    pub fn to_array(&self) -> Array {
+        self.candidates
+            .iter()
+            .map(|explained| {
+                dict! {
+                    "family" => explained.family.clone(),
+                    "fallback" => explained.fallback,
+                    "found" => explained.found,
+                    "loaded" => explained.font.is_some(),
+                    "used" => explained.used,
+                    "exact-variant" => explained.exact_variant,
+                    "covers" => explained.covers,
+                    "selected" => explained.selected,
+                }
+                .into_value()
+            })
+            .collect()
+    }
+}
+
+/// A candidate of the font selection together with the verdict on it.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct ExplainedCandidate {
+    /// The name of the family, as given in the styles, or the family of the
+    /// fallback font.
+    pub family: EcoString,
+    /// Whether the font was found by the automatic fallback.
+    pub fallback: bool,
+    /// Whether the family is in the font book.
+    pub found: bool,
+    /// The best matching variant of the family, if it is in the font book and
+    /// could be loaded.
+    pub font: Option<Font>,
+    /// Whether the font was skipped because it was already tried.
+    pub used: bool,
+    /// Whether the font has exactly the requested weight, style, and stretch.
+    /// Otherwise, the closest variant of the family was selected.
+    pub exact_variant: bool,
+    /// Whether the font covers the character. Only checked for fonts that
+    /// were selected.
+    pub covers: bool,
+    /// Whether the font is the final selection.
+    pub selected: bool,
+}
+
+impl ExplainedCandidate {
+    fn new(candidate: FontCandidate, variant: FontVariant) -> Self {
+        Self {
+            family: candidate.family.into(),
+            fallback: candidate.fallback,
+            found: candidate.found,
+            font: candidate.font.cloned(),
+            used: candidate.used,
+            exact_variant: candidate
+                .font
+                .is_some_and(|font| font.info().variant == variant),
+            covers: false,
+            selected: false,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use std::cmp::Reverse;
 use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Formatter};
+use std::path::PathBuf;
 
+use ecow::{eco_format, EcoString};
 use serde::{Deserialize, Serialize};
 use ttf_parser::{name_id, PlatformId, Tag};
 use unicode_segmentation::UnicodeSegmentation;
@@ -16,12 +18,30 @@ pub struct FontBook {
     families: BTreeMap<String, Vec<usize>>,
     /// Metadata about each font in the collection.
     infos: Vec<FontInfo>,
+    /// The priority of each font, breaking ties between equally good matches.
+    priorities: Vec<i32>,
+    /// For each font, the index of the font that shadows it, if any.
+    shadowed: Vec<Option<usize>>,
+    /// Faces that were found, but could not be loaded.
+    problems: Vec<FontProblem>,
 }
 
 impl FontBook {
     /// Create a new, empty font book.
     pub fn new() -> Self {
-        Self { families: BTreeMap::new(), infos: vec![] }
+        Self {
+            families: BTreeMap::new(),
+            infos: vec![],
+            priorities: vec![],
+            shadowed: vec![],
+            problems: vec![],
+        }
+    }
+
+    /// Start building a font book from fonts with custom priorities and
+    /// pinned variable font instances.
+    pub fn builder() -> FontBookBuilder {
+        FontBookBuilder::default()
     }
 
     /// Create a font book from a collection of font infos.
@@ -40,10 +60,76 @@ impl FontBook {
 
     /// Insert metadata into the font book.
     pub fn push(&mut self, info: FontInfo) {
+        self.push_with_priority(info, 0);
+    }
+
+    /// Insert metadata into the font book with a priority. Among fonts that
+    /// match a request equally well, the one with the highest priority is
+    /// selected.
+    ///
+    /// If the book already contains fonts of the same family and variant, only
+    /// one of them is selected, independently of the order in which they were
+    /// inserted. The others are [shadowed](Self::duplicates) by it. The
+    /// selected font is the first one by
+    /// - the highest priority,
+    /// - the highest revision (see [`FontInfo::revision`]),
+    /// - the largest coverage,
+    /// - and finally the highest hash of its metadata.
+    pub fn push_with_priority(&mut self, info: FontInfo, priority: i32) {
         let index = self.infos.len();
         let family = info.family.to_lowercase();
-        self.families.entry(family).or_default().push(index);
+        let variant = info.variant;
         self.infos.push(info);
+        self.priorities.push(priority);
+        self.shadowed.push(None);
+
+        let ids = self.families.entry(family).or_default();
+        ids.push(index);
+
+        // Determine which of the fonts with the same variant is selected.
+        let same: Vec<usize> = ids
+            .iter()
+            .copied()
+            .filter(|&id| self.infos[id].variant == variant)
+            .collect();
+        if same.len() > 1 {
+            let winner = same.iter().copied().max_by_key(|&id| self.preference(id));
+            for id in same {
+                self.shadowed[id] = winner.filter(|&winner| winner != id);
+            }
+        }
+    }
+
+    /// Pairs of fonts with the same family and variant. Each pair consists of
+    /// the index of a font that is never selected and the index of the font
+    /// that is selected instead.
+    pub fn duplicates(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.shadowed
+            .iter()
+            .enumerate()
+            .filter_map(|(id, winner)| winner.map(|winner| (id, winner)))
+    }
+
+    /// How much a font is preferred over other fonts with the same family and
+    /// variant.
+    fn preference(&self, id: usize) -> impl Ord {
+        let info = &self.infos[id];
+        (
+            self.priorities[id],
+            info.revision,
+            info.coverage.len(),
+            typst_utils::hash128(info),
+        )
+    }
+
+    /// Record a face that could not be loaded.
+    pub fn push_problem(&mut self, problem: FontProblem) {
+        self.problems.push(problem);
+    }
+
+    /// The faces that could not be loaded and are thus not part of the book.
+    pub fn problems(&self) -> &[FontProblem] {
+        &self.problems
     }
 
     /// Get the font info for the given index.
@@ -51,6 +137,16 @@ impl FontBook {
         self.infos.get(index)
     }
 
+    /// The number of fonts in the book. All font indices are below it.
+    pub fn len(&self) -> usize {
+        self.infos.len()
+    }
+
+    /// Whether the book contains no fonts.
+    pub fn is_empty(&self) -> bool {
+        self.infos.is_empty()
+    }
+
     /// Returns true if the book contains a font family with the given name.
     pub fn contains_family(&self, family: &str) -> bool {
         self.families.contains_key(family)
@@ -79,6 +175,20 @@ impl FontBook {
         self.find_best_variant(None, variant, ids.iter().copied())
     }
 
+    /// Like [`select`](Self::select), but yields all variants of the family
+    /// from the best to the worst match, so that the caller can move on if a
+    /// font fails to load.
+    ///
+    /// The `family` should be all lowercase.
+    pub fn select_variants(
+        &self,
+        family: &str,
+        variant: FontVariant,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let ids = self.select_family(family).collect();
+        self.ranked(None, variant, ids)
+    }
+
     /// Iterate over all variants of a family.
     pub fn select_family(&self, family: &str) -> impl Iterator<Item = usize> + '_ {
         self.families
@@ -112,6 +222,45 @@ impl FontBook {
         self.find_best_variant(like, variant, ids)
     }
 
+    /// Like [`select_fallback`](Self::select_fallback), but yields all
+    /// suitable fonts from the best to the worst match, so that the caller can
+    /// move on if a font fails to load.
+    pub fn select_fallbacks<'a>(
+        &'a self,
+        like: Option<&'a FontInfo>,
+        variant: FontVariant,
+        text: &str,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let c = text.chars().find(|c| !c.is_whitespace());
+        let ids = c
+            .map(|c| {
+                self.infos
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, info)| info.coverage.contains(c as u32))
+                    .map(|(index, _)| index)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.ranked(like, variant, ids)
+    }
+
+    /// Yield the given fonts from the best to the worst match according to
+    /// [`find_best_variant`](Self::find_best_variant).
+    fn ranked<'a>(
+        &'a self,
+        like: Option<&'a FontInfo>,
+        variant: FontVariant,
+        mut ids: Vec<usize>,
+    ) -> impl Iterator<Item = usize> + 'a {
+        std::iter::from_fn(move || {
+            let best = self.find_best_variant(like, variant, ids.iter().copied())?;
+            ids.retain(|&id| id != best);
+            Some(best)
+        })
+    }
+
     /// Find the font in the passed iterator that
     /// - is closest to the font `like` (if any)
     /// - is closest to the given `variant`
@@ -133,6 +282,9 @@ impl FontBook {
     ///   normal.
     /// - The absolute distance to the target stretch.
     /// - The absolute distance to the target weight.
+    /// - Whether the font is shadowed by a font with the same family and
+    ///   variant.
+    /// - The font's priority.
     fn find_best_variant(
         &self,
         like: Option<&FontInfo>,
@@ -158,6 +310,8 @@ impl FontBook {
                 current.variant.style.distance(variant.style),
                 current.variant.stretch.distance(variant.stretch),
                 current.variant.weight.distance(variant.weight),
+                self.shadowed[id].is_some(),
+                Reverse(self.priorities[id]),
             );
 
             if best_key.map_or(true, |b| key < b) {
@@ -170,6 +324,63 @@ impl FontBook {
     }
 }
 
+/// Builds a [`FontBook`] together with the fonts it describes.
+///
+/// The fonts are returned in the order of the book's indices, so that a
+/// [`World`](crate::World) can serve them from a plain vector.
+#[derive(Debug, Default, Clone)]
+pub struct FontBookBuilder {
+    book: FontBook,
+    fonts: Vec<Font>,
+}
+
+impl FontBookBuilder {
+    /// Add a font with the given priority.
+    pub fn add(mut self, font: Font, priority: i32) -> Self {
+        self.book.push_with_priority(font.info().clone(), priority);
+        self.fonts.push(font);
+        self
+    }
+
+    /// Add an instance of a variable font pinned to the given variation
+    /// coordinates, optionally under a different family name.
+    ///
+    /// If the font cannot be pinned (e.g. because it lacks one of the axes),
+    /// this is recorded as a [problem](FontBook::problems) instead.
+    pub fn add_variable(
+        mut self,
+        font: Font,
+        coords: &[(Tag, f32)],
+        family_alias: Option<&str>,
+    ) -> Self {
+        match font.pin(coords, family_alias) {
+            Ok(instance) => self.add(instance, 0),
+            Err(reason) => {
+                let index = font.index();
+                self.book.push_problem(FontProblem { path: None, index, reason });
+                self
+            }
+        }
+    }
+
+    /// Finish the book and return it together with its fonts.
+    pub fn build(self) -> (FontBook, Vec<Font>) {
+        (self.book, self.fonts)
+    }
+}
+
+/// A font face that was found, but could not be loaded.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FontProblem {
+    /// The path of the file the face is stored in, if known.
+    pub path: Option<PathBuf>,
+    /// The index of the face in its collection. Zero if the file is not a
+    /// collection.
+    pub index: u32,
+    /// Why the face could not be loaded.
+    pub reason: EcoString,
+}
+
 /// Properties of a single font.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FontInfo {
@@ -182,6 +393,65 @@ pub struct FontInfo {
     pub flags: FontFlags,
     /// The unicode coverage of the font.
     pub coverage: Coverage,
+    /// The font's revision from its `head` table, as a 16.16 fixed-point
+    /// number. Used to prefer newer versions of otherwise identical fonts.
+    #[serde(default)]
+    pub revision: u32,
+    /// Whether the font's license permits embedding it into documents.
+    #[serde(default)]
+    pub embedding: EmbeddingPermissions,
+}
+
+/// How a font's license permits embedding it into documents, as declared in
+/// the `fsType` field of its `OS/2` table.
+///
+/// These are ordered from the most to the least permissive.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmbeddingPermissions {
+    /// The font may be embedded and even be installed permanently on the
+    /// system that displays the document.
+    #[default]
+    Installable,
+    /// The font may be embedded into documents that are edited.
+    Editable,
+    /// The font may only be embedded into documents that are viewed and
+    /// printed, but not edited.
+    PreviewPrint,
+    /// Only the font's bitmaps may be embedded, not its outlines.
+    BitmapOnly,
+    /// The font may not be embedded at all.
+    NoEmbedding,
+}
+
+impl EmbeddingPermissions {
+    /// Interpret the `fsType` field of a font's `OS/2` table.
+    ///
+    /// Older fonts may set multiple of the usage bits, in which case the
+    /// least restrictive one applies.
+    pub fn from_fs_type(fs_type: u16) -> Self {
+        let usage = if fs_type & 0x0008 != 0 {
+            Self::Editable
+        } else if fs_type & 0x0004 != 0 {
+            Self::PreviewPrint
+        } else if fs_type & 0x0002 != 0 {
+            Self::NoEmbedding
+        } else {
+            Self::Installable
+        };
+
+        if usage != Self::NoEmbedding && fs_type & 0x0200 != 0 {
+            Self::BitmapOnly
+        } else {
+            usage
+        }
+    }
+
+    /// Whether the font's outlines may be embedded into documents.
+    pub fn allows_outlines(self) -> bool {
+        self <= Self::PreviewPrint
+    }
 }
 
 bitflags::bitflags! {
@@ -200,8 +470,15 @@ bitflags::bitflags! {
 impl FontInfo {
     /// Compute metadata for font at the `index` of the given data.
     pub fn new(data: &[u8], index: u32) -> Option<Self> {
-        let ttf = ttf_parser::Face::parse(data, index).ok()?;
-        Self::from_ttf(&ttf)
+        Self::load(data, index).ok()
+    }
+
+    /// Compute metadata for font at the `index` of the given data, explaining
+    /// why that failed if it did.
+    pub fn load(data: &[u8], index: u32) -> Result<Self, EcoString> {
+        let ttf = ttf_parser::Face::parse(data, index)
+            .map_err(|err| eco_format!("failed to parse font ({err})"))?;
+        Self::from_checked_ttf(&ttf)
     }
 
     /// Compute metadata for all fonts in the given data.
@@ -210,6 +487,23 @@ impl FontInfo {
         (0..count).filter_map(move |index| Self::new(data, index))
     }
 
+    /// Compute metadata for a single ttf-parser face after checking that the
+    /// face is sound enough to be used.
+    pub(super) fn from_checked_ttf(ttf: &ttf_parser::Face) -> Result<Self, EcoString> {
+        // Broken variation axes would lead to garbled text, so we'd rather
+        // not use such a font at all.
+        for axis in ttf.variation_axes() {
+            if !(axis.min_value..=axis.max_value).contains(&axis.def_value) {
+                return Err(eco_format!(
+                    "font has an invalid variation axis `{}`",
+                    axis.tag
+                ));
+            }
+        }
+
+        Self::from_ttf(ttf).ok_or_else(|| "font has no family name".into())
+    }
+
     /// Compute metadata for a single ttf-parser face.
     pub(super) fn from_ttf(ttf: &ttf_parser::Face) -> Option<Self> {
         let ps_name = find_name(ttf, name_id::POST_SCRIPT_NAME);
@@ -284,11 +578,31 @@ impl FontInfo {
             }
         }
 
+        // Determine the font's revision.
+        let revision = ttf
+            .raw_face()
+            .table(Tag::from_bytes(b"head"))
+            .and_then(|head| head.get(4..8))
+            .map_or(0, |bytes| u32::from_be_bytes(bytes.try_into().unwrap()));
+
+        // Determine the embedding permissions.
+        let embedding = ttf
+            .raw_face()
+            .table(Tag::from_bytes(b"OS/2"))
+            .and_then(|os2| os2.get(8..10))
+            .map_or(EmbeddingPermissions::Installable, |bytes| {
+                EmbeddingPermissions::from_fs_type(u16::from_be_bytes(
+                    bytes.try_into().unwrap(),
+                ))
+            });
+
         Some(FontInfo {
             family,
             variant,
             flags,
             coverage: Coverage::from_vec(codepoints),
+            revision,
+            embedding,
         })
     }
 
@@ -471,6 +785,16 @@ impl Coverage {
         false
     }
 
+    /// The number of covered codepoints.
+    pub fn len(&self) -> usize {
+        self.0.iter().skip(1).step_by(2).map(|&run| run as usize).sum()
+    }
+
+    /// Whether no codepoints are covered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Iterate over all covered codepoints.
     pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
         let mut inside = false;
@@ -543,4 +867,60 @@ mod tests {
         let coverage = Coverage::from_vec(codepoints.clone());
         assert_eq!(coverage.iter().collect::<Vec<_>>(), codepoints);
     }
+
+    fn info(family: &str, revision: u32, codepoints: &[u32]) -> FontInfo {
+        FontInfo {
+            family: family.into(),
+            variant: FontVariant::default(),
+            flags: FontFlags::empty(),
+            coverage: Coverage::from_vec(codepoints.to_vec()),
+            revision,
+            embedding: EmbeddingPermissions::default(),
+        }
+    }
+
+    #[test]
+    fn test_embedding_permissions_from_fs_type() {
+        use EmbeddingPermissions as P;
+        assert_eq!(P::from_fs_type(0x0000), P::Installable);
+        assert_eq!(P::from_fs_type(0x0002), P::NoEmbedding);
+        assert_eq!(P::from_fs_type(0x0004), P::PreviewPrint);
+        assert_eq!(P::from_fs_type(0x0008), P::Editable);
+        assert_eq!(P::from_fs_type(0x0006), P::PreviewPrint);
+        assert_eq!(P::from_fs_type(0x0208), P::BitmapOnly);
+        assert_eq!(P::from_fs_type(0x0202), P::NoEmbedding);
+        assert!(P::PreviewPrint.allows_outlines());
+        assert!(!P::BitmapOnly.allows_outlines());
+    }
+
+    #[test]
+    fn test_duplicates_prefer_newer_revision() {
+        let old = info("Family", 1 << 16, &[65, 66, 67]);
+        let new = info("Family", 2 << 16, &[65, 66]);
+        let other = info("Other", 1 << 16, &[65]);
+        let variant = FontVariant::default();
+
+        let book = FontBook::from_infos([old.clone(), new.clone(), other.clone()]);
+        assert_eq!(book.select("family", variant), Some(1));
+        assert_eq!(book.select_fallback(None, variant, "A"), Some(1));
+        assert_eq!(book.duplicates().collect::<Vec<_>>(), [(0, 1)]);
+
+        let book = FontBook::from_infos([new, other, old]);
+        assert_eq!(book.select("family", variant), Some(0));
+        assert_eq!(book.select_fallback(None, variant, "A"), Some(0));
+        assert_eq!(book.duplicates().collect::<Vec<_>>(), [(2, 0)]);
+    }
+
+    #[test]
+    fn test_duplicates_prefer_larger_coverage() {
+        let small = info("Family", 0, &[65]);
+        let large = info("Family", 0, &[65, 66]);
+        let variant = FontVariant::default();
+
+        let book = FontBook::from_infos([small.clone(), large.clone()]);
+        assert_eq!(book.select("family", variant), Some(1));
+
+        let book = FontBook::from_infos([large, small]);
+        assert_eq!(book.select("family", variant), Some(0));
+    }
 }
@@ -6,7 +6,10 @@ mod book;
 mod exceptions;
 mod variant;
 
-pub use self::book::{Coverage, FontBook, FontFlags, FontInfo};
+pub use self::book::{
+    Coverage, EmbeddingPermissions, FontBook, FontBookBuilder, FontFlags, FontInfo,
+    FontProblem,
+};
 pub use self::variant::{FontStretch, FontStyle, FontVariant, FontWeight};
 
 use std::cell::OnceCell;
@@ -14,11 +17,12 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use ttf_parser::GlyphId;
+use ecow::{eco_format, EcoString};
+use ttf_parser::{GlyphId, Tag};
 
 use self::book::find_name;
 use crate::foundations::{Bytes, Cast};
-use crate::layout::{Abs, Em, Frame};
+use crate::layout::{Abs, Em, Frame, Ratio};
 use crate::text::{BottomEdge, TopEdge};
 
 /// An OpenType font.
@@ -39,6 +43,9 @@ struct Repr {
     ttf: ttf_parser::Face<'static>,
     /// The underlying rustybuzz face.
     rusty: rustybuzz::Face<'static>,
+    /// The variation coordinates this instance is pinned to. Empty for static
+    /// fonts and variable fonts at their default instance.
+    variations: Vec<(Tag, f32)>,
     // NOTE: `ttf` and `rusty` reference `data`, so it's important for `data`
     // to be dropped after them or they will be left dangling while they're
     // dropped. Fields are dropped in declaration order, so `data` needs to be
@@ -52,6 +59,12 @@ struct Repr {
 impl Font {
     /// Parse a font from data and collection index.
     pub fn new(data: Bytes, index: u32) -> Option<Self> {
+        Self::load(data, index).ok()
+    }
+
+    /// Parse a font from data and collection index, explaining why that failed
+    /// if it did.
+    pub fn load(data: Bytes, index: u32) -> Result<Self, EcoString> {
         // Safety:
         // - The slices's location is stable in memory:
         //   - We don't move the underlying vector
@@ -61,18 +74,106 @@ impl Font {
         let slice: &'static [u8] =
             unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
 
-        let ttf = ttf_parser::Face::parse(slice, index).ok()?;
-        let rusty = rustybuzz::Face::from_slice(slice, index)?;
+        let ttf = ttf_parser::Face::parse(slice, index)
+            .map_err(|err| eco_format!("failed to parse font ({err})"))?;
+        let info = FontInfo::from_checked_ttf(&ttf)?;
+        let rusty = rustybuzz::Face::from_slice(slice, index)
+            .ok_or("failed to prepare font for shaping")?;
         let metrics = FontMetrics::from_ttf(&ttf);
-        let info = FontInfo::from_ttf(&ttf)?;
 
-        Some(Self(Arc::new(Repr { data, index, info, metrics, ttf, rusty })))
+        Ok(Self(Arc::new(Repr {
+            data,
+            index,
+            info,
+            metrics,
+            ttf,
+            rusty,
+            variations: vec![],
+        })))
+    }
+
+    /// Pin a variable font to the given variation coordinates, yielding a font
+    /// that behaves like a static instance during selection, shaping, and
+    /// export.
+    ///
+    /// The instance's weight, stretch, and style are derived from the `wght`,
+    /// `wdth`, `ital`, and `slnt` coordinates. If a `family` is given, the
+    /// instance is registered under that name instead of the font's own.
+    pub fn pin(
+        &self,
+        coords: &[(Tag, f32)],
+        family: Option<&str>,
+    ) -> Result<Self, EcoString> {
+        let data = self.0.data.clone();
+        let index = self.0.index;
+
+        // Safety: See `Font::load`.
+        let slice: &'static [u8] =
+            unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
+
+        let mut ttf = ttf_parser::Face::parse(slice, index)
+            .map_err(|err| eco_format!("failed to parse font ({err})"))?;
+        for &(tag, value) in coords {
+            ttf.set_variation(tag, value)
+                .ok_or_else(|| eco_format!("font has no variation axis `{tag}`"))?;
+        }
+
+        let mut rusty = rustybuzz::Face::from_slice(slice, index)
+            .ok_or("failed to prepare font for shaping")?;
+        let variations: Vec<_> = coords
+            .iter()
+            .map(|&(tag, value)| rustybuzz::Variation { tag, value })
+            .collect();
+        rusty.set_variations(&variations);
+
+        let mut info = self.0.info.clone();
+        if let Some(family) = family {
+            info.family = family.into();
+        }
+        for &(tag, value) in coords {
+            match &tag.to_bytes() {
+                b"wght" => info.variant.weight = FontWeight::from_number(value as u16),
+                b"wdth" => {
+                    info.variant.stretch =
+                        FontStretch::from_ratio(Ratio::new(value as f64 / 100.0))
+                }
+                b"ital" if value >= 1.0 => info.variant.style = FontStyle::Italic,
+                b"slnt" if value != 0.0 => info.variant.style = FontStyle::Oblique,
+                _ => {}
+            }
+        }
+
+        let metrics = FontMetrics::from_ttf(&ttf);
+
+        Ok(Self(Arc::new(Repr {
+            data,
+            index,
+            info,
+            metrics,
+            ttf,
+            rusty,
+            variations: coords.to_vec(),
+        })))
     }
 
     /// Parse all fonts in the given data.
     pub fn iter(data: Bytes) -> impl Iterator<Item = Self> {
+        Self::iter_checked(data).filter_map(Result::ok)
+    }
+
+    /// Parse all fonts in the given data, yielding a problem for each face
+    /// that could not be loaded.
+    ///
+    /// The problems have no path since the data's origin is unknown here.
+    pub fn iter_checked(data: Bytes) -> impl Iterator<Item = Result<Self, FontProblem>> {
         let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
-        (0..count).filter_map(move |index| Self::new(data.clone(), index))
+        (0..count).map(move |index| {
+            Self::load(data.clone(), index).map_err(|reason| FontProblem {
+                path: None,
+                index,
+                reason,
+            })
+        })
     }
 
     /// The underlying buffer.
@@ -85,11 +186,21 @@ impl Font {
         self.0.index
     }
 
+    /// The variation coordinates this font is pinned to, if any.
+    pub fn variations(&self) -> &[(Tag, f32)] {
+        &self.0.variations
+    }
+
     /// The font's metadata.
     pub fn info(&self) -> &FontInfo {
         &self.0.info
     }
 
+    /// Whether the font's license permits embedding it into documents.
+    pub fn embedding_permissions(&self) -> EmbeddingPermissions {
+        self.0.info.embedding
+    }
+
     /// The font's metrics.
     pub fn metrics(&self) -> &FontMetrics {
         &self.0.metrics
@@ -118,6 +229,16 @@ impl Font {
         find_name(&self.0.ttf, id)
     }
 
+    /// Whether the font has an OpenType layout feature with the given tag,
+    /// e.g. `tnum` for tabular figures.
+    pub fn has_feature(&self, tag: Tag) -> bool {
+        let tables = self.ttf().tables();
+        [tables.gsub, tables.gpos]
+            .into_iter()
+            .flatten()
+            .any(|table| table.features.find(tag).is_some())
+    }
+
     /// A reference to the underlying `ttf-parser` face.
     pub fn ttf(&self) -> &ttf_parser::Face<'_> {
         // We can't implement Deref because that would leak the
@@ -179,6 +300,10 @@ impl Hash for Font {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.data.hash(state);
         self.0.index.hash(state);
+        for (tag, value) in &self.0.variations {
+            tag.hash(state);
+            value.to_bits().hash(state);
+        }
     }
 }
 
@@ -192,7 +317,15 @@ impl Eq for Font {}
 
 impl PartialEq for Font {
     fn eq(&self, other: &Self) -> bool {
-        self.0.data == other.0.data && self.0.index == other.0.index
+        self.0.data == other.0.data
+            && self.0.index == other.0.index
+            && self.0.variations.len() == other.0.variations.len()
+            && self
+                .0
+                .variations
+                .iter()
+                .zip(&other.0.variations)
+                .all(|((t1, v1), (t2, v2))| t1 == t2 && v1.to_bits() == v2.to_bits())
     }
 }
 
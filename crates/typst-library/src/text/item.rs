@@ -4,7 +4,7 @@ use std::ops::Range;
 use ecow::EcoString;
 use typst_syntax::Span;
 
-use crate::layout::{Abs, Em};
+use crate::layout::{Abs, Em, Point};
 use crate::text::{is_default_ignorable, Font, Lang, Region};
 use crate::visualize::{FixedStroke, Paint};
 
@@ -35,6 +35,42 @@ impl TextItem {
     pub fn width(&self) -> Abs {
         self.glyphs.iter().map(|g| g.x_advance).sum::<Em>().at(self.size)
     }
+
+    /// Whether `next`, placed at `next_pos`, directly continues this run
+    /// placed at `pos` and is rendered with the same properties, so that the
+    /// two can be merged into one run.
+    ///
+    /// Gradient and tiling paints are laid out relative to the text they
+    /// fill, so runs using them are never merged.
+    pub(crate) fn continues_into(
+        &self,
+        pos: Point,
+        next: &Self,
+        next_pos: Point,
+    ) -> bool {
+        let solid = |paint: &Paint| matches!(paint, Paint::Solid(_));
+        pos.y.approx_eq(next_pos.y)
+            && (pos.x + self.width()).approx_eq(next_pos.x)
+            && self.font == next.font
+            && self.size == next.size
+            && self.fill == next.fill
+            && self.stroke == next.stroke
+            && self.lang == next.lang
+            && self.region == next.region
+            && solid(&self.fill)
+            && self.stroke.as_ref().map_or(true, |stroke| solid(&stroke.paint))
+            && self.text.len() + next.text.len() <= usize::from(u16::MAX)
+    }
+
+    /// Append the glyphs and text of another run to this one.
+    pub(crate) fn append(&mut self, next: Self) {
+        let offset = self.text.len() as u16;
+        self.text.push_str(&next.text);
+        self.glyphs.extend(next.glyphs.into_iter().map(|mut glyph| {
+            glyph.range = glyph.range.start + offset..glyph.range.end + offset;
+            glyph
+        }));
+    }
 }
 
 impl Debug for TextItem {
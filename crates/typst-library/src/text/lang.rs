@@ -247,6 +247,13 @@ pub trait LocalName {
     {
         Self::local_name(TextElem::lang_in(styles), TextElem::region_in(styles))
     }
+
+    /// Get the name in the form used in references, e.g. `рис.` instead of
+    /// `Рис.` in Russian. This is the same as the local name in languages
+    /// without a distinct form.
+    fn local_reference_name(lang: Lang, region: Option<Region>) -> &'static str {
+        localized_reference_str(lang, region, Self::KEY)
+    }
 }
 
 /// Retrieves the localized string for a given language and region.
@@ -267,6 +274,24 @@ pub fn localized_str(lang: Lang, region: Option<Region>, key: &str) -> &'static
     english_bundle.get(key).unwrap()
 }
 
+/// Retrieves the localized string for a given key in the form used in
+/// references. These are stored under the key with a `.reference` suffix.
+/// Falls back to [`localized_str`] if the language has no such form.
+#[comemo::memoize]
+pub fn localized_reference_str(
+    lang: Lang,
+    region: Option<Region>,
+    key: &str,
+) -> &'static str {
+    let reference = eco_format!("{key}.reference");
+    [region, None]
+        .into_iter()
+        .find_map(|region| {
+            parse_language_bundle(lang, region).unwrap().get(&*reference).copied()
+        })
+        .unwrap_or_else(|| localized_str(lang, region, key))
+}
+
 /// Parses the translation file for a given language and region.
 /// Only returns an error if the language file is malformed.
 #[comemo::memoize]
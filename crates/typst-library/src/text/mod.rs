@@ -1,7 +1,9 @@
 //! Text handling.
 
 mod case;
+mod collation;
 mod deco;
+mod fallback;
 mod font;
 mod item;
 mod lang;
@@ -10,13 +12,16 @@ mod linebreak;
 mod lorem_;
 mod raw;
 mod shift;
+mod shorthand;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
 mod smartquote;
 mod space;
 
 pub use self::case::*;
+pub use self::collation::*;
 pub use self::deco::*;
+pub use self::fallback::*;
 pub use self::font::*;
 pub use self::item::*;
 pub use self::lang::*;
@@ -24,6 +29,7 @@ pub use self::linebreak::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
 pub use self::shift::*;
+pub use self::shorthand::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
 pub use self::space::*;
@@ -32,6 +38,7 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::sync::LazyLock;
 
+use comemo::Tracked;
 use ecow::{eco_format, EcoString};
 use icu_properties::sets::CodePointSetData;
 use icu_provider::AsDeserializingBufferProvider;
@@ -39,15 +46,15 @@ use icu_provider_blob::BlobDataProvider;
 use rustybuzz::Feature;
 use smallvec::SmallVec;
 use ttf_parser::Tag;
-use typst_syntax::Spanned;
-use typst_utils::singleton;
+use typst_syntax::{Span, Spanned};
+use typst_utils::{singleton, Numeric};
 
-use crate::diag::{bail, warning, HintedStrResult, SourceResult};
+use crate::diag::{bail, warning, At, HintedStrResult, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, category, dict, elem, Args, Array, Cast, Category, Construct, Content, Dict,
-    Fold, IntoValue, NativeElement, Never, NoneValue, Packed, PlainText, Regex, Repr,
-    Resolve, Scope, Set, Smart, StyleChain,
+    cast, category, dict, elem, func, scope, Args, Array, Cast, Category, Construct,
+    Content, Context, Dict, Fold, IntoValue, NativeElement, Never, NoneValue, Packed,
+    PlainText, Regex, Repr, Resolve, Scope, Set, Smart, StyleChain,
 };
 use crate::layout::{Abs, Axis, Dir, Em, Length, Ratio, Rel};
 use crate::math::{EquationElem, MathSize};
@@ -95,7 +102,7 @@ pub(super) fn define(global: &mut Scope) {
 ///   With a function call.
 /// ])
 /// ```
-#[elem(Debug, Construct, PlainText, Repr)]
+#[elem(scope, Debug, Construct, PlainText, Repr)]
 pub struct TextElem {
     /// A font family descriptor or priority list of font family descriptor.
     ///
@@ -303,6 +310,11 @@ pub struct TextElem {
 
     /// The amount of space that should be added between characters.
     ///
+    /// No space is added after the last character of a line, so that tracked
+    /// text still ends flush with the margin when it is right-aligned or
+    /// justified. Unless configured otherwise, standard
+    /// [ligatures]($text.ligatures) are disabled for tracked text.
+    ///
     /// ```example
     /// #set text(tracking: 1.5pt)
     /// Distant text.
@@ -340,6 +352,35 @@ pub struct TextElem {
     #[ghost]
     pub cjk_latin_spacing: Smart<Option<Never>>,
 
+    /// How to distribute space in justified lines of CJK text.
+    ///
+    /// - `{auto}`: Lines that consist predominantly of CJK characters are
+    ///   justified by inter-character spacing, other lines first stretch the
+    ///   spaces between words.
+    /// - `{"inter-character"}`: Extra space is always distributed evenly
+    ///   between all characters and words, so that the few spaces around
+    ///   Latin words in CJK text don't become much wider than the gaps
+    ///   between CJK characters.
+    /// - `{"inter-word"}`: Extra space is only inserted between words, like in
+    ///   Latin text.
+    ///
+    /// Like [`cjk-latin-spacing`]($text.cjk-latin-spacing), this property is
+    /// read at the paragraph level.
+    ///
+    /// ```example
+    /// #set page(width: 160pt)
+    /// #set par(justify: true)
+    /// #set text(lang: "zh", font: "Noto Serif CJK SC")
+    ///
+    /// #set text(cjk-justify: "inter-character")
+    /// 我们使用 Typst 排版中文，效果非常好。
+    ///
+    /// #set text(cjk-justify: "inter-word")
+    /// 我们使用 Typst 排版中文，效果非常好。
+    /// ```
+    #[ghost]
+    pub cjk_justify: Smart<CjkJustify>,
+
     /// An amount to shift the text baseline by.
     ///
     /// ```example
@@ -576,6 +617,28 @@ pub struct TextElem {
     #[ghost]
     pub kerning: bool,
 
+    /// Manual kerning for specific pairs of characters.
+    ///
+    /// Maps pairs of characters, given as two-character strings, to an amount
+    /// of space that is added between them. Negative amounts move the
+    /// characters closer together. The amounts are added on top of the
+    /// font's own kerning and apply even if [`kerning`]($text.kerning) is
+    /// disabled.
+    ///
+    /// Adjustments from multiple set rules are combined. If a pair is given
+    /// again, the innermost amount wins.
+    ///
+    /// ```example
+    /// #set text(size: 25pt)
+    /// AVATAR
+    ///
+    /// #set text(kerning-adjustments: ("AV": -0.05em))
+    /// AVATAR
+    /// ```
+    #[fold]
+    #[ghost]
+    pub kerning_adjustments: KerningAdjustments,
+
     /// Whether to apply stylistic alternates.
     ///
     /// Sometimes fonts contain alternative glyphs for the same codepoint.
@@ -621,6 +684,10 @@ pub struct TextElem {
     /// these ligatures by turning off the OpenType `liga` and `clig` font
     /// features.
     ///
+    /// When set to `{auto}`, ligatures are active unless the text is
+    /// [tracked]($text.tracking), as spreading out the letters of a ligature
+    /// is typographically not possible.
+    ///
     /// ```example
     /// #set text(size: 20pt)
     /// A fine ligature.
@@ -628,9 +695,8 @@ pub struct TextElem {
     /// #set text(ligatures: false)
     /// A fine ligature.
     /// ```
-    #[default(true)]
     #[ghost]
-    pub ligatures: bool,
+    pub ligatures: Smart<bool>,
 
     /// Whether ligatures that should be used sparingly are active. Setting this
     /// to `{true}` enables the OpenType `dlig` font feature.
@@ -644,9 +710,37 @@ pub struct TextElem {
     #[ghost]
     pub historical_ligatures: bool,
 
+    /// Character sequences within which no ligatures are formed.
+    ///
+    /// This keeps specific ligatures from forming while leaving all others
+    /// active, for example when a brand's style guide forbids the "Th"
+    /// ligature of a font. Shaping behaves as if a zero-width non-joiner was
+    /// placed between the characters of each listed sequence, but the text
+    /// itself is not changed, so searching and copying it from an exported
+    /// PDF works as usual. Ligatures that do not join across these places
+    /// still form. With the exception `{"fi"}`, "office" thus keeps its "ff"
+    /// ligature.
+    ///
+    /// Exceptions from multiple set rules are combined.
+    ///
+    /// ```example
+    /// #set text(size: 20pt)
+    /// fish and fjord
+    ///
+    /// #set text(ligature-exceptions: ("fi",))
+    /// fish and fjord
+    /// ```
+    #[fold]
+    #[ghost]
+    pub ligature_exceptions: LigatureExceptions,
+
     /// Which kind of numbers / figures to select. When set to `{auto}`, the
     /// default numbers for the font are used.
     ///
+    /// Old-style figures are selected with the OpenType `onum` font feature.
+    /// If the font does not support it, Typst emits a warning and the font's
+    /// default figures are used.
+    ///
     /// ```example
     /// #set text(font: "Noto Sans", 20pt)
     /// #set text(number-type: "lining")
@@ -661,6 +755,11 @@ pub struct TextElem {
     /// The width of numbers / figures. When set to `{auto}`, the default
     /// numbers for the font are used.
     ///
+    /// Tabular figures are selected with the OpenType `tnum` font feature. If
+    /// the font does not support it and its digits have different widths,
+    /// Typst emits a warning and widens all digits to the width of the widest
+    /// one, so that numbers in a column still line up.
+    ///
     /// ```example
     /// #set text(font: "Noto Sans", 20pt)
     /// #set text(number-width: "proportional")
@@ -699,6 +798,29 @@ pub struct TextElem {
     #[ghost]
     pub fractions: bool,
 
+    /// Whether to apply smart typographic substitutions.
+    ///
+    /// When enabled, the [shorthands]($syntax/#markup) `--`, `---`, and `...`
+    /// turn into an en dash, an em dash, and an ellipsis, and straight quotes
+    /// turn into [smart quotes]($smartquote). Disabling this keeps the text as
+    /// it is written, which is useful for command line flags and similar
+    /// technical text outside of [raw] blocks. Ligatures and other font
+    /// features are not affected.
+    ///
+    /// These substitutions happen when the text is shown rather than when the
+    /// markup is parsed. This way, they can be disabled for any part of the
+    /// document, including content that was passed to a function.
+    ///
+    /// ```example
+    /// Use --verbose -- "or not"... \
+    /// #text(smart: false)[
+    ///   Use --verbose -- "or not"...
+    /// ]
+    /// ```
+    #[default(true)]
+    #[ghost]
+    pub smart: bool,
+
     /// Raw OpenType features to apply.
     ///
     /// - If given an array of strings, sets the features identified by the
@@ -768,6 +890,55 @@ impl TextElem {
     }
 }
 
+#[scope]
+impl TextElem {
+    /// Explains which font is chosen for a character with the current text
+    /// settings and why the fonts before it are skipped.
+    ///
+    /// This is only available when the `font-debug` feature is enabled.
+    ///
+    /// Returns an array with a dictionary for each font family of the
+    /// [`font`]($text.font) list that was considered and for each font found
+    /// by the automatic [fallback]($text.fallback), in order. The
+    /// dictionaries have the following keys:
+    /// - `family`: The name of the family.
+    /// - `fallback`: Whether the font was found by the automatic fallback.
+    /// - `found`: Whether the family is among the available fonts.
+    /// - `loaded`: Whether the font could be loaded.
+    /// - `used`: Whether the font was skipped because it was already tried.
+    /// - `exact-variant`: Whether the font has exactly the requested weight,
+    ///   style, and stretch. Otherwise, the closest variant of the family is
+    ///   used.
+    /// - `covers`: Whether the font has a glyph for the character and its
+    ///   family's `covers` setting includes it.
+    /// - `selected`: Whether the font is chosen for the character.
+    ///
+    /// ```typ
+    /// #context text.explain("ऋ")
+    /// ```
+    #[func(contextual)]
+    pub fn explain(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite context.
+        context: Tracked<Context>,
+        /// The callsite span.
+        span: Span,
+        /// The character to explain the font choice for.
+        character: char,
+    ) -> SourceResult<Array> {
+        if !engine.world.library().features.is_enabled(crate::Feature::FontDebug) {
+            bail!(
+                span,
+                "`text.explain` is only available when `--features font-debug` is passed"
+            );
+        }
+
+        let styles = context.styles().at(span)?;
+        Ok(explain_font_choice(engine.world, styles, character).to_array())
+    }
+}
+
 impl Debug for TextElem {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "Text({})", self.text)
@@ -1177,6 +1348,15 @@ cast! {
     },
 }
 
+/// How to justify lines of CJK text.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum CjkJustify {
+    /// Distribute extra space evenly between all characters and words.
+    InterCharacter,
+    /// Only insert extra space between words.
+    InterWord,
+}
+
 /// Which kind of numbers / figures to select.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum NumberType {
@@ -1235,6 +1415,95 @@ impl Fold for FontFeatures {
     }
 }
 
+/// Character sequences within which no ligatures are formed.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct LigatureExceptions(pub Vec<EcoString>);
+
+impl LigatureExceptions {
+    /// Checks that a sequence spans multiple characters.
+    fn check(sequence: EcoString) -> HintedStrResult<EcoString> {
+        if sequence.chars().nth(1).is_none() {
+            bail!(
+                "ligature exception must consist of at least two characters";
+                hint: "a ligature always joins multiple characters"
+            );
+        }
+        Ok(sequence)
+    }
+}
+
+cast! {
+    LigatureExceptions,
+    self => self.0.into_value(),
+    v: EcoString => Self(vec![Self::check(v)?]),
+    v: Array => Self(v
+        .into_iter()
+        .map(|v| Self::check(v.cast()?))
+        .collect::<HintedStrResult<_>>()?),
+}
+
+impl Fold for LigatureExceptions {
+    fn fold(self, mut outer: Self) -> Self {
+        for sequence in self.0 {
+            if !outer.0.contains(&sequence) {
+                outer.0.push(sequence);
+            }
+        }
+        outer
+    }
+}
+
+/// Manual kerning for pairs of characters.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct KerningAdjustments(pub Vec<([char; 2], Length)>);
+
+impl KerningAdjustments {
+    /// The adjustment between two clusters of text, if each of them is one
+    /// character and they form a configured pair.
+    pub fn get(&self, first: &str, second: &str) -> Option<Length> {
+        self.0
+            .iter()
+            .find(|([a, b], _)| first.chars().eq([*a]) && second.chars().eq([*b]))
+            .map(|&(_, amount)| amount)
+    }
+}
+
+cast! {
+    KerningAdjustments,
+    self => self.0
+        .into_iter()
+        .map(|([a, b], amount)| (eco_format!("{a}{b}").into(), amount.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    v: Dict => Self(v
+        .into_iter()
+        .map(|(key, amount)| {
+            let mut chars = key.chars();
+            let (Some(a), Some(b), None) = (chars.next(), chars.next(), chars.next())
+            else {
+                bail!(
+                    "kerning pair must consist of exactly two characters, found {}",
+                    key.repr();
+                    hint: "write the pair as a string like \"AV\""
+                );
+            };
+            Ok(([a, b], amount.cast()?))
+        })
+        .collect::<HintedStrResult<_>>()?),
+}
+
+impl Fold for KerningAdjustments {
+    fn fold(self, mut outer: Self) -> Self {
+        for (pair, amount) in self.0 {
+            match outer.0.iter_mut().find(|(p, _)| *p == pair) {
+                Some(entry) => entry.1 = amount,
+                None => outer.0.push((pair, amount)),
+            }
+        }
+        outer
+    }
+}
+
 /// Collect the OpenType features to apply.
 pub fn features(styles: StyleChain) -> Vec<Feature> {
     let mut tags = vec![];
@@ -1261,7 +1530,9 @@ pub fn features(styles: StyleChain) -> Vec<Feature> {
         feat(&storage, 1);
     }
 
-    if !TextElem::ligatures_in(styles) {
+    let ligatures = TextElem::ligatures_in(styles)
+        .unwrap_or_else(|| TextElem::tracking_in(styles).is_zero());
+    if !ligatures {
         feat(b"liga", 0);
         feat(b"clig", 0);
     }
@@ -1423,11 +1694,10 @@ fn check_font_list(engine: &mut Engine, list: &Spanned<FontList>) {
 
             engine.sink.warn(warning);
         } else if !found {
-            engine.sink.warn(warning!(
-                list.span,
-                "unknown font family: {}",
-                family.as_str(),
-            ));
+            engine.sink.warn(
+                warning!(list.span, "unknown font family: {}", family.as_str())
+                    .with_code("unknown-font-family"),
+            );
         }
     }
 }
@@ -6,15 +6,16 @@ use std::sync::{Arc, LazyLock};
 use ecow::{eco_format, EcoString, EcoVec};
 use syntect::highlighting::{self as synt, Theme};
 use syntect::parsing::{SyntaxDefinition, SyntaxSet, SyntaxSetBuilder};
-use typst_syntax::{split_newlines, LinkedNode, Span, Spanned};
+use typst_syntax::{is_newline, split_newlines, LinkedNode, Span, Spanned};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::Lang;
-use crate::diag::{At, FileError, HintedStrResult, SourceResult, StrResult};
+use crate::diag::{bail, At, FileError, HintedStrResult, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, Args, Array, Bytes, Content, Fold, NativeElement, Packed,
-    PlainText, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, TargetElem, Value,
+    array, cast, elem, scope, Args, Array, Bytes, Content, Fold, NativeElement, Packed,
+    PlainText, Regex, Show, ShowSet, Smart, StyleChain, Styles, Synthesize, TargetElem,
+    Value,
 };
 use crate::html::{tag, HtmlElem};
 use crate::layout::{BlockBody, BlockElem, Em, HAlignment};
@@ -268,6 +269,37 @@ pub struct RawElem {
     #[default(2)]
     pub tab_size: usize,
 
+    /// Substitutions to apply to the raw text before it is highlighted.
+    ///
+    /// Each substitution is an array of a pattern (a string or a
+    /// [regular expression]($regex)) and a replacement string. Within each
+    /// line, the earliest match of any pattern is replaced (preferring
+    /// patterns that come first in case of a tie) and replaced text is not
+    /// substituted again. Since substitutions happen before highlighting, the
+    /// highlighter sees the replaced text. This keeps the highlighting of the
+    /// surrounding code intact, which is not the case when using text show
+    /// rules on raw text.
+    ///
+    /// Patterns never match across lines and replacements may not contain
+    /// line breaks, so that the lines of the raw text stay as they are.
+    /// Positions within replaced text are mapped back to the start of the
+    /// original match, so jumping from the output to the source is only
+    /// approximate in lines with substitutions.
+    ///
+    /// ````example
+    /// #set raw(mapping: (
+    ///   ("->", "→"),
+    ///   (regex("!=|/="), "≠"),
+    /// ))
+    ///
+    /// ```rust
+    /// fn check(x: i32) -> bool {
+    ///     x != 0
+    /// }
+    /// ```
+    /// ````
+    pub mapping: Vec<RawSubstitution>,
+
     /// The stylized lines of raw text.
     ///
     /// Made accessible for the [`raw.line` element]($raw.line).
@@ -316,6 +348,9 @@ impl Packed<RawElem> {
     fn highlight(&self, styles: StyleChain) -> Vec<Packed<RawLine>> {
         let elem = self.as_ref();
         let lines = preprocess(elem.text(), styles, self.span());
+        let (lines, maps) = substitute(lines, &elem.mapping(styles));
+        let original =
+            |i: usize, offset| maps.get(i).map_or(offset, |m| m.original(offset));
 
         let count = lines.len() as i64;
         let lang = elem
@@ -374,6 +409,7 @@ impl Packed<RawElem> {
                     let span_offset = text[..range.start]
                         .rfind('\n')
                         .map_or(0, |i| range.start - (i + 1));
+                    let span_offset = original(i, span_offset);
                     styled(&text[range], foreground, style, span, span_offset)
                 },
                 &mut |i, range, line| {
@@ -414,7 +450,7 @@ impl Packed<RawElem> {
                         foreground,
                         style,
                         line_span,
-                        span_offset,
+                        original(i, span_offset),
                     ));
                     span_offset += piece.len();
                 }
@@ -532,6 +568,70 @@ cast! {
     v: EcoString => Self::Text(v),
 }
 
+/// A substitution applied to raw text before highlighting.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct RawSubstitution {
+    /// The text to search for.
+    pub pattern: RawPattern,
+    /// The text to replace matches with.
+    pub replacement: EcoString,
+}
+
+cast! {
+    RawSubstitution,
+    self => Value::Array(array![self.pattern.into_value(), self.replacement]),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(pattern), Some(replacement), None) => {
+                let pattern = pattern.cast::<RawPattern>()?;
+                let replacement = replacement.cast::<EcoString>()?;
+                if replacement.contains(is_newline) {
+                    bail!("replacement must not contain line breaks");
+                }
+                Self { pattern, replacement }
+            }
+            _ => bail!("substitution must be an array of a pattern and a replacement"),
+        }
+    },
+}
+
+/// A pattern for a [`RawSubstitution`].
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum RawPattern {
+    /// Matches the string literally.
+    Str(EcoString),
+    /// Matches the regular expression.
+    Regex(Regex),
+}
+
+impl RawPattern {
+    /// Find the first match in `text` starting at or after `start`.
+    fn find_at(&self, text: &str, start: usize) -> Option<Range<usize>> {
+        match self {
+            Self::Str(pat) => text[start..]
+                .find(pat.as_str())
+                .map(|i| start + i..start + i + pat.len()),
+            Self::Regex(regex) => regex.find_at(text, start).map(|m| m.range()),
+        }
+    }
+}
+
+cast! {
+    RawPattern,
+    self => match self {
+        Self::Str(v) => v.into_value(),
+        Self::Regex(v) => v.into_value(),
+    },
+    v: EcoString => {
+        if v.is_empty() {
+            bail!("pattern must not be empty");
+        }
+        Self::Str(v)
+    },
+    v: Regex => Self::Regex(v),
+}
+
 /// A highlighted line of raw text.
 ///
 /// This is a helper element that is synthesized by [`raw`] elements.
@@ -696,6 +796,76 @@ fn preprocess(
         .collect()
 }
 
+/// Applies the raw mapping to each line, also returning how to map offsets in
+/// the substituted lines back to the original ones.
+fn substitute(
+    lines: EcoVec<(EcoString, Span)>,
+    mapping: &[RawSubstitution],
+) -> (EcoVec<(EcoString, Span)>, Vec<OffsetMap>) {
+    if mapping.is_empty() {
+        return (lines, vec![]);
+    }
+
+    let mut maps = Vec::with_capacity(lines.len());
+    let lines = lines
+        .into_iter()
+        .map(|(line, span)| {
+            let mut map = OffsetMap::default();
+            let mut out = EcoString::with_capacity(line.len());
+            let mut cursor = 0;
+            while let Some((range, replacement)) = mapping
+                .iter()
+                .filter_map(|sub| {
+                    sub.pattern.find_at(&line, cursor).map(|r| (r, &sub.replacement))
+                })
+                .min_by_key(|(range, _)| range.start)
+            {
+                // Empty matches would loop forever, so we skip over them.
+                if range.is_empty() {
+                    match line[range.start..].chars().next() {
+                        Some(c) => {
+                            let end = range.start + c.len_utf8();
+                            out.push_str(&line[cursor..end]);
+                            cursor = end;
+                            continue;
+                        }
+                        None => break,
+                    }
+                }
+
+                out.push_str(&line[cursor..range.start]);
+                map.0.push((out.len(), range.start, true));
+                out.push_str(replacement);
+                map.0.push((out.len(), range.end, false));
+                cursor = range.end;
+            }
+            out.push_str(&line[cursor..]);
+            maps.push(map);
+            (out, span)
+        })
+        .collect();
+
+    (lines, maps)
+}
+
+/// Maps byte offsets in a substituted line back to the original line.
+///
+/// Holds the offsets in the substituted and the original line at which
+/// replacements and unchanged text start, and whether it's a replacement.
+#[derive(Default)]
+struct OffsetMap(Vec<(usize, usize, bool)>);
+
+impl OffsetMap {
+    /// Map an offset in the substituted line to the original line.
+    fn original(&self, offset: usize) -> usize {
+        match self.0.iter().rev().find(|&&(new, ..)| new <= offset) {
+            Some(&(_, old, true)) => old,
+            Some(&(new, old, false)) => old + (offset - new),
+            None => offset,
+        }
+    }
+}
+
 /// Style a piece of text with a syntect style.
 fn styled(
     piece: &str,
@@ -0,0 +1,59 @@
+use ecow::{eco_format, EcoString};
+use typst_syntax::ast;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Packed, PlainText, Repr, Show, StyleChain};
+use crate::text::TextElem;
+
+/// A typographic substitution written with a markup shorthand: An en dash
+/// (`--`), an em dash (`---`), or an ellipsis (`...`).
+///
+/// The substitution is decided when the element is shown rather than when
+/// the markup is parsed, so that it can be turned off with
+/// [`text.smart`]($text.smart) for any part of the document. When it is
+/// turned off, the shorthand is shown as written. Math shorthands are not
+/// affected and still evaluate to symbols.
+#[elem(Show, PlainText, Repr)]
+pub struct ShorthandElem {
+    /// The shorthand as written in markup.
+    #[required]
+    pub text: EcoString,
+}
+
+impl ShorthandElem {
+    /// The markup shorthands that are only substituted in smart text.
+    pub const SMART: &'static [&'static str] = &["--", "---", "..."];
+
+    /// The character that the shorthand stands for.
+    pub fn substitute(&self) -> char {
+        ast::Shorthand::LIST
+            .iter()
+            .find(|&&(s, _)| s == self.text().as_str())
+            .map_or(char::REPLACEMENT_CHARACTER, |&(_, c)| c)
+    }
+}
+
+impl Show for Packed<ShorthandElem> {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let text = if TextElem::smart_in(styles) {
+            self.substitute().into()
+        } else {
+            self.text().clone()
+        };
+        Ok(TextElem::packed(text).spanned(self.span()))
+    }
+}
+
+impl Repr for ShorthandElem {
+    fn repr(&self) -> EcoString {
+        // Matches the text that the shorthand used to evaluate to.
+        eco_format!("[{}]", self.substitute())
+    }
+}
+
+impl PlainText for Packed<ShorthandElem> {
+    fn plain_text(&self, text: &mut EcoString) {
+        text.push(self.substitute());
+    }
+}
@@ -1177,7 +1177,13 @@ impl Color {
             acc.map(|v| v / total)
         };
 
-        Ok(match space {
+        Ok(Self::from_vec4(space, m))
+    }
+
+    /// Construct a color in the given space from four floats, in the format
+    /// produced by [`to_vec4`](Self::to_vec4).
+    pub fn from_vec4(space: ColorSpace, m: [f32; 4]) -> Self {
+        match space {
             ColorSpace::Oklab => Color::Oklab(Oklab::new(m[0], m[1], m[2], m[3])),
             ColorSpace::Oklch => Color::Oklch(Oklch::new(m[0], m[1], m[2], m[3])),
             ColorSpace::Srgb => Color::Rgb(Rgb::new(m[0], m[1], m[2], m[3])),
@@ -1192,7 +1198,7 @@ impl Color {
             }
             ColorSpace::Cmyk => Color::Cmyk(Cmyk::new(m[0], m[1], m[2], m[3])),
             ColorSpace::D65Gray => Color::Luma(Luma::new(m[0], m[3])),
-        })
+        }
     }
 
     /// Construct a new RGBA color from 8-bit values.
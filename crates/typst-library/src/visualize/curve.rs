@@ -8,7 +8,7 @@ use crate::foundations::{
     cast, elem, Content, NativeElement, Packed, Show, Smart, StyleChain,
 };
 use crate::layout::{Abs, Axes, BlockElem, Length, Point, Rel, Size};
-use crate::visualize::{FillRule, Paint, Stroke};
+use crate::visualize::{FillRule, Marker, Paint, Stroke};
 
 /// A curve consisting of movements, lines, and Beziér segments.
 ///
@@ -88,6 +88,25 @@ pub struct CurveElem {
     #[fold]
     pub stroke: Smart<Option<Stroke>>,
 
+    /// A marker at the start of the curve's first segment.
+    ///
+    /// The marker is omitted if the segment is closed. See the
+    /// [line's `marker-end`]($line.marker-end) for the available markers.
+    pub marker_start: Option<Marker>,
+
+    /// A marker at the end of the curve's last segment.
+    ///
+    /// The marker is rotated along the curve's tangent at its end.
+    ///
+    /// ```example
+    /// #curve(
+    ///   marker-end: "arrow",
+    ///   curve.move((0pt, 30pt)),
+    ///   curve.quad((30pt, 0pt), (60pt, 30pt)),
+    /// )
+    /// ```
+    pub marker_end: Option<Marker>,
+
     /// The components of the curve, in the form of moves, line and Beziér
     /// segment, and closes.
     #[variadic]
@@ -465,6 +484,24 @@ impl Curve {
         self.0.is_empty()
     }
 
+    /// Whether the curve draws any segments, as opposed to only moving the
+    /// cursor.
+    pub fn has_segments(&self) -> bool {
+        self.0
+            .iter()
+            .any(|item| matches!(item, CurveItem::Line(_) | CurveItem::Cubic(..)))
+    }
+
+    /// Whether all points of the curve are finite.
+    pub fn is_finite(&self) -> bool {
+        let finite = |p: &Point| p.x.is_finite() && p.y.is_finite();
+        self.0.iter().all(|item| match item {
+            CurveItem::Move(p) | CurveItem::Line(p) => finite(p),
+            CurveItem::Cubic(p1, p2, p3) => finite(p1) && finite(p2) && finite(p3),
+            CurveItem::Close => true,
+        })
+    }
+
     /// Translate all points in this curve by the given offset.
     pub fn translate(&mut self, offset: Point) {
         if offset.is_zero() {
@@ -486,6 +523,10 @@ impl Curve {
 
     /// Computes the size of the bounding box of this curve.
     pub fn bbox_size(&self) -> Size {
+        if self.is_empty() {
+            return Size::zero();
+        }
+
         let mut min_x = Abs::inf();
         let mut min_y = Abs::inf();
         let mut max_x = -Abs::inf();
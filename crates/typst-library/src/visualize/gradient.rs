@@ -6,10 +6,12 @@ use std::sync::Arc;
 use ecow::EcoString;
 use kurbo::Vec2;
 use typst_syntax::{Span, Spanned};
+use typst_utils::Scalar;
 
 use crate::diag::{bail, SourceResult};
 use crate::foundations::{
-    array, cast, func, scope, ty, Args, Array, Cast, Func, IntoValue, Repr, Smart,
+    array, cast, dict, func, scope, ty, Args, Array, Cast, Dict, Func, IntoValue, Repr,
+    Smart, Value,
 };
 use crate::layout::{Angle, Axes, Dir, Quadrant, Ratio};
 use crate::visualize::{Color, ColorSpace, WeightedColor};
@@ -139,6 +141,42 @@ use crate::visualize::{Color, ColorSpace, WeightedColor};
 /// }
 /// ```
 ///
+/// # Hue interpolation
+/// In color spaces with a hue component ([Oklch]($color.oklch),
+/// [HSL]($color.hsl), and [HSV]($color.hsv)), there are two ways around the
+/// hue circle from one color to another. By default, gradients take the
+/// shorter one. With the `hue` argument, you can instead take the longer one
+/// or always go in the direction of increasing or decreasing hue.
+///
+/// ```example
+/// #set block(height: 20pt, width: 100%)
+/// #let a = color.oklch(70%, 0.15, 30deg)
+/// #let b = color.oklch(70%, 0.15, 90deg)
+/// #block(fill: gradient.linear(a, b, space: color.oklch))
+/// #block(fill: gradient.linear(a, b, space: color.oklch, hue: "longer"))
+/// ```
+///
+/// # Easing
+/// By default, the color changes at a constant rate between two stops. A stop
+/// can instead specify an easing as a third entry, after its offset. The
+/// easing then applies between this stop and the next one. It is either one
+/// of the presets `{"linear"}`, `{"ease-in"}`, `{"ease-out"}`, and
+/// `{"ease-in-out"}` or a positive [float] exponent. With an exponent `p`, the
+/// color at the fraction `t` of the way to the next stop is the one that a
+/// linear segment would have at `t` to the power of `p`. The `{"ease-in"}`
+/// preset is the same as an exponent of `{2.0}`. An exponent can also be
+/// applied from the end of the segment with a dictionary like
+/// `{(exponent: 3.0, reversed: true)}`. Then, the remaining fraction `1 - t`
+/// is raised to the power instead, so `{"ease-out"}` is the same as
+/// `{(exponent: 2.0, reversed: true)}`.
+///
+/// ```example
+/// #set block(height: 20pt, width: 100%)
+/// #block(fill: gradient.linear((red, 0%), (blue, 100%)))
+/// #block(fill: gradient.linear((red, 0%, "ease-in"), (blue, 100%)))
+/// #block(fill: gradient.linear((red, 0%, "ease-in-out"), (blue, 100%)))
+/// ```
+///
 /// # Direction
 /// Some gradients are sensitive to direction. For example, a linear gradient
 /// has an angle that determines its direction. Typst uses a clockwise angle,
@@ -175,6 +213,8 @@ use crate::visualize::{Color, ColorSpace, WeightedColor};
 ///   in between. This avoids needing to encode these color spaces in your PDF
 ///   file, but it does add extra stops to your gradient, which can increase
 ///   the file size.
+/// - Eased segments are likewise stored with extra stops in between, in both
+///   PDF and SVG files.
 #[ty(scope, cast)]
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Gradient {
@@ -214,6 +254,13 @@ impl Gradient {
         #[named]
         #[default(ColorSpace::Oklab)]
         space: ColorSpace,
+        /// How to interpolate the hue in color spaces with a hue component.
+        ///
+        /// See the section on [hue interpolation](#hue-interpolation) for
+        /// details.
+        #[named]
+        #[default(Spanned::new(HueInterpolation::Shorter, Span::detached()))]
+        hue: Spanned<HueInterpolation>,
         /// The [relative placement](#relativeness) of the gradient.
         ///
         /// For an element placed at the root/top level of the document, the
@@ -251,10 +298,13 @@ impl Gradient {
             );
         }
 
+        let (stops, easings) = process_stops(&stops)?;
         Ok(Self::Linear(Arc::new(LinearGradient {
-            stops: process_stops(&stops)?,
+            stops,
+            easings,
             angle,
             space,
+            hue: check_hue(space, hue)?,
             relative,
             anti_alias: true,
         })))
@@ -304,6 +354,13 @@ impl Gradient {
         #[named]
         #[default(ColorSpace::Oklab)]
         space: ColorSpace,
+        /// How to interpolate the hue in color spaces with a hue component.
+        ///
+        /// See the section on [hue interpolation](#hue-interpolation) for
+        /// details.
+        #[named]
+        #[default(Spanned::new(HueInterpolation::Shorter, Span::detached()))]
+        hue: Spanned<HueInterpolation>,
         /// The [relative placement](#relativeness) of the gradient.
         ///
         /// For an element placed at the root/top level of the document, the parent
@@ -373,13 +430,16 @@ impl Gradient {
             );
         }
 
+        let (stops, easings) = process_stops(&stops)?;
         Ok(Gradient::Radial(Arc::new(RadialGradient {
-            stops: process_stops(&stops)?,
+            stops,
+            easings,
             center: center.map(From::from),
             radius: radius.v,
             focal_center,
             focal_radius: focal_radius.v,
             space,
+            hue: check_hue(space, hue)?,
             relative,
             anti_alias: true,
         })))
@@ -423,6 +483,13 @@ impl Gradient {
         #[named]
         #[default(ColorSpace::Oklab)]
         space: ColorSpace,
+        /// How to interpolate the hue in color spaces with a hue component.
+        ///
+        /// See the section on [hue interpolation](#hue-interpolation) for
+        /// details.
+        #[named]
+        #[default(Spanned::new(HueInterpolation::Shorter, Span::detached()))]
+        hue: Spanned<HueInterpolation>,
         /// The [relative placement](#relativeness) of the gradient.
         ///
         /// For an element placed at the root/top level of the document, the parent
@@ -446,11 +513,14 @@ impl Gradient {
             );
         }
 
+        let (stops, easings) = process_stops(&stops)?;
         Ok(Gradient::Conic(Arc::new(ConicGradient {
-            stops: process_stops(&stops)?,
+            stops,
+            easings,
             angle,
             center: center.map(From::from),
             space,
+            hue: check_hue(space, hue)?,
             relative,
             anti_alias: true,
         })))
@@ -524,29 +594,38 @@ impl Gradient {
 
         stops.dedup();
 
+        // The easing is already part of the sampled colors.
+        let easings = vec![Easing::Linear; stops.len()];
+
         Ok(match self {
             Self::Linear(linear) => Self::Linear(Arc::new(LinearGradient {
                 stops,
+                easings,
                 angle: linear.angle,
                 space: linear.space,
+                hue: linear.hue,
                 relative: linear.relative,
                 anti_alias: false,
             })),
             Self::Radial(radial) => Self::Radial(Arc::new(RadialGradient {
                 stops,
+                easings,
                 center: radial.center,
                 radius: radial.radius,
                 focal_center: radial.focal_center,
                 focal_radius: radial.focal_radius,
                 space: radial.space,
+                hue: radial.hue,
                 relative: radial.relative,
                 anti_alias: false,
             })),
             Self::Conic(conic) => Self::Conic(Arc::new(ConicGradient {
                 stops,
+                easings,
                 angle: conic.angle,
                 center: conic.center,
                 space: conic.space,
+                hue: conic.hue,
                 relative: conic.relative,
                 anti_alias: false,
             })),
@@ -556,6 +635,11 @@ impl Gradient {
     /// Repeats this gradient a given number of times, optionally mirroring it
     /// at each repetition.
     ///
+    /// When mirroring, the [easings](#easing) of the segments are mirrored
+    /// as well, so that each repetition is an exact mirror image of the
+    /// previous one. An `{"ease-in"}` segment, for example, becomes an
+    /// `{"ease-out"}` segment.
+    ///
     /// ```example
     /// #circle(
     ///   radius: 40pt,
@@ -583,20 +667,28 @@ impl Gradient {
             .take(n)
             .enumerate()
             .flat_map(|(i, stops)| {
+                let mirrored = i % 2 == 1 && mirror;
                 let mut stops = stops
                     .iter()
-                    .map(move |&(color, offset)| {
+                    .enumerate()
+                    .map(move |(k, &(color, offset))| {
                         let t = i as f64 / n as f64;
                         let r = offset.get();
-                        if i % 2 == 1 && mirror {
-                            (color, Ratio::new(t + (1.0 - r) / n as f64))
+                        if mirrored {
+                            // In reverse, the segment starting at this stop
+                            // is the one that ends at it otherwise, and its
+                            // easing curve is traversed backwards.
+                            let easing = k
+                                .checked_sub(1)
+                                .map_or(Easing::Linear, |k| self.easing(k).reversed());
+                            (color, Ratio::new(t + (1.0 - r) / n as f64), easing)
                         } else {
-                            (color, Ratio::new(t + r / n as f64))
+                            (color, Ratio::new(t + r / n as f64), self.easing(k))
                         }
                     })
                     .collect::<Vec<_>>();
 
-                if i % 2 == 1 && mirror {
+                if mirrored {
                     stops.reverse();
                 }
 
@@ -604,31 +696,50 @@ impl Gradient {
             })
             .collect::<Vec<_>>();
 
-        stops.dedup();
+        // When dropping a duplicate stop, the segment that started at it now
+        // starts at the one that is kept.
+        stops.dedup_by(|next, prev| {
+            let duplicate = next.0 == prev.0 && next.1 == prev.1;
+            if duplicate {
+                prev.2 = next.2;
+            }
+            duplicate
+        });
+
+        let (stops, easings): (Vec<_>, Vec<_>) = stops
+            .into_iter()
+            .map(|(color, offset, easing)| ((color, offset), easing))
+            .unzip();
 
         Ok(match self {
             Self::Linear(linear) => Self::Linear(Arc::new(LinearGradient {
                 stops,
+                easings,
                 angle: linear.angle,
                 space: linear.space,
+                hue: linear.hue,
                 relative: linear.relative,
                 anti_alias: linear.anti_alias,
             })),
             Self::Radial(radial) => Self::Radial(Arc::new(RadialGradient {
                 stops,
+                easings,
                 center: radial.center,
                 radius: radial.radius,
                 focal_center: radial.focal_center,
                 focal_radius: radial.focal_radius,
                 space: radial.space,
+                hue: radial.hue,
                 relative: radial.relative,
                 anti_alias: radial.anti_alias,
             })),
             Self::Conic(conic) => Self::Conic(Arc::new(ConicGradient {
                 stops,
+                easings,
                 angle: conic.angle,
                 center: conic.center,
                 space: conic.space,
+                hue: conic.hue,
                 relative: conic.relative,
                 anti_alias: conic.anti_alias,
             })),
@@ -648,32 +759,15 @@ impl Gradient {
     /// Returns the stops of this gradient.
     #[func]
     pub fn stops(&self) -> Vec<GradientStop> {
-        match self {
-            Self::Linear(linear) => linear
-                .stops
-                .iter()
-                .map(|(color, offset)| GradientStop {
-                    color: *color,
-                    offset: Some(*offset),
-                })
-                .collect(),
-            Self::Radial(radial) => radial
-                .stops
-                .iter()
-                .map(|(color, offset)| GradientStop {
-                    color: *color,
-                    offset: Some(*offset),
-                })
-                .collect(),
-            Self::Conic(conic) => conic
-                .stops
-                .iter()
-                .map(|(color, offset)| GradientStop {
-                    color: *color,
-                    offset: Some(*offset),
-                })
-                .collect(),
-        }
+        self.stops_ref()
+            .iter()
+            .zip(self.easings_ref())
+            .map(|(&(color, offset), &easing)| GradientStop {
+                color,
+                offset: Some(offset),
+                easing,
+            })
+            .collect()
     }
 
     /// Returns the mixing space of this gradient.
@@ -686,6 +780,16 @@ impl Gradient {
         }
     }
 
+    /// Returns the hue interpolation of this gradient.
+    #[func]
+    pub fn hue(&self) -> HueInterpolation {
+        match self {
+            Self::Linear(linear) => linear.hue,
+            Self::Radial(radial) => radial.hue,
+            Self::Conic(conic) => conic.hue,
+        }
+    }
+
     /// Returns the relative placement of this gradient.
     #[func]
     pub fn relative(&self) -> Smart<RelativeTo> {
@@ -767,13 +871,27 @@ impl Gradient {
         /// The position at which to sample the gradient.
         t: RatioOrAngle,
     ) -> Color {
-        let value: f64 = t.to_ratio().get();
+        let t = t.to_ratio().get().clamp(0.0, 1.0);
+        let stops = self.stops_ref();
+        let mut low = 0;
+        let mut high = stops.len();
+
+        while low < high {
+            let mid = (low + high) / 2;
+            if stops[mid].1.get() < t {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
 
-        match self {
-            Self::Linear(linear) => sample_stops(&linear.stops, linear.space, value),
-            Self::Radial(radial) => sample_stops(&radial.stops, radial.space, value),
-            Self::Conic(conic) => sample_stops(&conic.stops, conic.space, value),
+        if low == 0 {
+            low = 1;
         }
+
+        let (_, pos_0) = stops[low - 1];
+        let (_, pos_1) = stops[low];
+        self.sample_segment(low - 1, (t - pos_0.get()) / (pos_1.get() - pos_0.get()))
     }
 
     /// Samples the gradient at multiple positions at once and returns the
@@ -790,6 +908,13 @@ impl Gradient {
 }
 
 impl Gradient {
+    /// The number of linear pieces with which exporters approximate a segment
+    /// whose colors do not change linearly, unless configured otherwise.
+    ///
+    /// See [`is_linear_segment`](Self::is_linear_segment) for which segments
+    /// this concerns.
+    pub const SEGMENT_SAMPLES: usize = 32;
+
     /// Clones this gradient, but with a different relative placement.
     pub fn with_relative(mut self, relative: RelativeTo) -> Self {
         match &mut self {
@@ -815,6 +940,40 @@ impl Gradient {
         }
     }
 
+    /// Returns a reference to the easings of this gradient's stops.
+    pub fn easings_ref(&self) -> &[Easing] {
+        match self {
+            Gradient::Linear(linear) => &linear.easings,
+            Gradient::Radial(radial) => &radial.easings,
+            Gradient::Conic(conic) => &conic.easings,
+        }
+    }
+
+    /// The easing between the stop with the given index and the next one.
+    pub fn easing(&self, index: usize) -> Easing {
+        self.easings_ref().get(index).copied().unwrap_or_default()
+    }
+
+    /// Whether the colors between the stop with the given index and the next
+    /// one change linearly in the gradient's color space. This is the case
+    /// unless the segment is eased or the space has a hue component.
+    ///
+    /// Export formats that interpolate linearly between stops need to
+    /// approximate all other segments with additional stops.
+    pub fn is_linear_segment(&self, index: usize) -> bool {
+        self.space().hue_index().is_none() && self.easing(index) == Easing::Linear
+    }
+
+    /// Samples the segment between the stop with the given index and the next
+    /// one. The position `t` is the fraction of the way between the two stops.
+    pub fn sample_segment(&self, index: usize, t: f64) -> Color {
+        let stops = self.stops_ref();
+        let (c0, _) = stops[index];
+        let (c1, _) = stops[index + 1];
+        let t = self.easing(index).apply(t);
+        mix(c0, c1, t, self.space(), self.hue())
+    }
+
     /// Samples the gradient at a given position, in the given container.
     /// Handles the aspect ratio and angle directly.
     pub fn sample_at(&self, (x, y): (f32, f32), (width, height): (f32, f32)) -> Color {
@@ -938,10 +1097,14 @@ impl Repr for Gradient {
 pub struct LinearGradient {
     /// The color stops of this gradient.
     pub stops: Vec<(Color, Ratio)>,
+    /// The easing of the segment that starts at the stop with the same index.
+    pub easings: Vec<Easing>,
     /// The direction of this gradient.
     pub angle: Angle,
     /// The color space in which to interpolate the gradient.
     pub space: ColorSpace,
+    /// How to interpolate the hue in color spaces with a hue component.
+    pub hue: HueInterpolation,
     /// The relative placement of the gradient.
     pub relative: Smart<RelativeTo>,
     /// Whether to anti-alias the gradient (used for sharp gradients).
@@ -973,6 +1136,12 @@ impl Repr for LinearGradient {
             r.push_str(", ");
         }
 
+        if self.hue != HueInterpolation::Shorter {
+            r.push_str("hue: ");
+            r.push_str(&self.hue.into_value().repr());
+            r.push_str(", ");
+        }
+
         if self.relative.is_custom() {
             r.push_str("relative: ");
             r.push_str(&self.relative.into_value().repr());
@@ -984,6 +1153,7 @@ impl Repr for LinearGradient {
             r.push_str(&color.repr());
             r.push_str(", ");
             r.push_str(&offset.repr());
+            push_easing(&mut r, self.easings[i]);
             r.push(')');
             if i != self.stops.len() - 1 {
                 r.push_str(", ");
@@ -1000,6 +1170,8 @@ impl Repr for LinearGradient {
 pub struct RadialGradient {
     /// The color stops of this gradient.
     pub stops: Vec<(Color, Ratio)>,
+    /// The easing of the segment that starts at the stop with the same index.
+    pub easings: Vec<Easing>,
     /// The center of last circle of this gradient.
     pub center: Axes<Ratio>,
     /// The radius of last circle of this gradient.
@@ -1010,6 +1182,8 @@ pub struct RadialGradient {
     pub focal_radius: Ratio,
     /// The color space in which to interpolate the gradient.
     pub space: ColorSpace,
+    /// How to interpolate the hue in color spaces with a hue component.
+    pub hue: HueInterpolation,
     /// The relative placement of the gradient.
     pub relative: Smart<RelativeTo>,
     /// Whether to anti-alias the gradient (used for sharp gradients).
@@ -1054,6 +1228,12 @@ impl Repr for RadialGradient {
             r.push_str(", ");
         }
 
+        if self.hue != HueInterpolation::Shorter {
+            r.push_str("hue: ");
+            r.push_str(&self.hue.into_value().repr());
+            r.push_str(", ");
+        }
+
         if self.relative.is_custom() {
             r.push_str("relative: ");
             r.push_str(&self.relative.into_value().repr());
@@ -1065,6 +1245,7 @@ impl Repr for RadialGradient {
             r.push_str(&color.repr());
             r.push_str(", ");
             r.push_str(&offset.repr());
+            push_easing(&mut r, self.easings[i]);
             r.push(')');
             if i != self.stops.len() - 1 {
                 r.push_str(", ");
@@ -1082,12 +1263,16 @@ impl Repr for RadialGradient {
 pub struct ConicGradient {
     /// The color stops of this gradient.
     pub stops: Vec<(Color, Ratio)>,
+    /// The easing of the segment that starts at the stop with the same index.
+    pub easings: Vec<Easing>,
     /// The direction of this gradient.
     pub angle: Angle,
     /// The center of last circle of this gradient.
     pub center: Axes<Ratio>,
     /// The color space in which to interpolate the gradient.
     pub space: ColorSpace,
+    /// How to interpolate the hue in color spaces with a hue component.
+    pub hue: HueInterpolation,
     /// The relative placement of the gradient.
     pub relative: Smart<RelativeTo>,
     /// Whether to anti-alias the gradient (used for sharp gradients).
@@ -1119,6 +1304,12 @@ impl Repr for ConicGradient {
             r.push_str(", ");
         }
 
+        if self.hue != HueInterpolation::Shorter {
+            r.push_str("hue: ");
+            r.push_str(&self.hue.into_value().repr());
+            r.push_str(", ");
+        }
+
         if self.relative.is_custom() {
             r.push_str("relative: ");
             r.push_str(&self.relative.into_value().repr());
@@ -1130,6 +1321,7 @@ impl Repr for ConicGradient {
             r.push_str(&color.repr());
             r.push_str(", ");
             r.push_str(&Angle::deg(offset.get() * 360.0).repr());
+            push_easing(&mut r, self.easings[i]);
             r.push(')');
             if i != self.stops.len() - 1 {
                 r.push_str(", ");
@@ -1157,35 +1349,149 @@ pub struct GradientStop {
     pub color: Color,
     /// The offset of the stop along the gradient.
     pub offset: Option<Ratio>,
+    /// The easing between this stop and the next one.
+    pub easing: Easing,
 }
 
 impl GradientStop {
     /// Create a new stop from a `color` and an `offset`.
     pub fn new(color: Color, offset: Ratio) -> Self {
-        Self { color, offset: Some(offset) }
+        Self {
+            color,
+            offset: Some(offset),
+            easing: Easing::Linear,
+        }
     }
 }
 
 cast! {
     GradientStop,
-    self => if let Some(offset) = self.offset {
-        array![self.color.into_value(), offset].into_value()
-    } else {
-        self.color.into_value()
+    self => match self.offset {
+        Some(offset) if self.easing != Easing::Linear => {
+            array![self.color.into_value(), offset, self.easing].into_value()
+        }
+        Some(offset) => array![self.color.into_value(), offset].into_value(),
+        None => self.color.into_value(),
     },
-    color: Color => Self { color, offset: None },
+    color: Color => Self { color, offset: None, easing: Easing::Linear },
     array: Array => {
         let mut iter = array.into_iter();
-        match (iter.next(), iter.next(), iter.next()) {
-            (Some(a), Some(b), None) => Self {
+        match (iter.next(), iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), c, None) => Self {
                 color: a.cast()?,
-                offset: Some(b.cast()?)
+                offset: Some(b.cast()?),
+                easing: c.map(Value::cast).transpose()?.unwrap_or_default(),
             },
-            _ => Err("a color stop must contain exactly two entries")?,
+            _ => Err("a color stop must contain two or three entries")?,
+        }
+    }
+}
+
+/// How the color progresses between two adjacent stops of a gradient.
+///
+/// See the section on [easing]($gradient/#easing) for details.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Easing {
+    /// The color changes at a constant rate.
+    #[default]
+    Linear,
+    /// The color changes slowly at first and then faster.
+    EaseIn,
+    /// The color changes quickly at first and then slower.
+    EaseOut,
+    /// The color changes slowly at both ends and faster in the middle.
+    EaseInOut,
+    /// The progress is raised to the given power.
+    Power(Scalar),
+    /// The remaining progress is raised to the given power.
+    ReversedPower(Scalar),
+}
+
+impl Easing {
+    /// Maps the linear progress between two stops to the eased progress.
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::EaseInOut if t < 0.5 => 2.0 * t * t,
+            Self::EaseInOut => 1.0 - 2.0 * (1.0 - t) * (1.0 - t),
+            Self::Power(p) => t.powf(p.get()),
+            Self::ReversedPower(p) => 1.0 - (1.0 - t).powf(p.get()),
+        }
+    }
+
+    /// The easing that produces the same curve when the segment is traversed
+    /// backwards.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Linear => Self::Linear,
+            Self::EaseIn => Self::EaseOut,
+            Self::EaseOut => Self::EaseIn,
+            Self::EaseInOut => Self::EaseInOut,
+            Self::Power(p) => Self::ReversedPower(p),
+            Self::ReversedPower(p) => Self::Power(p),
         }
     }
 }
 
+cast! {
+    Easing,
+    self => match self {
+        Self::Linear => "linear".into_value(),
+        Self::EaseIn => "ease-in".into_value(),
+        Self::EaseOut => "ease-out".into_value(),
+        Self::EaseInOut => "ease-in-out".into_value(),
+        Self::Power(p) => p.get().into_value(),
+        Self::ReversedPower(p) => dict! {
+            "exponent" => p.get(),
+            "reversed" => true,
+        }.into_value(),
+    },
+    /// The color changes at a constant rate.
+    "linear" => Self::Linear,
+    /// The color changes slowly at first and then faster.
+    "ease-in" => Self::EaseIn,
+    /// The color changes quickly at first and then slower.
+    "ease-out" => Self::EaseOut,
+    /// The color changes slowly at both ends and faster in the middle.
+    "ease-in-out" => Self::EaseInOut,
+    /// The progress between the stops is raised to this power.
+    v: f64 => {
+        if !(v > 0.0 && v.is_finite()) {
+            Err("easing exponent must be positive")?;
+        }
+        Self::Power(Scalar::new(v))
+    },
+    /// An exponent that is applied from the end of the segment, as in
+    /// `{(exponent: 3.0, reversed: true)}`.
+    mut v: Dict => {
+        let exponent: f64 = v.take("exponent")?.cast()?;
+        let reversed = v.take("reversed").ok().map(|v| v.cast()).transpose()?;
+        v.finish(&["exponent", "reversed"])?;
+        if !(exponent > 0.0 && exponent.is_finite()) {
+            Err("easing exponent must be positive")?;
+        }
+        let p = Scalar::new(exponent);
+        if reversed.unwrap_or(false) { Self::ReversedPower(p) } else { Self::Power(p) }
+    },
+}
+
+/// How to interpolate the hue of a gradient in color spaces with a hue
+/// component.
+#[derive(Cast, Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum HueInterpolation {
+    /// Takes the shorter way around the hue circle.
+    #[default]
+    Shorter,
+    /// Takes the longer way around the hue circle.
+    Longer,
+    /// Goes around the hue circle in the direction of increasing hue.
+    Increasing,
+    /// Goes around the hue circle in the direction of decreasing hue.
+    Decreasing,
+}
+
 /// A ratio or an angle.
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum RatioOrAngle {
@@ -1214,14 +1520,27 @@ cast! {
 }
 
 /// Pre-processes the stops, checking that they are valid and computing the
-/// offsets if necessary.
+/// offsets if necessary. Returns the stops and their easings.
 ///
 /// Returns an error if the stops are invalid.
 ///
 /// This is split into its own function because it is used by all of the
 /// different gradient types.
 #[comemo::memoize]
-fn process_stops(stops: &[Spanned<GradientStop>]) -> SourceResult<Vec<(Color, Ratio)>> {
+#[allow(clippy::type_complexity)]
+fn process_stops(
+    stops: &[Spanned<GradientStop>],
+) -> SourceResult<(Vec<(Color, Ratio)>, Vec<Easing>)> {
+    if let Some(Spanned { v: last, span }) = stops.last() {
+        if last.easing != Easing::Linear {
+            bail!(
+                *span, "the last stop cannot have an easing";
+                hint: "the easing of a stop applies between it and the next stop"
+            );
+        }
+    }
+
+    let easings = stops.iter().map(|stop| stop.v.easing).collect();
     let has_offset = stops.iter().any(|stop| stop.v.offset.is_some());
     if has_offset {
         let mut last_stop = f64::NEG_INFINITY;
@@ -1242,7 +1561,7 @@ fn process_stops(stops: &[Spanned<GradientStop>]) -> SourceResult<Vec<(Color, Ra
 
         let out = stops
             .iter()
-            .map(|Spanned { v: GradientStop { color, offset }, span }| {
+            .map(|Spanned { v: GradientStop { color, offset, .. }, span }| {
                 if offset.unwrap().get() > 1.0 || offset.unwrap().get() < 0.0 {
                     bail!(*span, "offset must be between 0 and 1");
                 }
@@ -1266,45 +1585,78 @@ fn process_stops(stops: &[Spanned<GradientStop>]) -> SourceResult<Vec<(Color, Ra
             );
         }
 
-        return Ok(out);
+        return Ok((out, easings));
     }
 
-    Ok(stops
+    let out = stops
         .iter()
         .enumerate()
         .map(|(i, stop)| {
             let offset = i as f64 / (stops.len() - 1) as f64;
             (stop.v.color, Ratio::new(offset))
         })
-        .collect())
+        .collect();
+
+    Ok((out, easings))
 }
 
-/// Sample the stops at a given position.
-fn sample_stops(stops: &[(Color, Ratio)], mixing_space: ColorSpace, t: f64) -> Color {
-    let t = t.clamp(0.0, 1.0);
-    let mut low = 0;
-    let mut high = stops.len();
+/// Checks that a hue interpolation other than the default is only used with
+/// color spaces that have a hue component.
+fn check_hue(
+    space: ColorSpace,
+    hue: Spanned<HueInterpolation>,
+) -> SourceResult<HueInterpolation> {
+    if hue.v != HueInterpolation::Shorter && space.hue_index().is_none() {
+        bail!(
+            hue.span, "hue interpolation requires a color space with a hue component";
+            hint: "try interpolating in `color.oklch`, `color.hsl`, or `color.hsv`"
+        );
+    }
+    Ok(hue.v)
+}
 
-    while low < high {
-        let mid = (low + high) / 2;
-        if stops[mid].1.get() < t {
-            low = mid + 1;
-        } else {
-            high = mid;
-        }
+/// Appends the easing of a stop to the stop's representation, if it has one.
+fn push_easing(r: &mut EcoString, easing: Easing) {
+    if easing != Easing::Linear {
+        r.push_str(", ");
+        r.push_str(&easing.into_value().repr());
     }
+}
 
-    if low == 0 {
-        low = 1;
+/// Mixes two colors in the given space, going around the hue circle as
+/// configured.
+fn mix(c0: Color, c1: Color, t: f64, space: ColorSpace, hue: HueInterpolation) -> Color {
+    let Some(index) = space.hue_index().filter(|_| hue != HueInterpolation::Shorter)
+    else {
+        return Color::mix_iter(
+            [WeightedColor::new(c0, 1.0 - t), WeightedColor::new(c1, t)],
+            space,
+        )
+        .unwrap();
+    };
+
+    let a = c0.to_space(space).to_vec4();
+    let b = c1.to_space(space).to_vec4();
+    let t = t as f32;
+    let mut m = [0.0; 4];
+    for i in 0..4 {
+        m[i] = a[i] + (b[i] - a[i]) * t;
     }
 
-    let (col_0, pos_0) = stops[low - 1];
-    let (col_1, pos_1) = stops[low];
-    let t = (t - pos_0.get()) / (pos_1.get() - pos_0.get());
+    // Adjust the hues such that going from one to the other in a straight
+    // line takes the configured way around the circle.
+    let (mut h0, mut h1) = (a[index], b[index]);
+    let delta = h1 - h0;
+    match hue {
+        HueInterpolation::Shorter => {}
+        HueInterpolation::Longer if delta > 0.0 && delta < 180.0 => h0 += 360.0,
+        HueInterpolation::Longer if delta > -180.0 && delta <= 0.0 => h1 += 360.0,
+        HueInterpolation::Longer => {}
+        HueInterpolation::Increasing if delta < 0.0 => h1 += 360.0,
+        HueInterpolation::Decreasing if delta > 0.0 => h0 += 360.0,
+        HueInterpolation::Increasing | HueInterpolation::Decreasing => {}
+    }
 
-    Color::mix_iter(
-        [WeightedColor::new(col_0, 1.0 - t), WeightedColor::new(col_1, t)],
-        mixing_space,
-    )
-    .unwrap()
+    m[index] = (h0 + (h1 - h0) * t).rem_euclid(360.0);
+    Color::from_vec4(space, m)
 }
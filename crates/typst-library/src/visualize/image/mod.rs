@@ -12,15 +12,15 @@ use std::sync::Arc;
 use comemo::Tracked;
 use ecow::EcoString;
 use typst_syntax::{Span, Spanned};
-use typst_utils::LazyHash;
+use typst_utils::{LazyHash, Scalar};
 
-use crate::diag::{At, SourceResult, StrResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, elem, func, scope, Bytes, Cast, Content, NativeElement, Packed, Show, Smart,
     StyleChain,
 };
-use crate::layout::{BlockElem, Length, Rel, Sizing};
+use crate::layout::{Axes, BlockElem, Length, Rel, Sizing};
 use crate::loading::Readable;
 use crate::model::Figurable;
 use crate::text::LocalName;
@@ -78,13 +78,46 @@ pub struct ImageElem {
     /// The height of the image.
     pub height: Sizing,
 
+    /// The resolution at which the image is laid out when neither its `width`
+    /// nor its `height` is given.
+    ///
+    /// When set to `{auto}`, the resolution embedded in the image is used. For
+    /// raster images without one and for SVGs, this is 72 pixels per inch. A
+    /// scan at 600 DPI thus appears at its physical size, while a screenshot
+    /// typically appears at the size it had on screen.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", dpi: 2000)
+    /// ```
+    pub dpi: Smart<Scalar>,
+
+    /// The maximum width of the image when it is sized naturally, that is,
+    /// when neither its `width` nor its `height` is given.
+    ///
+    /// Relative lengths are resolved against the width of the available space.
+    /// The image keeps its aspect ratio when it is scaled down. Explicit sizes
+    /// are never clamped. Regardless of this setting, a naturally sized image
+    /// never exceeds the available space.
+    ///
+    /// ```example
+    /// #image("tiger.jpg", max-width: 50%)
+    /// ```
+    pub max_width: Option<Rel<Length>>,
+
+    /// The maximum height of the image when it is sized naturally.
+    ///
+    /// Relative lengths are resolved against the height of the available
+    /// space. See [`max-width`]($image.max-width) for details.
+    pub max_height: Option<Rel<Length>>,
+
     /// A text describing the image.
     pub alt: Option<EcoString>,
 
     /// How the image should adjust itself to a given area (the area is defined
     /// by the `width` and `height` fields). Note that `fit` doesn't visually
     /// change anything if the area's aspect ratio is the same as the image's
-    /// one.
+    /// one. A naturally sized image always has its own aspect ratio, so `fit`
+    /// only matters if the `width` or the `height` is given.
     ///
     /// ```example
     /// #set page(width: 300pt, height: 50pt, margin: 10pt)
@@ -95,11 +128,45 @@ pub struct ImageElem {
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
 
+    /// A hint to viewers how they should scale the image.
+    ///
+    /// When set to `{auto}`, the default is left up to the viewer. For PNG
+    /// export, Typst will default to smooth scaling, like most PDF and SVG
+    /// viewers.
+    ///
+    /// _Note:_ The exact look may differ across PDF viewers.
+    ///
+    /// ```example
+    /// #set image(width: 40pt)
+    /// #image("pixel-art.png", scaling: "smooth")
+    /// #image("pixel-art.png", scaling: "pixelated")
+    /// ```
+    pub scaling: Smart<ImageScaling>,
+
     /// Whether text in SVG images should be converted into curves before
     /// embedding. This will result in the text becoming unselectable in the
     /// output.
     #[default(false)]
     pub flatten_text: bool,
+
+    /// Whether an SVG image is embedded as a raster image instead of as
+    /// vector graphics.
+    ///
+    /// - `{auto}`: The SVG is rasterized at 300 DPI if it uses features that
+    ///   cannot be rendered faithfully or if it has more than 10 000 elements.
+    ///   Very large SVGs otherwise make the output large and slow to render.
+    /// - `{"never"}`: The SVG is always embedded as vector graphics.
+    /// - `{"always"}`: The SVG is always rasterized at 300 DPI.
+    /// - A float: The SVG is always rasterized at the given resolution in
+    ///   pixels per inch of its size in the document.
+    ///
+    /// The rasterized image is used by all export formats. Raster images are
+    /// not affected by this setting.
+    ///
+    /// ```example
+    /// #image("diagram.svg", width: 50%, rasterize: 72)
+    /// ```
+    pub rasterize: Smart<ImageRasterize>,
 }
 
 #[scope]
@@ -137,6 +204,9 @@ impl ImageElem {
         /// How the image should adjust itself to a given area.
         #[named]
         fit: Option<ImageFit>,
+        /// A hint to viewers how they should scale the image.
+        #[named]
+        scaling: Option<Smart<ImageScaling>>,
     ) -> StrResult<Content> {
         let mut elem = ImageElem::new(EcoString::new(), data);
         if let Some(format) = format {
@@ -154,6 +224,9 @@ impl ImageElem {
         if let Some(fit) = fit {
             elem.push_fit(fit);
         }
+        if let Some(scaling) = scaling {
+            elem.push_scaling(scaling);
+        }
         Ok(elem.pack().spanned(span))
     }
 }
@@ -191,6 +264,46 @@ pub enum ImageFit {
     Stretch,
 }
 
+/// The image scaling algorithm a viewer should use.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ImageScaling {
+    /// Scale with a smoothing algorithm such as bilinear interpolation.
+    Smooth,
+    /// Scale with nearest neighbor or a similar algorithm to preserve the
+    /// pixelated look of the image.
+    Pixelated,
+}
+
+/// Whether an SVG image is rasterized.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ImageRasterize {
+    /// Always embed the SVG as vector graphics.
+    Never,
+    /// Always rasterize at [`Image::RASTER_DPI`].
+    Always,
+    /// Always rasterize at the given resolution in pixels per inch.
+    Dpi(Scalar),
+}
+
+cast! {
+    ImageRasterize,
+    self => match self {
+        Self::Never => "never".into_value(),
+        Self::Always => "always".into_value(),
+        Self::Dpi(dpi) => dpi.get().into_value(),
+    },
+    /// Always embed the SVG as vector graphics.
+    "never" => Self::Never,
+    /// Always rasterize the SVG at 300 DPI.
+    "always" => Self::Always,
+    v: f64 => {
+        if !(v.is_finite() && v > 0.0) {
+            bail!("dpi must be positive");
+        }
+        Self::Dpi(Scalar::new(v))
+    },
+}
+
 /// A loaded raster or vector image.
 ///
 /// Values of this type are cheap to clone and hash.
@@ -204,6 +317,8 @@ struct Repr {
     kind: ImageKind,
     /// A text describing the image.
     alt: Option<EcoString>,
+    /// The scaling algorithm to use.
+    scaling: Smart<ImageScaling>,
 }
 
 /// A kind of image.
@@ -223,6 +338,13 @@ impl Image {
     /// Should always be the same as the default DPI used by usvg.
     pub const USVG_DEFAULT_DPI: f64 = 96.0;
 
+    /// The resolution at which SVGs are rasterized unless a different one is
+    /// requested.
+    pub const RASTER_DPI: f64 = 300.0;
+
+    /// SVGs with more elements than this are rasterized automatically.
+    pub const MAX_VECTOR_ELEMENTS: usize = 10_000;
+
     /// Create an image from a buffer and a format.
     #[comemo::memoize]
     #[typst_macros::time(name = "load image")]
@@ -240,7 +362,7 @@ impl Image {
             }
         };
 
-        Ok(Self(Arc::new(LazyHash::new(Repr { kind, alt }))))
+        Ok(Self(Arc::new(LazyHash::new(Repr { kind, alt, scaling: Smart::Auto }))))
     }
 
     /// Create a possibly font-dependent image from a buffer and a format.
@@ -253,6 +375,7 @@ impl Image {
         world: Tracked<dyn World + '_>,
         families: &[&str],
         flatten_text: bool,
+        scaling: Smart<ImageScaling>,
     ) -> StrResult<Image> {
         let kind = match format {
             ImageFormat::Raster(format) => {
@@ -263,7 +386,7 @@ impl Image {
             }
         };
 
-        Ok(Self(Arc::new(LazyHash::new(Repr { kind, alt }))))
+        Ok(Self(Arc::new(LazyHash::new(Repr { kind, alt, scaling }))))
     }
 
     /// The raw image data.
@@ -311,10 +434,34 @@ impl Image {
         self.0.alt.as_deref()
     }
 
+    /// The image scaling algorithm to use for this image.
+    pub fn scaling(&self) -> Smart<ImageScaling> {
+        self.0.scaling
+    }
+
     /// The decoded image.
     pub fn kind(&self) -> &ImageKind {
         &self.0.kind
     }
+
+    /// Renders an SVG image into a raster image with the given pixel size,
+    /// keeping its alternative text and scaling. Raster images are returned
+    /// as is.
+    ///
+    /// The rasterization itself is provided by the [`World`].
+    pub fn rasterized(
+        &self,
+        world: Tracked<dyn World + '_>,
+        size: Axes<u32>,
+    ) -> StrResult<Image> {
+        let ImageKind::Svg(svg) = &self.0.kind else { return Ok(self.clone()) };
+        let kind = ImageKind::Raster(world.rasterize_svg(svg, size)?);
+        Ok(Self(Arc::new(LazyHash::new(Repr {
+            kind,
+            alt: self.0.alt.clone(),
+            scaling: self.0.scaling,
+        }))))
+    }
 }
 
 impl Debug for Image {
@@ -324,6 +471,7 @@ impl Debug for Image {
             .field("width", &self.width())
             .field("height", &self.height())
             .field("alt", &self.alt())
+            .field("scaling", &self.scaling())
             .finish()
     }
 }
@@ -3,7 +3,7 @@ use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
 use comemo::Tracked;
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 use siphasher::sip128::{Hasher128, SipHasher13};
 
 use crate::diag::{format_xml_like_error, StrResult};
@@ -24,6 +24,8 @@ struct Repr {
     size: Axes<f64>,
     flatten_text: bool,
     font_hash: u128,
+    unsupported: Option<EcoString>,
+    elements: usize,
     tree: usvg::Tree,
 }
 
@@ -34,10 +36,12 @@ impl SvgImage {
         let tree =
             usvg::Tree::from_data(&data, &base_options()).map_err(format_usvg_error)?;
         Ok(Self(Arc::new(Repr {
-            data,
             size: tree_size(&tree),
             font_hash: 0,
             flatten_text: false,
+            unsupported: find_unsupported(&data),
+            elements: count_elements(tree.root()),
+            data,
             tree,
         })))
     }
@@ -69,10 +73,12 @@ impl SvgImage {
         .map_err(format_usvg_error)?;
         let font_hash = resolver.into_inner().unwrap().finish();
         Ok(Self(Arc::new(Repr {
-            data,
             size: tree_size(&tree),
             font_hash,
             flatten_text,
+            unsupported: find_unsupported(&data),
+            elements: count_elements(tree.root()),
+            data,
             tree,
         })))
     }
@@ -101,6 +107,18 @@ impl SvgImage {
     pub fn tree(&self) -> &usvg::Tree {
         &self.0.tree
     }
+
+    /// A feature used by the SVG that cannot be rendered faithfully, if any.
+    ///
+    /// Such features are dropped or approximated during conversion.
+    pub fn unsupported_feature(&self) -> Option<&str> {
+        self.0.unsupported.as_deref()
+    }
+
+    /// The number of elements in the converted SVG.
+    pub fn element_count(&self) -> usize {
+        self.0.elements
+    }
 }
 
 impl Hash for Repr {
@@ -136,6 +154,45 @@ fn base_options() -> usvg::Options<'static> {
     }
 }
 
+/// Finds the first feature in the SVG source that usvg cannot render
+/// faithfully.
+fn find_unsupported(data: &[u8]) -> Option<EcoString> {
+    let text = std::str::from_utf8(data).ok()?;
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let document = roxmltree::Document::parse_with_options(text, options).ok()?;
+    for node in document.descendants().filter(roxmltree::Node::is_element) {
+        match node.tag_name().name() {
+            "meshgradient" | "mesh" => return Some("mesh gradients".into()),
+            "hatch" => return Some("hatch paint".into()),
+            _ => {}
+        }
+
+        // Filters that read the backdrop or the element's paint are resolved
+        // to transparent black.
+        for input in ["in", "in2"].into_iter().filter_map(|name| node.attribute(name)) {
+            if matches!(
+                input,
+                "BackgroundImage" | "BackgroundAlpha" | "FillPaint" | "StrokePaint"
+            ) {
+                return Some(eco_format!("filter input `{input}`"));
+            }
+        }
+    }
+    None
+}
+
+/// Counts the nodes in a group and its descendants.
+fn count_elements(group: &usvg::Group) -> usize {
+    group
+        .children()
+        .iter()
+        .map(|node| match node {
+            usvg::Node::Group(group) => 1 + count_elements(group),
+            _ => 1,
+        })
+        .sum()
+}
+
 /// The pixel size of an SVG.
 fn tree_size(tree: &usvg::Tree) -> Axes<f64> {
     Axes::new(tree.size().width() as f64, tree.size().height() as f64)
@@ -2,7 +2,7 @@ use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{elem, Content, NativeElement, Packed, Show, StyleChain};
 use crate::layout::{Abs, Angle, Axes, BlockElem, Length, Rel};
-use crate::visualize::Stroke;
+use crate::visualize::{Marker, Stroke};
 
 /// A line from one point to another.
 ///
@@ -53,6 +53,30 @@ pub struct LineElem {
     #[resolve]
     #[fold]
     pub stroke: Stroke,
+
+    /// A marker at the start of the line.
+    ///
+    /// See [`marker-end`]($line.marker-end) for the available markers.
+    pub marker_start: Option<Marker>,
+
+    /// A marker at the end of the line, like an arrowhead.
+    ///
+    /// The predefined shapes `{"arrow"}`, `{"bar"}`, and `{"circle"}` are
+    /// filled with the stroke's paint and scale with its thickness. The line
+    /// is shortened such that the tip of the marker lands exactly on the end
+    /// point.
+    ///
+    /// ```example
+    /// #set line(length: 100%)
+    /// #stack(
+    ///   spacing: 1em,
+    ///   line(marker-end: "arrow"),
+    ///   line(stroke: 2pt, marker-start: "bar", marker-end: "circle"),
+    ///   line(marker-end: (shape: "arrow", size: 10pt)),
+    ///   line(marker-end: text(0.8em)[$times$]),
+    /// )
+    /// ```
+    pub marker_end: Option<Marker>,
 }
 
 impl Show for Packed<LineElem> {
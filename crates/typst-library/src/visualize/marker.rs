@@ -0,0 +1,56 @@
+use crate::foundations::{cast, dict, Cast, Content, Dict, Smart, Value};
+use crate::layout::Length;
+
+/// A marker at the start or end of a stroked line or curve, like an
+/// arrowhead.
+///
+/// Markers are rotated along the direction of the line at the point where
+/// they are attached. The line is shortened such that the tip of the marker
+/// lands exactly on the line's start or end point.
+///
+/// A marker can be one of the following:
+/// - The name of a predefined shape: `{"arrow"}`, `{"bar"}`, or `{"circle"}`.
+///   The shape is filled with the paint of the stroke and its size scales
+///   with the stroke's thickness.
+/// - A dictionary with the keys `shape` and `size` to set the size of a
+///   predefined shape explicitly.
+/// - Arbitrary content, which is centered on the point and rotated such that
+///   its horizontal axis follows the line.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Marker {
+    /// A predefined shape with an optional explicit size.
+    Shape(MarkerShape, Smart<Length>),
+    /// Custom content.
+    Content(Content),
+}
+
+cast! {
+    Marker,
+    self => match self {
+        Self::Shape(shape, Smart::Auto) => shape.into_value(),
+        Self::Shape(shape, Smart::Custom(size)) => {
+            dict! { "shape" => shape, "size" => size }.into_value()
+        }
+        Self::Content(content) => content.into_value(),
+    },
+    shape: MarkerShape => Self::Shape(shape, Smart::Auto),
+    mut dict: Dict => {
+        let shape = dict.take("shape")?.cast()?;
+        let size = dict.take("size").ok().map(Value::cast)
+            .transpose()?.unwrap_or(Smart::Auto);
+        dict.finish(&["shape", "size"])?;
+        Self::Shape(shape, size)
+    },
+    content: Content => Self::Content(content),
+}
+
+/// A predefined marker shape.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum MarkerShape {
+    /// A filled triangle whose tip points away from the line.
+    Arrow,
+    /// A bar perpendicular to the line.
+    Bar,
+    /// A filled circle centered on the point.
+    Circle,
+}
@@ -5,6 +5,7 @@ mod curve;
 mod gradient;
 mod image;
 mod line;
+mod marker;
 mod paint;
 mod path;
 mod polygon;
@@ -17,6 +18,7 @@ pub use self::curve::*;
 pub use self::gradient::*;
 pub use self::image::*;
 pub use self::line::*;
+pub use self::marker::*;
 pub use self::paint::*;
 pub use self::path::*;
 pub use self::polygon::*;
@@ -24,6 +26,7 @@ pub use self::shape::*;
 pub use self::stroke::*;
 pub use self::tiling::*;
 
+use crate::diag::Deprecation;
 use crate::foundations::{category, Category, Scope, Type};
 
 /// Drawing and data visualization.
@@ -53,4 +56,8 @@ pub(super) fn define(global: &mut Scope) {
 
     // Compatibility.
     global.define("pattern", Type::of::<Tiling>());
+    global.deprecate(
+        "pattern",
+        Deprecation::new("the name `pattern` is deprecated, use `tiling` instead"),
+    );
 }
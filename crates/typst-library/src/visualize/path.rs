@@ -6,7 +6,7 @@ use crate::foundations::{
     StyleChain,
 };
 use crate::layout::{Axes, BlockElem, Length, Rel};
-use crate::visualize::{FillRule, Paint, Stroke};
+use crate::visualize::{FillRule, Marker, Paint, Stroke};
 
 /// A path through a list of points, connected by Bezier curves.
 ///
@@ -62,6 +62,17 @@ pub struct PathElem {
     #[fold]
     pub stroke: Smart<Option<Stroke>>,
 
+    /// A marker at the start of the path's first segment.
+    ///
+    /// The marker is omitted if the segment is closed. See the
+    /// [line's `marker-end`]($line.marker-end) for the available markers.
+    pub marker_start: Option<Marker>,
+
+    /// A marker at the end of the path's last segment.
+    ///
+    /// The marker is rotated along the path's tangent at its end.
+    pub marker_end: Option<Marker>,
+
     /// Whether to close this path with one last bezier curve. This curve will
     /// takes into account the adjacent control points. If you want to close
     /// with a straight line, simply add one last point that's the same as the
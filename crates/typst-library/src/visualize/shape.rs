@@ -1,10 +1,12 @@
+use typst_utils::Numeric;
+
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
     elem, Cast, Content, NativeElement, Packed, Show, Smart, StyleChain,
 };
 use crate::layout::{Abs, BlockElem, Corners, Length, Point, Rel, Sides, Size, Sizing};
-use crate::visualize::{Curve, FixedStroke, Paint, Stroke};
+use crate::visualize::{Curve, FixedStroke, LineCap, Paint, Stroke};
 
 /// A rectangle with optional content.
 ///
@@ -445,4 +447,59 @@ impl Geometry {
             Self::Curve(curve) => curve.bbox_size(),
         }
     }
+
+    /// Whether filling the geometry covers any area.
+    pub fn has_area(&self) -> bool {
+        match self {
+            Self::Line(_) => false,
+            Self::Rect(size) => {
+                !size.x.approx_eq(Abs::zero()) && !size.y.approx_eq(Abs::zero())
+            }
+            Self::Curve(curve) => curve.has_segments(),
+        }
+    }
+
+    /// Whether stroking the geometry with the given line cap draws anything.
+    ///
+    /// Zero-length lines are only visible with a round or square cap.
+    pub fn has_extent(&self, cap: LineCap) -> bool {
+        match self {
+            Self::Line(target) => !target.is_zero() || cap != LineCap::Butt,
+            Self::Rect(size) => {
+                !size.x.approx_eq(Abs::zero()) || !size.y.approx_eq(Abs::zero())
+            }
+            Self::Curve(curve) => curve.has_segments(),
+        }
+    }
+
+    /// Whether all coordinates of the geometry are finite.
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Self::Line(target) => target.x.is_finite() && target.y.is_finite(),
+            Self::Rect(size) => size.is_finite(),
+            Self::Curve(curve) => curve.is_finite(),
+        }
+    }
+}
+
+impl Shape {
+    /// Whether the shape paints nothing at all.
+    ///
+    /// This is the case if neither the fill nor the stroke cover anything,
+    /// e.g. for a curve that only moves the cursor or for a rectangle without
+    /// width and height. Shapes with non-finite coordinates are also
+    /// considered invisible. Exporters skip invisible shapes instead of
+    /// writing degenerate paths.
+    pub fn is_invisible(&self) -> bool {
+        if !self.geometry.is_finite() {
+            return true;
+        }
+
+        let filled = self.fill.is_some() && self.geometry.has_area();
+        let stroked = self.stroke.as_ref().is_some_and(|stroke| {
+            stroke.is_visible() && self.geometry.has_extent(stroke.cap)
+        });
+
+        !filled && !stroked
+    }
 }
@@ -603,6 +603,24 @@ impl FixedStroke {
             ..Default::default()
         }
     }
+
+    /// Whether the stroke has a positive, finite thickness.
+    pub fn is_visible(&self) -> bool {
+        self.thickness > Abs::zero() && self.thickness.is_finite()
+    }
+
+    /// The stroke's dash pattern, if it actually interrupts the line.
+    ///
+    /// A pattern without any positive length draws a solid line and one with
+    /// negative or non-finite lengths cannot be drawn at all, so both are
+    /// treated like no pattern. PDF does not even allow the former.
+    pub fn effective_dash(&self) -> Option<&DashPattern<Abs, Abs>> {
+        self.dash.as_ref().filter(|dash| {
+            dash.phase.is_finite()
+                && dash.array.iter().all(|l| l.is_finite() && *l >= Abs::zero())
+                && dash.array.iter().any(|l| *l > Abs::zero())
+        })
+    }
 }
 
 impl Default for FixedStroke {
@@ -633,10 +633,13 @@ fn create_style_chain_access(
 fn create_native_elem_impl(element: &Elem) -> TokenStream {
     let Elem { name, ident, title, scope, keywords, docs, .. } = element;
 
-    let local_name = if element.can("LocalName") {
-        quote! { Some(<#foundations::Packed<#ident> as ::typst_library::text::LocalName>::local_name) }
+    let (local_name, local_reference_name) = if element.can("LocalName") {
+        (
+            quote! { Some(<#foundations::Packed<#ident> as ::typst_library::text::LocalName>::local_name) },
+            quote! { Some(<#foundations::Packed<#ident> as ::typst_library::text::LocalName>::local_reference_name) },
+        )
     } else {
-        quote! { None }
+        (quote! { None }, quote! { None })
     };
 
     let scope = if *scope {
@@ -660,6 +663,7 @@ fn create_native_elem_impl(element: &Elem) -> TokenStream {
             field_name: |id| id.try_into().ok().map(Fields::to_str),
             field_from_styles: <#ident as #foundations::Fields>::field_from_styles,
             local_name: #local_name,
+            local_reference_name: #local_reference_name,
             scope: ::std::sync::LazyLock::new(|| #scope),
             params: ::std::sync::LazyLock::new(|| ::std::vec![#(#params),*])
         }
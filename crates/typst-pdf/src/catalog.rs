@@ -1,19 +1,31 @@
 use std::num::NonZeroUsize;
 
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 use pdf_writer::types::Direction;
 use pdf_writer::writers::PageLabel;
 use pdf_writer::{Finish, Name, Pdf, Ref, Str, TextStr};
 use typst_library::diag::{bail, SourceResult};
 use typst_library::foundations::{Datetime, Smart};
 use typst_library::layout::Dir;
+use typst_library::model::DocumentMetadataValue;
 use typst_library::text::Lang;
 use typst_syntax::Span;
-use xmp_writer::{DateTime, LangId, RenditionClass, XmpWriter};
+use xmp_writer::{
+    CustomNamespace, DateTime, LangId, Namespace, RenditionClass, XmpWriter,
+};
 
 use crate::page::PdfPageLabel;
 use crate::{hash_base64, outline, TextStrExt, Timezone, WithEverything};
 
+/// The XMP namespace of custom document metadata.
+fn typst_namespace() -> Namespace<'static> {
+    Namespace::Custom(Box::new(CustomNamespace::new(
+        "Typst metadata",
+        "typst",
+        "https://typst.app/ns/metadata/",
+    )))
+}
+
 /// Write the document catalog.
 pub fn write_catalog(
     ctx: WithEverything,
@@ -39,6 +51,9 @@ pub fn write_catalog(
     // Write the page labels.
     let page_labels = write_page_labels(pdf, alloc, &ctx);
 
+    // Write the attachments.
+    let attachments = write_attachments(pdf, alloc, &ctx);
+
     // Write the document information.
     let info_ref = alloc.bump();
     let mut info = pdf.document_info(info_ref);
@@ -105,6 +120,22 @@ pub fn write_catalog(
         }
     }
 
+    // PDF/A requires custom XMP properties to be described by an extension
+    // schema, which we don't write. The preflight warns about this.
+    let metadata = &ctx.document.info.metadata;
+    if !ctx.options.standards.pdfa {
+        for (key, value) in metadata.iter() {
+            let name = Name(key.as_bytes());
+            if let DocumentMetadataValue::Datetime(date) = value {
+                if let Some(pdf_date) = pdf_date(*date, None) {
+                    info.pair(name, pdf_date);
+                    continue;
+                }
+            }
+            info.pair(name, TextStr(&value.to_text()));
+        }
+    }
+
     info.finish();
 
     // A unique ID for this instance of the document. Changes if anything
@@ -128,7 +159,10 @@ pub fn write_catalog(
     xmp.document_id(&doc_id);
     xmp.instance_id(&instance_id);
     xmp.format("application/pdf");
-    xmp.pdf_version("1.7");
+    xmp.pdf_version(match ctx.options.standards.version() {
+        (1, 4) => "1.4",
+        _ => "1.7",
+    });
     xmp.language(ctx.resources.languages.keys().map(|lang| LangId(lang.as_str())));
     xmp.num_pages(ctx.document.pages.len() as u32);
     xmp.rendition_class(RenditionClass::Proof);
@@ -153,6 +187,20 @@ pub fn write_catalog(
         }
     }
 
+    if !ctx.options.standards.pdfa {
+        for (key, value) in metadata.iter() {
+            let element = xmp.element(key, typst_namespace());
+            match value {
+                DocumentMetadataValue::Int(int) => element.value(*int),
+                DocumentMetadataValue::Datetime(date) => match xmp_date(*date, None) {
+                    Some(xmp_date) => element.value(xmp_date),
+                    None => element.value(value.to_text().as_str()),
+                },
+                DocumentMetadataValue::Str(string) => element.value(string.as_str()),
+            }
+        }
+    }
+
     // Assert dominance.
     if ctx.options.standards.pdfa {
         let mut extension_schemas = xmp.extension_schemas();
@@ -162,7 +210,7 @@ pub fn write_catalog(
             .describe_instance_id();
         extension_schemas.pdf().properties().describe_all();
         extension_schemas.finish();
-        xmp.pdfa_part(2);
+        xmp.pdfa_part(ctx.options.standards.pdfa_part);
         xmp.pdfa_conformance("B");
     }
 
@@ -182,13 +230,24 @@ pub fn write_catalog(
     catalog.viewer_preferences().direction(dir);
     catalog.metadata(meta_ref);
 
-    // Write the named destination tree if there are any entries.
-    if !ctx.references.named_destinations.dests.is_empty() {
+    // Write the named destination and embedded file trees if there are any
+    // entries.
+    let dests = &ctx.references.named_destinations.dests;
+    if !dests.is_empty() || !attachments.is_empty() {
         let mut name_dict = catalog.names();
-        let mut dests_name_tree = name_dict.destinations();
-        let mut names = dests_name_tree.names();
-        for &(name, dest_ref, ..) in &ctx.references.named_destinations.dests {
-            names.insert(Str(name.resolve().as_bytes()), dest_ref);
+        if !dests.is_empty() {
+            let mut dests_name_tree = name_dict.destinations();
+            let mut names = dests_name_tree.names();
+            for (name, dest_ref) in dests {
+                names.insert(Str(name.as_bytes()), *dest_ref);
+            }
+        }
+        if !attachments.is_empty() {
+            let mut files_name_tree = name_dict.embedded_files();
+            let mut names = files_name_tree.names();
+            for (name, spec_ref) in &attachments {
+                names.insert(Str(name.as_bytes()), *spec_ref);
+            }
         }
     }
 
@@ -229,6 +288,38 @@ pub fn write_catalog(
     Ok(())
 }
 
+/// Write the attached files and their file specifications.
+///
+/// Returns the names of the files with their specifications, sorted by name
+/// as required for the name tree.
+fn write_attachments(
+    pdf: &mut Pdf,
+    alloc: &mut Ref,
+    ctx: &WithEverything,
+) -> Vec<(EcoString, Ref)> {
+    let mut specs = vec![];
+    for attachment in ctx.options.attachments {
+        let file_ref = alloc.bump();
+        let mut file = pdf.embedded_file(file_ref, attachment.data.as_slice());
+        file.params().size(attachment.data.len() as i32);
+        file.finish();
+
+        let spec_ref = alloc.bump();
+        let mut spec = pdf.file_spec(spec_ref);
+        spec.path(Str(attachment.name.as_bytes()));
+        spec.unic_file(TextStr(&attachment.name));
+        spec.embedded_file(file_ref);
+        if let Some(description) = &attachment.description {
+            spec.description(TextStr(description));
+        }
+        spec.finish();
+
+        specs.push((attachment.name.clone(), spec_ref));
+    }
+    specs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    specs
+}
+
 /// Write the page labels.
 pub(crate) fn write_page_labels(
     chunk: &mut Pdf,
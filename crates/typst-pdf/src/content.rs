@@ -319,7 +319,8 @@ impl Builder<'_, ()> {
                 Some(Paint::Gradient(_))
             )
         {
-            let FixedStroke { paint, thickness, cap, join, dash, miter_limit } = stroke;
+            let FixedStroke { paint, thickness, cap, join, miter_limit, .. } = stroke;
+            let dash = stroke.effective_dash();
             paint.set_as_stroke(self, on_text, transforms)?;
 
             self.content.set_line_width(thickness.to_f32());
@@ -329,7 +330,7 @@ impl Builder<'_, ()> {
             if self.state.stroke.as_ref().map(|s| &s.join) != Some(join) {
                 self.content.set_line_join(to_pdf_line_join(*join));
             }
-            if self.state.stroke.as_ref().map(|s| &s.dash) != Some(dash) {
+            if self.state.stroke.as_ref().map(|s| s.effective_dash()) != Some(dash) {
                 if let Some(dash) = dash {
                     self.content.set_dash_pattern(
                         dash.array.iter().map(|l| l.to_f32()),
@@ -388,6 +389,14 @@ pub(crate) fn write_frame(ctx: &mut Builder, frame: &Frame) -> SourceResult<()>
 
 /// Encode a group into the content stream.
 fn write_group(ctx: &mut Builder, pos: Point, group: &GroupItem) -> SourceResult<()> {
+    if group.is_invisible() {
+        return Ok(());
+    }
+
+    let Some(container_inverse) = ctx.state.container_transform.invert() else {
+        return Ok(());
+    };
+
     let translation = Transform::translate(pos.x, pos.y);
 
     ctx.save_state()?;
@@ -396,7 +405,7 @@ fn write_group(ctx: &mut Builder, pos: Point, group: &GroupItem) -> SourceResult
         ctx.group_transform(
             ctx.state
                 .transform
-                .post_concat(ctx.state.container_transform.invert().unwrap())
+                .post_concat(container_inverse)
                 .pre_concat(translation)
                 .pre_concat(group.transform),
         );
@@ -641,19 +650,19 @@ fn write_shape(ctx: &mut Builder, pos: Point, shape: &Shape) -> SourceResult<()>
     let x = pos.x.to_f32();
     let y = pos.y.to_f32();
 
-    let stroke = shape.stroke.as_ref().and_then(|stroke| {
-        if stroke.thickness.to_f32() > 0.0 {
-            Some(stroke)
-        } else {
-            None
-        }
-    });
+    // Painting operators without a path or with non-finite coordinates make
+    // the content stream invalid, so degenerate shapes are skipped entirely.
+    if shape.is_invisible() {
+        return Ok(());
+    }
 
-    if shape.fill.is_none() && stroke.is_none() {
+    let fill = shape.fill.as_ref().filter(|_| shape.geometry.has_area());
+    let stroke = shape.stroke.as_ref().filter(|stroke| stroke.is_visible());
+    if fill.is_none() && stroke.is_none() {
         return Ok(());
     }
 
-    if let Some(fill) = &shape.fill {
+    if let Some(fill) = fill {
         ctx.set_fill(fill, false, ctx.state.transforms(shape.geometry.bbox_size(), pos))?;
     }
 
@@ -665,7 +674,7 @@ fn write_shape(ctx: &mut Builder, pos: Point, shape: &Shape) -> SourceResult<()>
         )?;
     }
 
-    ctx.set_opacities(stroke, shape.fill.as_ref());
+    ctx.set_opacities(stroke, fill);
 
     match &shape.geometry {
         Geometry::Line(target) => {
@@ -675,18 +684,14 @@ fn write_shape(ctx: &mut Builder, pos: Point, shape: &Shape) -> SourceResult<()>
             ctx.content.line_to(x + dx, y + dy);
         }
         Geometry::Rect(size) => {
-            let w = size.x.to_f32();
-            let h = size.y.to_f32();
-            if w.abs() > f32::EPSILON && h.abs() > f32::EPSILON {
-                ctx.content.rect(x, y, w, h);
-            }
+            ctx.content.rect(x, y, size.x.to_f32(), size.y.to_f32());
         }
         Geometry::Curve(curve) => {
             write_curve(ctx, x, y, curve);
         }
     }
 
-    match (&shape.fill, &shape.fill_rule, stroke) {
+    match (fill, &shape.fill_rule, stroke) {
         (None, _, None) => unreachable!(),
         (Some(_), FillRule::NonZero, None) => ctx.content.fill_nonzero(),
         (Some(_), FillRule::EvenOdd, None) => ctx.content.fill_even_odd(),
@@ -0,0 +1,431 @@
+//! Flattening of transparency for standards and printing workflows that don't
+//! support it.
+
+use ecow::EcoVec;
+use typst_library::diag::{warning, SourceDiagnostic};
+use typst_library::foundations::Smart;
+use typst_library::layout::{
+    Abs, Frame, FrameItem, GroupItem, Page, PagedDocument, Point, Size, Transform,
+};
+use typst_library::text::TextItem;
+use typst_library::visualize::{
+    Color, CurveItem, Geometry, ImageKind, Paint, Rgb, Shape,
+};
+use typst_syntax::Span;
+
+use crate::PdfOptions;
+
+/// The resolution at which transparent areas are rasterized if no other
+/// resolution is configured.
+pub const DEFAULT_FLATTEN_DPI: f32 = 300.0;
+
+/// Replace transparent content on the exported pages with opaque content.
+///
+/// Where a solid color is painted over a known solid background, the
+/// composited color is computed directly. Everything else is rasterized
+/// together with what lies beneath it. Pages on which this was necessary are
+/// reported in a warning.
+#[typst_macros::time(name = "flatten transparency")]
+pub fn flatten(
+    document: &PagedDocument,
+    options: &PdfOptions,
+    warnings: &mut EcoVec<SourceDiagnostic>,
+) -> PagedDocument {
+    let dpi = options.flatten_dpi.unwrap_or(DEFAULT_FLATTEN_DPI);
+    let mut rasterized = vec![];
+    let mut pages = Vec::with_capacity(document.pages.len());
+
+    for (i, page) in document.pages.iter().enumerate() {
+        if options
+            .page_ranges
+            .as_ref()
+            .is_some_and(|ranges| !ranges.includes_page_index(i))
+            || !has_transparency(page)
+        {
+            pages.push(page.clone());
+            continue;
+        }
+
+        let mut flattener = Flattener::new(page, dpi / 72.0);
+        let frame = flattener.frame(&page.frame, Transform::identity());
+        if flattener.rasterized {
+            rasterized.push(i + 1);
+        }
+
+        let fill = match &page.fill {
+            Smart::Custom(Some(Paint::Solid(color))) => {
+                Smart::Custom(Some(composite(*color, Color::WHITE).into()))
+            }
+            fill => fill.clone(),
+        };
+
+        pages.push(Page { frame, fill, ..page.clone() });
+    }
+
+    if !rasterized.is_empty() {
+        let list = rasterized
+            .iter()
+            .map(|page| page.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let noun = if rasterized.len() == 1 { "page" } else { "pages" };
+        warnings.push(warning!(
+            Span::detached(),
+            "transparent content on {noun} {list} was rasterized";
+            hint: "the rasterized areas have a resolution of {dpi} DPI",
+        ));
+    }
+
+    PagedDocument { pages, ..document.clone() }
+}
+
+/// Walks the frames of a page and replaces transparent items.
+struct Flattener<'a> {
+    /// The page that is flattened.
+    page: &'a Page,
+    /// The solid color of the page's background, if it has one.
+    background: Option<Color>,
+    /// The number of pixels per point at which to rasterize.
+    pixel_per_pt: f32,
+    /// The number of leaf items (everything but groups) that were visited.
+    leaves: usize,
+    /// The bounding boxes of the painted items so far, in page coordinates.
+    painted: Vec<Bounds>,
+    /// Whether anything was rasterized.
+    rasterized: bool,
+}
+
+impl<'a> Flattener<'a> {
+    fn new(page: &'a Page, pixel_per_pt: f32) -> Self {
+        // A page without fill is printed on white paper.
+        let background = match page.fill_or_transparent() {
+            None => Some(Color::WHITE),
+            Some(Paint::Solid(color)) => Some(composite(color, Color::WHITE)),
+            Some(_) => None,
+        };
+
+        Self {
+            page,
+            background,
+            pixel_per_pt,
+            leaves: 0,
+            painted: vec![],
+            rasterized: false,
+        }
+    }
+
+    /// Flatten a frame that is placed on the page with the given transform.
+    fn frame(&mut self, frame: &Frame, ts: Transform) -> Frame {
+        let mut output = frame.clone();
+        output.clear();
+
+        for (pos, item) in frame.items() {
+            let item_ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+            if let FrameItem::Group(group) = item {
+                let frame = self.frame(&group.frame, item_ts.pre_concat(group.transform));
+                output.push(*pos, FrameItem::Group(GroupItem { frame, ..group.clone() }));
+                continue;
+            }
+
+            let leaf = self.leaves;
+            self.leaves += 1;
+
+            let Some(bounds) = bounds(item) else {
+                output.push(*pos, item.clone());
+                continue;
+            };
+
+            let bounds = bounds.transform(item_ts);
+            let composited = if needs_flattening(item) {
+                self.composite(item, bounds)
+            } else {
+                Some(item.clone())
+            };
+
+            if let Some(item) = composited {
+                output.push(*pos, item);
+                self.painted.push(bounds);
+            } else if let Some((image, bounds)) = self.rasterize(leaf, bounds) {
+                // The image is positioned in page coordinates, so undo the
+                // transform of the current frame.
+                if let Some(inverse) = ts.invert() {
+                    let group = GroupItem { transform: inverse, ..GroupItem::new(image) };
+                    output.push(Point::zero(), FrameItem::Group(group));
+                }
+                self.painted.push(bounds);
+                self.rasterized = true;
+            }
+        }
+
+        output
+    }
+
+    /// Try to replace the transparent paints of an item with the solid colors
+    /// they composite to over the page background.
+    ///
+    /// This is only possible if the paints are solid colors and nothing was
+    /// painted beneath the item yet.
+    fn composite(&self, item: &FrameItem, bounds: Bounds) -> Option<FrameItem> {
+        let background = self.background?;
+        if self.painted.iter().any(|other| other.intersects(&bounds)) {
+            return None;
+        }
+
+        let solid = |paint: &Paint| match paint {
+            Paint::Solid(color) => Some(Paint::Solid(composite(*color, background))),
+            _ if !is_translucent(paint) => Some(paint.clone()),
+            _ => None,
+        };
+
+        match item {
+            FrameItem::Text(text) => {
+                // A transparent stroke would show the fill beneath it.
+                if text.stroke.as_ref().is_some_and(|s| is_translucent(&s.paint)) {
+                    return None;
+                }
+
+                let fill = solid(&text.fill)?;
+                Some(FrameItem::Text(TextItem { fill, ..text.clone() }))
+            }
+            FrameItem::Shape(shape, span) => {
+                let mut shape = shape.clone();
+                if let Some(stroke) = &mut shape.stroke {
+                    if is_translucent(&stroke.paint) && shape.fill.is_some() {
+                        return None;
+                    }
+                    stroke.paint = solid(&stroke.paint)?;
+                }
+                if let Some(fill) = &mut shape.fill {
+                    *fill = solid(fill)?;
+                }
+                Some(FrameItem::Shape(shape, *span))
+            }
+            _ => None,
+        }
+    }
+
+    /// Rasterize the area of the page that is covered by the leaf item with
+    /// the given index into an opaque image, including everything that was
+    /// painted before it.
+    ///
+    /// Returns a page-sized frame with the image and the area it covers.
+    fn rasterize(&self, leaf: usize, bounds: Bounds) -> Option<(Frame, Bounds)> {
+        // Snap the area to the pixel grid and keep it on the page.
+        let ppp = f64::from(self.pixel_per_pt);
+        let snap = |v: Abs, f: fn(f64) -> f64| Abs::pt(f(v.to_pt() * ppp) / ppp);
+        let page_size = self.page.frame.size();
+        let min = Point::new(
+            snap(bounds.min.x, f64::floor).max(Abs::zero()),
+            snap(bounds.min.y, f64::floor).max(Abs::zero()),
+        );
+        let max = Point::new(
+            snap(bounds.max.x, f64::ceil).min(page_size.x),
+            snap(bounds.max.y, f64::ceil).min(page_size.y),
+        );
+
+        let size = Size::new(max.x - min.x, max.y - min.y);
+        if size.x <= Abs::zero() || size.y <= Abs::zero() {
+            return None;
+        }
+
+        // Paint the paper, the page's background, and everything up to the
+        // item.
+        let mut remaining = leaf + 1;
+        let content = truncate(&self.page.frame, &mut remaining);
+        let mut frame = Frame::hard(size);
+        let fills =
+            std::iter::once(Color::WHITE.into()).chain(self.page.fill_or_transparent());
+        for fill in fills {
+            let shape = Geometry::Rect(page_size).filled(fill);
+            frame.push(-min, FrameItem::Shape(shape, Span::detached()));
+        }
+        frame.push(-min, FrameItem::Group(GroupItem::new(content)));
+
+        let page = Page {
+            frame,
+            fill: Smart::Custom(None),
+            ..self.page.clone()
+        };
+        let image = typst_render::render_opaque(&page, self.pixel_per_pt)?;
+
+        let mut output = Frame::soft(page_size);
+        output.push(min, FrameItem::Image(image, size, Span::detached()));
+        Some((output, Bounds { min, max }))
+    }
+}
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+impl Bounds {
+    /// The bounding box of the transformed box.
+    fn transform(self, ts: Transform) -> Self {
+        let corners = [
+            self.min,
+            Point::new(self.max.x, self.min.y),
+            Point::new(self.min.x, self.max.y),
+            self.max,
+        ]
+        .map(|point| point.transform(ts));
+
+        let mut bounds = Bounds { min: corners[0], max: corners[0] };
+        for point in &corners[1..] {
+            bounds.min = Point::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y));
+            bounds.max = Point::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y));
+        }
+        bounds
+    }
+
+    /// Whether the two boxes overlap.
+    fn intersects(&self, other: &Self) -> bool {
+        self.min.x < other.max.x
+            && other.min.x < self.max.x
+            && self.min.y < other.max.y
+            && other.min.y < self.max.y
+    }
+}
+
+/// A conservative bounding box of what an item paints, relative to its
+/// position. Returns `None` for items that don't paint anything.
+fn bounds(item: &FrameItem) -> Option<Bounds> {
+    match item {
+        FrameItem::Text(text) => {
+            // Glyphs may extend beyond the font's ascender and descender and
+            // beyond their advances, so be generous.
+            let metrics = text.font.metrics();
+            let pad = text.size / 2.0
+                + text.stroke.as_ref().map_or(Abs::zero(), |stroke| stroke.thickness);
+            Some(Bounds {
+                min: Point::new(-pad, -metrics.ascender.at(text.size) - pad),
+                max: Point::new(
+                    text.width() + pad,
+                    -metrics.descender.at(text.size) + pad,
+                ),
+            })
+        }
+        FrameItem::Shape(shape, _) => {
+            let points = match &shape.geometry {
+                Geometry::Line(to) => vec![Point::zero(), *to],
+                Geometry::Rect(size) => vec![Point::zero(), size.to_point()],
+                // The control points enclose the curve.
+                Geometry::Curve(curve) => curve
+                    .0
+                    .iter()
+                    .flat_map(|item| match item {
+                        CurveItem::Move(p) | CurveItem::Line(p) => vec![*p],
+                        CurveItem::Cubic(a, b, c) => vec![*a, *b, *c],
+                        CurveItem::Close => vec![],
+                    })
+                    .collect(),
+            };
+
+            let pad =
+                shape.stroke.as_ref().map_or(Abs::zero(), |stroke| stroke.thickness);
+            let first = points.first()?;
+            let mut bounds = Bounds { min: *first, max: *first };
+            for point in &points[1..] {
+                bounds.min =
+                    Point::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y));
+                bounds.max =
+                    Point::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y));
+            }
+            bounds.min -= Point::splat(pad);
+            bounds.max += Point::splat(pad);
+            Some(bounds)
+        }
+        FrameItem::Image(_, size, _) => {
+            Some(Bounds { min: Point::zero(), max: size.to_point() })
+        }
+        FrameItem::Group(_) | FrameItem::Link(..) | FrameItem::Tag(_) => None,
+    }
+}
+
+/// Whether the page contains any transparency that must be flattened.
+fn has_transparency(page: &Page) -> bool {
+    let fill = page.fill_or_transparent();
+    fill.as_ref().is_some_and(is_translucent) || frame_has_transparency(&page.frame)
+}
+
+/// Whether any item in the frame paints anything transparent.
+fn frame_has_transparency(frame: &Frame) -> bool {
+    frame.items().any(|(_, item)| match item {
+        FrameItem::Group(group) => frame_has_transparency(&group.frame),
+        item => needs_flattening(item),
+    })
+}
+
+/// Whether a leaf item paints anything transparent.
+fn needs_flattening(item: &FrameItem) -> bool {
+    match item {
+        FrameItem::Text(text) => {
+            is_translucent(&text.fill)
+                || text.stroke.as_ref().is_some_and(|s| is_translucent(&s.paint))
+        }
+        FrameItem::Shape(shape, _) => is_shape_translucent(shape),
+        FrameItem::Image(image, _, _) => match image.kind() {
+            ImageKind::Raster(raster) => raster.dynamic().color().has_alpha(),
+            ImageKind::Svg(_) => false,
+        },
+        FrameItem::Group(_) | FrameItem::Link(..) | FrameItem::Tag(_) => false,
+    }
+}
+
+/// Whether a shape's fill or stroke is transparent.
+fn is_shape_translucent(shape: &Shape) -> bool {
+    let stroke = shape.stroke.as_ref().map(|stroke| &stroke.paint);
+    shape.fill.iter().chain(stroke).any(is_translucent)
+}
+
+/// Whether a paint is at least partially transparent.
+fn is_translucent(paint: &Paint) -> bool {
+    match paint {
+        Paint::Solid(color) => color.alpha().is_some_and(|a| a < 1.0),
+        Paint::Gradient(gradient) => gradient
+            .stops_ref()
+            .iter()
+            .any(|(color, _)| color.alpha().is_some_and(|a| a < 1.0)),
+        Paint::Tiling(tiling) => frame_has_transparency(tiling.frame()),
+    }
+}
+
+/// Composite a possibly transparent color over an opaque background.
+fn composite(color: Color, background: Color) -> Color {
+    let alpha = match color.alpha() {
+        Some(alpha) if alpha < 1.0 => alpha,
+        _ => return color,
+    };
+
+    let [r, g, b, _] = color.to_rgb().to_vec4();
+    let [br, bg, bb, _] = background.to_rgb().to_vec4();
+    let mix = |c: f32, b: f32| alpha * c + (1.0 - alpha) * b;
+    Color::Rgb(Rgb::new(mix(r, br), mix(g, bg), mix(b, bb), 1.0))
+}
+
+/// Copy a frame up to and including the leaf item with the given index.
+fn truncate(frame: &Frame, remaining: &mut usize) -> Frame {
+    let mut output = frame.clone();
+    output.clear();
+
+    for (pos, item) in frame.items() {
+        if *remaining == 0 {
+            break;
+        }
+
+        match item {
+            FrameItem::Group(group) => {
+                let frame = truncate(&group.frame, remaining);
+                output.push(*pos, FrameItem::Group(GroupItem { frame, ..group.clone() }));
+            }
+            item => {
+                output.push(*pos, item.clone());
+                *remaining -= 1;
+            }
+        }
+    }
+
+    output
+}
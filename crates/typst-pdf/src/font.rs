@@ -1,6 +1,6 @@
 use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use ecow::{eco_format, EcoString};
 use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
@@ -56,11 +56,15 @@ pub fn write_fonts(
             //
             // FIXME: CFF2 must be handled differently and requires PDF 2.0
             // (or we have to convert it to CFF).
-            let is_cff = ttf
-                .raw_face()
-                .table(CFF)
-                .or_else(|| ttf.raw_face().table(CFF2))
-                .is_some();
+            //
+            // Pinned variable font instances are always converted to CFF
+            // during subsetting.
+            let is_cff = !font.variations().is_empty()
+                || ttf
+                    .raw_face()
+                    .table(CFF)
+                    .or_else(|| ttf.raw_face().table(CFF2))
+                    .is_some();
 
             let base_font = base_font_name(font, glyph_set);
             let base_font_type0 = if is_cff {
@@ -206,15 +210,49 @@ pub fn write_font_descriptor<'a>(
 /// - For a font with TrueType outlines, this produces the whole OpenType font.
 /// - For a font with CFF outlines, this produces just the CFF font program.
 ///
-/// In both cases, this returns the already compressed data.
-#[comemo::memoize]
-#[typst_macros::time(name = "subset font")]
+/// For a pinned variable font instance, the outlines are instanced at the
+/// font's variation coordinates and converted to CFF.
+///
+/// In all cases, this returns the already compressed data.
+///
+/// Results are kept in the [subset cache](clear_subset_cache), so that
+/// repeated exports of the same document in one process don't subset the
+/// same fonts over and over again.
 fn subset_font(
     font: &Font,
     glyph_remapper: &GlyphRemapper,
+) -> Result<Arc<Vec<u8>>, subsetter::Error> {
+    // The key must include the remapping and not just the set of glyphs: The
+    // new glyph ids are assigned in order of first use and the content
+    // streams were already written with them. The export options don't
+    // affect subsetting, so they need not be part of the key.
+    let key = typst_utils::hash128(&(font, glyph_remapper));
+    if let Some(subset) = SUBSETS.lock().unwrap().get(key) {
+        return Ok(subset);
+    }
+
+    let subset = subset_font_impl(font, glyph_remapper)?;
+    SUBSETS.lock().unwrap().insert(key, subset.clone());
+    Ok(subset)
+}
+
+/// The uncached implementation of [`subset_font`].
+#[typst_macros::time(name = "subset font")]
+fn subset_font_impl(
+    font: &Font,
+    glyph_remapper: &GlyphRemapper,
 ) -> Result<Arc<Vec<u8>>, subsetter::Error> {
     let data = font.data();
-    let subset = subsetter::subset(data, font.index(), glyph_remapper)?;
+    let subset = if font.variations().is_empty() {
+        subsetter::subset(data, font.index(), glyph_remapper)?
+    } else {
+        let coords: Vec<_> = font
+            .variations()
+            .iter()
+            .map(|&(tag, value)| (subsetter::Tag::new(&tag.to_bytes()), value))
+            .collect();
+        subsetter::subset_with_variations(data, font.index(), &coords, glyph_remapper)?
+    };
     let mut data = subset.as_ref();
 
     // Extract the standalone CFF font program if applicable.
@@ -226,6 +264,73 @@ fn subset_font(
     Ok(Arc::new(deflate(data)))
 }
 
+/// The maximum total size of the font subsets that are kept in the subset
+/// cache, in bytes. When it is exceeded, the least recently used subsets are
+/// evicted.
+pub const SUBSET_CACHE_CAPACITY: usize = 64 * 1024 * 1024;
+
+/// Process-wide cache for font subsets.
+pub(crate) static SUBSETS: Mutex<SubsetCache> = Mutex::new(SubsetCache::new());
+
+/// Clear the cache of font subsets.
+///
+/// PDF export caches the subsets of the fonts it embeds, so that exporting a
+/// changed document again does not redo the (potentially expensive)
+/// subsetting of fonts whose glyph set did not change. The cache is shared by
+/// all exports in the process and holds at most [`SUBSET_CACHE_CAPACITY`]
+/// bytes.
+pub fn clear_subset_cache() {
+    SUBSETS.lock().unwrap().clear();
+}
+
+/// A least-recently-used cache of compressed font subsets.
+pub(crate) struct SubsetCache {
+    /// The subsets and their keys, least recently used first.
+    entries: Vec<(u128, Arc<Vec<u8>>)>,
+    /// The total size of all subsets.
+    size: usize,
+    /// How many subsets were computed because they weren't in the cache.
+    pub misses: usize,
+}
+
+impl SubsetCache {
+    /// Create an empty cache.
+    const fn new() -> Self {
+        Self { entries: Vec::new(), size: 0, misses: 0 }
+    }
+
+    /// Look up a subset and mark it as recently used.
+    fn get(&mut self, key: u128) -> Option<Arc<Vec<u8>>> {
+        let Some(i) = self.entries.iter().position(|&(k, _)| k == key) else {
+            self.misses += 1;
+            return None;
+        };
+        let entry = self.entries.remove(i);
+        let subset = entry.1.clone();
+        self.entries.push(entry);
+        Some(subset)
+    }
+
+    /// Insert a subset and evict old ones if the cache is full.
+    fn insert(&mut self, key: u128, subset: Arc<Vec<u8>>) {
+        if self.entries.iter().any(|&(k, _)| k == key) {
+            return;
+        }
+        self.size += subset.len();
+        self.entries.push((key, subset));
+        while self.size > SUBSET_CACHE_CAPACITY && self.entries.len() > 1 {
+            let (_, evicted) = self.entries.remove(0);
+            self.size -= evicted.len();
+        }
+    }
+
+    /// Remove all subsets.
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.size = 0;
+    }
+}
+
 /// Creates the base font name for a font with a specific glyph subset.
 /// Consists of a subset tag and the PostScript name of the font.
 ///
@@ -240,8 +345,15 @@ pub(crate) fn base_font_name<T: Hash>(font: &Font, glyphs: &T) -> EcoString {
     let trimmed = &name[..name.len().min(MAX_LEN)];
 
     // Hash the full name (we might have trimmed) and the glyphs to produce
-    // a fairly unique subset tag.
-    let subset_tag = subset_tag(&(name, glyphs));
+    // a fairly unique subset tag. Pinned instances of a variable font share
+    // the name, so they are told apart by their coordinates.
+    let subset_tag = if font.variations().is_empty() {
+        subset_tag(&(name, glyphs))
+    } else {
+        let coords: Vec<_> =
+            font.variations().iter().map(|(t, v)| (t, v.to_bits())).collect();
+        subset_tag(&(name, glyphs, coords))
+    };
 
     eco_format!("{subset_tag}+{trimmed}")
 }
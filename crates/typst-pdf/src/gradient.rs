@@ -8,9 +8,7 @@ use pdf_writer::writers::StreamShadingType;
 use pdf_writer::{Filter, Finish, Name, Ref};
 use typst_library::diag::SourceResult;
 use typst_library::layout::{Abs, Angle, Point, Quadrant, Ratio, Transform};
-use typst_library::visualize::{
-    Color, ColorSpace, Gradient, RatioOrAngle, RelativeTo, WeightedColor,
-};
+use typst_library::visualize::{Color, ColorSpace, Gradient, RelativeTo};
 use typst_utils::Numeric;
 
 use crate::color::{
@@ -61,10 +59,16 @@ pub fn write_gradients(
                 check_cmyk_allowed(context.options)?;
             }
 
+            let samples = context
+                .options
+                .gradient_samples
+                .unwrap_or(Gradient::SEGMENT_SAMPLES)
+                .max(1);
+
             let mut shading_pattern = match &gradient {
                 Gradient::Linear(_) => {
                     let shading_function =
-                        shading_function(gradient, &mut chunk, color_space);
+                        shading_function(gradient, &mut chunk, color_space, samples);
                     let mut shading_pattern = chunk.chunk.shading_pattern(shading);
                     let mut shading = shading_pattern.function_shading();
                     shading.shading_type(FunctionShadingType::Axial);
@@ -100,8 +104,12 @@ pub fn write_gradients(
                     shading_pattern
                 }
                 Gradient::Radial(radial) => {
-                    let shading_function =
-                        shading_function(gradient, &mut chunk, color_space_of(gradient));
+                    let shading_function = shading_function(
+                        gradient,
+                        &mut chunk,
+                        color_space_of(gradient),
+                        samples,
+                    );
                     let mut shading_pattern = chunk.chunk.shading_pattern(shading);
                     let mut shading = shading_pattern.function_shading();
                     shading.shading_type(FunctionShadingType::Radial);
@@ -130,7 +138,8 @@ pub fn write_gradients(
                     shading_pattern
                 }
                 Gradient::Conic(_) => {
-                    let vertices = compute_vertex_stream(gradient, *aspect_ratio);
+                    let vertices =
+                        compute_vertex_stream(gradient, *aspect_ratio, samples);
 
                     let stream_shading_id = chunk.alloc();
                     let mut stream_shading =
@@ -176,6 +185,7 @@ fn shading_function(
     gradient: &Gradient,
     chunk: &mut PdfChunk,
     color_space: ColorSpace,
+    samples: usize,
 ) -> Ref {
     let function = chunk.alloc();
     let mut functions = vec![];
@@ -183,19 +193,19 @@ fn shading_function(
     let mut encode = vec![];
 
     // Create the individual gradient functions for each pair of stops.
-    for window in gradient.stops_ref().windows(2) {
+    for (i, window) in gradient.stops_ref().windows(2).enumerate() {
         let (first, second) = (window[0], window[1]);
 
-        // If we have a hue index or are using Oklab, we will create several
-        // stops in-between to make the gradient smoother without interpolation
-        // issues with native color spaces.
+        // If the segment is eased or we have a hue index, we will create
+        // several stops in-between to make the gradient smoother without
+        // interpolation issues with native color spaces.
         let mut last_c = first.0;
-        if gradient.space().hue_index().is_some() {
-            for i in 0..=32 {
-                let t = i as f64 / 32.0;
+        if !gradient.is_linear_segment(i) {
+            for k in 1..samples {
+                let t = k as f64 / samples as f64;
                 let real_t = first.1.get() * (1.0 - t) + second.1.get() * t;
 
-                let c = gradient.sample(RatioOrAngle::Ratio(Ratio::new(real_t)));
+                let c = gradient.sample_segment(i, t);
                 functions.push(single_gradient(chunk, last_c, c, color_space));
                 bounds.push(real_t as f32);
                 encode.extend([0.0, 1.0]);
@@ -204,7 +214,7 @@ fn shading_function(
         }
 
         bounds.push(second.1.get() as f32);
-        functions.push(single_gradient(chunk, first.0, second.0, color_space));
+        functions.push(single_gradient(chunk, last_c, second.0, color_space));
         encode.extend([0.0, 1.0]);
     }
 
@@ -423,7 +433,11 @@ fn control_point(c: Point, r: f32, angle_start: f32, angle_end: f32) -> (Point,
 }
 
 #[comemo::memoize]
-fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8>> {
+fn compute_vertex_stream(
+    gradient: &Gradient,
+    aspect_ratio: Ratio,
+    samples: usize,
+) -> Arc<Vec<u8>> {
     let Gradient::Conic(conic) = gradient else { unreachable!() };
 
     // Generated vertices for the Coons patches
@@ -432,17 +446,19 @@ fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8
     // Correct the gradient's angle
     let angle = Gradient::correct_aspect_ratio(conic.angle, aspect_ratio);
 
-    for window in conic.stops.windows(2) {
+    for (i, window) in conic.stops.windows(2).enumerate() {
         let ((c0, t0), (c1, t1)) = (window[0], window[1]);
 
         // Precision:
         // - On an even color, insert a stop every 90deg
-        // - For a hue-based color space, insert 200 stops minimum
+        // - For an eased segment or a hue-based color space, insert
+        //   `samples` stops minimum, but at least 200 per full turn
         // - On any other, insert 20 stops minimum
-        let max_dt = if c0 == c1 {
+        let linear = gradient.is_linear_segment(i);
+        let max_dt = if c0 == c1 && linear {
             0.25
-        } else if conic.space.hue_index().is_some() {
-            0.005
+        } else if !linear {
+            (1.0 / samples as f64).min(0.005)
         } else {
             0.05
         };
@@ -472,20 +488,8 @@ fn compute_vertex_stream(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<Vec<u8
 
             // The current progress in the current window.
             let t = |t| (t - t0.get()) / (t1.get() - t0.get());
-            let c = Color::mix_iter(
-                [WeightedColor::new(c0, 1.0 - t(t_x)), WeightedColor::new(c1, t(t_x))],
-                conic.space,
-            )
-            .unwrap();
-
-            let c_next = Color::mix_iter(
-                [
-                    WeightedColor::new(c0, 1.0 - t(t_next)),
-                    WeightedColor::new(c1, t(t_next)),
-                ],
-                conic.space,
-            )
-            .unwrap();
+            let c = gradient.sample_segment(i, t(t_x));
+            let c_next = gradient.sample_segment(i, t(t_next));
 
             write_patch(
                 &mut vertices,
@@ -5,8 +5,9 @@ use ecow::eco_format;
 use image::{DynamicImage, GenericImageView, Rgba};
 use pdf_writer::{Chunk, Filter, Finish, Ref};
 use typst_library::diag::{At, SourceResult, StrResult};
+use typst_library::foundations::Smart;
 use typst_library::visualize::{
-    ColorSpace, Image, ImageKind, RasterFormat, RasterImage, SvgImage,
+    ColorSpace, Image, ImageKind, ImageScaling, RasterFormat, RasterImage, SvgImage,
 };
 use typst_utils::Deferred;
 
@@ -25,6 +26,7 @@ pub fn write_images(
                 continue;
             }
 
+            let scaling = image.scaling();
             let (handle, span) = resources.deferred_images.get(&i).unwrap();
             let encoded = handle.wait().as_ref().map_err(Clone::clone).at(*span)?;
 
@@ -47,6 +49,20 @@ pub fn write_images(
                     image.height(*height as i32);
                     image.bits_per_component(8);
 
+                    // Pass on the scaling hint. PDF/A forbids interpolation,
+                    // so we only ever disable it there.
+                    match scaling {
+                        Smart::Custom(ImageScaling::Pixelated) => {
+                            image.interpolate(false);
+                        }
+                        Smart::Custom(ImageScaling::Smooth)
+                            if !context.options.standards.pdfa =>
+                        {
+                            image.interpolate(true);
+                        }
+                        _ => {}
+                    }
+
                     let mut icc_ref = None;
                     let space = image.color_space();
                     if icc.is_some() {
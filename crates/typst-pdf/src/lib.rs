@@ -5,25 +5,31 @@ mod color;
 mod color_font;
 mod content;
 mod extg;
+mod flatten;
 mod font;
 mod gradient;
 mod image;
 mod named_destination;
 mod outline;
 mod page;
+mod preflight;
 mod resources;
 mod tiling;
 
+pub use self::flatten::DEFAULT_FLATTEN_DPI;
+pub use self::font::{clear_subset_cache, SUBSET_CACHE_CAPACITY};
+
 use std::collections::HashMap;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::Hash;
 use std::ops::{Deref, DerefMut};
 
 use base64::Engine;
+use ecow::{eco_format, EcoString, EcoVec};
 use pdf_writer::{Chunk, Name, Pdf, Ref, Str, TextStr};
 use serde::{Deserialize, Serialize};
-use typst_library::diag::{bail, SourceResult, StrResult};
-use typst_library::foundations::{Datetime, Smart};
+use typst_library::diag::{bail, SourceDiagnostic, SourceResult, StrResult, Warned};
+use typst_library::foundations::{Bytes, Datetime, Smart};
 use typst_library::layout::{Abs, Em, PageRanges, PagedDocument, Transform};
 use typst_library::text::Font;
 use typst_library::visualize::Image;
@@ -34,11 +40,13 @@ use crate::catalog::write_catalog;
 use crate::color::{alloc_color_functions_refs, ColorFunctionRefs};
 use crate::color_font::{write_color_fonts, ColorFontSlice};
 use crate::extg::{write_graphic_states, ExtGState};
+use crate::flatten::flatten;
 use crate::font::write_fonts;
 use crate::gradient::{write_gradients, PdfGradient};
 use crate::image::write_images;
 use crate::named_destination::{write_named_destinations, NamedDestinations};
 use crate::page::{alloc_page_refs, traverse_pages, write_page_tree, EncodedPage};
+use crate::preflight::preflight;
 use crate::resources::{
     alloc_resources_refs, write_resource_dictionaries, Resources, ResourcesRefs,
 };
@@ -46,9 +54,43 @@ use crate::tiling::{write_tilings, PdfTiling};
 
 /// Export a document into a PDF file.
 ///
+/// Returns the raw bytes making up the PDF file. Warnings that arise during
+/// export, e.g. about fonts whose license only permits embedding them into
+/// documents for previewing and printing, are discarded. Use
+/// [`pdf_with_warnings`] to report them.
+pub fn pdf(document: &PagedDocument, options: &PdfOptions) -> SourceResult<Vec<u8>> {
+    pdf_with_warnings(document, options).output
+}
+
+/// Export a document into a PDF file and also return the warnings that arose
+/// during export.
+///
 /// Returns the raw bytes making up the PDF file.
 #[typst_macros::time(name = "pdf")]
-pub fn pdf(document: &PagedDocument, options: &PdfOptions) -> SourceResult<Vec<u8>> {
+pub fn pdf_with_warnings(
+    document: &PagedDocument,
+    options: &PdfOptions,
+) -> Warned<SourceResult<Vec<u8>>> {
+    let mut warnings = EcoVec::new();
+    let flattened;
+    let document = if options.flatten_transparency {
+        flattened = flatten(document, options, &mut warnings);
+        &flattened
+    } else {
+        document
+    };
+
+    let output = export(document, options, &mut warnings);
+    Warned { output, warnings }
+}
+
+/// Check and export an already flattened document.
+fn export(
+    document: &PagedDocument,
+    options: &PdfOptions,
+    warnings: &mut EcoVec<SourceDiagnostic>,
+) -> SourceResult<Vec<u8>> {
+    preflight(document, options, warnings)?;
     PdfBuilder::new(document, options)
         .phase(|builder| builder.run(traverse_pages))?
         .phase(|builder| {
@@ -97,6 +139,51 @@ pub struct PdfOptions<'a> {
     pub page_ranges: Option<PageRanges>,
     /// A list of PDF standards that Typst will enforce conformance with.
     pub standards: PdfStandards,
+    /// Whether transparent content is replaced with opaque content, which is
+    /// rasterized where necessary.
+    ///
+    /// This is required by PDF/A-1 and helps with printing workflows that
+    /// handle transparency poorly. A solid color painted over the page's solid
+    /// background is replaced by the color it composites to. Other
+    /// transparent content, like overlapping transparent shapes, gradients
+    /// with transparent stops, and images with an alpha channel, is
+    /// rasterized together with what lies beneath it. A warning lists the
+    /// pages on which this happened.
+    pub flatten_transparency: bool,
+    /// The resolution in dots per inch at which transparent content is
+    /// rasterized when flattening. Defaults to [`DEFAULT_FLATTEN_DPI`].
+    pub flatten_dpi: Smart<f32>,
+    /// The number of pieces with which a gradient segment is approximated if
+    /// its colors do not change linearly, e.g. because it is eased. More
+    /// pieces give smoother gradients, but larger files. Defaults to
+    /// [`Gradient::SEGMENT_SAMPLES`](typst_library::visualize::Gradient::SEGMENT_SAMPLES).
+    pub gradient_samples: Smart<usize>,
+    /// Whether to embed fonts regardless of the embedding permissions in
+    /// their license.
+    ///
+    /// By default, export fails with an error if a font may not be embedded
+    /// and warns if a font may only be embedded into documents that are
+    /// previewed and printed. The warning is only reported by
+    /// [`pdf_with_warnings`]. Only enable this if you have a separate license
+    /// that permits embedding the fonts.
+    pub ignore_embedding_restrictions: bool,
+    /// Files that are embedded into the PDF as attachments.
+    ///
+    /// PDF/A-1b does not allow attachments and PDF/A-2b only allows attaching
+    /// other PDF files, so export fails if an attachment violates the
+    /// requested standards.
+    pub attachments: &'a [PdfAttachment],
+}
+
+/// A file that is embedded into a PDF.
+#[derive(Debug, Clone)]
+pub struct PdfAttachment {
+    /// The name of the file, as shown by PDF viewers.
+    pub name: EcoString,
+    /// The contents of the file.
+    pub data: Bytes,
+    /// A description of the file.
+    pub description: Option<EcoString>,
 }
 
 /// A timestamp with timezone information.
@@ -147,16 +234,49 @@ pub enum Timezone {
 /// Encapsulates a list of compatible PDF standards.
 #[derive(Clone)]
 pub struct PdfStandards {
-    /// For now, we simplify to just PDF/A, since we only support PDF/A-2b. But
-    /// it can be more fine-grained in the future.
+    /// For now, we simplify to just PDF/A, since we only support PDF/A-1b and
+    /// PDF/A-2b. But it can be more fine-grained in the future.
     pub(crate) pdfa: bool,
+    /// The part of the PDF/A standard. Only meaningful if `pdfa` is set.
+    pub(crate) pdfa_part: i32,
 }
 
 impl PdfStandards {
     /// Validates a list of PDF standards for compatibility and returns their
     /// encapsulated representation.
     pub fn new(list: &[PdfStandard]) -> StrResult<Self> {
-        Ok(Self { pdfa: list.contains(&PdfStandard::A_2b) })
+        if list.contains(&PdfStandard::A_1b) {
+            if let Some(other) =
+                list.iter().find(|&&standard| standard != PdfStandard::A_1b)
+            {
+                bail!("PDF/A-1b is not compatible with {}", other.name());
+            }
+            return Ok(Self { pdfa: true, pdfa_part: 1 });
+        }
+        Ok(Self {
+            pdfa: list.contains(&PdfStandard::A_2b),
+            pdfa_part: 2,
+        })
+    }
+
+    /// The name of the PDF/A standard that is enforced, for use in
+    /// diagnostics.
+    pub(crate) fn name(&self) -> EcoString {
+        eco_format!("PDF/A-{}b", self.pdfa_part)
+    }
+
+    /// Whether the enforced standards allow transparency.
+    pub(crate) fn allows_transparency(&self) -> bool {
+        !self.pdfa || self.pdfa_part >= 2
+    }
+
+    /// The PDF version the enforced standards are based on.
+    pub(crate) fn version(&self) -> (u8, u8) {
+        if self.pdfa && self.pdfa_part == 1 {
+            (1, 4)
+        } else {
+            (1, 7)
+        }
     }
 }
 
@@ -169,7 +289,7 @@ impl Debug for PdfStandards {
 #[allow(clippy::derivable_impls)]
 impl Default for PdfStandards {
     fn default() -> Self {
-        Self { pdfa: false }
+        Self { pdfa: false, pdfa_part: 2 }
     }
 }
 
@@ -183,11 +303,25 @@ pub enum PdfStandard {
     /// PDF 1.7.
     #[serde(rename = "1.7")]
     V_1_7,
+    /// PDF/A-1b.
+    #[serde(rename = "a-1b")]
+    A_1b,
     /// PDF/A-2b.
     #[serde(rename = "a-2b")]
     A_2b,
 }
 
+impl PdfStandard {
+    /// The human-readable name of the standard.
+    fn name(self) -> &'static str {
+        match self {
+            Self::V_1_7 => "PDF 1.7",
+            Self::A_1b => "PDF/A-1b",
+            Self::A_2b => "PDF/A-2b",
+        }
+    }
+}
+
 /// A struct to build a PDF following a fixed succession of phases.
 ///
 /// This type uses generics to represent its current state. `S` (for "state") is
@@ -381,9 +515,12 @@ impl<'a> From<(WithRefs<'a>, Ref)> for WithEverything<'a> {
 impl<'a> PdfBuilder<WithDocument<'a>> {
     /// Start building a PDF for a Typst document.
     fn new(document: &'a PagedDocument, options: &'a PdfOptions<'a>) -> Self {
+        let mut pdf = Pdf::new();
+        let (major, minor) = options.standards.version();
+        pdf.set_version(major, minor);
         Self {
             alloc: Ref::new(1),
-            pdf: Pdf::new(),
+            pdf,
             state: WithDocument { document, options },
         }
     }
@@ -660,6 +797,13 @@ fn transform_to_array(ts: Transform) -> [f32; 6] {
 
 #[cfg(test)]
 mod tests {
+    use typst::testing::TestWorld;
+    use typst_library::foundations::{Bytes, Content};
+    use typst_library::layout::{Frame, FrameItem, Page, Point, Sides, Size};
+    use typst_library::model::DocumentInfo;
+    use typst_library::text::{Glyph, Lang, TextItem};
+    use typst_library::visualize::{Color, Geometry, Rgb};
+
     use super::*;
 
     #[test]
@@ -694,4 +838,277 @@ mod tests {
         assert!(Timestamp::new_local(dummy_datetime, i32::MAX).is_none());
         assert!(Timestamp::new_local(dummy_datetime, i32::MIN).is_none());
     }
+
+    #[test]
+    fn test_subset_cache_reused_across_exports() {
+        let data = typst_assets::fonts().next().unwrap();
+        let font = Font::new(Bytes::from_static(data), 0).unwrap();
+        let text = "Subset";
+        let glyphs = text
+            .char_indices()
+            .map(|(i, c)| Glyph {
+                id: font.ttf().glyph_index(c).unwrap().0,
+                x_advance: Em::new(0.5),
+                x_offset: Em::zero(),
+                range: i as u16..i as u16 + 1,
+                span: (Span::detached(), 0),
+            })
+            .collect();
+
+        let mut frame = Frame::soft(Size::splat(Abs::pt(100.0)));
+        frame.push(
+            Point::with_y(Abs::pt(20.0)),
+            FrameItem::Text(TextItem {
+                font,
+                size: Abs::pt(11.0),
+                fill: Color::BLACK.into(),
+                stroke: None,
+                lang: Lang::ENGLISH,
+                region: None,
+                text: text.into(),
+                glyphs,
+            }),
+        );
+
+        let document = PagedDocument {
+            pages: vec![Page {
+                frame,
+                fill: Smart::Auto,
+                numbering: None,
+                supplement: Content::empty(),
+                number: 1,
+                bleed: Sides::default(),
+                rotated: false,
+            }],
+            ..Default::default()
+        };
+
+        let misses = || font::SUBSETS.lock().unwrap().misses;
+        let first = pdf(&document, &PdfOptions::default()).unwrap();
+        let before = misses();
+        let second = pdf(&document, &PdfOptions::default()).unwrap();
+        assert_eq!(misses(), before);
+        assert_eq!(first, second);
+    }
+
+    /// Create a titled document with a page that has the given rectangles on
+    /// it.
+    fn rects(rects: &[(Point, Color)]) -> PagedDocument {
+        let mut frame = Frame::soft(Size::splat(Abs::pt(100.0)));
+        for &(pos, color) in rects {
+            let shape = Geometry::Rect(Size::splat(Abs::pt(20.0))).filled(color);
+            frame.push(pos, FrameItem::Shape(shape, Span::detached()));
+        }
+
+        PagedDocument {
+            pages: vec![Page {
+                frame,
+                fill: Smart::Auto,
+                numbering: None,
+                supplement: Content::empty(),
+                number: 1,
+                bleed: Sides::default(),
+                rotated: false,
+            }],
+            info: DocumentInfo { title: Some("Rects".into()), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_flatten_transparency_composites_solid_fill() {
+        let red = Color::from_u8(255, 0, 0, 255).with_alpha(0.5);
+        let document = rects(&[(Point::splat(Abs::pt(10.0)), red)]);
+        let standards = || PdfStandards::new(&[PdfStandard::A_1b]).unwrap();
+        let options = PdfOptions { standards: standards(), ..Default::default() };
+        assert!(pdf(&document, &options).is_err());
+
+        let options = PdfOptions {
+            standards: standards(),
+            flatten_transparency: true,
+            ..Default::default()
+        };
+        let Warned { output, warnings } = pdf_with_warnings(&document, &options);
+        assert!(output.is_ok());
+        assert!(warnings.is_empty());
+
+        let mut warnings = EcoVec::new();
+        let flattened = flatten(&document, &options, &mut warnings);
+        let Some((_, FrameItem::Shape(shape, _))) =
+            flattened.pages[0].frame.items().next()
+        else {
+            panic!("expected a shape");
+        };
+        assert_eq!(shape.fill, Some(Color::Rgb(Rgb::new(1.0, 0.5, 0.5, 1.0)).into()));
+    }
+
+    #[test]
+    fn test_flatten_transparency_rasterizes_overlap() {
+        let red = Color::from_u8(255, 0, 0, 255).with_alpha(0.5);
+        let blue = Color::from_u8(0, 0, 255, 255).with_alpha(0.5);
+        let document = rects(&[
+            (Point::splat(Abs::pt(10.0)), red),
+            (Point::splat(Abs::pt(20.0)), blue),
+        ]);
+        let options = PdfOptions {
+            standards: PdfStandards::new(&[PdfStandard::A_1b]).unwrap(),
+            flatten_transparency: true,
+            flatten_dpi: Smart::Custom(72.0),
+            ..Default::default()
+        };
+
+        let Warned { output, warnings } = pdf_with_warnings(&document, &options);
+        assert!(output.is_ok());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("page 1"));
+    }
+
+    #[test]
+    fn test_pdfa_metadata_declares_standard() {
+        let document = rects(&[]);
+        let export = |standards: &[PdfStandard]| {
+            let options = PdfOptions {
+                standards: PdfStandards::new(standards).unwrap(),
+                ..Default::default()
+            };
+            String::from_utf8_lossy(&pdf(&document, &options).unwrap()).into_owned()
+        };
+
+        // PDF/A-1 is based on PDF 1.4, which the header and the metadata must
+        // agree on.
+        let text = export(&[PdfStandard::A_1b]);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("<pdf:PDFVersion>1.4</pdf:PDFVersion>"));
+        assert!(text.contains("<pdfaid:part>1</pdfaid:part>"));
+        assert!(text.contains("<pdfaid:conformance>B</pdfaid:conformance>"));
+
+        let text = export(&[PdfStandard::A_2b]);
+        assert!(text.starts_with("%PDF-1.7"));
+        assert!(text.contains("<pdf:PDFVersion>1.7</pdf:PDFVersion>"));
+        assert!(text.contains("<pdfaid:part>2</pdfaid:part>"));
+
+        let text = export(&[]);
+        assert!(text.contains("<pdf:PDFVersion>1.7</pdf:PDFVersion>"));
+        assert!(!text.contains("pdfaid:part"));
+    }
+
+    #[test]
+    fn test_preflight_warns_about_missing_title() {
+        let mut document = rects(&[]);
+        let options = PdfOptions {
+            standards: PdfStandards::new(&[PdfStandard::A_2b]).unwrap(),
+            ..Default::default()
+        };
+        let Warned { output, warnings } = pdf_with_warnings(&document, &options);
+        assert!(output.is_ok());
+        assert!(warnings.is_empty());
+
+        document.info.title = None;
+        let Warned { output, warnings } = pdf_with_warnings(&document, &options);
+        assert!(output.is_ok());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "PDF/A-2b document has no title");
+
+        let Warned { warnings, .. } = pdf_with_warnings(&document, &Default::default());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_preflight_checks_attachments() {
+        let document = rects(&[]);
+        let attachment = |name: &str, data: &'static [u8]| PdfAttachment {
+            name: name.into(),
+            data: Bytes::from_static(data),
+            description: None,
+        };
+        let data = [attachment("data.csv", b"a,b\n1,2\n")];
+        let report = [attachment("report.pdf", b"%PDF-1.7\n%%EOF")];
+        let export = |attachments, standards: &[PdfStandard]| {
+            let options = PdfOptions {
+                standards: PdfStandards::new(standards).unwrap(),
+                attachments,
+                ..Default::default()
+            };
+            pdf(&document, &options)
+        };
+
+        let bytes = export(&data, &[]).unwrap();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("/EmbeddedFiles"));
+        assert!(text.contains("(data.csv)"));
+
+        let errors = export(&data, &[PdfStandard::A_1b]).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("PDF/A-1b does not allow attachments"));
+        assert!(export(&report, &[PdfStandard::A_1b]).is_err());
+
+        let errors = export(&data, &[PdfStandard::A_2b]).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("data.csv is not a PDF file"));
+        assert!(export(&report, &[PdfStandard::A_2b]).is_ok());
+    }
+
+    #[test]
+    fn test_custom_metadata() {
+        let world = TestWorld::new(
+            "#set document(title: \"Report\", metadata: (\n\
+             department: \"R&D\",\n\
+             classification: \"internal\",\n\
+             revision: 3,\n\
+             issued: datetime(year: 2024, month: 1, day: 2),\n\
+             ))\n\
+             = Report",
+        );
+        let document = typst::compile::<PagedDocument>(&world).output.unwrap();
+
+        let bytes = pdf(&document, &PdfOptions::default()).unwrap();
+        assert_eq!(bytes, pdf(&document, &PdfOptions::default()).unwrap());
+
+        let text = String::from_utf8_lossy(&bytes);
+        let xmp = roxmltree::Document::parse(xmp(&text)).unwrap();
+        let entries: Vec<_> = xmp
+            .descendants()
+            .filter(|node| node.tag_name().namespace() == Some(TYPST_NS))
+            .map(|node| (node.tag_name().name(), node.text().unwrap_or_default()))
+            .collect();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0], ("department", "R&D"));
+        assert_eq!(entries[1], ("classification", "internal"));
+        assert_eq!(entries[2], ("revision", "3"));
+        assert_eq!(entries[3].0, "issued");
+        assert!(entries[3].1.starts_with("2024-01-02"));
+
+        // The names are safe, so the entries are also in the document
+        // information dictionary.
+        for needle in ["/department (R&D)", "/revision (3)", "/issued (D:20240102"] {
+            assert!(text.contains(needle), "missing {needle}");
+        }
+
+        // PDF/A files don't get custom metadata, but a warning.
+        let options = PdfOptions {
+            standards: PdfStandards::new(&[PdfStandard::A_2b]).unwrap(),
+            ..Default::default()
+        };
+        let Warned { output, warnings } = pdf_with_warnings(&document, &options);
+        let text = String::from_utf8_lossy(&output.unwrap()).into_owned();
+        let xmp = roxmltree::Document::parse(xmp(&text)).unwrap();
+        assert!(xmp
+            .descendants()
+            .all(|node| node.tag_name().namespace() != Some(TYPST_NS)));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "custom document metadata is not exported to PDF/A-2b files"
+        );
+    }
+
+    /// The namespace of custom metadata in XMP.
+    const TYPST_NS: &str = "https://typst.app/ns/metadata/";
+
+    /// Extracts the XMP packet from the text of a PDF.
+    fn xmp(pdf: &str) -> &str {
+        let start = pdf.find("<x:xmpmeta").unwrap();
+        let end = pdf.find("</x:xmpmeta>").unwrap() + "</x:xmpmeta>".len();
+        &pdf[start..end]
+    }
 }
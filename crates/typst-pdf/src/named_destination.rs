@@ -1,9 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
+use ecow::EcoString;
 use pdf_writer::writers::Destination;
 use pdf_writer::{Ref, Str};
 use typst_library::diag::SourceResult;
-use typst_library::foundations::{Label, NativeElement};
+use typst_library::foundations::NativeElement;
 use typst_library::introspection::Location;
 use typst_library::layout::Abs;
 use typst_library::model::HeadingElem;
@@ -13,15 +14,17 @@ use crate::{AbsExt, PdfChunk, Renumber, StrExt, WithGlobalRefs};
 /// A list of destinations in the PDF document (a specific point on a specific
 /// page), that have a name associated with them.
 ///
-/// Typst creates a named destination for each heading in the document, that
-/// will then be written in the document catalog. PDF readers can then display
-/// them to show a clickable outline of the document.
+/// Typst creates a named destination for each labelled heading in the
+/// document, that will then be written in the document catalog. PDF readers
+/// can then display them to show a clickable outline of the document. The
+/// destinations are named by the heading's anchor, so that they match the
+/// `id`s in HTML export.
 #[derive(Default)]
 pub struct NamedDestinations {
-    /// A map between elements and their associated labels
-    pub loc_to_dest: HashMap<Location, Label>,
+    /// A map between elements and the names of their destinations.
+    pub loc_to_dest: HashMap<Location, EcoString>,
     /// A sorted list of all named destinations.
-    pub dests: Vec<(Label, Ref)>,
+    pub dests: Vec<(EcoString, Ref)>,
 }
 
 impl Renumber for NamedDestinations {
@@ -43,26 +46,29 @@ pub fn write_named_destinations(
 
     // Find all headings that have a label and are the first among other
     // headings with the same label.
-    let mut matches: Vec<_> = context
-        .document
-        .introspector
+    let introspector = &context.document.introspector;
+    let naming = context.document.info.anchors;
+    let mut matches: Vec<_> = introspector
         .query(&HeadingElem::elem().select())
         .iter()
         .filter_map(|elem| elem.location().zip(elem.label()))
         .filter(|&(_, label)| seen.insert(label))
+        .map(|(loc, label)| (loc, introspector.anchor_name(label, naming)))
         .collect();
 
     // Named destinations must be sorted by key.
-    matches.sort_by_key(|&(_, label)| label.resolve());
+    matches.sort_by(|(_, a), (_, b)| a.cmp(b));
 
-    for (loc, label) in matches {
+    for (loc, name) in matches {
         // Don't encode named destinations that would exceed the limit. Those
         // will instead be encoded as normal links.
-        if label.resolve().len() > Str::PDFA_LIMIT {
+        if name.len() > Str::PDFA_LIMIT {
             continue;
         }
 
-        let pos = context.document.introspector.position(loc);
+        let Some(pos) = context.document.introspector.position(loc) else {
+            continue;
+        };
         let index = pos.page.get() - 1;
         let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
 
@@ -72,8 +78,8 @@ pub fn write_named_destinations(
             let dest_ref = chunk.alloc();
             let x = pos.point.x.to_f32();
             let y = (page.content.size.y - y).to_f32();
-            out.dests.push((label, dest_ref));
-            out.loc_to_dest.insert(loc, label);
+            out.dests.push((name.clone(), dest_ref));
+            out.loc_to_dest.insert(loc, name);
             chunk
                 .indirect(dest_ref)
                 .start::<Destination>()
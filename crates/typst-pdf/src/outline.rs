@@ -188,19 +188,20 @@ fn write_outline_item(
     outline.title(TextStr::trimmed(body.plain_text().trim()));
 
     let loc = node.element.location().unwrap();
-    let pos = ctx.document.introspector.position(loc);
-    let index = pos.page.get() - 1;
-
-    // Don't link to non-exported pages.
-    if let Some((Some(page), Some(page_ref))) =
-        ctx.pages.get(index).zip(ctx.globals.pages.get(index))
-    {
-        let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
-        outline.dest().page(*page_ref).xyz(
-            pos.point.x.to_f32(),
-            (page.content.size.y - y).to_f32(),
-            None,
-        );
+    if let Some(pos) = ctx.document.introspector.position(loc) {
+        let index = pos.page.get() - 1;
+
+        // Don't link to non-exported pages.
+        if let Some((Some(page), Some(page_ref))) =
+            ctx.pages.get(index).zip(ctx.globals.pages.get(index))
+        {
+            let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
+            outline.dest().page(*page_ref).xyz(
+                pos.point.x.to_f32(),
+                (page.content.size.y - y).to_f32(),
+                None,
+            );
+        }
     }
 
     outline.finish();
@@ -5,10 +5,11 @@ use ecow::EcoString;
 use pdf_writer::types::{ActionType, AnnotationFlags, AnnotationType, NumberingStyle};
 use pdf_writer::{Filter, Finish, Name, Rect, Ref, Str};
 use typst_library::diag::SourceResult;
-use typst_library::foundations::Label;
 use typst_library::introspection::Location;
-use typst_library::layout::{Abs, Page};
+use typst_library::layout::{Abs, FrameItem, Page, Point, Sides};
 use typst_library::model::{Destination, Numbering};
+use typst_library::visualize::Geometry;
+use typst_syntax::Span;
 
 use crate::{
     content, AbsExt, PdfChunk, PdfOptions, Resources, WithDocument, WithRefs,
@@ -64,15 +65,26 @@ fn construct_page(
     out: &mut Resources<()>,
     page: &Page,
 ) -> SourceResult<EncodedPage> {
+    let mut fill = page.fill_or_transparent();
+    let mut frame = page.frame.clone();
+
+    // The content stream's origin stays at the top-left corner of the page
+    // without bleed, so the fill must be extended into the bleed separately.
+    if page.has_bleed() {
+        if let Some(paint) = fill.take() {
+            let shape = Geometry::Rect(page.full_size()).filled(paint);
+            frame.prepend(
+                Point::new(-page.bleed.left, -page.bleed.top),
+                FrameItem::Shape(shape, Span::detached()),
+            );
+        }
+    }
+
     Ok(EncodedPage {
-        content: content::build(
-            options,
-            out,
-            &page.frame,
-            page.fill_or_transparent(),
-            None,
-        )?,
+        content: content::build(options, out, &frame, fill, None)?,
         label: None,
+        bleed: page.bleed,
+        rotated: page.rotated,
     })
 }
 
@@ -122,7 +134,7 @@ fn write_page(
     ctx: &WithRefs,
     content_id: Ref,
     page_tree_ref: Ref,
-    loc_to_dest: &HashMap<Location, Label>,
+    loc_to_dest: &HashMap<Location, EcoString>,
     i: usize,
 ) {
     let Some((page, page_ref)) = ctx.pages[i].as_ref().zip(ctx.globals.pages[i]) else {
@@ -154,10 +166,12 @@ fn write_page(
                         .action()
                         .action_type(ActionType::GoTo)
                         // `key` must be a `Str`, not a `Name`.
-                        .pair(Name(b"D"), Str(key.resolve().as_bytes()));
+                        .pair(Name(b"D"), Str(key.as_bytes()));
                     continue;
+                } else if let Some(pos) = ctx.document.introspector.position(*loc) {
+                    pos
                 } else {
-                    ctx.document.introspector.position(*loc)
+                    continue;
                 }
             }
         };
@@ -181,9 +195,27 @@ fn write_page(
     let mut page_writer = chunk.page(page_ref);
     page_writer.parent(page_tree_ref);
 
+    debug_assert!(
+        page.content.size.all(|side| *side >= Abs::zero()),
+        "page size must not be negative",
+    );
     let w = page.content.size.x.to_f32();
     let h = page.content.size.y.to_f32();
-    page_writer.media_box(Rect::new(0.0, 0.0, w, h));
+    if page.bleed.iter().any(|side| *side != Abs::zero()) {
+        // The trim box is the page itself and the media box extends beyond it
+        // by the bleed.
+        let bleed = page.bleed.map(Abs::to_f32);
+        let media_box =
+            Rect::new(-bleed.left, -bleed.bottom, w + bleed.right, h + bleed.top);
+        page_writer.media_box(media_box);
+        page_writer.bleed_box(media_box);
+        page_writer.trim_box(Rect::new(0.0, 0.0, w, h));
+    } else {
+        page_writer.media_box(Rect::new(0.0, 0.0, w, h));
+    }
+    if page.rotated {
+        page_writer.rotate(90);
+    }
     page_writer.contents(content_id);
     page_writer.pair(Name(b"Resources"), ctx.resources.reference);
 
@@ -307,4 +339,8 @@ impl PdfPageLabelStyle {
 pub struct EncodedPage {
     pub content: content::Encoded,
     pub label: Option<PdfPageLabel>,
+    /// How far the page's media box extends beyond the content's size.
+    pub bleed: Sides<Abs>,
+    /// Whether the page is displayed rotated by 90 degrees clockwise.
+    pub rotated: bool,
 }
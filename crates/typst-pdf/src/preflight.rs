@@ -0,0 +1,235 @@
+//! Checking a document for conformance with the requested PDF standards and
+//! with the licenses of its fonts before exporting it.
+
+use std::collections::HashSet;
+
+use ecow::EcoVec;
+use typst_library::diag::{error, warning, SourceDiagnostic, SourceResult};
+use typst_library::layout::{Frame, FrameItem, PagedDocument};
+use typst_library::text::{EmbeddingPermissions, Font, TextItem};
+use typst_library::visualize::{ImageKind, Paint};
+use typst_syntax::Span;
+
+use crate::PdfOptions;
+
+/// Check the exported pages for violations of the requested PDF standards and
+/// for fonts whose license restricts embedding them.
+///
+/// In contrast to the checks during export, this reports all violations at
+/// once, each pointing to the offending element and the page it is on.
+#[typst_macros::time(name = "preflight")]
+pub fn preflight(
+    document: &PagedDocument,
+    options: &PdfOptions,
+    warnings: &mut EcoVec<SourceDiagnostic>,
+) -> SourceResult<()> {
+    let mut checker = Checker {
+        options,
+        page: 0,
+        fonts: HashSet::new(),
+        errors: EcoVec::new(),
+        warnings,
+    };
+
+    for (i, page) in document.pages.iter().enumerate() {
+        if options
+            .page_ranges
+            .as_ref()
+            .is_some_and(|ranges| !ranges.includes_page_index(i))
+        {
+            continue;
+        }
+
+        checker.page = i + 1;
+        checker.frame(&page.frame);
+    }
+
+    if options.standards.pdfa && !document.info.metadata.is_empty() {
+        let standard = options.standards.name();
+        checker.warnings.push(warning!(
+            Span::detached(),
+            "custom document metadata is not exported to {standard} files";
+            hint: "{standard} requires custom metadata to be described by an \
+                   extension schema, which Typst does not support yet",
+        ));
+    }
+
+    // PDF/A-1b and PDF/A-2b only recommend a title, but many validators
+    // expect one.
+    if options.standards.pdfa && document.info.title.is_none() {
+        let standard = options.standards.name();
+        checker.warnings.push(warning!(
+            Span::detached(),
+            "{standard} document has no title";
+            hint: "many validators reject {standard} files without a title";
+            hint: "set one with `#set document(title: ..)`",
+        ));
+    }
+
+    checker.attachments();
+
+    if checker.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(checker.errors)
+    }
+}
+
+/// Collects the violations while walking the frames.
+struct Checker<'a> {
+    options: &'a PdfOptions<'a>,
+    /// The physical number of the page that is currently checked.
+    page: usize,
+    /// Fonts that were already checked.
+    fonts: HashSet<Font>,
+    errors: EcoVec<SourceDiagnostic>,
+    warnings: &'a mut EcoVec<SourceDiagnostic>,
+}
+
+impl Checker<'_> {
+    fn frame(&mut self, frame: &Frame) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => self.frame(&group.frame),
+                FrameItem::Text(text) => self.text(text),
+                FrameItem::Shape(shape, span) => {
+                    let stroke = shape.stroke.as_ref().map(|stroke| &stroke.paint);
+                    for paint in shape.fill.iter().chain(stroke) {
+                        self.paint(paint, "shape", *span);
+                    }
+                }
+                FrameItem::Image(image, _, span) if self.options.standards.pdfa => {
+                    if let ImageKind::Raster(raster) = image.kind() {
+                        if raster.dynamic().color().has_alpha() {
+                            self.transparency("image", *span);
+                        }
+                    }
+                }
+                FrameItem::Image(..) | FrameItem::Link(..) | FrameItem::Tag(_) => {}
+            }
+        }
+    }
+
+    fn text(&mut self, text: &TextItem) {
+        let span = Span::find(text.glyphs.iter().map(|g| g.span.0));
+        let stroke = text.stroke.as_ref().map(|stroke| &stroke.paint);
+        for paint in std::iter::once(&text.fill).chain(stroke) {
+            self.paint(paint, "text", span);
+        }
+
+        if self.fonts.insert(text.font.clone()) {
+            self.font(&text.font, span);
+        }
+    }
+
+    /// Check that the font's license permits embedding it. Typst always
+    /// embeds subsets of the fonts it uses.
+    fn font(&mut self, font: &Font, span: Span) {
+        if self.options.ignore_embedding_restrictions {
+            return;
+        }
+
+        let family = &font.info().family;
+        let reason = match font.embedding_permissions() {
+            EmbeddingPermissions::Installable | EmbeddingPermissions::Editable => {
+                return;
+            }
+            EmbeddingPermissions::PreviewPrint => {
+                self.warnings.push(warning!(
+                    span,
+                    "font {family} may only be embedded into documents that are \
+                     not edited";
+                    hint: "the font's license only permits previewing and printing \
+                           documents that embed it",
+                ));
+                return;
+            }
+            EmbeddingPermissions::BitmapOnly => {
+                "the font's license only permits embedding its bitmaps"
+            }
+            EmbeddingPermissions::NoEmbedding => {
+                "the font's license forbids embedding it"
+            }
+        };
+
+        self.errors.push(error!(
+            span,
+            "font {family} on page {} may not be embedded",
+            self.page;
+            hint: "{reason}";
+            hint: "try using a different font, like one of the fonts that ship \
+                   with Typst";
+            hint: "if you have a license that permits embedding the font, enable \
+                   the option to ignore embedding restrictions",
+        ));
+    }
+
+    /// Check that the standards permit the attached files. Typst never
+    /// encrypts PDFs, so attachments are the only restricted kind of embedded
+    /// content.
+    fn attachments(&mut self) {
+        if !self.options.standards.pdfa {
+            return;
+        }
+
+        let standard = self.options.standards.name();
+        for attachment in self.options.attachments {
+            let name = &attachment.name;
+            if self.options.standards.pdfa_part == 1 {
+                self.errors.push(error!(
+                    Span::detached(),
+                    "attachment {name} is not allowed, because {standard} does \
+                     not allow attachments";
+                    hint: "try exporting without the attachment or to PDF/A-2b",
+                ));
+            } else if !attachment.data.starts_with(b"%PDF") {
+                self.errors.push(error!(
+                    Span::detached(),
+                    "attachment {name} is not a PDF file, but {standard} only \
+                     allows attaching PDF files";
+                    hint: "try exporting without the attachment",
+                ));
+            }
+        }
+    }
+
+    fn paint(&mut self, paint: &Paint, kind: &str, span: Span) {
+        if !self.options.standards.pdfa {
+            // Only tiling frames need to be checked, for their fonts.
+            if let Paint::Tiling(tiling) = paint {
+                self.frame(tiling.frame());
+            }
+            return;
+        }
+
+        let translucent = match paint {
+            Paint::Solid(color) => color.alpha().is_some_and(|a| a < 1.0),
+            Paint::Gradient(gradient) => gradient
+                .stops_ref()
+                .iter()
+                .any(|(color, _)| color.alpha().is_some_and(|a| a < 1.0)),
+            Paint::Tiling(tiling) => {
+                self.frame(tiling.frame());
+                false
+            }
+        };
+
+        if translucent {
+            self.transparency(kind, span);
+        }
+    }
+
+    fn transparency(&mut self, kind: &str, span: Span) {
+        if self.options.standards.allows_transparency() {
+            return;
+        }
+
+        let standard = self.options.standards.name();
+        self.errors.push(error!(
+            span,
+            "{kind} on page {} is transparent, but {standard} does not allow transparency",
+            self.page;
+            hint: "try using fully opaque colors and images",
+        ));
+    }
+}
@@ -12,24 +12,29 @@ use bumpalo::collections::{String as BumpString, Vec as BumpVec};
 use comemo::Track;
 use ecow::EcoString;
 use typst_library::diag::{bail, At, SourceResult};
-use typst_library::engine::Engine;
+use typst_library::engine::{CompileStats, Engine};
 use typst_library::foundations::{
-    Content, Context, ContextElem, Element, NativeElement, Recipe, RecipeIndex, Selector,
-    SequenceElem, Show, ShowSet, Style, StyleChain, StyleVec, StyledElem, Styles,
-    Synthesize, Transformation,
+    Content, Context, ContextElem, Element, Fields, NativeElement, NativeShowRule,
+    Recipe, RecipeIndex, Selector, SequenceElem, Show, ShowSet, Style, StyleChain,
+    StyleVec, StyledElem, Styles, Synthesize, TargetElem, Transformation,
 };
 use typst_library::html::{tag, HtmlElem};
-use typst_library::introspection::{Locatable, SplitLocator, Tag, TagElem};
+use typst_library::introspection::{
+    CounterDisplayElem, Locatable, Location, SplitLocator, Tag, TagElem,
+};
 use typst_library::layout::{
-    AlignElem, BoxElem, HElem, InlineElem, PageElem, PagebreakElem, VElem,
+    AlignElem, BoxElem, ColbreakElem, FlushElem, HElem, InlineElem, PageElem,
+    PagebreakElem, PlaceElem, RotateElem, ScaleElem, SkewElem, VElem,
 };
 use typst_library::math::{EquationElem, Mathy};
 use typst_library::model::{
-    CiteElem, CiteGroup, DocumentElem, EnumElem, ListElem, ListItemLike, ListLike,
-    ParElem, ParbreakElem, TermsElem,
+    BibliographyElem, CiteElem, CiteGroup, Destination, DocumentElem, DropCapElem,
+    EnumElem, FootnoteElem, LinkElem, LinkTarget, ListElem, ListItemLike, ListLike,
+    OutlineElem, ParElem, ParbreakElem, RefElem, TermsElem,
 };
 use typst_library::routines::{Arenas, Pair, RealizationKind};
 use typst_library::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use typst_library::World;
 use typst_syntax::Span;
 use typst_utils::{SliceExt, SmallBitSet};
 
@@ -51,20 +56,29 @@ pub fn realize<'a>(
             RealizationKind::LayoutDocument(_) | RealizationKind::LayoutFragment => {
                 LAYOUT_RULES
             }
-            RealizationKind::HtmlDocument(_) => HTML_DOCUMENT_RULES,
-            RealizationKind::HtmlFragment => HTML_FRAGMENT_RULES,
+            RealizationKind::HtmlDocument(_)
+            | RealizationKind::HtmlFragment { paragraphs: true } => HTML_DOCUMENT_RULES,
+            RealizationKind::HtmlFragment { paragraphs: false } => HTML_FRAGMENT_RULES,
             RealizationKind::Math => MATH_RULES,
         },
         sink: vec![],
         groupings: ArrayVec::new(),
         outside: matches!(kind, RealizationKind::LayoutDocument(_)),
         may_attach: false,
+        predecessor: None,
+        visible: 0,
         kind,
     };
 
     visit(&mut s, content, styles)?;
     finish(&mut s)?;
 
+    s.engine.sink.count(CompileStats {
+        elements: s.sink.len(),
+        peak_arena_bytes: arenas.allocated_bytes(),
+        ..CompileStats::default()
+    });
+
     Ok(s.sink)
 }
 
@@ -98,6 +112,11 @@ struct State<'a, 'x, 'y, 'z> {
     outside: bool,
     /// Whether now following attach spacing can survive.
     may_attach: bool,
+    /// The last visible block-level element. Paragraphs record this so that
+    /// layout can decide whether to indent them.
+    predecessor: Option<Element>,
+    /// How many visible block-level elements were output so far.
+    visible: usize,
 }
 
 /// Defines a rule for how certain elements shall be grouped during realization.
@@ -111,6 +130,9 @@ struct GroupingRule {
     tags: bool,
     /// Defines which kinds of elements start and make up this kind of grouping.
     trigger: fn(&Content, &RealizationKind) -> bool,
+    /// Defines elements that trigger this kind of grouping, but must not be
+    /// grouped with other elements. Each of them forms a group of its own.
+    isolate: fn(&Content, StyleChain) -> bool,
     /// Defines elements that may appear in the interior of the grouping, but
     /// not at the edges.
     inner: fn(&Content) -> bool,
@@ -146,12 +168,16 @@ struct Verdict<'a> {
     map: Styles,
     /// An optional show rule transformation to apply to the element.
     step: Option<ShowStep<'a>>,
+    /// A freshly generated location for the element, if it needs one.
+    location: Option<Location>,
 }
 
 /// A show rule transformation to apply to the element.
 enum ShowStep<'a> {
     /// A user-defined transformational show rule.
     Recipe(&'a Recipe, RecipeIndex),
+    /// A show rule that the embedder implemented natively.
+    Native(NativeShowRule),
     /// The built-in show rule.
     Builtin,
 }
@@ -230,12 +256,23 @@ fn visit<'a>(
         return Ok(());
     }
 
+    // Let layout know when something may depend on what comes later.
+    if looks_ahead(content) {
+        s.engine.sink.lookahead();
+    }
+
     // Transformations for math content based on the realization kind. Needs
     // to happen before show rules.
     if visit_math_rules(s, content, styles)? {
         return Ok(());
     }
 
+    // Reflowing transforms within paragraphs are laid out inline. Needs to
+    // happen before show rules turn them into blocks.
+    if visit_transform_rules(s, content, styles)? {
+        return Ok(());
+    }
+
     // Apply show rules and preparation.
     if visit_show_rules(s, content, styles)? {
         return Ok(());
@@ -269,10 +306,46 @@ fn visit<'a>(
     // No further transformations to apply, so we can finally just push it to
     // the output!
     s.sink.push((content, styles));
+    track_predecessor(s, content);
 
     Ok(())
 }
 
+/// Whether showing the element may require introspection of parts of the
+/// document that come after it.
+///
+/// This is conservative: For instance, all `context` expressions are assumed
+/// to look ahead even though many of them only look at earlier content.
+fn looks_ahead(content: &Content) -> bool {
+    content.is::<ContextElem>()
+        || content.is::<OutlineElem>()
+        || content.is::<RefElem>()
+        || content.is::<CiteGroup>()
+        || content.is::<BibliographyElem>()
+        || content
+            .to_packed::<CounterDisplayElem>()
+            .is_some_and(|elem| elem.both)
+        || content.to_packed::<FootnoteElem>().is_some_and(|elem| elem.is_ref())
+        || content.to_packed::<LinkElem>().is_some_and(|elem| {
+            !matches!(elem.dest, LinkTarget::Dest(Destination::Url(_)))
+        })
+}
+
+/// Keeps track of the last visible block-level element for paragraphs.
+fn track_predecessor(s: &mut State, content: &Content) {
+    if content.is::<PagebreakElem>() {
+        // Paragraphs after a page break start a new flow.
+        s.predecessor = None;
+    } else if !(content.is::<VElem>()
+        || content.is::<PlaceElem>()
+        || content.is::<FlushElem>()
+        || content.is::<ColbreakElem>())
+    {
+        s.predecessor = Some(content.elem());
+        s.visible += 1;
+    }
+}
+
 // Handles special cases for math in normal content and nested equations in
 // math.
 fn visit_math_rules<'a>(
@@ -313,6 +386,49 @@ fn visit_math_rules<'a>(
     Ok(false)
 }
 
+/// Wraps reflowing transforms that appear within a paragraph into boxes, so
+/// that the line makes room for their transformed bounding box.
+fn visit_transform_rules<'a>(
+    s: &mut State<'a, '_, '_, '_>,
+    content: &'a Content,
+    styles: StyleChain<'a>,
+) -> SourceResult<bool> {
+    if !matches!(
+        s.kind,
+        RealizationKind::LayoutDocument(_) | RealizationKind::LayoutFragment
+    ) {
+        return Ok(false);
+    }
+
+    // Only a transform that follows inline content is part of a paragraph.
+    // One at the start of a paragraph stays a block, as before.
+    let in_par = s.groupings.iter().any(|grouping| {
+        std::ptr::eq(grouping.rule, &PAR) || std::ptr::eq(grouping.rule, &TEXTUAL)
+    });
+    if !in_par {
+        return Ok(false);
+    }
+
+    let baseline = if let Some(elem) = content.to_packed::<RotateElem>() {
+        elem.reflow(styles).then(|| elem.baseline(styles))
+    } else if let Some(elem) = content.to_packed::<ScaleElem>() {
+        elem.reflow(styles).then(|| elem.baseline(styles))
+    } else if let Some(elem) = content.to_packed::<SkewElem>() {
+        elem.reflow(styles).then(|| elem.baseline(styles))
+    } else {
+        None
+    };
+
+    let Some(baseline) = baseline else { return Ok(false) };
+    let boxed = BoxElem::new()
+        .with_body(Some(content.clone()))
+        .with_baseline(baseline)
+        .pack()
+        .spanned(content.span());
+    visit(s, s.store(boxed), styles)?;
+    Ok(true)
+}
+
 /// Tries to apply show rules to or prepare content. Returns `true` if the
 /// element was handled.
 fn visit_show_rules<'a>(
@@ -321,7 +437,8 @@ fn visit_show_rules<'a>(
     styles: StyleChain<'a>,
 ) -> SourceResult<bool> {
     // Determines whether and how to proceed with show rule application.
-    let Some(Verdict { prepared, mut map, step }) = verdict(s.engine, content, styles)
+    let Some(Verdict { prepared, mut map, step, location }) =
+        verdict(s.engine, s.locator, content, styles)
     else {
         return Ok(false);
     };
@@ -333,7 +450,7 @@ fn visit_show_rules<'a>(
     // prepare it.
     let mut tags = None;
     if !prepared {
-        tags = prepare(s.engine, s.locator, output.to_mut(), &mut map, styles)?;
+        tags = prepare(s.engine, output.to_mut(), location, &mut map, styles)?;
     }
 
     // Apply a show rule step, if there is one.
@@ -350,6 +467,9 @@ fn visit_show_rules<'a>(
                 )
             }
 
+            // Apply a native show rule.
+            ShowStep::Native(rule) => rule(&output, s.engine, chained),
+
             // Apply a built-in show rule.
             ShowStep::Builtin => {
                 output.with::<dyn Show>().unwrap().show(s.engine, chained)
@@ -378,6 +498,7 @@ fn visit_show_rules<'a>(
     }
 
     let prev_outside = s.outside;
+    let prev_visible = s.visible;
     s.outside &= content.is::<ContextElem>();
     s.engine.route.increase();
     s.engine.route.check_show_depth().at(content.span())?;
@@ -387,6 +508,17 @@ fn visit_show_rules<'a>(
     s.outside = prev_outside;
     s.engine.route.decrease();
 
+    // If the shown element produced visible block-level output, a following
+    // paragraph follows this element rather than whatever its show rule
+    // produced. Wrappers that just pass through their body are ignored.
+    if s.visible > prev_visible
+        && s.groupings.is_empty()
+        && !content.is::<ContextElem>()
+        && !content.is::<AlignElem>()
+    {
+        s.predecessor = Some(content.elem());
+    }
+
     // Push end tag.
     if let Some(tag) = end {
         visit(s, s.store(TagElem::packed(tag)), styles)?;
@@ -399,6 +531,7 @@ fn visit_show_rules<'a>(
 /// proceed with the styling.
 fn verdict<'a>(
     engine: &mut Engine,
+    locator: &mut SplitLocator,
     target: &'a Content,
     styles: StyleChain<'a>,
 ) -> Option<Verdict<'a>> {
@@ -406,6 +539,25 @@ fn verdict<'a>(
     let mut map = Styles::new();
     let mut step = None;
 
+    // Generate a location for the element, which uniquely identifies it in
+    // the document. This has some overhead, so we only do it for elements
+    // that are explicitly marked as locatable, labelled elements, and
+    // paragraphs if requested. We do it here rather than during preparation
+    // so that pre-synthesis can already make use of it.
+    //
+    // The element could already have a location even if it is not prepared
+    // when it stems from a query.
+    let mut location = None;
+    if !prepared
+        && target.location().is_none()
+        && (target.can::<dyn Locatable>()
+            || target.label().is_some()
+            || (target.is::<ParElem>() && ParElem::located_in(styles)))
+    {
+        let key = typst_utils::hash128(target);
+        location = Some(locator.next_location(engine.introspector, key));
+    }
+
     // Do pre-synthesis on a cloned element to be able to match on synthesized
     // fields before real synthesis runs (during preparation). It's really
     // unfortunate that we have to do this, but otherwise
@@ -414,6 +566,9 @@ fn verdict<'a>(
     let mut slot;
     if !prepared && target.can::<dyn Synthesize>() {
         slot = target.clone();
+        if let Some(loc) = location {
+            slot.set_location(loc);
+        }
         slot.with_mut::<dyn Synthesize>()
             .unwrap()
             .synthesize(engine, styles)
@@ -466,7 +621,15 @@ fn verdict<'a>(
         }
     }
 
-    // If we found no user-defined rule, also consider the built-in show rule.
+    // If we found no user-defined rule, consider a native rule of the embedder
+    // and then the built-in show rule.
+    if step.is_none() {
+        let library = engine.world.library();
+        if !library.rules.is_empty() {
+            let rule = library.rules.get(target.elem(), TargetElem::target_in(styles));
+            step = rule.map(ShowStep::Native);
+        }
+    }
     if step.is_none() && target.can::<dyn Show>() {
         step = Some(ShowStep::Builtin);
     }
@@ -475,7 +638,8 @@ fn verdict<'a>(
     if step.is_none()
         && map.is_empty()
         && (prepared || {
-            target.label().is_none()
+            location.is_none()
+                && target.label().is_none()
                 && target.location().is_none()
                 && !target.can::<dyn ShowSet>()
                 && !target.can::<dyn Locatable>()
@@ -485,31 +649,33 @@ fn verdict<'a>(
         return None;
     }
 
-    Some(Verdict { prepared, map, step })
+    Some(Verdict { prepared, map, step, location })
 }
 
 /// This is only executed the first time an element is visited.
 fn prepare(
     engine: &mut Engine,
-    locator: &mut SplitLocator,
     target: &mut Content,
+    location: Option<Location>,
     map: &mut Styles,
     styles: StyleChain,
 ) -> SourceResult<Option<(Tag, Tag)>> {
-    // Generate a location for the element, which uniquely identifies it in
-    // the document. This has some overhead, so we only do it for elements
-    // that are explicitly marked as locatable and labelled elements.
-    //
-    // The element could already have a location even if it is not prepared
-    // when it stems from a query.
+    // Assign the location generated while determining the verdict.
     let key = typst_utils::hash128(&target);
-    if target.location().is_none()
-        && (target.can::<dyn Locatable>() || target.label().is_some())
-    {
-        let loc = locator.next_location(engine.introspector, key);
+    if let Some(loc) = location {
         target.set_location(loc);
     }
 
+    // Check the label against the document's label policy, if there is one.
+    // The errors are delayed so that all violations are reported at once.
+    if let Some(label) = target.label() {
+        if let Some(policy) = DocumentElem::label_policy_in(styles) {
+            if let Err(errors) = policy.check(label).at(target.span()) {
+                engine.sink.delay(errors);
+            }
+        }
+    }
+
     // Apply built-in show-set rules. User-defined show-set rules are already
     // considered in the map built while determining the verdict.
     if let Some(show_settable) = target.with::<dyn ShowSet>() {
@@ -555,6 +721,13 @@ fn visit_styled<'a>(
         return visit(s, content, outer);
     }
 
+    // A style boundary cages its styles just like a show rule.
+    let boundary = local.iter().any(Style::is_boundary);
+    let prev_outside = s.outside;
+    if boundary {
+        s.outside = false;
+    }
+
     // Check for document and page styles.
     let mut pagebreak = false;
     for style in local.iter() {
@@ -562,13 +735,24 @@ fn visit_styled<'a>(
         if elem == DocumentElem::elem() {
             match &mut s.kind {
                 RealizationKind::LayoutDocument(info)
-                | RealizationKind::HtmlDocument(info) => info.populate(&local),
+                | RealizationKind::HtmlDocument(info) => {
+                    info.populate(&local);
+                    let world = s.engine.world;
+                    if info.date_from_source && world.modified(world.main()).is_none() {
+                        bail!(
+                            style.span(),
+                            "cannot derive the document date from its source files";
+                            hint: "the environment does not provide modification dates";
+                            hint: "try setting the date explicitly instead"
+                        );
+                    }
+                }
                 _ => bail!(
                     style.span(),
                     "document set rules are not allowed inside of containers"
                 ),
             }
-        } else if elem == PageElem::elem() {
+        } else if elem == PageElem::elem() && !is_marginal_info(style) {
             let RealizationKind::LayoutDocument(_) = s.kind else {
                 let span = style.span();
                 bail!(span, "page configuration is not allowed inside of containers");
@@ -615,9 +799,21 @@ fn visit_styled<'a>(
         visit(s, PagebreakElem::shared_boundary(), *outer)?;
     }
 
+    if boundary {
+        s.outside = prev_outside;
+    }
+
     Ok(())
 }
 
+/// Whether the style only provides the information for `page.context` to a
+/// page's marginals. Unlike other page styles, it is set within the page.
+fn is_marginal_info(style: &Style) -> bool {
+    style.property().is_some_and(|property| {
+        property.is(PageElem::elem(), <PageElem as Fields>::Enum::Marginal as u8)
+    })
+}
+
 /// Tries to group the content in an active group or start a new one if any
 /// grouping rule matches. Returns `true` if the element was grouped.
 fn visit_grouping_rules<'a>(
@@ -626,6 +822,7 @@ fn visit_grouping_rules<'a>(
     styles: StyleChain<'a>,
 ) -> SourceResult<bool> {
     let matching = s.rules.iter().find(|&rule| (rule.trigger)(content, &s.kind));
+    let isolated = matching.is_some_and(|rule| (rule.isolate)(content, styles));
 
     // Try to continue or finish an existing grouping.
     while let Some(active) = s.groupings.last() {
@@ -635,7 +832,9 @@ fn visit_grouping_rules<'a>(
         }
 
         // If the element can be added to the active grouping, do it.
-        if (active.rule.trigger)(content, &s.kind) || (active.rule.inner)(content) {
+        if !isolated
+            && ((active.rule.trigger)(content, &s.kind) || (active.rule.inner)(content))
+        {
             s.sink.push((content, styles));
             return Ok(true);
         }
@@ -643,11 +842,15 @@ fn visit_grouping_rules<'a>(
         finish_innermost_grouping(s)?;
     }
 
-    // Start a new grouping.
+    // Start a new grouping. An isolated element is grouped on its own right
+    // away, so that no following element can join it.
     if let Some(rule) = matching {
         let start = s.sink.len();
         s.groupings.push(Grouping { start, rule });
         s.sink.push((content, styles));
+        if isolated {
+            finish_innermost_grouping(s)?;
+        }
         return Ok(true);
     }
 
@@ -662,7 +865,10 @@ fn visit_filter_rules<'a>(
     styles: StyleChain<'a>,
 ) -> SourceResult<bool> {
     if content.is::<SpaceElem>()
-        && !matches!(s.kind, RealizationKind::Math | RealizationKind::HtmlFragment)
+        && !matches!(
+            s.kind,
+            RealizationKind::Math | RealizationKind::HtmlFragment { paragraphs: false }
+        )
     {
         // Outside of maths, spaces that were not collected by the paragraph
         // grouper don't interest us.
@@ -799,6 +1005,7 @@ static TEXTUAL: GroupingRule = GroupingRule {
             || elem == LinebreakElem::elem()
             || elem == SmartQuoteElem::elem()
     },
+    isolate: |_, _| false,
     inner: |content| content.elem() == SpaceElem::elem(),
     // Any kind of style interrupts this kind of grouping since regex show
     // rules cannot match over style changes anyway.
@@ -818,13 +1025,15 @@ static PAR: GroupingRule = GroupingRule {
             || elem == SmartQuoteElem::elem()
             || elem == InlineElem::elem()
             || elem == BoxElem::elem()
+            || elem == DropCapElem::elem()
             || (matches!(
                 kind,
-                RealizationKind::HtmlDocument(_) | RealizationKind::HtmlFragment
+                RealizationKind::HtmlDocument(_) | RealizationKind::HtmlFragment { .. }
             ) && content
                 .to_packed::<HtmlElem>()
                 .is_some_and(|elem| tag::is_inline_by_default(elem.tag)))
     },
+    isolate: |_, _| false,
     inner: |content| content.elem() == SpaceElem::elem(),
     interrupt: |elem| elem == ParElem::elem() || elem == AlignElem::elem(),
     finish: finish_par,
@@ -835,6 +1044,11 @@ static CITES: GroupingRule = GroupingRule {
     priority: 2,
     tags: false,
     trigger: |content, _| content.elem() == CiteElem::elem(),
+    isolate: |content, styles| {
+        content
+            .to_packed::<CiteElem>()
+            .is_some_and(|elem| !elem.group(styles))
+    },
     inner: |content| content.elem() == SpaceElem::elem(),
     interrupt: |elem| {
         elem == CiteGroup::elem() || elem == ParElem::elem() || elem == AlignElem::elem()
@@ -857,6 +1071,7 @@ const fn list_like_grouping<T: ListLike>() -> GroupingRule {
         priority: 2,
         tags: false,
         trigger: |content, _| content.elem() == T::Item::elem(),
+        isolate: |_, _| false,
         inner: |content| {
             let elem = content.elem();
             elem == SpaceElem::elem() || elem == ParbreakElem::elem()
@@ -922,8 +1137,9 @@ fn finish_par(mut grouped: Grouped) -> SourceResult<()> {
 
     // Create and visit the paragraph.
     let s = grouped.end();
-    let elem = ParElem::new(children).pack().spanned(span);
-    visit(s, s.store(elem), trunk)
+    let mut elem = ParElem::new(children);
+    elem.push_predecessor(s.predecessor);
+    visit(s, s.store(elem.pack().spanned(span)), trunk)
 }
 
 /// Builds the `CiteGroup` from `CiteElem`s.
@@ -1065,7 +1281,7 @@ fn find_regex_match_in_str<'a>(
     for entry in styles.entries() {
         let recipe = match &**entry {
             Style::Recipe(recipe) => recipe,
-            Style::Property(_) => continue,
+            Style::Property(_) | Style::Boundary => continue,
             Style::Revocation(index) => {
                 revoked.insert(index.0);
                 continue;
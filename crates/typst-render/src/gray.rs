@@ -0,0 +1,223 @@
+//! Conversion of rendered pages into grayscale for e-ink and similar displays.
+
+use tiny_skia as sk;
+use typst_library::layout::Page;
+
+/// How to distribute the error that arises when reducing the number of gray
+/// levels.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Dither {
+    /// Every pixel is rounded to the nearest level.
+    None,
+    /// The rounding error is diffused to the neighbouring pixels with the
+    /// Floyd–Steinberg weights.
+    ///
+    /// The error is only diffused within smooth areas like gradients and
+    /// photos. The antialiased edges of text and shapes are rounded like
+    /// without dithering, so that they stay crisp instead of becoming noisy.
+    #[default]
+    FloydSteinberg,
+}
+
+/// Options for rendering a page into grayscale.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct GrayscaleOptions {
+    /// The number of gray levels, including black and white. Values below two
+    /// are treated as two.
+    pub levels: u8,
+    /// How to dither.
+    pub dither: Dither,
+}
+
+impl Default for GrayscaleOptions {
+    /// Sixteen levels with Floyd–Steinberg dithering, which is what most
+    /// e-ink displays support.
+    fn default() -> Self {
+        Self { levels: 16, dither: Dither::FloydSteinberg }
+    }
+}
+
+/// A grayscale image with packed pixels.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct GrayImage {
+    /// The width in pixels.
+    pub width: u32,
+    /// The height in pixels.
+    pub height: u32,
+    /// The number of gray levels.
+    pub levels: u8,
+    /// The packed pixels, as described in [`to_grayscale`].
+    pub data: Vec<u8>,
+}
+
+impl GrayImage {
+    /// The number of bits that each pixel takes up.
+    pub fn bits_per_pixel(&self) -> u8 {
+        bits_per_pixel(self.levels)
+    }
+
+    /// The gray level of the pixel at the given position, from zero for black
+    /// to `levels - 1` for white.
+    pub fn get(&self, x: u32, y: u32) -> Option<u8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let bits = self.bits_per_pixel() as usize;
+        let stride = row_len(self.width, bits);
+        let bit = x as usize * bits;
+        let byte = self.data[y as usize * stride + bit / 8];
+        let shift = 8 - bits - bit % 8;
+        Some((byte >> shift) & mask(bits))
+    }
+}
+
+/// Export a page into a grayscale image.
+///
+/// This renders the page like [`render`](crate::render) and converts it with
+/// [`to_grayscale`]. The full-color image is dropped as soon as its luminance
+/// is known.
+#[typst_macros::time(name = "render grayscale")]
+pub fn render_grayscale(
+    page: &Page,
+    pixel_per_pt: f32,
+    options: GrayscaleOptions,
+) -> GrayImage {
+    let pixmap = crate::render(page, pixel_per_pt);
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let luma = luminance(&pixmap);
+    drop(pixmap);
+
+    let levels = options.levels.max(2);
+    let data = quantize(&luma, width, height, levels, options.dither);
+    GrayImage { width, height, levels, data }
+}
+
+/// Convert a rendered image into packed grayscale pixels with the given number
+/// of levels.
+///
+/// The luminance of each pixel is computed from its linear-light color after
+/// compositing it onto white and then quantized to the nearest of `levels`
+/// evenly spaced sRGB gray levels. Level zero is black and level `levels - 1`
+/// is white. Values of `levels` below two are treated as two.
+///
+/// Each pixel takes up the smallest number of bits out of 1, 2, 4, and 8 that
+/// fits all levels. Pixels are packed from the most significant bit of each
+/// byte onwards and every row starts on a new byte.
+pub fn to_grayscale(pixmap: &sk::Pixmap, levels: u8, dither: Dither) -> Vec<u8> {
+    let luma = luminance(pixmap);
+    quantize(&luma, pixmap.width(), pixmap.height(), levels.max(2), dither)
+}
+
+/// Compute the sRGB-encoded luminance of each pixel, composited onto white.
+fn luminance(pixmap: &sk::Pixmap) -> Vec<u8> {
+    let linear: Vec<f32> = (0..=255u8)
+        .map(|v| {
+            let v = v as f32 / 255.0;
+            if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            }
+        })
+        .collect();
+
+    pixmap
+        .pixels()
+        .iter()
+        .map(|pixel| {
+            // The pixels are premultiplied, so compositing onto white just
+            // adds the uncovered part.
+            let white = 255 - pixel.alpha();
+            let [r, g, b] = [pixel.red(), pixel.green(), pixel.blue()]
+                .map(|c| linear[c.saturating_add(white) as usize]);
+            let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let encoded = if y <= 0.0031308 {
+                12.92 * y
+            } else {
+                1.055 * y.powf(1.0 / 2.4) - 0.055
+            };
+            (encoded * 255.0).round().clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+/// Reduce luminance values to the given number of levels and pack them.
+fn quantize(luma: &[u8], width: u32, height: u32, levels: u8, dither: Dither) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let bits = bits_per_pixel(levels) as usize;
+    let stride = row_len(width as u32, bits);
+    let step = 255.0 / (levels - 1) as f32;
+    let mut data = vec![0; stride * height];
+
+    // The error that is diffused into the current and the next row. The
+    // buffers have an extra pixel on each side to skip bounds checks.
+    let mut current = vec![0.0f32; width + 2];
+    let mut next = vec![0.0f32; width + 2];
+
+    for y in 0..height {
+        let row = &luma[y * width..(y + 1) * width];
+        for x in 0..width {
+            let value = row[x] as f32 + current[x + 1];
+            let level = (value / step).round().clamp(0.0, (levels - 1) as f32);
+            let error = value - level * step;
+
+            let shift = 8 - bits - (x * bits) % 8;
+            data[y * stride + x * bits / 8] |= (level as u8) << shift;
+
+            if dither == Dither::FloydSteinberg && is_smooth(luma, width, x, y, step) {
+                current[x + 2] += error * 7.0 / 16.0;
+                next[x] += error * 3.0 / 16.0;
+                next[x + 1] += error * 5.0 / 16.0;
+                next[x + 2] += error * 1.0 / 16.0;
+            }
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        next.fill(0.0);
+    }
+
+    data
+}
+
+/// Whether none of the direct neighbours of a pixel differs from it by more
+/// than one level.
+///
+/// Error is only diffused from such pixels. At the edges of text and shapes,
+/// the luminance jumps within a pixel or two, so their antialiasing is kept
+/// as is instead of being turned into noise.
+fn is_smooth(luma: &[u8], width: usize, x: usize, y: usize, step: f32) -> bool {
+    let height = luma.len() / width;
+    let value = luma[y * width + x];
+    let neighbours = [
+        x.checked_sub(1).map(|x| (x, y)),
+        (x + 1 < width).then_some((x + 1, y)),
+        y.checked_sub(1).map(|y| (x, y)),
+        (y + 1 < height).then_some((x, y + 1)),
+    ];
+
+    neighbours
+        .into_iter()
+        .flatten()
+        .all(|(x, y)| (luma[y * width + x].abs_diff(value) as f32) <= step)
+}
+
+/// The number of bits per pixel for the given number of levels.
+fn bits_per_pixel(levels: u8) -> u8 {
+    match levels {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    }
+}
+
+/// The number of bytes in a row of packed pixels.
+fn row_len(width: u32, bits: usize) -> usize {
+    (width as usize * bits).div_ceil(8)
+}
+
+/// A mask for the lowest `bits` bits.
+fn mask(bits: usize) -> u8 {
+    (((1u16) << bits) - 1) as u8
+}
@@ -1,10 +1,15 @@
+use std::io::Cursor;
 use std::sync::Arc;
 
 use image::imageops::FilterType;
 use image::{GenericImageView, Rgba};
 use tiny_skia as sk;
-use typst_library::layout::Size;
-use typst_library::visualize::{Image, ImageKind};
+use typst_library::diag::StrResult;
+use typst_library::foundations::{Bytes, Smart};
+use typst_library::layout::{Axes, Page, Size};
+use typst_library::visualize::{
+    Image, ImageFormat, ImageKind, ImageScaling, RasterFormat, RasterImage, SvgImage,
+};
 
 use crate::{AbsExt, State};
 
@@ -55,6 +60,54 @@ pub fn render_image(
     Some(())
 }
 
+/// Export a page into an opaque PNG image.
+///
+/// Transparent areas of the page are painted over white paper, so the image
+/// has no alpha channel. Returns `None` if the image could not be encoded.
+#[typst_macros::time(name = "render opaque")]
+pub fn render_opaque(page: &Page, pixel_per_pt: f32) -> Option<Image> {
+    let pixmap = crate::render(page, pixel_per_pt);
+
+    // Composite the premultiplied pixels over white.
+    let rgb = pixmap
+        .data()
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            [r, g, b].map(|c| c.saturating_add(255 - a))
+        })
+        .collect();
+
+    let buffer = image::RgbImage::from_raw(pixmap.width(), pixmap.height(), rgb)?;
+    let mut data = vec![];
+    buffer
+        .write_to(&mut Cursor::new(&mut data), image::ImageFormat::Png)
+        .ok()?;
+
+    Image::new(Bytes::from(data), ImageFormat::Raster(RasterFormat::Png), None).ok()
+}
+
+/// Renders an SVG image into a PNG raster image with the given pixel size.
+///
+/// The result is memoized, so an SVG that is embedded multiple times at the
+/// same size is only rasterized once.
+#[comemo::memoize]
+#[typst_macros::time(name = "rasterize svg")]
+pub fn rasterize_svg(svg: &SvgImage, size: Axes<u32>) -> StrResult<RasterImage> {
+    let Axes { x: width, y: height } = size;
+    let mut pixmap = sk::Pixmap::new(width, height)
+        .ok_or_else(|| format!("cannot rasterize SVG at {width}x{height} pixels"))?;
+    let ts = sk::Transform::from_scale(
+        width as f32 / svg.tree().size().width(),
+        height as f32 / svg.tree().size().height(),
+    );
+    resvg::render(svg.tree(), ts, &mut pixmap.as_mut());
+    let png = pixmap
+        .encode_png()
+        .map_err(|err| format!("failed to encode rasterized SVG ({err})"))?;
+    RasterImage::new(Bytes::from(png), RasterFormat::Png)
+}
+
 /// Prepare a texture for an image at a scaled size.
 #[comemo::memoize]
 fn scaled_texture(image: &Image, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
@@ -62,8 +115,11 @@ fn scaled_texture(image: &Image, w: u32, h: u32) -> Option<Arc<sk::Pixmap>> {
     match image.kind() {
         ImageKind::Raster(raster) => {
             let downscale = w < raster.width();
-            let filter =
-                if downscale { FilterType::Lanczos3 } else { FilterType::CatmullRom };
+            let filter = match image.scaling() {
+                Smart::Custom(ImageScaling::Pixelated) => FilterType::Nearest,
+                _ if downscale => FilterType::Lanczos3,
+                _ => FilterType::CatmullRom,
+            };
             let buf = raster.dynamic().resize(w, h, filter);
             for ((_, _, src), dest) in buf.pixels().zip(pixmap.pixels_mut()) {
                 let Rgba([r, g, b, a]) = src;
@@ -1,15 +1,22 @@
 //! Rendering of Typst documents into raster images.
 
+mod gray;
 mod image;
 mod paint;
 mod shape;
 mod text;
 
+pub use self::gray::{
+    render_grayscale, to_grayscale, Dither, GrayImage, GrayscaleOptions,
+};
+pub use self::image::{rasterize_svg, render_opaque};
+
 use tiny_skia as sk;
 use typst_library::layout::{
-    Abs, Axes, Frame, FrameItem, FrameKind, GroupItem, Page, PagedDocument, Point, Size,
-    Transform,
+    Abs, Axes, Frame, FrameItem, FrameKind, GroupItem, Page, PagedDocument, Point, Ratio,
+    Sides, Size, Transform,
 };
+use typst_library::model::{link_regions, LinkRegion};
 use typst_library::visualize::{Color, Geometry, Paint};
 
 /// Export a page into a raster image.
@@ -18,7 +25,26 @@ use typst_library::visualize::{Color, Geometry, Paint};
 /// the resulting `tiny-skia` pixel buffer.
 #[typst_macros::time(name = "render")]
 pub fn render(page: &Page, pixel_per_pt: f32) -> sk::Pixmap {
-    let size = page.frame.size();
+    render_with_bleed(page, pixel_per_pt, Sides::default())
+}
+
+/// Export a page into a raster image, including its bleed.
+///
+/// In contrast to [`render`], the image also shows the area around the page
+/// into which the page's fill, background, and foreground extend. For pages
+/// without bleed, both functions produce the same image.
+#[typst_macros::time(name = "render bleed")]
+pub fn render_bleed(page: &Page, pixel_per_pt: f32) -> sk::Pixmap {
+    render_with_bleed(page, pixel_per_pt, page.bleed)
+}
+
+/// Render a page that is extended by the given bleed.
+fn render_with_bleed(page: &Page, pixel_per_pt: f32, bleed: Sides<Abs>) -> sk::Pixmap {
+    debug_assert!(
+        page.frame.size().all(|side| *side >= Abs::zero()),
+        "page size must not be negative",
+    );
+    let size = page.frame.size() + bleed.sum_by_axis();
     let pxw = (pixel_per_pt * size.x.to_f32()).round().max(1.0) as u32;
     let pxh = (pixel_per_pt * size.y.to_f32()).round().max(1.0) as u32;
 
@@ -31,16 +57,28 @@ pub fn render(page: &Page, pixel_per_pt: f32) -> sk::Pixmap {
         if let Paint::Solid(color) = fill {
             canvas.fill(paint::to_sk_color(color));
         } else {
-            let rect = Geometry::Rect(page.frame.size()).filled(fill);
+            let rect = Geometry::Rect(size).filled(fill);
             shape::render_shape(&mut canvas, state, &rect);
         }
     }
 
+    let ts = ts.pre_translate(bleed.left.to_f32(), bleed.top.to_f32());
+    let state = State::new(page.frame.size(), ts, pixel_per_pt);
     render_frame(&mut canvas, state, &page.frame);
 
     canvas
 }
 
+/// Determine the link areas of a page in pixel coordinates.
+///
+/// The regions match the image produced by [`render`] with the same number of
+/// pixels per point. Links to locations in the document stay unresolved; use
+/// [`link_regions`] with the document's introspector to resolve them.
+pub fn link_map(page: &Page, pixel_per_pt: f32) -> Vec<LinkRegion> {
+    let scale = Ratio::new(pixel_per_pt.into());
+    link_regions(&page.frame, Transform::scale(scale, scale), None)
+}
+
 /// Export a document with potentially multiple pages into a single raster image.
 pub fn render_merged(
     document: &PagedDocument,
@@ -175,17 +213,21 @@ fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
 
 /// Render a group frame with optional transform and clipping into the canvas.
 fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &GroupItem) {
+    if group.is_invisible() {
+        return;
+    }
+
+    let Some(container_inverse) = state.container_transform.invert() else {
+        return;
+    };
+
     let sk_transform = to_sk_transform(&group.transform);
     let state = match group.frame.kind() {
         FrameKind::Soft => state.pre_translate(pos).pre_concat(sk_transform),
         FrameKind::Hard => state
             .pre_translate(pos)
             .pre_concat(sk_transform)
-            .pre_concat_container(
-                state
-                    .transform
-                    .post_concat(state.container_transform.invert().unwrap()),
-            )
+            .pre_concat_container(state.transform.post_concat(container_inverse))
             .pre_concat_container(to_sk_transform(&Transform::translate(pos.x, pos.y)))
             .pre_concat_container(sk_transform)
             .with_size(group.frame.size()),
@@ -194,38 +236,43 @@ fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &Group
     let mut mask = state.mask;
     let storage;
     if let Some(clip_curve) = group.clip.as_ref() {
-        if let Some(path) = shape::convert_curve(clip_curve)
+        // A clip path that cannot be converted is degenerate and clips
+        // everything. Rendering the group unclipped instead would show
+        // content that should be hidden.
+        let Some(path) = shape::convert_curve(clip_curve)
             .and_then(|path| path.transform(state.transform))
-        {
-            if let Some(mask) = mask {
-                let mut mask = mask.clone();
-                mask.intersect_path(
-                    &path,
-                    sk::FillRule::default(),
-                    false,
-                    sk::Transform::default(),
-                );
-                storage = mask;
-            } else {
-                let pxw = canvas.width();
-                let pxh = canvas.height();
-                let Some(mut mask) = sk::Mask::new(pxw, pxh) else {
-                    // Fails if clipping rect is empty. In that case we just
-                    // clip everything by returning.
-                    return;
-                };
-
-                mask.fill_path(
-                    &path,
-                    sk::FillRule::default(),
-                    false,
-                    sk::Transform::default(),
-                );
-                storage = mask;
+        else {
+            return;
+        };
+
+        if let Some(mask) = mask {
+            let mut mask = mask.clone();
+            mask.intersect_path(
+                &path,
+                sk::FillRule::default(),
+                false,
+                sk::Transform::default(),
+            );
+            storage = mask;
+        } else {
+            let pxw = canvas.width();
+            let pxh = canvas.height();
+            let Some(mut mask) = sk::Mask::new(pxw, pxh) else {
+                // Fails if clipping rect is empty. In that case we just
+                // clip everything by returning.
+                return;
             };
 
-            mask = Some(&storage);
-        }
+            mask.fill_path(
+                &path,
+                sk::FillRule::default(),
+                false,
+                sk::Transform::default(),
+            );
+            storage = mask;
+        };
+
+        mask = Some(&storage);
     }
 
     render_frame(canvas, state.with_mask(mask), &group.frame);
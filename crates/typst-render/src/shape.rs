@@ -1,5 +1,5 @@
 use tiny_skia as sk;
-use typst_library::layout::{Abs, Axes, Point, Ratio, Size};
+use typst_library::layout::{Abs, Point, Ratio, Size};
 use typst_library::visualize::{
     Curve, CurveItem, DashPattern, FillRule, FixedStroke, Geometry, LineCap, LineJoin,
     Shape,
@@ -9,6 +9,10 @@ use crate::{paint, AbsExt, State};
 
 /// Render a geometrical shape into the canvas.
 pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Option<()> {
+    if shape.is_invisible() {
+        return Some(());
+    }
+
     let ts = state.transform;
     let path = match &shape.geometry {
         Geometry::Line(target) => {
@@ -36,7 +40,7 @@ pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Opt
         Geometry::Curve(curve) => convert_curve(curve)?,
     };
 
-    if let Some(fill) = &shape.fill {
+    if let Some(fill) = shape.fill.as_ref().filter(|_| shape.geometry.has_area()) {
         let mut pixmap = None;
         let mut paint: sk::Paint = paint::to_sk_paint(
             fill,
@@ -59,14 +63,13 @@ pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Opt
         canvas.fill_path(&path, &paint, rule, ts, state.mask);
     }
 
-    if let Some(FixedStroke { paint, thickness, cap, join, dash, miter_limit }) =
-        &shape.stroke
-    {
+    if let Some(stroke) = &shape.stroke {
+        let FixedStroke { paint, thickness, cap, join, miter_limit, .. } = stroke;
         let width = thickness.to_f32();
 
         // Don't draw zero-pt stroke.
-        if width > 0.0 {
-            let dash = dash.as_ref().and_then(to_sk_dash_pattern);
+        if stroke.is_visible() {
+            let dash = stroke.effective_dash().and_then(to_sk_dash_pattern);
 
             let bbox = shape.geometry.bbox_size();
             let offset_bbox = (!matches!(shape.geometry, Geometry::Line(..)))
@@ -88,10 +91,15 @@ pub fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Opt
                             -*thickness * state.pixel_per_pt as f64,
                             -*thickness * state.pixel_per_pt as f64,
                         ),
-                        Axes::new(
-                            Ratio::new(offset_bbox.x / bbox.x),
-                            Ratio::new(offset_bbox.y / bbox.y),
-                        ),
+                        // A shape without extent along an axis (e.g. a vertical
+                        // line drawn as a curve) has nothing to stretch.
+                        offset_bbox.zip_map(bbox, |offset, side| {
+                            if side.approx_eq(Abs::zero()) {
+                                Ratio::one()
+                            } else {
+                                Ratio::new(offset / side)
+                            }
+                        }),
                     )
                 });
 
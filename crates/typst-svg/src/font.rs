@@ -0,0 +1,577 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use base64::Engine;
+use ecow::EcoString;
+use subsetter::GlyphRemapper;
+use ttf_parser::{RawFace, Tag};
+use typst_library::text::Font;
+
+const CMAP: Tag = Tag::from_bytes(b"cmap");
+const GLYF: Tag = Tag::from_bytes(b"glyf");
+const HEAD: Tag = Tag::from_bytes(b"head");
+const LOCA: Tag = Tag::from_bytes(b"loca");
+const OS2: Tag = Tag::from_bytes(b"OS/2");
+const NAME: Tag = Tag::from_bytes(b"name");
+const POST: Tag = Tag::from_bytes(b"post");
+
+/// A font whose subset is embedded into the SVG.
+#[derive(Debug, Clone)]
+pub struct EmbeddedFont {
+    /// The font to subset.
+    pub font: Font,
+    /// The characters that are drawn with the font, mapped to their glyphs
+    /// in the original font.
+    pub chars: BTreeMap<char, u16>,
+}
+
+impl EmbeddedFont {
+    /// Create an embedded font that doesn't draw any characters yet.
+    pub fn new(font: Font) -> Self {
+        Self { font, chars: BTreeMap::new() }
+    }
+}
+
+/// Subset a font to the glyphs of the given characters and encode it as a
+/// WOFF2 data URL.
+///
+/// Viewers draw the text with the subset themselves, so it gets a character
+/// map of its own. Since the subset lacks the font's layout tables, viewers
+/// don't apply its features, like ligatures, to the text. Hence, only
+/// characters that are drawn with their default glyphs may be embedded this
+/// way.
+///
+/// Returns `None` if the font could not be subset.
+#[comemo::memoize]
+#[typst_macros::time(name = "embed font")]
+pub fn convert_font_to_base64_url(
+    font: &Font,
+    chars: &BTreeMap<char, u16>,
+) -> Option<EcoString> {
+    let mut remapper = GlyphRemapper::new();
+    let glyphs: BTreeMap<u16, u16> = chars
+        .values()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|&glyph| (glyph, remapper.remap(glyph)))
+        .collect();
+
+    let data = font.data();
+    let subset = if font.variations().is_empty() {
+        subsetter::subset(data, font.index(), &remapper).ok()?
+    } else {
+        let coords: Vec<_> = font
+            .variations()
+            .iter()
+            .map(|&(tag, value)| (subsetter::Tag::new(&tag.to_bytes()), value))
+            .collect();
+        subsetter::subset_with_variations(data, font.index(), &coords, &remapper).ok()?
+    };
+
+    let raw = RawFace::parse(&subset, 0).ok()?;
+    let original = RawFace::parse(data, font.index()).ok()?;
+    let flavor = u32::from_be_bytes(subset.get(..4)?.try_into().ok()?);
+
+    let mut tables: Vec<(Tag, Vec<u8>)> = raw
+        .table_records
+        .into_iter()
+        .filter(|record| record.tag != CMAP)
+        .map(|record| {
+            let start = record.offset as usize;
+            let end = start.checked_add(record.length as usize)?;
+            Some((record.tag, raw.data.get(start..end)?.to_vec()))
+        })
+        .collect::<Option<_>>()?;
+
+    // Browsers require these tables, but the subsetter may drop them because
+    // PDF export doesn't need them. They are taken over from the original font.
+    for tag in [OS2, NAME, POST] {
+        if raw.table(tag).is_some() {
+            continue;
+        }
+
+        let Some(table) = original.table(tag) else { continue };
+        let table = if tag == POST {
+            // The glyph names refer to the glyphs of the original font, so
+            // only the header is kept, as a table of version 3.
+            let mut header = table.get(..32)?.to_vec();
+            header[..4].copy_from_slice(&0x0003_0000_u32.to_be_bytes());
+            header
+        } else {
+            table.to_vec()
+        };
+        tables.push((tag, table));
+    }
+
+    let mapping = chars.iter().map(|(&c, glyph)| (c as u32, glyphs[glyph])).collect();
+    tables.push((CMAP, write_cmap(&mapping)));
+
+    let woff2 = write_woff2(flavor, tables)?;
+    let mut url: EcoString = "data:font/woff2;base64,".into();
+    url.push_str(&base64::engine::general_purpose::STANDARD.encode(woff2));
+    Some(url)
+}
+
+/// Write a `cmap` table with a format 4 subtable for the Basic Multilingual
+/// Plane and a format 12 subtable for all characters.
+fn write_cmap(mapping: &BTreeMap<u32, u16>) -> Vec<u8> {
+    let all = ranges(mapping.iter().map(|(&c, &g)| (c, g)));
+    let mut bmp =
+        ranges(mapping.iter().filter(|(&c, _)| c < 0xFFFF).map(|(&c, &g)| (c, g)));
+
+    // Format 4 requires a final segment that maps 0xFFFF to the missing glyph.
+    bmp.push((0xFFFF, 0xFFFF, 0));
+
+    // The length of a format 4 subtable must fit into 16 bits. If it
+    // doesn't, the format 12 subtable suffices.
+    let format4_len = 16 + 8 * bmp.len();
+    let format4 = format4_len <= u16::MAX as usize;
+
+    let records: u16 = if format4 { 2 } else { 1 };
+    let mut out = Vec::new();
+    push_u16(&mut out, 0);
+    push_u16(&mut out, records);
+
+    let mut offset = 4 + 8 * records as u32;
+    if format4 {
+        // Windows, Unicode BMP.
+        push_u16(&mut out, 3);
+        push_u16(&mut out, 1);
+        push_u32(&mut out, offset);
+        offset += format4_len as u32;
+    }
+
+    // Windows, Unicode full repertoire.
+    push_u16(&mut out, 3);
+    push_u16(&mut out, 10);
+    push_u32(&mut out, offset);
+
+    if format4 {
+        let count = bmp.len() as u16;
+        let selector = 15 - count.leading_zeros() as u16;
+        let search_range = 2 << selector;
+        push_u16(&mut out, 4);
+        push_u16(&mut out, format4_len as u16);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, 2 * count);
+        push_u16(&mut out, search_range);
+        push_u16(&mut out, selector);
+        push_u16(&mut out, 2 * count - search_range);
+        for &(_, end, _) in &bmp {
+            push_u16(&mut out, end as u16);
+        }
+        push_u16(&mut out, 0);
+        for &(start, _, _) in &bmp {
+            push_u16(&mut out, start as u16);
+        }
+        for &(start, _, glyph) in &bmp {
+            // The delta is added to the character modulo 65536.
+            push_u16(&mut out, glyph.wrapping_sub(start as u16));
+        }
+        for _ in &bmp {
+            push_u16(&mut out, 0);
+        }
+    }
+
+    push_u16(&mut out, 12);
+    push_u16(&mut out, 0);
+    push_u32(&mut out, 16 + 12 * all.len() as u32);
+    push_u32(&mut out, 0);
+    push_u32(&mut out, all.len() as u32);
+    for (start, end, glyph) in all {
+        push_u32(&mut out, start);
+        push_u32(&mut out, end);
+        push_u32(&mut out, glyph as u32);
+    }
+
+    out
+}
+
+/// Group a sorted mapping into ranges of consecutive characters that map to
+/// consecutive glyphs, as `(first char, last char, first glyph)`.
+fn ranges(mapping: impl Iterator<Item = (u32, u16)>) -> Vec<(u32, u32, u16)> {
+    let mut ranges: Vec<(u32, u32, u16)> = vec![];
+    for (c, glyph) in mapping {
+        if let Some((start, end, first)) = ranges.last_mut() {
+            if c == *end + 1 && u32::from(glyph) == u32::from(*first) + (c - *start) {
+                *end = c;
+                continue;
+            }
+        }
+        ranges.push((c, c, glyph));
+    }
+    ranges
+}
+
+/// The tags that WOFF2 encodes as an index into this list instead of
+/// spelling them out.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ",
+    b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp",
+    b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF",
+    b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL",
+    b"SVG ", b"sbix", b"acnt", b"avar", b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc",
+    b"feat", b"fmtx", b"fvar", b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx",
+    b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill",
+];
+
+/// Encode the tables of a font as a WOFF2 file.
+///
+/// The tables are stored as they are: The `glyf` and `loca` tables use the
+/// null transform, which leaves all the work to the Brotli compression.
+fn write_woff2(flavor: u32, mut tables: Vec<(Tag, Vec<u8>)>) -> Option<Vec<u8>> {
+    // The tables are ordered by tag, except that `loca` must directly follow
+    // `glyf`, which sorts before `head`, `hhea`, and `hmtx`.
+    tables.sort_by_key(|(tag, _)| *tag);
+    if let Some(loca) = tables.iter().position(|(tag, _)| *tag == LOCA) {
+        if let Some(glyf) = tables.iter().position(|(tag, _)| *tag == GLYF) {
+            let table = tables.remove(loca);
+            tables.insert(glyf + 1, table);
+        }
+    }
+
+    // Bit 11 of the flags in the `head` table marks the font as losslessly
+    // transformed, which the WOFF2 specification requires.
+    if let Some((_, head)) = tables.iter_mut().find(|(tag, _)| *tag == HEAD) {
+        let flags = u16::from_be_bytes(head.get(16..18)?.try_into().ok()?) | 1 << 11;
+        head[16..18].copy_from_slice(&flags.to_be_bytes());
+    }
+
+    let mut directory = Vec::new();
+    let mut stream = Vec::new();
+    for (tag, data) in &tables {
+        // The transform version 3 is the null transform for `glyf` and
+        // `loca`, while it is 0 for all other tables.
+        let null_transform = match &tag.to_bytes() {
+            b"glyf" | b"loca" => 3 << 6,
+            _ => 0,
+        };
+
+        match KNOWN_TAGS.iter().position(|known| **known == tag.to_bytes()) {
+            Some(index) => directory.push(index as u8 | null_transform),
+            None => {
+                directory.push(63 | null_transform);
+                directory.extend(tag.to_bytes());
+            }
+        }
+
+        push_base128(&mut directory, u32::try_from(data.len()).ok()?);
+        stream.extend_from_slice(data);
+    }
+
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: 11,
+        lgwin: 22,
+        mode: brotli::enc::backward_references::BrotliEncoderMode::BROTLI_MODE_FONT,
+        size_hint: stream.len(),
+        ..Default::default()
+    };
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut stream.as_slice(), &mut compressed, &params).ok()?;
+
+    let sfnt_size = 12
+        + 16 * tables.len()
+        + tables
+            .iter()
+            .map(|(_, data)| data.len().next_multiple_of(4))
+            .sum::<usize>();
+    let header_size = 48 + directory.len();
+    let length = (header_size + compressed.len()).next_multiple_of(4);
+
+    let mut out = Vec::with_capacity(length);
+    out.extend(b"wOF2");
+    push_u32(&mut out, flavor);
+    push_u32(&mut out, u32::try_from(length).ok()?);
+    push_u16(&mut out, u16::try_from(tables.len()).ok()?);
+    push_u16(&mut out, 0);
+    push_u32(&mut out, u32::try_from(sfnt_size).ok()?);
+    push_u32(&mut out, u32::try_from(compressed.len()).ok()?);
+
+    // The version of the font, followed by the offsets and lengths of the
+    // absent metadata and private data blocks.
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 0);
+    for _ in 0..5 {
+        push_u32(&mut out, 0);
+    }
+
+    out.extend(directory);
+    out.extend(compressed);
+    out.resize(length, 0);
+    Some(out)
+}
+
+/// Write a variable-length WOFF2 `UIntBase128` number.
+fn push_base128(out: &mut Vec<u8>, value: u32) {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        bytes.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(bytes.iter().rev());
+}
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend(value.to_be_bytes());
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend(value.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use allsorts::binary::read::ReadScope;
+    use allsorts::font_data::FontData;
+    use allsorts::tables::FontTableProvider;
+    use ttf_parser::cmap::{Format, Table};
+    use ttf_parser::GlyphId;
+    use typst::layout::PagedDocument;
+    use typst::testing::TestWorld;
+    use typst_library::diag::Warned;
+    use typst_library::foundations::{Bytes, Content, Smart};
+    use typst_library::layout::{Abs, Em, Frame, FrameItem, Page, Point, Sides, Size};
+    use typst_library::text::{EmbeddingPermissions, FontVariant, Glyph, Lang, TextItem};
+    use typst_library::visualize::Color;
+    use typst_syntax::Span;
+
+    use super::*;
+    use crate::{svg, svg_with_warnings, SvgOptions};
+
+    #[test]
+    fn test_write_cmap() {
+        let mapping = BTreeMap::from([(0x41, 3), (0x42, 4), (0x44, 9), (0x1F600, 10)]);
+        let data = write_cmap(&mapping);
+
+        // The format 4 subtable has a segment for each range and the final
+        // segment for 0xFFFF.
+        assert_eq!(&data[20..34], [0, 4, 0, 40, 0, 0, 0, 6, 0, 4, 0, 1, 0, 2]);
+
+        let subtables: Vec<_> =
+            Table::parse(&data).unwrap().subtables.into_iter().collect();
+        assert_eq!(subtables.len(), 2);
+        assert!(matches!(subtables[0].format, Format::SegmentMappingToDeltaValues(_)));
+        assert!(matches!(subtables[1].format, Format::SegmentedCoverage(_)));
+        for subtable in &subtables {
+            assert_eq!(subtable.glyph_index(0x41), Some(GlyphId(3)));
+            assert_eq!(subtable.glyph_index(0x42), Some(GlyphId(4)));
+            assert_eq!(subtable.glyph_index(0x43), None);
+            assert_eq!(subtable.glyph_index(0x44), Some(GlyphId(9)));
+        }
+
+        // Characters beyond the BMP are only in the format 12 subtable.
+        assert_eq!(subtables[0].glyph_index(0x1F600), None);
+        assert_eq!(subtables[1].glyph_index(0x1F600), Some(GlyphId(10)));
+    }
+
+    #[test]
+    fn test_write_woff2() {
+        let tables = vec![
+            (LOCA, vec![4; 4]),
+            (HEAD, vec![2; 200]),
+            (GLYF, vec![1; 10]),
+            (Tag::from_bytes(b"TEST"), vec![3; 3]),
+        ];
+        let woff2 = write_woff2(0x0001_0000, tables).unwrap();
+        let u16_at = |at: usize| u16::from_be_bytes([woff2[at], woff2[at + 1]]);
+        let u32_at =
+            |at: usize| u32::from_be_bytes(woff2[at..at + 4].try_into().unwrap());
+
+        assert_eq!(&woff2[..4], b"wOF2");
+        assert_eq!(u32_at(4), 0x0001_0000);
+        assert_eq!(u32_at(8) as usize, woff2.len());
+        assert_eq!(woff2.len() % 4, 0);
+        assert_eq!(u16_at(12), 4);
+        assert_eq!(u32_at(16), 12 + 16 * 4 + 4 + 12 + 4 + 200);
+
+        // The tables are ordered by tag, but `loca` directly follows `glyf`.
+        // Known tags are encoded as their index, with the null transform for
+        // `glyf` and `loca`, and other tags are spelled out. The lengths are
+        // base 128.
+        let directory =
+            [0x3F, b'T', b'E', b'S', b'T', 3, 0xCA, 10, 0xCB, 4, 0x01, 0x81, 0x48];
+        assert_eq!(woff2[48..48 + directory.len()], directory);
+
+        // The `head` table's flags mark the font as transformed.
+        let mut head = vec![2; 200];
+        head[16] |= 0x08;
+        let data = [vec![3; 3], vec![1; 10], vec![4; 4], head].concat();
+
+        let start = 48 + directory.len();
+        let compressed = &woff2[start..start + u32_at(20) as usize];
+        let mut stream = vec![];
+        brotli::BrotliDecompress(&mut &compressed[..], &mut stream).unwrap();
+        assert_eq!(stream, data);
+    }
+
+    #[test]
+    fn test_embed_fonts() {
+        let text = "The office in Vienna costs 100 €. ".repeat(60);
+        let world = TestWorld::new(&text);
+        let doc = typst::compile::<PagedDocument>(&world).output.unwrap();
+        let page = &doc.pages[0];
+        let Warned { output: embedded, warnings } = svg_with_warnings(page, &embed());
+        assert!(warnings.is_empty());
+
+        // Real text with an embedded subset beats the glyph outlines.
+        assert!(embedded.len() < svg(page).len());
+
+        // The ligature is drawn as a glyph, but its text remains.
+        assert!(embedded.contains(r#"fill="transparent">ffi</tspan>"#));
+
+        // The subset is a valid WOFF2 font with the glyphs of the characters
+        // drawn with it.
+        let prefix = "data:font/woff2;base64,";
+        let start = embedded.find(prefix).unwrap() + prefix.len();
+        let end = start + embedded[start..].find('"').unwrap();
+        assert_eq!(embedded.matches(prefix).count(), 1);
+        let woff2 = base64::engine::general_purpose::STANDARD
+            .decode(&embedded[start..end])
+            .unwrap();
+        let sfnt = decode_woff2(&woff2);
+        let face = ttf_parser::Face::parse(&sfnt, 0).unwrap();
+        for c in text.replace("ffi", "").chars().filter(|c| !c.is_whitespace()) {
+            let id = face.glyph_index(c).unwrap();
+            assert!(face.glyph_bounding_box(id).is_some(), "{c:?} is not drawn");
+        }
+        assert_eq!(face.glyph_index('f'), None);
+        assert_eq!(face.glyph_index('x'), None);
+
+        // The font is marked as transformed.
+        let head = face.raw_face().table(HEAD).unwrap();
+        assert_eq!(head[16] & 0x08, 0x08);
+    }
+
+    #[test]
+    fn test_embed_fonts_respects_license() {
+        let data = with_fs_type(font().data(), 0x0002);
+        let font = Font::new(Bytes::from(data), 0).unwrap();
+        assert_eq!(font.embedding_permissions(), EmbeddingPermissions::NoEmbedding);
+
+        let Warned { output, warnings } =
+            svg_with_warnings(&page(font, "Hello"), &embed());
+        assert!(!output.contains("@font-face"));
+        assert!(output.contains("<use"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "font Libertinus Serif may not be embedded into the SVG"
+        );
+    }
+
+    /// Options that enable font embedding.
+    fn embed() -> SvgOptions {
+        SvgOptions { embed_fonts: true, ..SvgOptions::default() }
+    }
+
+    /// The regular style of Libertinus Serif.
+    fn font() -> Font {
+        typst_assets::fonts()
+            .flat_map(|data| Font::iter(Bytes::from_static(data)))
+            .find(|font| {
+                font.info().family == "Libertinus Serif"
+                    && font.info().variant == FontVariant::default()
+            })
+            .unwrap()
+    }
+
+    /// Create a page with a single run of text that is drawn with the default
+    /// glyphs of its characters.
+    ///
+    /// The test world can't provide a font with modified data, so this builds
+    /// the page directly.
+    fn page(font: Font, text: &str) -> Page {
+        let glyphs = text
+            .char_indices()
+            .map(|(i, c)| Glyph {
+                id: font.ttf().glyph_index(c).unwrap().0,
+                x_advance: Em::new(0.5),
+                x_offset: Em::zero(),
+                range: i as u16..(i + c.len_utf8()) as u16,
+                span: (Span::detached(), 0),
+            })
+            .collect();
+
+        let mut frame = Frame::soft(Size::new(Abs::pt(100.0), Abs::pt(20.0)));
+        frame.push(
+            Point::with_y(Abs::pt(15.0)),
+            FrameItem::Text(TextItem {
+                font,
+                size: Abs::pt(11.0),
+                fill: Color::BLACK.into(),
+                stroke: None,
+                lang: Lang::ENGLISH,
+                region: None,
+                text: text.into(),
+                glyphs,
+            }),
+        );
+
+        Page {
+            frame,
+            fill: Smart::Auto,
+            numbering: None,
+            supplement: Content::empty(),
+            number: 1,
+            bleed: Sides::default(),
+            rotated: false,
+        }
+    }
+
+    /// Replaces the `fsType` field in the `OS/2` table of a font.
+    fn with_fs_type(data: &[u8], fs_type: u16) -> Vec<u8> {
+        let mut data = data.to_vec();
+        let raw = RawFace::parse(&data, 0).unwrap();
+        let record = raw.table_records.into_iter().find(|r| r.tag == OS2).unwrap();
+        let offset = record.offset as usize + 8;
+        data[offset..offset + 2].copy_from_slice(&fs_type.to_be_bytes());
+        data
+    }
+
+    /// Decodes a WOFF2 file with an independent decoder and assembles its
+    /// tables into an OpenType font.
+    fn decode_woff2(data: &[u8]) -> Vec<u8> {
+        let font = ReadScope::new(data).read::<FontData>().unwrap();
+        let provider = font.table_provider(0).unwrap();
+        let tables: Vec<_> = [
+            CMAP,
+            GLYF,
+            HEAD,
+            LOCA,
+            NAME,
+            OS2,
+            POST,
+            Tag::from_bytes(b"hhea"),
+            Tag::from_bytes(b"hmtx"),
+            Tag::from_bytes(b"maxp"),
+        ]
+        .into_iter()
+        .filter_map(|tag| {
+            let data =
+                provider.table_data(u32::from_be_bytes(tag.to_bytes())).unwrap()?;
+            Some((tag, data.into_owned()))
+        })
+        .collect();
+
+        // The search parameters of the table directory are not needed for
+        // parsing.
+        let mut out = vec![];
+        push_u32(&mut out, 0x0001_0000);
+        push_u16(&mut out, tables.len() as u16);
+        out.extend([0; 6]);
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, data) in &tables {
+            out.extend(tag.to_bytes());
+            push_u32(&mut out, 0);
+            push_u32(&mut out, offset as u32);
+            push_u32(&mut out, data.len() as u32);
+            offset += data.len().next_multiple_of(4);
+        }
+        for (_, data) in tables {
+            let len = out.len() + data.len().next_multiple_of(4);
+            out.extend(data);
+            out.resize(len, 0);
+        }
+        out
+    }
+}
@@ -1,34 +1,81 @@
 //! Rendering of Typst documents into SVG images.
 
+mod font;
 mod image;
 mod paint;
 mod shape;
+mod slides;
 mod text;
 
-use std::collections::HashMap;
+pub use self::slides::{svg_slides, svg_slides_with_warnings, SlidePreset};
+
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display, Formatter, Write};
+use std::num::NonZeroUsize;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString, EcoVec};
 use ttf_parser::OutlineBuilder;
+use typst_library::diag::{SourceDiagnostic, Warned};
+use typst_library::foundations::Smart;
 use typst_library::layout::{
     Abs, Frame, FrameItem, FrameKind, GroupItem, Page, PagedDocument, Point, Ratio, Size,
     Transform,
 };
+use typst_library::model::{link_regions, LinkRegion, LinkRegionDest};
+use typst_library::text::Font;
 use typst_library::visualize::{Geometry, Gradient, Tiling};
 use typst_utils::hash128;
 use xmlwriter::XmlWriter;
 
+use crate::font::EmbeddedFont;
 use crate::paint::{GradientRef, SVGSubGradient, TilingRef};
 use crate::text::RenderedGlyph;
 
 /// Export a frame into a SVG file.
 #[typst_macros::time(name = "svg")]
 pub fn svg(page: &Page) -> String {
+    svg_with_options(page, &SvgOptions::default())
+}
+
+/// Export a frame into a SVG file with the given options.
+pub fn svg_with_options(page: &Page, options: &SvgOptions) -> String {
+    svg_with_warnings(page, options).output
+}
+
+/// Export a frame into a SVG file with the given options and also return the
+/// warnings that arose during export.
+///
+/// Warnings arise when fonts are embedded, but the license of a font does not
+/// permit it.
+pub fn svg_with_warnings(page: &Page, options: &SvgOptions) -> Warned<String> {
     let mut renderer = SVGRenderer::new();
+    renderer.options = *options;
     renderer.write_header(page.frame.size());
 
     let state = State::new(page.frame.size(), Transform::identity());
     renderer.render_page(state, Transform::identity(), page);
+    renderer.finalize_with_warnings()
+}
+
+/// Export a page into a SVG file, including its bleed.
+///
+/// In contrast to [`svg`], the image also shows the area around the page into
+/// which the page's fill, background, and foreground extend. For pages without
+/// bleed, both functions produce the same image.
+#[typst_macros::time(name = "svg bleed")]
+pub fn svg_bleed(page: &Page) -> String {
+    let size = page.full_size();
+    let mut renderer = SVGRenderer::new();
+    renderer.write_header(size);
+
+    if let Some(fill) = page.fill_or_white() {
+        let shape = Geometry::Rect(size).filled(fill);
+        renderer.render_shape(State::new(size, Transform::identity()), &shape);
+    }
+
+    let ts = Transform::translate(page.bleed.left, page.bleed.top);
+    let state = State::new(page.frame.size(), Transform::identity());
+    renderer.render_frame(state, ts, &page.frame);
     renderer.finalize()
 }
 
@@ -43,9 +90,21 @@ pub fn svg_frame(frame: &Frame) -> String {
     renderer.finalize()
 }
 
+/// Determine the link areas of a page in the coordinates of the SVG produced by
+/// [`svg`].
+///
+/// Links to locations in the document stay unresolved; use [`link_regions`]
+/// with the document's introspector to resolve them.
+pub fn link_map(page: &Page) -> Vec<LinkRegion> {
+    link_regions(&page.frame, Transform::identity(), None)
+}
+
 /// Export a document with potentially multiple pages into a single SVG file.
 ///
-/// The padding will be added around and between the individual frames.
+/// The padding will be added around and between the individual frames. Each
+/// page is wrapped in a group with the id `page-{n}`, counting from one, and
+/// links are clickable. Links within the document point to the group of the
+/// page they lead to.
 pub fn svg_merged(document: &PagedDocument, padding: Abs) -> String {
     let width = 2.0 * padding
         + document
@@ -61,24 +120,87 @@ pub fn svg_merged(document: &PagedDocument, padding: Abs) -> String {
             .map(|page| page.frame.height() + padding)
             .sum::<Abs>();
 
+    // The offsets of the pages' top-left corners in the SVG's coordinates.
+    let mut y = padding;
+    let offsets: Vec<Point> = document
+        .pages
+        .iter()
+        .map(|page| {
+            let offset = Point::new(padding, y);
+            y += page.frame.height() + padding;
+            offset
+        })
+        .collect();
+
     let mut renderer = SVGRenderer::new();
     renderer.write_header(Size::new(width, height));
 
-    let [x, mut y] = [padding; 2];
-    for page in &document.pages {
-        let ts = Transform::translate(x, y);
+    for (i, (page, offset)) in document.pages.iter().zip(&offsets).enumerate() {
+        let ts = Transform::translate(offset.x, offset.y);
         let state = State::new(page.frame.size(), Transform::identity());
+        renderer.xml.start_element("g");
+        renderer.xml.write_attribute_fmt("id", format_args!("page-{}", i + 1));
+        renderer.xml.write_attribute("class", "typst-page");
         renderer.render_page(state, ts, page);
-        y += page.frame.height() + padding;
+        renderer.render_links(
+            &link_regions(&page.frame, ts, Some(&document.introspector)),
+            document,
+            &offsets,
+        );
+        renderer.xml.end_element();
     }
 
     renderer.finalize()
 }
 
+/// Settings for SVG export.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SvgOptions {
+    /// Whether to add an invisible text layer on top of the glyphs, such that
+    /// the text can be selected, copied, and searched in viewers.
+    pub selectable_text: bool,
+    /// How the characters of the selectable text are positioned.
+    pub glyph_positioning: GlyphPositioning,
+    /// The minimum number of stops with which a gradient segment is
+    /// approximated if its colors do not change linearly, e.g. because it is
+    /// eased. More stops give smoother gradients, but larger files. Defaults
+    /// to [`Gradient::SEGMENT_SAMPLES`].
+    pub gradient_samples: Smart<usize>,
+    /// Whether to draw text as real text with embedded WOFF2 subsets of the
+    /// fonts instead of as glyph outlines. For typical documents, this gives
+    /// smaller files and the text is selectable.
+    ///
+    /// Each subset contains exactly the glyphs of the characters that are
+    /// drawn with it and its family is named after its id, so that it does
+    /// not clash with fonts installed on the viewer's system. Text whose font
+    /// may not be embedded according to its license, which is reported by
+    /// [`svg_with_warnings`], or that is filled with a gradient or tiling or
+    /// stroked is still drawn as glyph outlines. The same holds for parts of
+    /// the text whose glyphs the viewer couldn't reproduce from the subset,
+    /// like ligatures.
+    pub embed_fonts: bool,
+}
+
+/// How the characters of the selectable text layer are positioned.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum GlyphPositioning {
+    /// Each text run is positioned as a whole and the viewer lays out its
+    /// characters. This keeps the output small, but if the viewer falls back
+    /// to a different font, the text drifts away from the visible glyphs.
+    #[default]
+    Run,
+    /// Each glyph cluster is positioned individually at its shaped position.
+    /// This keeps the text aligned with the glyphs regardless of the font the
+    /// viewer uses, at the cost of larger output.
+    Exact,
+}
+
 /// Renders one or multiple frames to an SVG file.
 struct SVGRenderer {
     /// The internal XML writer.
     xml: XmlWriter,
+    /// The export settings.
+    options: SvgOptions,
     /// Prepared glyphs.
     glyphs: Deduplicator<RenderedGlyph>,
     /// Clip paths are used to clip a group. A clip path is a path that defines
@@ -113,6 +235,15 @@ struct SVGRenderer {
     tilings: Deduplicator<Tiling>,
     /// These are the gradients that compose a conic gradient.
     conic_subgradients: Deduplicator<SVGSubGradient>,
+    /// The fonts whose subsets are embedded.
+    fonts: Deduplicator<EmbeddedFont>,
+    /// The fonts that may not be embedded and were already reported.
+    refused_fonts: HashSet<Font>,
+    /// Prefixes the ids of all definitions, such that they are unique across
+    /// multiple SVG files.
+    scope: Option<NonZeroUsize>,
+    /// The warnings that arose during export.
+    warnings: EcoVec<SourceDiagnostic>,
 }
 
 /// Contextual information for rendering.
@@ -156,21 +287,46 @@ impl State {
 impl SVGRenderer {
     /// Create a new SVG renderer with empty glyph and clip path.
     fn new() -> Self {
+        Self::scoped(None)
+    }
+
+    /// Create a new SVG renderer whose definition ids are prefixed with
+    /// `s{scope}-`.
+    fn scoped(scope: Option<NonZeroUsize>) -> Self {
         SVGRenderer {
             xml: XmlWriter::new(xmlwriter::Options::default()),
-            glyphs: Deduplicator::new('g'),
-            clip_paths: Deduplicator::new('c'),
-            gradient_refs: Deduplicator::new('g'),
-            gradients: Deduplicator::new('f'),
-            conic_subgradients: Deduplicator::new('s'),
-            tiling_refs: Deduplicator::new('p'),
-            tilings: Deduplicator::new('t'),
+            options: SvgOptions::default(),
+            glyphs: Deduplicator::new('g', scope),
+            clip_paths: Deduplicator::new('c', scope),
+            gradient_refs: Deduplicator::new('g', scope),
+            gradients: Deduplicator::new('f', scope),
+            conic_subgradients: Deduplicator::new('s', scope),
+            tiling_refs: Deduplicator::new('p', scope),
+            tilings: Deduplicator::new('t', scope),
+            fonts: Deduplicator::new('w', scope),
+            refused_fonts: HashSet::new(),
+            scope,
+            warnings: EcoVec::new(),
+        }
+    }
+
+    /// Write the id of a group of definitions.
+    fn write_defs_id(&mut self, name: &str) {
+        match self.scope {
+            Some(scope) => {
+                self.xml.write_attribute_fmt("id", format_args!("s{scope}-{name}"))
+            }
+            None => self.xml.write_attribute("id", name),
         }
     }
 
     /// Write the SVG header, including the `viewBox` and `width` and `height`
     /// attributes.
     fn write_header(&mut self, size: Size) {
+        debug_assert!(
+            size.all(|side| *side >= Abs::zero()),
+            "document size must not be negative",
+        );
         self.xml.start_element("svg");
         self.xml.write_attribute("class", "typst-doc");
         self.xml.write_attribute_fmt(
@@ -197,6 +353,64 @@ impl SVGRenderer {
         self.render_frame(state, ts, &page.frame);
     }
 
+    /// Render clickable areas for the given link regions.
+    ///
+    /// Links to positions and elements in the document point to the group of
+    /// the page they lead to, as created by [`svg_merged`]. Viewers that
+    /// support SVG view fragments instead scroll to the exact point, which is
+    /// shifted by the page's `offset` in the SVG, just like the page content.
+    fn render_links(
+        &mut self,
+        regions: &[LinkRegion],
+        document: &PagedDocument,
+        offsets: &[Point],
+    ) {
+        for region in regions {
+            let (page, x, y) = match &region.dest {
+                LinkRegionDest::Url { url } => {
+                    self.xml.start_element("a");
+                    self.xml.write_attribute("xlink:href", url);
+                    self.render_link_area(region);
+                    continue;
+                }
+                LinkRegionDest::Position { page, x, y }
+                | LinkRegionDest::Anchor { page, x, y, .. } => (*page, *x, *y),
+                LinkRegionDest::Unresolved => continue,
+            };
+
+            let Some((target, offset)) =
+                document.pages.get(page.get() - 1).zip(offsets.get(page.get() - 1))
+            else {
+                continue;
+            };
+
+            // The view is as large as the target page and starts at the point.
+            let size = target.frame.size();
+            let (vx, vy) = (offset.x.to_pt() + x, offset.y.to_pt() + y);
+            let (vw, vh) = (size.x.to_pt(), size.y.to_pt());
+
+            self.xml.start_element("a");
+            self.xml
+                .write_attribute_fmt("xlink:href", format_args!("#page-{page}"));
+            self.xml.write_attribute_fmt(
+                "href",
+                format_args!("#svgView(viewBox({vx},{vy},{vw},{vh}))"),
+            );
+            self.render_link_area(region);
+        }
+    }
+
+    /// Render the clickable area of a link region and close the link element
+    /// it belongs to.
+    fn render_link_area(&mut self, region: &LinkRegion) {
+        let points = region.quad.map(|[x, y]| eco_format!("{x},{y}"));
+        self.xml.start_element("polygon");
+        self.xml.write_attribute("points", &points.join(" "));
+        self.xml.write_attribute("fill", "transparent");
+        self.xml.end_element();
+        self.xml.end_element();
+    }
+
     /// Render a frame with the given transform.
     fn render_frame(&mut self, state: State, ts: Transform, frame: &Frame) {
         self.xml.start_element("g");
@@ -241,6 +455,10 @@ impl SVGRenderer {
     /// Render a group. If the group has `clips` set to true, a clip path will
     /// be created.
     fn render_group(&mut self, state: State, group: &GroupItem) {
+        if group.is_invisible() {
+            return;
+        }
+
         let state = match group.frame.kind() {
             FrameKind::Soft => state.pre_concat(group.transform),
             FrameKind::Hard => state
@@ -267,7 +485,13 @@ impl SVGRenderer {
     }
 
     /// Finalize the SVG file. This must be called after all rendering is done.
-    fn finalize(mut self) -> String {
+    fn finalize(self) -> String {
+        self.finalize_with_warnings().output
+    }
+
+    /// Finalize the SVG file and return it together with the warnings that
+    /// arose during export.
+    fn finalize_with_warnings(mut self) -> Warned<String> {
         self.write_glyph_defs();
         self.write_clip_path_defs();
         self.write_gradients();
@@ -275,7 +499,12 @@ impl SVGRenderer {
         self.write_subgradients();
         self.write_tilings();
         self.write_tiling_refs();
-        self.xml.end_document()
+        // Tilings may draw text, so the fonts come last.
+        self.write_font_defs();
+        Warned {
+            output: self.xml.end_document(),
+            warnings: self.warnings,
+        }
     }
 
     /// Build the clip path definitions.
@@ -285,7 +514,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "clip-path");
+        self.write_defs_id("clip-path");
 
         for (id, path) in self.clip_paths.iter() {
             self.xml.start_element("clipPath");
@@ -307,13 +536,19 @@ impl SVGRenderer {
 #[derive(Debug, Clone)]
 struct Deduplicator<T> {
     kind: char,
+    scope: Option<NonZeroUsize>,
     vec: Vec<(u128, T)>,
     present: HashMap<u128, Id>,
 }
 
 impl<T> Deduplicator<T> {
-    fn new(kind: char) -> Self {
-        Self { kind, vec: Vec::new(), present: HashMap::new() }
+    fn new(kind: char, scope: Option<NonZeroUsize>) -> Self {
+        Self {
+            kind,
+            scope,
+            vec: Vec::new(),
+            present: HashMap::new(),
+        }
     }
 
     /// Inserts a value into the vector. If the hash is already present, returns
@@ -327,16 +562,21 @@ impl<T> Deduplicator<T> {
         *self.present.entry(hash).or_insert_with(|| {
             let index = self.vec.len();
             self.vec.push((hash, f()));
-            Id(self.kind, hash, index)
+            Id(self.kind, self.scope, hash, index)
         })
     }
 
+    /// Returns the value with the given id.
+    fn get_mut(&mut self, id: Id) -> &mut T {
+        &mut self.vec[id.3].1
+    }
+
     /// Iterate over the elements alongside their ids.
     fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
         self.vec
             .iter()
             .enumerate()
-            .map(|(i, (id, v))| (Id(self.kind, *id, i), v))
+            .map(|(i, (id, v))| (Id(self.kind, self.scope, *id, i), v))
     }
 
     /// Returns true if the deduplicator is empty.
@@ -347,11 +587,14 @@ impl<T> Deduplicator<T> {
 
 /// Identifies a `<def>`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-struct Id(char, u128, usize);
+struct Id(char, Option<NonZeroUsize>, u128, usize);
 
 impl Display for Id {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{:0X}", self.0, self.1)
+        if let Some(scope) = self.1 {
+            write!(f, "s{scope}-")?;
+        }
+        write!(f, "{}{:0X}", self.0, self.2)
     }
 }
 
@@ -120,7 +120,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "gradients");
+        self.write_defs_id("gradients");
 
         for (id, (gradient, ratio)) in self.gradients.iter() {
             match &gradient {
@@ -242,7 +242,7 @@ impl SVGRenderer {
                 }
             }
 
-            for window in gradient.stops_ref().windows(2) {
+            for (i, window) in gradient.stops_ref().windows(2).enumerate() {
                 let (start_c, start_t) = window[0];
                 let (end_c, end_t) = window[1];
 
@@ -256,10 +256,19 @@ impl SVGRenderer {
                 // They tend to just ignore the color space of the gradient.
                 // The goal is to have smooth gradients but not to balloon the file size
                 // too much if there are already a lot of stops as in most presets.
-                let len = if gradient.anti_alias() {
+                // Segments that are eased or in a hue-based space need more
+                // stops to show their progression.
+                let len = if !gradient.anti_alias() {
+                    2
+                } else if gradient.is_linear_segment(i) {
                     (256 / gradient.stops_ref().len() as u32).max(2)
                 } else {
-                    2
+                    let samples = self
+                        .options
+                        .gradient_samples
+                        .unwrap_or(Gradient::SEGMENT_SAMPLES)
+                        .max(1) as u32;
+                    (256 / gradient.stops_ref().len() as u32).max(samples + 1)
                 };
 
                 for i in 1..(len - 1) {
@@ -292,7 +301,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "subgradients");
+        self.write_defs_id("subgradients");
         for (id, gradient) in self.conic_subgradients.iter() {
             let x1 = 2.0 - gradient.t0.cos() as f32 + gradient.center.x.get() as f32;
             let y1 = gradient.t0.sin() as f32 + gradient.center.y.get() as f32;
@@ -328,7 +337,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "gradient-refs");
+        self.write_defs_id("gradient-refs");
         for (id, gradient_ref) in self.gradient_refs.iter() {
             match gradient_ref.kind {
                 GradientKind::Linear => {
@@ -376,7 +385,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "tilings");
+        self.write_defs_id("tilings");
 
         for (id, tiling) in
             self.tilings.iter().map(|(i, p)| (i, p.clone())).collect::<Vec<_>>()
@@ -410,7 +419,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "tilings-refs");
+        self.write_defs_id("tilings-refs");
         for (id, tiling_ref) in self.tiling_refs.iter() {
             self.xml.start_element("pattern");
             self.xml
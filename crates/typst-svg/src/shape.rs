@@ -11,6 +11,10 @@ use crate::{SVGRenderer, State, SvgPathBuilder};
 impl SVGRenderer {
     /// Render a shape element.
     pub(super) fn render_shape(&mut self, state: State, shape: &Shape) {
+        if shape.is_invisible() {
+            return;
+        }
+
         self.xml.start_element("path");
         self.xml.write_attribute("class", "typst-shape");
 
@@ -137,7 +141,7 @@ impl SVGRenderer {
         );
         self.xml
             .write_attribute("stroke-miterlimit", &stroke.miter_limit.get());
-        if let Some(dash) = &stroke.dash {
+        if let Some(dash) = stroke.effective_dash() {
             self.xml.write_attribute("stroke-dashoffset", &dash.phase.to_pt());
             self.xml.write_attribute(
                 "stroke-dasharray",
@@ -0,0 +1,236 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoVec;
+use typst_library::diag::{warning, Warned};
+use typst_library::layout::{Abs, Page, PagedDocument, Ratio, Size, Transform};
+use typst_syntax::Span;
+
+use crate::{SVGRenderer, State, SvgMatrix};
+
+/// The size of the slides of a presentation, as offered by common
+/// presentation software.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SlidePreset {
+    /// A widescreen slide of 13.333 × 7.5 inches, with an aspect ratio of
+    /// 16:9.
+    #[default]
+    Widescreen16x9,
+    /// A standard slide of 10 × 7.5 inches, with an aspect ratio of 4:3.
+    Standard4x3,
+}
+
+impl SlidePreset {
+    /// The size of the slide.
+    pub fn size(self) -> Size {
+        match self {
+            Self::Widescreen16x9 => Size::new(Abs::pt(960.0), Abs::pt(540.0)),
+            Self::Standard4x3 => Size::new(Abs::pt(720.0), Abs::pt(540.0)),
+        }
+    }
+
+    /// The paper size of Typst with the same aspect ratio.
+    fn paper(self) -> &'static str {
+        match self {
+            Self::Widescreen16x9 => "presentation-16-9",
+            Self::Standard4x3 => "presentation-4-3",
+        }
+    }
+}
+
+/// Export each page of a document into a separate SVG file that has exactly
+/// the size of a presentation slide.
+///
+/// The files are meant to be imported into presentation software:
+/// - Pages with the aspect ratio of the slide are scaled to fill it. Other
+///   pages are scaled to fit and centered on the slide, leaving empty bars at
+///   the sides. [`svg_slides_with_warnings`] also reports these pages.
+/// - Text is always drawn as glyph outlines, so that the slides look the same
+///   without the document's fonts installed.
+/// - Links are not exported.
+/// - Each file contains all of its definitions and their ids are prefixed
+///   with the slide number, like `s2-`. Thus, the files can be used together
+///   without their definitions interfering.
+pub fn svg_slides(document: &PagedDocument, preset: SlidePreset) -> Vec<String> {
+    svg_slides_with_warnings(document, preset).output
+}
+
+/// Export each page of a document into a separate SVG file that has exactly
+/// the size of a presentation slide and also return the warnings that arose
+/// during export.
+///
+/// See [`svg_slides`] for more details.
+#[typst_macros::time(name = "svg slides")]
+pub fn svg_slides_with_warnings(
+    document: &PagedDocument,
+    preset: SlidePreset,
+) -> Warned<Vec<String>> {
+    let mut warnings = EcoVec::new();
+    let slide = preset.size();
+    let output = document
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| {
+            let (ts, fitted) = letterbox(page.frame.size(), slide);
+            if !fitted {
+                warnings.push(warning!(
+                    Span::detached(),
+                    "page {} does not have the aspect ratio of the slide",
+                    i + 1;
+                    hint: "the page was scaled to fit and centered on the slide";
+                    hint: "use `set page(paper: \"{}\")` to fill the slide",
+                    preset.paper(),
+                ));
+            }
+            svg_slide(page, i + 1, slide, ts)
+        })
+        .collect();
+
+    Warned { output, warnings }
+}
+
+/// Render a page onto a slide with the transform of its contents.
+fn svg_slide(page: &Page, number: usize, slide: Size, ts: Transform) -> String {
+    let mut renderer = SVGRenderer::scoped(NonZeroUsize::new(number));
+    renderer.write_header(slide);
+
+    renderer.xml.start_element("g");
+    renderer.xml.write_attribute("transform", &SvgMatrix(ts));
+    let state = State::new(page.frame.size(), Transform::identity());
+    renderer.render_page(state, Transform::identity(), page);
+    renderer.xml.end_element();
+
+    renderer.finalize()
+}
+
+/// Determine the transform that scales content of the given size to fit into
+/// the slide and centers it. Also returns whether the content fills the slide
+/// entirely.
+fn letterbox(size: Size, slide: Size) -> (Transform, bool) {
+    if size.x <= Abs::zero() || size.y <= Abs::zero() {
+        return (Transform::identity(), false);
+    }
+
+    let scale = (slide.x / size.x).min(slide.y / size.y);
+    let dx = (slide.x - size.x * scale) / 2.0;
+    let dy = (slide.y - size.y * scale) / 2.0;
+    let fitted = dx.approx_eq(Abs::zero()) && dy.approx_eq(Abs::zero());
+    let ts = Transform::translate(dx, dy)
+        .pre_concat(Transform::scale(Ratio::new(scale), Ratio::new(scale)));
+    (ts, fitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use typst::testing::TestWorld;
+
+    use super::*;
+
+    #[test]
+    fn test_letterbox() {
+        let slide = SlidePreset::Widescreen16x9.size();
+        let (ts, fitted) = letterbox(Size::new(Abs::pt(480.0), Abs::pt(270.0)), slide);
+        assert!(fitted);
+        assert_eq!((ts.sx.get(), ts.sy.get()), (2.0, 2.0));
+        assert!(ts.tx.approx_eq(Abs::zero()) && ts.ty.approx_eq(Abs::zero()));
+
+        // Taller pages are scaled to the height of the slide and centered
+        // horizontally.
+        let (ts, fitted) = letterbox(Size::new(Abs::pt(270.0), Abs::pt(270.0)), slide);
+        assert!(!fitted);
+        assert_eq!((ts.sx.get(), ts.sy.get()), (2.0, 2.0));
+        assert!(ts.tx.approx_eq(Abs::pt(210.0)) && ts.ty.approx_eq(Abs::zero()));
+
+        let (ts, fitted) = letterbox(Size::zero(), slide);
+        assert!(!fitted);
+        assert!(ts.is_identity());
+    }
+
+    #[test]
+    fn test_svg_slides() {
+        let document = compile(
+            "#set page(paper: \"presentation-16-9\")\n\
+             = Slide\n\
+             #link(\"https://typst.app\")[Typst]\n\
+             #pagebreak()\n\
+             = Another slide",
+        );
+        let Warned { output, warnings } =
+            svg_slides_with_warnings(&document, SlidePreset::Widescreen16x9);
+        assert_eq!(output.len(), 2);
+        assert!(warnings.is_empty());
+
+        for (i, svg) in output.iter().enumerate() {
+            assert!(svg.contains(r#"viewBox="0 0 960 540" width="960pt" height="540pt""#));
+            assert!(!svg.contains("<a "));
+            assert!(!svg.contains("<text"));
+
+            // The page fills the slide.
+            let [sx, ky, kx, sy, tx, ty] = transform(svg);
+            let scale = 960.0 / document.pages[i].frame.width().to_pt();
+            assert!((sx - scale).abs() < 1e-6 && (sy - scale).abs() < 1e-6);
+            assert_eq!((ky, kx), (0.0, 0.0));
+            assert!(tx.abs() < 1e-6 && ty.abs() < 1e-6);
+
+            // All definitions and references to them are scoped to the slide.
+            let names = names(svg);
+            assert!(!names.is_empty());
+            let prefix = format!("s{}-", i + 1);
+            assert!(names.iter().all(|name| name.starts_with(&prefix)), "{names:?}");
+        }
+    }
+
+    #[test]
+    fn test_svg_slides_warns_about_aspect_ratio() {
+        let document = compile("#set page(paper: \"a4\")\n= Slide");
+        let Warned { output, warnings } =
+            svg_slides_with_warnings(&document, SlidePreset::Widescreen16x9);
+        assert!(output[0].contains(r#"width="960pt" height="540pt""#));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].message,
+            "page 1 does not have the aspect ratio of the slide"
+        );
+
+        // The portrait page is scaled to the height of the slide and centered
+        // horizontally, leaving bars at the sides.
+        let size = document.pages[0].frame.size();
+        let scale = 540.0 / size.y.to_pt();
+        let [sx, ky, kx, sy, tx, ty] = transform(&output[0]);
+        assert!((sx - scale).abs() < 1e-6 && (sy - scale).abs() < 1e-6);
+        assert_eq!((ky, kx), (0.0, 0.0));
+        assert!((tx - (960.0 - size.x.to_pt() * scale) / 2.0).abs() < 1e-6);
+        assert!(tx > 100.0);
+        assert!(ty.abs() < 1e-6);
+    }
+
+    /// Compile a document from the given source.
+    fn compile(text: &str) -> PagedDocument {
+        typst::compile(&TestWorld::new(text)).output.unwrap()
+    }
+
+    /// The components of the transform of a slide's contents.
+    fn transform(svg: &str) -> [f64; 6] {
+        let prefix = "<g transform=\"matrix(";
+        let start = svg.find(prefix).unwrap() + prefix.len();
+        let end = start + svg[start..].find(')').unwrap();
+        let values: Vec<f64> = svg[start..end]
+            .split(' ')
+            .map(|value| value.parse().unwrap())
+            .collect();
+        values.try_into().unwrap()
+    }
+
+    /// The ids that an SVG defines and the ids that it refers to.
+    fn names(svg: &str) -> Vec<&str> {
+        let mut names = vec![];
+        for pattern in [" id=\"", "href=\"#", "url(#"] {
+            for (i, _) in svg.match_indices(pattern) {
+                let rest = &svg[i + pattern.len()..];
+                let end = rest.find(['"', ')']).unwrap_or(rest.len());
+                names.push(&rest[..end]);
+            }
+        }
+        names
+    }
+}
@@ -1,20 +1,54 @@
+use std::fmt::Write;
 use std::io::Read;
+use std::ops::Range;
 
 use base64::Engine;
 use ecow::EcoString;
 use ttf_parser::GlyphId;
-use typst_library::layout::{Abs, Point, Ratio, Size, Transform};
-use typst_library::text::{Font, TextItem};
-use typst_library::visualize::{FillRule, Image, Paint, RasterFormat, RelativeTo};
+use typst_library::diag::warning;
+use typst_library::layout::{Abs, Em, Point, Ratio, Size, Transform};
+use typst_library::text::{EmbeddingPermissions, Font, TextItem};
+use typst_library::visualize::{Color, FillRule, Image, Paint, RasterFormat, RelativeTo};
+use typst_syntax::Span;
 use typst_utils::hash128;
 
-use crate::{SVGRenderer, State, SvgMatrix, SvgPathBuilder};
+use crate::font::{convert_font_to_base64_url, EmbeddedFont};
+use crate::paint::ColorEncode;
+use crate::{GlyphPositioning, SVGRenderer, State, SvgMatrix, SvgPathBuilder};
 
 impl SVGRenderer {
-    /// Render a text item. The text is rendered as a group of glyphs. We will
-    /// try to render the text as SVG first, then bitmap, then outline. If none
-    /// of them works, we will skip the text.
+    /// Render a text item.
+    ///
+    /// If fonts are embedded and permit it, the text is drawn as real text
+    /// with a subset of its font. Otherwise, the text is rendered as a group
+    /// of glyphs.
     pub(super) fn render_text(&mut self, state: State, text: &TextItem) {
+        match text.fill {
+            Paint::Solid(color)
+                if self.options.embed_fonts
+                    && text.stroke.is_none()
+                    && self.may_embed_font(text) =>
+            {
+                self.render_embedded_text(state, text, color);
+            }
+            _ => {
+                self.render_glyphs(state, text, |_| true);
+                if self.options.selectable_text {
+                    self.render_selectable_text(text);
+                }
+            }
+        }
+    }
+
+    /// Render the glyphs of a text item for which `filter` returns true. We
+    /// will try to render each glyph as SVG first, then bitmap, then outline.
+    /// If none of them works, we will skip the glyph.
+    fn render_glyphs(
+        &mut self,
+        state: State,
+        text: &TextItem,
+        filter: impl Fn(usize) -> bool,
+    ) {
         let scale: f64 = text.size.to_pt() / text.font.units_per_em();
 
         self.xml.start_element("g");
@@ -22,7 +56,12 @@ impl SVGRenderer {
         self.xml.write_attribute("transform", "scale(1, -1)");
 
         let mut x: f64 = 0.0;
-        for glyph in &text.glyphs {
+        for (i, glyph) in text.glyphs.iter().enumerate() {
+            if !filter(i) {
+                x += glyph.x_advance.at(text.size).to_pt();
+                continue;
+            }
+
             let id = GlyphId(glyph.id);
             let offset = x + glyph.x_offset.at(text.size).to_pt();
 
@@ -46,6 +85,165 @@ impl SVGRenderer {
         self.xml.end_element();
     }
 
+    /// Whether the license of a text item's font permits embedding it.
+    /// Reports each font that doesn't once.
+    fn may_embed_font(&mut self, text: &TextItem) -> bool {
+        let reason = match text.font.embedding_permissions() {
+            EmbeddingPermissions::Installable | EmbeddingPermissions::Editable => {
+                return true;
+            }
+            EmbeddingPermissions::PreviewPrint => {
+                "the font's license only permits embedding it into documents that \
+                 are not edited"
+            }
+            EmbeddingPermissions::BitmapOnly => {
+                "the font's license only permits embedding its bitmaps"
+            }
+            EmbeddingPermissions::NoEmbedding => {
+                "the font's license forbids embedding it"
+            }
+        };
+
+        if self.refused_fonts.insert(text.font.clone()) {
+            let span = Span::find(text.glyphs.iter().map(|g| g.span.0));
+            self.warnings.push(warning!(
+                span,
+                "font {} may not be embedded into the SVG",
+                text.font.info().family;
+                hint: "{reason}";
+                hint: "its text was drawn as glyph outlines instead",
+            ));
+        }
+
+        false
+    }
+
+    /// Render a text item as real text that is drawn with an embedded subset
+    /// of its font.
+    ///
+    /// Clusters that aren't drawn with the default glyphs of their
+    /// characters, like ligatures, are rendered as glyphs instead. Their text
+    /// stays in place, but is transparent, so that the whole text remains
+    /// selectable.
+    fn render_embedded_text(&mut self, state: State, text: &TextItem, color: Color) {
+        // There is nothing to see in runs of only whitespace.
+        if text.text.trim().is_empty() {
+            return;
+        }
+
+        let clusters = clusters(text);
+        let embeddable: Vec<bool> =
+            clusters.iter().map(|cluster| is_embeddable(text, cluster)).collect();
+
+        let id = self
+            .fonts
+            .insert_with(hash128(&text.font), || EmbeddedFont::new(text.font.clone()));
+        let embedded = self.fonts.get_mut(id);
+
+        // Glyphs that cover no text don't belong to any cluster and are thus
+        // rendered as well.
+        let mut outlined = vec![true; text.glyphs.len()];
+        for (cluster, _) in clusters.iter().zip(&embeddable).filter(|(_, e)| **e) {
+            let glyphs = &text.glyphs[cluster.glyphs.clone()];
+            for (c, glyph) in cluster.text.chars().zip(glyphs) {
+                embedded.chars.insert(c, glyph.id);
+            }
+            outlined[cluster.glyphs.clone()].fill(false);
+        }
+
+        if outlined.contains(&true) {
+            self.render_glyphs(state, text, |i| outlined[i]);
+        }
+
+        self.xml.start_element("text");
+        self.xml.write_attribute("class", "typst-embedded");
+        self.xml.write_attribute("xml:space", "preserve");
+        self.xml.write_attribute("fill", &color.encode());
+        self.xml.write_attribute("font-family", &id);
+        self.xml.write_attribute("font-size", &text.size.to_pt());
+        self.xml.set_preserve_whitespaces(true);
+        self.write_clusters(&clusters, |k| !embeddable[k]);
+        self.xml.end_element();
+        self.xml.set_preserve_whitespaces(false);
+    }
+
+    /// Render the invisible, selectable text of a text item on top of its
+    /// glyphs.
+    fn render_selectable_text(&mut self, text: &TextItem) {
+        // Viewers handle runs of only whitespace inconsistently, and there is
+        // nothing to select in them anyway.
+        if text.text.trim().is_empty() {
+            return;
+        }
+
+        self.xml.start_element("text");
+        self.xml.write_attribute("class", "typst-selectable");
+        self.xml.write_attribute("xml:space", "preserve");
+        self.xml.write_attribute("fill", "transparent");
+        self.xml.write_attribute("font-family", &text.font.info().family);
+        self.xml.write_attribute("font-size", &text.size.to_pt());
+
+        // Indentation would become part of the text.
+        self.xml.set_preserve_whitespaces(true);
+        match self.options.glyph_positioning {
+            GlyphPositioning::Run => self.xml.write_text(&text.text),
+            GlyphPositioning::Exact => self.write_clusters(&clusters(text), |_| false),
+        }
+
+        self.xml.end_element();
+        self.xml.set_preserve_whitespaces(false);
+    }
+
+    /// Write glyph clusters as `tspan`s with one x-coordinate per cluster.
+    ///
+    /// An SVG position list assigns one coordinate to each character. Runs of
+    /// clusters that consist of a single character thus share one `tspan` with
+    /// a list of coordinates. Clusters of multiple characters, like ligatures,
+    /// get a `tspan` of their own with a single coordinate, which positions
+    /// the cluster's first character while the others follow it.
+    ///
+    /// The clusters at the indices for which `hidden` returns true are made
+    /// transparent.
+    fn write_clusters(&mut self, clusters: &[Cluster], hidden: impl Fn(usize) -> bool) {
+        let single = |cluster: &Cluster| {
+            let mut chars = cluster.text.chars();
+            chars.next().is_some_and(|c| c.len_utf16() == 1) && chars.next().is_none()
+        };
+
+        let mut i = 0;
+        while i < clusters.len() {
+            let start = i;
+            i += 1;
+            if single(&clusters[start]) {
+                while i < clusters.len()
+                    && single(&clusters[i])
+                    && hidden(i) == hidden(start)
+                {
+                    i += 1;
+                }
+            }
+
+            let run = &clusters[start..i];
+            let mut xs = EcoString::new();
+            let mut content = EcoString::new();
+            for (k, cluster) in run.iter().enumerate() {
+                if k > 0 {
+                    xs.push(' ');
+                }
+                write!(xs, "{}", (cluster.x * 1000.0).round() / 1000.0).unwrap();
+                content.push_str(cluster.text);
+            }
+
+            self.xml.start_element("tspan");
+            self.xml.write_attribute("x", &xs);
+            if hidden(start) {
+                self.xml.write_attribute("fill", "transparent");
+            }
+            self.xml.write_text(&content);
+            self.xml.end_element();
+        }
+    }
+
     /// Render a glyph defined by an SVG.
     fn render_svg_glyph(
         &mut self,
@@ -179,7 +377,7 @@ impl SVGRenderer {
         }
 
         self.xml.start_element("defs");
-        self.xml.write_attribute("id", "glyph");
+        self.write_defs_id("glyph");
 
         for (id, glyph) in self.glyphs.iter() {
             self.xml.start_element("symbol");
@@ -210,6 +408,45 @@ impl SVGRenderer {
 
         self.xml.end_element();
     }
+
+    /// Build the `@font-face` rules of the embedded fonts.
+    pub(super) fn write_font_defs(&mut self) {
+        let mut rules = EcoString::new();
+        for (id, embedded) in self.fonts.iter() {
+            if embedded.chars.is_empty() {
+                continue;
+            }
+
+            let Some(url) = convert_font_to_base64_url(&embedded.font, &embedded.chars)
+            else {
+                self.warnings.push(warning!(
+                    Span::detached(),
+                    "failed to embed font {}",
+                    embedded.font.info().family;
+                    hint: "viewers will draw its text with a different font",
+                ));
+                continue;
+            };
+
+            write!(
+                rules,
+                "@font-face {{ font-family: \"{id}\"; \
+                 src: url(\"{url}\") format(\"woff2\"); }}"
+            )
+            .unwrap();
+        }
+
+        if rules.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.write_defs_id("font");
+        self.xml.start_element("style");
+        self.xml.write_text(&rules);
+        self.xml.end_element();
+        self.xml.end_element();
+    }
 }
 
 /// Represents a glyph to be rendered.
@@ -224,6 +461,61 @@ pub enum RenderedGlyph {
     Image { url: EcoString, width: f64, height: f64, ts: Transform },
 }
 
+/// A glyph cluster of a text item.
+struct Cluster<'a> {
+    /// The horizontal position of the cluster's first glyph.
+    x: f64,
+    /// The text that the cluster covers.
+    text: &'a str,
+    /// The indices of the cluster's glyphs in the text item.
+    glyphs: Range<usize>,
+}
+
+/// The glyph clusters of a text item in text order.
+///
+/// Consecutive glyphs for the same part of the text, like a base character
+/// and its decomposed mark, form one cluster. Glyphs that cover no text, like
+/// zero-width glyphs inserted by the shaper, are skipped because there is no
+/// character to position.
+fn clusters(text: &TextItem) -> Vec<Cluster> {
+    let mut clusters: Vec<(f64, Range<usize>, Range<usize>)> = vec![];
+    let mut x = 0.0;
+    for (i, glyph) in text.glyphs.iter().enumerate() {
+        let range = glyph.range();
+        match clusters.last_mut() {
+            Some((_, last, glyphs)) if *last == range => glyphs.end = i + 1,
+            _ => clusters.push((x, range, i..i + 1)),
+        }
+        x += glyph.x_advance.at(text.size).to_pt();
+    }
+
+    clusters.retain(|(_, range, _)| !range.is_empty());
+    clusters.sort_by_key(|(_, range, _)| range.start);
+    clusters
+        .into_iter()
+        .map(|(x, range, glyphs)| Cluster { x, text: &text.text[range], glyphs })
+        .collect()
+}
+
+/// Whether a cluster is drawn with the default glyphs of its characters at
+/// their default positions. Only then, viewers draw it the same way with the
+/// embedded subset of the font, which lacks the font's layout features.
+fn is_embeddable(text: &TextItem, cluster: &Cluster) -> bool {
+    let ttf = text.font.ttf();
+    let glyphs = &text.glyphs[cluster.glyphs.clone()];
+    glyphs.iter().all(|glyph| {
+        let id = GlyphId(glyph.id);
+        glyph.x_offset == Em::zero()
+            && !ttf.is_color_glyph(id)
+            && ttf.glyph_svg_image(id).is_none()
+            && ttf.glyph_raster_image(id, u16::MAX).is_none()
+    }) && cluster
+        .text
+        .chars()
+        .map(|c| ttf.glyph_index(c).map(|id| id.0))
+        .eq(glyphs.iter().map(|glyph| Some(glyph.id)))
+}
+
 /// Convert an outline glyph to an SVG path.
 #[comemo::memoize]
 fn convert_outline_glyph_to_path(
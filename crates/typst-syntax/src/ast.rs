@@ -2150,14 +2150,31 @@ impl<'a> RenamedImportItem<'a> {
 }
 
 node! {
-    /// A module include: `include "chapter1.typ"`.
+    /// A module include: `include "chapter1.typ"` or
+    /// `include("chapter1.typ", inputs: (draft: true))`.
     ModuleInclude
 }
 
 impl<'a> ModuleInclude<'a> {
     /// The module or path from which the content should be included.
+    ///
+    /// For the call form, this is the first positional argument.
     pub fn source(self) -> Expr<'a> {
-        self.0.cast_last_match().unwrap_or_default()
+        match self.args() {
+            Some(args) => args
+                .items()
+                .find_map(|arg| match arg {
+                    Arg::Pos(expr) => Some(expr),
+                    _ => Option::None,
+                })
+                .unwrap_or_default(),
+            Option::None => self.0.cast_last_match().unwrap_or_default(),
+        }
+    }
+
+    /// The arguments of the call form: `include("chapter1.typ", ..)`.
+    pub fn args(self) -> Option<Args<'a>> {
+        self.0.cast_first_match()
     }
 }
 
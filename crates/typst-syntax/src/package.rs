@@ -174,7 +174,7 @@ impl PackageManifest {
 }
 
 /// Identifies a package.
-#[derive(Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct PackageSpec {
     /// The namespace the package lives in.
     pub namespace: EcoString,
@@ -217,6 +217,19 @@ impl Display for PackageSpec {
     }
 }
 
+impl Serialize for PackageSpec {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PackageSpec {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let string = EcoString::deserialize(d)?;
+        string.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 /// Identifies a package, but not a specific version of it.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct VersionlessPackageSpec {
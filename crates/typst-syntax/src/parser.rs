@@ -981,7 +981,11 @@ fn import_items(p: &mut Parser) {
 fn module_include(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Include);
-    code_expr(p);
+    if p.directly_at(SyntaxKind::LeftParen) {
+        args(p);
+    } else {
+        code_expr(p);
+    }
     p.wrap(m, SyntaxKind::ModuleInclude);
 }
 
@@ -0,0 +1,70 @@
+use wasm_bindgen::prelude::*;
+
+use crate::InMemoryWorld;
+
+/// A Typst compiler that can be driven from JavaScript.
+///
+/// All files and fonts must be pushed into the compiler before compiling. If a
+/// compilation fails because of a missing file, its path is listed by
+/// `take_missing`, so that it can be fetched and the compilation repeated.
+#[wasm_bindgen]
+pub struct Compiler {
+    world: InMemoryWorld,
+}
+
+#[wasm_bindgen]
+impl Compiler {
+    /// Create a compiler whose entrypoint is the source at the given path.
+    #[wasm_bindgen(constructor)]
+    pub fn new(main: &str) -> Result<Compiler, JsError> {
+        Ok(Self { world: InMemoryWorld::new(main).map_err(error)? })
+    }
+
+    /// Change the entrypoint of the compilation.
+    pub fn set_main(&mut self, path: &str) -> Result<(), JsError> {
+        self.world.set_main(path).map_err(error)
+    }
+
+    /// Add or update a source file.
+    pub fn set_source(&mut self, path: &str, text: &str) -> Result<(), JsError> {
+        self.world.set_source(path, text).map_err(error)
+    }
+
+    /// Add or update a binary file.
+    pub fn set_file(&mut self, path: &str, data: Vec<u8>) -> Result<(), JsError> {
+        self.world.set_file(path, data).map_err(error)
+    }
+
+    /// Remove a source or binary file.
+    pub fn remove(&mut self, path: &str) -> Result<(), JsError> {
+        self.world.remove(path).map_err(error)
+    }
+
+    /// Add all fonts in a font file or collection and return how many there
+    /// were.
+    pub fn add_font(&mut self, data: Vec<u8>) -> usize {
+        self.world.add_font(data)
+    }
+
+    /// Return and forget the paths of missing files.
+    pub fn take_missing(&self) -> Vec<String> {
+        self.world.take_missing().into_iter().map(Into::into).collect()
+    }
+
+    /// Compile the document and export it to PDF with options given as
+    /// UTF-8 encoded JSON.
+    pub fn compile_pdf(&self, options: &[u8]) -> Result<Vec<u8>, JsError> {
+        crate::compile_pdf(&self.world, options).map_err(error)
+    }
+
+    /// Compile the document and export the page with the given zero-based
+    /// index to SVG.
+    pub fn compile_svg(&self, page: usize) -> Result<String, JsError> {
+        crate::compile_svg(&self.world, page).map_err(error)
+    }
+}
+
+/// Turn an error message into a JavaScript error.
+fn error(message: impl AsRef<str>) -> JsError {
+    JsError::new(message.as_ref())
+}
@@ -0,0 +1,126 @@
+//! An example of running the full Typst pipeline in the browser.
+//!
+//! The [`InMemoryWorld`] provides sources, files, and fonts that were loaded by
+//! the embedder. With the `wasm` feature, the `Compiler` exposes it to
+//! JavaScript through `wasm-bindgen`, together with PDF and SVG export:
+//!
+//! ```js
+//! import init, { Compiler } from "./typst_wasm_example.js";
+//!
+//! await init();
+//! const compiler = new Compiler("main.typ");
+//! compiler.add_font(new Uint8Array(await (await fetch("font.otf")).arrayBuffer()));
+//! compiler.set_source("main.typ", "Hello, world!");
+//! const svg = compiler.compile_svg(0);
+//! const pdf = compiler.compile_pdf(new TextEncoder().encode("{}"));
+//! ```
+
+#[cfg(feature = "wasm")]
+mod bindings;
+mod world;
+
+#[cfg(feature = "wasm")]
+pub use self::bindings::Compiler;
+pub use self::world::InMemoryWorld;
+
+use std::fmt::Write;
+
+use ecow::{eco_format, EcoString};
+use serde::Deserialize;
+use typst::diag::{Severity, SourceDiagnostic, Warned};
+use typst::foundations::Smart;
+use typst::layout::PagedDocument;
+use typst::{World, WorldExt};
+use typst_pdf::{PdfOptions, PdfStandard, PdfStandards};
+
+/// Compile the world's main file and export it to PDF.
+///
+/// The `options` are a JSON object with the optional fields `ident` (a stable
+/// identifier for the document), `standards` (a list of PDF standards, like
+/// `"a-2b"`), and `ignore_embedding_restrictions` (whether to embed fonts whose
+/// license forbids it). Empty options use the defaults.
+pub fn compile_pdf(world: &InMemoryWorld, options: &[u8]) -> Result<Vec<u8>, EcoString> {
+    let config: PdfConfig = if options.is_empty() {
+        PdfConfig::default()
+    } else {
+        serde_json::from_slice(options)
+            .map_err(|err| eco_format!("invalid PDF options: {err}"))?
+    };
+
+    let document = compile(world)?;
+    let options = PdfOptions {
+        ident: config.ident.as_deref().map_or(Smart::Auto, Smart::Custom),
+        timestamp: None,
+        page_ranges: None,
+        standards: PdfStandards::new(&config.standards)?,
+        flatten_transparency: false,
+        flatten_dpi: Smart::Auto,
+        gradient_samples: Smart::Auto,
+        ignore_embedding_restrictions: config.ignore_embedding_restrictions,
+        attachments: &[],
+    };
+
+    typst_pdf::pdf(&document, &options).map_err(|errors| format(world, &errors))
+}
+
+/// Compile the world's main file and export the page with the given
+/// zero-based index to SVG.
+pub fn compile_svg(world: &InMemoryWorld, page: usize) -> Result<String, EcoString> {
+    let document = compile(world)?;
+    let count = document.pages.len();
+    let page = document.pages.get(page).ok_or_else(|| {
+        eco_format!("page index {page} is out of bounds (document has {count} pages)")
+    })?;
+    Ok(typst_svg::svg(page))
+}
+
+/// Options for PDF export, deserialized from JSON.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PdfConfig {
+    ident: Option<String>,
+    standards: Vec<PdfStandard>,
+    ignore_embedding_restrictions: bool,
+}
+
+/// Compile the world's main file.
+fn compile(world: &InMemoryWorld) -> Result<PagedDocument, EcoString> {
+    let Warned { output, .. } = typst::compile::<PagedDocument>(world);
+
+    // Keep the memoization cache from growing without bounds across
+    // recompilations.
+    comemo::evict(10);
+
+    output.map_err(|errors| format(world, &errors))
+}
+
+/// Format diagnostics as human-readable text.
+fn format(world: &InMemoryWorld, diagnostics: &[SourceDiagnostic]) -> EcoString {
+    let mut out = EcoString::new();
+    for diagnostic in diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        write!(out, "{severity}: {}", diagnostic.message_with_occurrences()).unwrap();
+        if let Some((id, range)) = diagnostic.span.id().zip(world.range(diagnostic.span))
+        {
+            if let Ok(source) = world.source(id) {
+                let line = source.byte_to_line(range.start).unwrap_or(0);
+                let column = source.byte_to_column(range.start).unwrap_or(0);
+                let path = id.vpath().as_rootless_path().display();
+                write!(out, " ({path}:{}:{})", line + 1, column + 1).unwrap();
+            }
+        }
+
+        for hint in &diagnostic.hints {
+            write!(out, "\n  hint: {hint}").unwrap();
+        }
+    }
+    out
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use ecow::{eco_format, EcoString};
+use typst::diag::{FileError, FileResult, StrResult};
+use typst::foundations::{Bytes, Datetime};
+use typst::syntax::package::PackageSpec;
+use typst::syntax::{FileId, Source, VirtualPath};
+use typst::text::{Font, FontBook};
+use typst::utils::LazyHash;
+use typst::{Library, World};
+
+/// A world that keeps all sources, files, and fonts in memory.
+///
+/// A [`World`] is queried synchronously during compilation, so it cannot fetch
+/// anything by itself. Instead, the embedder loads files up front (e.g. with
+/// `fetch` or from IndexedDB) and pushes them into the world. Files that a
+/// compilation asked for but that were not available are recorded, so that
+/// the embedder can load them and compile again.
+///
+/// All updates are designed to keep Typst's incremental compilation effective:
+/// Sources are edited in place instead of being replaced and pushing unchanged
+/// data is a no-op.
+pub struct InMemoryWorld {
+    /// Typst's standard library.
+    library: LazyHash<Library>,
+    /// Metadata about all added fonts.
+    book: LazyHash<FontBook>,
+    /// The added fonts, in the order of the book.
+    fonts: Vec<Font>,
+    /// The entrypoint of the compilation.
+    main: FileId,
+    /// Text files, parsed as Typst sources.
+    sources: HashMap<FileId, Source>,
+    /// Binary files.
+    files: HashMap<FileId, Bytes>,
+    /// The date to use instead of the current one.
+    today: Option<Datetime>,
+    /// Files that were requested, but are not available.
+    missing: Mutex<Vec<FileId>>,
+}
+
+impl InMemoryWorld {
+    /// Create a new world without any files or fonts.
+    ///
+    /// The `main` path is resolved like the paths passed to
+    /// [`set_source`](Self::set_source).
+    pub fn new(main: &str) -> StrResult<Self> {
+        Ok(Self {
+            library: LazyHash::new(Library::default()),
+            book: LazyHash::new(FontBook::new()),
+            fonts: vec![],
+            main: file_id(main)?,
+            sources: HashMap::new(),
+            files: HashMap::new(),
+            today: None,
+            missing: Mutex::new(vec![]),
+        })
+    }
+
+    /// Change the entrypoint of the compilation.
+    pub fn set_main(&mut self, path: &str) -> StrResult<()> {
+        self.main = file_id(path)?;
+        Ok(())
+    }
+
+    /// Add or update a source file.
+    ///
+    /// The path is relative to the project root, e.g. `chapters/intro.typ`.
+    /// Files in packages are addressed with the package specification as a
+    /// prefix, e.g. `@preview/example:0.1.0/lib.typ`.
+    ///
+    /// Updating an existing source only reparses the changed part of it, so
+    /// that the unchanged parts keep their spans and memoized results.
+    pub fn set_source(&mut self, path: &str, text: &str) -> StrResult<()> {
+        let id = file_id(path)?;
+        match self.sources.get_mut(&id) {
+            Some(source) => {
+                if source.text() != text {
+                    source.replace(text);
+                }
+            }
+            None => {
+                self.sources.insert(id, Source::new(id, text.into()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Add or update a binary file, like an image or a data file.
+    ///
+    /// The path is resolved like for [`set_source`](Self::set_source).
+    pub fn set_file(&mut self, path: &str, data: Vec<u8>) -> StrResult<()> {
+        let id = file_id(path)?;
+        if self.files.get(&id).map_or(true, |prev| prev.as_slice() != data) {
+            self.files.insert(id, Bytes::from(data));
+        }
+        Ok(())
+    }
+
+    /// Remove a source or binary file.
+    pub fn remove(&mut self, path: &str) -> StrResult<()> {
+        let id = file_id(path)?;
+        self.sources.remove(&id);
+        self.files.remove(&id);
+        Ok(())
+    }
+
+    /// Add all fonts in a font file or collection and return how many there
+    /// were.
+    ///
+    /// Fonts can only be added, so that the indices of existing fonts stay
+    /// valid.
+    pub fn add_font(&mut self, data: Vec<u8>) -> usize {
+        let mut count = 0;
+        for font in Font::iter(Bytes::from(data)) {
+            self.book.push(font.info().clone());
+            self.fonts.push(font);
+            count += 1;
+        }
+        count
+    }
+
+    /// Fix the date returned by `datetime.today()`, instead of using the
+    /// current date.
+    pub fn set_today(&mut self, today: Option<Datetime>) {
+        self.today = today;
+    }
+
+    /// Return and forget the paths of the files that were requested since the
+    /// last call, but are not available.
+    ///
+    /// Since compilation results are memoized, a missing file is only reported
+    /// after the compilation that first requested it.
+    pub fn take_missing(&self) -> Vec<EcoString> {
+        let mut missing = self.missing.lock().unwrap();
+        missing.drain(..).map(display).collect()
+    }
+
+    /// Record that a file is not available.
+    fn not_found(&self, id: FileId) -> FileError {
+        let mut missing = self.missing.lock().unwrap();
+        if !missing.contains(&id) {
+            missing.push(id);
+        }
+        FileError::NotFound(id.vpath().as_rootless_path().into())
+    }
+}
+
+impl World for InMemoryWorld {
+    fn library(&self) -> &LazyHash<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> FileId {
+        self.main
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        match self.sources.get(&id) {
+            Some(source) => Ok(source.clone()),
+            None => Err(self.not_found(id)),
+        }
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if let Some(data) = self.files.get(&id) {
+            Ok(data.clone())
+        } else if let Some(source) = self.sources.get(&id) {
+            Ok(Bytes::from(source.text().as_bytes()))
+        } else {
+            Err(self.not_found(id))
+        }
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.get(index).cloned()
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.today.or_else(|| now(offset))
+    }
+}
+
+/// Resolve a path like `chapters/intro.typ` or `@preview/example:0.1.0/lib.typ`
+/// to a file id.
+fn file_id(path: &str) -> StrResult<FileId> {
+    if !path.starts_with('@') {
+        return Ok(FileId::new(None, VirtualPath::new(path)));
+    }
+
+    let split = path
+        .find(':')
+        .and_then(|colon| path[colon..].find('/').map(|slash| colon + slash))
+        .ok_or_else(|| eco_format!("package file path is missing a file: {path}"))?;
+    let spec: PackageSpec = path[..split].parse()?;
+    Ok(FileId::new(Some(spec), VirtualPath::new(&path[split..])))
+}
+
+/// Format a file id like the paths accepted by [`file_id`].
+fn display(id: FileId) -> EcoString {
+    let path = id.vpath().as_rootless_path().display();
+    match id.package() {
+        Some(spec) => eco_format!("{spec}/{path}"),
+        None => eco_format!("{path}"),
+    }
+}
+
+/// The current date in the browser's time zone or, if an offset is given, in
+/// UTC shifted by the offset in hours.
+///
+/// `std::time` is not available on `wasm32-unknown-unknown`, so this uses
+/// JavaScript's `Date` instead.
+#[cfg(feature = "wasm")]
+fn now(offset: Option<i64>) -> Option<Datetime> {
+    let date = js_sys::Date::new_0();
+    let (year, month, day) = match offset {
+        Some(hours) => {
+            let millis = date.get_time() + hours as f64 * 3_600_000.0;
+            let date = js_sys::Date::new(&millis.into());
+            (date.get_utc_full_year(), date.get_utc_month(), date.get_utc_date())
+        }
+        None => (date.get_full_year(), date.get_month(), date.get_date()),
+    };
+
+    // JavaScript's months are zero-based.
+    Datetime::from_ymd(year as i32, month as u8 + 1, day as u8)
+}
+
+/// Without the JavaScript bindings, there is no portable way to get the
+/// current date, so it must be set explicitly.
+#[cfg(not(feature = "wasm"))]
+fn now(_: Option<i64>) -> Option<Datetime> {
+    None
+}
@@ -0,0 +1,56 @@
+//! Compiles documents natively and in a headless JavaScript environment.
+//!
+//! Run in JavaScript with
+//! `wasm-pack test --node crates/typst-wasm-example --features wasm`.
+
+#![cfg(any(not(target_arch = "wasm32"), feature = "wasm"))]
+
+use typst_wasm_example::{compile_svg, InMemoryWorld};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+/// Create a world with the default fonts and the given main source.
+fn world(text: &str) -> InMemoryWorld {
+    let mut world = InMemoryWorld::new("main.typ").unwrap();
+    for data in typst_assets::fonts() {
+        world.add_font(data.to_vec());
+    }
+    world.set_source("main.typ", text).unwrap();
+    world
+}
+
+#[test]
+fn test_hello_world_svg() {
+    let world = world("Hello, world!");
+    let svg = compile_svg(&world, 0).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("<symbol id=\"g"));
+    assert!(svg.contains("xlink:href=\"#g"));
+}
+
+#[cfg(target_arch = "wasm32")]
+#[test]
+fn test_today_from_javascript() {
+    let world = world("#datetime.today().display()");
+    assert!(compile_svg(&world, 0).is_ok());
+}
+
+#[test]
+fn test_missing_file() {
+    let mut world = world("#image(\"logo.svg\")");
+    assert!(compile_svg(&world, 0).is_err());
+    assert_eq!(world.take_missing(), ["logo.svg"]);
+
+    let logo = "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"10\" height=\"10\"/>";
+    world.set_file("logo.svg", logo.as_bytes().to_vec()).unwrap();
+    assert!(compile_svg(&world, 0).is_ok());
+}
+
+#[test]
+fn test_update_main_source() {
+    let mut world = world("= Old");
+    let old = compile_svg(&world, 0).unwrap();
+    world.set_source("main.typ", "= New").unwrap();
+    let new = compile_svg(&world, 0).unwrap();
+    assert_ne!(old, new);
+}
@@ -0,0 +1,239 @@
+//! Cheap analysis of a document for editor tooling.
+
+use std::num::NonZeroUsize;
+
+use comemo::Track;
+use ecow::{EcoString, EcoVec};
+use typst_library::diag::{ErrorMode, SourceDiagnostic};
+use typst_library::engine::{Route, Sink, Traced};
+use typst_library::foundations::{Content, Label, StyleChain, Value};
+use typst_library::model::HeadingElem;
+use typst_library::World;
+use typst_syntax::ast::{self, AstNode};
+use typst_syntax::{Span, SyntaxNode};
+use typst_utils::PicoStr;
+
+use crate::{deduplicate, hint_invalid_main_file, ROUTINES};
+
+/// Evaluates the main source file and summarizes its contents, without laying
+/// out the document.
+///
+/// This only parses and evaluates the main file, so it is much cheaper than a
+/// full compilation: There is no realization, no layout, and no introspection
+/// loop. Evaluation is memoized, so that running this on every keystroke only
+/// re-evaluates what changed. In return, the result only reflects the content
+/// as it was written, before any show rules apply. Elements produced by show
+/// rules or in `context` expressions are not included.
+///
+/// Evaluation continues past recoverable errors, like unknown variables in
+/// markup. If it fails nonetheless, the labels and headings are extracted
+/// from the syntax of the main file instead, so that an editor can keep
+/// offering them while the document is broken.
+#[typst_macros::time]
+pub fn analyze(world: &dyn World) -> Analysis {
+    let world = world.track();
+    let mut sink = Sink::new();
+    let mut analysis = Analysis::default();
+
+    let main = world.main();
+    let source = match world.source(main) {
+        Ok(source) => source,
+        Err(err) => {
+            analysis.diagnostics = hint_invalid_main_file(world, err, main);
+            return analysis;
+        }
+    };
+
+    let result = typst_eval::eval_with_mode(
+        &ROUTINES,
+        world,
+        Traced::default().track(),
+        sink.track_mut(),
+        Route::default().track(),
+        &source,
+        ErrorMode::Collect,
+    );
+
+    let mut errors = match result {
+        Ok(module) => {
+            analysis.bindings = module
+                .scope()
+                .iter()
+                .map(|(name, value, span)| (name.clone(), value.clone(), span))
+                .collect();
+            analysis.summarize_content(module.content());
+            EcoVec::new()
+        }
+        Err(errors) => {
+            analysis.summarize_syntax(source.root());
+            errors
+        }
+    };
+
+    // Errors that evaluation recovered from come first.
+    let mut delayed = sink.delayed();
+    delayed.extend(errors);
+    errors = deduplicate(delayed);
+    errors.extend(sink.warnings());
+    analysis.diagnostics = errors;
+    analysis
+}
+
+/// The result of [`analyze`].
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    /// The bindings defined at the top level of the main file, with the spans
+    /// of their definitions. These are the bindings that other files can
+    /// import. Empty if evaluation failed.
+    pub bindings: Vec<(EcoString, Value, Span)>,
+    /// The labels in the document, in document order, with the spans of the
+    /// labelled elements. If evaluation failed, these are the spans of the
+    /// labels themselves.
+    pub labels: Vec<(Label, Span)>,
+    /// The headings in the document, in document order.
+    pub headings: Vec<HeadingSummary>,
+    /// The errors and warnings that occurred during evaluation.
+    pub diagnostics: EcoVec<SourceDiagnostic>,
+}
+
+impl Analysis {
+    /// Collects the labels and headings of the evaluated content.
+    fn summarize_content(&mut self, content: Content) {
+        content.traverse(&mut |elem| {
+            if let Some(label) = elem.label() {
+                self.labels.push((label, elem.span()));
+            }
+
+            if let Some(heading) = elem.to_packed::<HeadingElem>() {
+                self.headings.push(HeadingSummary {
+                    level: heading.resolve_level(StyleChain::default()),
+                    title: heading.body().plain_text().trim().into(),
+                    span: heading.span(),
+                });
+            }
+        });
+    }
+
+    /// Collects the labels and headings written in markup.
+    fn summarize_syntax(&mut self, node: &SyntaxNode) {
+        if let Some(label) = node.cast::<ast::Label>() {
+            let label = Label::new(PicoStr::intern(label.get()));
+            self.labels.push((label, node.span()));
+        } else if let Some(heading) = node.cast::<ast::Heading>() {
+            let body = heading.body().to_untyped().clone().into_text();
+            self.headings.push(HeadingSummary {
+                level: heading.depth(),
+                title: body.trim().into(),
+                span: node.span(),
+            });
+        }
+
+        for child in node.children() {
+            self.summarize_syntax(child);
+        }
+    }
+}
+
+/// A heading found by [`analyze`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HeadingSummary {
+    /// The heading's level, as given by its `level` or `depth` and `offset`
+    /// arguments. Set rules are not taken into account.
+    pub level: NonZeroUsize,
+    /// The plain text of the heading's body.
+    ///
+    /// If evaluation failed, this is the heading's markup instead.
+    pub title: EcoString,
+    /// The span of the heading.
+    pub span: Span,
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::WorldExt;
+
+    use super::*;
+    use crate::testing::TestWorld;
+
+    /// Returns the trimmed source text that a span covers.
+    fn spanned(world: &TestWorld, span: Span) -> Option<String> {
+        let source = world.source(world.main()).ok()?;
+        Some(source.text()[world.range(span)?].trim().into())
+    }
+
+    #[test]
+    fn test_analyze() {
+        let world = TestWorld::new(
+            "#let author = \"Jane\"\n\
+             = Introduction <intro>\n\
+             == Details\n\
+             #figure[A] <fig>\n\
+             #heading(level: 1)[Results]",
+        );
+        let analysis = analyze(&world);
+        assert!(analysis.diagnostics.is_empty());
+
+        let bindings: Vec<_> =
+            analysis.bindings.iter().map(|(name, ..)| name.as_str()).collect();
+        assert_eq!(bindings, ["author"]);
+
+        let labels: Vec<_> = analysis
+            .labels
+            .iter()
+            .map(|&(label, span)| (label.resolve().to_string(), spanned(&world, span)))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                ("intro".to_string(), Some("= Introduction".to_string())),
+                ("fig".to_string(), Some("figure[A]".to_string())),
+            ]
+        );
+
+        let headings: Vec<_> = analysis
+            .headings
+            .iter()
+            .map(|heading| (heading.level.get(), heading.title.as_str()))
+            .collect();
+        assert_eq!(headings, [(1, "Introduction"), (2, "Details"), (1, "Results")]);
+        assert_eq!(
+            spanned(&world, analysis.headings[1].span).as_deref(),
+            Some("== Details")
+        );
+    }
+
+    #[test]
+    fn test_analyze_recoverable_error() {
+        // A recoverable error keeps the evaluated content.
+        let world = TestWorld::new("= Known <known>\n#unknown");
+        let analysis = analyze(&world);
+        assert_eq!(analysis.diagnostics.len(), 1);
+        assert_eq!(analysis.headings.len(), 1);
+        assert_eq!(analysis.labels.len(), 1);
+    }
+
+    #[test]
+    fn test_analyze_fatal_error() {
+        // A fatal error falls back to the syntax.
+        let world = TestWorld::new("= Intro <intro>\n#let x = 1 + \"a\"\n== More");
+        let analysis = analyze(&world);
+        let messages: Vec<_> = analysis
+            .diagnostics
+            .iter()
+            .map(|diag| diag.message.as_str())
+            .collect();
+        assert_eq!(messages, ["cannot add integer and string"]);
+        assert!(analysis.bindings.is_empty());
+
+        let (label, span) = analysis.labels[0];
+        assert_eq!(label.resolve().as_str(), "intro");
+        assert_eq!(spanned(&world, span).as_deref(), Some("<intro>"));
+
+        let headings: Vec<_> = analysis
+            .headings
+            .iter()
+            .map(|heading| (heading.level.get(), heading.title.as_str()))
+            .collect();
+        assert_eq!(headings, [(1, "Intro"), (2, "More")]);
+    }
+}
@@ -0,0 +1,241 @@
+//! Inspection and control of the memoization cache.
+//!
+//! Typst memoizes evaluation and layout across compilations. A long-running
+//! process, like a compile server, can use the functions in this module to
+//! fill the cache before the first request arrives, to monitor it, and to
+//! keep it from growing without bounds.
+
+use std::sync::Arc;
+
+use comemo::Track;
+use typst_library::diag::{FileResult, StrResult};
+use typst_library::engine::{
+    enable_memo_counters, Engine, MemoCounters, Route, Sink, Traced, MEMO_COUNTERS,
+};
+use typst_library::foundations::{
+    Bytes, Datetime, StyleChain, Styles, Target, TargetElem,
+};
+use typst_library::introspection::{Introspector, Locator};
+use typst_library::layout::{Axes, PagedDocument};
+use typst_library::model::DocumentInfo;
+use typst_library::routines::{Arenas, RealizationKind};
+use typst_library::text::{Font, FontBook};
+use typst_library::visualize::{RasterImage, SvgImage};
+use typst_library::{Library, World};
+use typst_syntax::package::PackageSpec;
+use typst_syntax::{FileId, Source};
+use typst_utils::LazyHash;
+
+use crate::ROUTINES;
+
+/// Fill the memoization cache by compiling the given documents and discarding
+/// the results.
+///
+/// Each file in `documents` is compiled as if it was the world's main file.
+/// Subsequent compilations of the same documents (or of documents that import
+/// them) are then served from the cache where possible. Errors and warnings
+/// are ignored, they are reported again by the actual compilation.
+///
+/// If `layout` is `false`, the documents are only evaluated and realized, but
+/// not laid out. This is much cheaper, but only warms the cache for modules
+/// and closures (including those of show rules): Layout results are never
+/// cached without a full layout.
+#[typst_macros::time]
+pub fn prewarm(world: &dyn World, documents: &[FileId], layout: bool) {
+    for &main in documents {
+        let world = MainWorld { world, main };
+        if layout {
+            let _ = crate::compile::<PagedDocument>(&world);
+        } else {
+            realize(&world);
+        }
+    }
+}
+
+/// Evaluate and realize the world's main file like the first layout
+/// iteration of a compilation does, discarding the results.
+fn realize(world: &dyn World) {
+    let Ok(source) = world.source(world.main()) else { return };
+    let world = world.track();
+    let traced = Traced::default();
+    let mut sink = Sink::new();
+    let Ok(module) = typst_eval::eval(
+        &ROUTINES,
+        world,
+        traced.track(),
+        sink.track_mut(),
+        Route::default().track(),
+        &source,
+    ) else {
+        return;
+    };
+
+    let library = world.library();
+    let base = StyleChain::new(&library.styles);
+    let mut target = Styles::new();
+    target.set(TargetElem::set_target(Target::Paged));
+    let styles = base.chain(&target);
+
+    // Mirror the setup of document layout, so that the show rules are called
+    // with the same arguments as in the compilation.
+    let styles = styles.to_map().outside();
+    let styles = StyleChain::new(&styles);
+    let introspector = Introspector::default();
+    let mut engine = Engine {
+        routines: &ROUTINES,
+        world,
+        introspector: introspector.track(),
+        traced: traced.track(),
+        sink: sink.track_mut(),
+        route: Route::default().unnested(),
+    };
+
+    let arenas = Arenas::default();
+    let mut info = DocumentInfo::default();
+    let _ = (ROUTINES.realize)(
+        RealizationKind::LayoutDocument(&mut info),
+        &mut engine,
+        &mut Locator::root().split(),
+        &arenas,
+        &module.content(),
+        styles,
+    );
+}
+
+/// Approximate statistics about the contents of the memoization cache.
+///
+/// Each count is the number of results that the respective kind of memoized
+/// routine added to the cache since it was last cleared completely. Entries
+/// that were removed by a partial [`evict`] are still counted, so the counts
+/// are an upper bound. The cache is shared by all compilations in the process,
+/// so the counts also include work of concurrent compilations.
+///
+/// The cache does not track the size of its entries, so there is no measure
+/// of its memory usage. The number of layouts is typically the best proxy for
+/// it.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CacheStats {
+    /// The number of evaluated source files and strings.
+    pub modules: usize,
+    /// The number of calls of user-defined closures.
+    pub closures: usize,
+    /// The number of laid out documents, page runs, flows, blocks, and
+    /// paragraphs.
+    pub layouts: usize,
+    /// The number of text shaping plans.
+    pub shape_plans: usize,
+}
+
+impl CacheStats {
+    /// The total number of cached entries.
+    pub fn total(&self) -> usize {
+        self.modules + self.closures + self.layouts + self.shape_plans
+    }
+}
+
+impl From<&MemoCounters> for CacheStats {
+    fn from(counters: &MemoCounters) -> Self {
+        Self {
+            modules: counters.modules.get(),
+            closures: counters.closures.get(),
+            layouts: counters.layouts.get(),
+            shape_plans: counters.shape_plans.get(),
+        }
+    }
+}
+
+/// Start collecting the statistics returned by [`cache_stats`].
+///
+/// Counting has a small cost for each execution of a memoized routine, so it
+/// is disabled by default. Results that were added to the cache before this
+/// was called are not counted.
+pub fn enable_cache_stats() {
+    enable_memo_counters();
+}
+
+/// Return approximate statistics about the contents of the memoization cache.
+///
+/// All counts stay at zero unless [`enable_cache_stats`] was called.
+pub fn cache_stats() -> CacheStats {
+    CacheStats::from(&MEMO_COUNTERS)
+}
+
+/// Run `f` and return statistics about the results that it added to the
+/// memoization cache.
+///
+/// Unlike the difference of two calls to [`cache_stats`], this only counts
+/// the work of compilations that run on the current thread, including the
+/// work they distribute to other threads. Concurrent compilations on other
+/// threads are not counted. If the current thread belongs to a thread pool
+/// that runs unrelated tasks while a compilation waits for its parallel work,
+/// their work is counted, too. To avoid this, run `f` on a dedicated thread.
+pub fn count_cache_additions<T>(f: impl FnOnce() -> T) -> (T, CacheStats) {
+    let counters = Arc::new(MemoCounters::new());
+    let output = {
+        let _scope = counters.enter();
+        f()
+    };
+    (output, CacheStats::from(&*counters))
+}
+
+/// Evict entries from the memoization cache that were not used in the last
+/// `max_age` evictions.
+///
+/// Calling this after each compilation with a small `max_age` (e.g. 10) keeps
+/// the results that are needed to recompile the same documents, while bounding
+/// the size of the cache. With a `max_age` of zero, the cache is cleared
+/// completely and the [statistics](cache_stats) are reset.
+pub fn evict(max_age: usize) {
+    comemo::evict(max_age);
+    if max_age == 0 {
+        MEMO_COUNTERS.reset();
+    }
+}
+
+/// Wraps a world and replaces its main file.
+struct MainWorld<'a> {
+    world: &'a dyn World,
+    main: FileId,
+}
+
+impl World for MainWorld<'_> {
+    fn library(&self) -> &LazyHash<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> FileId {
+        self.main
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.world.source(id)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.world.file(id)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        self.world.modified(id)
+    }
+
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        self.world.package_files(spec)
+    }
+
+    fn rasterize_svg(&self, svg: &SvgImage, size: Axes<u32>) -> StrResult<RasterImage> {
+        self.world.rasterize_svg(svg, size)
+    }
+}
@@ -0,0 +1,296 @@
+//! Comparison of the output of different export backends.
+
+use ecow::eco_format;
+use resvg::usvg;
+use tiny_skia as sk;
+use typst_library::diag::{bail, At, SourceResult, StrResult};
+use typst_library::layout::{Abs, Page, PagedDocument, Point};
+use typst_syntax::Span;
+
+use crate::World;
+
+/// Compiles the world's main file and compares how the export backends
+/// display each of its pages.
+///
+/// Every page is rasterized directly with `typst-render` and, as a second
+/// opinion, exported to SVG with `typst-svg` and rasterized with `resvg`.
+/// Things that are visible in one of the images, but not in the other, point
+/// to a bug in one of the backends.
+///
+/// This is a diagnostic for investigating such bugs, not a check for pixel
+/// perfect equality: The rasterizers antialias differently, so the result
+/// always contains some small differences. Use [`BackendDiff::exceeds`] to
+/// decide which differences matter. If the document is already compiled, use
+/// [`compare_document`] instead, which also configures how pixels are
+/// compared.
+///
+/// This is only available with the `compare` feature.
+pub fn compare_backends(
+    world: &dyn World,
+    pixel_per_pt: f32,
+) -> SourceResult<Vec<BackendDiff>> {
+    let document = crate::compile::<PagedDocument>(world).output?;
+    let options = CompareOptions { pixel_per_pt, ..Default::default() };
+    compare_document(&document, &options).at(Span::detached())
+}
+
+/// Compares how the export backends display each page of a document.
+///
+/// See [`compare_backends`] for more details.
+#[typst_macros::time(name = "compare backends")]
+pub fn compare_document(
+    document: &PagedDocument,
+    options: &CompareOptions,
+) -> StrResult<Vec<BackendDiff>> {
+    document
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| compare_page(i, page, options))
+        .collect()
+}
+
+/// Compares two rasterizations of the page with the given index.
+///
+/// Both images are composited onto white before they are compared, as the
+/// backends treat the default page fill differently. Pixels that exist in
+/// only one of the images count as differing.
+pub fn compare_pixmaps(
+    page: usize,
+    reference: &sk::Pixmap,
+    other: &sk::Pixmap,
+    options: &CompareOptions,
+) -> BackendDiff {
+    let width = reference.width().max(other.width());
+    let height = reference.height().max(other.height());
+
+    // Differing pixels are grouped into square cells, so that nearby
+    // differences end up in the same region.
+    let cell = (options.merge_distance * options.pixel_per_pt).round().max(1.0) as u32;
+    let columns = width.div_ceil(cell) as usize;
+    let rows = height.div_ceil(cell) as usize;
+    let mut cells: Vec<Option<Bounds>> = vec![None; columns * rows];
+
+    let mut differing = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let a = reference.pixel(x, y).map(on_white);
+            let b = other.pixel(x, y).map(on_white);
+            let same = a.zip(b).is_some_and(|(a, b)| {
+                a.iter().zip(b).all(|(a, b)| a.abs_diff(b) <= options.tolerance)
+            });
+
+            if !same {
+                differing += 1;
+                let index = (y / cell) as usize * columns + (x / cell) as usize;
+                cells[index].get_or_insert(Bounds::new(x, y)).include(x, y);
+            }
+        }
+    }
+
+    let regions = merge_cells(&mut cells, columns)
+        .into_iter()
+        .map(|bounds| bounds.to_region(options.pixel_per_pt))
+        .collect();
+
+    BackendDiff {
+        page,
+        pixels: width as usize * height as usize,
+        differing,
+        regions,
+    }
+}
+
+/// Options for comparing the output of export backends.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CompareOptions {
+    /// The resolution at which the pages are rasterized.
+    pub pixel_per_pt: f32,
+    /// By how much the color channels of a pixel may differ between the
+    /// images before the pixel counts as differing.
+    pub tolerance: u8,
+    /// The distance in points up to which differing pixels are reported as
+    /// part of the same region.
+    pub merge_distance: f32,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            pixel_per_pt: 2.0,
+            tolerance: 64,
+            merge_distance: 4.0,
+        }
+    }
+}
+
+/// The differences between the backends on one page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendDiff {
+    /// The zero-based index of the page.
+    pub page: usize,
+    /// The number of compared pixels.
+    pub pixels: usize,
+    /// The number of pixels that differ by more than the tolerance.
+    pub differing: usize,
+    /// The bounding boxes of the areas with differing pixels.
+    pub regions: Vec<DiffRegion>,
+}
+
+impl BackendDiff {
+    /// The fraction of the compared pixels that differ.
+    pub fn ratio(&self) -> f64 {
+        if self.pixels == 0 {
+            return 0.0;
+        }
+        self.differing as f64 / self.pixels as f64
+    }
+
+    /// Whether more than the given fraction of the pixels differ.
+    pub fn exceeds(&self, threshold: f64) -> bool {
+        self.ratio() > threshold
+    }
+}
+
+/// A rectangular area of a page, in points from the top-left corner.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DiffRegion {
+    /// The top-left corner of the area.
+    pub min: Point,
+    /// The bottom-right corner of the area.
+    pub max: Point,
+}
+
+/// Rasterizes a page directly and through SVG and compares the results.
+fn compare_page(
+    index: usize,
+    page: &Page,
+    options: &CompareOptions,
+) -> StrResult<BackendDiff> {
+    let direct = typst_render::render(page, options.pixel_per_pt);
+    let svg = rasterize_svg(&typst_svg::svg(page), direct.width(), direct.height())
+        .map_err(|err| eco_format!("page {}: {err}", index + 1))?;
+    Ok(compare_pixmaps(index, &direct, &svg, options))
+}
+
+/// Rasterizes an SVG into an image of the given size.
+///
+/// If the SVG cannot be parsed, the image stays empty, which shows up as a
+/// difference everywhere the page has content.
+fn rasterize_svg(svg: &str, width: u32, height: u32) -> StrResult<sk::Pixmap> {
+    let Some(mut pixmap) = sk::Pixmap::new(width, height) else {
+        bail!("cannot rasterize an image of {width}x{height} pixels");
+    };
+    if let Ok(tree) = usvg::Tree::from_str(svg, &usvg::Options::default()) {
+        let ts = sk::Transform::from_scale(
+            width as f32 / tree.size().width(),
+            height as f32 / tree.size().height(),
+        );
+        resvg::render(&tree, ts, &mut pixmap.as_mut());
+    }
+    Ok(pixmap)
+}
+
+/// Composites a premultiplied pixel onto white.
+fn on_white(pixel: sk::PremultipliedColorU8) -> [u8; 3] {
+    let white = 255 - pixel.alpha();
+    [pixel.red(), pixel.green(), pixel.blue()].map(|c| c.saturating_add(white))
+}
+
+/// Merges the bounds of adjacent cells, including diagonally adjacent ones.
+fn merge_cells(cells: &mut [Option<Bounds>], columns: usize) -> Vec<Bounds> {
+    let rows = cells.len() / columns.max(1);
+    let mut merged = vec![];
+    for start in 0..cells.len() {
+        let Some(mut bounds) = cells[start].take() else { continue };
+        let mut stack = vec![start];
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % columns, index / columns);
+            for ny in y.saturating_sub(1)..=(y + 1).min(rows - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(columns - 1) {
+                    let neighbour = ny * columns + nx;
+                    if let Some(other) = cells[neighbour].take() {
+                        bounds.merge(other);
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        merged.push(bounds);
+    }
+    merged
+}
+
+/// The pixel bounds of some differing pixels, all inclusive.
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: (u32, u32),
+    max: (u32, u32),
+}
+
+impl Bounds {
+    fn new(x: u32, y: u32) -> Self {
+        Self { min: (x, y), max: (x, y) }
+    }
+
+    fn include(&mut self, x: u32, y: u32) {
+        self.merge(Self::new(x, y));
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.min = (self.min.0.min(other.min.0), self.min.1.min(other.min.1));
+        self.max = (self.max.0.max(other.max.0), self.max.1.max(other.max.1));
+    }
+
+    fn to_region(self, pixel_per_pt: f32) -> DiffRegion {
+        let pt = |px: u32| Abs::pt(px as f64 / pixel_per_pt as f64);
+        DiffRegion {
+            min: Point::new(pt(self.min.0), pt(self.min.1)),
+            max: Point::new(pt(self.max.0 + 1), pt(self.max.1 + 1)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestWorld;
+
+    const TEXT: &str = "#set page(width: 100pt, height: 60pt, fill: aqua)\n\
+                        #rect(fill: red, width: 40pt, height: 20pt)\n\
+                        Hello";
+
+    #[test]
+    fn test_compare_backends_agree() {
+        let world = TestWorld::new(TEXT);
+        let diffs = compare_backends(&world, 2.0).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert!(!diffs[0].exceeds(0.01));
+    }
+
+    #[test]
+    fn test_compare_pixmaps_flags_divergence() {
+        let world = TestWorld::new(TEXT);
+        let document = crate::compile::<PagedDocument>(&world).output.unwrap();
+        let options = CompareOptions::default();
+
+        // Paint something that is only visible in one of the images.
+        let reference = typst_render::render(&document.pages[0], options.pixel_per_pt);
+        let mut other = reference.clone();
+        let mut paint = sk::Paint::default();
+        paint.set_color_rgba8(0, 0, 0, 255);
+        let rect = sk::Rect::from_xywh(120.0, 60.0, 40.0, 20.0).unwrap();
+        other.fill_rect(rect, &paint, sk::Transform::identity(), None);
+
+        let diff = compare_pixmaps(0, &reference, &other, &options);
+        assert!(diff.exceeds(0.01));
+        assert_eq!(diff.differing, 800);
+        assert_eq!(
+            diff.regions,
+            [DiffRegion {
+                min: Point::new(Abs::pt(60.0), Abs::pt(30.0)),
+                max: Point::new(Abs::pt(80.0), Abs::pt(40.0)),
+            }]
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! Semantic comparison of two compiled documents.
+
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+use typst_library::foundations::{Content, Element, NativeElement};
+use typst_library::introspection::{Location, Tag};
+use typst_library::layout::{Abs, Frame, FrameItem, PagedDocument, Point};
+use typst_library::math::EquationElem;
+use typst_library::model::{FigureElem, FootnoteElem, HeadingElem, ParElem};
+
+/// Compares two revisions of a document and lists the elements that were
+/// added, removed, modified, or moved.
+///
+/// Elements are matched across revisions by their kind and plain text and, if
+/// that is ambiguous, by their location. Elements that could not be matched
+/// exactly are paired up with an unmatched element of the same kind between
+/// the same unchanged neighbours, which makes them modified.
+///
+/// Only paragraphs, headings, figures, equations, and footnotes are compared,
+/// by their laid-out text. Such elements nested in another one (e.g. a
+/// paragraph in a figure's caption) count as part of it. Paragraphs are only
+/// compared if both documents were compiled with
+/// [`locate_paragraphs`](crate::CompileOptions::locate_paragraphs).
+///
+/// The result is deterministic: Changes are listed in the order of the new
+/// revision, followed by removals in the order of the old revision.
+#[typst_macros::time]
+pub fn diff(old: &PagedDocument, new: &PagedDocument) -> DocumentDiff {
+    let old = units(old);
+    let new = units(new);
+
+    // Match elements whose content is unchanged, preferring those that also
+    // kept their location.
+    let mut old_match = vec![None; old.len()];
+    let mut new_match = vec![None; new.len()];
+    for pass in [true, false] {
+        for (j, unit) in new.iter().enumerate() {
+            if new_match[j].is_some() {
+                continue;
+            }
+            let found = old.iter().enumerate().position(|(i, other)| {
+                old_match[i].is_none()
+                    && other.elem == unit.elem
+                    && other.text == unit.text
+                    && (!pass || other.location == unit.location)
+            });
+            if let Some(i) = found {
+                old_match[i] = Some(j);
+                new_match[j] = Some(i);
+            }
+        }
+    }
+
+    // Exact matches that keep their relative order are unchanged, all others
+    // were moved.
+    let pairs: Vec<(usize, usize)> = new_match
+        .iter()
+        .enumerate()
+        .filter_map(|(j, i)| i.map(|i| (i, j)))
+        .collect();
+    let stable = longest_increasing(&pairs);
+
+    let mut changes = vec![];
+    let mut modified = vec![false; old.len()];
+    let mut anchor = 0;
+    let mut next_stable = stable.iter().peekable();
+    for (j, unit) in new.iter().enumerate() {
+        match new_match[j] {
+            Some(i) => {
+                if next_stable.peek().is_some_and(|&&(_, sj)| sj == j) {
+                    next_stable.next();
+                    anchor = i + 1;
+                } else {
+                    changes.push(Change::new(ChangeKind::Moved, Some(&old[i]), unit));
+                }
+            }
+            None => {
+                // Look for an unmatched old element of the same kind before
+                // the next unchanged one.
+                let end = next_stable.peek().map_or(old.len(), |&&(i, _)| i);
+                let candidate = (anchor..end).find(|&i| {
+                    old_match[i].is_none() && !modified[i] && old[i].elem == unit.elem
+                });
+                match candidate {
+                    Some(i) => {
+                        modified[i] = true;
+                        anchor = i + 1;
+                        changes.push(Change::new(
+                            ChangeKind::Modified,
+                            Some(&old[i]),
+                            unit,
+                        ));
+                    }
+                    None => changes.push(Change::new(ChangeKind::Added, None, unit)),
+                }
+            }
+        }
+    }
+
+    for (i, unit) in old.iter().enumerate() {
+        if old_match[i].is_none() && !modified[i] {
+            changes.push(Change {
+                kind: ChangeKind::Removed,
+                elem: unit.elem,
+                summary: summarize(&unit.text),
+                old_page: Some(unit.page),
+                new_page: None,
+            });
+        }
+    }
+
+    DocumentDiff { changes }
+}
+
+/// The semantic changes between two revisions of a document.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct DocumentDiff {
+    /// The individual changes.
+    pub changes: Vec<Change>,
+}
+
+impl DocumentDiff {
+    /// Whether the revisions are semantically identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// A change to a single element.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Change {
+    /// How the element changed.
+    pub kind: ChangeKind,
+    /// The kind of element that changed.
+    pub elem: Element,
+    /// A short plain-text summary of the element in the new revision (or in
+    /// the old one if it was removed).
+    pub summary: EcoString,
+    /// The page the element is on in the old revision, if it exists there.
+    pub old_page: Option<NonZeroUsize>,
+    /// The page the element is on in the new revision, if it exists there.
+    pub new_page: Option<NonZeroUsize>,
+}
+
+impl Change {
+    fn new(kind: ChangeKind, old: Option<&Unit>, new: &Unit) -> Self {
+        Self {
+            kind,
+            elem: new.elem,
+            summary: summarize(&new.text),
+            old_page: old.map(|unit| unit.page),
+            new_page: Some(new.page),
+        }
+    }
+}
+
+/// How an element changed between two revisions.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ChangeKind {
+    /// The element only exists in the new revision.
+    Added,
+    /// The element only exists in the old revision.
+    Removed,
+    /// The element exists in both revisions, but its content changed.
+    Modified,
+    /// The element is unchanged, but its position relative to the other
+    /// elements changed.
+    Moved,
+}
+
+/// A comparable element of a document.
+struct Unit {
+    elem: Element,
+    location: Option<Location>,
+    text: EcoString,
+    page: NonZeroUsize,
+}
+
+/// Collects the comparable elements of a document in layout order.
+fn units(document: &PagedDocument) -> Vec<Unit> {
+    let mut collector = Collector { units: vec![], open: vec![], last_y: None };
+    for (i, page) in document.pages.iter().enumerate() {
+        let page_number = NonZeroUsize::new(i + 1).unwrap();
+        collector.last_y = None;
+        collector.frame(&page.frame, Point::zero(), page_number);
+    }
+    collector.units
+}
+
+/// Collects the comparable elements and their laid-out text.
+struct Collector {
+    units: Vec<Unit>,
+    /// The locations of the comparable elements whose end tag is still
+    /// outstanding.
+    open: Vec<Location>,
+    /// The vertical position of the last text that was added to a unit, to
+    /// separate lines with a space.
+    last_y: Option<Abs>,
+}
+
+impl Collector {
+    fn frame(&mut self, frame: &Frame, offset: Point, page: NonZeroUsize) {
+        for (pos, item) in frame.items() {
+            let pos = offset + *pos;
+            match item {
+                FrameItem::Group(group) => self.frame(&group.frame, pos, page),
+                FrameItem::Tag(Tag::Start(elem)) if is_comparable(elem) => {
+                    let location = elem.location();
+                    if self.open.is_empty() {
+                        self.units.push(Unit {
+                            elem: elem.elem(),
+                            location,
+                            text: EcoString::new(),
+                            page,
+                        });
+                        self.last_y = None;
+                    }
+                    self.open.extend(location);
+                }
+                FrameItem::Tag(Tag::End(location, _)) => {
+                    if let Some(i) = self.open.iter().rposition(|l| l == location) {
+                        self.open.truncate(i);
+                    }
+                }
+                FrameItem::Text(text) if !self.open.is_empty() => {
+                    let Some(unit) = self.units.last_mut() else { continue };
+                    if self.last_y.is_some_and(|y| y != pos.y) {
+                        unit.text.push(' ');
+                    }
+                    unit.text.push_str(&text.text);
+                    self.last_y = Some(pos.y);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Whether changes to the element are reported.
+fn is_comparable(elem: &Content) -> bool {
+    [
+        ParElem::elem(),
+        HeadingElem::elem(),
+        FigureElem::elem(),
+        EquationElem::elem(),
+        FootnoteElem::elem(),
+    ]
+    .contains(&elem.elem())
+}
+
+/// Shortens a plain text to a summary of at most a few words.
+fn summarize(text: &str) -> EcoString {
+    const MAX_CHARS: usize = 40;
+    let text = text.trim();
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((i, _)) => eco_format!("{}…", text[..i].trim_end()),
+        None => text.into(),
+    }
+}
+
+/// Finds the longest subsequence of `(old, new)` index pairs, which are sorted
+/// by `new`, whose `old` indices are increasing as well.
+fn longest_increasing(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // Patience sorting: `tails[k]` is the index of the smallest possible last
+    // pair of an increasing subsequence of length `k + 1`.
+    let mut tails: Vec<usize> = vec![];
+    let mut prev = vec![None; pairs.len()];
+    for (k, &(i, _)) in pairs.iter().enumerate() {
+        let pos = tails.partition_point(|&t| pairs[t].0 < i);
+        if pos > 0 {
+            prev[k] = Some(tails[pos - 1]);
+        }
+        if pos == tails.len() {
+            tails.push(k);
+        } else {
+            tails[pos] = k;
+        }
+    }
+
+    let mut result = vec![];
+    let mut cursor = tails.last().copied();
+    while let Some(k) = cursor {
+        result.push(pairs[k]);
+        cursor = prev[k];
+    }
+    result.reverse();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestWorld;
+    use crate::CompileOptions;
+
+    const TEXT: &str = "#set page(height: 150pt)\n\
+                        = Introduction\n\
+                        First paragraph.\n\
+                        \n\
+                        #pagebreak()\n\
+                        Second paragraph.";
+
+    /// Compiles a document with locatable paragraphs.
+    fn compile(text: &str) -> PagedDocument {
+        let options = CompileOptions { locate_paragraphs: true, ..Default::default() };
+        let world = TestWorld::new(text);
+        crate::compile_with_options(&world, &options).0.output.unwrap()
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        assert!(diff(&compile(TEXT), &compile(TEXT)).is_empty());
+    }
+
+    #[test]
+    fn test_diff_edited() {
+        let edited = TEXT.replace(
+            "Second paragraph.",
+            "Second paragraph, edited.\n\n#figure(rect(), caption: [New])",
+        );
+        let changes: Vec<_> = diff(&compile(TEXT), &compile(&edited))
+            .changes
+            .into_iter()
+            .map(|change| {
+                let page = |page: Option<NonZeroUsize>| page.map(NonZeroUsize::get);
+                (
+                    change.kind,
+                    change.elem.name(),
+                    page(change.old_page),
+                    page(change.new_page),
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (ChangeKind::Modified, "par", Some(2), Some(2)),
+                (ChangeKind::Added, "figure", None, Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        assert_eq!(summarize("  Short text. "), "Short text.");
+        assert_eq!(
+            summarize(&"word ".repeat(20)),
+            "word word word word word word word word…"
+        );
+    }
+
+    #[test]
+    fn test_longest_increasing() {
+        let pairs = [(3, 0), (0, 1), (4, 2), (1, 3), (2, 4)];
+        assert_eq!(longest_increasing(&pairs), [(0, 1), (1, 3), (2, 4)]);
+        assert!(longest_increasing(&[]).is_empty());
+    }
+}
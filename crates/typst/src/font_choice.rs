@@ -0,0 +1,119 @@
+//! Explanations of the font that is chosen for a character.
+
+use comemo::Track;
+use typst_library::foundations::{StyleChain, Styles};
+use typst_library::text::FontChoiceExplanation;
+use typst_library::World;
+
+/// Explains which font is chosen for a character and why the fonts before it
+/// in the font list and the fallback chain are skipped.
+///
+/// The `styles` are applied on top of the styles of the world's library, like
+/// set rules at the start of the document. This runs the same font selection
+/// as layout does, so that the explanation matches the font that text
+/// actually uses. It does not depend on the `font-debug` feature, which only
+/// gates the `text.explain` function in Typst code.
+pub fn explain_font_choice(
+    world: &dyn World,
+    c: char,
+    styles: &Styles,
+) -> FontChoiceExplanation {
+    let world = world.track();
+    let library = world.library();
+    let base = StyleChain::new(&library.styles);
+    typst_library::text::explain_font_choice(world, base.chain(styles), c)
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::layout::{Frame, FrameItem, PagedDocument};
+    use typst_library::text::{Font, FontFamily, FontList, FontWeight, TextElem};
+    use typst_library::Feature;
+
+    use super::*;
+    use crate::testing::TestWorld;
+
+    #[test]
+    fn test_explain_font_choice() {
+        let world = TestWorld::new("");
+        let c = fallback_char(&world);
+
+        let mut styles = Styles::new();
+        styles.set(TextElem::set_font(FontList(vec![
+            FontFamily::new("Nonexistent"),
+            FontFamily::new("DejaVu Sans Mono"),
+        ])));
+        styles.set(TextElem::set_weight(FontWeight::from_number(900)));
+
+        let explanation = explain_font_choice(&world, c, &styles);
+        let (last, rest) = explanation.candidates.split_last().unwrap();
+
+        // The missing family is reported, but never loaded.
+        assert_eq!(rest[0].family.as_str(), "nonexistent");
+        assert!(!rest[0].found);
+        assert!(rest[0].font.is_none());
+
+        // The listed family and the default family don't have the glyph.
+        assert_eq!(rest[1].family.as_str(), "dejavu sans mono");
+        assert!(rest[1].found);
+        assert!(!rest[1].covers);
+        assert_eq!(rest[2].family.as_str(), "libertinus serif");
+        assert!(rest[2].found);
+        assert!(!rest[2].exact_variant);
+        assert!(!rest[2].covers);
+        assert!(!rest[..3].iter().any(|c| c.selected || c.fallback));
+
+        // The automatic fallback finds a font with the glyph.
+        assert!(last.fallback);
+        assert!(last.covers);
+        assert!(last.selected);
+
+        // The explanation agrees with the font that layout picks.
+        let world = TestWorld::new(&format!(
+            "#set text(font: (\"Nonexistent\", \"DejaVu Sans Mono\"), weight: 900)\n{c}"
+        ));
+        let document = crate::compile::<PagedDocument>(&world).output.unwrap();
+        let laid_out = first_text_font(&document.pages[0].frame);
+        assert!(laid_out.is_some());
+        assert_eq!(explanation.selection, laid_out);
+    }
+
+    #[test]
+    fn test_explain_font_choice_in_typst() {
+        let code = "#context test(text.explain(\"a\").last().selected, true)";
+        let world = TestWorld::new(code);
+        assert!(crate::compile::<PagedDocument>(&world).output.is_err());
+        let world = world.with_features(&[Feature::FontDebug]);
+        assert!(crate::compile::<PagedDocument>(&world).output.is_ok());
+    }
+
+    /// Finds a letter that neither DejaVu Sans Mono nor the default family
+    /// Libertinus Serif covers, but another font does.
+    fn fallback_char(world: &TestWorld) -> char {
+        let book = world.book();
+        let infos: Vec<_> = (0..).map_while(|i| book.info(i)).collect();
+        let covers = |family: &str, c: char| {
+            infos.iter().any(|info| {
+                info.family.eq_ignore_ascii_case(family)
+                    && info.coverage.contains(c as u32)
+            })
+        };
+        ('\u{80}'..='\u{FFFF}')
+            .find(|&c| {
+                c.is_alphabetic()
+                    && !covers("DejaVu Sans Mono", c)
+                    && !covers("Libertinus Serif", c)
+                    && infos.iter().any(|info| info.coverage.contains(c as u32))
+            })
+            .unwrap()
+    }
+
+    /// The font of the first text item in a frame.
+    fn first_text_font(frame: &Frame) -> Option<Font> {
+        frame.items().find_map(|(_, item)| match item {
+            FrameItem::Group(group) => first_text_font(&group.frame),
+            FrameItem::Text(text) => Some(text.font.clone()),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+//! Positions and source text of all glyphs in a document.
+
+use ecow::{eco_format, EcoString};
+use serde::Serialize;
+use typst_library::layout::{Frame, FrameItem, PagedDocument, Point, Transform};
+use typst_library::text::{Font, TextItem};
+use typst_syntax::Span;
+
+/// Lists every glyph of a laid-out document, page by page.
+///
+/// This is meant for external tools that need to relate the printed output
+/// to the source, e.g. to align OCR results of a printed proof with the text
+/// that produced it. Within a page, the glyphs are listed in the order in
+/// which they appear in the page's frames. Within a run of text, this is the
+/// visual order, so the glyphs of right-to-left text are listed from right to
+/// left.
+///
+/// The format of the returned records, including their serialized form, is
+/// stable within a minor version.
+#[typst_macros::time]
+pub fn glyph_inventory(document: &PagedDocument) -> Vec<PageGlyphs> {
+    document
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(page, p)| {
+            let mut glyphs = vec![];
+            collect(&mut glyphs, &p.frame, Transform::identity());
+            PageGlyphs { page, glyphs }
+        })
+        .collect()
+}
+
+/// The glyphs of one page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageGlyphs {
+    /// The zero-based index of the page.
+    pub page: usize,
+    /// The glyphs on the page.
+    pub glyphs: Vec<GlyphRecord>,
+}
+
+/// A single glyph on a page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GlyphRecord {
+    /// The horizontal position of the glyph's origin, in points from the left
+    /// edge of the page.
+    pub x: f64,
+    /// The vertical position of the glyph's origin on the baseline, in points
+    /// from the top edge of the page.
+    pub y: f64,
+    /// The advance width of the glyph in points, before any transformations
+    /// of the text.
+    pub advance: f64,
+    /// The font size in points.
+    pub size: f64,
+    /// The font the glyph is taken from.
+    pub font: FontRecord,
+    /// The glyph's index in the font.
+    pub glyph: u16,
+    /// The source text that this glyph represents.
+    ///
+    /// For a ligature, this is all the text that the ligature stands for. If a
+    /// piece of text is represented by multiple glyphs, the first of them
+    /// carries the text and the others have an empty one. Thus, the text of
+    /// all records of a page concatenates to the page's text.
+    pub text: EcoString,
+    /// The span of the source code that produced the text and the offset of
+    /// the text within the span.
+    ///
+    /// This is not serialized, as it is only meaningful together with the
+    /// `World` that the document was compiled in.
+    #[serde(skip)]
+    pub span: (Span, u16),
+}
+
+/// Identifies the font of a glyph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FontRecord {
+    /// The font's family name.
+    pub family: EcoString,
+    /// A hexadecimal hash of the font's data, its index in a collection, and
+    /// its variation settings. Glyphs from the same font have the same
+    /// fingerprint.
+    pub fingerprint: EcoString,
+}
+
+impl FontRecord {
+    fn new(font: &Font) -> Self {
+        Self {
+            family: font.info().family.as_str().into(),
+            fingerprint: eco_format!("{:032x}", typst_utils::hash128(font)),
+        }
+    }
+}
+
+/// Collects the glyphs of a frame.
+fn collect(sink: &mut Vec<GlyphRecord>, frame: &Frame, ts: Transform) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                collect(sink, &group.frame, ts);
+            }
+            FrameItem::Text(text) => collect_text(sink, text, *pos, ts),
+            _ => {}
+        }
+    }
+}
+
+/// Collects the glyphs of a text run placed at `pos`.
+fn collect_text(sink: &mut Vec<GlyphRecord>, text: &TextItem, pos: Point, ts: Transform) {
+    let font = FontRecord::new(&text.font);
+    let mut x = pos.x;
+    let mut prev = None;
+    for glyph in &text.glyphs {
+        let origin = Point::new(x + glyph.x_offset.at(text.size), pos.y).transform(ts);
+        let range = glyph.range();
+        let text_of_glyph = if prev.as_ref() == Some(&range) {
+            EcoString::new()
+        } else {
+            text.text[range.clone()].into()
+        };
+
+        sink.push(GlyphRecord {
+            x: origin.x.to_pt(),
+            y: origin.y.to_pt(),
+            advance: glyph.x_advance.at(text.size).to_pt(),
+            size: text.size.to_pt(),
+            font: font.clone(),
+            glyph: glyph.id,
+            text: text_of_glyph,
+            span: glyph.span,
+        });
+
+        x += glyph.x_advance.at(text.size);
+        prev = Some(range);
+    }
+}
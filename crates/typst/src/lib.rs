@@ -39,24 +39,65 @@ pub use typst_syntax as syntax;
 #[doc(inline)]
 pub use typst_utils as utils;
 
-use std::collections::HashSet;
+mod analyze;
+mod cache;
+#[cfg(feature = "compare")]
+mod compare;
+mod diff;
+mod font_choice;
+mod glyphs;
+mod panic;
+mod session;
+#[cfg(any(test, feature = "testing"))]
+#[doc(hidden)]
+pub mod testing;
+
+pub use self::analyze::{analyze, Analysis, HeadingSummary};
+pub use self::cache::{
+    cache_stats, count_cache_additions, enable_cache_stats, evict, prewarm, CacheStats,
+};
+#[cfg(feature = "compare")]
+pub use self::compare::{
+    compare_backends, compare_document, compare_pixmaps, BackendDiff, CompareOptions,
+    DiffRegion,
+};
+pub use self::diff::{diff, Change, ChangeKind, DocumentDiff};
+pub use self::font_choice::explain_font_choice;
+pub use self::glyphs::{glyph_inventory, FontRecord, GlyphRecord, PageGlyphs};
+pub use self::panic::{install_panic_context, take_panic_context, PanicContext};
+pub use self::session::{CompilationSession, SessionStats};
+
+use std::collections::{HashMap, HashSet};
+use std::num::{NonZeroU16, NonZeroUsize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 
 use comemo::{Track, Tracked, Validate};
 use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use typst_library::diag::{
-    bail, warning, FileError, SourceDiagnostic, SourceResult, Warned,
+    bail, warning, DeprecationPolicy, ErrorMode, FileError, FileResult, Severity,
+    SourceDiagnostic, SourceResult, StrResult, Tracepoint, Warned,
 };
-use typst_library::engine::{Engine, Route, Sink, Traced};
-use typst_library::foundations::{StyleChain, Styles, Value};
-use typst_library::html::HtmlDocument;
-use typst_library::introspection::Introspector;
-use typst_library::layout::PagedDocument;
+use typst_library::engine::{CompileStats, Engine, Route, Sink, Traced};
+use typst_library::foundations::{
+    Bytes, Content, ContextElem, Datetime, Label, NativeElement, Repr, Selector, Smart,
+    StyleChain, Styles, Value,
+};
+use typst_library::html::{HtmlDocument, HtmlNode};
+use typst_library::introspection::{Introspector, MetadataElem};
+use typst_library::layout::{Axes, Frame, FrameItem, PagedDocument};
+use typst_library::package::PackageLock;
 use typst_library::routines::Routines;
-use typst_syntax::{FileId, Span};
+use typst_library::text::{Font, FontBook};
+use typst_library::visualize::{ImageKind, RasterImage, SvgImage};
+use typst_syntax::package::PackageSpec;
+use typst_syntax::{FileId, Source, Span, Spanned, SyntaxKind, SyntaxNode};
 use typst_timing::{timed, TimingScope};
+use typst_utils::LazyHash;
 
 use crate::foundations::{Target, TargetElem};
-use crate::model::DocumentInfo;
+use crate::model::{DocumentInfo, LabelPolicy, NearDuplicates, ParElem};
+use crate::panic::with_panic_context;
 
 /// Compile sources into a fully layouted document.
 ///
@@ -64,15 +105,207 @@ use crate::model::DocumentInfo;
 /// - Returns `Err(errors)` if there were fatal errors.
 #[typst_macros::time]
 pub fn compile<D>(world: &dyn World) -> Warned<SourceResult<D>>
+where
+    D: Document,
+{
+    compile_with_stats(world).0
+}
+
+/// Compile sources into a fully layouted document and collect statistics about
+/// the work that was performed.
+///
+/// The statistics are approximate, see [`CompileStats`] for details. They are
+/// also available if compilation fails, but then only cover the work performed
+/// up to the failure.
+#[typst_macros::time]
+pub fn compile_with_stats<D>(world: &dyn World) -> (Warned<SourceResult<D>>, CompileStats)
+where
+    D: Document,
+{
+    compile_with_options(world, &CompileOptions::default())
+}
+
+/// Compile sources with additional options and collect statistics about the
+/// work that was performed.
+///
+/// See [`CompileOptions`] for the available options and [`compile_with_stats`]
+/// for details on the statistics.
+#[typst_macros::time]
+pub fn compile_with_options<D>(
+    world: &dyn World,
+    options: &CompileOptions,
+) -> (Warned<SourceResult<D>>, CompileStats)
+where
+    D: Document,
+{
+    compile_with_previous(world, options, None)
+}
+
+/// Recompile a paged document after an edit, reusing work from the result of
+/// a previous compilation of it.
+///
+/// When a document is recompiled after an edit, as in watch mode, most of its
+/// pages are typically unchanged. Only the pages that differ from the
+/// `previous` document's pages are searched for introspectable elements, while
+/// the elements found on the other pages are reused. The resulting document is
+/// the same as the one [`compile_with_options`] produces.
+///
+/// Only documents produced by this function keep what is needed for reuse.
+/// Thus, if the `previous` document stems from [`compile_with_options`], all
+/// pages are searched once and subsequent recompilations benefit.
+#[typst_macros::time]
+pub fn recompile_with_options(
+    world: &dyn World,
+    options: &CompileOptions,
+    previous: &PagedDocument,
+) -> (Warned<SourceResult<PagedDocument>>, CompileStats) {
+    compile_with_previous(world, options, Some(previous))
+}
+
+/// The shared implementation of [`compile_with_options`] and
+/// [`recompile_with_options`].
+fn compile_with_previous<D: Document>(
+    world: &dyn World,
+    options: &CompileOptions,
+    previous: Option<&D>,
+) -> (Warned<SourceResult<D>>, CompileStats) {
+    let mut sink = Sink::new();
+    let recorder = Recorder::new(world, options.package_lock.as_ref());
+    let traced = Traced::default();
+    let mut output = with_panic_context(world, || {
+        compile_impl::<D>(&recorder, traced.track(), &mut sink, options, previous)
+    })
+    .map_err(deduplicate);
+    let errors = apply_deprecation_policy(&mut sink, options);
+    if !errors.is_empty() {
+        output = Err(match output {
+            Ok(_) => errors,
+            Err(mut prior) => {
+                prior.extend(errors);
+                prior
+            }
+        });
+    }
+    let stats = sink.stats();
+    let limit = options.max_warnings_per_code.unwrap_or(Sink::MAX_WARNINGS_PER_CODE);
+    (Warned { output, warnings: sink.warnings_with_limit(limit) }, stats)
+}
+
+/// Lay out content that was constructed in Rust into a document.
+///
+/// The content is laid out in the same way as the content of an evaluated
+/// main file, with the world providing fonts, files, and the standard library.
+/// The world's main file is not evaluated. See the [`ContentBuilder`] for a
+/// convenient way to construct content.
+///
+/// - Returns `Ok(document)` if there were no fatal errors.
+/// - Returns `Err(errors)` if there were fatal errors.
+///
+/// [`ContentBuilder`]: crate::foundations::ContentBuilder
+#[typst_macros::time]
+pub fn compile_content<D>(world: &dyn World, content: &Content) -> Warned<SourceResult<D>>
 where
     D: Document,
 {
     let mut sink = Sink::new();
-    let output = compile_impl::<D>(world.track(), Traced::default().track(), &mut sink)
-        .map_err(deduplicate);
+    let recorder = Recorder::new(world, None);
+    let traced = Traced::default();
+    let options = CompileOptions::default();
+    let output = with_panic_context(world, || {
+        let world: &dyn World = &recorder;
+        prepare::<D>(world.track(), &mut sink).and_then(|()| {
+            layout_impl::<D>(
+                &recorder,
+                traced.track(),
+                &mut sink,
+                &options,
+                content,
+                None,
+            )
+        })
+    })
+    .map_err(deduplicate);
     Warned { output, warnings: sink.warnings() }
 }
 
+/// Options for [`compile_with_options`].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct CompileOptions {
+    /// The number of pages that are needed from a paged document.
+    ///
+    /// When only a prefix of the pages is exported, laying out the rest of the
+    /// document is wasted work. If this is set, layout stops once this many
+    /// pages are complete and the resulting document only contains these
+    /// pages. This is conservative: If anything on these pages performs
+    /// introspection that may depend on later parts of the document (e.g. a
+    /// `context` expression, an outline, or a reference), the full document
+    /// is laid out instead. Either way, the requested pages are the same as
+    /// in a full compilation.
+    ///
+    /// This has no effect on HTML documents.
+    pub max_pages: Option<NonZeroUsize>,
+    /// How evaluation deals with errors.
+    ///
+    /// With [`ErrorMode::Collect`], evaluation continues past unknown
+    /// variables and values of the wrong type in markup, so that a single
+    /// compilation reports as many independent errors as possible. The
+    /// compilation still fails if there are any errors.
+    pub error_mode: ErrorMode,
+    /// The maximum number of reported warnings of the same kind.
+    ///
+    /// Warnings that share a [code](SourceDiagnostic::code) beyond this
+    /// limit are summarized in a single warning. Defaults to
+    /// [`Sink::MAX_WARNINGS_PER_CODE`] if not set.
+    pub max_warnings_per_code: Option<usize>,
+    /// A lock that packages must match.
+    ///
+    /// If this is set, each package is verified against the lock the first
+    /// time one of its files is accessed, which requires the world to
+    /// [list the package's files](World::package_files). Accessing a file of a
+    /// package that is missing from the lock or whose contents changed fails.
+    /// A lock can be created with [`PackageLock::record`] after a successful
+    /// compilation.
+    pub package_lock: Option<PackageLock>,
+    /// How uses of deprecated definitions are reported.
+    pub deprecation_policy: DeprecationPolicy,
+    /// Whether uses of deprecated definitions that are past their removal
+    /// version are reported according to the
+    /// [`deprecation_policy`](Self::deprecation_policy).
+    ///
+    /// By default, they are errors regardless of the policy. This is an
+    /// escape hatch for projects that cannot migrate away from them yet.
+    pub allow_past_due_deprecations: bool,
+    /// Whether paragraphs receive a location.
+    ///
+    /// Paragraphs are not locatable by default, so they don't appear in the
+    /// document's introspector. Set this for documents that are compared with
+    /// [`diff`], which only reports changes to paragraphs if they are located.
+    pub locate_paragraphs: bool,
+}
+
+/// Applies the deprecation policy to the collected warnings and returns the
+/// ones that are turned into errors.
+fn apply_deprecation_policy(
+    sink: &mut Sink,
+    options: &CompileOptions,
+) -> EcoVec<SourceDiagnostic> {
+    let policy = options.deprecation_policy;
+    let mut errors = EcoVec::new();
+    sink.retain_warnings(|warning| {
+        match policy.severity(warning, options.allow_past_due_deprecations) {
+            Some(Severity::Warning) => true,
+            Some(Severity::Error) => {
+                let mut error = warning.clone();
+                error.severity = Severity::Error;
+                errors.push(error);
+                false
+            }
+            None => false,
+        }
+    });
+    errors
+}
+
 /// Compiles sources and returns all values and styles observed at the given
 /// `span` during compilation.
 #[typst_macros::time]
@@ -81,27 +314,104 @@ where
     D: Document,
 {
     let mut sink = Sink::new();
+    let recorder = Recorder::new(world, None);
     let traced = Traced::new(span);
-    compile_impl::<D>(world.track(), traced.track(), &mut sink).ok();
+    let options = CompileOptions::default();
+    compile_impl::<D>(&recorder, traced.track(), &mut sink, &options, None).ok();
     sink.values()
 }
 
+/// Extracts all labelled metadata values from the main source file without
+/// laying out the document.
+///
+/// This only evaluates the main source file and then searches the resulting
+/// content tree for [`metadata`](MetadataElem) elements with a label. It is
+/// thus much cheaper than a full compilation, but cannot see metadata that is
+/// only produced within a `context` expression, as that requires layout. For
+/// each `context` expression that mentions `metadata`, a warning is emitted
+/// instead. Use [`compile`] and query the document's introspector if such
+/// metadata matters.
+///
+/// - Returns `Ok(values)` if there were no fatal errors.
+/// - Returns `Err(errors)` if there were fatal errors.
+#[typst_macros::time]
+pub fn extract_metadata(world: &dyn World) -> Warned<SourceResult<Vec<(Label, Value)>>> {
+    let mut sink = Sink::new();
+    let output = extract_metadata_impl(world.track(), &mut sink).map_err(deduplicate);
+    Warned { output, warnings: sink.warnings() }
+}
+
+/// The internal implementation of `extract_metadata`.
+fn extract_metadata_impl(
+    world: Tracked<dyn World + '_>,
+    sink: &mut Sink,
+) -> SourceResult<Vec<(Label, Value)>> {
+    let main = world.main();
+    let main = world
+        .source(main)
+        .map_err(|err| hint_invalid_main_file(world, err, main))?;
+
+    let content = typst_eval::eval(
+        &ROUTINES,
+        world,
+        Traced::default().track(),
+        sink.track_mut(),
+        Route::default().track(),
+        &main,
+    )?
+    .content();
+
+    let selector = Selector::Or(eco_vec![
+        MetadataElem::elem().select(),
+        ContextElem::elem().select(),
+    ]);
+
+    let mut values = vec![];
+    for elem in content.query(selector) {
+        if let Some(metadata) = elem.to_packed::<MetadataElem>() {
+            if let Some(label) = elem.label() {
+                values.push((label, metadata.value.clone()));
+            }
+        } else if mentions_metadata(world, elem.span()) {
+            sink.warn(warning!(
+                elem.span(),
+                "metadata in context expressions cannot be extracted without layout";
+                hint: "compile the document and query it to access this metadata"
+            ));
+        }
+    }
+
+    Ok(values)
+}
+
+/// Whether the syntax at the span mentions `metadata`.
+///
+/// This is only a syntactical approximation: Metadata that a `context`
+/// expression produces through a function defined elsewhere is not detected.
+fn mentions_metadata(world: Tracked<dyn World + '_>, span: Span) -> bool {
+    fn visit(node: &SyntaxNode) -> bool {
+        (node.kind() == SyntaxKind::Ident && node.text() == "metadata")
+            || node.children().any(visit)
+    }
+
+    span.id()
+        .and_then(|id| world.source(id).ok())
+        .and_then(|source| source.find(span).map(|node| visit(node.get())))
+        .unwrap_or(false)
+}
+
 /// The internal implementation of `compile` with a bit lower-level interface
 /// that is also used by `trace`.
 fn compile_impl<D: Document>(
-    world: Tracked<dyn World + '_>,
+    recorder: &Recorder,
     traced: Tracked<Traced>,
     sink: &mut Sink,
+    options: &CompileOptions,
+    previous: Option<&D>,
 ) -> SourceResult<D> {
-    if D::TARGET == Target::Html {
-        warn_or_error_for_html(world, sink)?;
-    }
-
-    let library = world.library();
-    let base = StyleChain::new(&library.styles);
-    let target = TargetElem::set_target(D::TARGET).wrap();
-    let styles = base.chain(&target);
-    let empty_introspector = Introspector::default();
+    let world: &dyn World = recorder;
+    let world = world.track();
+    prepare::<D>(world, sink)?;
 
     // Fetch the main source file once.
     let main = world.main();
@@ -110,20 +420,70 @@ fn compile_impl<D: Document>(
         .map_err(|err| hint_invalid_main_file(world, err, main))?;
 
     // First evaluate the main source file into a module.
-    let content = typst_eval::eval(
+    let content = typst_eval::eval_with_mode(
         &ROUTINES,
         world,
         traced,
         sink.track_mut(),
         Route::default().track(),
         &main,
-    )?
+        options.error_mode,
+    )
+    .map_err(|errors| with_delayed(sink, errors))?
     .content();
 
+    layout_impl(recorder, traced, sink, options, &content, previous)
+}
+
+/// Emits the warnings and errors that concern the whole compilation, before
+/// anything is evaluated or laid out.
+fn prepare<D: Document>(
+    world: Tracked<dyn World + '_>,
+    sink: &mut Sink,
+) -> SourceResult<()> {
+    if D::TARGET == Target::Html {
+        warn_or_error_for_html(world, sink)?;
+    }
+
+    if world.library().features.is_enabled(Feature::FontDebug) {
+        warn_for_shadowed_fonts(world, sink);
+    }
+
+    Ok(())
+}
+
+/// Lays out content into a document, repeating layout until all
+/// introspections stabilize.
+///
+/// If a `previous` version of the document is given, each iteration reuses
+/// the introspection results of its unchanged pages.
+fn layout_impl<D: Document>(
+    recorder: &Recorder,
+    traced: Tracked<Traced>,
+    sink: &mut Sink,
+    options: &CompileOptions,
+    content: &Content,
+    previous: Option<&D>,
+) -> SourceResult<D> {
+    let world: &dyn World = recorder;
+    let world = world.track();
+
+    let library = world.library();
+    let base = StyleChain::new(&library.styles);
+    let mut target = Styles::new();
+    target.set(TargetElem::set_target(D::TARGET));
+    if options.locate_paragraphs {
+        target.set(ParElem::set_located(true));
+    }
+    let styles = base.chain(&target);
+    let empty_introspector = Introspector::default();
+
     let mut iter = 0;
+    let mut source_date = None;
+    let mut stats = CompileStats::default();
     let mut subsink;
     let mut introspector = &empty_introspector;
-    let mut document: D;
+    let mut document: Option<D> = None;
 
     // Relayout until all introspections stabilize.
     // If that doesn't happen within five attempts, we give up.
@@ -146,10 +506,21 @@ fn compile_impl<D: Document>(
         };
 
         // Layout!
-        document = D::create(&mut engine, &content, styles)?;
+        let created = D::create(&mut engine, content, styles, options, previous)
+            .map_err(|errors| with_delayed(sink, errors))?;
+        let document = document.insert(created);
         introspector = document.introspector();
         iter += 1;
 
+        // Accumulate the statistics of all iterations.
+        stats.merge(&subsink.stats());
+
+        // If requested, derive the document date from the files accessed in
+        // the first iteration that needs it. It is kept stable afterwards.
+        if source_date.is_none() && document.info().date_from_source {
+            source_date = Some(recorder.newest());
+        }
+
         if timed!("check stabilized", introspector.validate(&constraint)) {
             break;
         }
@@ -165,6 +536,22 @@ fn compile_impl<D: Document>(
 
     sink.extend_from_sink(subsink);
 
+    let mut document = document.unwrap();
+    if let Some(policy) = &document.info().label_policy {
+        warn_near_duplicate_labels(sink, document.introspector(), policy);
+    }
+
+    stats.iterations = iter;
+    stats.fonts = recorder.fonts();
+    document.count(&mut stats);
+    sink.count(stats);
+
+    if let Some(date) = source_date {
+        if document.info().date_from_source {
+            document.info_mut().date = Smart::Custom(date);
+        }
+    }
+
     // Promote delayed errors.
     let delayed = sink.delayed();
     if !delayed.is_empty() {
@@ -174,6 +561,210 @@ fn compile_impl<D: Document>(
     Ok(document)
 }
 
+/// Warns about each pair of labels that only differ in case or in underscores
+/// and hyphens, if the document's label policy asks for it.
+fn warn_near_duplicate_labels(
+    sink: &mut Sink,
+    introspector: &Introspector,
+    policy: &LabelPolicy,
+) {
+    if policy.near_duplicates != NearDuplicates::Warn {
+        return;
+    }
+
+    // The distinct labels seen so far, with the span of the first element
+    // that has them, grouped by their normalized name.
+    let mut groups: HashMap<EcoString, Vec<(Label, Span)>> = HashMap::new();
+    for elem in introspector.all() {
+        let Some(label) = elem.label() else { continue };
+        let group = groups.entry(LabelPolicy::normalize(label)).or_default();
+        if group.iter().any(|&(other, _)| other == label) {
+            continue;
+        }
+
+        for &(other, span) in group.iter() {
+            let mut warning = warning!(
+                elem.span(),
+                "label `{}` is easily confused with `{}`",
+                label.repr(),
+                other.repr();
+                hint: "labels that only differ in case or in underscores and \
+                       hyphens are easily mixed up in references"
+            );
+            warning
+                .trace
+                .push(Spanned::new(Tracepoint::SimilarLabel(other.repr()), span));
+            sink.warn(warning);
+        }
+
+        group.push((label, elem.span()));
+    }
+}
+
+/// Prepends the errors that were delayed so far to fatal errors.
+///
+/// Errors that were collected during evaluation are thus reported even if a
+/// later error aborts the compilation.
+fn with_delayed(
+    sink: &mut Sink,
+    errors: EcoVec<SourceDiagnostic>,
+) -> EcoVec<SourceDiagnostic> {
+    let mut delayed = sink.delayed();
+    delayed.extend(errors);
+    delayed
+}
+
+/// Wraps a world and records all files that are accessed through it.
+///
+/// Since comemo replays all tracked calls when validating cached results, this
+/// sees the full set of dependencies even if most of the compilation is served
+/// from the cache.
+struct Recorder<'a> {
+    world: &'a dyn World,
+    lock: Option<&'a PackageLock>,
+    accessed: AtomicBitSet,
+    fonts: AtomicBitSet,
+    verified: Mutex<HashMap<PackageSpec, FileResult<()>>>,
+}
+
+impl<'a> Recorder<'a> {
+    /// Create a new recorder for the given world that verifies packages
+    /// against the lock, if any.
+    fn new(world: &'a dyn World, lock: Option<&'a PackageLock>) -> Self {
+        Self {
+            world,
+            lock,
+            // File IDs are 16-bit, so all of them fit.
+            accessed: AtomicBitSet::new(1 << 16),
+            // Font indices point into the font book, so all of them fit.
+            fonts: AtomicBitSet::new(world.book().len()),
+            verified: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Verify the package a file belongs to against the lock. Each package is
+    /// only verified once.
+    fn verify(&self, id: FileId) -> FileResult<()> {
+        let (Some(lock), Some(spec)) = (self.lock, id.package()) else {
+            return Ok(());
+        };
+
+        self.verified
+            .lock()
+            .unwrap()
+            .entry(spec.clone())
+            .or_insert_with(|| lock.verify(self.world, spec))
+            .clone()
+    }
+
+    /// The number of distinct fonts loaded so far.
+    fn fonts(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// The most recent modification date among all files accessed so far.
+    fn newest(&self) -> Option<Datetime> {
+        let mut newest: Option<Datetime> = None;
+        for date in self
+            .accessed
+            .iter()
+            .filter_map(|raw| NonZeroU16::new(u16::try_from(raw).ok()?))
+            .filter_map(|raw| self.world.modified(FileId::from_raw(raw)))
+        {
+            if newest.map_or(true, |prev| date > prev) {
+                newest = Some(date);
+            }
+        }
+        newest
+    }
+}
+
+impl World for Recorder<'_> {
+    fn library(&self) -> &LazyHash<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> FileId {
+        self.world.main()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.accessed.insert(id.into_raw().get().into());
+        self.verify(id)?;
+        self.world.source(id)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.accessed.insert(id.into_raw().get().into());
+        self.verify(id)?;
+        self.world.file(id)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.insert(index);
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        self.world.modified(id)
+    }
+
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        self.world.package_files(spec)
+    }
+
+    fn rasterize_svg(&self, svg: &SvgImage, size: Axes<u32>) -> StrResult<RasterImage> {
+        self.world.rasterize_svg(svg, size)
+    }
+}
+
+/// A set of small integers that can be inserted into from multiple threads
+/// without locking.
+///
+/// The recorder sees every file and font access of a compilation, so it must
+/// not contend on a lock.
+struct AtomicBitSet(Box<[AtomicU64]>);
+
+impl AtomicBitSet {
+    /// Create a set that can hold the integers below `capacity`.
+    fn new(capacity: usize) -> Self {
+        Self((0..capacity.div_ceil(64)).map(|_| AtomicU64::new(0)).collect())
+    }
+
+    /// Insert an integer. Integers beyond the capacity are ignored.
+    fn insert(&self, value: usize) {
+        if let Some(word) = self.0.get(value / 64) {
+            word.fetch_or(1 << (value % 64), Ordering::Relaxed);
+        }
+    }
+
+    /// The number of integers in the set.
+    fn len(&self) -> usize {
+        self.0
+            .iter()
+            .map(|word| word.load(Ordering::Relaxed).count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterate over the integers in the set in ascending order.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, word)| {
+            let bits = word.load(Ordering::Relaxed);
+            (0..64)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| i * 64 + bit)
+        })
+    }
+}
+
 /// Deduplicate diagnostics.
 fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic> {
     let mut unique = HashSet::new();
@@ -229,6 +820,27 @@ fn hint_invalid_main_file(
     eco_vec![diagnostic]
 }
 
+/// Warn about fonts that are never selected because another font has the same
+/// family and variant.
+fn warn_for_shadowed_fonts(world: Tracked<dyn World + '_>, sink: &mut Sink) {
+    let book = world.book();
+    for (shadowed, by) in book.duplicates() {
+        let (Some(info), Some(winner)) = (book.info(shadowed), book.info(by)) else {
+            continue;
+        };
+        sink.warn(warning!(
+            Span::detached(),
+            "shadowed font: font {shadowed} ({} {:?}) is never used",
+            info.family,
+            info.variant;
+            hint: "font {by} has the same family and variant and is preferred";
+            hint: "its revision is {:#x}, the shadowed font's revision is {:#x}",
+            winner.revision,
+            info.revision
+        ));
+    }
+}
+
 /// HTML export will warn or error depending on whether the feature flag is enabled.
 fn warn_or_error_for_html(
     world: Tracked<dyn World + '_>,
@@ -291,38 +903,105 @@ mod sealed {
     pub trait Sealed: Sized {
         const TARGET: Target;
 
+        fn info_mut(&mut self) -> &mut DocumentInfo;
+
+        fn count(&self, stats: &mut CompileStats);
+
         fn create(
             engine: &mut Engine,
             content: &Content,
             styles: StyleChain,
+            options: &CompileOptions,
+            previous: Option<&Self>,
         ) -> SourceResult<Self>;
     }
 
     impl Sealed for PagedDocument {
         const TARGET: Target = Target::Paged;
 
+        fn info_mut(&mut self) -> &mut DocumentInfo {
+            &mut self.info
+        }
+
+        fn count(&self, stats: &mut CompileStats) {
+            let mut images = HashSet::new();
+            for page in &self.pages {
+                count_frame(&page.frame, stats, &mut images);
+            }
+        }
+
         fn create(
             engine: &mut Engine,
             content: &Content,
             styles: StyleChain,
+            options: &CompileOptions,
+            previous: Option<&Self>,
         ) -> SourceResult<Self> {
-            typst_layout::layout_document(engine, content, styles)
+            typst_layout::layout_document(
+                engine,
+                content,
+                styles,
+                options.max_pages,
+                previous,
+            )
         }
     }
 
     impl Sealed for HtmlDocument {
         const TARGET: Target = Target::Html;
 
+        fn info_mut(&mut self) -> &mut DocumentInfo {
+            &mut self.info
+        }
+
+        fn count(&self, stats: &mut CompileStats) {
+            let mut images = HashSet::new();
+            count_nodes(&self.root.children, stats, &mut images);
+        }
+
         fn create(
             engine: &mut Engine,
             content: &Content,
             styles: StyleChain,
+            _: &CompileOptions,
+            _: Option<&Self>,
         ) -> SourceResult<Self> {
             typst_html::html_document(engine, content, styles)
         }
     }
 }
 
+/// Counts the frames, items, and decoded image bytes in HTML nodes.
+fn count_nodes(nodes: &[HtmlNode], stats: &mut CompileStats, images: &mut HashSet<u128>) {
+    for node in nodes {
+        stats.items += 1;
+        match node {
+            HtmlNode::Element(element) => count_nodes(&element.children, stats, images),
+            HtmlNode::Frame(frame) => count_frame(frame, stats, images),
+            HtmlNode::Tag(_) | HtmlNode::Text(..) => {}
+        }
+    }
+}
+
+/// Counts the frames, items, and decoded image bytes in a frame.
+fn count_frame(frame: &Frame, stats: &mut CompileStats, images: &mut HashSet<u128>) {
+    stats.frames += 1;
+    for (_, item) in frame.items() {
+        stats.items += 1;
+        match item {
+            FrameItem::Group(group) => count_frame(&group.frame, stats, images),
+            FrameItem::Image(image, _, _) => {
+                if let ImageKind::Raster(raster) = image.kind() {
+                    if images.insert(typst_utils::hash128(image)) {
+                        stats.image_bytes += raster.dynamic().as_bytes().len();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Defines implementation of various Typst compiler routines as a table of
 /// function pointers.
 ///
@@ -339,6 +1018,8 @@ pub static ROUTINES: Routines = Routines {
     layout_enum: typst_layout::layout_enum,
     layout_grid: typst_layout::layout_grid,
     layout_table: typst_layout::layout_table,
+    html_table: typst_layout::html_table,
+    html_grid: typst_layout::html_grid,
     layout_stack: typst_layout::layout_stack,
     layout_columns: typst_layout::layout_columns,
     layout_move: typst_layout::layout_move,
@@ -346,6 +1027,7 @@ pub static ROUTINES: Routines = Routines {
     layout_scale: typst_layout::layout_scale,
     layout_skew: typst_layout::layout_skew,
     layout_repeat: typst_layout::layout_repeat,
+    layout_fit_text: typst_layout::layout_fit_text,
     layout_pad: typst_layout::layout_pad,
     layout_line: typst_layout::layout_line,
     layout_curve: typst_layout::layout_curve,
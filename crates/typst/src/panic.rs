@@ -0,0 +1,155 @@
+//! Reporting of the document location at which the compiler panicked.
+//!
+//! A panic in the compiler is always a bug, but without knowing which part of
+//! the document triggered it, it is hard to reproduce. Layout entry points
+//! therefore enter a [`PanicScope`] for the content they process. When a
+//! compilation panics, the spans of the scopes that were active are resolved
+//! against the compilation's world and kept around for embedders that catch
+//! panics. Once [installed](install_panic_context), they are also printed.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ecow::{eco_format, EcoString};
+use typst_library::diag::{PanicScope, PanicSpan, SourceDiagnostic};
+use typst_library::World;
+use typst_syntax::Span;
+
+/// The environment variable that enables printing the context of panics.
+const ENV_VAR: &str = "TYPST_PANIC_CONTEXT";
+
+/// Whether the context of panics is printed.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The context of the last panic of a compilation on this thread.
+    static CAPTURED: RefCell<Option<PanicContext>> = const { RefCell::new(None) };
+}
+
+/// Report which part of the document was being processed when the compiler
+/// panics.
+///
+/// After the panic message, a line like `while processing main.typ:43:7 (grid
+/// cell)` is printed to stderr for each active scope, innermost first.
+///
+/// Instead of calling this function, the reporting can also be enabled by
+/// setting the `TYPST_PANIC_CONTEXT` environment variable. Independently of
+/// this, embedders that catch panics with
+/// [`catch_unwind`](std::panic::catch_unwind) can always retrieve the context
+/// with [`take_panic_context`] and report a proper diagnostic instead of
+/// crashing.
+pub fn install_panic_context() {
+    INSTALLED.store(true, Ordering::Relaxed);
+}
+
+/// Take the context of the last panic of a compilation on the current thread.
+///
+/// Returns `None` if no compilation panicked since the last call.
+pub fn take_panic_context() -> Option<PanicContext> {
+    CAPTURED.with_borrow_mut(Option::take)
+}
+
+/// What the compiler was processing when it panicked.
+#[derive(Debug, Clone)]
+pub struct PanicContext {
+    /// The panic message.
+    pub message: EcoString,
+    /// The spans of the active scopes and what kind of content they belong
+    /// to, innermost first.
+    pub scopes: Vec<PanicSpan>,
+    /// The resolved locations of the scopes, in the same order.
+    locations: Vec<EcoString>,
+}
+
+impl PanicContext {
+    /// The innermost span that points into a file, if any.
+    pub fn span(&self) -> Option<Span> {
+        self.scopes
+            .iter()
+            .map(|&(span, _)| span)
+            .find(|span| !span.is_detached())
+    }
+
+    /// Describe where the panic occurred, with one line per scope.
+    pub fn describe(&self) -> EcoString {
+        let mut out = EcoString::new();
+        for (location, (_, kind)) in self.locations.iter().zip(&self.scopes) {
+            writeln!(out, "while processing {location} ({kind})").unwrap();
+        }
+        out
+    }
+
+    /// Turn the panic into an error diagnostic at the innermost span.
+    pub fn to_diagnostic(&self) -> SourceDiagnostic {
+        let mut diag = SourceDiagnostic::error(
+            self.span().unwrap_or(Span::detached()),
+            eco_format!("internal compiler error: {}", self.message),
+        );
+        diag.hint("this is a bug in Typst, please report it");
+        diag
+    }
+}
+
+/// Runs a compilation and captures the context if it panics.
+///
+/// The panic is propagated afterwards. The world is only used while it is
+/// still borrowed by the compilation, to resolve the captured spans.
+pub(crate) fn with_panic_context<T>(world: &dyn World, f: impl FnOnce() -> T) -> T {
+    // Discard the scopes of earlier panics that were caught elsewhere.
+    PanicScope::take_captured();
+
+    let payload = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(output) => return output,
+        Err(payload) => payload,
+    };
+
+    let scopes = PanicScope::take_captured().unwrap_or_default();
+    let locations = scopes.iter().map(|&(span, _)| locate(world, span)).collect();
+    let context = PanicContext { message: message(&*payload), scopes, locations };
+
+    if INSTALLED.load(Ordering::Relaxed) || std::env::var_os(ENV_VAR).is_some() {
+        eprint!("{}", context.describe());
+    }
+
+    CAPTURED.with_borrow_mut(|captured| *captured = Some(context));
+    panic::resume_unwind(payload)
+}
+
+/// Extract the message of a panic.
+fn message(payload: &(dyn Any + Send)) -> EcoString {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).into()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.as_str().into()
+    } else {
+        "unknown panic".into()
+    }
+}
+
+/// Resolve a span to a `path:line:column` location.
+fn locate(world: &dyn World, span: Span) -> EcoString {
+    let Some(id) = span.id() else { return "unknown location".into() };
+
+    let mut out = EcoString::new();
+    if let Some(spec) = id.package() {
+        write!(out, "{spec}/").unwrap();
+    }
+    write!(out, "{}", id.vpath().as_rootless_path().display()).unwrap();
+
+    let position = (|| {
+        let source = world.source(id).ok()?;
+        let range = span.range().or_else(|| source.range(span))?;
+        let line = source.byte_to_line(range.start)?;
+        let column = source.byte_to_column(range.start)?;
+        Some((line + 1, column + 1))
+    })();
+
+    if let Some((line, column)) = position {
+        write!(out, ":{line}:{column}").unwrap();
+    }
+
+    out
+}
@@ -0,0 +1,268 @@
+//! Repeated compilation of a changing project.
+//!
+//! Watch and preview tools compile the same project over and over again while
+//! its sources are edited. A [`CompilationSession`] takes care of the
+//! recurring parts: It keeps edited sources in memory, compiles them, and
+//! evicts stale results from the memoization cache afterwards.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use typst_library::diag::{FileResult, SourceResult, Warned};
+use typst_library::foundations::{Bytes, Datetime};
+use typst_library::text::{Font, FontBook};
+use typst_library::{Library, World};
+use typst_syntax::package::PackageSpec;
+use typst_syntax::{FileId, Source};
+use typst_utils::LazyHash;
+
+use crate::{count_cache_additions, evict, Document};
+
+/// A compilation session for a project whose sources change between
+/// compilations.
+///
+/// The session wraps a [`World`] and overlays it with sources that were
+/// updated through [`update_file`](Self::update_file). Updating a source
+/// reparses it incrementally, so that unchanged parts of the document can be
+/// served from the memoization cache in the next compilation.
+///
+/// # Eviction
+/// After each compilation, the session evicts all entries from the
+/// memoization cache that were not used in the last
+/// [`max_age`](Self::with_max_age) compilations. Results that are still needed
+/// for the current state of the project are thus kept, while results for old
+/// states of edited sources are dropped after a while. A small age keeps
+/// memory usage low, but results for content that is only needed every now and
+/// then (e.g. when switching between files) may have to be recomputed. A large
+/// age lets the cache grow with each edit until the oldest results expire.
+///
+/// The memoization cache is shared by the whole process. Eviction thus also
+/// affects other compilations that run in the same process.
+pub struct CompilationSession<W> {
+    /// The wrapped world.
+    world: W,
+    /// Sources that were updated in the session.
+    sources: HashMap<FileId, Source>,
+    /// The number of compilations after which unused cache entries are
+    /// evicted.
+    max_age: usize,
+    /// Statistics about the session.
+    stats: SessionStats,
+}
+
+/// Statistics about a [`CompilationSession`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SessionStats {
+    /// The number of compilations that were run.
+    pub compiles: usize,
+    /// The number of cache entries that the session's compilations added in
+    /// total.
+    pub added_entries: usize,
+}
+
+impl<W: World> CompilationSession<W> {
+    /// The default number of compilations after which unused cache entries
+    /// are evicted.
+    pub const DEFAULT_MAX_AGE: usize = 10;
+
+    /// Start a new session for the given world.
+    pub fn new(world: W) -> Self {
+        Self {
+            world,
+            sources: HashMap::new(),
+            max_age: Self::DEFAULT_MAX_AGE,
+            stats: SessionStats::default(),
+        }
+    }
+
+    /// Set after how many compilations unused cache entries are evicted.
+    ///
+    /// With an age of zero, the cache is cleared completely after each
+    /// compilation.
+    pub fn with_max_age(mut self, max_age: usize) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// The wrapped world.
+    pub fn world(&self) -> &W {
+        &self.world
+    }
+
+    /// The wrapped world, mutably.
+    ///
+    /// Sources that were updated through the session take precedence over
+    /// the world's sources until they are [reset](Self::reset_file).
+    pub fn world_mut(&mut self) -> &mut W {
+        &mut self.world
+    }
+
+    /// Update the text of a source file.
+    ///
+    /// The file does not need to exist in the wrapped world.
+    pub fn update_file(&mut self, id: FileId, text: &str) {
+        match self.sources.entry(id) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().replace(text);
+            }
+            Entry::Vacant(entry) => {
+                let mut source = self
+                    .world
+                    .source(id)
+                    .unwrap_or_else(|_| Source::new(id, String::new()));
+                source.replace(text);
+                entry.insert(source);
+            }
+        }
+    }
+
+    /// Forget the updated text of a source file and use the world's version
+    /// of it again.
+    pub fn reset_file(&mut self, id: FileId) {
+        self.sources.remove(&id);
+    }
+
+    /// Compile the project and evict stale results from the cache.
+    pub fn compile<D>(&mut self) -> Warned<SourceResult<D>>
+    where
+        D: Document,
+    {
+        let (warned, added) = count_cache_additions(|| crate::compile::<D>(&*self));
+        evict(self.max_age);
+
+        self.stats.compiles += 1;
+        self.stats.added_entries += added.total();
+
+        warned
+    }
+
+    /// Statistics about the compilations in this session so far.
+    pub fn stats(&self) -> SessionStats {
+        self.stats
+    }
+}
+
+impl<W: World> World for CompilationSession<W> {
+    fn library(&self) -> &LazyHash<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> FileId {
+        self.world.main()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        match self.sources.get(&id) {
+            Some(source) => Ok(source.clone()),
+            None => self.world.source(id),
+        }
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        match self.sources.get(&id) {
+            Some(source) => Ok(Bytes::from(source.text().as_bytes())),
+            None => self.world.file(id),
+        }
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        self.world.modified(id)
+    }
+
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        self.world.package_files(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::layout::{Frame, FrameItem, PagedDocument};
+    use typst_library::package::PackageLock;
+
+    use super::*;
+    use crate::testing::TestWorld;
+    use crate::CompileOptions;
+
+    const MANIFEST: &str = "[package]\n\
+                            name = \"adder\"\n\
+                            version = \"0.1.0\"\n\
+                            entrypoint = \"lib.typ\"";
+
+    #[test]
+    fn test_session_verifies_package_lock() {
+        let spec: PackageSpec = "@test/adder:0.1.0".parse().unwrap();
+        let world = TestWorld::new("#import \"@test/adder:0.1.0\": add\n#add(1, 2)")
+            .with_source(Some(&spec), "typst.toml", MANIFEST)
+            .with_source(Some(&spec), "lib.typ", "#let add(x, y) = x + y");
+
+        let mut lock = PackageLock::new();
+        lock.record(&world, [&spec]).unwrap();
+
+        let session = CompilationSession::new(world);
+        let options = CompileOptions {
+            package_lock: Some(lock),
+            ..CompileOptions::default()
+        };
+        let (warned, _) =
+            crate::compile_with_options::<PagedDocument>(&session, &options);
+        assert!(warned.output.is_ok());
+    }
+
+    /// The text of all pages of a document.
+    fn text(document: &PagedDocument) -> String {
+        fn collect(frame: &Frame, text: &mut String) {
+            for (_, item) in frame.items() {
+                match item {
+                    FrameItem::Group(group) => collect(&group.frame, text),
+                    FrameItem::Text(item) => text.push_str(&item.text),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut text = String::new();
+        for page in &document.pages {
+            collect(&page.frame, &mut text);
+        }
+        text
+    }
+
+    #[test]
+    fn test_session_compiles_updated_files() {
+        let world = TestWorld::new("= Original");
+        let main = world.main();
+        let mut session = CompilationSession::new(world).with_max_age(2);
+        let mut texts = vec![];
+        for i in 0..10 {
+            // Cycle through three versions of a heading.
+            let heading = format!("Session {}", i % 3);
+            session.update_file(main, &format!("= {heading}\nStable text."));
+            let document = session.compile::<PagedDocument>().output.unwrap();
+            texts.push(text(&document));
+            assert!(texts[i].contains(&heading));
+            if i >= 3 {
+                assert_eq!(texts[i], texts[i - 3]);
+            }
+        }
+
+        let stats = session.stats();
+        assert_eq!(stats.compiles, 10);
+        assert!(stats.added_entries > 0);
+
+        session.reset_file(main);
+        let document = session.compile::<PagedDocument>().output.unwrap();
+        assert_eq!(text(&document), "Original");
+    }
+}
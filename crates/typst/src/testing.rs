@@ -0,0 +1,192 @@
+//! A world for unit tests of the compiler and the exporters.
+//!
+//! This is not part of the public API and only available with the `testing`
+//! feature, which the exporters enable for their tests.
+
+use std::collections::HashMap;
+
+use typst_library::diag::{FileError, FileResult};
+use typst_library::foundations::{Bytes, Datetime};
+use typst_library::text::{Font, FontBook};
+use typst_library::{Feature, Library, World};
+use typst_syntax::package::PackageSpec;
+use typst_syntax::{FileId, Source, VirtualPath};
+use typst_utils::{singleton, LazyHash};
+
+/// A world with a main source and a few additional sources.
+pub struct TestWorld {
+    main: Source,
+    library: LazyHash<Library>,
+    sources: HashMap<FileId, Source>,
+    files: HashMap<FileId, Bytes>,
+    base: &'static TestBase,
+}
+
+impl TestWorld {
+    /// Create a new world with the given main source.
+    pub fn new(text: &str) -> Self {
+        let id = FileId::new(None, VirtualPath::new("main.typ"));
+        Self {
+            main: Source::new(id, text.into()),
+            library: LazyHash::new(Library::default()),
+            sources: HashMap::new(),
+            files: HashMap::new(),
+            base: singleton!(TestBase, TestBase::default()),
+        }
+    }
+
+    /// Add a source file at the given path, in a package if a spec is given.
+    pub fn with_source(
+        mut self,
+        spec: Option<&PackageSpec>,
+        path: &str,
+        text: &str,
+    ) -> Self {
+        let id = FileId::new(spec.cloned(), VirtualPath::new(path));
+        self.sources.insert(id, Source::new(id, text.into()));
+        self
+    }
+
+    /// Enable the given features.
+    pub fn with_features(mut self, features: &[Feature]) -> Self {
+        self.library.features = features.iter().copied().collect();
+        self
+    }
+
+    /// Add a binary file at the given path in the project root.
+    pub fn with_file(mut self, path: &str, data: Bytes) -> Self {
+        self.files.insert(FileId::new(None, VirtualPath::new(path)), data);
+        self
+    }
+}
+
+impl World for TestWorld {
+    fn library(&self) -> &LazyHash<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &LazyHash<FontBook> {
+        &self.base.book
+    }
+
+    fn main(&self) -> FileId {
+        self.main.id()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if id == self.main.id() {
+            Ok(self.main.clone())
+        } else if let Some(source) = self.sources.get(&id) {
+            Ok(source.clone())
+        } else {
+            Err(FileError::NotFound(id.vpath().as_rootless_path().into()))
+        }
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        match self.files.get(&id) {
+            Some(data) => Ok(data.clone()),
+            None => Ok(Bytes::from(self.source(id)?.text().as_bytes())),
+        }
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.base.fonts.get(index).cloned()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Datetime::from_ymd(1970, 1, 1)
+    }
+
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        let mut files: Vec<_> = self
+            .sources
+            .keys()
+            .filter(|id| id.package() == Some(spec))
+            .copied()
+            .collect();
+        files.sort_by_key(|id| id.vpath().as_rootless_path().to_owned());
+        Ok(files)
+    }
+}
+
+/// Shared foundation of all test worlds.
+struct TestBase {
+    book: LazyHash<FontBook>,
+    fonts: Vec<Font>,
+}
+
+impl Default for TestBase {
+    fn default() -> Self {
+        let fonts: Vec<_> = typst_assets::fonts()
+            .flat_map(|data| Font::iter(Bytes::from_static(data)))
+            .collect();
+
+        Self {
+            book: LazyHash::new(FontBook::from_fonts(&fonts)),
+            fonts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use typst_library::foundations::Bytes;
+    use typst_library::layout::{Frame, FrameItem, PagedDocument};
+    use typst_library::visualize::{Image, ImageKind};
+
+    use super::TestWorld;
+
+    #[test]
+    fn test_compile_stats() {
+        // The final heading count is only known after the first layout iteration,
+        // so this needs two of them.
+        let png = tiny_skia::Pixmap::new(4, 4).unwrap().encode_png().unwrap();
+        let world = TestWorld::new(
+            "= Stats\n\
+             #box(image(\"image.png\", height: 6pt))\n\
+             #context counter(heading).final().first()",
+        )
+        .with_file("image.png", Bytes::from(png));
+
+        let (warned, stats) = crate::compile_with_stats::<PagedDocument>(&world);
+        assert!(warned.output.is_ok());
+        assert_eq!(stats.iterations, 2);
+        for (name, count) in [
+            ("elements", stats.elements),
+            ("frames", stats.frames),
+            ("items", stats.items),
+            ("fonts", stats.fonts),
+            ("image bytes", stats.image_bytes),
+            ("peak arena bytes", stats.peak_arena_bytes),
+        ] {
+            assert!(count > 0, "{name} count is zero");
+        }
+    }
+
+    #[test]
+    fn test_rasterize_without_rasterizer() {
+        // This world doesn't provide a rasterizer, so the SVG stays vector
+        // graphics with a warning.
+        let world = TestWorld::new(
+            "#image.decode(\"<svg xmlns='http://www.w3.org/2000/svg' width='20' \
+             height='10'/>\", rasterize: \"always\")",
+        );
+
+        let warned = crate::compile::<PagedDocument>(&world);
+        let doc = warned.output.unwrap();
+        let image = first_image(&doc.pages[0].frame).unwrap();
+        assert!(matches!(image.kind(), ImageKind::Svg(_)));
+        assert_eq!(warned.warnings.len(), 1);
+        assert!(warned.warnings[0].message.contains("could not be rasterized"));
+    }
+
+    /// The first image in a frame.
+    fn first_image(frame: &Frame) -> Option<&Image> {
+        frame.items().find_map(|(_, item)| match item {
+            FrameItem::Group(group) => first_image(&group.frame),
+            FrameItem::Image(image, ..) => Some(image),
+            _ => None,
+        })
+    }
+}
@@ -1,13 +1,14 @@
-use std::fmt::{self, Debug, Formatter};
+use std::fmt::{self, Debug, Formatter, Write};
 use std::ops::Range;
+use std::panic::Location;
 
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 use heck::{ToKebabCase, ToTitleCase};
 use pulldown_cmark as md;
 use serde::{Deserialize, Serialize};
 use typed_arena::Arena;
-use typst::diag::{FileError, FileResult, StrResult};
-use typst::foundations::{Bytes, Datetime};
+use typst::diag::{bail, FileError, FileResult, StrResult};
+use typst::foundations::{Bytes, Datetime, Smart};
 use typst::layout::{Abs, PagedDocument, Point, Size};
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
@@ -79,7 +80,8 @@ impl Html {
         };
 
         let ids = Arena::new();
-        let mut handler = Handler::new(text, resolver, nesting, &ids);
+        let origin = Location::caller();
+        let mut handler = Handler::new(text, resolver, nesting, &ids, origin);
         let mut events =
             md::Parser::new_with_broken_link_callback(text, options, Some(&mut link))
                 .peekable();
@@ -165,6 +167,7 @@ struct Handler<'a> {
     outline: Vec<OutlineItem>,
     nesting: Option<usize>,
     ids: &'a Arena<String>,
+    origin: &'static Location<'static>,
 }
 
 impl<'a> Handler<'a> {
@@ -173,6 +176,7 @@ impl<'a> Handler<'a> {
         resolver: &'a dyn Resolver,
         nesting: Option<usize>,
         ids: &'a Arena<String>,
+        origin: &'static Location<'static>,
     ) -> Self {
         Self {
             text,
@@ -183,6 +187,7 @@ impl<'a> Handler<'a> {
             outline: vec![],
             nesting,
             ids,
+            origin,
         }
     }
 
@@ -263,7 +268,7 @@ impl<'a> Handler<'a> {
             }
             md::Event::End(md::Tag::CodeBlock(md::CodeBlockKind::Fenced(_))) => {
                 let Some(lang) = self.lang.take() else { return false };
-                let html = code_block(self.resolver, &lang, &self.code);
+                let html = code_block(self.resolver, &lang, &self.code, self.origin);
                 *event = md::Event::Html(html.raw.into());
             }
 
@@ -351,7 +356,7 @@ impl<'a> Handler<'a> {
 }
 
 /// Render a code block to HTML.
-fn code_block(resolver: &dyn Resolver, lang: &str, text: &str) -> Html {
+fn code_block(resolver: &dyn Resolver, tag: &str, text: &str, origin: &Location) -> Html {
     let mut display = String::new();
     let mut compile = String::new();
     for line in text.lines() {
@@ -369,23 +374,9 @@ fn code_block(resolver: &dyn Resolver, lang: &str, text: &str) -> Html {
         }
     }
 
-    let mut parts = lang.split(':');
-    let lang = parts.next().unwrap_or(lang);
-
-    let mut zoom: Option<[Abs; 4]> = None;
-    let mut single = false;
-    if let Some(args) = parts.next() {
-        single = true;
-        if !args.contains("single") {
-            zoom = args
-                .split(',')
-                .take(4)
-                .map(|s| Abs::pt(s.parse().unwrap()))
-                .collect::<Vec<_>>()
-                .try_into()
-                .ok();
-        }
-    }
+    let (lang, args) = ExampleArgs::parse(tag).unwrap_or_else(|err| {
+        panic!("{err} in code block of markdown loaded at {origin}:\n{text}")
+    });
 
     if lang.is_empty() {
         let mut buf = String::from("<pre>");
@@ -415,8 +406,22 @@ fn code_block(resolver: &dyn Resolver, lang: &str, text: &str) -> Html {
         highlighted = Some(html);
     }
 
+    let document = compile_example(text, &compile, &args, false);
+    let dark = args.dark.then(|| compile_example(text, &compile, &args, true));
+
+    let hash = typst::utils::hash128(&(tag, text));
+    resolver.example(hash, highlighted, &document, dark.as_ref())
+}
+
+/// Compile an example with the given arguments.
+fn compile_example(
+    text: &str,
+    compile: &str,
+    args: &ExampleArgs,
+    dark: bool,
+) -> PagedDocument {
     let id = FileId::new(None, VirtualPath::new("main.typ"));
-    let source = Source::new(id, compile);
+    let source = Source::new(id, args.preamble(dark) + compile);
     let world = DocWorld(source);
 
     let mut document = match typst::compile::<PagedDocument>(&world).output {
@@ -427,17 +432,143 @@ fn code_block(resolver: &dyn Resolver, lang: &str, text: &str) -> Html {
         }
     };
 
-    if let Some([x, y, w, h]) = zoom {
+    if let Some([x, y, w, h]) = args.zoom {
         document.pages[0].frame.translate(Point::new(-x, -y));
         *document.pages[0].frame.size_mut() = Size::new(w, h);
     }
 
-    if single {
+    if args.single {
         document.pages.truncate(1);
     }
 
-    let hash = typst::utils::hash128(&(lang, text));
-    resolver.example(hash, highlighted, &document)
+    document
+}
+
+/// How to render an example, as configured in the tag of its code block.
+///
+/// The tag is either just the language, the language with legacy arguments
+/// (`example:single` or `example:x,y,w,h` to zoom into a part of the first
+/// page), or the language with directives, like
+/// `example(width: 400pt, dark: true)`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExampleArgs {
+    /// Whether to only show the first page.
+    pub single: bool,
+    /// The part of the first page to show, as x, y, width, and height.
+    pub zoom: Option<[Abs; 4]>,
+    /// Overrides the page width.
+    pub width: Option<Abs>,
+    /// Overrides the page height.
+    pub height: Option<Smart<Abs>>,
+    /// Overrides the page margins on all sides.
+    pub margin: Option<Abs>,
+    /// Whether to additionally render the example for dark mode.
+    pub dark: bool,
+}
+
+impl ExampleArgs {
+    /// Parse the tag of a code block into its language and arguments.
+    ///
+    /// Fails for unknown or malformed directives.
+    pub fn parse(tag: &str) -> StrResult<(&str, Self)> {
+        let mut args = Self::default();
+        let Some(split) = tag.find(['(', ':']) else { return Ok((tag, args)) };
+        let (lang, rest) = tag.split_at(split);
+
+        if let Some(legacy) = rest.strip_prefix(':') {
+            args.single = true;
+            if !legacy.contains("single") {
+                args.zoom = legacy
+                    .split(',')
+                    .take(4)
+                    .map(|s| Abs::pt(s.parse().unwrap()))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .ok();
+            }
+            return Ok((lang, args));
+        }
+
+        let Some(directives) = rest
+            .strip_prefix('(')
+            .and_then(|rest| rest.trim_end().strip_suffix(')'))
+        else {
+            bail!("unclosed example directives: `{tag}`");
+        };
+
+        for directive in directives.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let Some((key, value)) = directive.split_once(':') else {
+                bail!("expected `key: value`, found `{directive}`");
+            };
+
+            let value = value.trim();
+            match key.trim() {
+                "width" => args.width = Some(parse_length(value)?),
+                "height" if value == "auto" => args.height = Some(Smart::Auto),
+                "height" => args.height = Some(Smart::Custom(parse_length(value)?)),
+                "margin" => args.margin = Some(parse_length(value)?),
+                "dark" => {
+                    args.dark = match value {
+                        "true" => true,
+                        "false" => false,
+                        _ => bail!("expected boolean for `dark`, found `{value}`"),
+                    }
+                }
+                key => bail!("unknown example directive `{key}`"),
+            }
+        }
+
+        Ok((lang, args))
+    }
+
+    /// Typst code that applies the arguments to the page and, for dark mode,
+    /// switches to white text on a transparent background.
+    fn preamble(&self, dark: bool) -> String {
+        let mut page = vec![];
+        if let Some(width) = self.width {
+            page.push(format!("width: {}pt", width.to_pt()));
+        }
+        match self.height {
+            Some(Smart::Auto) => page.push("height: auto".into()),
+            Some(Smart::Custom(height)) => {
+                page.push(format!("height: {}pt", height.to_pt()))
+            }
+            None => {}
+        }
+        if let Some(margin) = self.margin {
+            page.push(format!("margin: {}pt", margin.to_pt()));
+        }
+        if dark {
+            page.push("fill: none".into());
+        }
+
+        let mut preamble = String::new();
+        if !page.is_empty() {
+            writeln!(preamble, "#set page({})", page.join(", ")).unwrap();
+        }
+        if dark {
+            preamble.push_str("#set text(fill: white)\n");
+        }
+        preamble
+    }
+}
+
+/// Parse a length with an absolute unit, like `400pt` or `2cm`.
+fn parse_length(s: &str) -> StrResult<Abs> {
+    let units = [
+        ("pt", Abs::pt as fn(f64) -> Abs),
+        ("mm", Abs::mm),
+        ("cm", Abs::cm),
+        ("in", Abs::inches),
+    ];
+    for (unit, f) in units {
+        if let Some(number) = s.strip_suffix(unit) {
+            if let Ok(number) = number.parse() {
+                return Ok(f(number));
+            }
+        }
+    }
+    Err(eco_format!("expected absolute length, found `{s}`"))
 }
 
 /// Extract an attribute value from an HTML element.
@@ -105,8 +105,17 @@ pub trait Resolver {
     fn image(&self, filename: &str, data: &[u8]) -> String;
 
     /// Produce HTML for an example.
-    fn example(&self, hash: u128, source: Option<Html>, document: &PagedDocument)
-        -> Html;
+    ///
+    /// If the example requested a dark mode variant, it is passed as `dark`,
+    /// so that the page can pick the matching rendering through the
+    /// `prefers-color-scheme` media query.
+    fn example(
+        &self,
+        hash: u128,
+        source: Option<Html>,
+        document: &PagedDocument,
+        dark: Option<&PagedDocument>,
+    ) -> Html;
 
     /// Determine the commits between two tags.
     fn commits(&self, from: &str, to: &str) -> Vec<Commit>;
@@ -787,21 +796,86 @@ impl GroupData {
 
 #[cfg(test)]
 mod tests {
+    use std::cell::RefCell;
+
     use super::*;
 
     #[test]
     fn test_docs() {
-        provide(&TestResolver);
+        provide(&TestResolver::default());
+    }
+
+    #[test]
+    fn test_example_args() {
+        let parse = |tag| ExampleArgs::parse(tag).unwrap();
+        assert_eq!(parse("example"), ("example", ExampleArgs::default()));
+        assert_eq!(
+            parse("example:single"),
+            ("example", ExampleArgs { single: true, ..Default::default() })
+        );
+        assert_eq!(
+            parse("example:0,0,20,10").1.zoom,
+            Some([Abs::zero(), Abs::zero(), Abs::pt(20.0), Abs::pt(10.0)])
+        );
+        assert_eq!(
+            parse("example(width: 400pt, height: auto, margin: 1cm, dark: true)"),
+            (
+                "example",
+                ExampleArgs {
+                    width: Some(Abs::pt(400.0)),
+                    height: Some(Smart::Auto),
+                    margin: Some(Abs::cm(1.0)),
+                    dark: true,
+                    ..Default::default()
+                }
+            )
+        );
+        assert_eq!(parse("example()"), ("example", ExampleArgs::default()));
+        assert!(ExampleArgs::parse("example(color: red)").is_err());
+        assert!(ExampleArgs::parse("example(width: 400)").is_err());
+        assert!(ExampleArgs::parse("example(width: 400pt").is_err());
     }
 
-    struct TestResolver;
+    #[test]
+    #[should_panic(expected = "unknown example directive `colour` in code block")]
+    fn test_example_unknown_directive() {
+        let md = "```example(colour: red)\nHello\n```";
+        Html::markdown(&TestResolver::default(), md, None);
+    }
+
+    #[test]
+    fn test_example_dark() {
+        let resolver = TestResolver::default();
+        let md = "```example(width: 100pt, dark: true)\nHello\n```";
+        Html::markdown(&resolver, md, None);
+        let examples = resolver.examples.borrow();
+        assert_eq!(examples.len(), 1);
+        let (light, dark) = &examples[0];
+        assert_eq!(light.pages[0].frame.width(), Abs::pt(100.0));
+        let dark = dark.as_ref().expect("dark document is missing");
+        assert_eq!(dark.pages[0].frame.width(), Abs::pt(100.0));
+        assert_eq!(light.pages[0].fill, Smart::Auto);
+        assert_eq!(dark.pages[0].fill, Smart::Custom(None));
+    }
+
+    #[derive(Default)]
+    struct TestResolver {
+        examples: RefCell<Vec<(PagedDocument, Option<PagedDocument>)>>,
+    }
 
     impl Resolver for TestResolver {
         fn link(&self, _: &str) -> Option<String> {
             None
         }
 
-        fn example(&self, _: u128, _: Option<Html>, _: &PagedDocument) -> Html {
+        fn example(
+            &self,
+            _: u128,
+            _: Option<Html>,
+            document: &PagedDocument,
+            dark: Option<&PagedDocument>,
+        ) -> Html {
+            self.examples.borrow_mut().push((document.clone(), dark.cloned()));
             Html::new(String::new())
         }
 
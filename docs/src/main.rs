@@ -13,6 +13,19 @@ struct CliResolver<'a> {
     base: &'a str,
 }
 
+impl CliResolver<'_> {
+    /// Render the first page of an example and return its URL.
+    fn save_example(&self, filename: &str, document: &PagedDocument) -> String {
+        let page = document.pages.first().expect("page 0");
+        let pixmap = render(page, 2.0);
+        let path = self.assets_dir.join(filename);
+        fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+        pixmap.save_png(path.as_path()).expect("save png");
+        eprintln!("Generated example image {path:?}");
+        format!("{}assets/{filename}", self.base)
+    }
+}
+
 impl Resolver for CliResolver<'_> {
     fn commits(&self, from: &str, to: &str) -> Vec<typst_docs::Commit> {
         if self.verbose {
@@ -26,32 +39,32 @@ impl Resolver for CliResolver<'_> {
         hash: u128,
         source: Option<Html>,
         document: &PagedDocument,
+        dark: Option<&PagedDocument>,
     ) -> typst_docs::Html {
         if self.verbose {
             eprintln!(
-                "example(0x{hash:x}, {:?} chars, Document)",
-                source.as_ref().map(|s| s.as_str().len())
+                "example(0x{hash:x}, {:?} chars, Document, {})",
+                source.as_ref().map(|s| s.as_str().len()),
+                if dark.is_some() { "Document" } else { "None" },
             );
         }
 
-        let page = document.pages.first().expect("page 0");
-        let pixmap = render(page, 2.0);
-        let filename = format!("{hash:x}.png");
-        let path = self.assets_dir.join(&filename);
-        fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
-        pixmap.save_png(path.as_path()).expect("save png");
-        let src = format!("{}assets/{filename}", self.base);
-        eprintln!("Generated example image {path:?}");
+        let src = self.save_example(&format!("{hash:x}.png"), document);
+        let mut preview = format!(r#"<img src="{src}" alt="Preview" />"#);
+        if let Some(dark) = dark {
+            let dark_src = self.save_example(&format!("{hash:x}-dark.png"), dark);
+            preview = format!(
+                r#"<picture><source srcset="{dark_src}" media="(prefers-color-scheme: dark)" />{preview}</picture>"#
+            );
+        }
 
         if let Some(code) = source {
             let code_safe = code.as_str();
             Html::new(format!(
-                r#"<div class="previewed-code"><pre>{code_safe}</pre><div class="preview"><img src="{src}" alt="Preview" /></div></div>"#
+                r#"<div class="previewed-code"><pre>{code_safe}</pre><div class="preview">{preview}</div></div>"#
             ))
         } else {
-            Html::new(format!(
-                r#"<div class="preview"><img src="{src}" alt="Preview" /></div>"#
-            ))
+            Html::new(format!(r#"<div class="preview">{preview}</div>"#))
         }
     }
 
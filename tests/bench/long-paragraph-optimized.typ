@@ -0,0 +1,4 @@
+// The same paragraph as in `long-paragraph.typ`, but explicitly optimized.
+// This is how all justified paragraphs were broken before the fallback.
+#set par(justify: true, linebreaks: "optimized")
+#lorem(20000)
@@ -0,0 +1,4 @@
+// A justified paragraph with 20000 words. Under `linebreaks: auto`, it is
+// broken in simple first-fit style.
+#set par(justify: true)
+#lorem(20000)
@@ -0,0 +1,7 @@
+// Many justified paragraphs of normal length. These are still optimized under
+// `linebreaks: auto`.
+#set par(justify: true)
+#for i in range(200) [
+  #lorem(100)
+
+]
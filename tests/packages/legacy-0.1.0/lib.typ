@@ -0,0 +1 @@
+#let stripes = pattern(size: (4pt, 4pt), line(end: (100%, 100%)))
@@ -43,6 +43,18 @@ pub struct CliArguments {
     /// Runs SVG export.
     #[arg(long)]
     pub svg: bool,
+    /// Compares the direct rasterization of each test with the rasterized SVG
+    /// export and fails tests whose pages differ too much.
+    #[arg(long)]
+    pub compare_backends: bool,
+    /// The fraction of pixels that may differ between the backends when
+    /// running with '--compare-backends'.
+    #[arg(long, default_value_t = 0.01)]
+    pub compare_threshold: f64,
+    /// The resolution at which the pages are rasterized when running with
+    /// '--compare-backends'.
+    #[arg(long, default_value_t = 2.0)]
+    pub compare_pixel_per_pt: f32,
     /// Displays the syntax tree before running tests.
     ///
     /// Note: This is ignored if using '--syntax-compare'.
@@ -102,4 +114,10 @@ pub enum Command {
     Clean,
     /// Deletes all dangling reference output.
     Undangle,
+    /// Compiles the documents in 'tests/bench' and reports how long each took.
+    Bench {
+        /// How often to compile each document.
+        #[arg(short, long, default_value_t = 5)]
+        iterations: usize,
+    },
 }
@@ -63,6 +63,8 @@ pub enum Attr {
     Html,
     Render,
     Large,
+    Collect,
+    A11yExtras,
 }
 
 /// The size of a file.
@@ -299,6 +301,8 @@ impl<'a> Parser<'a> {
                 "large" => Attr::Large,
                 "html" => Attr::Html,
                 "render" => Attr::Render,
+                "collect" => Attr::Collect,
+                "a11y-extras" => Attr::A11yExtras,
                 found => {
                     self.error(format!(
                         "expected attribute or closing ---, found `{found}`"
@@ -1,18 +1,54 @@
+use std::collections::BTreeSet;
 use std::fmt::Write;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
 
-use typst::foundations::Smart;
-use typst::layout::PagedDocument;
-use typst::model::DocumentInfo;
-use typst::World;
+use ecow::eco_format;
+use ttf_parser::Tag;
+use typst::diag::{
+    Deprecation, DeprecationPolicy, Severity, SourceDiagnostic, SourceResult, Warned,
+};
+use typst::engine::Engine;
+use typst::foundations::{
+    Bytes, Content, ContentBuilder, Datetime, Label, NativeElement, NativeShowRule, Repr,
+    Smart, StyleChain, Target, Value,
+};
+use typst::html::{attr, tag, HtmlDocument};
+use typst::introspection::metrics::{MetricsLine, MetricsPageBreak};
+use typst::introspection::{Introspector, Location, MetadataElem};
+use typst::layout::{
+    Abs, Em, Frame, FrameItem, FrameRole, Page, PagedDocument, Point, Position, Ratio,
+    Size, Transform,
+};
+use typst::model::{DocumentInfo, HeadingElem, StrongElem};
+use typst::package::{PackageHash, PackageLock};
+use typst::syntax::package::PackageSpec;
+use typst::syntax::{FileId, VirtualPath};
+use typst::text::{
+    Case, EmbeddingPermissions, Font, FontBook, FontProblem, FontVariant, FontWeight,
+    TextElem, TextItem,
+};
+use typst::utils::PicoStr;
+use typst::visualize::{Color, Image, ImageKind, Paint};
+use typst::{CompileOptions, Feature, Library, World, WorldExt};
+use typst_pdf::{PdfOptions, PdfStandard, PdfStandards};
+use typst_render::{Dither, GrayImage, GrayscaleOptions};
+use typst_svg::{GlyphPositioning, SvgOptions};
 
-use crate::collect::Test;
+use crate::collect::{Attr, Test};
 use crate::world::TestWorld;
 
 /// We don't want to panic when there is a failure.
 macro_rules! test_eq {
     ($sink:expr, $lhs:expr, $rhs:expr) => {
-        if $lhs != $rhs {
-            writeln!(&mut $sink, "{:?} != {:?}", $lhs, $rhs).unwrap();
+        match (&($lhs), &($rhs)) {
+            (lhs, rhs) => {
+                if lhs != rhs {
+                    writeln!(&mut $sink, "{lhs:?} != {rhs:?}").unwrap();
+                }
+            }
         }
     };
 }
@@ -27,6 +63,48 @@ pub fn check(test: &Test, world: &TestWorld, doc: Option<&PagedDocument>) -> Str
             test_eq!(sink, info.author, ["A", "B"]);
             test_eq!(sink, info.date, Smart::Custom(world.today(None)));
         }
+        "page-context-dictionary-header" => {
+            let (_, stats) = typst::compile_with_stats::<PagedDocument>(world);
+            test_eq!(sink, stats.iterations, 1);
+            let Some(doc) = doc else { return sink };
+            for page in &doc.pages {
+                let headings = headings(&page.frame);
+                let term = |heading: Option<&Content>| {
+                    heading
+                        .and_then(|elem| elem.to_packed::<HeadingElem>())
+                        .map(|elem| elem.body().plain_text())
+                        .unwrap_or_default()
+                };
+                let header =
+                    format!("{} — {}", term(headings.first()), term(headings.last()));
+                test_eq!(sink, top_line(&page.frame), header);
+            }
+        }
+        "page-max-pages"
+        | "page-max-pages-outline"
+        | "page-max-pages-total"
+        | "page-max-pages-header"
+        | "page-max-pages-final-in-show-rule" => {
+            let options = CompileOptions {
+                max_pages: NonZeroUsize::new(3),
+                ..CompileOptions::default()
+            };
+            let (Warned { output, .. }, stats) =
+                typst::compile_with_options::<PagedDocument>(world, &options);
+            let (_, full) = typst::compile_with_stats::<PagedDocument>(world);
+            let pages = output.ok().map(|doc| doc.pages.len());
+            if test.name == "page-max-pages" {
+                // Later pages are not laid out at all, so there is less to
+                // realize.
+                test_eq!(sink, pages, Some(3));
+                test_eq!(sink, stats.elements < full.elements, true);
+            } else {
+                // The first pages depend on the total number of pages, so the
+                // full document must be laid out.
+                test_eq!(sink, pages, doc.map(|doc| doc.pages.len()));
+                test_eq!(sink, stats.elements, full.elements);
+            }
+        }
         "issue-4065-document-context" => {
             let info = info(doc);
             test_eq!(sink, info.title.as_deref(), Some("Top level"));
@@ -36,11 +114,1799 @@ pub fn check(test: &Test, world: &TestWorld, doc: Option<&PagedDocument>) -> Str
             test_eq!(sink, info.author, ["Changed"]);
             test_eq!(sink, info.title.as_deref(), Some("Alternative"));
         }
+        "document-date-source" => {
+            // The main file and the asset provide modification dates.
+            let hello = FileId::new(None, VirtualPath::new("assets/text/hello.txt"));
+            let world = world
+                .clone()
+                .with_modified(world.main(), Datetime::from_ymd(2024, 1, 1).unwrap())
+                .with_modified(hello, Datetime::from_ymd(2024, 6, 1).unwrap());
+            let doc = typst::compile::<PagedDocument>(&world).output.ok();
+            test_eq!(
+                sink,
+                info(doc.as_ref()).date,
+                Smart::Custom(Datetime::from_ymd(2024, 6, 1))
+            );
+        }
+        "text-font-broken" => {
+            let world = with_broken_font(world).with_main(
+                "#set text(font: \"Libertinus Serif\", fallback: false)\nHello",
+            );
+            let problems = world.book().problems();
+            test_eq!(sink, problems.len(), 1);
+            if let Some(problem) = problems.first() {
+                test_eq!(sink, problem.path.as_deref(), Some(Path::new("broken.ttf")));
+                test_eq!(sink, problem.index, 0);
+            }
+
+            // The regular variant is the best match, but since it fails to
+            // load, the bold variant of the same family is used instead.
+            // Without fallback, the text would have no font otherwise.
+            let Warned { output, .. } = typst::compile::<PagedDocument>(&world);
+            let font = output.ok().and_then(|doc| {
+                doc.pages.first().and_then(|page| text_font(&page.frame))
+            });
+            test_eq!(
+                sink,
+                font.as_ref().map(|font| font.info().variant.weight),
+                Some(FontWeight::BOLD)
+            );
+        }
+        "text-font-priority" => {
+            let font = |family: &str| {
+                let id = world.book().select(family, FontVariant::default());
+                id.and_then(|id| world.font(id)).unwrap()
+            };
+            let libertinus = font("libertinus serif");
+            let pt_sans = font("pt sans");
+
+            // Both fonts match equally well, so the priority decides.
+            for (priority, expected) in [(-1, &pt_sans), (1, &libertinus)] {
+                let built = FontBook::builder()
+                    .add(libertinus.clone(), priority)
+                    .add_variable(pt_sans.clone(), &[], Some("Libertinus Serif"))
+                    .build();
+                let world = with_book(world, built)
+                    .with_main("#set text(font: \"Libertinus Serif\")\nHello");
+                let Warned { output, .. } = typst::compile::<PagedDocument>(&world);
+                let used = output.ok().and_then(|doc| {
+                    doc.pages.first().and_then(|page| text_font(&page.frame))
+                });
+                test_eq!(sink, used.as_ref(), Some(expected));
+            }
+        }
+        "text-font-variable-pinned" => {
+            let wght = Tag::from_bytes(b"wght");
+            let Some(variable) = (0..).map_while(|i| world.font(i)).find(|font| {
+                font.ttf().variation_axes().into_iter().any(|axis| axis.tag == wght)
+            }) else {
+                writeln!(&mut sink, "no variable font with a weight axis").unwrap();
+                return sink;
+            };
+
+            let built = FontBook::builder()
+                .add_variable(variable.clone(), &[(wght, 700.0)], Some("Pinned"))
+                .build();
+            test_eq!(sink, built.0.problems().is_empty(), true);
+            let world =
+                with_book(world, built).with_main("#set text(font: \"Pinned\")\nHello");
+            let Warned { output, .. } = typst::compile::<PagedDocument>(&world);
+            let doc = output.ok();
+
+            // The instance is shaped with the pinned coordinates and its
+            // outlines are bolder than those of the default instance.
+            let pinned = doc
+                .as_ref()
+                .and_then(|doc| doc.pages.first())
+                .and_then(|page| text_font(&page.frame));
+            test_eq!(
+                sink,
+                pinned.as_ref().map(|font| font.variations().to_vec()),
+                Some(vec![(wght, 700.0)])
+            );
+            if let Some(pinned) = &pinned {
+                test_eq!(sink, pinned.info().variant.weight, FontWeight::BOLD);
+                let stem_width = |font: &Font| {
+                    let id = font.ttf().glyph_index('l')?;
+                    font.ttf().glyph_bounding_box(id).map(|bbox| bbox.width())
+                };
+                test_eq!(sink, stem_width(pinned) > stem_width(&variable), true);
+            }
+
+            // The PDF embeds the instanced outlines.
+            let pdf =
+                doc.and_then(|doc| typst_pdf::pdf(&doc, &PdfOptions::default()).ok());
+            test_eq!(sink, pdf.is_some(), true);
+        }
+        "text-number-width-synthesized" => {
+            let tnum = Tag::from_bytes(b"tnum");
+            let proportional = |font: &Font| {
+                let mut advances = ('0'..='9')
+                    .filter_map(|c| font.ttf().glyph_index(c))
+                    .filter_map(|id| font.advance(id.0));
+                let first = advances.next();
+                advances.any(|advance| Some(advance) != first)
+            };
+            let Some(plain) = (0..)
+                .map_while(|i| world.font(i))
+                .find(|font| !font.has_feature(tnum) && proportional(font))
+            else {
+                writeln!(&mut sink, "no font with only proportional figures").unwrap();
+                return sink;
+            };
+
+            let built =
+                FontBook::builder().add_variable(plain, &[], Some("Plain")).build();
+            let world = with_book(world, built).with_main(
+                "#set text(font: \"Plain\", number-width: \"tabular\")\n\
+                 #table(align: right, [11.10], [0.84], [100.01])",
+            );
+            let Warned { output, warnings } = typst::compile::<PagedDocument>(&world);
+            let messages: Vec<_> = warnings.iter().map(|w| w.message.as_str()).collect();
+            // The font is reported once for the whole document, at the
+            // first cell.
+            test_eq!(sink, messages, ["font \"Plain\" has no tabular figures"]);
+            test_eq!(sink, warnings.iter().all(|w| !w.span.is_detached()), true);
+            test_eq!(sink, warnings.iter().all(|w| w.occurrences >= 3), true);
+
+            // The widened digits align the decimal points of the right-aligned
+            // cells.
+            let points = output
+                .ok()
+                .and_then(|doc| doc.pages.first().cloned())
+                .map(|page| glyph_xs(&page.frame, Transform::identity(), '.'))
+                .unwrap_or_default();
+            test_eq!(sink, points.len(), 3);
+            if let Some(first) = points.first() {
+                for x in &points {
+                    test_eq!(sink, x.approx_eq(*first), true);
+                }
+            }
+        }
+        "show-text-merge-runs" => {
+            let runs = text_runs(doc);
+            let texts = runs
+                .first()
+                .map(|runs| runs.iter().map(|(s, _)| s.as_str()).collect::<Vec<_>>());
+            test_eq!(sink, texts, Some(vec!["H", "ello", " ", "world"]));
+        }
+        "link-map-rotated" => {
+            // The text of the link starts on the left edge of the rotated link
+            // area, in both the raster and the SVG coordinate space.
+            let Some(page) = doc.and_then(|doc| doc.pages.first()) else { return sink };
+            for (scale, regions) in [
+                (2.0, typst_render::link_map(page, 2.0)),
+                (1.0, typst_svg::link_map(page)),
+            ] {
+                test_eq!(sink, regions.len(), 1);
+                let Some(region) = regions.first() else { continue };
+                let [q0, q1, _, q3] = region.quad;
+                test_eq!(sink, (q0[1] - q1[1]).abs() > 1.0, true);
+
+                let ts = Transform::scale(Ratio::new(scale), Ratio::new(scale));
+                let Some(origin) = text_origin(&page.frame, ts) else { continue };
+                let (ex, ey) = (q3[0] - q0[0], q3[1] - q0[1]);
+                let (tx, ty) = (origin.x.to_pt() - q0[0], origin.y.to_pt() - q0[1]);
+                let t = (ex * tx + ey * ty) / (ex * ex + ey * ey);
+                test_eq!(sink, (ex * ty - ey * tx).abs() < 1e-6, true);
+                test_eq!(sink, (0.0..=1.0).contains(&t), true);
+            }
+        }
+        "image-scaling-pixelated" => {
+            // All exporters keep the hard edges of the checkerboard.
+            let Some(doc) = doc else { return sink };
+            let pdf = typst_pdf::pdf(doc, &PdfOptions::default()).unwrap_or_default();
+            let needle = b"/Interpolate false";
+            test_eq!(sink, pdf.windows(needle.len()).any(|w| w == needle), true);
+            let svg = typst_svg::svg(&doc.pages[0]);
+            test_eq!(sink, svg.contains("image-rendering: pixelated"), true);
+            let pixmap = typst_render::render(&doc.pages[0], 1.0);
+            let colors: BTreeSet<_> = pixmap
+                .pixels()
+                .iter()
+                .map(|p| (p.red(), p.green(), p.blue(), p.alpha()))
+                .collect();
+            test_eq!(
+                sink,
+                colors,
+                BTreeSet::from([(0, 0, 0, 255), (255, 255, 255, 255)])
+            );
+        }
+        "rect-transparent-pdfa" => {
+            let Some(doc) = doc else { return sink };
+            let standards = PdfStandards::new(&[PdfStandard::A_1b]).unwrap();
+            let options = PdfOptions { standards, ..Default::default() };
+            let errors = typst_pdf::pdf(doc, &options).err().unwrap_or_default();
+            test_eq!(sink, errors.len(), 1);
+            if let (Some(error), Ok(source)) =
+                (errors.first(), world.source(world.main()))
+            {
+                let text = source.range(error.span).map(|range| &source.text()[range]);
+                test_eq!(sink, text, Some("rect(fill: red.transparentize(50%))"));
+                test_eq!(sink, error.message.contains("PDF/A-1b"), true);
+            }
+            test_eq!(sink, typst_pdf::pdf(doc, &PdfOptions::default()).is_ok(), true);
+
+            // With flattening, the rectangle is composited over the page, so
+            // the same document passes as a PDF/A-1b file.
+            let standards = PdfStandards::new(&[PdfStandard::A_1b]).unwrap();
+            let options = PdfOptions {
+                standards,
+                flatten_transparency: true,
+                ..Default::default()
+            };
+            let Warned { output, warnings } = typst_pdf::pdf_with_warnings(doc, &options);
+            test_eq!(
+                sink,
+                warnings.iter().map(|w| w.message.as_str()).collect::<Vec<_>>(),
+                ["PDF/A-1b document has no title"]
+            );
+            let pdf = String::from_utf8_lossy(&output.unwrap_or_default()).into_owned();
+            test_eq!(sink, pdf.starts_with("%PDF-1.4"), true);
+            test_eq!(sink, pdf.contains("<pdfaid:part>1</pdfaid:part>"), true);
+        }
+        "link-svg-merged" => {
+            let Some(doc) = doc else { return sink };
+            let svg = typst_svg::svg_merged(doc, Abs::pt(5.0));
+            test_eq!(sink, svg.contains(r#"<g id="page-2""#), true);
+            test_eq!(sink, svg.matches(r##"xlink:href="#page-2""##).count(), 1);
+
+            // Viewers that support view fragments go to the heading, which is
+            // on the second page, 5pt + 60pt + 5pt from the top of the SVG.
+            let view: Vec<f64> = svg
+                .split_once(r##" href="#svgView(viewBox("##)
+                .and_then(|(_, rest)| rest.split_once(")"))
+                .map(|(view, _)| view.split(',').filter_map(|v| v.parse().ok()).collect())
+                .unwrap_or_default();
+            test_eq!(sink, view.len(), 4);
+            if let [x, y, ..] = view[..] {
+                test_eq!(sink, (5.0..125.0).contains(&x), true);
+                test_eq!(sink, (70.0..130.0).contains(&y), true);
+            }
+        }
+        "query-extract-metadata" => {
+            let Warned { output, warnings } = typst::extract_metadata(world);
+            let values: Vec<_> = output
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(label, value)| (label.resolve().to_string(), value))
+                .collect();
+            test_eq!(
+                sink,
+                values,
+                [
+                    ("a".to_string(), Value::Int(1)),
+                    ("b".to_string(), Value::Str("two".into()))
+                ]
+            );
+            let messages: Vec<_> = warnings.iter().map(|w| w.message.as_str()).collect();
+            test_eq!(
+                sink,
+                messages,
+                ["metadata in context expressions cannot be extracted without layout"]
+            );
+        }
+        "document-prewarm" => {
+            // Counts what a step adds to the cache. The step runs on a
+            // dedicated thread pool so that concurrent tests are not counted.
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+            let count = |step: &(dyn Fn() + Sync)| {
+                pool.install(|| typst::count_cache_additions(step).1)
+            };
+
+            // Documents that no other test compiles, so that they are not
+            // cached yet.
+            let variant = |heading: &str| {
+                let text = format!("{}\n= {heading}", test.source.text());
+                world.clone().with_main(&text)
+            };
+            let compile = |world: &TestWorld| {
+                typst::compile::<PagedDocument>(world).output.unwrap();
+            };
+
+            // Without layout, evaluation and show rules are cached: The
+            // module, the show rule, and the function it calls.
+            let world = variant("Evaluated");
+            let main = [world.main()];
+            let warm = count(&|| typst::prewarm(&world, &main, false));
+            test_eq!(sink, (warm.modules, warm.closures, warm.layouts), (1, 2, 0));
+            let stats = count(&|| compile(&world));
+            test_eq!(sink, (stats.modules, stats.closures), (0, 0));
+            test_eq!(sink, stats.layouts > 0, true);
+
+            // With layout, the compilation is served from the cache.
+            let world = variant("Laid out");
+            let main = [world.main()];
+            let warm = count(&|| typst::prewarm(&world, &main, true));
+            test_eq!(sink, warm.layouts > 0, true);
+            test_eq!(sink, count(&|| compile(&world)).total(), 0);
+
+            // A document that was not prewarmed is compiled from scratch.
+            let world = variant("Cold");
+            test_eq!(sink, count(&|| compile(&world)).modules, 1);
+        }
+        "show-native-rule" => {
+            let world = with_strong_rule(world, upper_strong);
+            let doc = typst::compile::<PagedDocument>(&world).output.ok();
+            let text: String = text_runs(doc.as_ref())
+                .into_iter()
+                .flatten()
+                .map(|(text, _)| text)
+                .collect();
+            // The native rule replaces the built-in one, but not a user-defined
+            // show rule.
+            test_eq!(sink, text.contains("WORLD"), true);
+            test_eq!(sink, text.contains("again"), true);
+        }
+        "grid-cell-panic-context" => {
+            // Compile on a dedicated thread pool, so that the page runs, which
+            // are laid out in parallel, can end up on its idle second thread.
+            let world = with_strong_rule(world, panicking_strong);
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+            let (result, context) = pool.install(|| {
+                let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    typst::compile::<PagedDocument>(&world)
+                }));
+                (result.is_err(), typst::take_panic_context())
+            });
+            test_eq!(sink, result, true);
+
+            let Some(context) = context else {
+                writeln!(&mut sink, "panic context was not captured").unwrap();
+                return sink;
+            };
+            test_eq!(sink, context.message.as_str(), "deliberate panic");
+
+            // The cell that contains the panicking element should be reported.
+            let cell = context.scopes.iter().find(|(_, kind)| *kind == "grid cell");
+            let source = world.source(world.main()).unwrap();
+            let text = cell
+                .and_then(|&(span, _)| world.range(span))
+                .map(|range| &source.text()[range]);
+            test_eq!(sink, text, Some("*boom*"));
+            test_eq!(sink, context.span().is_some(), true);
+
+            // The scopes of the thread that spawned the page runs must be
+            // carried over if the cell was laid out on another thread.
+            let outermost = context.scopes.last().map(|&(_, kind)| kind);
+            test_eq!(sink, outermost, Some("document"));
+        }
+        "heading-basic" => {
+            let heading = |level| FrameRole::Heading(NonZeroUsize::new(level).unwrap());
+            test_eq!(sink, roles(doc), [1, 2, 3, 11].map(|level| (0, heading(level))));
+        }
+        "figure-table" => {
+            let cell = |x, y| FrameRole::TableCell { x, y };
+            test_eq!(
+                sink,
+                roles(doc),
+                [(0, cell(0, 0)), (0, cell(1, 0)), (0, FrameRole::Caption)]
+            );
+        }
+        "footnote-duplicate" => {
+            // The footnote markers are links, which are nested in the list
+            // items and cells that contain them.
+            let (links, others): (Vec<_>, Vec<_>) =
+                roles(doc).into_iter().partition(|&(_, role)| role == FrameRole::Link);
+            let cell = |x, y| FrameRole::TableCell { x, y };
+            test_eq!(
+                sink,
+                others,
+                [
+                    (0, FrameRole::ListItem),
+                    (0, FrameRole::ListItem),
+                    (0, FrameRole::ListItem),
+                    (0, FrameRole::ListItem),
+                    (0, cell(0, 0)),
+                    (0, cell(1, 0)),
+                    (0, cell(0, 1)),
+                    (0, cell(1, 1)),
+                ]
+            );
+            test_eq!(sink, links.iter().filter(|&&(depth, _)| depth == 1).count(), 6);
+        }
+        "page-marginals" => {
+            // The background, header, and footer of the first page.
+            let first = doc.and_then(|doc| doc.pages.first());
+            test_eq!(
+                sink,
+                first.map(|page| frame_roles(&page.frame)),
+                Some(vec![(0, FrameRole::Artifact); 3])
+            );
+        }
+        "link-basic" => {
+            // Each link is marked on each of its lines.
+            let roles = roles(doc);
+            test_eq!(sink, roles.len() >= 5, true);
+            test_eq!(sink, roles.iter().all(|&role| role == (0, FrameRole::Link)), true);
+        }
+        "stroke-degenerate-export" => {
+            let text = "#rect(width: 0pt, height: 0pt, fill: red, stroke: blue)\n\
+                        #rect(width: 1e-10pt, height: 0pt, fill: red)\n\
+                        #box(width: 0pt, stroke: (dash: \"dashed\"))[x]\n\
+                        #line(length: 0pt)\n\
+                        #line(length: 10pt, stroke: (dash: (0pt, 0pt)))\n\
+                        #curve()\n\
+                        #polygon(fill: red)\n\
+                        #box(width: 0pt, height: 0pt, clip: true)[Hidden]\n\
+                        #box(width: 0pt, height: 0pt)[Overflow]\n\
+                        #scale(x: 0%, reflow: false)[Flat]\n\
+                        #rect(width: 10pt, height: 10pt, fill: gradient.linear(red, blue))";
+
+            let world = world.clone().with_main(text);
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "degenerate shapes failed to compile").unwrap();
+                return sink;
+            };
+
+            let page = &doc.pages[0];
+            typst_render::render(page, 1.0);
+
+            let svg = typst_svg::svg(page);
+            let non_finite = svg
+                .split(|c: char| !c.is_ascii_alphabetic())
+                .any(|word| word == "NaN" || word == "inf");
+            test_eq!(sink, non_finite, false);
+            test_eq!(sink, svg.contains("d=\"\""), false);
+            test_eq!(sink, svg.contains("stroke-dasharray=\"0 0\""), false);
+
+            test_eq!(sink, typst_pdf::pdf(&doc, &PdfOptions::default()).is_ok(), true);
+
+            // Pages with a negative size are laid out with zero size. The
+            // exporters assert that they never see negative sizes.
+            let text = "#set page(width: -10pt, height: 20pt, margin: 0pt)\nNegative";
+            let world = world.clone().with_main(text);
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "negative page failed to compile").unwrap();
+                return sink;
+            };
+
+            let page = &doc.pages[0];
+            test_eq!(sink, page.frame.size(), Size::new(Abs::zero(), Abs::pt(20.0)));
+            typst_render::render(page, 1.0);
+            test_eq!(sink, typst_svg::svg(page).contains("width=\"0pt\""), true);
+            test_eq!(sink, typst_pdf::pdf(&doc, &PdfOptions::default()).is_ok(), true);
+        }
+        "page-bleed-boxes" => {
+            let text = "#set page(width: 100pt, height: 100pt, bleed: 3mm, fill: red)";
+            let world = world.clone().with_main(text);
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "page with bleed failed to compile").unwrap();
+                return sink;
+            };
+
+            let pdf = typst_pdf::pdf(&doc, &PdfOptions::default()).unwrap();
+            let pdf = String::from_utf8_lossy(&pdf);
+            let find = |key: &str| -> Option<Vec<f64>> {
+                let pattern =
+                    regex::Regex::new(&format!(r"/{key} \[([^\]]*)\]")).unwrap();
+                let captures = pattern.captures(&pdf)?;
+                captures[1].split_whitespace().map(|n| n.parse().ok()).collect()
+            };
+
+            let (Some(media), Some(bleed), Some(trim)) =
+                (find("MediaBox"), find("BleedBox"), find("TrimBox"))
+            else {
+                writeln!(&mut sink, "page boxes are missing").unwrap();
+                return sink;
+            };
+
+            let six_mm = Abs::mm(6.0).to_pt();
+            let close = |a: f64, b: f64| (a - b).abs() < 1e-3;
+            test_eq!(sink, trim, vec![0.0, 0.0, 100.0, 100.0]);
+            test_eq!(sink, bleed, media);
+            test_eq!(
+                sink,
+                close((media[2] - media[0]) - (trim[2] - trim[0]), six_mm),
+                true
+            );
+            test_eq!(
+                sink,
+                close((media[3] - media[1]) - (trim[3] - trim[1]), six_mm),
+                true
+            );
+
+            // The fill covers the bleed up to the edges of the media box.
+            let page = &doc.pages[0];
+            let pixmap = typst_render::render_bleed(page, 1.0);
+            test_eq!(sink, pixmap.width(), (100.0 + six_mm).round() as u32);
+            for (x, y) in [(0, 0), (pixmap.width() - 1, pixmap.height() - 1)] {
+                let pixel = pixmap.pixel(x, y).unwrap();
+                test_eq!(sink, (pixel.red(), pixel.green(), pixel.blue()), (255, 65, 54));
+            }
+
+            let svg = typst_svg::svg_bleed(page);
+            test_eq!(sink, svg.contains("viewBox=\"0 0 117."), true);
+            test_eq!(sink, typst_render::render(page, 1.0).width(), 100);
+        }
+        "render-grayscale" => {
+            let world = world.clone().with_main(
+                "#set page(width: 100pt, height: 60pt, margin: 0pt)\n\
+                 #place(top, block(inset: 4pt, text(16pt)[Hamburg]))\n\
+                 #place(bottom, rect(\n\
+                   width: 100%, height: 30pt, fill: gradient.linear(black, white),\n\
+                 ))",
+            );
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "failed to compile").unwrap();
+                return sink;
+            };
+
+            let page = &doc.pages[0];
+            let render = |dither| {
+                let options = GrayscaleOptions { levels: 16, dither };
+                typst_render::render_grayscale(page, 2.0, options)
+            };
+            let plain = render(Dither::None);
+            let dithered = render(Dither::FloydSteinberg);
+
+            // Two pixels of four bits each fit into a byte.
+            test_eq!(sink, (plain.width, plain.height), (200, 120));
+            test_eq!(sink, plain.data.len(), 100 * 120);
+            test_eq!(
+                sink,
+                typst_render::to_grayscale(
+                    &typst_render::render(page, 2.0),
+                    16,
+                    Dither::None
+                ),
+                plain.data
+            );
+
+            // The gradient spans all levels.
+            let levels: BTreeSet<_> = (0..plain.height)
+                .flat_map(|y| (0..plain.width).map(move |x| (x, y)))
+                .filter_map(|(x, y)| plain.get(x, y))
+                .collect();
+            test_eq!(sink, levels.len(), 16);
+
+            // Dithering leaves the text in the upper half alone and only
+            // changes the gradient in the lower half.
+            let rows = |image: &GrayImage, rows: Range<usize>| {
+                image.data[rows.start * 100..rows.end * 100].to_vec()
+            };
+            test_eq!(sink, rows(&plain, 0..60) == rows(&dithered, 0..60), true);
+            test_eq!(sink, rows(&plain, 60..120) != rows(&dithered, 60..120), true);
+            test_eq!(sink, rows(&plain, 0..60).iter().any(|&b| b != 0xff), true);
+        }
+        "content-builder" => {
+            // A two-section document built from Rust is laid out without any
+            // markup being parsed.
+            let content = ContentBuilder::new()
+                .heading(1, "Sales")
+                .par("In *units*.")
+                .table([["Region", "Q1", "Q2"], ["North", "12", "15"]])
+                .styled(|styles| styles.set(TextElem::set_fill(Color::RED.into())))
+                .heading(1, "Costs")
+                .table([vec!["Rent", "3"], vec!["Power"]])
+                .build();
+
+            let Warned { output, warnings } =
+                typst::compile_content::<PagedDocument>(world, &content);
+            test_eq!(sink, warnings.len(), 0);
+            let Ok(doc) = output else {
+                writeln!(&mut sink, "built content failed to lay out").unwrap();
+                return sink;
+            };
+
+            test_eq!(
+                sink,
+                text_lines_of(&doc.pages[0].frame),
+                [
+                    "Sales",
+                    "In *units*.",
+                    "RegionQ1Q2",
+                    "North1215",
+                    "Costs",
+                    "Rent3",
+                    "Power",
+                ]
+            );
+            test_eq!(
+                sink,
+                doc.introspector.query(&HeadingElem::elem().select()).len(),
+                2
+            );
+            let fill = |needle: &str| {
+                text_runs(Some(&doc))
+                    .into_iter()
+                    .flatten()
+                    .find(|(text, _)| text == needle)
+                    .map(|(_, fill)| fill)
+            };
+            test_eq!(sink, fill("Sales"), Some(Paint::from(Color::BLACK)));
+            test_eq!(sink, fill("Costs"), Some(Paint::from(Color::RED)));
+        }
+        "svg-selectable-text-exact" => {
+            let world = world.clone().with_main("fish");
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "ligature text failed to compile").unwrap();
+                return sink;
+            };
+
+            let page = &doc.pages[0];
+            let Some(item) = first_text_item(&page.frame) else {
+                writeln!(&mut sink, "no text item found").unwrap();
+                return sink;
+            };
+
+            // The "fi" ligature makes for fewer clusters than characters.
+            let mut ranges: Vec<_> = item.glyphs.iter().map(|g| g.range()).collect();
+            ranges.dedup();
+            test_eq!(sink, ranges.len(), 3);
+
+            let options = SvgOptions {
+                selectable_text: true,
+                glyph_positioning: GlyphPositioning::Exact,
+                ..SvgOptions::default()
+            };
+            let svg = typst_svg::svg_with_options(page, &options);
+            let re = regex::Regex::new(r#"<tspan x="([^"]*)">([^<]*)</tspan>"#).unwrap();
+            let mut xs = 0;
+            let mut content = String::new();
+            for caps in re.captures_iter(&svg) {
+                xs += caps[1].split(' ').count();
+                content.push_str(&caps[2]);
+            }
+            test_eq!(sink, xs, ranges.len());
+            test_eq!(sink, content, "fish");
+            test_eq!(sink, svg.contains(r#"<tspan x="0">fi</tspan>"#), true);
+
+            let options = SvgOptions { selectable_text: true, ..SvgOptions::default() };
+            let svg = typst_svg::svg_with_options(page, &options);
+            test_eq!(sink, svg.contains("<tspan"), false);
+            test_eq!(sink, svg.contains(">fish</text>"), true);
+            test_eq!(sink, typst_svg::svg(page).contains("typst-selectable"), false);
+        }
+        "warning-dedup" => {
+            // Identical warnings are reported once with their count.
+            let repeated = world.clone().with_main("#for i in range(100) [**]");
+            let warnings = typst::compile::<PagedDocument>(&repeated).warnings;
+            test_eq!(sink, warnings.len(), 1);
+            if let Some(warning) = warnings.first() {
+                test_eq!(sink, warning.occurrences, 100);
+                test_eq!(
+                    sink,
+                    warning.message_with_occurrences(),
+                    "no text within stars (repeated 100 times)"
+                );
+            }
+
+            // Warnings of the same kind at different places are capped.
+            let text = "#text(font: \"nope\")[A]\n".repeat(30);
+            let world = world.clone().with_main(&text);
+            let warnings = |limit| {
+                let options =
+                    CompileOptions { max_warnings_per_code: limit, ..Default::default() };
+                typst::compile_with_options::<PagedDocument>(&world, &options)
+                    .0
+                    .warnings
+            };
+
+            let capped = warnings(None);
+            test_eq!(sink, capped.len(), 21);
+            test_eq!(sink, capped.iter().all(|w| w.occurrences == 1), true);
+            if let Some(summary) = capped.last() {
+                test_eq!(
+                    sink,
+                    summary.message,
+                    "10 more warnings of the kind `unknown-font-family` were suppressed"
+                );
+            }
+
+            test_eq!(sink, warnings(Some(5)).len(), 6);
+            test_eq!(sink, warnings(Some(usize::MAX)).len(), 30);
+        }
+        "deprecation-policy" => {
+            // Returns whether compilation succeeded, together with the
+            // severities of the diagnostics in the package and in the main
+            // file.
+            let compile = |world: &TestWorld, policy, allow_past_due| {
+                let options = CompileOptions {
+                    deprecation_policy: policy,
+                    allow_past_due_deprecations: allow_past_due,
+                    ..CompileOptions::default()
+                };
+                let Warned { output, warnings } =
+                    typst::compile_with_options::<PagedDocument>(world, &options).0;
+                let diags = match &output {
+                    Ok(_) => warnings,
+                    Err(errors) => errors.clone(),
+                };
+                let severities = |package: bool| {
+                    diags
+                        .iter()
+                        .filter(|diag| {
+                            diag.span.id().is_some_and(|id| id.package().is_some())
+                                == package
+                        })
+                        .map(|diag| diag.severity)
+                        .collect::<Vec<_>>()
+                };
+                (output.is_ok(), severities(true), severities(false))
+            };
+
+            let (warning, error) = (Severity::Warning, Severity::Error);
+            let text = "#import \"@test/legacy:0.1.0\": stripes\n\
+                        #let own = pattern(size: (4pt, 4pt), line(end: (100%, 100%)))\n\
+                        #rect(fill: stripes)\n\
+                        #rect(fill: own)";
+            let world = world.clone().with_main(text);
+            test_eq!(
+                sink,
+                compile(&world, DeprecationPolicy::Warn, false),
+                (true, vec![warning], vec![warning])
+            );
+            test_eq!(
+                sink,
+                compile(&world, DeprecationPolicy::Error, false),
+                (false, vec![error], vec![error])
+            );
+            test_eq!(
+                sink,
+                compile(&world, DeprecationPolicy::AllowInPackages, false),
+                (true, vec![], vec![warning])
+            );
+
+            // Uses of definitions that are past their removal version are
+            // errors regardless of the policy, unless explicitly allowed.
+            let world = world.clone().with_main("#old").with_library(|library| {
+                let scope = library.global.scope_mut();
+                scope.define("old", 1);
+                scope.deprecate(
+                    "old",
+                    Deprecation::new("`old` is deprecated").with_until("0.1.0"),
+                );
+            });
+            test_eq!(
+                sink,
+                compile(&world, DeprecationPolicy::AllowInPackages, false),
+                (false, vec![], vec![error])
+            );
+            test_eq!(
+                sink,
+                compile(&world, DeprecationPolicy::AllowInPackages, true),
+                (true, vec![], vec![warning])
+            );
+        }
+        "metrics-line-query" => {
+            let text = "First \\ Second \\ Third \\ Fourth\n#pagebreak()\nFifth";
+            let lines = |features: &[Feature]| {
+                let world = world.clone().with_main(text).with_features(features);
+                let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok()
+                else {
+                    return vec![];
+                };
+                let introspector = &doc.introspector;
+                introspector
+                    .query(&MetricsLine::elem().select())
+                    .iter()
+                    .map(|elem| {
+                        let line = elem.to_packed::<MetricsLine>().unwrap();
+                        let pos =
+                            introspector.position(elem.location().unwrap()).unwrap();
+                        let range = line.start().zip(*line.end());
+                        let source =
+                            range.map_or("", |(start, end)| text[start..end].trim());
+                        (pos.page.get(), pos.point.y, source.to_string())
+                    })
+                    .collect()
+            };
+
+            test_eq!(sink, lines(&[]).len(), 0);
+
+            let lines = lines(&[Feature::LayoutMetrics]);
+            let pages: Vec<_> = lines.iter().map(|(page, ..)| *page).collect();
+            test_eq!(sink, pages, vec![1, 1, 1, 1, 2]);
+            let sources: Vec<_> =
+                lines.iter().map(|(.., source)| source.as_str()).collect();
+            test_eq!(sink, sources, vec!["First", "Second", "Third", "Fourth", "Fifth"]);
+            let increasing =
+                lines[..4.min(lines.len())].windows(2).all(|w| w[0].1 < w[1].1);
+            test_eq!(sink, increasing, true);
+
+            let world =
+                world.clone().with_main(text).with_features(&[Feature::LayoutMetrics]);
+            let breaks = typst::compile::<PagedDocument>(&world).output.ok().map(|doc| {
+                doc.introspector
+                    .query(&MetricsPageBreak::elem().select())
+                    .iter()
+                    .filter_map(|elem| {
+                        doc.introspector.position(elem.location().unwrap())
+                    })
+                    .map(|pos| (pos.page.get(), pos.point))
+                    .collect::<Vec<_>>()
+            });
+            test_eq!(sink, breaks, Some(vec![(2, Point::zero())]));
+        }
+        "metrics-line-numbers" => {
+            // Legal-style line numbers in the margin, placed on the baselines
+            // of the lines.
+            let text = "#set page(margin: (left: 20pt), foreground: {\n\
+                          set metrics.line(record: false)\n\
+                          context for (i, line) in query(metrics.line).enumerate() {\n\
+                            let pos = line.location().position()\n\
+                            if pos.page == here().page() {\n\
+                              let number = text(7pt, top-edge: \"baseline\")[#(i + 1)]\n\
+                              place(dx: 5pt, dy: pos.y + line.baseline, number)\n\
+                            }\n\
+                          }\n\
+                        })\n\
+                        First \\ Second \\ Third \\ Fourth";
+
+            let world =
+                world.clone().with_main(text).with_features(&[Feature::LayoutMetrics]);
+            let Warned { output, warnings } = typst::compile::<PagedDocument>(&world);
+            test_eq!(sink, warnings.len(), 0);
+            let lines = output
+                .ok()
+                .and_then(|doc| doc.pages.first().map(|page| text_lines_of(&page.frame)))
+                .unwrap_or_default();
+            test_eq!(sink, lines, ["1First", "2Second", "3Third", "4Fourth"]);
+        }
+        "package-lock" => {
+            // The package's entrypoint can be tampered with.
+            let spec: PackageSpec = "@test/adder:0.1.0".parse().unwrap();
+            let package = |tampered: bool| {
+                let world = world
+                    .clone()
+                    .with_main("#import \"@test/adder:0.1.0\": add\n#add(1, 2)");
+                if !tampered {
+                    return world;
+                }
+                let lib = FileId::new(Some(spec.clone()), VirtualPath::new("lib.typ"));
+                world.with_source(lib, "#let add(x, y) = x - y")
+            };
+            let compile = |tampered: bool, lock: &PackageLock| {
+                let world = package(tampered);
+                let options = CompileOptions {
+                    package_lock: Some(lock.clone()),
+                    ..CompileOptions::default()
+                };
+                typst::compile_with_options::<PagedDocument>(&world, &options)
+                    .0
+                    .output
+                    .map(|_| ())
+                    .map_err(|errors| errors[0].message.clone())
+            };
+
+            // A package that is missing from the lock is rejected.
+            let mut lock = PackageLock::new();
+            let missing = compile(false, &lock).unwrap_err();
+            test_eq!(sink, missing.contains("@test/adder:0.1.0 is not in the"), true);
+
+            // A recorded lock matches the package.
+            lock.record(&package(false), [&spec]).unwrap();
+            test_eq!(sink, compile(false, &lock), Ok(()));
+
+            // A changed package is rejected, naming both hashes.
+            let expected = lock.get(&spec).unwrap();
+            let found = PackageHash::compute(&package(true), &spec).unwrap();
+            test_eq!(sink, expected == found, false);
+            test_eq!(
+                sink,
+                compile(true, &lock),
+                Err(eco_format!(
+                    "package @test/adder:0.1.0 does not match the package lock \
+                     (expected hash {expected}, found {found})"
+                ))
+            );
+        }
+        "plugin-host-info" => {
+            let text = "#let p = plugin(\"/host-info.wasm\")\n\
+                        #context json.decode(p.host_info()).target";
+
+            let world = world
+                .clone()
+                .with_main(text)
+                .with_file("/host-info.wasm", host_info_plugin());
+            let doc = typst::compile::<PagedDocument>(&world).output.ok();
+            let lines = doc.map(|doc| text_lines_of(&doc.pages[0].frame));
+            test_eq!(sink, lines, Some(vec!["paged".to_string()]));
+
+            let world = world
+                .clone()
+                .with_main(text)
+                .with_features(&[Feature::Html])
+                .with_file("/host-info.wasm", host_info_plugin());
+            let doc = typst::compile::<HtmlDocument>(&world).output.ok();
+            let html = doc.and_then(|doc| typst_html::html(&doc).ok());
+            let has_line = |html: String| html.lines().any(|line| line.trim() == "html");
+            test_eq!(sink, html.is_some_and(has_line), true);
+        }
+        "introspector-slim" => {
+            let text = "= Intro\n\
+                        Text#footnote[Note] #metadata(1) #metadata(2)\n\
+                        #figure(rect(), caption: [Box]) <fig>\n\
+                        #pagebreak()\n\
+                        = Details <details>\n\
+                        #metadata(3)";
+
+            let world = world.clone().with_main(text);
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "document failed to compile").unwrap();
+                return sink;
+            };
+
+            let keep =
+                |elem: &Content| elem.is::<HeadingElem>() || elem.label().is_some();
+            let full = &doc.introspector;
+            let slim = full.slim(&keep);
+            test_eq!(sink, full.is_slim(), false);
+            test_eq!(sink, slim.is_slim(), true);
+
+            // Only the headings and the labelled figure are retained.
+            let retained = slim.all().count();
+            test_eq!(sink, retained, full.all().filter(|elem| keep(elem)).count());
+            test_eq!(sink, retained, 3);
+            test_eq!(sink, full.all().count() > retained, true);
+
+            // The outline and labels resolve like before.
+            let headings = HeadingElem::elem().select();
+            test_eq!(sink, slim.query(&headings), full.query(&headings));
+            for (name, page) in [("fig", 1), ("details", 2)] {
+                let label = Label::new(PicoStr::intern(name));
+                let loc = slim.query_label(label).ok().and_then(Content::location);
+                test_eq!(sink, loc.map(|loc| slim.page(loc).get()), Some(page));
+            }
+
+            // Dropped elements are not found and have no position.
+            let metadata = full.query(&MetadataElem::elem().select());
+            test_eq!(sink, slim.query(&MetadataElem::elem().select()).len(), 0);
+            let loc = metadata.first().and_then(Content::location);
+            test_eq!(sink, loc.map(|loc| full.position(loc).is_some()), Some(true));
+            test_eq!(sink, loc.map(|loc| slim.position(loc).is_none()), Some(true));
+        }
+        "image-rasterize" => {
+            let clean = "<svg xmlns='http://www.w3.org/2000/svg' width='20' height='10'>\
+                         <rect width='20' height='10'/></svg>";
+            let filtered = "<svg xmlns='http://www.w3.org/2000/svg' width='20' height='10'>\
+                            <filter id='f'><feBlend in='SourceGraphic' in2='BackgroundImage'/>\
+                            </filter><rect width='20' height='10' filter='url(#f)'/></svg>";
+
+            let compile = |text: String| {
+                let world = world.clone().with_main(&text);
+                let Warned { output, warnings } = typst::compile::<PagedDocument>(&world);
+                let images = output
+                    .map(|doc| image_items(&doc.pages[0].frame))
+                    .unwrap_or_default();
+                (images, warnings)
+            };
+            let raster_size = |image: &Image| match image.kind() {
+                ImageKind::Raster(raster) => Some((raster.width(), raster.height())),
+                ImageKind::Svg(_) => None,
+            };
+
+            // An unsupported filter input is reported and rasterized.
+            let (images, warnings) = compile(format!("#image.decode(\"{filtered}\")"));
+            test_eq!(sink, images.len(), 1);
+            test_eq!(sink, images.iter().all(|image| raster_size(image).is_some()), true);
+            test_eq!(sink, warnings.len(), 1);
+            test_eq!(
+                sink,
+                warnings.iter().all(|w| w.message.contains("`BackgroundImage`")),
+                true
+            );
+
+            // A clean SVG stays vector graphics.
+            let (images, warnings) = compile(format!("#image.decode(\"{clean}\")"));
+            test_eq!(sink, images.len(), 1);
+            test_eq!(sink, images.iter().all(|image| raster_size(image).is_none()), true);
+            test_eq!(sink, warnings.len(), 0);
+
+            // A fixed resolution forces rasterization at the image's size.
+            let (images, _) = compile(format!(
+                "#set image(rasterize: 144)\n#image.decode(\"{clean}\", width: 2in)"
+            ));
+            test_eq!(sink, images.first().and_then(raster_size), Some((288, 144)));
+        }
+        "html-rewrite" => {
+            let text = "#html.elem(\"style\")[p { color: red; }]\n\
+                        #html.elem(\"img\", attrs: (src: \"logo.png\", alt: \"Logo\"))\n\
+                        #html.elem(\"img\", attrs: (src: \"photo.jpg\"))";
+
+            let world = world.clone().with_main(text).with_features(&[Feature::Html]);
+            let Some(mut doc) = typst::compile::<HtmlDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "HTML document failed to compile").unwrap();
+                return sink;
+            };
+
+            doc.rewrite(|element| {
+                if element.tag == tag::img {
+                    if let Some(src) = element.attrs.get(attr::src) {
+                        let src = eco_format!("https://cdn.example.com/{src}");
+                        element.attrs.set(attr::src, src);
+                    }
+                } else if element.tag == tag::style {
+                    element.attrs.set(attr::nonce, "r4nd0m");
+                }
+            });
+
+            let html = typst_html::html(&doc).ok();
+            let contains =
+                |needle: &str| html.as_ref().is_some_and(|h| h.contains(needle));
+            test_eq!(
+                sink,
+                contains("<img src=\"https://cdn.example.com/logo.png\" alt=\"Logo\">"),
+                true
+            );
+            test_eq!(
+                sink,
+                contains("<img src=\"https://cdn.example.com/photo.jpg\">"),
+                true
+            );
+            test_eq!(sink, contains("<style nonce=\"r4nd0m\">"), true);
+            test_eq!(sink, html.map(|h| h.matches(" src=").count()), Some(2));
+        }
+        "glyph-inventory" => {
+            let text = "#set page(width: 80pt)\n\
+                        The office finds #text(fill: red)[fluffy] files.\n\
+                        #pagebreak()\n\
+                        = Second";
+
+            let world = world.clone().with_main(text);
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "document failed to compile").unwrap();
+                return sink;
+            };
+
+            let inventory = typst::glyph_inventory(&doc);
+            test_eq!(sink, inventory.len(), 2);
+
+            // Without the spaces at line breaks, the source text of the
+            // records is the text of the source.
+            let texts: Vec<String> = inventory
+                .iter()
+                .map(|page| {
+                    let text: String =
+                        page.glyphs.iter().map(|glyph| glyph.text.as_str()).collect();
+                    text.split_whitespace().collect()
+                })
+                .collect();
+            test_eq!(sink, texts, ["Theofficefindsfluffyfiles.", "Second"]);
+            test_eq!(sink, inventory[1].page, 1);
+
+            // Ligatures stand for multiple characters and aren't the glyph of
+            // their first character.
+            let f = world
+                .book()
+                .select("libertinus serif", FontVariant::default())
+                .and_then(|id| world.font(id))
+                .and_then(|font| font.ttf().glyph_index('f'))
+                .map(|id| id.0);
+            for ligature in ["ffi", "ffl", "fi"] {
+                let glyph = inventory[0].glyphs.iter().find(|g| g.text == ligature);
+                test_eq!(
+                    sink,
+                    (ligature, glyph.map(|g| Some(g.glyph) != f)),
+                    (ligature, Some(true))
+                );
+            }
+
+            // The first glyph sits at the left margin, which is a fraction of
+            // the page width, and the glyphs of the first line follow each
+            // other.
+            let round = |v: f64| (v * 1000.0).round() / 1000.0;
+            let first = &inventory[0].glyphs[0];
+            test_eq!(
+                sink,
+                (first.text.as_str(), round(first.x)),
+                ("T", round(80.0 * 2.5 / 21.0))
+            );
+            let line: Vec<_> =
+                inventory[0].glyphs.iter().take_while(|g| g.y == first.y).collect();
+            let follow =
+                line.windows(2).all(|w| round(w[0].x + w[0].advance) <= round(w[1].x));
+            test_eq!(sink, (line.len() > 3, follow), (true, true));
+
+            // The heading on the second page has a larger size and a font
+            // from the same family.
+            let heading = &inventory[1].glyphs[0];
+            test_eq!(sink, heading.size > first.size, true);
+            test_eq!(sink, &heading.font.family, &first.font.family);
+        }
+        "text-font-embedding-restricted" => {
+            let id = world.book().select("libertinus serif", FontVariant::default());
+            let data = id.and_then(|id| world.font(id)).unwrap().data().to_vec();
+
+            // Compiles a document with a copy of the font with the given
+            // embedding bits and exports it to PDF.
+            let export = |fs_type: u16, ignore: bool| {
+                let font = Font::new(Bytes::from(with_fs_type(&data, fs_type)), 0)?;
+                let permissions = font.embedding_permissions();
+                let built = FontBook::builder().add(font, 0).build();
+                let world = with_book(world, built).with_main("Hello");
+                let doc = typst::compile::<PagedDocument>(&world).output.ok()?;
+                let options = PdfOptions {
+                    ignore_embedding_restrictions: ignore,
+                    ..Default::default()
+                };
+                let Warned { output, warnings } =
+                    typst_pdf::pdf_with_warnings(&doc, &options);
+                let messages = |diags: &[SourceDiagnostic]| {
+                    diags.iter().map(|diag| diag.message.to_string()).collect::<Vec<_>>()
+                };
+                let errors = output.err().map(|errors| messages(&errors));
+                Some((permissions, errors, messages(&warnings)))
+            };
+
+            test_eq!(
+                sink,
+                export(0x0002, false),
+                Some((
+                    EmbeddingPermissions::NoEmbedding,
+                    Some(vec![
+                        "font Libertinus Serif on page 1 may not be embedded".to_string()
+                    ]),
+                    vec![]
+                ))
+            );
+            test_eq!(
+                sink,
+                export(0x0002, true),
+                Some((EmbeddingPermissions::NoEmbedding, None, vec![]))
+            );
+            test_eq!(
+                sink,
+                export(0x0004, false),
+                Some((
+                    EmbeddingPermissions::PreviewPrint,
+                    None,
+                    vec!["font Libertinus Serif may only be embedded into documents \
+                          that are not edited"
+                        .to_string()]
+                ))
+            );
+            test_eq!(
+                sink,
+                export(0x0000, false),
+                Some((EmbeddingPermissions::Installable, None, vec![]))
+            );
+        }
+        "document-anchors" => {
+            let body = "= Introduction <sec:intro>\n\
+                        = Results <Sec.Results>\n\
+                        #heading[Again] #label(\"sec intro\")\n\
+                        #context [#metadata((\n\
+                          anchor(<sec:intro>),\n\
+                          anchor(<Sec.Results>),\n\
+                          anchor(label(\"sec intro\")),\n\
+                        )) <anchors>]";
+
+            let anchors = |introspector: &Introspector| {
+                introspector
+                    .query_label(Label::new(PicoStr::intern("anchors")))
+                    .ok()
+                    .and_then(|elem| elem.to_packed::<MetadataElem>())
+                    .map(|meta| meta.value.repr().to_string())
+            };
+
+            for (naming, expected) in [
+                ("label", ["sec:intro", "Sec.Results", "sec intro"]),
+                ("unified", ["sec-intro", "sec-results", "sec-intro-2"]),
+            ] {
+                let text = format!("#set document(anchors: \"{naming}\")\n{body}");
+                let repr = format!("{:?}", expected).replace(['[', ']'], "");
+                let repr = Some(format!("({repr})"));
+
+                let world = world.clone().with_main(&text);
+                let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok()
+                else {
+                    writeln!(&mut sink, "{naming}: PDF document failed").unwrap();
+                    continue;
+                };
+                test_eq!(sink, anchors(&doc.introspector), repr);
+                let pdf =
+                    typst_pdf::pdf(&doc, &PdfOptions::default()).unwrap_or_default();
+                let pdf = String::from_utf8_lossy(&pdf);
+                for anchor in expected {
+                    test_eq!(sink, pdf.contains(&format!("({anchor})")), true);
+                }
+
+                let world =
+                    world.clone().with_main(&text).with_features(&[Feature::Html]);
+                let Some(doc) = typst::compile::<HtmlDocument>(&world).output.ok() else {
+                    writeln!(&mut sink, "{naming}: HTML document failed").unwrap();
+                    continue;
+                };
+                test_eq!(sink, anchors(&doc.introspector), repr);
+                let html = typst_html::html(&doc).unwrap_or_default();
+                for anchor in expected {
+                    test_eq!(sink, html.contains(&format!("id=\"{anchor}\"")), true);
+                }
+            }
+        }
+        "page-orientation-rotate-content" => {
+            let text = "#set page(width: 120pt, height: 160pt, margin: 10pt, numbering: \"1\")\n\
+                        Portrait\n\
+                        #page(flipped: true, orientation: \"rotate-content\")[Wide]\n\
+                        Portrait";
+            let world = world.clone().with_main(text);
+            let Some(doc) = typst::compile::<PagedDocument>(&world).output.ok() else {
+                writeln!(&mut sink, "document failed").unwrap();
+                return sink;
+            };
+
+            test_eq!(sink, doc.pages.len(), 3);
+            let portrait = Size::new(Abs::pt(120.0), Abs::pt(160.0));
+            for (i, page) in doc.pages.iter().enumerate() {
+                test_eq!(sink, page.frame.size(), portrait);
+                test_eq!(sink, page.rotated, i == 1);
+            }
+
+            // The page numbers are in the same place on all pages.
+            let numbers: Vec<_> = doc
+                .pages
+                .iter()
+                .enumerate()
+                .filter_map(|(i, page)| {
+                    text_positions(&page.frame)
+                        .into_iter()
+                        .find(|(_, text)| *text == (i + 1).to_string())
+                        .map(|(pos, _)| pos)
+                })
+                .collect();
+            test_eq!(sink, numbers.len(), 3);
+            for pos in &numbers {
+                test_eq!(sink, pos.y, numbers[0].y);
+                test_eq!(sink, (pos.x - numbers[0].x).abs() < Abs::pt(5.0), true);
+            }
+
+            // The body of the flipped page starts at the bottom left of the
+            // page and runs upwards.
+            let origin = text_origin(&doc.pages[1].frame, Transform::identity());
+            test_eq!(
+                sink,
+                origin.map(|point| point.x < Abs::pt(40.0) && point.y > Abs::pt(100.0)),
+                Some(true)
+            );
+
+            let pdf = typst_pdf::pdf(&doc, &PdfOptions::default()).unwrap_or_default();
+            let pdf = String::from_utf8_lossy(&pdf);
+            test_eq!(sink, pdf.matches("/Rotate 90").count(), 1);
+        }
+        "cite-group-sort" => {
+            // Each line holds one citation group. Whether Leeson (2007) comes
+            // before Astley (2020) tells whether the group was sorted by year.
+            let lines = doc
+                .and_then(|doc| doc.pages.first())
+                .map(|page| text_lines_of(&page.frame))
+                .unwrap_or_default();
+            let order: Vec<_> = lines
+                .iter()
+                .map(|line| line.find("Leeson") < line.find("Astley"))
+                .collect();
+            test_eq!(sink, order, [false, true, true, false]);
+        }
+        "cite-group-false" => {
+            // Each group gets one parenthetical.
+            let lines = doc
+                .and_then(|doc| doc.pages.first())
+                .map(|page| text_lines_of(&page.frame))
+                .unwrap_or_default();
+            let parens: Vec<_> =
+                lines.iter().map(|line| line.matches('(').count()).collect();
+            test_eq!(sink, parens, [3, 2]);
+        }
+        "fit-text-size" => {
+            let sentence = "The quick brown fox jumps over the lazy dog and keeps \
+                            running far away.";
+            let sizes = text_sizes(doc);
+            let (Some(&size), Some(&last)) = (sizes.first(), sizes.last()) else {
+                writeln!(&mut sink, "fitted text is missing").unwrap();
+                return sink;
+            };
+
+            // The sentence fits at the selected size, but not one step above.
+            let height_at = |size: Abs| {
+                let text = format!(
+                    "#set page(width: 3cm, height: auto, margin: 0pt)\n\
+                     #set text(size: {}pt)\n\
+                     {sentence}",
+                    size.to_pt()
+                );
+                let world = world.clone().with_main(&text);
+                let doc = typst::compile::<PagedDocument>(&world).output.ok();
+                doc.map(|doc| doc.pages[0].frame.height())
+            };
+            let max = Abs::cm(2.0);
+            test_eq!(sink, height_at(size).is_some_and(|h| max.fits(h)), true);
+            let above = height_at(size + Abs::pt(0.5));
+            test_eq!(sink, above.is_some_and(|h| !max.fits(h)), true);
+
+            // The short word is set at the maximum size.
+            test_eq!(sink, last, Abs::pt(24.0));
+        }
+        "fit-text-min" => {
+            test_eq!(sink, text_sizes(doc), [Abs::pt(10.0)]);
+        }
+        "table-min-rows-per-region" => {
+            // Each row is one line of text.
+            let counts: Vec<usize> = doc
+                .into_iter()
+                .flat_map(|doc| &doc.pages)
+                .map(|page| text_lines_of(&page.frame).len())
+                .filter(|&n| n > 0)
+                .collect();
+            test_eq!(sink, counts.iter().sum::<usize>(), 10);
+            test_eq!(sink, counts.first().is_some_and(|&n| n >= 3), true);
+            test_eq!(sink, counts.last().is_some_and(|&n| n >= 3), true);
+        }
+        "text-ligature-exceptions" => {
+            let frame = doc.map(|doc| &doc.pages[0].frame);
+            let clusters = frame.map(non_space_clusters).unwrap_or_default();
+            test_eq!(
+                sink,
+                clusters,
+                [
+                    "fi", "s", "h", //
+                    "f", "i", "s", "h", //
+                    "f", "i", "s", "h", "f", "l", "o", "w", //
+                    "fi", "s", "h", "fl", "o", "w",
+                ]
+            );
+
+            // The text itself is not changed.
+            let lines = frame.map(text_lines_of).unwrap_or_default();
+            test_eq!(sink, lines.join(" "), "fish fish fish flow fish flow");
+        }
+        "text-ligature-exceptions-partial" => {
+            let frame = doc.map(|doc| &doc.pages[0].frame);
+            let clusters = frame.map(non_space_clusters).unwrap_or_default();
+            test_eq!(sink, clusters, ["o", "ffi", "c", "e", "o", "ff", "i", "c", "e"]);
+        }
+        "text-kerning-adjustments" => {
+            let advances: Vec<_> = doc
+                .map(|doc| glyph_clusters(&doc.pages[0].frame))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(cluster, _)| !cluster.trim().is_empty())
+                .map(|(_, advance)| advance)
+                .collect();
+            let [a, v, _, a1, v1, _, a2, v2, _] = advances.as_slice() else {
+                writeln!(&mut sink, "expected nine glyphs, found {advances:?}").unwrap();
+                return sink;
+            };
+
+            // The advance of the first glyph of a pair changes by the
+            // configured amount.
+            let milli = |em: Em| (em.get() * 1000.0).round();
+            test_eq!(sink, milli(*a1 - *a), -50.0);
+            test_eq!(sink, milli(*v1 - *v), 0.0);
+            test_eq!(sink, milli(*a2 - *a), -100.0);
+            test_eq!(sink, milli(*v2 - *v), 100.0);
+        }
         _ => {}
     }
+
+    // Frames only carry semantic roles with the `a11y-extras` feature.
+    if !test.attrs.contains(&Attr::A11yExtras) && !roles(doc).is_empty() {
+        writeln!(&mut sink, "frames have roles without the a11y-extras feature").unwrap();
+    }
+
+    // An introspector that is updated from a different previous version of
+    // the document must match the one built from scratch. In the previous
+    // version, every other page holds the content of the page after it and
+    // there is an additional page at the end, as if something was removed.
+    // Thus, some pages are reused and some are searched again.
+    if let Some((doc, first)) = doc.and_then(|doc| Some((doc, doc.pages.first()?))) {
+        let blank = Page { frame: Frame::hard(Size::zero()), ..first.clone() };
+        let pages: Vec<_> = (0..=doc.pages.len())
+            .map(|i| {
+                let i = if i % 2 == 1 { i + 1 } else { i };
+                doc.pages.get(i).cloned().unwrap_or_else(|| blank.clone())
+            })
+            .collect();
+        let previous = PagedDocument {
+            introspector: Introspector::update(&Introspector::default(), &pages, &[]),
+            pages,
+            info: doc.info.clone(),
+        };
+        let changed = previous.changed_pages(&doc.pages);
+        let updated = Introspector::update(&previous.introspector, &doc.pages, &changed);
+        if introspection(&updated) != introspection(&doc.introspector) {
+            writeln!(&mut sink, "updated introspector differs").unwrap();
+        }
+    }
+
     sink
 }
 
+/// The elements of an introspector with their positions, in document order,
+/// and its number of pages.
+fn introspection(introspector: &Introspector) -> (Vec<(Location, Position)>, usize) {
+    let elems = introspector
+        .all()
+        .map(|elem| {
+            let loc = elem.location().unwrap();
+            (loc, introspector.position(loc).unwrap())
+        })
+        .collect();
+    (elems, introspector.pages().get())
+}
+
+/// Derives a world whose library has a native show rule for `strong`.
+fn with_strong_rule(world: &TestWorld, rule: NativeShowRule) -> TestWorld {
+    world.clone().with_library(|library| {
+        library.rules = Library::builder()
+            .with_native_rules(|rules| {
+                rules.register(StrongElem::elem(), Target::Paged, rule)
+            })
+            .build()
+            .rules;
+    })
+}
+
+/// Shows strong emphasis in uppercase instead of in bold.
+fn upper_strong(
+    content: &Content,
+    _: &mut Engine,
+    _: StyleChain,
+) -> SourceResult<Content> {
+    let strong = content.to_packed::<StrongElem>().unwrap();
+    Ok(strong.body.clone().styled(TextElem::set_case(Some(Case::Upper))))
+}
+
+/// Simulates a broken invariant in the compiler.
+fn panicking_strong(_: &Content, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+    panic!("deliberate panic");
+}
+
+/// Derives a world with the fonts of a built font book.
+fn with_book(world: &TestWorld, (book, fonts): (FontBook, Vec<Font>)) -> TestWorld {
+    world.clone().with_fonts(book, fonts.into_iter().map(Some))
+}
+
+/// Derives a world that describes the regular and the bold Libertinus Serif in
+/// its book, but fails to load the regular one, as if its file was truncated
+/// after the book was built.
+fn with_broken_font(world: &TestWorld) -> TestWorld {
+    let load = |weight| {
+        let variant = FontVariant { weight, ..FontVariant::default() };
+        let id = world.book().select("libertinus serif", variant);
+        id.and_then(|id| world.font(id)).unwrap()
+    };
+    let regular = load(FontWeight::REGULAR);
+    let bold = load(FontWeight::BOLD);
+    let truncated = Bytes::from(regular.data()[..64].to_vec());
+
+    let mut book = FontBook::from_infos([regular.info().clone(), bold.info().clone()]);
+    for result in Font::iter_checked(truncated.clone()) {
+        if let Err(problem) = result {
+            book.push_problem(FontProblem { path: Some("broken.ttf".into()), ..problem });
+        }
+    }
+
+    world.clone().with_fonts(book, [Font::new(truncated, 0), Some(bold)])
+}
+
+/// Replaces the `fsType` field in the `OS/2` table of a font.
+fn with_fs_type(data: &[u8], fs_type: u16) -> Vec<u8> {
+    let mut data = data.to_vec();
+    let read = |data: &[u8], at: usize| {
+        u32::from_be_bytes(data[at..at + 4].try_into().unwrap()) as usize
+    };
+    let tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+    for record in (0..tables).map(|i| 12 + 16 * i) {
+        if &data[record..record + 4] == b"OS/2" {
+            let offset = read(&data, record + 8) + 8;
+            data[offset..offset + 2].copy_from_slice(&fs_type.to_be_bytes());
+        }
+    }
+    data
+}
+
+/// Collect the semantic roles in the frames of all pages in order, together
+/// with the number of roles they are nested in.
+fn roles(doc: Option<&PagedDocument>) -> Vec<(usize, FrameRole)> {
+    doc.into_iter()
+        .flat_map(|doc| &doc.pages)
+        .flat_map(|page| frame_roles(&page.frame))
+        .collect()
+}
+
+/// Collect the semantic roles in a frame in order, together with the number of
+/// roles they are nested in.
+fn frame_roles(frame: &Frame) -> Vec<(usize, FrameRole)> {
+    fn collect(frame: &Frame, depth: usize, roles: &mut Vec<(usize, FrameRole)>) {
+        for (_, item) in frame.items() {
+            if let FrameItem::Group(group) = item {
+                let mut depth = depth;
+                if let Some(role) = group.role {
+                    roles.push((depth, role));
+                    depth += 1;
+                }
+                collect(&group.frame, depth, roles);
+            }
+        }
+    }
+
+    let mut roles = vec![];
+    collect(frame, 0, &mut roles);
+    roles
+}
+
+/// Returns the text of each line of a frame, from top to bottom.
+fn text_lines_of(frame: &Frame) -> Vec<String> {
+    let mut runs = text_positions(frame);
+    runs.sort_by_key(|(pos, _)| (pos.y, pos.x));
+
+    let mut lines: Vec<(Abs, String)> = vec![];
+    for (pos, text) in runs {
+        match lines.last_mut() {
+            Some((y, line)) if y.approx_eq(pos.y) => line.push_str(&text),
+            _ => lines.push((pos.y, text)),
+        }
+    }
+    lines.into_iter().map(|(_, line)| line.trim().to_string()).collect()
+}
+
+/// Returns the text items of a frame and its descendants with their positions,
+/// ignoring group transforms.
+fn text_positions(frame: &Frame) -> Vec<(Point, String)> {
+    fn collect(frame: &Frame, offset: Point, runs: &mut Vec<(Point, String)>) {
+        for (pos, item) in frame.items() {
+            let pos = offset + *pos;
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, pos, runs),
+                FrameItem::Text(text) => runs.push((pos, text.text.to_string())),
+                _ => {}
+            }
+        }
+    }
+
+    let mut runs = vec![];
+    collect(frame, Point::zero(), &mut runs);
+    runs
+}
+
+/// Returns the text and advance of each glyph in a frame and its descendants.
+fn glyph_clusters(frame: &Frame) -> Vec<(String, Em)> {
+    let mut glyphs = vec![];
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => glyphs.extend(glyph_clusters(&group.frame)),
+            FrameItem::Text(text) => glyphs.extend(
+                text.glyphs
+                    .iter()
+                    .map(|glyph| (text.text[glyph.range()].to_string(), glyph.x_advance)),
+            ),
+            _ => {}
+        }
+    }
+    glyphs
+}
+
+/// Returns the text of each glyph in a frame that is not a space.
+fn non_space_clusters(frame: &Frame) -> Vec<String> {
+    glyph_clusters(frame)
+        .into_iter()
+        .map(|(cluster, _)| cluster)
+        .filter(|cluster| !cluster.trim().is_empty())
+        .collect()
+}
+
+/// Returns the text of the topmost line of a frame.
+fn top_line(frame: &Frame) -> String {
+    fn collect(frame: &Frame, offset: Point, runs: &mut Vec<(Point, String)>) {
+        for (pos, item) in frame.items() {
+            let pos = offset + *pos;
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, pos, runs),
+                FrameItem::Text(text) => runs.push((pos, text.text.to_string())),
+                _ => {}
+            }
+        }
+    }
+
+    let mut runs = vec![];
+    collect(frame, Point::zero(), &mut runs);
+    let Some(top) = runs.iter().map(|(pos, _)| pos.y).min() else {
+        return String::new();
+    };
+    runs.retain(|(pos, _)| pos.y.approx_eq(top));
+    runs.sort_by_key(|(pos, _)| pos.x);
+    runs.into_iter().map(|(_, text)| text).collect()
+}
+
+/// The headings that start in a frame, in layout order.
+fn headings(frame: &Frame) -> Vec<Content> {
+    fn collect(frame: &Frame, headings: &mut Vec<Content>) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, headings),
+                FrameItem::Tag(typst::introspection::Tag::Start(elem))
+                    if elem.is::<HeadingElem>() =>
+                {
+                    headings.push(elem.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut headings = vec![];
+    collect(frame, &mut headings);
+    headings
+}
+
+/// The distinct sizes of the text items on the first page, in order.
+fn text_sizes(doc: Option<&PagedDocument>) -> Vec<Abs> {
+    fn collect(frame: &Frame, sizes: &mut Vec<Abs>) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, sizes),
+                FrameItem::Text(text) if sizes.last() != Some(&text.size) => {
+                    sizes.push(text.size)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut sizes = vec![];
+    if let Some(page) = doc.and_then(|doc| doc.pages.first()) {
+        collect(&page.frame, &mut sizes);
+    }
+    sizes
+}
+
+/// Find the first text item in a frame.
+fn first_text_item(frame: &Frame) -> Option<&TextItem> {
+    frame.items().find_map(|(_, item)| match item {
+        FrameItem::Group(group) => first_text_item(&group.frame),
+        FrameItem::Text(text) => Some(text),
+        _ => None,
+    })
+}
+
+/// Collect the text and fill of each text item on the first page, grouped by
+/// baseline from top to bottom.
+fn text_runs(doc: Option<&PagedDocument>) -> Vec<Vec<(String, Paint)>> {
+    fn collect(
+        frame: &Frame,
+        offset: Point,
+        lines: &mut Vec<(Abs, Vec<(String, Paint)>)>,
+    ) {
+        for (pos, item) in frame.items() {
+            let pos = offset + *pos;
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, pos, lines),
+                FrameItem::Text(text) => {
+                    let run = (text.text.to_string(), text.fill.clone());
+                    match lines.iter_mut().find(|(y, _)| y.approx_eq(pos.y)) {
+                        Some((_, runs)) => runs.push(run),
+                        None => lines.push((pos.y, vec![run])),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut lines = vec![];
+    if let Some(page) = doc.and_then(|doc| doc.pages.first()) {
+        collect(&page.frame, Point::zero(), &mut lines);
+    }
+    lines.sort_by(|a, b| a.0.cmp(&b.0));
+    lines.into_iter().map(|(_, runs)| runs).collect()
+}
+
+/// Find the origin of the first text item in a frame, with all group
+/// transforms applied on top of the given one.
+fn text_origin(frame: &Frame, ts: Transform) -> Option<Point> {
+    frame.items().find_map(|(pos, item)| {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                text_origin(&group.frame, ts.pre_concat(group.transform))
+            }
+            FrameItem::Text(_) => Some(Point::zero().transform(ts)),
+            _ => None,
+        }
+    })
+}
+
+/// The font used by the first text item in a frame.
+fn text_font(frame: &Frame) -> Option<Font> {
+    frame.items().find_map(|(_, item)| match item {
+        FrameItem::Group(group) => text_font(&group.frame),
+        FrameItem::Text(text) => Some(text.font.clone()),
+        _ => None,
+    })
+}
+
+/// A plugin whose function `host_info` returns the information it receives
+/// through the `typst_host_info` import.
+fn host_info_plugin() -> Bytes {
+    let name = |name: &str| [&[name.len() as u8][..], name.as_bytes()].concat();
+    let parts: &[&[u8]] = &[
+        b"\0asm\x01\0\0\0",
+        // Type section: (i32, i32) -> i32, (i32, i32) -> (), and () -> i32.
+        &[0x01, 0x10, 0x03],
+        &[0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f],
+        &[0x60, 0x02, 0x7f, 0x7f, 0x00],
+        &[0x60, 0x00, 0x01, 0x7f],
+        // Import section: the host information and the result function.
+        &[0x02, 0x53, 0x02],
+        &name("typst_env"),
+        &name("typst_host_info"),
+        &[0x00, 0x00],
+        &name("typst_env"),
+        &name("wasm_minimal_protocol_send_result_to_host"),
+        &[0x00, 0x01],
+        // Function section: one function of type 2.
+        &[0x03, 0x02, 0x01, 0x02],
+        // Memory section: one memory with one page.
+        &[0x05, 0x03, 0x01, 0x00, 0x01],
+        // Export section: `memory` and `host_info`.
+        &[0x07, 0x16, 0x02],
+        &name("memory"),
+        &[0x02, 0x00],
+        &name("host_info"),
+        &[0x00, 0x02],
+        // Code section with one function that has one local.
+        &[0x0a, 0x1d, 0x01, 0x1b, 0x01, 0x01, 0x7f],
+        // len = typst_host_info(0, 0)
+        &[0x41, 0x00, 0x41, 0x00, 0x10, 0x00, 0x21, 0x00],
+        // drop(typst_host_info(0, len))
+        &[0x41, 0x00, 0x20, 0x00, 0x10, 0x00, 0x1a],
+        // send_result_to_host(0, len); return 0
+        &[0x41, 0x00, 0x20, 0x00, 0x10, 0x01, 0x41, 0x00, 0x0b],
+    ];
+    Bytes::from(parts.concat())
+}
+
+/// The horizontal positions of the glyphs for the given character in a frame,
+/// with all group transforms applied on top of the given one.
+fn glyph_xs(frame: &Frame, ts: Transform, c: char) -> Vec<Abs> {
+    let mut xs = vec![];
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                xs.extend(glyph_xs(&group.frame, ts.pre_concat(group.transform), c))
+            }
+            FrameItem::Text(text) => {
+                let mut x = Point::zero().transform(ts).x;
+                for glyph in &text.glyphs {
+                    if text.text[glyph.range()].starts_with(c) {
+                        xs.push(x);
+                    }
+                    x += glyph.x_advance.at(text.size);
+                }
+            }
+            _ => {}
+        }
+    }
+    xs
+}
+
+/// Count the shapes in a frame.
+fn shapes(frame: &Frame) -> usize {
+    frame
+        .items()
+        .map(|(_, item)| match item {
+            FrameItem::Group(group) => shapes(&group.frame),
+            FrameItem::Shape(..) => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Collects the images in a frame and its descendants.
+fn image_items(frame: &Frame) -> Vec<Image> {
+    let mut images = vec![];
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => images.extend(image_items(&group.frame)),
+            FrameItem::Image(image, _, _) => images.push(image.clone()),
+            _ => {}
+        }
+    }
+    images
+}
+
 /// Extract the document information.
 fn info(doc: Option<&PagedDocument>) -> DocumentInfo {
     doc.map(|doc| doc.info.clone()).unwrap_or_default()
@@ -4,11 +4,11 @@ use std::path::PathBuf;
 
 use ecow::eco_vec;
 use tiny_skia as sk;
-use typst::diag::{SourceDiagnostic, Warned};
+use typst::diag::{ErrorMode, SourceDiagnostic, Warned};
 use typst::html::HtmlDocument;
 use typst::layout::{Abs, Frame, FrameItem, PagedDocument, Transform};
 use typst::visualize::Color;
-use typst::{Document, WorldExt};
+use typst::{CompareOptions, CompileOptions, DiffRegion, Document, Feature, WorldExt};
 use typst_pdf::PdfOptions;
 
 use crate::collect::{Attr, FileSize, NoteKind, Test};
@@ -44,9 +44,13 @@ pub struct Runner<'a> {
 impl<'a> Runner<'a> {
     /// Create a new test runner.
     fn new(test: &'a Test) -> Self {
+        let mut world = TestWorld::new(test.source.clone());
+        if test.attrs.contains(&Attr::A11yExtras) {
+            world = world.with_features(&[Feature::Html, Feature::A11yExtras]);
+        }
         Self {
             test,
-            world: TestWorld::new(test.source.clone()),
+            world,
             seen: vec![false; test.notes.len()],
             result: TestResult {
                 errors: String::new(),
@@ -80,7 +84,15 @@ impl<'a> Runner<'a> {
 
     /// Run test specific to document format.
     fn run_test<D: OutputType>(&mut self) {
-        let Warned { output, warnings } = typst::compile(&self.world);
+        let Warned { output, warnings } = if self.test.attrs.contains(&Attr::Collect) {
+            let options = CompileOptions {
+                error_mode: ErrorMode::Collect,
+                ..Default::default()
+            };
+            typst::compile_with_options(&self.world, &options).0
+        } else {
+            typst::compile(&self.world)
+        };
         let (doc, errors) = match output {
             Ok(doc) => (Some(doc), eco_vec![]),
             Err(errors) => (None, errors),
@@ -438,6 +450,43 @@ impl OutputType for PagedDocument {
                 log!(runner, "  {line}");
             }
         }
+
+        if let Some(doc) = doc.filter(|_| crate::ARGS.compare_backends) {
+            let options = CompareOptions {
+                pixel_per_pt: crate::ARGS.compare_pixel_per_pt,
+                ..Default::default()
+            };
+            let diffs = match typst::compare_document(doc, &options) {
+                Ok(diffs) => diffs,
+                Err(error) => {
+                    log!(runner, "failed to compare backends: {error}");
+                    return;
+                }
+            };
+
+            for diff in diffs {
+                if !diff.exceeds(crate::ARGS.compare_threshold) {
+                    continue;
+                }
+
+                log!(
+                    runner,
+                    "page {} differs between backends ({:.2}% of pixels)",
+                    diff.page + 1,
+                    diff.ratio() * 100.0,
+                );
+                for DiffRegion { min, max } in &diff.regions {
+                    log!(
+                        runner,
+                        "  in region from ({:.1}pt, {:.1}pt) to ({:.1}pt, {:.1}pt)",
+                        min.x.to_pt(),
+                        min.y.to_pt(),
+                        max.x.to_pt(),
+                        max.y.to_pt(),
+                    );
+                }
+            }
+        }
     }
 }
 
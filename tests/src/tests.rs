@@ -31,6 +31,9 @@ static ARGS: LazyLock<CliArguments> = LazyLock::new(CliArguments::parse);
 /// The directory where the test suite is located.
 const SUITE_PATH: &str = "tests/suite";
 
+/// The directory where the benchmark documents are located.
+const BENCH_PATH: &str = "tests/bench";
+
 /// The directory where the full test results are stored.
 const STORE_PATH: &str = "tests/store";
 
@@ -53,6 +56,10 @@ fn main() {
         None => test(),
         Some(Command::Clean) => clean(),
         Some(Command::Undangle) => undangle(),
+        #[cfg(feature = "default")]
+        Some(Command::Bench { iterations }) => bench(*iterations),
+        #[cfg(not(feature = "default"))]
+        Some(Command::Bench { .. }) => eprintln!("benchmarks need the default features"),
     }
 }
 
@@ -168,6 +175,42 @@ fn undangle() {
     }
 }
 
+#[cfg(feature = "default")]
+fn bench(iterations: usize) {
+    use std::time::Instant;
+
+    use typst::layout::PagedDocument;
+    use typst_syntax::{FileId, Source, VirtualPath};
+
+    let mut paths: Vec<_> = std::fs::read_dir(BENCH_PATH)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "typ"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let text = std::fs::read_to_string(&path).unwrap();
+        let id = FileId::new(None, VirtualPath::new(&path));
+        let world = crate::world::TestWorld::new(Source::new(id, text));
+
+        let mut total = Duration::ZERO;
+        for _ in 0..iterations.max(1) {
+            // Measure from scratch every time instead of the cache.
+            comemo::evict(0);
+            let start = Instant::now();
+            if let Err(errors) = typst::compile::<PagedDocument>(&world).output {
+                eprintln!("❌ {} failed to compile: {errors:?}", path.display());
+                std::process::exit(1);
+            }
+            total += start.elapsed();
+        }
+
+        let mean = total / iterations.max(1) as u32;
+        eprintln!("{}: {mean:.2?} (mean of {iterations})", path.display());
+    }
+}
+
 fn create_syntax_store(ref_path: Option<PathBuf>) -> (&'static Path, Option<PathBuf>) {
     if ref_path.as_ref().is_some_and(|p| !p.exists()) {
         eprintln!("syntax reference path doesn't exist");
@@ -4,7 +4,7 @@ use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
 use comemo::Tracked;
 use parking_lot::Mutex;
@@ -13,19 +13,29 @@ use typst::engine::Engine;
 use typst::foundations::{
     func, Array, Bytes, Context, Datetime, IntoValue, NoneValue, Repr, Smart, Value,
 };
-use typst::layout::{Abs, Margin, PageElem};
+use typst::introspection::metrics;
+use typst::layout::{Abs, Axes, Margin, PageElem};
 use typst::model::{Numbering, NumberingPattern};
-use typst::syntax::{FileId, Source, Span};
+use typst::syntax::package::PackageSpec;
+use typst::syntax::{FileId, Source, Span, VirtualPath};
 use typst::text::{Font, FontBook, TextElem, TextSize};
 use typst::utils::{singleton, LazyHash};
-use typst::visualize::Color;
+use typst::visualize::{Color, RasterImage, SvgImage};
 use typst::{Feature, Library, World};
 
 /// A world that provides access to the tests environment.
+///
+/// Custom checks can derive worlds with a different main source, library,
+/// fonts, or files from the world of their test.
 #[derive(Clone)]
 pub struct TestWorld {
     main: Source,
     base: &'static TestBase,
+    library: Option<LazyHash<Library>>,
+    fonts: Option<Arc<TestFonts>>,
+    sources: HashMap<FileId, Source>,
+    files: HashMap<FileId, Bytes>,
+    modified: HashMap<FileId, Datetime>,
 }
 
 impl TestWorld {
@@ -37,17 +47,79 @@ impl TestWorld {
         Self {
             main: source,
             base: singleton!(TestBase, TestBase::default()),
+            library: None,
+            fonts: None,
+            sources: HashMap::new(),
+            files: HashMap::new(),
+            modified: HashMap::new(),
         }
     }
+
+    /// Replace the text of the main source file.
+    pub fn with_main(mut self, text: &str) -> Self {
+        self.main = Source::new(self.main.id(), text.into());
+        self
+    }
+
+    /// Enable exactly the given features.
+    pub fn with_features(self, features: &[Feature]) -> Self {
+        self.with_library(|library| {
+            library.features = features.iter().copied().collect();
+            if features.contains(&Feature::LayoutMetrics) {
+                library.global.scope_mut().define_module(metrics::module());
+            }
+        })
+    }
+
+    /// Modify the standard library.
+    pub fn with_library(mut self, f: impl FnOnce(&mut Library)) -> Self {
+        let library = self.library.get_or_insert_with(|| self.base.library.clone());
+        f(library);
+        self
+    }
+
+    /// Replace the fonts. Fonts that are `None` fail to load.
+    pub fn with_fonts(
+        mut self,
+        book: FontBook,
+        fonts: impl IntoIterator<Item = Option<Font>>,
+    ) -> Self {
+        self.fonts = Some(Arc::new(TestFonts {
+            book: LazyHash::new(book),
+            fonts: fonts.into_iter().collect(),
+        }));
+        self
+    }
+
+    /// Replace the source file with the given id.
+    pub fn with_source(mut self, id: FileId, text: &str) -> Self {
+        self.sources.insert(id, Source::new(id, text.into()));
+        self
+    }
+
+    /// Replace the file at the given path in the project root.
+    pub fn with_file(mut self, path: &str, data: Bytes) -> Self {
+        self.files.insert(FileId::new(None, VirtualPath::new(path)), data);
+        self
+    }
+
+    /// Provide a modification date for the file with the given id.
+    pub fn with_modified(mut self, id: FileId, date: Datetime) -> Self {
+        self.modified.insert(id, date);
+        self
+    }
 }
 
 impl World for TestWorld {
     fn library(&self) -> &LazyHash<Library> {
-        &self.base.library
+        self.library.as_ref().unwrap_or(&self.base.library)
     }
 
     fn book(&self) -> &LazyHash<FontBook> {
-        &self.base.book
+        match &self.fonts {
+            Some(fonts) => &fonts.book,
+            None => &self.base.book,
+        }
     }
 
     fn main(&self) -> FileId {
@@ -57,22 +129,48 @@ impl World for TestWorld {
     fn source(&self, id: FileId) -> FileResult<Source> {
         if id == self.main.id() {
             Ok(self.main.clone())
+        } else if let Some(source) = self.sources.get(&id) {
+            Ok(source.clone())
         } else {
             self.slot(id, FileSlot::source)
         }
     }
 
     fn file(&self, id: FileId) -> FileResult<Bytes> {
-        self.slot(id, FileSlot::file)
+        if let Some(source) = self.sources.get(&id) {
+            Ok(Bytes::from(source.text().as_bytes()))
+        } else if let Some(data) = self.files.get(&id) {
+            Ok(data.clone())
+        } else {
+            self.slot(id, FileSlot::file)
+        }
     }
 
     fn font(&self, index: usize) -> Option<Font> {
-        Some(self.base.fonts[index].clone())
+        match &self.fonts {
+            Some(fonts) => fonts.fonts.get(index).cloned().flatten(),
+            None => self.base.fonts.get(index).cloned(),
+        }
     }
 
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
         Some(Datetime::from_ymd(1970, 1, 1).unwrap())
     }
+
+    fn modified(&self, id: FileId) -> Option<Datetime> {
+        self.modified.get(&id).copied()
+    }
+
+    fn package_files(&self, spec: &PackageSpec) -> FileResult<Vec<FileId>> {
+        let root = package_root(spec);
+        let mut files = vec![];
+        collect_package_files(spec, &root, &root, &mut files)?;
+        Ok(files)
+    }
+
+    fn rasterize_svg(&self, svg: &SvgImage, size: Axes<u32>) -> StrResult<RasterImage> {
+        typst_render::rasterize_svg(svg, size)
+    }
 }
 
 impl TestWorld {
@@ -110,6 +208,12 @@ impl Default for TestBase {
     }
 }
 
+/// Fonts that replace the ones of the test base.
+struct TestFonts {
+    book: LazyHash<FontBook>,
+    fonts: Vec<Option<Font>>,
+}
+
 /// Holds the processed data for a file ID.
 #[derive(Clone)]
 struct FileSlot {
@@ -150,14 +254,34 @@ impl FileSlot {
 
 /// The file system path for a file ID.
 fn system_path(id: FileId) -> FileResult<PathBuf> {
-    let root: PathBuf = match id.package() {
-        Some(spec) => format!("tests/packages/{}-{}", spec.name, spec.version).into(),
-        None => PathBuf::new(),
-    };
-
+    let root = id.package().map(package_root).unwrap_or_default();
     id.vpath().resolve(&root).ok_or(FileError::AccessDenied)
 }
 
+/// The directory of a test package.
+fn package_root(spec: &PackageSpec) -> PathBuf {
+    format!("tests/packages/{}-{}", spec.name, spec.version).into()
+}
+
+/// Collects the ids of all files in a package directory, recursively.
+fn collect_package_files(
+    spec: &PackageSpec,
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<FileId>,
+) -> FileResult<()> {
+    let f = |err| FileError::from_io(err, dir);
+    for entry in fs::read_dir(dir).map_err(f)? {
+        let path = entry.map_err(f)?.path();
+        if path.is_dir() {
+            collect_package_files(spec, root, &path, files)?;
+        } else if let Some(vpath) = VirtualPath::within_root(&path, root) {
+            files.push(FileId::new(Some(spec.clone()), vpath));
+        }
+    }
+    Ok(())
+}
+
 /// Read a file.
 fn read(path: &Path) -> FileResult<Cow<'static, [u8]>> {
     // Resolve asset.
@@ -356,6 +356,54 @@
 #test((2, 1, 3, -10, -5, 8, 6, -7, 2).sorted(key: x => x), (-10, -7, -5, 1, 2, 2, 3, 6, 8))
 #test((2, 1, 3, -10, -5, 8, 6, -7, 2).sorted(key: x => x * x), (1, 2, 2, 3, -5, 6, -7, 8, -10))
 
+--- array-sorted-natural ---
+#test(("item10", "item2", "item1").sorted(by: "natural"), ("item1", "item2", "item10"))
+#test(("v1.10", "v1.9", "v1.09", "v2").sorted(by: "natural"), ("v1.9", "v1.09", "v1.10", "v2"))
+#test(("b2", "a10", "a9").sorted(by: "natural"), ("a9", "a10", "b2"))
+#test(((1, "x10"), (1, "x9")).sorted(by: "natural"), ((1, "x9"), (1, "x10")))
+
+--- array-sorted-locale ---
+#let words = ("Zebra", "Ärger", "Apfel", "Ofen", "Öl")
+#test(words.sorted(), ("Apfel", "Ofen", "Zebra", "Ärger", "Öl"))
+#test(words.sorted(by: "locale", lang: "de"), ("Apfel", "Ärger", "Ofen", "Öl", "Zebra"))
+#test(words.sorted(by: "locale", lang: "sv"), ("Apfel", "Ofen", "Zebra", "Ärger", "Öl"))
+#test(("nube", "ñu", "oso").sorted(by: "locale", lang: "es"), ("nube", "ñu", "oso"))
+#test(("b", "B", "a", "A").sorted(by: "locale"), ("a", "A", "b", "B"))
+
+--- array-sorted-locale-context ---
+#set text(lang: "sv")
+#context test(("Öl", "Zebra").sorted(by: "locale"), ("Zebra", "Öl"))
+#test(("Öl", "Zebra").sorted(by: "locale"), ("Öl", "Zebra"))
+
+--- array-sorted-stable ---
+#let people = ((name: "Eva", age: 30), (name: "Bob", age: 25), (name: "Ada", age: 30))
+#test(
+  people.sorted(key: p => p.age, by: "natural").map(p => p.name),
+  ("Bob", "Eva", "Ada"),
+)
+#test(
+  ("ab", "Ab", "ab").sorted(key: lower, by: "locale"),
+  ("ab", "Ab", "ab"),
+)
+
+--- array-sorted-by-function ---
+#test((1, 5, 3).sorted(by: (a, b) => a > b), (5, 3, 1))
+#test(("bb", "a", "cc").sorted(by: (a, b) => a.len() < b.len()), ("a", "bb", "cc"))
+
+--- array-sorted-by-function-not-bool ---
+// Error: 2-36 expected boolean, found integer
+#(1, 2).sorted(by: (a, b) => a - b)
+
+--- array-sorted-by-invalid ---
+// Error: 20-29 expected "natural", "locale", or function
+#(1, 2).sorted(by: "reverse")
+
+--- array-sorted-by-function-inconsistent ---
+// A comparison that is not a total order yields some order, but no panic.
+#let values = range(50).map(i => calc.rem(i * 7, 13))
+#test(values.sorted(by: (a, b) => true).len(), 50)
+#test(values.sorted(by: (a, b) => calc.rem(a + b, 3) == 0).len(), 50)
+
 --- array-sorted-key-function-positional-1 ---
 // Error: 12-18 unexpected argument
 #().sorted(x => x)
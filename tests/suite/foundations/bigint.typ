@@ -0,0 +1,60 @@
+--- bigint-constructor ---
+#test(bigint(5), 5)
+#test(bigint("-12"), -12)
+#test(bigint(bigint(3)), 3)
+#test(repr(bigint("123456789012345678901234567890")), "bigint(\"123456789012345678901234567890\")")
+
+--- bigint-constructor-invalid ---
+// Error: 9-14 invalid big integer: 1.5
+#bigint("1.5")
+
+--- bigint-arithmetic ---
+#let max = bigint(9223372036854775807)
+#test(str(max + 1), "9223372036854775808")
+#test(str(1 - max - 3), "−9223372036854775809")
+#test(str(max * max), "85070591730234615847396907784232501249")
+#test(str(-max * 2), "−18446744073709551614")
+#test(str(bigint(2).pow(100)), "1267650600228229401496703205376")
+
+--- bigint-compare ---
+#let big = bigint(2).pow(64)
+#test(big > 9223372036854775807, true)
+#test(-big < -9223372036854775807, true)
+#test(big == bigint("18446744073709551616"), true)
+#test(bigint(3) == 3, true)
+#test(calc.max(big, 5), big)
+
+--- bigint-compare-float ---
+#let big = bigint(2).pow(64)
+#test(bigint(3) == 3.0, true)
+#test(bigint(3) != 3.5, true)
+#test(bigint(3) < 3.5, true)
+#test(-3.5 < bigint(-3), true)
+#test(big == 18446744073709551616.0, true)
+#test(big + 1 == 18446744073709551616.0, false)
+#test(big + 1 > 18446744073709551616.0, true)
+#test(big < calc.inf, true)
+#test(-big > -calc.inf, true)
+
+--- bigint-compare-float-nan ---
+// Error: 3-24 cannot compare bigint("1") with float.nan
+#(bigint(1) < float.nan)
+
+--- bigint-add-float ---
+// Error: 3-18 cannot add big integer and float
+#(bigint(1) + 2.0)
+
+--- bigint-conversion ---
+#let big = bigint(2).pow(64)
+#test(str(big), "18446744073709551616")
+#test(float(big), 18446744073709551616.0)
+#test(int(bigint(42)), 42)
+#test(type(int(bigint(42))), int)
+
+--- bigint-conversion-int-too-large ---
+// Error: 6-23 number too large
+#int(bigint(2).pow(64))
+
+--- bigint-pow-too-large ---
+// Error: 2-27 the result is too large
+#bigint(2).pow(1000000000)
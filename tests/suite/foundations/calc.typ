@@ -216,6 +216,13 @@
 // Error: 2-25 the result is too large
 #calc.pow(2, 2147483647)
 
+--- calc-pow-bigint ---
+// Integer powers that leave the 64-bit range are promoted like `calc.fact`.
+#test(type(calc.pow(2, 62)), int)
+#test(type(calc.pow(2, 63)), bigint)
+#test(calc.pow(2, 63), bigint(2).pow(63))
+#test(str(calc.pow(-3, 41)), "−36472996377170786403")
+
 --- calc-pow-too-large-decimal ---
 // Error: 2-56 the result is too large
 #calc.pow(decimal("2222222222222222222222222222"), 100)
@@ -265,9 +272,18 @@
 #test(calc.fact(0), 1)
 #test(calc.fact(5), 120)
 
+--- calc-fact-bigint ---
+#test(type(calc.fact(20)), int)
+#test(calc.fact(20), 2432902008176640000)
+#test(type(calc.fact(21)), bigint)
+#test(str(calc.fact(25)), "15511210043330985984000000")
+#test(calc.fact(25), bigint("15511210043330985984000000"))
+
 --- calc-fact-too-large ---
-// Error: 2-15 the result is too large
-#calc.fact(21)
+// Results past `int` are promoted to `bigint`, so only factorials beyond the
+// size limit of big integers fail.
+// Error: 2-18 the result is too large
+#calc.fact(10000)
 
 --- calc-perm ---
 // Test the `perm` function.
@@ -277,8 +293,13 @@
 #test(calc.perm(5, 6), 0)
 
 --- calc-perm-too-large ---
-// Error: 2-19 the result is too large
-#calc.perm(21, 21)
+// Like `calc.fact`, only results beyond the size limit of big integers fail.
+// Error: 2-26 the result is too large
+#calc.perm(100000, 10000)
+
+--- calc-perm-bigint ---
+#test(calc.perm(21, 21), calc.fact(21))
+#test(str(calc.perm(30, 15)), "202843204931727360000")
 
 --- calc-binom ---
 // Test the `binom` function.
@@ -287,6 +308,7 @@
 #test(calc.binom(5, 5), 1)
 #test(calc.binom(5, 6), 0)
 #test(calc.binom(6, 2), 15)
+#test(str(calc.binom(100, 50)), "100891344545564193334812497256")
 
 --- calc-gcd ---
 // Test the `gcd` function.
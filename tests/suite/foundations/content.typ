@@ -130,3 +130,64 @@
 --- content-try-to-access-internal-field ---
 // Error: 9-15 hide does not have field "hidden"
 #hide[].hidden
+
+--- content-equals-ignore-spans ---
+// Content created at different places only differs in its spans.
+#let a = [Hello *World*]
+#let b = [Hello *World*]
+#test(content.equals(a, b), true)
+#test(content.equals(a, b, ignore-spans: false), false)
+#test(content.equals(a, a, ignore-spans: false), true)
+#test(content.equals(a, [Hello *Welt*]), false)
+#test(content.diff(a, b), "")
+
+--- content-diff-text ---
+// Only the differing text node is mentioned.
+#let a = [Hello *World* and more]
+#let b = [Hello *Welt* and more]
+#test(
+  content.diff(a, b),
+  "children.at(2).body.text:\n  - \"World\"\n  + \"Welt\"",
+)
+
+--- content-diff-structure ---
+#test(
+  content.diff(strong[A], emph[A]),
+  "(root):\n  - strong(body: [A])\n  + emph(body: [A])",
+)
+#test(
+  content.diff([A *B*], [A]),
+  "(root):\n  - sequence([A], [ ], strong(body: [B]))\n  + [A]",
+)
+#test(
+  content.diff(heading[A], heading(level: 2)[A]),
+  "level:\n  - (unset)\n  + 2",
+)
+#test(
+  content.diff(list[A][B], list[A]),
+  "children.at(1):\n  - item(body: [B])\n  + (missing)",
+)
+
+--- content-diff-bounded ---
+#let diff = content.diff(
+  list(..range(10).map(i => [#i])),
+  list(..range(10).map(i => [#(i + 1)])),
+)
+#test(diff.split("\n").len(), 16)
+#test(diff.ends-with("… and 5 more differences"), true)
+
+--- content-diff-closure ---
+// Closures compare by identity.
+#let marker = n => [-]
+#test(content.equals(list(marker: marker)[A], list(marker: marker)[A]), true)
+#test(content.equals(list(marker: n => [-])[A], list(marker: n => [-])[A]), false)
+#test(
+  content.diff(list(marker: n => [-])[A], list(marker: n => [-])[A]).ends-with(
+    "note: functions are compared by identity",
+  ),
+  true,
+)
+
+--- content-builder ---
+// The custom check builds a document with two sections and tables from Rust
+// and lays it out without parsing any markup.
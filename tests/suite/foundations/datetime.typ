@@ -72,6 +72,60 @@
 #test(datetime(day: 1, month: 3, year: 2001).ordinal(), 31 + 28 + 1);
 #test(datetime(day: 31, month: 12, year: 2001).ordinal(), 365);
 
+--- datetime-display-ordinal-day ---
+#let day(d) = datetime(year: 2024, month: 1, day: d).display("[day repr:ordinal]")
+#test(
+  (1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 24, 31).map(day).join(" "),
+  "1st 2nd 3rd 4th 11th 12th 13th 21st 22nd 23rd 24th 31st",
+)
+#test(
+  datetime(year: 2024, month: 3, day: 2).display("[month repr:long] [day repr:ordinal], [year]"),
+  "March 2nd, 2024",
+)
+
+--- datetime-display-quarter ---
+#let quarter(m) = datetime(year: 2024, month: m, day: 1).display("Q[quarter]")
+#test(range(1, 13).map(quarter).join(" "), "Q1 Q1 Q1 Q2 Q2 Q2 Q3 Q3 Q3 Q4 Q4 Q4")
+#test(
+  datetime(year: 2024, month: 5, day: 10, hour: 8, minute: 0, second: 0)
+    .display("[year]-Q[quarter] [hour]:[minute]"),
+  "2024-Q2 08:00",
+)
+
+--- datetime-display-iso-week-and-day-of-year ---
+#let fmt = "[year base:iso_week]-W[week_number] ([ordinal padding:none])"
+#test(datetime(year: 2020, month: 12, day: 31).display(fmt), "2020-W53 (366)")
+#test(datetime(year: 2021, month: 1, day: 3).display(fmt), "2020-W53 (3)")
+#test(datetime(year: 2021, month: 1, day: 4).display(fmt), "2021-W01 (4)")
+#test(datetime(year: 2023, month: 12, day: 31).display(fmt), "2023-W52 (365)")
+
+--- datetime-display-custom-component-insufficient-information ---
+// Error: 2-62 failed to format datetime (insufficient information)
+#datetime(hour: 1, minute: 0, second: 0).display("[quarter]")
+
+--- datetime-display-invalid-after-custom-component ---
+// Error: 49-70 invalid component name 'nothing' at index 11
+#datetime(year: 2024, month: 1, day: 1).display("[quarter] [nothing]")
+
+--- datetime-add-months ---
+#let date(y, m, d) = datetime(year: y, month: m, day: d)
+#test(date(2024, 1, 15).add(months: 1), date(2024, 2, 15))
+#test(date(2024, 1, 31).add(months: 1), date(2024, 2, 29))
+#test(date(2023, 1, 31).add(months: 1), date(2023, 2, 28))
+#test(date(2024, 3, 31).add(months: -1), date(2024, 2, 29))
+#test(date(2024, 2, 29).add(years: 1), date(2025, 2, 28))
+#test(date(2024, 11, 30).add(months: 3), date(2025, 2, 28))
+#test(date(2024, 1, 31).add(months: 1, days: 1), date(2024, 3, 1))
+#test(date(2024, 12, 25).add(weeks: 2), date(2024, 12, 25) + duration(weeks: 2))
+#test(
+  datetime(year: 2024, month: 1, day: 31, hour: 12, minute: 0, second: 0).add(months: 1, hours: 13),
+  datetime(year: 2024, month: 3, day: 1, hour: 1, minute: 0, second: 0),
+)
+
+--- datetime-add-months-to-time ---
+// Error: 2-56 cannot add years or months to a time
+#datetime(hour: 1, minute: 0, second: 0).add(months: 1)
+
 --- datetime-display-missing-closing-bracket ---
 // Error: 27-34 missing closing bracket for bracket at index 0
 #datetime.today().display("[year")
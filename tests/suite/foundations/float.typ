@@ -13,7 +13,7 @@
 #test(type(float(10)), float)
 
 --- float-constructor-bad-type ---
-// Error: 8-13 expected float, boolean, integer, decimal, ratio, or string, found type
+// Error: 8-13 expected float, boolean, integer, big integer, decimal, ratio, or string, found type
 #float(float)
 
 --- float-constructor-bad-value ---
@@ -79,6 +79,20 @@
 #test(repr(-float.inf), "-float.inf")
 #test(repr(float.nan), "float.nan")
 
+--- float-repr-str-consistent ---
+// Test that floats are formatted with the same shortest digits by `repr` and
+// `str`, with adversarial values.
+#test(repr(0.1 + 0.2), "0.30000000000000004")
+#test(str(0.1 + 0.2), "0.30000000000000004")
+#test(repr(1e22), "1e22")
+#test(str(1e22), "10000000000000000000000")
+#test(repr(1e-5), "1e-5")
+#test(str(1e-5), "0.00001")
+#test(repr(5e-324), "5e-324")
+#test(float(str(5e-324)), 5e-324)
+#test(float(repr(2.2250738585072014e-308)), 2.2250738585072014e-308)
+#test(float(str(2.2250738585072014e-308)), 2.2250738585072014e-308)
+
 --- float-display ---
 // Test floats.
 #12.0 \
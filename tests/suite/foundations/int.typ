@@ -26,7 +26,7 @@
 #test(int(decimal("-224.342211")), -224)
 
 --- int-constructor-bad-type ---
-// Error: 6-10 expected integer, boolean, float, decimal, or string, found length
+// Error: 6-10 expected integer, boolean, float, decimal, big integer, or string, found length
 #int(10pt)
 
 --- int-constructor-bad-value ---
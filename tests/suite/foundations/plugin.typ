@@ -45,3 +45,7 @@
 
 // Error: 2-27 plugin tried to write out of bounds: pointer 0x40000000 is out of bounds for write of length 3
 #p.write_oob(bytes("xyz"))
+
+--- plugin-host-info ---
+// Test that plugins can query the compilation target. This is checked in
+// `custom.rs`.
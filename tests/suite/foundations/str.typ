@@ -54,7 +54,7 @@
 #test(str(4 - 8), "−4")
 
 --- str-constructor-bad-type ---
-// Error: 6-8 expected integer, float, decimal, version, bytes, label, type, or string, found content
+// Error: 6-8 expected integer, float, decimal, big integer, version, bytes, label, type, or string, found content
 #str([])
 
 --- str-constructor-bad-base ---
@@ -65,6 +65,23 @@
 // Error: 18-19 base is only supported for integers
 #str(1.23, base: 2)
 
+--- str-from-float-digits ---
+// Test rounding floats to a number of digits.
+#test(str(0.1 + 0.2, digits: 2), "0.30")
+#test(str(2.675, digits: 2), "2.67")
+#test(str(0.125, digits: 2), "0.13")
+#test(str(-0.125, digits: 2), "−0.13")
+#test(str(-0.004, digits: 2), "0.00")
+#test(str(9.995, digits: 2), "9.99")
+#test(str(9.9951, digits: 2), "10.00")
+#test(str(0.5, digits: 0), "1")
+#test(str(12.0, digits: 3), "12.000")
+#test(str(float.inf, digits: 2), "∞")
+
+--- str-constructor-unsupported-digits ---
+// Error: 19-20 digits are only supported for floats
+#str(123, digits: 2)
+
 --- str-from-and-to-unicode ---
 // Test the unicode function.
 #test(str.from-unicode(97), "a")
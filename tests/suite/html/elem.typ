@@ -0,0 +1,45 @@
+--- html-elem-attrs-typed html ---
+#html.elem("input", attrs: (type: "checkbox", checked: true, disabled: false))
+
+#html.elem("img", attrs: (src: "logo.png", width: 96pt, height: 48pt))
+
+#html.elem("span", attrs: (data-color: red, data-ratio: 1.5, data-count: 3, data-size: 12pt))[Typed]
+
+--- html-elem-void-body ---
+// Error: 19-25 <meta> is a void element and cannot have a body
+// Hint: 19-25 the void elements are area, base, br, col, embed, hr, img, input, link, meta, param, source, track, and wbr
+#html.elem("meta")[Body]
+
+--- html-elem-attr-data-uppercase ---
+// Error: 26-45 data attribute name must not contain uppercase letters
+#html.elem("div", attrs: (data-Color: "red"))
+
+--- html-elem-attr-aria-invalid ---
+// Error: 26-48 ARIA attribute name must only consist of lowercase letters after `aria-`
+#html.elem("div", attrs: (aria-hidden2: "true"))
+
+--- html-elem-attr-relative-length ---
+// Error: 26-38 cannot convert a font-relative length to pixels
+// Hint: 26-38 use an absolute length like `10pt` instead
+#html.elem("div", attrs: (width: 2em))
+
+--- html-elem-attr-invalid-type ---
+// Error: 26-39 expected string, boolean, integer, float, length, or color, found none
+#html.elem("div", attrs: (title: none))
+
+--- html-introspection-shared-template html ---
+// Warning: the page counter is always 1 in HTML export
+// Hint: HTML documents are not split into pages
+#let position = context {
+  let current = query(selector(<chapter>).before(here())).last()
+  [#current.value on page #counter(page).display(), anchor #here().position().anchor]
+}
+#let chapter(id, title) = [#html.elem("span", attrs: (id: id), title)#metadata(title)<chapter>: #position]
+
+#chapter("intro")[Introduction]
+
+#chapter("usage")[Usage]
+
+--- html-rewrite ---
+// Test that embedders can rewrite the HTML document before encoding it. This
+// is checked in `custom.rs`.
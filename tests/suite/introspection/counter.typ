@@ -164,3 +164,59 @@ B
 #context test(c.get(), (1,))
 #c.step(level: 3)
 #context test(c.get(), (1, 0, 1))
+
+--- counter-snapshot-restore ---
+// Numbering pauses for an interlude and resumes at the saved value.
+#let c = counter("problem")
+#c.step()
+#c.step()
+#c.snapshot("main")
+#c.update(0)
+#c.step()
+#context test(c.get(), (1,))
+#context test(c.at-snapshot("main"), (2,))
+#c.restore("main")
+#context test(c.get(), (2,))
+#c.step()
+#context test(c.get(), (3,))
+
+--- counter-snapshot-replace ---
+#let c = counter("c")
+#c.update(5)
+#c.snapshot("a")
+#c.update(7)
+#c.snapshot("a")
+#c.update(1)
+#c.restore("a")
+#context test(c.get(), (7,))
+
+--- counter-restore-missing ---
+// Error: 2-30 counter has no snapshot named "nope"
+#counter("x").restore("nope")
+#context counter("x").get()
+
+--- counter-offset-remaining ---
+// The questions remaining are computed on every page from the total and the
+// current count.
+#let q = counter("question")
+#set page(height: 40pt, header: context {
+  let remaining = q.final().first() - q.get().first()
+  test(remaining, (3, 2, 1).at(here().page() - 1))
+  test(q.offset((1,)), (here().page(),))
+})
+
+#q.step() Question
+#pagebreak()
+#q.step() Question
+#pagebreak()
+#q.step() Question
+
+--- counter-offset-levels ---
+#let c = counter("c")
+#c.update((1, 2))
+#context test(c.offset((0, 1, 1)), (1, 3, 1))
+#context test(c.offset(-1), (0, 2))
+
+--- counter-offset-negative ---
+// Error: 10-33 counter value would become negative
+#context counter("x").offset(-1)
@@ -250,3 +250,79 @@
   t("b")
   block(height: 1fr, metadata("b"))
 }
+
+--- query-extract-metadata ---
+// Metadata in context expressions is not extracted without layout, which is
+// reported, while ordinary context expressions don't cause warnings. This is
+// checked in `custom.rs`.
+#metadata(1) <a>
+#metadata("two") <b>
+#context [#metadata(3) <c>]
+#context if text.lang == "de" [Hallo]
+
+--- metrics-line-query ---
+// The custom check compiles a paragraph with the `layout-metrics` feature and
+// queries its lines and page breaks.
+
+--- metrics-line-numbers ---
+// The custom check places line numbers in the margin based on queried lines
+// and checks that they are aligned with the lines.
+
+--- introspector-slim ---
+// Test that a reduced introspector only retains the selected elements and
+// still resolves headings and labels. This is checked in `custom.rs`.
+
+--- query-where-contains ---
+#show heading: none
+#show heading.where(body: selector.contains("Appendix")): it => metadata(it.body)
+
+= Introduction
+= Appendix A
+= Appendices
+= The Appendix
+
+#context test(
+  query(metadata).map(it => it.value),
+  ([Appendix A], [The Appendix]),
+)
+
+--- query-where-one-of ---
+#show figure: none
+
+#figure(table[A], caption: [Table])
+#figure(raw("B"), caption: [Code])
+#figure(rect(), caption: [Rect])
+#figure([Tab], kind: table, caption: [Another table])
+
+#context test(query(figure.where(kind: selector.one-of(table, raw))).len(), 3)
+#context test(query(figure.where(kind: selector.one-of(image))).len(), 1)
+
+--- query-where-regex ---
+#metadata("https://typst.app")
+#metadata("https://example.com")
+#metadata("http://typst.app/docs")
+
+#context test(
+  query(metadata.where(value: regex("^https?://typst"))).map(it => it.value),
+  ("https://typst.app", "http://typst.app/docs"),
+)
+
+--- query-where-regex-show ---
+// Regex matchers also select elements in show rules. The later rule takes
+// precedence for the links it matches.
+#show link: it => [#metadata(none) <link>]
+#show link.where(dest: regex("^https?://typst")): it => [#metadata(it.dest) <link>]
+#{
+  link("https://typst.app")[A]
+  link("https://example.com")[B]
+  link("http://typst.app/docs")[C]
+}
+
+#context test(
+  query(<link>).map(it => it.value),
+  ("https://typst.app", none, "http://typst.app/docs"),
+)
+
+--- query-where-matcher-repr ---
+#test(repr(selector.contains("A")), "selector.contains(\"A\")")
+#test(repr(selector.one-of(1, "b")), "selector.one-of(1, \"b\")")
@@ -186,6 +186,59 @@ C
 #block(sticky: true, lines(4))
 E
 
+--- block-keep ---
+// A group that doesn't fit moves to the next page as a whole.
+#set page(height: 100pt)
+#lines(4)
+#block(keep: true)[
+  #block[A] <keep-a>
+  #block[B]
+  #block[C] <keep-c>
+]
+#context {
+  test(locate(<keep-a>).page(), 2)
+  test(locate(<keep-c>).page(), 2)
+}
+
+--- block-keep-too-tall ---
+// A group that is taller than a page breaks normally.
+#set page(height: 60pt)
+A
+#block(keep: true)[
+  #block[B] <keep-b>
+  #lines(6)
+]
+#context test(locate(<keep-b>).page(), 1)
+
+--- block-keep-too-tall-warn ---
+#set page(height: 60pt)
+A
+// Warning: 2-31 block is too tall to be kept together
+// Hint: 2-31 it was broken across regions instead
+#block(keep: "warn", lines(6))
+
+--- block-keep-nested ---
+// The outermost group that fits is kept together.
+#set page(height: 100pt)
+#lines(3)
+#block(keep: true)[
+  #block(keep: true)[
+    #block[A] <nested-a>
+    #block[B]
+  ]
+  #block[C] <nested-c>
+]
+#context test(locate(<nested-a>).page(), 2)
+#context test(locate(<nested-c>).page(), 2)
+
+--- block-keep-sticky ---
+// A sticky heading moves with the group it sticks to.
+#set page(height: 100pt)
+#lines(3)
+= Heading <keep-heading>
+#block(keep: true, lines(3))
+#context test(locate(<keep-heading>).page(), 2)
+
 --- box-clip-rect ---
 // Test box clipping with a rectangle
 Hello #box(width: 1em, height: 1em, clip: false)[#rect(width: 3em, height: 3em, fill: red)]
@@ -0,0 +1,22 @@
+// Test fitting text into a container.
+
+--- fit-text-size ---
+// Test that the largest size that fits is selected, but at most `max`.
+#let card(body) = box(
+  width: 3cm,
+  height: 2cm,
+  stroke: 0.5pt,
+  fit-text(step: 0.5pt, max: 24pt, body),
+)
+#card[The quick brown fox jumps over the lazy dog and keeps running far away.]
+#card[Hi]
+
+--- fit-text-min ---
+// Test that the size is clamped to `min` when nothing fits, so that the body
+// overflows its container.
+#box(
+  width: 2cm,
+  height: 0.5cm,
+  stroke: 0.5pt,
+  fit-text(min: 10pt)[Nothing fits here at the minimum size],
+)
@@ -163,7 +163,7 @@ A
   rect(),
 )
 
---- footnote-duplicate ---
+--- footnote-duplicate a11y-extras ---
 // Test duplicate footnotes.
 #let lang = footnote[Languages.]
 #let nums = footnote[Numbers.]
@@ -358,3 +358,14 @@ C
 #set footnote.entry(separator: v(5em))
 
 #footnote[]
+
+--- footnote-restart-page ---
+// Test that per-page numbering restarts on each page and that a continued
+// footnote is preceded by its indicator.
+#set page(width: 120pt, height: 80pt, margin: 10pt)
+#set footnote(restart: "page")
+#set footnote.entry(separator: none, continuation-indicator: [(cont.)])
+
+A#footnote[x] B#footnote[y]
+#pagebreak()
+C#footnote[z] D#footnote[#lorem(20)]
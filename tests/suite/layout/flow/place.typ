@@ -387,3 +387,51 @@ Paragraph after place.
 #place(auto, float: true, block(height: 100%, width: 100%, fill: aqua))
 #place(auto, float: true, block(height: 100%, width: 100%, fill: red))
 #lines(7)
+
+--- place-avoid-overlap ---
+// Test that margin notes anchored to adjacent lines are moved apart in
+// document order. Each note is taller than a line, so the notes would overlap
+// otherwise.
+#set page(width: 120pt, height: 120pt, margin: (right: 60pt))
+#let note(body) = box(place(
+  right,
+  dx: 55pt,
+  avoid-overlap: "shift-down",
+  group: "notes",
+  block(width: 50pt, height: 30pt, fill: aqua.transparentize(50%), body),
+))
+A#note[N1] \
+B#note[N2] \
+C#note[N3]
+
+--- place-avoid-overlap-both-margins ---
+// Test that notes in opposite margins don't push each other down, while notes
+// in the same margin are still stacked.
+#set page(height: 20pt, margin: (y: 0pt))
+#let note(side, body) = box(place(
+  side,
+  dx: if side == left { -55pt } else { 55pt },
+  avoid-overlap: "shift-down",
+  group: "notes",
+  body,
+))
+#let body = block(width: 50pt, height: 30pt)
+#hide[A#note(left)[#body<a>] B#note(right)[#body<b>] C#note(right)[#body<c>]]
+#context {
+  let y(target) = locate(target).position().y
+  test(y(<b>), y(<a>))
+  test(y(<c>) - y(<b>), 30pt)
+}
+
+--- place-avoid-overlap-none ---
+// Test that notes overlap without overlap avoidance.
+#set page(width: 120pt, height: 120pt, margin: (right: 60pt))
+#let note(body) = box(place(
+  right,
+  dx: 55pt,
+  group: "notes",
+  block(width: 50pt, height: 30pt, fill: aqua.transparentize(50%), body),
+))
+A#note[N1] \
+B#note[N2] \
+C#note[N3]
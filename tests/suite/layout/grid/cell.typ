@@ -82,6 +82,49 @@
   grid.cell(align: left, fill: aqua)[BL], grid.cell(align: top, fill: red.lighten(50%))[TR]
 )
 
+--- grid-cell-align-baseline ---
+// The text cells share their first baseline, while the cell without text stays
+// at the top.
+#set page(width: 160pt, height: auto)
+#table(
+  columns: 3,
+  align: table.baseline,
+  rect(width: 30pt, height: 40pt),
+  text(16pt)[Big \ text],
+  [Small],
+)
+
+--- grid-cell-align-baseline-rowspan ---
+// A rowspan is aligned with the baseline of its first row.
+#set page(width: 160pt, height: auto)
+#table(
+  columns: 2,
+  align: table.baseline + left,
+  table.cell(rowspan: 2)[Spanning],
+  text(16pt)[Big],
+  [Below],
+)
+
+--- grid-cell-align-baseline-override ---
+// Only the cells with baseline alignment are aligned.
+#set page(width: 160pt, height: auto)
+#grid(
+  columns: 3,
+  inset: 4pt,
+  grid.cell(align: grid.baseline, text(16pt)[Big]),
+  grid.cell(align: grid.baseline)[Small],
+  [Top],
+)
+
+--- grid-cell-align-baseline-scoped ---
+// Baseline alignment only exists for grid and table cells.
+// Error: 8-16 unknown variable: baseline
+#align(baseline)[A]
+
+--- grid-cell-align-baseline-not-in-alignment ---
+// Error: 18-26 type alignment does not contain field `baseline`
+#align(alignment.baseline)[A]
+
 --- grid-cell-various-overrides ---
 #grid(
   columns: 2,
@@ -131,3 +174,15 @@
 // Error: 7-19 cannot use `table.cell` as a grid cell
 // Hint: 7-19 use `grid.cell` instead
 #grid(table.cell[])
+
+--- grid-cell-panic-context ---
+// The custom check compiles this with a native show rule for `strong` that
+// panics and checks that the panic is attributed to the second cell. No panic
+// hook is needed for that. The explicit page makes for multiple page runs,
+// which are laid out in parallel.
+#page[Before]
+#grid(
+  columns: 2,
+  [Fine],
+  [*boom*],
+)
@@ -283,3 +283,16 @@ The following:
   // Error: 3-50 cannot create grid with infinite width
   grid(gutter: infinite-length, columns: 2)[A][B]
 })
+
+--- table-min-rows-per-region ---
+// Test that neither the first nor the last region of a table has fewer body
+// rows than the minimum. Only two rows would fit on the first page, so the
+// table starts on the second one, and the last page gets at least three rows.
+#set page(height: 80pt)
+#v(30pt)
+#table(
+  min-rows-per-region: 3,
+  rows: 15pt,
+  inset: 2pt,
+  ..range(10).map(i => [Row #i]),
+)
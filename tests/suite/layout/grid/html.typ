@@ -0,0 +1,33 @@
+--- table-html html ---
+#figure(
+  table(
+    columns: (1fr, 2fr, 1fr),
+    table.header[Name][Value][Unit],
+    table.cell(header: "row")[Mass], [12], [g],
+    table.cell(header: "row", rowspan: 2)[Size], table.cell(colspan: 2)[3 x 4],
+    [5], [cm],
+  ),
+  caption: [Measurements],
+)
+
+--- table-html-footer html ---
+#table(
+  columns: (auto, 50%, 20pt),
+  gutter: 3pt,
+  [A], [B], [C],
+  table.footer(table.cell(colspan: 3)[Total]),
+)
+
+--- grid-html html ---
+#grid(
+  columns: (1fr, auto, 20pt),
+  gutter: 3pt,
+  [A], grid.cell(colspan: 2)[B],
+  grid.cell(rowspan: 2)[C], [D], [E],
+  [F], [G],
+)
+
+--- grid-html-unsupported html ---
+// Warning: 2-52 grid fills and strokes were ignored during HTML export
+// Hint: 2-52 CSS grid has no equivalent for them
+#grid(columns: 2, stroke: 1pt, fill: red, [A], [B])
@@ -193,3 +193,32 @@
   ),
   ..range(0, 10).map(i => ([\##i], table.cell(stroke: green)[123], table.cell(stroke: blue)[456], [789], [?], table.hline(start: 4, end: 5, stroke: red))).flatten()
 )
+
+--- grid-dir ---
+// In Hebrew, the columns of a table and its lines are mirrored unless an
+// explicit direction overrides the text direction. A horizontal stack with
+// an automatic direction follows the text as well.
+#set text(lang: "he")
+#let cells = (table.vline(x: 2, stroke: red), [A], [B], [C])
+#table(columns: (20pt,) * 3, ..cells)
+#table(columns: (20pt,) * 3, dir: ltr, ..cells)
+#stack(dir: auto, spacing: 5pt, [D], [E])
+
+--- grid-dir-align ---
+// The start alignment of cells follows the direction of the table instead of
+// the text direction, so the cells of a right-to-left table in left-to-right
+// text are aligned to the right.
+#place(hide(table(
+  columns: (30pt,) * 2,
+  inset: 0pt,
+  stroke: none,
+  dir: rtl,
+  [#box(width: 5pt)<a>],
+  [#box(width: 5pt)<b>],
+)))
+#context test(locate(<a>).position().x, 65pt)
+#context test(locate(<b>).position().x, 35pt)
+
+--- grid-dir-vertical ---
+// Error: 2-17 grid direction must be horizontal
+#table(dir: ttb)
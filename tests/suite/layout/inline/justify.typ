@@ -156,6 +156,31 @@ int main() {
 
 标注在字间的标点符号（乙式括号省略号以外）通常占一个汉字宽度，使其易于识别、适合配置及排版，有些排版风格完全不对标点宽度进行任何调整。但是为了让文字体裁更加紧凑易读，，，以及执行3.1.4 行首行尾禁则时，就需要对标点符号的宽度进行调整。是否调整取决于……
 
+--- justify-cjk-inter-character ---
+// With inter-character justification (first page), extra space is spread
+// across all characters instead of ending up in the few spaces around Latin
+// words (second page). The Latin words are highlighted so that the gaps around
+// them are visible.
+#set page(width: 120pt, height: auto)
+#set par(justify: true)
+#set text(lang: "zh", font: ("Libertinus Serif", "Noto Serif CJK SC"))
+#show regex("[A-Za-z]+"): highlight
+
+#set text(cjk-justify: "inter-character")
+我们在中文里混排 Typst 和 Rust 这样的 English 单词，行尾依然应当整齐。这段文字用于比较两种不同的两端对齐方式。
+
+#pagebreak()
+#set text(cjk-justify: "inter-word")
+我们在中文里混排 Typst 和 Rust 这样的 English 单词，行尾依然应当整齐。这段文字用于比较两种不同的两端对齐方式。
+
+--- justify-last ---
+// Both lines end at the same position. Overhang is disabled so that the final
+// period doesn't hang into the margin.
+#set page(width: 120pt, height: auto)
+#set par(justify: true, justify-last: true)
+#set text(overhang: false)
+This paragraph's last line is short.
+
 --- justify-without-justifiables ---
 // Test breaking a line without justifiables.
 #set par(justify: true)
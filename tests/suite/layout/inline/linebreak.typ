@@ -139,3 +139,31 @@ Some texts feature many longer
 words. Those are often exceedingly
 challenging to break in a visually
 pleasing way.
+
+--- linebreak-long-paragraph ---
+// Justified paragraphs with too many break opportunities fall back to simple
+// line breaking, while normal paragraphs are still optimized. The warning is
+// only reported once per compilation.
+// Warning: 8:23-8:34 paragraph is too long for optimized line breaking
+// Hint: 8:23-8:34 paragraphs with more than 5000 break opportunities are broken in simple first-fit style
+// Hint: 8:23-8:34 set `par(linebreaks: "optimized")` to optimize them anyway
+#set par(justify: true)
+#context {
+  let height(linebreaks, body) = measure(
+    width: 100pt,
+    par(linebreaks: linebreaks, body),
+  ).height
+  test(height(auto, lorem(100)), height("optimized", lorem(100)))
+  test(height(auto, [#lorem(6000)]), height("simple", [#lorem(6000)]))
+  test(height(auto, [#lorem(5500)]), height("simple", [#lorem(5500)]))
+}
+
+--- linebreak-optimized-negative-costs ---
+// Negative costs can make lines cost nothing, so the optimizer may not prune
+// predecessors by the minimum line cost.
+#set par(justify: true)
+#context {
+  let costs = (hyphenation: -100%, runt: -100%)
+  let body = text(hyphenate: true, costs: costs, lorem(60))
+  test(measure(width: 100pt, body).height > 0pt, true)
+}
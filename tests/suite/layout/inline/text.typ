@@ -32,6 +32,11 @@ waltz vs #text(discretionary-ligatures: true)[waltz]
 #text(number-width: "tabular")[3456789123] \
 #text(number-width: "tabular")[0123456789]
 
+--- text-number-width-synthesized ---
+// Test that tabular figures are synthesized for a font without the `tnum`
+// feature, with a single warning. This is checked in `custom.rs` with a font
+// chosen from the available ones.
+
 --- text-slashed-zero-and-fractions ---
 // Test extra number stuff.
 #set text(font: "IBM Plex Serif")
@@ -43,6 +48,26 @@ waltz vs #text(discretionary-ligatures: true)[waltz]
 #text(features: ("smcp",))[Smcp] \
 fi vs. #text(features: (liga: 0))[No fi]
 
+--- text-ligature-exceptions ---
+// Test that ligatures are only disabled within the configured sequences and
+// that the text is not changed.
+fish #[
+  #set text(ligature-exceptions: "fi")
+  fish #text(ligature-exceptions: "fl")[fish flow]
+] fish flow
+
+--- text-ligature-exceptions-partial ---
+// Only the joint between "f" and "i" is broken up, so the "ff" ligature still
+// forms instead of the "ffi" one.
+office #text(ligature-exceptions: "fi")[office]
+
+--- text-kerning-adjustments ---
+// Test that kerning adjustments are added to the advances of the configured
+// pairs and fold across set rules.
+AVA
+#set text(kerning-adjustments: ("AV": -0.05em))
+AVA #text(kerning-adjustments: ("VA": 0.1em, "AV": -0.1em))[AVA]
+
 --- text-stylistic-set-bad-type ---
 // Error: 26-31 expected none, integer, or array, found boolean
 #set text(stylistic-set: false)
@@ -83,6 +108,36 @@ I'm in#text(tracking: 0.15em + 1.5pt)[ spaace]!
 #set text(tracking: 0.3em)
 النص
 
+--- text-tracking-line-end ---
+// Test that tracking is not applied after the last glyph of a line.
+#let approx(a, b) = assert(calc.abs(a - b) < 1e-6pt)
+#context {
+  let a = measure[a].width
+  let b = measure[b].width
+  approx(measure(text(tracking: 2pt)[ab]).width, a + b + 2pt)
+  approx(measure(text(tracking: 2pt)[a#strong[b]]).width, a + measure[*b*].width + 2pt)
+}
+
+--- text-tracking-line-end-tag ---
+// Test that tracking is not applied after the last glyph of a line that ends
+// with a labelled element.
+#let approx(a, b) = assert(calc.abs(a - b) < 1e-6pt)
+#context {
+  let a = measure[a].width
+  let b = measure[b].width
+  approx(measure(text(tracking: 2pt)[ab#metadata(none) <end>]).width, a + b + 2pt)
+}
+
+--- text-tracking-ligatures ---
+// Test that tracking disables standard ligatures unless they are enabled
+// explicitly.
+#let approx(a, b) = assert(calc.abs(a - b) < 1e-6pt)
+#context {
+  let separate = measure(text(ligatures: false)[fi]).width
+  approx(measure(text(tracking: 3pt)[fi]).width, separate + 3pt)
+  approx(measure(text(tracking: 3pt, ligatures: true)[fi]).width, measure[fi].width)
+}
+
 --- text-spacing ---
 // Test word spacing.
 #set text(spacing: 1em)
@@ -92,3 +147,17 @@ My text has spaces.
 // Test word spacing relative to the font's space width.
 #set text(spacing: 50% + 1pt)
 This is tight.
+
+--- text-ligature-exceptions-single-char ---
+// Error: 32-43 ligature exception must consist of at least two characters
+// Hint: 32-43 a ligature always joins multiple characters
+#set text(ligature-exceptions: ("fi", "f"))
+
+--- text-kerning-adjustments-bad-pair ---
+// Error: 32-47 kerning pair must consist of exactly two characters, found "AVA"
+// Hint: 32-47 write the pair as a string like "AV"
+#set text(kerning-adjustments: ("AVA": -0.1em))
+
+--- text-kerning-adjustments-bad-amount ---
+// Error: 32-42 expected length, found ratio
+#set text(kerning-adjustments: ("AV": 5%))
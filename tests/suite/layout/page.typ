@@ -137,7 +137,7 @@
 // Error: 20-23 must be `left` or `right`
 #set page(binding: top)
 
---- page-marginals ---
+--- page-marginals a11y-extras ---
 #set page(
   paper: "a8",
   margin: (x: 15pt, y: 30pt),
@@ -384,3 +384,129 @@ text
 #metadata(none)
 #set page(fill: red)
 2
+
+--- page-max-pages ---
+// The custom check compiles this with a limit of three pages.
+#set page(width: 40pt, height: auto, margin: 5pt)
+#set text(6pt)
+#for i in range(1, 21) [
+  #block[Page #i]
+  #pagebreak(weak: true)
+]
+
+--- page-max-pages-outline ---
+// The custom check compiles this with a limit of three pages, but the outline
+// depends on all of them.
+#set page(width: 60pt, height: auto, margin: 5pt)
+#set text(6pt)
+#outline()
+#for i in range(1, 6) [
+  #pagebreak()
+  = Page #i
+]
+
+--- page-max-pages-total ---
+// The custom check compiles this with a limit of three pages, but the page
+// numbering shows the total number of pages.
+#set page(width: 40pt, height: 30pt, margin: 5pt, numbering: "1/1")
+#set text(6pt)
+#for i in range(1, 6) [
+  #i
+  #pagebreak(weak: true)
+]
+
+--- page-max-pages-header ---
+// The custom check compiles this with a limit of three pages, but the header
+// shows the total number of pages.
+#set page(
+  width: 60pt,
+  height: 40pt,
+  margin: (top: 15pt, rest: 5pt),
+  header: context [Page #here().page() of #counter(page).final().first()],
+)
+#set text(6pt)
+#for i in range(1, 6) [
+  #i
+  #pagebreak(weak: true)
+]
+
+--- page-max-pages-final-in-show-rule ---
+// The custom check compiles this with a limit of three pages, but the show
+// rule reads the final page count without an explicit `context`.
+#set page(width: 40pt, height: 30pt, margin: 5pt)
+#set text(6pt)
+#show heading: it => [#it.body of #counter(page).final().first()]
+#for i in range(1, 6) [
+  = #i
+  #pagebreak(weak: true)
+]
+
+--- page-context-dictionary-header ---
+// The custom check verifies that the header shows the first and last term of
+// each page without additional layout iterations.
+#set page(
+  width: 80pt,
+  height: 80pt,
+  margin: (top: 20pt, rest: 5pt),
+  header: context {
+    let info = page.context()
+    if info.first != none [
+      #info.first.body — #info.last.body
+    ]
+  },
+)
+#set text(6pt)
+#show heading: set text(6pt)
+#for term in (
+  "Aardvark", "Abacus", "Abbey", "Abbot", "Abdomen", "Abduct", "Abide",
+  "Ability", "Able", "Aboard",
+) [
+  = #term
+  A word.
+]
+
+--- page-context-headings ---
+// The page number is the physical one, even if the page counter is updated.
+#set page(height: 60pt, foreground: context {
+  let info = page.context()
+  test(info.headings.len(), 1)
+  test(info.first, info.last)
+  test(info.first.func(), heading)
+  test(info.number, if info.first.body == [A] { 1 } else { 2 })
+})
+#counter(page).update(10)
+= A
+#pagebreak()
+= B
+
+--- page-context-outside-marginals ---
+// Error: 10-24 page information is only available in the marginals of a page
+// Hint: 10-24 use it in the header, footer, background, or foreground
+#context page.context()
+
+--- page-bleed-boxes ---
+// The custom check exports a page with a bleed of 3mm to PDF, PNG, and SVG and
+// checks the page boxes and that the fill covers the bleed.
+
+--- page-bleed-full-size ---
+#set page(width: 100pt, height: 50pt, bleed: (x: 5pt, top: 10pt))
+#context test(page.full-size(), (width: 110pt, height: 60pt))
+
+--- page-bleed-full-size-flipped ---
+#set page(width: 100pt, height: 50pt, flipped: true, bleed: 2pt)
+#context test(page.full-size(), (width: 54pt, height: 104pt))
+
+--- page-bleed-full-size-auto ---
+#set page(width: 100pt, height: auto)
+// Error: 10-26 the full size of a page is only known if its size is fixed
+// Hint: 10-26 use relative sizes like `100%` in the background instead
+#context page.full-size()
+
+--- page-orientation-rotate-content ---
+// Test that a flipped page with rotated content stays in portrait orientation,
+// keeps its page number in the same place as the other pages, and is marked
+// for rotation in PDF export. This is checked in `custom.rs`.
+
+--- page-orientation-invalid ---
+// Error: 24-34 expected "flip-box" or "rotate-content"
+#set page(orientation: "sideways")
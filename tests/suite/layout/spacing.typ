@@ -76,3 +76,51 @@ This is the first line \ #h(2cm, weak: false) A new line
 
 --- issue-5253-consecutive-weak-space-math ---
 $= thin thin$ a
+
+--- spacing-v-collapse-max ---
+// Max-collapsing spacing merges with the heading's and the paragraph's
+// spacing into the largest one, so both columns line up.
+#set page(width: 120pt, height: auto)
+#set par(spacing: 8pt)
+#grid(
+  columns: (1fr, 1fr),
+  {
+    show heading: it => {
+      it
+      v(12pt, collapse: "max")
+    }
+    [= A]
+    [B]
+  },
+  {
+    show heading: set block(below: 12pt)
+    [= A]
+    [B]
+  },
+)
+
+--- spacing-v-collapse-sum ---
+// By default, spacing adds up with the heading's spacing.
+#set page(width: 120pt, height: auto)
+#set par(spacing: 8pt)
+#grid(
+  columns: (1fr, 1fr),
+  {
+    show heading: set block(below: 12pt)
+    show heading: it => {
+      it
+      v(4pt)
+    }
+    [= A]
+    [B]
+  },
+  {
+    show heading: set block(below: 16pt)
+    [= A]
+    [B]
+  },
+)
+
+--- spacing-v-collapse-invalid ---
+// Error: 19-24 expected "sum" or "max"
+#v(1pt, collapse: "min")
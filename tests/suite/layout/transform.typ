@@ -165,3 +165,33 @@ Hello #skewed[World]!\
 
 #set skew(reflow: true)
 Hello #skewed[World]!
+
+--- transform-rotate-reflow-inline ---
+// A reflowing rotation within a paragraph is laid out inline and the line
+// makes room for the rotated bounding box, centered on the baseline.
+#context {
+  let word = measure[NEW!]
+  let size = measure[a#rotate(90deg, reflow: true)[NEW!]b]
+  let width = measure[a].width + word.height + measure[b].width
+  assert(calc.abs(size.width - width) < 0.01pt)
+  assert(calc.abs(size.height - word.width) < 0.01pt)
+}
+
+--- transform-rotate-reflow-inline-baseline ---
+// With a full baseline shift, the rotated box hangs below the baseline.
+#context {
+  let word = measure[NEW!]
+  let size = measure[a#rotate(90deg, reflow: true, baseline: 100%)[NEW!]]
+  assert(calc.abs(size.height - word.width - measure[a].height) < 0.01pt)
+}
+
+--- transform-rotate-reflow-table-header ---
+// Rotated table headers size the header row to the length of the text.
+#context {
+  let word = measure[Header]
+  let size = measure(table(
+    columns: 2,
+    table.header(rotate(-90deg, reflow: true)[Header], [B]),
+  ))
+  assert(calc.abs(size.height - word.width - 10pt) < 0.01pt)
+}
@@ -25,3 +25,67 @@
 // Test error numbering with dictionary rows.
 // Error: 6-28 failed to parse CSV (found 3 instead of 2 fields in line 3)
 #csv("/assets/data/bad.csv", row-type: dictionary)
+
+--- csv-decode-large-slice ---
+// Test selecting rows and columns from a large file.
+#let data = range(20000).map(i => str(i) + "," + str(i * 2) + ",x").join("\n")
+#let rows = csv.decode(data, rows: (100, 200), columns: (1, 0))
+#test(rows.len(), 100)
+#test(rows.first(), ("200", "100"))
+#test(rows.last(), ("398", "199"))
+#let tail = csv.decode(
+  data,
+  rows: (19990, 20010),
+  filter: row => calc.even(int(row.at(0))),
+)
+#test(tail.map(row => row.at(0)), ("19990", "19992", "19994", "19996", "19998"))
+
+--- csv-decode-columns-by-name ---
+// Test selecting columns by header and filtering rows.
+#let data = "name,value\nA,1\nB,12\nC,7\nD,30"
+#let rows = csv.decode(
+  data,
+  row-type: dictionary,
+  rows: (1, 4),
+  columns: ("value", "name"),
+  filter: row => int(row.value) > 10,
+)
+#test(rows, ((value: "12", name: "B"), (value: "30", name: "D")))
+#test(csv.decode(data, rows: (2, 2)), ())
+
+--- csv-decode-rows-not-bounds ---
+// Error: 27-36 rows must be given as a start and an end index
+// Hint: 27-36 use `(start, end)` to select the rows from `start` up to, but not including, `end`
+#csv.decode("a\nb", rows: (0, 1, 2))
+
+--- csv-decode-rows-reversed ---
+// Error: 27-33 the end of the rows must not be before their start
+#csv.decode("a\nb", rows: (2, 0))
+
+--- csv-decode-columns-without-header ---
+// Error: 13-18 cannot select columns by name without a header row
+// Hint: 13-18 use `row-type: dictionary` to read the header row
+#csv.decode("a,b", columns: ("a",))
+
+--- csv-decode-column-missing ---
+// Error: 13-23 column "c" does not exist
+#csv.decode("a,b\n1,2", row-type: dictionary, columns: ("c",))
+
+--- csv-decode-column-out-of-bounds ---
+// Error: 13-18 column index 2 is out of bounds (2 fields in line 1)
+#csv.decode("a,b", columns: (2,))
+
+--- csv-decode-columns-mixed ---
+// Error: 27-35 columns must be selected either all by name or all by index
+#csv.decode("a", columns: ("a", 0))
+
+--- csv-decode-filter-error ---
+// Test that errors in the filter mention the row.
+// Error: 36-55 cannot divide by zero
+// Hint: 36-55 error occurred while filtering row 1 (line 2)
+#csv.decode("1\n0", filter: row => 10 / int(row.at(0)) > 1)
+
+--- csv-decode-filter-not-bool ---
+// Error: 26-38 expected boolean, found string
+// Hint: 26-38 error occurred while filtering row 0 (line 1)
+#csv.decode("a", filter: row => "yes")
@@ -14,3 +14,27 @@
 // but not overflow
 #let bignum = json("/assets/data/big-number.json")
 #bignum
+
+--- json-decode-rows ---
+// Test selecting elements of a top-level array.
+#let data = "[1, [2], {\"a\": 3}, 4, 5]"
+#test(json.decode(data, rows: (1, 3)), ((2,), (a: 3)))
+#test(json.decode(data, rows: (3, 10)), (4, 5))
+#test(
+  json("/assets/data/zoo.json", rows: (1, 2), columns: ("name",)),
+  ((name: json("/assets/data/zoo.json").at(1).name),),
+)
+
+--- json-decode-columns ---
+// Test selecting columns from objects and arrays.
+#let objects = "[{\"name\": \"A\", \"value\": 1, \"extra\": true}, {\"value\": 2}]"
+#test(
+  json.decode(objects, columns: ("value", "name")),
+  ((value: 1, name: "A"), (value: 2)),
+)
+#test(json.decode("[[1, 2, 3], [4]]", columns: (2, 0)), ((3, 1), (4,)))
+
+--- json-decode-columns-wrong-type ---
+// Error: 14-36 expected array for row 1, found dictionary
+// Hint: 14-36 columns can only be selected by index from arrays
+#json.decode("[[1, 2], {\"a\": 1}]", columns: (1,))
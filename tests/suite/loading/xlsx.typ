@@ -0,0 +1,95 @@
+--- xlsx-workbook ---
+// Test reading typed cells and dates from XLSX files. Formulas are not
+// evaluated, their cached values are used.
+#let data = xlsx("/tests/assets/xlsx/grades.xlsx")
+#test(xlsx.sheets("/tests/assets/xlsx/grades.xlsx"), ("Grades", "Totals"))
+#test(data.len(), 2)
+#test(data.at(0), (
+  Name: "Alice",
+  Grade: 1.7,
+  Passed: true,
+  Date: datetime(year: 2024, month: 3, day: 1),
+))
+#test(type(data.at(1).Grade), int)
+#test(data.at(1).Passed, false)
+#test(
+  data.at(1).Date,
+  datetime(year: 2024, month: 3, day: 2, hour: 12, minute: 0, second: 0),
+)
+#test(
+  xlsx("/tests/assets/xlsx/grades.xlsx", sheet: 1, header: false),
+  (("Total", 4.7),),
+)
+
+--- xlsx-warn-formulas ---
+// Warning: 18-50 sheet "Totals" contains formulas
+// Hint: 18-50 the values computed when the file was last saved are used
+// Hint: 18-50 the first formula is in cell B1
+#let data = xlsx("/tests/assets/xlsx/grades.xlsx", sheet: "Totals", warn-formulas: true)
+
+--- xlsx-ods ---
+// Test reading an ODS file whose repeated empty rows and cells span the whole
+// sheet.
+#test(
+  xlsx("/tests/assets/xlsx/tasks.ods"),
+  ((Task: "Essay", Due: datetime(year: 2024, month: 3, day: 1)),),
+)
+
+--- xlsx-ods-later-sheets ---
+// Test that an ODS file is only read up to the requested sheet, so that later
+// sheets don't matter.
+#test(xlsx.sheets("/tests/assets/xlsx/sheets.ods"), ("First", "Broken"))
+#test(xlsx("/tests/assets/xlsx/sheets.ods"), ((Task: "Essay"),))
+
+--- xlsx-ods-cell-invalid-number ---
+// Error: 7-38 failed to convert cell A1 in sheet "Broken" (invalid number)
+#xlsx("/tests/assets/xlsx/sheets.ods", sheet: 1)
+
+--- xlsx-color-format ---
+// Test that colors in number formats don't make numbers dates.
+#test(
+  xlsx("/tests/assets/xlsx/formats.xlsx"),
+  ((Amount: 5, Date: datetime(year: 2024, month: 3, day: 1)),),
+)
+
+--- xlsx-duplicate-header ---
+// Error: 7-40 sheet "Duplicates" has multiple columns with the header "Name"
+// Hint: 7-40 columns A and C have the same header
+// Hint: 7-40 rename one of the headers or use `header: false`
+#xlsx("/tests/assets/xlsx/formats.xlsx", sheet: "Duplicates")
+
+--- xlsx-duplicate-header-disabled ---
+#test(
+  xlsx("/tests/assets/xlsx/formats.xlsx", sheet: "Duplicates", header: false),
+  (("Name", "Score", "Name"), ("Ada", 3, "Lovelace")),
+)
+
+--- xlsx-cell-error ---
+// Error: 7-39 cell B1 in sheet "Data" contains the error #DIV/0!
+#xlsx("/tests/assets/xlsx/errors.xlsx", sheet: 0)
+
+--- xlsx-cell-invalid-number ---
+// Error: 7-39 failed to convert cell C1 in sheet "Numbers" (invalid number)
+#xlsx("/tests/assets/xlsx/errors.xlsx", sheet: "Numbers")
+
+--- xlsx-sheet-too-large ---
+// Error: 7-39 sheet "Huge" exceeds the maximum size
+// Hint: 7-39 sheets can have at most 16384 columns and 1048576 rows
+#xlsx("/tests/assets/xlsx/errors.xlsx", sheet: "Huge")
+
+--- xlsx-sheet-missing ---
+// Error: 7-39 sheet "Missing" does not exist
+// Hint: 7-39 available sheets are "Data", "Numbers", "Huge"
+#xlsx("/tests/assets/xlsx/errors.xlsx", sheet: "Missing")
+
+--- xlsx-file-not-found ---
+// Error: 7-18 file not found (searched at tests/suite/loading/nope.xlsx)
+#xlsx("nope.xlsx")
+
+--- xlsx-decode-invalid ---
+// Error: 14-32 file is not a valid XLSX or ODS spreadsheet
+#xlsx.decode(bytes("not a zip"))
+
+--- xlsx-sheet-negative ---
+// Error: 32-34 number must be at least zero
+#xlsx.decode(bytes(()), sheet: -1)
@@ -59,6 +59,14 @@ $ ext(bar.v) quad ext(bar.v.double) quad
   ext(brace.l) quad ext(brace.r) quad
   ext(bracket.l) quad ext(bracket.r) $
 
+--- math-stretch-scaled-fallback ---
+// Test that a glyph without an assembly is scaled when its largest variant is
+// way too small.
+// Warning: 3-32 glyph `⟨` was scaled because the font New Computer Modern Math cannot stretch it far enough
+// Hint: 3-32 it may appear distorted
+// Hint: 3-32 try a math font that provides larger variants for it
+$ stretch(angle.l, size: #20em) $
+
 --- math-stretch-shorthand ---
 // Test stretch when base is given with shorthand.
 $stretch(||, size: #2em)$
@@ -45,3 +45,12 @@ _abc $sin(x) "abc"$_ \
 $italic(sin(x) "abc" #box[abc])$ \
 *abc $sin(x) "abc"$* \
 $bold(sin(x) "abc" #box[abc])$ \
+
+--- math-font-cal-override ---
+// Test that an alphabet font only replaces the letters of its alphabet. The
+// font doesn't have the styled letter, so the plain one is used.
+// Warning: 3:6-3:7 font Libertinus Serif has no calligraphic letters
+// Hint: 3:6-3:7 the plain letter is used instead
+$cal(A) + x$ \
+#set math.equation(cal-font: "Libertinus Serif")
+$cal(A) + x$
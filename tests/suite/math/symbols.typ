@@ -6,12 +6,12 @@
 
 --- math-symbol-underscore ---
 #let sym = symbol("s", ("test_underscore", "s"))
-// Error: 6-10 unknown symbol modifier
+// Error: 6-10 unknown symbol modifier "test", the only available modifier is "test_underscore"
 $sym.test_underscore$
 
 --- math-symbol-dash ---
 #let sym = symbol("s", ("test-dash", "s"))
-// Error: 6-10 unknown symbol modifier
+// Error: 6-10 unknown symbol modifier "test", the only available modifier is "test-dash"
 $sym.test-dash$
 
 --- math-symbol-double ---
@@ -20,10 +20,24 @@ $sym.test-dash$
 
 --- math-symbol-double-underscore ---
 #let sym = symbol("s", ("one.test_underscore", "s"))
-// Error: 10-14 unknown symbol modifier
+// Error: 10-14 unknown symbol modifier "test", the only available modifier is "test_underscore"
 $sym.one.test_underscore$
 
 --- math-symbol-double-dash ---
 #let sym = symbol("s", ("one.test-dash", "s"))
-// Error: 10-14 unknown symbol modifier
+// Error: 10-14 unknown symbol modifier "test", the only available modifier is "test-dash"
 $sym.one.test-dash$
+
+--- math-symbol-class-override ---
+// A custom symbol with a relation class is spaced like a built-in relation.
+#let corr = symbol("x", (modifiers: "rel", value: "x", math-class: "relation"))
+#context {
+  let gap(eq, symbol) = measure(eq).width - measure(symbol).width
+  let rel = gap($a = b$, $=$)
+  test(calc.abs((gap($a corr.rel b$, $corr.rel$) - rel).pt()) < 1e-6, true)
+  test(gap($a corr b$, $corr$) < rel, true)
+}
+
+$a corr b$ \
+$a corr.rel b$ \
+$a = b$
@@ -65,3 +65,17 @@ Now we have multiple bibliographies containing @glacier-melt @keshav2007read
 @Zee04
 
 #bibliography("/assets/bib/works_too.bib")
+
+--- bibliography-locale-order ---
+// Test that alphabetically sorted references follow the alphabet of the
+// text language, so that "Ärger" is sorted between "Apfel" and "Zebra".
+#set page(width: 200pt)
+#set text(lang: "de")
+#bibliography("/tests/assets/bib/umlauts.yml", style: "apa", full: true)
+
+--- bibliography-locale-order-numeric ---
+// Numeric styles keep the order of citation, which determines the numbers.
+#set page(width: 200pt)
+#set text(lang: "de")
+@zebra @aerger @apfel
+#bibliography("/tests/assets/bib/umlauts.yml", style: "ieee")
@@ -147,3 +147,28 @@ B #cite(<netwok>) #cite(<arrgh>).
 // Error: 7-17 expected label, found string
 // Hint: 7-17 use `label("%@&#*!\\")` to create a label
 #cite("%@&#*!\\")
+
+--- cite-group-sort ---
+// Test the sorting of adjacent citations. APA sorts by author by default,
+// which puts Astley (2020) before Leeson (2007).
+#set page(width: auto)
+#set cite(style: "apa")
+@netwok @arrgh @quark \
+#set cite(group-sort: "year")
+@netwok @arrgh @quark \
+#set cite(group-sort: "none")
+@arrgh @netwok @quark \
+@netwok @arrgh @quark
+
+#show bibliography: none
+#bibliography("/assets/bib/works.bib")
+
+--- cite-group-false ---
+// A citation that is excluded from grouping gets its own parenthetical.
+#set page(width: auto)
+#set cite(style: "apa")
+@netwok #cite(<arrgh>, group: false) @quark \
+@netwok @quark #cite(<arrgh>, group: false)
+
+#show bibliography: none
+#bibliography("/assets/bib/works.bib")
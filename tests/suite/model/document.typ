@@ -8,9 +8,18 @@ What's up?
 #set document(author: ("A", "B"), date: datetime.today())
 
 --- document-date-bad ---
-// Error: 21-28 expected datetime, none, or auto, found string
+// Error: 21-28 expected datetime, none, "source", or auto
 #set document(date: "today")
 
+--- document-date-source ---
+// The test world provides no modification dates. The date derivation itself is
+// checked in `custom.rs` with a world that does.
+// Error: 2-30 cannot derive the document date from its source files
+// Hint: 2-30 the environment does not provide modification dates
+// Hint: 2-30 try setting the date explicitly instead
+#set document(date: "source")
+#let data = read("/assets/text/hello.txt")
+
 --- document-author-bad ---
 // Error: 23-29 expected string, found integer
 #set document(author: (123,))
@@ -52,3 +61,74 @@ Hello
   set document(author: "Changed") if "Normal" in document.author
   set document(title: "Changed") if document.title ==  "Normal"
 }
+
+--- document-prewarm ---
+// The custom check compiles variants of this with headings after warming the
+// cache.
+#let greet(name) = [Hello, #name!]
+#show heading: it => greet(it.body)
+
+--- document-anchors ---
+// Test that labelled headings get the same anchors in PDF and HTML and that
+// the `anchor` function returns them. This is checked in `custom.rs`.
+
+--- document-html-numbering-bad ---
+// Error: 31-35 expected "text" or "css"
+#set document(html-numbering: "js")
+
+--- document-metadata-reserved ---
+// Error: 25-42 metadata key "title" is reserved
+// Hint: 25-42 use the `title` parameter of `document` instead
+#set document(metadata: (title: "Report"))
+
+--- document-metadata-reserved-normalized ---
+// Error: 25-49 metadata key "Creation_Date" is reserved
+// Hint: 25-49 it collides with a standard metadata field
+#set document(metadata: (Creation_Date: "today"))
+
+--- document-metadata-bad-key ---
+// Error: 25-37 metadata key "2nd" is not valid
+// Hint: 25-37 keys must start with a letter and may only contain ASCII letters, digits, hyphens, and underscores
+#set document(metadata: ("2nd": "x"))
+
+--- document-metadata-bad-value ---
+// Error: 25-43 expected string, integer, or datetime, found array
+#set document(metadata: (tags: ("a", "b")))
+
+--- document-label-policy ---
+// Test that labels which only differ in case or in underscores and hyphens
+// are reported once per pair and that labels following the policy compile
+// silently. Repeating the same label is fine.
+#set document(label-policy: (
+  pattern: regex("(?i)^(fig|sec):[a-z0-9_-]+$"),
+  near-duplicates: "warn",
+))
+#metadata(none) <sec:intro>
+#metadata(none) <fig:my-results>
+#metadata(none) <fig:my-results>
+#metadata(none) <fig:other>
+// Warning: 2-16 label `<fig:my_results>` is easily confused with `<fig:my-results>`
+// Hint: 2-16 labels that only differ in case or in underscores and hyphens are easily mixed up in references
+#metadata(none) <fig:my_results>
+// Warning: 2-16 label `<FIG:MY-RESULTS>` is easily confused with `<fig:my-results>`
+// Hint: 2-16 labels that only differ in case or in underscores and hyphens are easily mixed up in references
+// Warning: 2-16 label `<FIG:MY-RESULTS>` is easily confused with `<fig:my_results>`
+// Hint: 2-16 labels that only differ in case or in underscores and hyphens are easily mixed up in references
+#metadata(none) <FIG:MY-RESULTS>
+
+--- document-label-policy-none ---
+// Without a policy, near-duplicate labels are not checked.
+#metadata(none) <fig:my-results>
+#metadata(none) <fig:my_results>
+#metadata(none) <FIG:MY-RESULTS>
+
+--- document-label-policy-pattern ---
+#set document(label-policy: (pattern: regex("^(fig|sec):[a-z0-9-]+$")))
+#metadata(none) <sec:intro>
+// Error: 2-16 label `<Results>` does not match the label pattern
+// Hint: 2-16 the document's label policy requires labels to match regex("^(fig|sec):[a-z0-9-]+$")
+#metadata(none) <Results>
+
+--- document-label-policy-bad-key ---
+// Error: 29-44 unexpected key "prefix", valid keys are "pattern" and "near-duplicates"
+#set document(label-policy: (prefix: "fig"))
@@ -72,3 +72,8 @@ Normal
 // Warning: 11-13 no text within stars
 // Hint: 11-13 using multiple consecutive stars (e.g. **) has no additional effect
 **not bold**
+
+--- warning-dedup ---
+// The custom check emits the same warning many times and checks that it is
+// reported once with its count, and that warnings of the same kind at many
+// places are capped.
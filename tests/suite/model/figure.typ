@@ -30,7 +30,7 @@ We can clearly see that @fig-cylinder and
   caption: [Start-aligned]
 )
 
---- figure-table ---
+--- figure-table a11y-extras ---
 // Testing figures with tables.
 #figure(
   table(
@@ -216,9 +216,61 @@ We can clearly see that @fig-cylinder and
 #set figure.caption(position: top)
 
 --- figure-caption-position-bad ---
-// Error: 31-38 expected `top` or `bottom`, found horizon
+// Error: 31-38 expected `top`, `bottom`, `start`, or `end`, found horizon
 #set figure.caption(position: horizon)
 
+--- figure-caption-position-per-kind ---
+#set figure.caption(position: (table: top, image: bottom))
+#show figure.caption: it => [#metadata(none) <caption> #it]
+
+#figure(
+  table[#metadata(none) <body> A],
+  caption: [Above],
+)
+
+#figure(
+  rect[#metadata(none) <body> B],
+  caption: [Below],
+)
+
+#figure(
+  [#metadata(none) <body> C],
+  caption: [Below by default],
+  kind: "sketch",
+  supplement: [Sketch],
+)
+
+#context {
+  let ys(label) = query(label).map(it => it.location().position().y)
+  let (captions, bodies) = (ys(<caption>), ys(<body>))
+  test(captions.at(0) < bodies.at(0), true)
+  test(captions.at(1) > bodies.at(1), true)
+  test(captions.at(2) > bodies.at(2), true)
+}
+
+--- figure-caption-position-per-kind-bad ---
+// Error: 31-47 expected `top`, `bottom`, `start`, or `end`, found horizon
+#set figure.caption(position: (table: horizon))
+
+--- figure-caption-position-side ---
+#set page(width: 200pt, margin: 0pt)
+#set figure(gap: 10pt)
+#set figure.caption(position: start, side-width: 50pt)
+#show figure.caption: it => layout(size => test(size.width, 50pt))
+
+#figure(
+  layout(size => test(size.width, 140pt)),
+  caption: [On the side],
+)
+
+--- figure-caption-position-side-rtl ---
+#set text(dir: rtl)
+#set figure.caption(position: end, side-width: 40%)
+#figure(
+  rect(width: 100%, height: 20pt),
+  caption: [Next to the body],
+)
+
 --- figure-localization-fr ---
 // Test French
 #set text(lang: "fr")
@@ -244,6 +296,38 @@ We can clearly see that @fig-cylinder and
     caption: [Пятиугольник],
 )
 
+--- figure-supplement-form ---
+// Test that a supplement function that knows about forms declines the
+// supplement differently in references than in captions.
+#set text(lang: "ru")
+#set figure(supplement: (form: none, ..) => {
+  if form == "reference" [рис.] else [Рисунок]
+})
+#figure(rect(height: 10pt), caption: [Круг])
+#figure(rect(height: 10pt), caption: [Треугольник]) <tri>
+См. @tri.
+
+--- figure-supplement-form-builtin ---
+// The built-in supplement has a reference form in Russian.
+#set text(lang: "ru")
+#figure(rect(height: 10pt), caption: [Круг]) <circle>
+См. @circle.
+
+--- figure-supplement-form-unchanged ---
+// English and plain content supplements are the same in both forms.
+#figure(rect(height: 10pt), caption: [A circle]) <circle>
+#figure(rect(height: 10pt), caption: [A dot], supplement: [Dot]) <dot>
+See @circle and @dot.
+
+--- figure-supplement-form-sink ---
+// A supplement function with just an argument sink doesn't receive the form.
+#set figure(supplement: (..args) => {
+  test(args.named(), (:))
+  [Shape]
+})
+#show figure: none
+#figure(rect(height: 10pt), caption: [A circle])
+
 --- figure-localization-el ---
 // Test Greek
 #set text(lang: "el")
@@ -1,6 +1,6 @@
 // Test headings.
 
---- heading-basic ---
+--- heading-basic a11y-extras ---
 // Different number of equals signs.
 
 = Level 1
@@ -115,3 +115,56 @@ Not in heading
 // Error: 1:19-1:25 cannot reference heading without numbering
 // Hint: 1:19-1:25 you can enable heading numbering with `#set heading(numbering: "1.")`
 Cannot be used as @intro
+
+--- heading-auto-label ---
+// Labels are generated for unlabelled headings, with suffixes for duplicates.
+// An explicit label is kept and the generated ones avoid it.
+#set heading(numbering: "1.", auto-label: true)
+= Introduction
+See @results, @results-2, and @custom-2.
+= Results
+= Results
+= Über Größe
+= Explicit <custom>
+= Custom
+
+#context {
+  let titles(name) = query(label(name)).map(it => it.body.text)
+  test(titles("introduction"), ("Introduction",))
+  test(titles("results"), ("Results",))
+  test(titles("results-2"), ("Results",))
+  test(titles("über-größe"), ("Über Größe",))
+  test(titles("custom"), ("Explicit",))
+  test(titles("custom-2"), ("Custom",))
+  test(titles("explicit"), ())
+
+  // The duplicates are numbered in document order.
+  test(counter(heading).at(<results>), (2,))
+  test(counter(heading).at(<results-2>), (3,))
+}
+
+--- heading-auto-label-func html ---
+// Functions can provide the labels, and HTML export uses them as ids.
+#set heading(auto-label: it => "sec-" + lower(it.body.text))
+= Intro
+= Outro <end>
+
+--- html-numbering-css html ---
+// Numbered headings and figures are numbered with CSS counters in HTML export,
+// except for numbering functions.
+#set document(html-numbering: "css")
+#set heading(numbering: "1.a")
+= Intro
+== Scope
+= Method
+#figure([A], caption: [Cap], kind: "sketch", supplement: [Sketch])
+// Warning: 2-74 numbering functions cannot be displayed with CSS counters
+// Hint: 2-74 the number was written as text instead
+#heading(level: 2, numbering: (..n) => "§" + str(n.pos().last()))[Custom]
+
+--- html-numbering-default html ---
+// By default, the numbers are part of the text and there is no stylesheet.
+#set heading(numbering: "1.a")
+= Intro
+== Scope
+#figure([A], caption: [Cap], kind: "sketch", supplement: [Sketch])
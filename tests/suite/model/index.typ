@@ -0,0 +1,56 @@
+--- index-ranges ---
+// Entries on consecutive pages are merged into ranges, subentries are grouped
+// under their entry, and the entries are sorted for the text language.
+#set page(height: auto)
+#index.entry("Cat")
+#index.entry("Cat", sub: "Tabby")
+#index.see("Kitten", "Cat")
+#index.entry("Zebra")
+#pagebreak()
+#index.entry("Cat") #index.entry("Cat")
+#index.entry("Ärger")
+#pagebreak()
+#index.entry("Cat")
+#index.entry("Cat", sub: "Tabby")
+#pagebreak()
+#pagebreak()
+#index.entry("Cat")
+#index.entry("apple")
+#pagebreak()
+#index()
+
+--- index-ranges-sv ---
+// In Swedish, "Ä" sorts after "Z".
+#set text(lang: "sv")
+#set page(height: auto)
+#index.entry("Cat")
+#index.entry("Cat", sub: "Tabby")
+#index.see("Kitten", "Cat")
+#index.entry("Zebra")
+#pagebreak()
+#index.entry("Cat") #index.entry("Cat")
+#index.entry("Ärger")
+#pagebreak()
+#index.entry("Cat")
+#index.entry("Cat", sub: "Tabby")
+#pagebreak()
+#pagebreak()
+#index.entry("Cat")
+#index.entry("apple")
+#pagebreak()
+#index()
+
+--- index-entry-query ---
+#index.entry("Cat", sub: "Tabby")
+#index.see("Kitten", "Cat")
+#context test(query(index.entry).first().sub, "Tabby")
+#context test(query(index.see).first().target, "Cat")
+
+--- index-entry-empty ---
+// An index without entries only shows its title.
+#show heading: none
+#index()
+
+--- index-entry-wrong-type ---
+// Error: 14-15 expected string, found integer
+#index.entry(1)
@@ -1,6 +1,6 @@
 // Test hyperlinking.
 
---- link-basic render html ---
+--- link-basic render html a11y-extras ---
 // Link syntax.
 https://example.com/
 
@@ -14,6 +14,13 @@ This link appears #link("https://google.com/")[in the middle of] a paragraph.
 Contact #link("mailto:hi@typst.app") or
 call #link("tel:123") for more information.
 
+--- link-html-location html ---
+// Links into the document, including references, go to the id of their
+// destination in HTML export.
+#set heading(numbering: "1.a")
+= Intro <intro>
+See #ref(<intro>, supplement: none) and #link(<intro>)[the intro].
+
 --- link-trailing-period ---
 // Test that the period is trimmed.
 #show link: underline
@@ -44,6 +51,42 @@ https://exam(ple.com/
 You could also make the
 #link("https://html5zombo.com/")[link look way more typical.]
 
+--- link-dest-kind ---
+#set heading(numbering: "1.")
+= Intro <intro>
+#link("https://typst.app")[A]
+#link(<intro>)[B]
+#link((page: 1, x: 0pt, y: 0pt))[C]
+@intro
+
+#context test(
+  query(link).map(it => it.dest-kind),
+  ("url", "location", "position", "location"),
+)
+
+--- link-same-page ---
+= Intro <intro>
+#link(<intro>)[Here]
+#link("https://typst.app")[Web]
+#pagebreak()
+#link(<intro>)[There]
+#link((page: 2, x: 0pt, y: 0pt))[Top]
+
+#context test(
+  query(link).map(it => it.same-page),
+  (true, none, false, true),
+)
+
+--- link-show-where-dest-kind ---
+// Only the external link is underlined and only the reference is blue.
+#set heading(numbering: "1.")
+#show link.where(dest-kind: "url"): underline
+#show link.where(dest-kind: "location"): set text(blue)
+= Intro <intro>
+#link("https://typst.app")[External] \
+See @intro. \
+#link((page: 1, x: 0pt, y: 0pt))[Top]
+
 --- link-transformed ---
 // Transformed link.
 #set page(height: 60pt)
@@ -75,3 +118,15 @@ Text <hey>
 Text <hey>
 // Error: 2-20 label `<hey>` occurs multiple times in the document
 #link(<hey>)[Nope.]
+
+--- link-map-rotated ---
+#rotate(30deg, link("https://typst.app")[Typst])
+
+--- link-svg-merged ---
+// The custom check exports this into a single SVG file, where the link on the
+// first page leads to the group of the second page and to the heading's
+// offset in the SVG.
+#set page(height: 60pt)
+#link(<target>)[Go to the heading.]
+#pagebreak()
+= Target <target>
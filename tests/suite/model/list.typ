@@ -233,3 +233,38 @@ World
 - h
 #align(right)[- i]
 - j
+
+--- list-multi-paragraph html render ---
+// The blocks of an item with multiple paragraphs are aligned with each other.
+// In HTML, the item contains a paragraph for each paragraph of the body, while
+// the single-paragraph item stays plain.
+- First paragraph.
+
+  ```
+  code
+  ```
+
+  Second paragraph.
+- Next
+
+--- list-tight-multi-paragraph-spacing ---
+// Tightness only affects the spacing between items. An item with multiple
+// paragraphs is separated from its neighbours by paragraph spacing.
+#set par(leading: 5pt, spacing: 20pt)
+#let tight = [
+- A
+
+  B
+- C
+]
+#let wide = [
+- A
+
+  B
+
+- C
+]
+#context test(
+  measure(width: 100pt, tight).height,
+  measure(width: 100pt, wide).height,
+)
@@ -73,6 +73,33 @@ A
 
 = Heading
 
+--- outline-fill-levels ---
+// Only level 2 entries have leaders and all page numbers, whether one or two
+// digits wide, end at the same position.
+#outline(fill: (none, repeat[.]), number-width: 1em)
+
+#pagebreak()
+#counter(page).update(9)
+= Alpha
+== Beta
+#pagebreak()
+= Gamma
+== Delta
+
+--- outline-fill-func ---
+#set heading(numbering: "1.")
+#outline(fill: n => if n > 1 { repeat[--] })
+
+= Introduction
+== Motivation
+= Conclusion
+
+--- outline-fill-bad-type ---
+// Error: 2-33 expected content or none, found dictionary
+#outline(fill: n => (a: "dict"))
+
+= Heading
+
 --- outline-first-line-indent ---
 #set par(first-line-indent: 1.5em)
 #set heading(numbering: "1.1.a.")
@@ -176,3 +203,46 @@ A
 
 = 测
 = 很
+
+--- outline-indent-auto ---
+// The titles of an automatically indented outline align per level, even if
+// the numberings have different widths, and nested titles are indented
+// further.
+#set heading(numbering: "I.")
+#show heading: none
+#outline(title: none, indent: auto)
+
+= A
+= B
+= C
+== D
+== E
+
+--- outline-indent-auto-numbering-function ---
+// The same holds for numberings produced by a function.
+#set heading(numbering: (..n) => box(
+  width: 6pt * n.pos().last(),
+  height: 6pt,
+  fill: aqua,
+))
+#show heading: none
+#outline(title: none, indent: auto)
+
+= A
+= B
+= C
+== D
+== E
+
+--- outline-indent-auto-rtl ---
+// In right-to-left text, nested titles are indented further to the left.
+#set text(dir: rtl)
+#set heading(numbering: "I.")
+#show heading: none
+#outline(title: none, indent: auto)
+
+= A
+= B
+= C
+== D
+== E
@@ -64,6 +64,30 @@ Why would anybody ever ...
 
 ... want spacing and indent?
 
+--- par-first-line-indent-after ---
+// The paragraph after the heading is indented, the one after the figure isn't.
+#set par(spacing: 0.65em, first-line-indent: (
+  amount: 12pt,
+  after: (heading: true, figure: false),
+))
+#show heading: it => block(it.body)
+
+= Heading
+A
+
+B
+
+#figure(rect(width: 10pt, height: 10pt))
+C
+
+--- par-first-line-indent-dict ---
+#set par(first-line-indent: 1em)
+#context test(par.first-line-indent, (amount: 1em, all: false, after: (:)))
+
+--- par-first-line-indent-bad-key ---
+// Error: 29-50 unexpected key "foo", valid keys are "amount", "all", and "after"
+#set par(first-line-indent: (amount: 1em, foo: 1))
+
 --- par-hanging-indent ---
 // Test hanging indent.
 #set par(hanging-indent: 15pt, justify: true)
@@ -94,6 +118,26 @@ Welcome \ here. Does this work well?
 #set text(hyphenate: false)
 Lorem ipsum dolor #metadata(none) nonumy eirmod tempor.
 
+--- par-dropcap ---
+#dropcap(text(size: 30pt)[T])his paragraph starts with a drop cap that spans
+multiple lines and then returns to the full measure.
+
+--- par-dropcap-ltr ---
+// The first three lines are indented by the width of the drop cap and the gap.
+#dropcap(lines: 3, gap: 5pt, rect(width: 20pt, height: 20pt))
+#range(24).map(_ => "word").join(" ")
+
+--- par-dropcap-rtl ---
+// In right-to-left text, the drop cap and the indent are on the right.
+#set text(dir: rtl)
+#dropcap(lines: 3, gap: 5pt, rect(width: 20pt, height: 20pt))
+#range(24).map(_ => "word").join(" ")
+
+--- par-dropcap-not-at-start ---
+// Error: 8-30 drop cap must be at the start of a paragraph
+// Hint: 8-30 try adding a paragraph break before the drop cap
+Hello #dropcap(text(30pt)[A])
+
 --- issue-4278-par-trim-before-equation ---
 #set par(justify: true)
 #lorem(6) aa $a = c + b$
@@ -198,6 +198,26 @@
 #let f( param : v ) = param
 #test(f( param /* ok */ : 2 ), 2)
 
+--- call-error-mode-fatal ---
+// By default, evaluation stops at the first error.
+// Error: 2-5 unknown variable: foo
+#foo()
+#bar()
+#baz()
+
+--- call-error-mode-collect collect ---
+// When errors are collected, all independent errors are reported.
+// Error: 2-5 unknown variable: foo
+#foo()
+#set text(size: 12pt)
+// Error: 5-8 unknown variable: bar
+A *#bar[x]* B
+// Error: 13-18 expected length, found string
+#text(size: "big")[C]
+#let x = 1
+// Error: 2-5 unknown variable: baz
+#baz(x)
+
 --- call-args-unclosed ---
 // Error: 7-8 unclosed delimiter
 #{func(}
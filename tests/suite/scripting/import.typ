@@ -420,3 +420,7 @@ This is never reached.
 --- import-from-file-package-lookalike ---
 // Error: 9-28 file not found (searched at tests/suite/scripting/#test/mypkg:1.0.0)
 #import "#test/mypkg:1.0.0": *
+
+--- package-lock ---
+// Checks that packages are verified against a package lock when compiling,
+// rejecting packages that are missing from the lock or whose contents changed.
@@ -28,3 +28,48 @@
 --- include-semicolon-or-linebreak ---
 // Error: 18 expected semicolon or line break
 #include "hi.typ" Hi
+
+--- include-inputs ---
+// The same file with different inputs is evaluated separately.
+#let draft = include("modules/chap-inputs.typ", inputs: (number: 1, draft: true))
+#let final = include("modules/chap-inputs.typ", inputs: (number: 2, draft: false))
+#let inputs(it) = it.children.find(child => child.func() == metadata).value
+#test(inputs(draft), (number: 1, draft: true))
+#test(inputs(final), (number: 2, draft: false))
+#test(draft == final, false)
+
+// Repeating an include yields the same content.
+#test(draft, include("modules/chap-inputs.typ", inputs: (number: 1, draft: true)))
+
+#draft
+#final
+
+--- include-inputs-not-in-scope ---
+// The inputs are only visible to the included file.
+#include("modules/chap-inputs.typ", inputs: (number: 1, draft: true))
+// Error: 2-8 unknown variable: number
+#number
+
+--- include-call-without-inputs ---
+// The call form also works without inputs.
+#include("modules/chap1.typ")
+#include ("modules/chap" + "1.typ")
+
+--- include-inputs-invalid-name ---
+// Error: 45-61 input name `not valid` is not a valid identifier
+#include("modules/chap-inputs.typ", inputs: ("not valid": 1))
+
+--- include-inputs-module ---
+#import "modules/chap1.typ" as chap
+// Error: 24-27 cannot pass inputs to a module
+// Hint: 24-27 the module was already evaluated, pass a path instead
+#include(chap, inputs: (:))
+
+--- include-inputs-cyclic ---
+// Cyclic include of this very file.
+// Error: 10-25 cyclic import
+#include("./include.typ", inputs: (x: 1))
+
+--- include-inputs-unexpected-argument ---
+// Error: 31-42 unexpected argument: draft
+#include("modules/chap1.typ", draft: true)
@@ -0,0 +1,4 @@
+// SKIP
+#metadata((number: number, draft: draft))
+= Chapter #number
+#if draft [_Draft_] else [Final]
@@ -33,8 +33,23 @@
 
 --- ops-add-too-large ---
 // Error: 3-26 value is too large
+// Hint: 3-26 the result of `9223372036854775807 + 1` does not fit into a 64-bit integer
+// Hint: 3-26 use `bigint` to compute with arbitrarily large integers
 #(9223372036854775807 + 1)
 
+--- ops-multiply-too-large ---
+// Error: 3-26 value is too large
+// Hint: 3-26 the result of `4294967296 * 4294967296` does not fit into a 64-bit integer
+// Hint: 3-26 use `bigint` to compute with arbitrarily large integers
+#(4294967296 * 4294967296)
+
+--- ops-negate-too-large ---
+#let min = -9223372036854775807 - 1
+// Error: 3-7 value is too large
+// Hint: 3-7 the result of `-(-9223372036854775808)` does not fit into a 64-bit integer
+// Hint: 3-7 use `bigint` to compute with arbitrarily large integers
+#(-min)
+
 --- ops-binary-basic ---
 // Subtraction.
 #test(1-4, 3*-1)
@@ -220,3 +220,13 @@ $x$ c $y$
   c.step()
   [bc]
 }
+
+--- show-text-merge-runs ---
+// Each letter is styled by its own show rule application, but adjacent letters
+// with equal styles still end up in a single text run.
+#show regex("[a-z]"): set text(red)
+Hello world
+
+--- show-text-coalesce-html html ---
+#show regex("[a-z]"): it => html.elem("span", attrs: (class: "letter"), it)
+abc de
@@ -258,3 +258,13 @@ I am *strong*, I am _emphasized_, and I am #[special<special>].
 
 = Hello
 *strong*
+
+--- show-native-rule ---
+// The custom check compiles this with a native show rule for `strong` that
+// uppercases instead of emboldening.
+Hello *world*
+
+#[
+  #show strong: it => it.body
+  *again*
+]
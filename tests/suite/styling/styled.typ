@@ -0,0 +1,41 @@
+// Test the `styled` function.
+
+--- styled-basic ---
+#let note(body) = styled(
+  body,
+  text.with(fill: blue),
+  text.with(size: 8pt).with(style: "italic"),
+)
+
+#note[Small and blue] \
+Normal
+
+--- styled-contained-in-context ---
+// Neither the given styles nor set rules in the body reach the footer, even
+// though the body is a context block.
+#set page(height: 60pt, footer: [Footer])
+#let component(body) = styled(
+  context {
+    set text(red)
+    body
+  },
+  text.with(size: 8pt),
+)
+
+#component[Body]
+
+--- styled-context-leak ---
+// Without `styled`, the set rule in the context block is lifted to the page
+// level and also colors the footer.
+#set page(height: 60pt, footer: [Footer])
+#context {
+  set text(red)
+  [Body]
+}
+
+--- styled-precedence ---
+#styled([Blue], text.with(red), text.with(blue))
+
+--- styled-not-element ---
+// Error: 17-23 only element functions can be used as set rules
+#styled([Body], x => x)
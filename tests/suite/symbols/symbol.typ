@@ -82,10 +82,52 @@
   ("variant.duplicate", "y"),
 )
 
+--- symbol-constructor-math-class ---
+#let corr = symbol(
+  "⟐",
+  (modifiers: "rel", value: "⟐", math-class: "relation"),
+  (value: "⟡", modifiers: "alt"),
+)
+#test(
+  repr(corr),
+  ```
+  symbol(
+    "⟐",
+    (modifiers: "rel", value: "⟐", math-class: "relation"),
+    ("alt", "⟡"),
+  )
+  ```.text,
+)
+#test(
+  repr(corr.rel),
+  ```
+  symbol(
+    (modifiers: "", value: "⟐", math-class: "relation"),
+  )
+  ```.text,
+)
+
+--- symbol-constructor-bad-key ---
+// Error: 9-40 unexpected key "class", valid keys are "modifiers", "value", and "math-class"
+#symbol((value: "⟐", class: "relation"))
+
+--- symbol-constructor-bad-math-class ---
+// Error: 9-47 expected "normal", "punctuation", "opening", "closing", "fence", "large", "relation", "unary", "binary", or "vary"
+#symbol((value: "⟐", math-class: "relational"))
+
 --- symbol-unknown-modifier ---
-// Error: 13-20 unknown symbol modifier
+// Error: 13-20 unknown symbol modifier "garbage"
 #emoji.face.garbage
 
+--- symbol-unknown-modifier-similar ---
+// Error: 12-18 unknown symbol modifier "dobule", did you mean "double"?
+#sym.arrow.dobule
+
+--- symbol-unknown-modifier-available ---
+#let envelope = symbol("🖂", ("stamped", "🖃"), ("stamped.pen", "🖆"), ("fly", "🖅"))
+// Error: 11-20 unknown symbol modifier "lightning", available modifiers are "fly", "pen", and "stamped"
+#envelope.lightning
+
 --- symbol-repr ---
 #test(
   repr(sym.amp),
@@ -64,3 +64,38 @@ $<==>$\
 $[|$\
 $|]$\
 $||$
+
+--- shorthand-smart-disabled ---
+// Without smart substitutions, dashes and ellipses stay as written.
+#context {
+  test(measure(text(smart: false)[--flag]), measure[\-\-flag])
+  test(measure(text(smart: false)[---]), measure[\-\-\-])
+  test(measure(text(smart: false)[...]), measure[\.\.\.])
+  test(measure[--flag], measure[–flag])
+  test(measure[---], measure[—])
+  test(measure[...], measure[…])
+  test(measure[--flag] == measure[\-\-flag], false)
+}
+
+--- shorthand-smart-scoped ---
+// The substitution is decided when the content is shown, so it can be turned
+// off for content that was passed to a function and back on within it.
+#let cli(body) = text(smart: false, body)
+#context {
+  test(measure(cli[--flag]), measure[\-\-flag])
+  test(measure(cli[#text(smart: true)[--flag]]), measure[–flag])
+}
+
+--- shorthand-repr ---
+// Shorthands still look like the text they stand for.
+#test(repr([--]), "[–]")
+#test(repr([---]), "[—]")
+#test(repr([...]), "[…]")
+#test(repr([~]), "[\u{a0}]")
+
+--- shorthand-math-symbol ---
+// Math shorthands are not affected by `text.smart` and still evaluate to
+// symbols.
+#test($1, 2, ...$.body.children.last(), […])
+#test(repr($...$.body), repr([…]))
+#test($...$.body == $dots.h$.body, true)
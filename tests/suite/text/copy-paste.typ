@@ -6,3 +6,11 @@ The after fira 🏳️‍🌈!
 
 #set text(lang: "ar", font: "Noto Sans Arabic")
 مرحبًا
+
+--- svg-selectable-text-exact ---
+// The custom check exports text with an "fi" ligature to SVG with a selectable
+// text layer and checks that there is one x-coordinate per glyph cluster.
+
+--- glyph-inventory ---
+// The glyph inventory maps each glyph back to its source text.
+// This is checked in `custom.rs`.
@@ -83,3 +83,37 @@ We can also specify a customized value
 #highlight(stroke: 2pt + blue)[abc]
 #highlight(stroke: (top: blue, left: red, bottom: green, right: orange))[abc]
 #highlight(stroke: 1pt, radius: 3pt)[#lorem(5)]
+
+--- deco-html html ---
+#underline[Default]
+
+#underline(stroke: 1.5pt + red, offset: 2pt, evade: false)[Under]
+
+#overline(stroke: (thickness: 1pt, dash: "dotted"))[Over]
+
+#strike(stroke: (paint: blue, dash: "dashed"))[Struck]
+
+--- deco-html-unsupported html ---
+// Warning: 2-30 strike offset was ignored during HTML export
+// Hint: 2-30 CSS has no equivalent for it
+#strike(offset: -3pt)[Struck]
+
+// Warning: 2-49 underline background placement was ignored during HTML export
+// Hint: 2-49 CSS has no equivalent for it
+// Warning: 2-49 underline extent was ignored during HTML export
+// Hint: 2-49 CSS has no equivalent for it
+#underline(extent: 2pt, background: true)[Under]
+
+--- highlight-html html ---
+#highlight[Default]
+
+#highlight(fill: blue, stroke: red, radius: 3pt, extent: 2pt)[A highlight that may span multiple lines]
+
+#highlight(stroke: (top: red, bottom: 2pt + blue), radius: (top-left: 50%, rest: 1pt))[Sides]
+
+--- highlight-html-unsupported html ---
+// Warning: 2-53 highlight negative extent was ignored during HTML export
+// Hint: 2-53 CSS has no equivalent for it
+// Warning: 2-53 highlight top edge was ignored during HTML export
+// Hint: 2-53 CSS has no equivalent for it
+#highlight(top-edge: "x-height", extent: -1pt)[Edge]
@@ -77,6 +77,18 @@ I
 #let var = text(font: ("list-of", "nonexistent-fonts"))[don't]
 #var
 
+--- text-font-broken ---
+// An explicitly listed family skips variants that fail to load. This is
+// checked in `custom.rs`.
+
+--- text-font-priority ---
+// Font priorities need a custom font book. They are checked in `custom.rs`
+// with a second font aliased as "Libertinus Serif".
+
+--- text-font-variable-pinned ---
+// Pinned variable font instances need a custom font book. They are checked in
+// `custom.rs` with a bold instance pinned under the family name "Pinned".
+
 --- text-font-linux-libertine ---
 // Warning: 17-34 Typst's default font has changed from Linux Libertine to its successor Libertinus Serif
 // Hint: 17-34 please set the font to `"Libertinus Serif"` instead
@@ -154,3 +166,11 @@ The number 123.
 #set text(-1pt)
 
 a
+
+--- text-font-embedding-restricted ---
+// PDF export enforces the embedding restrictions in the `fsType` of a font.
+// This is checked in `custom.rs`.
+
+--- text-explain-without-feature ---
+// Error: 10-27 `text.explain` is only available when `--features font-debug` is passed
+#context text.explain("a")
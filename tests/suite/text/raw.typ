@@ -697,6 +697,39 @@ a b c --------------------
 #let foo = "bar"
 ```
 
+--- raw-mapping ---
+// The mapped block is highlighted just like the unmapped one.
+```rust
+fn f() -> i32 {}
+```
+
+#set raw(mapping: (("->", "→"),))
+```rust
+fn f() -> i32 {}
+```
+
+--- raw-mapping-regex ---
+#set raw(mapping: (
+  (regex("!=|/="), "≠"),
+  ("=", "←"),
+  ("==", "not reached"),
+))
+```typ
+#let x = 1 != 2 == 3
+```
+
+--- raw-mapping-bad-replacement ---
+// Error: 19-35 replacement must not contain line breaks
+#set raw(mapping: (("a", "b\nc"),))
+
+--- raw-mapping-bad-pattern ---
+// Error: 19-31 pattern must not be empty
+#set raw(mapping: (("", "b"),))
+
+--- raw-mapping-bad-length ---
+// Error: 19-28 substitution must be an array of a pattern and a replacement
+#set raw(mapping: (("a",),))
+
 --- raw-unclosed ---
 // Test unterminated raw text.
 //
@@ -168,3 +168,9 @@ Some people's thought on this would be #[#set smartquote(enabled: false); "stran
 
 --- issue-5146-smartquotes-after-equations ---
 $i$'s $i$ 's
+
+--- smartquote-text-smart-disabled ---
+#context {
+  test(measure(text(smart: false)["a" 'b']), measure[\"a\" \'b\'])
+  test(measure["a" 'b'], measure[“a” ‘b’])
+}
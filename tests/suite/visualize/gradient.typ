@@ -591,6 +591,75 @@ $ A = mat(
 #test(gradient.linear(red, green, space: color.hsl).space(), color.hsl)
 #test(gradient.linear(red, green, space: color.hsv).space(), color.hsv)
 
+--- gradient-hue ---
+#let hue(grad, t) = calc.round(grad.sample(t).components().at(2).deg(), digits: 2)
+#let a = color.oklch(70%, 0.1, 30deg)
+#let b = color.oklch(70%, 0.1, 90deg)
+#test(hue(gradient.linear(a, b, space: oklch), 50%), 60.0)
+#test(hue(gradient.linear(a, b, space: oklch, hue: "longer"), 25%), 315.0)
+#test(hue(gradient.linear(a, b, space: oklch, hue: "longer"), 50%), 240.0)
+#test(hue(gradient.linear(a, b, space: oklch, hue: "longer"), 75%), 165.0)
+#test(hue(gradient.linear(a, b, space: oklch, hue: "increasing"), 50%), 60.0)
+#test(hue(gradient.linear(a, b, space: oklch, hue: "decreasing"), 50%), 240.0)
+#test(hue(gradient.linear(b, a, space: oklch, hue: "increasing"), 50%), 240.0)
+#test(gradient.linear(a, b, space: oklch).hue(), "shorter")
+#test(gradient.conic(a, b, space: color.hsl, hue: "longer").hue(), "longer")
+
+--- gradient-hue-without-hue-component ---
+// Error: 34-42 hue interpolation requires a color space with a hue component
+// Hint: 34-42 try interpolating in `color.oklch`, `color.hsl`, or `color.hsv`
+#gradient.linear(red, blue, hue: "longer")
+
+--- gradient-easing ---
+#let lightness(easing, t) = {
+  let grad = gradient.linear((luma(0%), 0%, easing), (luma(100%), 100%), space: luma)
+  calc.round(grad.sample(t).components().first() / 1%, digits: 2)
+}
+#test(lightness("linear", 50%), 50.0)
+#test(lightness("ease-in", 50%), 25.0)
+#test(lightness("ease-out", 50%), 75.0)
+#test(lightness("ease-in-out", 25%), 12.5)
+#test(lightness("ease-in-out", 75%), 87.5)
+#test(lightness(3.0, 50%), 12.5)
+#test(lightness((exponent: 3.0), 50%), 12.5)
+#test(lightness((exponent: 3.0, reversed: true), 50%), 87.5)
+#test(lightness((exponent: 2.0, reversed: true), 30%), lightness("ease-out", 30%))
+
+--- gradient-easing-single-segment ---
+// The easing only applies between its stop and the next one.
+#let grad = gradient.linear(
+  (luma(0%), 0%),
+  (luma(50%), 50%, "ease-in"),
+  (luma(100%), 100%),
+  space: luma,
+)
+#let lightness(t) = calc.round(grad.sample(t).components().first() / 1%, digits: 2)
+#test(lightness(25%), 25.0)
+#test(lightness(75%), 62.5)
+
+--- gradient-easing-stops ---
+#test(
+  gradient.linear((red, 0%, "ease-in"), (blue, 100%)).stops(),
+  ((red, 0%, "ease-in"), (blue, 100%)),
+)
+#test(
+  gradient.radial((red, 0%, 2.0), (blue, 100%)).stops(),
+  ((red, 0%, 2.0), (blue, 100%)),
+)
+#test(
+  gradient.linear((red, 0%, (exponent: 2.0, reversed: true)), (blue, 100%)).stops(),
+  ((red, 0%, (exponent: 2.0, reversed: true)), (blue, 100%)),
+)
+
+--- gradient-easing-last-stop ---
+// Error: 29-52 the last stop cannot have an easing
+// Hint: 29-52 the easing of a stop applies between it and the next stop
+#gradient.linear((red, 0%), (blue, 100%, "ease-in"))
+
+--- gradient-easing-negative-exponent ---
+// Error: 18-33 easing exponent must be positive
+#gradient.linear((red, 0%, -1.0), (blue, 100%))
+
 --- gradient-relative ---
 #test(gradient.linear(red, green, relative: "self").relative(), "self")
 #test(gradient.linear(red, green, relative: "parent").relative(), "parent")
@@ -613,6 +682,22 @@ $ A = mat(
   ((red, 0%), (green, 25%), (blue, 50%), (green, 75%), (red, 100%))
 )
 
+--- gradient-repeat-mirror-easing ---
+// Mirrored repetitions traverse the easing curves backwards.
+#let grad = gradient.linear(
+  (luma(0%), 0%, "ease-in"),
+  (luma(100%), 100%),
+  space: luma,
+).repeat(2, mirror: true)
+#let lightness(t) = calc.round(grad.sample(t).components().first() / 1%, digits: 2)
+#test(grad.stops(), ((luma(0%), 0%, "ease-in"), (luma(100%), 50%, "ease-out"), (luma(0%), 100%)))
+#test(lightness(10%), lightness(90%))
+#test(lightness(30%), lightness(70%))
+
+#let grad = gradient.linear((red, 0%, 3.0), (blue, 100%)).repeat(2, mirror: true)
+#test(grad.stops().at(1), (blue, 50%, (exponent: 3.0, reversed: true)))
+#test(grad.sample(20%), grad.sample(80%))
+
 --- issue-2902-gradient-oklch-panic ---
 // Minimal reproduction of #2902
 #set page(width: 15cm, height: auto, margin: 1em)
@@ -658,3 +743,7 @@ $ A = mat(
   height: 10pt,
   fill: gradient.linear(violet, blue, space: cmyk)
 )
+
+--- render-grayscale ---
+// Test conversion of rendered pages into grayscale with and without
+// dithering. This is checked in `custom.rs`.
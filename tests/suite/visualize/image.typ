@@ -71,6 +71,78 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 // width, but rather max out at its natural size.
 #image("/assets/images/f2t.jpg")
 
+--- image-dpi-physical-size ---
+// The 48x80 image has 220 DPI embedded, so that it is laid out at its
+// physical size. Overriding the DPI changes that size.
+#context {
+  let natural = measure(image("/assets/images/f2t.jpg"))
+  test(calc.round(natural.width.pt(), digits: 2), 15.71)
+  let scan = measure(image("/assets/images/f2t.jpg", dpi: 600))
+  test(calc.round(scan.width.pt(), digits: 2), 5.76)
+  test(calc.round(scan.height.pt(), digits: 2), 9.6)
+}
+
+--- image-max-size ---
+#context {
+  let tiger = "/assets/images/tiger.jpg"
+  // Without a maximum, a large image is clamped to the available width.
+  test(measure(image(tiger), width: 100pt).width, 100pt)
+  // A relative maximum width is resolved against the available width.
+  test(measure(image(tiger, max-width: 50%), width: 100pt).width, 50pt)
+  test(measure(image(tiger, max-height: 20pt), width: 100pt).height, 20pt)
+  // Explicit sizes are not clamped.
+  let sized = image(tiger, width: 80pt, max-width: 10pt)
+  test(measure(sized, width: 100pt).width, 80pt)
+}
+
+--- image-dpi-invalid ---
+// Error: 2-43 dpi must be positive
+#image("/assets/images/tiger.jpg", dpi: 0)
+
+--- image-scaling-methods ---
+// A 4×4 checkerboard.
+#let checkers = bytes((
+  137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 4,
+  0, 0, 0, 4, 8, 0, 0, 0, 0, 140, 154, 193, 162, 0, 0, 0, 15, 73, 68, 65, 84,
+  120, 218, 99, 96, 248, 15, 133, 12, 112, 22, 0, 79, 196, 7, 249, 68, 111,
+  17, 49, 0, 0, 0, 0, 73, 69, 78, 68, 174, 66, 96, 130,
+))
+
+#set page(width: auto)
+#set image(width: 40pt)
+#stack(
+  dir: ltr,
+  spacing: 4pt,
+  image.decode(checkers),
+  image.decode(checkers, scaling: "smooth"),
+  image.decode(checkers, scaling: "pixelated"),
+  rotate(30deg, image.decode(checkers, scaling: "pixelated")),
+  rect(
+    width: 40pt,
+    height: 40pt,
+    fill: tiling(
+      size: (20pt, 20pt),
+      image.decode(checkers, width: 20pt, scaling: "pixelated"),
+    ),
+  ),
+)
+
+--- image-scaling-pixelated ---
+// The custom check asserts the hint in all export formats.
+#set page(width: 40pt, height: 40pt, margin: 0pt)
+#let checkers = bytes((
+  137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 4,
+  0, 0, 0, 4, 8, 0, 0, 0, 0, 140, 154, 193, 162, 0, 0, 0, 15, 73, 68, 65, 84,
+  120, 218, 99, 96, 248, 15, 133, 12, 112, 22, 0, 79, 196, 7, 249, 68, 111,
+  17, 49, 0, 0, 0, 0, 73, 69, 78, 68, 174, 66, 96, 130,
+))
+
+#image.decode(checkers, width: 40pt, scaling: "pixelated")
+
+--- image-scaling-bad ---
+// Error: 45-53 expected "smooth", "pixelated", or auto
+#image("/assets/images/rhino.png", scaling: "linear")
+
 --- image-file-not-found ---
 // Error: 8-29 file not found (searched at tests/suite/visualize/path/does/not/exist)
 #image("path/does/not/exist")
@@ -123,3 +195,7 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 --- issue-3733-dpi-svg ---
 #set page(width: 200pt, height: 200pt, margin: 0pt)
 #image("/assets/images/relative.svg")
+
+--- image-rasterize ---
+// Test that SVGs with unsupported features are rasterized with a warning and
+// that a fixed resolution forces rasterization. This is checked in `custom.rs`.
@@ -94,3 +94,25 @@
 --- line-infinite-length ---
 // Error: 2-54 cannot create line with infinite length
 #line(start: (0pt, 0pt), end: (float.inf * 1pt, 0pt))
+
+--- line-marker-arrow ---
+// Test that the stroke of a line ends within its arrowhead and that the tip
+// of the arrowhead lies on the line's end point, marked by the thin line.
+#set page(height: auto)
+#place(line(start: (60pt, 0pt), end: (60pt, 20pt), stroke: 0.5pt + red))
+#line(
+  start: (0pt, 10pt),
+  end: (60pt, 10pt),
+  stroke: 2pt + blue.transparentize(50%),
+  marker-end: "arrow",
+)
+
+--- line-marker-kinds ---
+// Test the different kinds of markers.
+#set page(height: auto)
+#stack(
+  spacing: 8pt,
+  line(length: 80pt, marker-end: "arrow"),
+  line(length: 80pt, stroke: 2pt, marker-start: "bar", marker-end: "circle"),
+  line(length: 80pt, marker-end: (shape: "arrow", size: 10pt)),
+)
@@ -87,6 +87,12 @@
 // Error: 15-38 unexpected key "cake", valid keys are "top-left", "top-right", "bottom-right", "bottom-left", "left", "top", "right", "bottom", and "rest"
 #rect(radius: (left: 10pt, cake: 5pt))
 
+--- rect-transparent-pdfa ---
+// The custom check exports this with PDF/A-1b, which does not allow
+// transparency, with plain PDF 1.7, which does, and with PDF/A-1b and
+// flattened transparency, which turns the fill into an opaque pink.
+#rect(fill: red.transparentize(50%))
+
 --- issue-1825-rect-overflow ---
 #set page(width: 17.8cm)
 #set par(justify: true)
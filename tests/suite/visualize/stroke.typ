@@ -165,3 +165,7 @@
   height: 10pt,
   stroke: (left: rgb("46b3c2") + 16.0mm),
 )
+
+--- stroke-degenerate-export ---
+// The custom check exports zero-sized and otherwise degenerate shapes, strokes,
+// and clipped groups to PNG, SVG, and PDF and checks that the output is valid.
@@ -159,5 +159,10 @@
 
 --- tiling-pattern-compatibility ---
 #set page(width: auto, height: auto, margin: 0pt)
+// Warning: 10-17 the name `pattern` is deprecated, use `tiling` instead
 #let t = pattern(size: (10pt, 10pt), line(stroke: 4pt, start: (0%, 0%), end: (100%, 100%)))
 #rect(width: 50pt, height: 50pt, fill: t)
+
+--- deprecation-policy ---
+// Test how uses of the deprecated `pattern` in a package and in the main file
+// are reported under each deprecation policy. This is checked in `custom.rs`.